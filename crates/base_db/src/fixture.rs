@@ -3,14 +3,18 @@ use std::{mem, str::FromStr, sync::Arc};
 
 use cfg::CfgOptions;
 use rustc_hash::FxHashMap;
+use syntax::SmolStr;
 use test_utils::{
     extract_range_or_offset, Fixture, RangeOrOffset, CURSOR_MARKER, ESCAPED_CURSOR_MARKER,
 };
 use vfs::{file_set::FileSet, VfsPath};
 
+use tt::{Delimiter, DelimiterKind, Ident, Leaf, Subtree, TokenId, TokenTree};
+
 use crate::{
-    input::CrateName, Change, CrateDisplayName, CrateGraph, CrateId, Edition, Env, FileId,
-    FilePosition, FileRange, SourceDatabaseExt, SourceRoot, SourceRootId,
+    input::CrateName, Change, CrateDisplayName, CrateGraph, CrateId, CrateOrigin, Edition, Env,
+    FileId, FilePosition, FileRange, ProcMacro, ProcMacroExpander, ProcMacroKind,
+    SourceDatabaseExt, SourceRoot, SourceRootId, TargetInfo,
 };
 
 pub const WORKSPACE: SourceRootId = SourceRootId(0);
@@ -90,6 +94,7 @@ impl ChangeFixture {
         let mut crate_deps = Vec::new();
         let mut default_crate_root: Option<FileId> = None;
         let mut default_cfg = CfgOptions::default();
+        let mut default_target = TargetInfo::host();
 
         let mut file_set = FileSet::default();
         let mut current_source_root_kind = SourceRootKind::Local;
@@ -118,6 +123,9 @@ impl ChangeFixture {
             if !meta.deps.is_empty() {
                 assert!(meta.krate.is_some(), "can't specify deps without naming the crate")
             }
+            if !meta.proc_macro.is_empty() {
+                assert!(meta.krate.is_some(), "can't specify proc macros without naming the crate")
+            }
 
             if let Some(kind) = &meta.introduce_new_source_root {
                 let root = match current_source_root_kind {
@@ -136,8 +144,13 @@ impl ChangeFixture {
                     Some(crate_name.clone().into()),
                     meta.cfg.clone(),
                     meta.cfg,
+                    meta.target,
                     meta.env,
-                    Default::default(),
+                    meta.proc_macro,
+                    match current_source_root_kind {
+                        SourceRootKind::Local => CrateOrigin::Workspace,
+                        SourceRootKind::Library => CrateOrigin::Dependency,
+                    },
                 );
                 let prev = crates.insert(crate_name.clone(), crate_id);
                 assert!(prev.is_none());
@@ -149,6 +162,7 @@ impl ChangeFixture {
                 assert!(default_crate_root.is_none());
                 default_crate_root = Some(file_id);
                 default_cfg = meta.cfg;
+                default_target = meta.target;
             }
 
             change.change_file(file_id, Some(Arc::new(text)));
@@ -166,8 +180,10 @@ impl ChangeFixture {
                 Some(CrateName::new("test").unwrap().into()),
                 default_cfg.clone(),
                 default_cfg,
+                default_target,
                 Env::default(),
                 Default::default(),
+                CrateOrigin::Workspace,
             );
         } else {
             for (from, to) in crate_deps {
@@ -195,8 +211,10 @@ impl ChangeFixture {
                 Some(CrateDisplayName::from_canonical_name("core".to_string())),
                 CfgOptions::default(),
                 CfgOptions::default(),
+                TargetInfo::host(),
                 Env::default(),
                 Vec::new(),
+                CrateOrigin::Dependency,
             );
 
             for krate in all_crates {
@@ -229,7 +247,9 @@ struct FileMeta {
     cfg: CfgOptions,
     edition: Edition,
     env: Env,
+    target: TargetInfo,
     introduce_new_source_root: Option<SourceRootKind>,
+    proc_macro: Vec<ProcMacro>,
 }
 
 impl From<Fixture> for FileMeta {
@@ -245,11 +265,138 @@ impl From<Fixture> for FileMeta {
             cfg,
             edition: f.edition.as_ref().map_or(Edition::CURRENT, |v| Edition::from_str(v).unwrap()),
             env: f.env.into_iter().collect(),
+            target: f.target.map_or_else(TargetInfo::host, |it| TargetInfo::from_triple(&it)),
             introduce_new_source_root: f.introduce_new_source_root.map(|kind| match &*kind {
                 "local" => SourceRootKind::Local,
                 "library" => SourceRootKind::Library,
                 invalid => panic!("invalid source root kind '{}'", invalid),
             }),
+            proc_macro: f.proc_macro.iter().map(|name| test_proc_macro(name)).collect(),
         }
     }
 }
+
+/// Returns one of the in-process proc macros available to fixtures via
+/// `proc_macro:name1,name2`.
+///
+/// The crate exporting them still has to declare the corresponding functions:
+///
+/// ```not_rust
+/// //- /proc_macros.rs crate:proc_macros proc_macro:identity,derive_generated,duplicate,timeout
+/// #[proc_macro_attribute]
+/// pub fn identity(_attr: TokenStream, item: TokenStream) -> TokenStream { item }
+/// #[proc_macro_derive(DeriveGenerated)]
+/// pub fn derive_generated(item: TokenStream) -> TokenStream { item }
+/// #[proc_macro]
+/// pub fn duplicate(input: TokenStream) -> TokenStream { input }
+/// #[proc_macro]
+/// pub fn timeout(input: TokenStream) -> TokenStream { input }
+/// ```
+fn test_proc_macro(name: &str) -> ProcMacro {
+    let (name, kind, expander): (&str, _, Arc<dyn ProcMacroExpander>) = match name {
+        "identity" => ("identity", ProcMacroKind::Attr, Arc::new(IdentityProcMacroExpander)),
+        "derive_generated" => (
+            "DeriveGenerated",
+            ProcMacroKind::CustomDerive,
+            Arc::new(DeriveGeneratedProcMacroExpander),
+        ),
+        "duplicate" => ("duplicate", ProcMacroKind::FuncLike, Arc::new(DuplicateProcMacroExpander)),
+        "timeout" => ("timeout", ProcMacroKind::FuncLike, Arc::new(TimeoutProcMacroExpander)),
+        _ => panic!("unknown test proc macro: {:?}", name),
+    };
+    ProcMacro { name: SmolStr::new(name), kind, expander }
+}
+
+/// `#[identity]`: returns the annotated item unchanged.
+#[derive(Debug)]
+struct IdentityProcMacroExpander;
+impl ProcMacroExpander for IdentityProcMacroExpander {
+    fn expand(
+        &self,
+        subtree: &Subtree,
+        _: Option<&Subtree>,
+        _: &Env,
+    ) -> Result<Subtree, tt::ExpansionError> {
+        Ok(subtree.clone())
+    }
+}
+
+/// `#[derive(DeriveGenerated)]`: adds an inherent `pub fn generated() {}` to the
+/// annotated (non-generic) ADT.
+#[derive(Debug)]
+struct DeriveGeneratedProcMacroExpander;
+impl ProcMacroExpander for DeriveGeneratedProcMacroExpander {
+    fn expand(
+        &self,
+        subtree: &Subtree,
+        _: Option<&Subtree>,
+        _: &Env,
+    ) -> Result<Subtree, tt::ExpansionError> {
+        let adt_name = subtree
+            .token_trees
+            .iter()
+            .skip_while(|tt| {
+                !matches!(tt, TokenTree::Leaf(Leaf::Ident(it)) if matches!(&*it.text, "struct" | "enum" | "union"))
+            })
+            .nth(1)
+            .and_then(|tt| match tt {
+                TokenTree::Leaf(Leaf::Ident(it)) => Some(it.text.clone()),
+                _ => None,
+            })
+            .ok_or_else(|| tt::ExpansionError::Unknown("expected an ADT".to_string()))?;
+
+        let ident = |text: &str| {
+            TokenTree::from(Leaf::from(Ident { text: text.into(), id: TokenId::unspecified() }))
+        };
+        let subtree = |kind, token_trees| {
+            TokenTree::from(Subtree {
+                delimiter: Some(Delimiter { id: TokenId::unspecified(), kind }),
+                token_trees,
+            })
+        };
+        let fn_def = vec![
+            ident("pub"),
+            ident("fn"),
+            ident("generated"),
+            subtree(DelimiterKind::Parenthesis, Vec::new()),
+            subtree(DelimiterKind::Brace, Vec::new()),
+        ];
+        Ok(Subtree {
+            delimiter: None,
+            token_trees: vec![
+                ident("impl"),
+                ident(&adt_name),
+                subtree(DelimiterKind::Brace, fn_def),
+            ],
+        })
+    }
+}
+
+/// `duplicate!(...)`: expands to its input, twice.
+#[derive(Debug)]
+struct DuplicateProcMacroExpander;
+impl ProcMacroExpander for DuplicateProcMacroExpander {
+    fn expand(
+        &self,
+        subtree: &Subtree,
+        _: Option<&Subtree>,
+        _: &Env,
+    ) -> Result<Subtree, tt::ExpansionError> {
+        let token_trees = subtree.token_trees.iter().chain(&subtree.token_trees).cloned().collect();
+        Ok(Subtree { delimiter: None, token_trees })
+    }
+}
+
+/// `timeout!(...)`: fails like an expansion the proc macro server gave up on.
+#[derive(Debug)]
+struct TimeoutProcMacroExpander;
+impl ProcMacroExpander for TimeoutProcMacroExpander {
+    fn expand(
+        &self,
+        _: &Subtree,
+        _: Option<&Subtree>,
+        _: &Env,
+    ) -> Result<Subtree, tt::ExpansionError> {
+        Err(tt::ExpansionError::ExpansionTimeout("`timeout` did not finish within 10s".to_string()))
+    }
+}
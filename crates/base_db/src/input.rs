@@ -186,9 +186,20 @@ pub struct CrateData {
     pub display_name: Option<CrateDisplayName>,
     pub cfg_options: CfgOptions,
     pub potential_cfg_options: CfgOptions,
+    pub target: TargetInfo,
     pub env: Env,
     pub dependencies: Vec<Dependency>,
     pub proc_macro: Vec<ProcMacro>,
+    pub origin: CrateOrigin,
+}
+
+/// Whether a crate is part of the project being worked on, or one of its dependencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrateOrigin {
+    /// A member of the workspace.
+    Workspace,
+    /// A crates.io, git or path dependency, or a sysroot crate.
+    Dependency,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -202,6 +213,21 @@ impl Edition {
     pub const CURRENT: Edition = Edition::Edition2018;
 }
 
+/// Basic information about the target a crate is compiled for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetInfo {
+    /// The target triple, `None` if the crate is compiled for the host.
+    pub triple: Option<String>,
+    pub pointer_width: u32,
+    pub endian: Endian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct Env {
     entries: FxHashMap<String, String>,
@@ -221,8 +247,10 @@ impl CrateGraph {
         display_name: Option<CrateDisplayName>,
         cfg_options: CfgOptions,
         potential_cfg_options: CfgOptions,
+        target: TargetInfo,
         env: Env,
         proc_macro: Vec<ProcMacro>,
+        origin: CrateOrigin,
     ) -> CrateId {
         let data = CrateData {
             root_file_id: file_id,
@@ -230,9 +258,11 @@ impl CrateGraph {
             display_name,
             cfg_options,
             potential_cfg_options,
+            target,
             env,
             proc_macro,
             dependencies: Vec::new(),
+            origin,
         };
         let crate_id = CrateId(self.arena.len() as u32);
         let prev = self.arena.insert(crate_id, data);
@@ -422,6 +452,66 @@ impl CrateData {
     }
 }
 
+impl TargetInfo {
+    /// The target rust-analyzer itself runs on.
+    pub fn host() -> TargetInfo {
+        TargetInfo {
+            triple: None,
+            pointer_width: (std::mem::size_of::<usize>() * 8) as u32,
+            endian: if cfg!(target_endian = "big") { Endian::Big } else { Endian::Little },
+        }
+    }
+
+    /// Guesses the target information from the architecture part of a target triple. Unknown
+    /// architectures get the pointer width of the host.
+    pub fn from_triple(triple: &str) -> TargetInfo {
+        let arch = triple.split('-').next().unwrap_or_default();
+        let arch_is = |prefixes: &[&str]| prefixes.iter().any(|it| arch.starts_with(it));
+        let pointer_width = if arch_is(&[
+            "aarch64",
+            "bpf",
+            "loongarch64",
+            "mips64",
+            "nvptx64",
+            "powerpc64",
+            "riscv64",
+            "s390x",
+            "sparc64",
+            "sparcv9",
+            "wasm64",
+            "x86_64",
+        ]) {
+            64
+        } else if arch_is(&["avr", "msp430"]) {
+            16
+        } else if arch_is(&[
+            "arm", "csky", "hexagon", "i386", "i586", "i686", "m68k", "mips", "powerpc", "riscv32",
+            "sparc", "thumb", "wasm32",
+        ]) {
+            32
+        } else {
+            TargetInfo::host().pointer_width
+        };
+        let endian = if arch.ends_with("el") || arch.ends_with("le") {
+            Endian::Little
+        } else if arch.ends_with("eb")
+            || arch.ends_with("_be")
+            || arch_is(&["armeb", "m68k", "mips", "powerpc", "s390x", "sparc"])
+        {
+            Endian::Big
+        } else {
+            Endian::Little
+        };
+        TargetInfo { triple: Some(triple.to_string()), pointer_width, endian }
+    }
+}
+
+impl Default for TargetInfo {
+    fn default() -> TargetInfo {
+        TargetInfo::host()
+    }
+}
+
 impl FromStr for Edition {
     type Err = ParseEditionError;
 
@@ -497,7 +587,10 @@ impl fmt::Display for CyclicDependenciesError {
 
 #[cfg(test)]
 mod tests {
-    use super::{CfgOptions, CrateGraph, CrateName, Dependency, Edition::Edition2018, Env, FileId};
+    use super::{
+        CfgOptions, CrateGraph, CrateName, CrateOrigin, Dependency, Edition::Edition2018, Endian,
+        Env, FileId, TargetInfo,
+    };
 
     #[test]
     fn detect_cyclic_dependency_indirect() {
@@ -508,8 +601,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         let crate2 = graph.add_crate_root(
             FileId(2u32),
@@ -517,8 +612,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         let crate3 = graph.add_crate_root(
             FileId(3u32),
@@ -526,8 +623,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         assert!(graph.add_dep(crate1, CrateName::new("crate2").unwrap(), crate2).is_ok());
         assert!(graph.add_dep(crate2, CrateName::new("crate3").unwrap(), crate3).is_ok());
@@ -543,8 +642,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         let crate2 = graph.add_crate_root(
             FileId(2u32),
@@ -552,8 +653,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         assert!(graph.add_dep(crate1, CrateName::new("crate2").unwrap(), crate2).is_ok());
         assert!(graph.add_dep(crate2, CrateName::new("crate2").unwrap(), crate2).is_err());
@@ -568,8 +671,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         let crate2 = graph.add_crate_root(
             FileId(2u32),
@@ -577,8 +682,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         let crate3 = graph.add_crate_root(
             FileId(3u32),
@@ -586,8 +693,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         assert!(graph.add_dep(crate1, CrateName::new("crate2").unwrap(), crate2).is_ok());
         assert!(graph.add_dep(crate2, CrateName::new("crate3").unwrap(), crate3).is_ok());
@@ -602,8 +711,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         let crate2 = graph.add_crate_root(
             FileId(2u32),
@@ -611,8 +722,10 @@ mod tests {
             None,
             CfgOptions::default(),
             CfgOptions::default(),
+            TargetInfo::default(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         assert!(graph
             .add_dep(crate1, CrateName::normalize_dashes("crate-name-with-dashes"), crate2)
@@ -625,4 +738,25 @@ mod tests {
             }]
         );
     }
+
+    #[test]
+    fn target_info_from_triple() {
+        let check = |triple: &str, pointer_width, endian| {
+            let target = TargetInfo::from_triple(triple);
+            assert_eq!(
+                (target.pointer_width, target.endian),
+                (pointer_width, endian),
+                "{}",
+                triple
+            );
+        };
+        check("x86_64-unknown-linux-gnu", 64, Endian::Little);
+        check("i686-pc-windows-msvc", 32, Endian::Little);
+        check("thumbv7em-none-eabihf", 32, Endian::Little);
+        check("mips64el-unknown-linux-gnuabi64", 64, Endian::Little);
+        check("mips-unknown-linux-gnu", 32, Endian::Big);
+        check("powerpc64le-unknown-linux-gnu", 64, Endian::Little);
+        check("s390x-unknown-linux-gnu", 64, Endian::Big);
+        check("avr-unknown-gnu-atmega328", 16, Endian::Little);
+    }
 }
@@ -11,8 +11,9 @@ use syntax::{ast, Parse, SourceFile, TextRange, TextSize};
 pub use crate::{
     change::Change,
     input::{
-        CrateData, CrateDisplayName, CrateGraph, CrateId, CrateName, Dependency, Edition, Env,
-        ProcMacro, ProcMacroExpander, ProcMacroId, ProcMacroKind, SourceRoot, SourceRootId,
+        CrateData, CrateDisplayName, CrateGraph, CrateId, CrateName, CrateOrigin, Dependency,
+        Edition, Endian, Env, ProcMacro, ProcMacroExpander, ProcMacroId, ProcMacroKind, SourceRoot,
+        SourceRootId, TargetInfo,
     },
 };
 pub use salsa::{self, Cancelled};
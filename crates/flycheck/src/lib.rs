@@ -29,6 +29,8 @@ pub enum FlycheckConfig {
         all_features: bool,
         features: Vec<String>,
         extra_args: Vec<String>,
+        /// Always check the whole workspace, even if a narrower [`CheckScope`] was requested.
+        workspace: bool,
     },
     CustomCommand {
         command: String,
@@ -47,12 +49,58 @@ impl fmt::Display for FlycheckConfig {
     }
 }
 
+/// The part of the workspace a check covers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckScope {
+    Workspace,
+    /// A single package, identified by its `-p` spec, or only one of its targets.
+    Package {
+        spec: String,
+        target: Option<PackageTarget>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PackageTarget {
+    Lib,
+    Bin(String),
+}
+
+impl CheckScope {
+    /// Whether a check of `self` covers everything `other` does.
+    pub fn contains(&self, other: &CheckScope) -> bool {
+        match (self, other) {
+            (CheckScope::Workspace, _) => true,
+            (CheckScope::Package { .. }, CheckScope::Workspace) => false,
+            (
+                CheckScope::Package { spec, target },
+                CheckScope::Package { spec: other_spec, target: other_target },
+            ) => spec == other_spec && (target.is_none() || target == other_target),
+        }
+    }
+
+    /// A check covering both `self` and `other`.
+    fn union(self, other: CheckScope) -> CheckScope {
+        match (self, other) {
+            (
+                CheckScope::Package { spec, target },
+                CheckScope::Package { spec: other_spec, target: other_target },
+            ) if spec == other_spec => {
+                let target = if target == other_target { target } else { None };
+                CheckScope::Package { spec, target }
+            }
+            _ => CheckScope::Workspace,
+        }
+    }
+}
+
 /// Flycheck wraps the shared state and communication machinery used for
 /// running `cargo check` (or other compatible command) and providing
 /// diagnostics based on the output.
 /// The spawned thread is shut down when this struct is dropped.
 #[derive(Debug)]
 pub struct FlycheckHandle {
+    id: usize,
     // XXX: drop order is significant
     sender: Sender<Restart>,
     thread: jod_thread::JoinHandle,
@@ -71,12 +119,22 @@ impl FlycheckHandle {
             .name("Flycheck".to_owned())
             .spawn(move || actor.run(receiver))
             .expect("failed to spawn thread");
-        FlycheckHandle { sender, thread }
+        FlycheckHandle { id, sender, thread }
     }
 
     /// Schedule a re-start of the cargo check worker.
     pub fn update(&self) {
-        self.sender.send(Restart).unwrap();
+        self.update_scope(CheckScope::Workspace);
+    }
+
+    /// Schedule a re-start of the cargo check worker, checking only `scope` if the config allows
+    /// it.
+    pub fn update_scope(&self, scope: CheckScope) {
+        self.sender.send(Restart(scope)).unwrap();
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
     }
 }
 
@@ -109,19 +167,22 @@ impl fmt::Debug for Message {
 
 #[derive(Debug)]
 pub enum Progress {
-    DidStart,
+    /// A check started. Diagnostics of earlier checks within `scope` are now outdated.
+    DidStart(CheckScope),
     DidCheckCrate(String),
     DidFinish(io::Result<()>),
     DidCancel,
 }
 
-struct Restart;
+struct Restart(CheckScope);
 
 struct FlycheckActor {
     id: usize,
     sender: Box<dyn Fn(Message) + Send>,
     config: FlycheckConfig,
     workspace_root: AbsPathBuf,
+    /// The scope of the running check.
+    scope: CheckScope,
     /// WatchThread exists to wrap around the communication needed to be able to
     /// run `cargo check` without blocking. Currently the Rust standard library
     /// doesn't provide a way to read sub-process output without blocking, so we
@@ -142,7 +203,14 @@ impl FlycheckActor {
         config: FlycheckConfig,
         workspace_root: AbsPathBuf,
     ) -> FlycheckActor {
-        FlycheckActor { id, sender, config, workspace_root, cargo_handle: None }
+        FlycheckActor {
+            id,
+            sender,
+            config,
+            workspace_root,
+            scope: CheckScope::Workspace,
+            cargo_handle: None,
+        }
     }
     fn progress(&self, progress: Progress) {
         self.send(Message::Progress { id: self.id, progress });
@@ -157,17 +225,25 @@ impl FlycheckActor {
     fn run(mut self, inbox: Receiver<Restart>) {
         while let Some(event) = self.next_event(&inbox) {
             match event {
-                Event::Restart(Restart) => {
-                    while let Ok(Restart) = inbox.recv_timeout(Duration::from_millis(50)) {}
+                Event::Restart(Restart(mut scope)) => {
+                    while let Ok(Restart(next)) = inbox.recv_timeout(Duration::from_millis(50)) {
+                        scope = scope.union(next);
+                    }
 
+                    // The diagnostics of a cancelled check are incomplete, so the next check has
+                    // to cover its scope as well.
+                    if self.cargo_handle.is_some() {
+                        scope = scope.union(self.scope.clone());
+                    }
                     self.cancel_check_process();
+                    self.scope = self.effective_scope(scope);
 
                     let mut command = self.check_command();
                     log::info!("restart flycheck {:?}", command);
                     command.stdout(Stdio::piped()).stderr(Stdio::null()).stdin(Stdio::null());
                     if let Ok(child) = command.spawn().map(JodChild) {
                         self.cargo_handle = Some(CargoHandle::spawn(child));
-                        self.progress(Progress::DidStart);
+                        self.progress(Progress::DidStart(self.scope.clone()));
                     }
                 }
                 Event::CheckEvent(None) => {
@@ -205,6 +281,21 @@ impl FlycheckActor {
             self.progress(Progress::DidCancel);
         }
     }
+    /// Narrower checks are only possible with cargo, and only if the user didn't opt out of them.
+    /// Checking all targets of a package always covers the whole package.
+    fn effective_scope(&self, scope: CheckScope) -> CheckScope {
+        match (&self.config, scope) {
+            (
+                FlycheckConfig::CargoCommand { workspace: false, all_targets, .. },
+                CheckScope::Package { spec, target },
+            ) => {
+                let target = if *all_targets { None } else { target };
+                CheckScope::Package { spec, target }
+            }
+            _ => CheckScope::Workspace,
+        }
+    }
+
     fn check_command(&self) -> Command {
         let mut cmd = match &self.config {
             FlycheckConfig::CargoCommand {
@@ -215,18 +306,36 @@ impl FlycheckActor {
                 all_features,
                 extra_args,
                 features,
+                workspace: _,
             } => {
                 let mut cmd = Command::new(toolchain::cargo());
                 cmd.arg(command);
                 cmd.current_dir(&self.workspace_root);
-                cmd.args(&["--workspace", "--message-format=json", "--manifest-path"])
+                cmd.args(&["--message-format=json", "--manifest-path"])
                     .arg(self.workspace_root.join("Cargo.toml").as_os_str());
+                match &self.scope {
+                    CheckScope::Workspace => {
+                        cmd.arg("--workspace");
+                    }
+                    CheckScope::Package { spec, .. } => {
+                        cmd.args(&["-p", spec.as_str()]);
+                    }
+                }
 
                 if let Some(target) = target_triple {
                     cmd.args(&["--target", target.as_str()]);
                 }
-                if *all_targets {
-                    cmd.arg("--all-targets");
+                match &self.scope {
+                    _ if *all_targets => {
+                        cmd.arg("--all-targets");
+                    }
+                    CheckScope::Package { target: Some(PackageTarget::Lib), .. } => {
+                        cmd.arg("--lib");
+                    }
+                    CheckScope::Package { target: Some(PackageTarget::Bin(name)), .. } => {
+                        cmd.args(&["--bin", name.as_str()]);
+                    }
+                    _ => (),
                 }
                 if *all_features {
                     cmd.arg("--all-features");
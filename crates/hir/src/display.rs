@@ -7,14 +7,15 @@ use hir_def::{
 };
 use hir_ty::display::{
     write_bounds_like_dyn_trait_with_prefix, write_visibility, HirDisplay, HirDisplayError,
-    HirFormatter, SizedByDefault,
+    HirDisplayWrapper, HirFormatter, SizedByDefault,
 };
 use hir_ty::Interner;
 use syntax::ast::{self, NameOwner};
 
 use crate::{
-    Adt, Const, ConstParam, Enum, Field, Function, GenericParam, HasVisibility, LifetimeParam,
-    Module, Static, Struct, Trait, TyBuilder, Type, TypeAlias, TypeParam, Union, Variant,
+    db::HirDatabase, Adt, Const, ConstParam, Enum, Field, Function, GenericParam, HasVisibility,
+    LifetimeParam, Module, Static, Struct, Trait, TyBuilder, Type, TypeAlias, TypeParam, Union,
+    Variant,
 };
 
 impl HirDisplay for Function {
@@ -180,7 +181,7 @@ impl HirDisplay for Variant {
         let data = self.variant_data(f.db);
         match &*data {
             VariantData::Unit => {}
-            VariantData::Tuple(fields) => {
+            VariantData::Tuple { fields, .. } => {
                 write!(f, "(")?;
                 let mut first = true;
                 for (_, field) in fields.iter() {
@@ -194,7 +195,7 @@ impl HirDisplay for Variant {
                 }
                 write!(f, ")")?;
             }
-            VariantData::Record(fields) => {
+            VariantData::Record { fields, .. } => {
                 write!(f, " {{")?;
                 let mut first = true;
                 for (_, field) in fields.iter() {
@@ -221,6 +222,20 @@ impl HirDisplay for Type {
     }
 }
 
+impl Type {
+    /// Renders the type, replacing everything nested deeper than `max_depth` with `…`.
+    ///
+    /// Note that this shadows [`HirDisplay::display_truncated`], which limits the rendered
+    /// length instead.
+    pub fn display_truncated<'a>(
+        &'a self,
+        db: &'a dyn HirDatabase,
+        max_depth: Option<usize>,
+    ) -> HirDisplayWrapper<'a, Type> {
+        self.display_max_depth(db, max_depth)
+    }
+}
+
 impl HirDisplay for GenericParam {
     fn hir_fmt(&self, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
         match self {
@@ -438,10 +453,13 @@ impl HirDisplay for TypeAlias {
         write_visibility(self.module(f.db).id, self.visibility(f.db), f)?;
         let data = f.db.type_alias_data(self.id);
         write!(f, "type {}", data.name)?;
+        let def_id = GenericDefId::TypeAliasId(self.id);
+        write_generic_params(def_id, f)?;
         if !data.bounds.is_empty() {
             write!(f, ": ")?;
             f.write_joined(&data.bounds, " + ")?;
         }
+        write_where_clause(def_id, f)?;
         if let Some(ty) = &data.type_ref {
             write!(f, " = ")?;
             ty.hir_fmt(f)?;
@@ -31,25 +31,30 @@ pub mod db;
 
 mod display;
 
+#[cfg(test)]
+mod tests;
+#[cfg(test)]
+mod test_db;
+
 use std::{iter, sync::Arc};
 
 use arrayvec::ArrayVec;
-use base_db::{CrateDisplayName, CrateId, Edition, FileId};
+use base_db::{CrateDisplayName, CrateId, Edition, FileId, TargetInfo};
 use either::Either;
 use hir_def::{
-    adt::{ReprKind, VariantData},
+    adt::{ReprData, ReprKind, VariantData},
     body::{BodyDiagnostic, SyntheticSyntax},
-    expr::{BindingAnnotation, LabelId, Pat, PatId},
+    expr::{BindingAnnotation, Expr, ExprId, LabelId, Pat, PatId},
     item_tree::ItemTreeNode,
     lang_item::LangItemTarget,
     nameres,
     per_ns::PerNs,
-    resolver::{HasResolver, Resolver},
+    resolver::{resolver_for_expr, HasResolver, Resolver, ValueNs},
     src::HasSource as _,
-    AdtId, AssocContainerId, AssocItemId, AssocItemLoc, AttrDefId, ConstId, ConstParamId,
-    DefWithBodyId, EnumId, FunctionId, GenericDefId, HasModule, ImplId, LifetimeParamId,
-    LocalEnumVariantId, LocalFieldId, Lookup, ModuleId, StaticId, StructId, TraitId, TypeAliasId,
-    TypeParamId, UnionId,
+    AdtId, AsMacroCall, AssocContainerId, AssocItemId, AssocItemLoc, AttrDefId, ConstId,
+    ConstParamId, DefWithBodyId, EnumId, EnumVariantId, FunctionId, GenericDefId, HasModule,
+    ImplId, LifetimeParamId, LocalEnumVariantId, LocalFieldId, Lookup, ModuleId, StaticId,
+    StructId, TraitId, TypeAliasId, TypeParamId, UnionId,
 };
 use hir_expand::{name::name, MacroCallKind, MacroDefId, MacroDefKind};
 use hir_ty::{
@@ -73,7 +78,7 @@ use rustc_hash::FxHashSet;
 use stdx::{format_to, impl_from};
 use syntax::{
     ast::{self, AttrsOwner, NameOwner},
-    AstNode, AstPtr, SmolStr, SyntaxKind, SyntaxNodePtr,
+    AstNode, AstPtr, SmolStr, SyntaxKind, SyntaxNode, SyntaxNodePtr, TextSize,
 };
 use tt::{Ident, Leaf, Literal, TokenTree};
 
@@ -89,7 +94,9 @@ pub use crate::{
         UnresolvedModule, UnresolvedProcMacro,
     },
     has_source::HasSource,
-    semantics::{PathResolution, Semantics, SemanticsScope, TypeInfo},
+    semantics::{
+        ExpandedMacro, MacroInvocation, PathResolution, Semantics, SemanticsScope, TypeInfo,
+    },
 };
 
 // Be careful with these re-exports.
@@ -106,7 +113,7 @@ pub use {
     hir_def::{
         adt::StructKind,
         attr::{Attr, Attrs, AttrsWithOwner, Documentation},
-        find_path::PrefixKind,
+        find_path::{FindPathConfig, PrefixKind},
         import_map,
         nameres::ModuleSource,
         path::{ModPath, PathKind},
@@ -115,9 +122,14 @@ pub use {
     },
     hir_expand::{
         name::{known, Name},
-        ExpandResult, HirFileId, InFile, MacroFile, Origin,
+        ExpandError, ExpandResult, HirFileId, InFile, MacroFile, Origin,
+    },
+    hir_ty::{
+        consteval::{ComputedExpr, ConstEvalError},
+        display::HirDisplay,
+        layout::LayoutError,
+        object_safety::MethodViolationCode,
     },
-    hir_ty::display::HirDisplay,
 };
 
 // These are negative re-exports: pub using these names is forbidden, they
@@ -187,6 +199,11 @@ impl Crate {
         db.crate_graph()[self.id].display_name.clone()
     }
 
+    /// The version of the package this crate belongs to, as set in `CARGO_PKG_VERSION`.
+    pub fn version(self, db: &dyn HirDatabase) -> Option<String> {
+        db.crate_graph()[self.id].env.get("CARGO_PKG_VERSION")
+    }
+
     pub fn query_external_importables(
         self,
         db: &dyn DefDatabase,
@@ -236,6 +253,10 @@ impl Crate {
     pub fn potential_cfg(&self, db: &dyn HirDatabase) -> CfgOptions {
         db.crate_graph()[self.id].potential_cfg_options.clone()
     }
+
+    pub fn target(&self, db: &dyn HirDatabase) -> TargetInfo {
+        db.crate_graph()[self.id].target.clone()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -366,6 +387,10 @@ impl ModuleDef {
             ModuleDef::BuiltinType(_) => return None,
         })
     }
+
+    pub fn docs(&self, db: &dyn HirDatabase) -> Option<Documentation> {
+        self.attrs(db)?.docs()
+    }
 }
 
 impl HasVisibility for ModuleDef {
@@ -656,8 +681,13 @@ impl Module {
 
     /// Finds a path that can be used to refer to the given item from within
     /// this module, if possible.
-    pub fn find_use_path(self, db: &dyn DefDatabase, item: impl Into<ItemInNs>) -> Option<ModPath> {
-        hir_def::find_path::find_path(db, item.into().into(), self.into())
+    pub fn find_use_path(
+        self,
+        db: &dyn DefDatabase,
+        item: impl Into<ItemInNs>,
+        config: FindPathConfig,
+    ) -> Option<ModPath> {
+        hir_def::find_path::find_path(db, item.into().into(), self.into(), config)
     }
 
     /// Finds a path that can be used to refer to the given item from within
@@ -667,8 +697,15 @@ impl Module {
         db: &dyn DefDatabase,
         item: impl Into<ItemInNs>,
         prefix_kind: PrefixKind,
+        config: FindPathConfig,
     ) -> Option<ModPath> {
-        hir_def::find_path::find_path_prefixed(db, item.into().into(), self.into(), prefix_kind)
+        hir_def::find_path::find_path_prefixed(
+            db,
+            item.into().into(),
+            self.into(),
+            prefix_kind,
+            config,
+        )
     }
 }
 
@@ -694,7 +731,7 @@ pub enum FieldSource {
 
 impl Field {
     pub fn name(&self, db: &dyn HirDatabase) -> Name {
-        self.parent.variant_data(db).fields()[self.id].name.clone()
+        self.parent.variant_data(db).field_or_cfg_disabled(self.id).name.clone()
     }
 
     /// Returns the type as in the signature of the struct (i.e., with
@@ -708,19 +745,34 @@ impl Field {
             VariantDef::Variant(it) => it.parent.id.into(),
         };
         let substs = TyBuilder::type_params_subst(db, generic_def_id);
-        let ty = db.field_types(var_id)[self.id].clone().substitute(&Interner, &substs);
+        let ty = match db.field_types(var_id).get(self.id) {
+            Some(ty) => ty.clone().substitute(&Interner, &substs),
+            // Fields disabled by `#[cfg]` are not part of the type, lower them on their own.
+            None => {
+                let resolver = var_id.resolver(db.upcast());
+                let ctx = hir_ty::TyLoweringContext::new(db, &resolver);
+                let variant_data = self.parent.variant_data(db);
+                ctx.lower_ty(&variant_data.field_or_cfg_disabled(self.id).type_ref)
+            }
+        };
         Type::new(db, self.parent.module(db).id.krate(), var_id, ty)
     }
 
     pub fn parent_def(&self, _db: &dyn HirDatabase) -> VariantDef {
         self.parent
     }
+
+    /// Whether the `#[cfg]` attributes on this field are enabled in its crate.
+    pub fn is_cfg_enabled(&self, db: &dyn HirDatabase) -> bool {
+        let krate = self.parent.module(db).id.krate();
+        self.attrs(db).is_cfg_enabled(&db.crate_graph()[krate].cfg_options)
+    }
 }
 
 impl HasVisibility for Field {
     fn visibility(&self, db: &dyn HirDatabase) -> Visibility {
         let variant_data = self.parent.variant_data(db);
-        let visibility = &variant_data.fields()[self.id].visibility;
+        let visibility = &variant_data.field_or_cfg_disabled(self.id).visibility;
         let parent_id: hir_def::VariantId = self.parent.into();
         visibility.resolve(db.upcast(), &parent_id.resolver(db.upcast()))
     }
@@ -754,6 +806,11 @@ impl Struct {
     }
 
     pub fn repr(self, db: &dyn HirDatabase) -> Option<ReprKind> {
+        db.struct_data(self.id).repr.as_ref().map(|repr| repr.kind.clone())
+    }
+
+    /// All of the `#[repr]` attributes of the struct, including packing and alignment.
+    pub fn repr_data(self, db: &dyn HirDatabase) -> Option<ReprData> {
         db.struct_data(self.id).repr.clone()
     }
 
@@ -761,6 +818,18 @@ impl Struct {
         self.variant_data(db).kind()
     }
 
+    pub fn is_unit(self, db: &dyn HirDatabase) -> bool {
+        self.kind(db) == StructKind::Unit
+    }
+
+    pub fn is_tuple(self, db: &dyn HirDatabase) -> bool {
+        self.kind(db) == StructKind::Tuple
+    }
+
+    pub fn is_record(self, db: &dyn HirDatabase) -> bool {
+        self.kind(db) == StructKind::Record
+    }
+
     fn variant_data(self, db: &dyn HirDatabase) -> Arc<VariantData> {
         db.struct_data(self.id).variant_data.clone()
     }
@@ -855,7 +924,7 @@ impl Variant {
     }
 
     pub fn name(self, db: &dyn HirDatabase) -> Name {
-        db.enum_data(self.parent.id).variants[self.id].name.clone()
+        db.enum_data(self.parent.id).variant_or_cfg_disabled(self.id).name.clone()
     }
 
     pub fn fields(self, db: &dyn HirDatabase) -> Vec<Field> {
@@ -870,8 +939,41 @@ impl Variant {
         self.variant_data(db).kind()
     }
 
+    pub fn is_unit(self, db: &dyn HirDatabase) -> bool {
+        self.kind(db) == StructKind::Unit
+    }
+
+    pub fn is_tuple(self, db: &dyn HirDatabase) -> bool {
+        self.kind(db) == StructKind::Tuple
+    }
+
+    pub fn is_record(self, db: &dyn HirDatabase) -> bool {
+        self.kind(db) == StructKind::Record
+    }
+
     pub(crate) fn variant_data(self, db: &dyn HirDatabase) -> Arc<VariantData> {
-        db.enum_data(self.parent.id).variants[self.id].variant_data.clone()
+        db.enum_data(self.parent.id).variant_or_cfg_disabled(self.id).variant_data.clone()
+    }
+
+    /// Whether the `#[cfg]` attributes on this variant are enabled in its crate.
+    pub fn is_cfg_enabled(self, db: &dyn HirDatabase) -> bool {
+        let krate = self.module(db).id.krate();
+        self.attrs(db).is_cfg_enabled(&db.crate_graph()[krate].cfg_options)
+    }
+
+    /// The position of this variant among the variants of its enum, in source order.
+    /// Variants that are disabled by `#[cfg]` are not counted, they are numbered after all the
+    /// enabled ones.
+    pub fn source_order_index(self, _db: &dyn HirDatabase) -> usize {
+        u32::from(self.id.into_raw()) as usize
+    }
+
+    /// Evaluates the discriminant of the variant, explicit or implicit.
+    pub fn eval_discriminant(self, db: &dyn HirDatabase) -> Result<i128, ConstEvalError> {
+        if !self.is_cfg_enabled(db) {
+            return Err(ConstEvalError::NotSupported("variant disabled by `#[cfg]`"));
+        }
+        db.const_eval_discriminant(EnumVariantId { parent: self.parent.id, local_id: self.id })
     }
 }
 
@@ -897,6 +999,17 @@ impl Adt {
         subst.iter().any(|ty| ty.skip_binders().is_unknown())
     }
 
+    /// The memory layout of this ADT. Fails for generic ADTs.
+    pub fn layout(self, db: &dyn HirDatabase) -> Result<Layout, LayoutError> {
+        let layout = db.layout_of_adt(self.into())?;
+        let variant = match self {
+            Adt::Struct(it) => Some(VariantDef::Struct(it)),
+            Adt::Union(it) => Some(VariantDef::Union(it)),
+            Adt::Enum(_) => None,
+        };
+        Ok(Layout { layout, variant })
+    }
+
     /// Turns this ADT into a type. Any type parameters of the ADT will be
     /// turned into unknown types, which is good for e.g. finding the most
     /// general set of completions, but will not look very nice when printed.
@@ -1025,6 +1138,44 @@ impl Function {
         Type::new_with_resolver_inner(db, krate, &resolver, ty)
     }
 
+    /// For async functions, returns the return type as written in the signature, which is
+    /// the output of the future returned by the function.
+    pub fn async_ret_type(self, db: &dyn HirDatabase) -> Option<Type> {
+        let async_ret_type = db.function_data(self.id).async_ret_type.clone()?;
+        let resolver = self.id.resolver(db.upcast());
+        let krate = self.id.lookup(db.upcast()).container.module(db.upcast()).krate();
+        let ctx = hir_ty::TyLoweringContext::new(db, &resolver);
+        let ty = ctx.lower_ty(&async_ret_type);
+        Some(Type::new_with_resolver_inner(db, krate, &resolver, ty))
+    }
+
+    /// Whether the return type is written as `impl Trait`. The implicit `impl Future` of
+    /// async functions does not count.
+    pub fn ret_type_is_impl_trait(self, db: &dyn HirDatabase) -> bool {
+        let data = db.function_data(self.id);
+        !data.is_async() && matches!(&*data.ret_type, TypeRef::ImplTrait(_))
+    }
+
+    /// The traits bounding the return type, if it is written as `impl Trait`.
+    pub fn impl_trait_bounds_of_ret(self, db: &dyn HirDatabase) -> Option<Vec<Trait>> {
+        if !self.ret_type_is_impl_trait(db) {
+            return None;
+        }
+        let impl_traits = db.return_type_impl_traits(self.id)?;
+        let bounds = impl_traits.skip_binders().bounds(0)?;
+        let traits = bounds
+            .skip_binders()
+            .iter()
+            .filter_map(|pred| match pred.skip_binders() {
+                hir_ty::WhereClause::Implemented(trait_ref) => {
+                    Some(Trait::from(trait_ref.hir_trait_id()))
+                }
+                _ => None,
+            })
+            .collect();
+        Some(traits)
+    }
+
     pub fn self_param(self, db: &dyn HirDatabase) -> Option<SelfParam> {
         if !db.function_data(self.id).has_self_param() {
             return None;
@@ -1061,10 +1212,58 @@ impl Function {
         db.function_data(self.id).is_unsafe()
     }
 
+    pub fn is_const(self, db: &dyn HirDatabase) -> bool {
+        db.function_data(self.id).is_const()
+    }
+
     pub fn is_async(self, db: &dyn HirDatabase) -> bool {
         db.function_data(self.id).is_async()
     }
 
+    /// Whether this function is annotated with `#[test]`.
+    pub fn is_test(self, db: &dyn HirDatabase) -> bool {
+        self.attrs(db).by_key("test").exists()
+    }
+
+    /// Whether this function is annotated with `#[bench]`.
+    pub fn is_bench(self, db: &dyn HirDatabase) -> bool {
+        self.attrs(db).by_key("bench").exists()
+    }
+
+    /// Returns the functions and methods called from the body of this function, including
+    /// calls produced by macro expansion. Each callee comes with the name reference of the
+    /// call, or the whole callee expression if it has no name.
+    pub fn callees(self, db: &dyn HirDatabase) -> Vec<(Function, InFile<SyntaxNodePtr>)> {
+        let (body, source_map) = db.body_with_source_map(self.id.into());
+        let infer = db.infer(self.id.into());
+        body.exprs
+            .iter()
+            .filter_map(|(expr_id, expr)| {
+                let callee = match expr {
+                    Expr::Call { callee, .. } => match infer[*callee].callable_def(db)? {
+                        CallableDefId::FunctionId(it) => it,
+                        _ => return None,
+                    },
+                    Expr::MethodCall { .. } => infer.method_resolution(expr_id)?.0,
+                    _ => return None,
+                };
+                let src = source_map.expr_syntax(expr_id).ok()?;
+                let root = db.parse_or_expand(src.file_id)?;
+                let name_ref = match src.value.to_node(&root) {
+                    ast::Expr::CallExpr(call) => match call.expr()? {
+                        ast::Expr::PathExpr(path) => {
+                            path.path()?.segment()?.name_ref()?.syntax().clone()
+                        }
+                        callee => callee.syntax().clone(),
+                    },
+                    ast::Expr::MethodCallExpr(call) => call.name_ref()?.syntax().clone(),
+                    _ => return None,
+                };
+                Some((callee.into(), src.with_value(SyntaxNodePtr::new(&name_ref))))
+            })
+            .collect()
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase, acc: &mut Vec<AnyDiagnostic>) {
         let krate = self.module(db).id.krate();
 
@@ -1287,6 +1486,7 @@ impl Function {
 }
 
 // Note: logically, this belongs to `hir_ty`, but we are not using it there yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Access {
     Shared,
     Exclusive,
@@ -1302,6 +1502,13 @@ impl From<hir_ty::Mutability> for Access {
     }
 }
 
+/// The way a binding pattern binds its value, after match ergonomics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindingMode {
+    Move,
+    Ref(Mutability),
+}
+
 #[derive(Clone, Debug)]
 pub struct Param {
     func: Function,
@@ -1321,6 +1528,11 @@ impl Param {
         Local { parent, pat_id: body.params[self.idx] }
     }
 
+    /// Returns the name of the parameter if its pattern is a plain binding, like `x` in `x: u32`.
+    pub fn name(&self, db: &dyn HirDatabase) -> Option<Name> {
+        self.as_local(db).name(db)
+    }
+
     pub fn pattern_source(&self, db: &dyn HirDatabase) -> Option<ast::Pat> {
         self.source(db).and_then(|p| p.value.pat())
     }
@@ -1405,6 +1617,11 @@ impl Const {
         let ty = ctx.lower_ty(&data.type_ref);
         Type::new_with_resolver_inner(db, krate.id, &resolver, ty)
     }
+
+    /// Evaluates the value of the constant.
+    pub fn eval(self, db: &dyn HirDatabase) -> Result<ComputedExpr, ConstEvalError> {
+        db.const_eval(self.id)
+    }
 }
 
 impl HasVisibility for Const {
@@ -1467,6 +1684,14 @@ impl Trait {
         db.trait_data(self.id).items.iter().map(|(_name, it)| (*it).into()).collect()
     }
 
+    /// Like [`Trait::items`], but also includes the items of all supertraits.
+    pub fn items_with_supertraits(self, db: &dyn HirDatabase) -> Vec<AssocItem> {
+        hir_ty::all_super_traits(db.upcast(), self.id)
+            .into_iter()
+            .flat_map(|trait_| Trait::from(trait_).items(db))
+            .collect()
+    }
+
     pub fn is_auto(self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).is_auto
     }
@@ -1474,6 +1699,43 @@ impl Trait {
     pub fn is_unsafe(&self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).is_unsafe
     }
+
+    /// Whether `dyn Trait` is a valid type.
+    pub fn is_object_safe(self, db: &dyn HirDatabase) -> bool {
+        db.object_safety_violations(self.id).is_empty()
+    }
+
+    /// The reasons why this trait is not object safe, including the ones
+    /// caused by its supertraits.
+    pub fn object_safety_violations(self, db: &dyn HirDatabase) -> Vec<ObjectSafetyViolation> {
+        db.object_safety_violations(self.id)
+            .iter()
+            .map(|violation| match *violation {
+                hir_ty::object_safety::ObjectSafetyViolation::SizedSelf => {
+                    ObjectSafetyViolation::SizedSelf
+                }
+                hir_ty::object_safety::ObjectSafetyViolation::AssocConst(it) => {
+                    ObjectSafetyViolation::AssocConst(it.into())
+                }
+                hir_ty::object_safety::ObjectSafetyViolation::GenericAssocType(it) => {
+                    ObjectSafetyViolation::GenericAssocType(it.into())
+                }
+                hir_ty::object_safety::ObjectSafetyViolation::Method(it, code) => {
+                    ObjectSafetyViolation::Method(it.into(), code)
+                }
+            })
+            .collect()
+    }
+}
+
+/// A reason why a trait is not object safe, see [`Trait::object_safety_violations`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectSafetyViolation {
+    /// The trait, or one of its supertraits, requires `Self: Sized`.
+    SizedSelf,
+    AssocConst(Const),
+    GenericAssocType(TypeAlias),
+    Method(Function, MethodViolationCode),
 }
 
 impl HasVisibility for Trait {
@@ -1593,6 +1855,32 @@ impl MacroDef {
             MacroKind::Attr | MacroKind::Derive => false,
         }
     }
+
+    /// Whether this macro is defined by a `proc-macro` crate, regardless of its kind.
+    pub fn is_proc_macro(&self) -> bool {
+        matches!(self.id.kind, MacroDefKind::ProcMacro(..))
+    }
+
+    /// Whether this macro can be used inside of `#[derive(...)]`.
+    pub fn is_derive(&self) -> bool {
+        self.kind() == MacroKind::Derive
+    }
+
+    /// Whether this macro can be used as an attribute, like `#[test]` or a `#[proc_macro_attribute]`.
+    pub fn is_attr(&self) -> bool {
+        self.kind() == MacroKind::Attr
+    }
+
+    /// Expands `call` with this macro, one level deep.
+    ///
+    /// Macro calls in the expansion are left unexpanded. If the file of the call belongs to
+    /// several crates, the call is assumed to be made from the one with the lowest id.
+    pub fn expand(self, db: &dyn HirDatabase, call: InFile<&ast::MacroCall>) -> Option<SyntaxNode> {
+        let file_id = call.file_id.original_file(db.upcast());
+        let krate = *db.relevant_crates(file_id).iter().min()?;
+        let macro_call_id = call.as_call_id(db.upcast(), krate, |_| Some(self.id))?;
+        db.parse_or_expand(macro_call_id.as_file())
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
@@ -1899,6 +2187,67 @@ impl Local {
             ast.map_left(|it| it.cast().unwrap().to_node(&root)).map_right(|it| it.to_node(&root))
         })
     }
+
+    /// Whether the local is accessed exclusively at or after `position`, an offset into the file
+    /// of the enclosing body.
+    ///
+    /// This counts assignments, `&mut` borrows and method calls taking `&mut self`, also through
+    /// fields, indexing and dereferences, like [`Semantics::classify_local_access`] does.
+    pub fn is_mutated_after(self, db: &dyn HirDatabase, position: TextSize) -> bool {
+        let (body, source_map) = db.body_with_source_map(self.parent);
+        let infer = db.infer(self.parent);
+        for (expr_id, expr) in body.exprs.iter() {
+            let mut place = match expr {
+                Expr::BinaryOp {
+                    lhs,
+                    op: Some(hir_def::expr::BinaryOp::Assignment { .. }),
+                    ..
+                } => *lhs,
+                Expr::Ref { expr, mutability: Mutability::Mut, .. } => *expr,
+                Expr::MethodCall { receiver, .. } => match infer.method_resolution(expr_id) {
+                    Some((func, _))
+                        if matches!(
+                            Function::from(func).self_param(db).map(|it| it.access(db)),
+                            Some(Access::Exclusive)
+                        ) =>
+                    {
+                        *receiver
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            while let Expr::Field { expr, .. }
+            | Expr::Index { base: expr, .. }
+            | Expr::UnaryOp { expr, op: hir_def::expr::UnaryOp::Deref } = &body[place]
+            {
+                place = *expr;
+            }
+            if let Expr::Path(path) = &body[place] {
+                let resolver = resolver_for_expr(db.upcast(), self.parent, place);
+                let resolved =
+                    resolver.resolve_path_in_value_ns_fully(db.upcast(), path.mod_path());
+                if resolved == Some(ValueNs::LocalBinding(self.pat_id))
+                    && self.expr_offset(db, &source_map, place) >= Some(position)
+                {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn expr_offset(
+        self,
+        db: &dyn HirDatabase,
+        source_map: &hir_def::body::BodySourceMap,
+        expr: ExprId,
+    ) -> Option<TextSize> {
+        let src = source_map.expr_syntax(expr).ok()?;
+        let root = src.file_syntax(db.upcast());
+        let node = src.value.to_node(&root);
+        Some(src.with_value(node.syntax()).original_file_range(db.upcast()).range.start())
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -1970,6 +2319,10 @@ impl TypeParam {
         self.id.parent.module(db.upcast()).into()
     }
 
+    pub fn parent(self, _db: &dyn HirDatabase) -> GenericDef {
+        self.id.parent.into()
+    }
+
     pub fn ty(self, db: &dyn HirDatabase) -> Type {
         let resolver = self.id.parent.resolver(db.upcast());
         let krate = self.id.parent.module(db.upcast()).krate();
@@ -2090,31 +2443,16 @@ impl Impl {
                     .filter(filter),
             )
         });
-        for id in def_crates
-            .iter()
-            .flat_map(|&id| Crate { id }.transitive_reverse_dependencies(db))
-            .map(|Crate { id }| id)
-            .chain(def_crates.iter().copied())
-            .unique()
-        {
-            all.extend(
-                db.trait_impls_in_crate(id)
-                    .for_self_ty_without_blanket_impls(fp)
-                    .map(Self::from)
-                    .filter(filter),
-            );
+        for &id in def_crates.iter() {
+            let impls = db.trait_impls_for_self_ty_in_deps(id, fp);
+            all.extend(impls.iter().copied().map(Self::from).filter(filter));
         }
-        all
+        all.into_iter().unique().collect()
     }
 
     pub fn all_for_trait(db: &dyn HirDatabase, trait_: Trait) -> Vec<Impl> {
         let krate = trait_.module(db).krate();
-        let mut all = Vec::new();
-        for Crate { id } in krate.transitive_reverse_dependencies(db).into_iter() {
-            let impls = db.trait_impls_in_crate(id);
-            all.extend(impls.for_trait(trait_.id).map(Self::from))
-        }
-        all
+        db.trait_impls_in_rev_deps(krate.id).for_trait(trait_.id).map(Self::from).collect()
     }
 
     // FIXME: the return type is wrong. This should be a hir version of
@@ -2138,10 +2476,36 @@ impl Impl {
         db.impl_data(self.id).items.iter().map(|it| (*it).into()).collect()
     }
 
+    /// Like `items`, but sorted by the position of the items in the source.
+    /// Items expanded from a macro call are placed where the call is.
+    pub fn items_in_source_order(self, db: &dyn HirDatabase) -> Vec<AssocItem> {
+        let mut items = self.items(db);
+        items.sort_by_cached_key(|item| {
+            let range = match *item {
+                AssocItem::Function(it) => {
+                    it.source(db).map(|src| src.syntax().original_file_range(db.upcast()))
+                }
+                AssocItem::Const(it) => {
+                    it.source(db).map(|src| src.syntax().original_file_range(db.upcast()))
+                }
+                AssocItem::TypeAlias(it) => {
+                    it.source(db).map(|src| src.syntax().original_file_range(db.upcast()))
+                }
+            };
+            // items without a source go last
+            (range.is_none(), range.map(|it| (it.file_id, it.range.start())))
+        });
+        items
+    }
+
     pub fn is_negative(self, db: &dyn HirDatabase) -> bool {
         db.impl_data(self.id).is_negative
     }
 
+    pub fn is_unsafe(self, db: &dyn HirDatabase) -> bool {
+        db.impl_data(self.id).is_unsafe
+    }
+
     pub fn module(self, db: &dyn HirDatabase) -> Module {
         self.id.lookup(db.upcast()).container.into()
     }
@@ -2245,6 +2609,23 @@ impl Type {
         }
     }
 
+    /// Returns the element type of a slice type.
+    pub fn as_slice_element(&self) -> Option<Type> {
+        match self.ty.kind(&Interner) {
+            TyKind::Slice(ty) => Some(self.derived(ty.clone())),
+            _ => None,
+        }
+    }
+
+    /// Returns the element type of an array type, together with its length if that is known.
+    /// The length is unknown if it depends on a const generic parameter, for example.
+    pub fn as_array(&self, _db: &dyn HirDatabase) -> Option<(Type, Option<u64>)> {
+        match self.ty.kind(&Interner) {
+            TyKind::Array(ty, len) => Some((self.derived(ty.clone()), len.as_usize())),
+            _ => None,
+        }
+    }
+
     pub fn strip_references(&self) -> Type {
         self.derived(self.ty.strip_references().clone())
     }
@@ -2301,6 +2682,33 @@ impl Type {
         )
     }
 
+    /// Returns all impls for this type, inherent ones first and then the trait
+    /// impls grouped by trait. Within a group, impls are sorted by crate, with
+    /// dependencies before their dependents, and then by their position in
+    /// the source.
+    pub fn applicable_impls(&self, db: &dyn HirDatabase) -> Vec<(Option<Trait>, Impl)> {
+        let crate_order = db.crate_graph().crates_in_topological_order();
+        let mut impls: Vec<_> = Impl::all_for_type(db, self.clone())
+            .into_iter()
+            .map(|impl_| (impl_.trait_(db), impl_))
+            .collect();
+        impls.sort_by_cached_key(|(_, impl_)| {
+            let krate = impl_.module(db).krate().id;
+            let range = impl_.source(db).map(|src| src.syntax().original_file_range(db.upcast()));
+            (
+                crate_order.iter().position(|&it| it == krate),
+                range.map(|it| (it.file_id, it.range.start())),
+            )
+        });
+
+        // Trait groups are ordered by where their first impl is.
+        let traits: Vec<Trait> = impls.iter().filter_map(|(trait_, _)| *trait_).unique().collect();
+        impls.sort_by_key(|(trait_, _)| {
+            trait_.and_then(|trait_| traits.iter().position(|&it| it == trait_))
+        });
+        impls
+    }
+
     pub fn impls_trait(&self, db: &dyn HirDatabase, trait_: Trait, args: &[Type]) -> bool {
         let trait_ref = TyBuilder::trait_ref(db, trait_.id)
             .push(self.ty.clone())
@@ -2315,16 +2723,38 @@ impl Type {
         db.trait_solve(self.krate, goal).is_some()
     }
 
+    /// Normalizes `<Self as Trait<args>>::Alias<assoc_args>`. `assoc_args` are the arguments
+    /// of a generic associated type's own parameters; any that are left out, as well as missing
+    /// trait arguments, are inferred.
     pub fn normalize_trait_assoc_type(
         &self,
         db: &dyn HirDatabase,
         args: &[Type],
         alias: TypeAlias,
+        assoc_args: &[Type],
     ) -> Option<Type> {
-        let projection = TyBuilder::assoc_type_projection(db, alias.id)
-            .push(self.ty.clone())
-            .fill(args.iter().map(|t| t.ty.clone()))
-            .build();
+        let mut builder = TyBuilder::assoc_type_projection(db, alias.id).push(self.ty.clone());
+        let trait_param_count =
+            builder.remaining() - TyBuilder::assoc_type_own_param_count(db, alias.id);
+        // The bound variable 0 is the normalized type, fresh variables for missing arguments
+        // come after it.
+        let mut fresh_vars = 0;
+        for idx in 0..builder.remaining() {
+            let arg = match idx.checked_sub(trait_param_count) {
+                None => args.get(idx),
+                Some(idx) => assoc_args.get(idx),
+            };
+            let ty = match arg {
+                Some(arg) => arg.ty.clone(),
+                None => {
+                    fresh_vars += 1;
+                    TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, fresh_vars))
+                        .intern(&Interner)
+                }
+            };
+            builder = builder.push(ty);
+        }
+        let projection = builder.build();
         let goal = hir_ty::make_canonical(
             InEnvironment::new(
                 &self.env.env,
@@ -2335,7 +2765,7 @@ impl Type {
                 }
                 .cast(&Interner),
             ),
-            [TyVariableKind::General].iter().copied(),
+            iter::repeat(TyVariableKind::General).take(1 + fresh_vars),
         );
 
         match db.trait_solve(self.krate, goal)? {
@@ -2349,6 +2779,23 @@ impl Type {
         }
     }
 
+    /// Returns `<Self as Deref>::Target`, as declared by the type's `Deref` impl.
+    ///
+    /// Unlike [`Type::autoderef`], this only takes the single step through the trait and does
+    /// not dereference references and raw pointers built-in.
+    pub fn deref_target(&self, db: &dyn HirDatabase) -> Option<Type> {
+        let deref_trait = match db.lang_item(self.krate, SmolStr::new("deref"))? {
+            LangItemTarget::TraitId(it) => it,
+            _ => return None,
+        };
+        // Without an impl, the projection would just normalize to itself.
+        if !self.impls_trait(db, deref_trait.into(), &[]) {
+            return None;
+        }
+        let target = db.trait_data(deref_trait).associated_type_by_name(&name![Target])?;
+        self.normalize_trait_assoc_type(db, &[], target.into(), &[])
+    }
+
     pub fn is_copy(&self, db: &dyn HirDatabase) -> bool {
         let lang_item = db.lang_item(self.krate, SmolStr::new("copy"));
         let copy_trait = match lang_item {
@@ -2381,7 +2828,7 @@ impl Type {
 
         let adt = adt_id.into();
         match adt {
-            Adt::Struct(s) => matches!(s.repr(db), Some(ReprKind::Packed)),
+            Adt::Struct(s) => s.repr_data(db).map_or(false, |repr| repr.is_packed()),
             _ => false,
         }
     }
@@ -2534,6 +2981,76 @@ impl Type {
         )
     }
 
+    /// Like [`Type::iterate_method_candidates`], but after the candidates that are callable with
+    /// `traits_in_scope` it also visits the methods of all other traits implemented in the
+    /// dependencies of `krate`. Those are reported with the trait that has to be imported to
+    /// call them, all other candidates get `None`.
+    pub fn iterate_method_candidates_with_traits<T>(
+        &self,
+        db: &dyn HirDatabase,
+        krate: Crate,
+        traits_in_scope: &FxHashSet<TraitId>,
+        name: Option<&Name>,
+        mut callback: impl FnMut(&Ty, Function, Option<Trait>) -> Option<T>,
+    ) -> Option<T> {
+        let _p = profile::span("iterate_method_candidates_with_traits");
+        let mut callable = FxHashSet::default();
+        if let Some(res) =
+            self.iterate_method_candidates(db, krate, traits_in_scope, name, |ty, f| {
+                callable.insert(f);
+                callback(ty, f, None)
+            })
+        {
+            return Some(res);
+        }
+
+        let other_traits: FxHashSet<_> = db
+            .trait_impls_in_deps(krate.id)
+            .traits()
+            .filter(|it| !traits_in_scope.contains(it))
+            .collect();
+        self.iterate_method_candidates(db, krate, &other_traits, name, |ty, f| {
+            if callable.contains(&f) {
+                return None;
+            }
+            let trait_ = f.as_assoc_item(db)?.containing_trait(db)?;
+            callback(ty, f, Some(trait_))
+        })
+    }
+
+    /// Like [`Type::iterate_method_candidates`], but also visits methods of traits that are
+    /// not in scope and reports where each method comes from. Methods shadowed by another method
+    /// of the same name that method resolution would pick instead are skipped, and methods of
+    /// traits that are not in scope never shadow anything.
+    pub fn iterate_all_method_candidates<T>(
+        &self,
+        db: &dyn HirDatabase,
+        krate: Crate,
+        traits_in_scope: &FxHashSet<TraitId>,
+        mut callback: impl FnMut(&Ty, Function, MethodOrigin) -> Option<T>,
+    ) -> Option<T> {
+        let _p = profile::span("iterate_all_method_candidates");
+        let mut seen_methods = FxHashSet::default();
+        self.iterate_method_candidates_with_traits(
+            db,
+            krate,
+            traits_in_scope,
+            None,
+            |ty, func, trait_to_import| {
+                if !seen_methods.insert(func.name(db)) {
+                    return None;
+                }
+                // Methods of dyn and where clause traits are found regardless of `traits_in_scope`.
+                let origin = match (func.as_assoc_item(db)?.container(db), trait_to_import) {
+                    (_, Some(it)) => MethodOrigin::TraitNotInScope(it),
+                    (AssocItemContainer::Impl(it), None) => MethodOrigin::Inherent(it),
+                    (AssocItemContainer::Trait(it), None) => MethodOrigin::TraitInScope(it),
+                };
+                callback(ty, func, origin)
+            },
+        )
+    }
+
     pub fn iterate_path_candidates<T>(
         &self,
         db: &dyn HirDatabase,
@@ -2713,6 +3230,65 @@ impl Type {
         let tys = hir_ty::replace_errors_with_variables(&(self.ty.clone(), other.ty.clone()));
         could_unify(db, self.env.clone(), &tys)
     }
+
+    pub fn layout(&self, db: &dyn HirDatabase) -> Result<Layout, LayoutError> {
+        let variant = match self.ty.kind(&Interner) {
+            TyKind::Adt(hir_ty::AdtId(AdtId::StructId(it)), _) => {
+                Some(VariantDef::Struct((*it).into()))
+            }
+            TyKind::Adt(hir_ty::AdtId(AdtId::UnionId(it)), _) => {
+                Some(VariantDef::Union((*it).into()))
+            }
+            _ => None,
+        };
+        let layout = hir_ty::layout::layout_of_ty(db, &self.ty, self.krate)?;
+        Ok(Layout { layout, variant })
+    }
+}
+
+/// Where a method found by [`Type::iterate_all_method_candidates`] comes from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MethodOrigin {
+    Inherent(Impl),
+    /// A trait method that can be called without importing anything.
+    TraitInScope(Trait),
+    /// A trait method that can only be called once the trait is imported.
+    TraitNotInScope(Trait),
+}
+
+/// The memory layout of a type, see [`Type::layout`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Layout {
+    layout: hir_ty::layout::Layout,
+    /// The variant whose fields `layout.field_offsets` refers to, `None` for tuples.
+    variant: Option<VariantDef>,
+}
+
+impl Layout {
+    pub fn size(&self) -> u64 {
+        self.layout.size
+    }
+
+    pub fn align(&self) -> u64 {
+        self.layout.align
+    }
+
+    /// The offset of a field of the struct or union this is the layout of.
+    pub fn field_offset(&self, field: Field) -> Option<u64> {
+        if self.variant != Some(field.parent) {
+            return None;
+        }
+        let idx = u32::from(field.id.into_raw()) as usize;
+        self.layout.field_offsets.get(idx).copied()
+    }
+
+    /// The offset of the `idx`th field of the tuple this is the layout of.
+    pub fn tuple_field_offset(&self, idx: usize) -> Option<u64> {
+        if self.variant.is_some() {
+            return None;
+        }
+        self.layout.field_offsets.get(idx).copied()
+    }
 }
 
 // FIXME: closures
@@ -1,6 +1,7 @@
 //! See `Semantics`.
 
 mod source_to_def;
+mod macro_expansion;
 
 use std::{cell::RefCell, fmt, iter::successors};
 
@@ -10,7 +11,7 @@ use hir_def::{
     resolver::{self, HasResolver, Resolver, TypeNs},
     AsMacroCall, FunctionId, TraitId, VariantId,
 };
-use hir_expand::{name::AsName, ExpansionInfo};
+use hir_expand::{name::AsName, ExpandError, ExpansionInfo, MacroCallId, MacroCallKind};
 use hir_ty::{associated_type_shorthand_candidates, Interner};
 use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -20,13 +21,15 @@ use syntax::{
     match_ast, AstNode, SyntaxNode, SyntaxNodePtr, SyntaxToken, TextRange, TextSize,
 };
 
+pub use self::macro_expansion::{ExpandedMacro, MacroInvocation};
+
 use crate::{
     db::HirDatabase,
     semantics::source_to_def::{ChildContainer, SourceToDefCache, SourceToDefCtx},
     source_analyzer::{resolve_hir_path, SourceAnalyzer},
-    Access, AssocItem, Callable, ConstParam, Crate, Field, Function, HirFileId, Impl, InFile,
-    Label, LifetimeParam, Local, MacroDef, Module, ModuleDef, Name, Path, ScopeDef, Trait, Type,
-    TypeAlias, TypeParam, VariantDef,
+    Access, AssocItem, BindingMode, Callable, ComputedExpr, ConstEvalError, ConstParam, Crate,
+    Field, Function, HirFileId, Impl, InFile, Label, LifetimeParam, Local, MacroDef, Module,
+    ModuleDef, Name, Path, ScopeDef, Trait, Type, TypeAlias, TypeParam, VariantDef,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -143,6 +146,27 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.expand(macro_call)
     }
 
+    /// Returns the error that occurred while expanding `macro_call`, if any.
+    ///
+    /// Note that an expansion can fail only partially, in which case
+    /// [`Semantics::expand`] still returns the (incomplete) expansion.
+    pub fn macro_expansion_error(&self, macro_call: &ast::MacroCall) -> Option<ExpandError> {
+        self.imp.macro_expansion_error(macro_call)
+    }
+
+    /// Expands `call` together with the macro calls in its expansion, up to `depth` levels deep.
+    ///
+    /// `depth: None` expands everything, and `Some(1)` only `call` itself. Items are expanded
+    /// through their attribute macro, or else through all of their derives, whose expansions
+    /// become the children of a root that stands for the item itself.
+    pub fn expand_recursive(
+        &self,
+        call: impl Into<MacroInvocation>,
+        depth: Option<usize>,
+    ) -> Option<ExpandedMacro> {
+        self.imp.expand_recursive(call.into(), depth)
+    }
+
     /// If `item` has an attribute macro attached to it, expands it.
     pub fn expand_attr_macro(&self, item: &ast::Item) -> Option<SyntaxNode> {
         self.imp.expand_attr_macro(item)
@@ -239,10 +263,42 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.type_of_expr(expr)
     }
 
+    /// Evaluates `expr` like a constant expression, evaluating calls to const fns on the way.
+    pub fn eval_const_expr(
+        &self,
+        expr: &ast::Expr,
+    ) -> Option<Result<ComputedExpr, ConstEvalError>> {
+        self.imp.eval_const_expr(expr)
+    }
+
+    /// Infers the type `replacement` would have if it was written in place of `original_expr`.
+    ///
+    /// The body enclosing `original_expr` is lowered and inferred again from scratch with the
+    /// replacement spliced in, and nothing is cached, so every call costs about as much as
+    /// re-checking that whole body. Use this for one-off queries only, not in loops.
+    pub fn speculative_type_of_expr(
+        &self,
+        original_expr: &ast::Expr,
+        replacement: &ast::Expr,
+    ) -> Option<Type> {
+        self.imp.speculative_type_of_expr(original_expr, replacement)
+    }
+
     pub fn type_of_pat(&self, pat: &ast::Pat) -> Option<TypeInfo> {
         self.imp.type_of_pat(pat)
     }
 
+    /// The binding mode of `pat`, which differs from its annotation when match ergonomics apply.
+    pub fn binding_mode_of_pat(&self, pat: &ast::IdentPat) -> Option<BindingMode> {
+        self.imp.binding_mode_of_pat(pat)
+    }
+
+    /// The reference types implicitly dereferenced by match ergonomics before matching `pat`,
+    /// outermost first.
+    pub fn pattern_adjustments(&self, pat: &ast::Pat) -> Vec<Type> {
+        self.imp.pattern_adjustments(pat)
+    }
+
     pub fn type_of_self(&self, param: &ast::SelfParam) -> Option<Type> {
         self.imp.type_of_self(param)
     }
@@ -251,10 +307,27 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.resolve_method_call(call).map(Function::from)
     }
 
+    /// Classifies how the place rooted at the local referenced by `expr` is used.
+    ///
+    /// Assignments, `&mut` borrows and method calls taking `&mut self` are exclusive accesses,
+    /// also through fields, indexing and dereferences. Other uses of `Copy` values are shared
+    /// accesses, other uses of non-`Copy` values are moves. Returns `None` if `expr` is not a
+    /// path to a local.
+    pub fn classify_local_access(&self, expr: &ast::Expr) -> Option<Access> {
+        self.imp.classify_local_access(expr)
+    }
+
     pub fn resolve_method_call_as_callable(&self, call: &ast::MethodCallExpr) -> Option<Callable> {
         self.imp.resolve_method_call_as_callable(call)
     }
 
+    /// Returns the inferred generic arguments of the function called by `call`, which has to be
+    /// an `ast::MethodCallExpr` or an `ast::CallExpr`. The arguments for the generic parameters
+    /// of the parent impl or trait come first, followed by the function's own.
+    pub fn resolve_method_call_generics(&self, call: &ast::Expr) -> Option<Vec<Type>> {
+        self.imp.resolve_method_call_generics(call)
+    }
+
     pub fn resolve_field(&self, field: &ast::FieldExpr) -> Option<Field> {
         self.imp.resolve_field(field)
     }
@@ -367,6 +440,12 @@ impl<'db> SemanticsImpl<'db> {
         Some(node)
     }
 
+    fn macro_expansion_error(&self, macro_call: &ast::MacroCall) -> Option<ExpandError> {
+        let sa = self.analyze(macro_call.syntax());
+        let macro_call_id = sa.macro_call_id(self.db, InFile::new(sa.file_id, macro_call))?;
+        self.db.macro_expand_error(macro_call_id)
+    }
+
     fn expand_attr_macro(&self, item: &ast::Item) -> Option<SyntaxNode> {
         let sa = self.analyze(item.syntax());
         let src = InFile::new(sa.file_id, item.clone());
@@ -377,6 +456,99 @@ impl<'db> SemanticsImpl<'db> {
         Some(node)
     }
 
+    fn expand_recursive(
+        &self,
+        call: MacroInvocation,
+        depth: Option<usize>,
+    ) -> Option<ExpandedMacro> {
+        if depth == Some(0) {
+            return None;
+        }
+        let item = match call {
+            MacroInvocation::FnLike(macro_call) => {
+                return self.expand_fn_like_recursive(&macro_call, depth)
+            }
+            MacroInvocation::Item(item) => item,
+        };
+        let sa = self.analyze(item.syntax());
+        let src = InFile::new(sa.file_id, item.clone());
+        if let Some(macro_call_id) = self.with_ctx(|ctx| ctx.item_to_macro_call(src.clone())) {
+            let name = match self.db.lookup_intern_macro(macro_call_id).kind {
+                MacroCallKind::Attr { attr_name, .. } => attr_name,
+                _ => String::new(),
+            };
+            return self.expand_call_recursive(name, item.syntax().clone(), macro_call_id, depth);
+        }
+
+        let derives = self.with_ctx(|ctx| ctx.item_to_derive_macro_calls(src))?;
+        let children = derives
+            .iter()
+            .filter_map(|&macro_call_id| {
+                let name = match self.db.lookup_intern_macro(macro_call_id).kind {
+                    MacroCallKind::Derive { derive_name, .. } => derive_name,
+                    _ => String::new(),
+                };
+                self.expand_call_recursive(name, item.syntax().clone(), macro_call_id, depth)
+            })
+            .collect();
+        Some(ExpandedMacro {
+            name: "derive".to_string(),
+            def: None,
+            call: item.syntax().clone(),
+            expansion: item.syntax().clone(),
+            error: None,
+            children,
+        })
+    }
+
+    fn expand_fn_like_recursive(
+        &self,
+        macro_call: &ast::MacroCall,
+        depth: Option<usize>,
+    ) -> Option<ExpandedMacro> {
+        let sa = self.analyze(macro_call.syntax());
+        let macro_call_id = sa.macro_call_id(self.db, InFile::new(sa.file_id, macro_call))?;
+        let name = macro_call
+            .path()
+            .and_then(|path| path.segment()?.name_ref())
+            .map(|it| it.to_string())
+            .unwrap_or_default();
+        self.expand_call_recursive(name, macro_call.syntax().clone(), macro_call_id, depth)
+    }
+
+    fn expand_call_recursive(
+        &self,
+        name: String,
+        call: SyntaxNode,
+        macro_call_id: MacroCallId,
+        depth: Option<usize>,
+    ) -> Option<ExpandedMacro> {
+        let file_id = macro_call_id.as_file();
+        if file_id.expansion_level(self.db.upcast()) >= 64 {
+            return None;
+        }
+        let expansion = self.db.parse_or_expand(file_id)?;
+        self.cache(expansion.clone(), file_id);
+
+        let children = match depth.map(|it| it - 1) {
+            Some(0) => Vec::new(),
+            depth => expansion
+                .descendants()
+                .filter_map(ast::MacroCall::cast)
+                .filter_map(|it| self.expand_fn_like_recursive(&it, depth))
+                .collect(),
+        };
+        let def = MacroDef { id: self.db.lookup_intern_macro(macro_call_id).def };
+        Some(ExpandedMacro {
+            name,
+            def: Some(def),
+            call,
+            expansion,
+            error: self.db.macro_expand_error(macro_call_id).map(|it| it.to_string()),
+            children,
+        })
+    }
+
     fn is_attr_macro_call(&self, item: &ast::Item) -> bool {
         let sa = self.analyze(item.syntax());
         let src = InFile::new(sa.file_id, item.clone());
@@ -585,12 +757,42 @@ impl<'db> SemanticsImpl<'db> {
             .map(|(ty, coerced)| TypeInfo { original: ty, adjusted: coerced })
     }
 
+    fn eval_const_expr(&self, expr: &ast::Expr) -> Option<Result<ComputedExpr, ConstEvalError>> {
+        self.analyze(expr.syntax()).eval_const_expr(self.db, expr)
+    }
+
+    fn speculative_type_of_expr(
+        &self,
+        original_expr: &ast::Expr,
+        replacement: &ast::Expr,
+    ) -> Option<Type> {
+        let node = self.find_file(original_expr.syntax().clone());
+        let def = match self.with_ctx(|ctx| ctx.find_container(node.as_ref()))? {
+            ChildContainer::DefWithBodyId(def) => def,
+            _ => return None,
+        };
+        let (body, source_map, expr) =
+            body::Body::speculative(self.db.upcast(), def, original_expr, replacement)?;
+        let expr_id = source_map.node_expr(expr.as_ref())?;
+        let infer = hir_ty::infer_speculative(self.db, def, body);
+        let resolver = def.resolver(self.db.upcast());
+        Type::new_with_resolver(self.db, &resolver, infer[expr_id].clone())
+    }
+
     fn type_of_pat(&self, pat: &ast::Pat) -> Option<TypeInfo> {
         self.analyze(pat.syntax())
             .type_of_pat(self.db, pat)
             .map(|(ty, coerced)| TypeInfo { original: ty, adjusted: coerced })
     }
 
+    fn binding_mode_of_pat(&self, pat: &ast::IdentPat) -> Option<BindingMode> {
+        self.analyze(pat.syntax()).binding_mode_of_pat(self.db, pat)
+    }
+
+    fn pattern_adjustments(&self, pat: &ast::Pat) -> Vec<Type> {
+        self.analyze(pat.syntax()).pattern_adjustments(self.db, pat).unwrap_or_default()
+    }
+
     fn type_of_self(&self, param: &ast::SelfParam) -> Option<Type> {
         self.analyze(param.syntax()).type_of_self(self.db, param)
     }
@@ -599,6 +801,58 @@ impl<'db> SemanticsImpl<'db> {
         self.analyze(call.syntax()).resolve_method_call(self.db, call).map(|(id, _)| id)
     }
 
+    fn classify_local_access(&self, expr: &ast::Expr) -> Option<Access> {
+        let path = match expr {
+            ast::Expr::PathExpr(it) => it.path()?,
+            _ => return None,
+        };
+        match self.resolve_path(&path)? {
+            PathResolution::Local(_) => (),
+            _ => return None,
+        }
+
+        let mut place = expr.clone();
+        loop {
+            let parent = match place.syntax().parent().and_then(ast::Expr::cast) {
+                Some(it) => it,
+                None => break,
+            };
+            let is_projection = match &parent {
+                ast::Expr::ParenExpr(_) => true,
+                ast::Expr::FieldExpr(field) => field.expr().as_ref() == Some(&place),
+                ast::Expr::IndexExpr(index) => index.base().as_ref() == Some(&place),
+                ast::Expr::PrefixExpr(prefix) => prefix.op_kind() == Some(ast::UnaryOp::Deref),
+                ast::Expr::BinExpr(bin) => {
+                    if matches!(bin.op_kind(), Some(ast::BinaryOp::Assignment { .. }))
+                        && bin.lhs().as_ref() == Some(&place)
+                    {
+                        return Some(Access::Exclusive);
+                    }
+                    false
+                }
+                ast::Expr::RefExpr(ref_expr) => {
+                    return Some(if ref_expr.mut_token().is_some() {
+                        Access::Exclusive
+                    } else {
+                        Access::Shared
+                    });
+                }
+                ast::Expr::MethodCallExpr(call) if call.receiver().as_ref() == Some(&place) => {
+                    let func = self.resolve_method_call(call).map(Function::from)?;
+                    return Some(func.self_param(self.db)?.access(self.db));
+                }
+                _ => false,
+            };
+            if !is_projection {
+                break;
+            }
+            place = parent;
+        }
+
+        let ty = self.type_of_expr(&place)?.original;
+        Some(if ty.is_copy(self.db) { Access::Shared } else { Access::Owned })
+    }
+
     fn resolve_method_call_as_callable(&self, call: &ast::MethodCallExpr) -> Option<Callable> {
         let (func, subst) = self.analyze(call.syntax()).resolve_method_call(self.db, call)?;
         let ty = self.db.value_ty(func.into()).substitute(&Interner, &subst);
@@ -609,6 +863,16 @@ impl<'db> SemanticsImpl<'db> {
         Some(res)
     }
 
+    fn resolve_method_call_generics(&self, call: &ast::Expr) -> Option<Vec<Type>> {
+        let analyzer = self.analyze(call.syntax());
+        let subst = analyzer.resolve_call_substitution(self.db, call)?;
+        subst
+            .iter(&Interner)
+            .filter_map(|arg| arg.ty(&Interner))
+            .map(|ty| Type::new_with_resolver(self.db, &analyzer.resolver, ty.clone()))
+            .collect()
+    }
+
     fn resolve_field(&self, field: &ast::FieldExpr) -> Option<Field> {
         self.analyze(field.syntax()).resolve_field(self.db, field)
     }
@@ -0,0 +1,86 @@
+//! Structured, recursive macro expansion, as needed by the "Expand Macro" feature.
+
+use syntax::{ast, ted, SyntaxNode};
+
+use crate::{MacroDef, MacroKind};
+
+/// A macro invocation that can be expanded through [`crate::Semantics::expand_recursive`].
+#[derive(Debug, Clone)]
+pub enum MacroInvocation {
+    /// A function-like macro call, `foo!()`.
+    FnLike(ast::MacroCall),
+    /// An item annotated with an attribute macro, or with `#[derive]`s.
+    Item(ast::Item),
+}
+
+impl From<ast::MacroCall> for MacroInvocation {
+    fn from(it: ast::MacroCall) -> MacroInvocation {
+        MacroInvocation::FnLike(it)
+    }
+}
+
+impl From<ast::Item> for MacroInvocation {
+    fn from(it: ast::Item) -> MacroInvocation {
+        match it {
+            ast::Item::MacroCall(it) => MacroInvocation::FnLike(it),
+            it => MacroInvocation::Item(it),
+        }
+    }
+}
+
+/// One level of a macro expansion, together with the expansions of the macro calls inside of it.
+#[derive(Debug, Clone)]
+pub struct ExpandedMacro {
+    /// The name of the macro, as written at the call site.
+    pub name: String,
+    /// The expanded macro, or `None` for the root of the expansions of an item's derives.
+    pub def: Option<MacroDef>,
+    /// The node that was expanded: a macro call, or an item with attribute or derive macros.
+    pub call: SyntaxNode,
+    /// The syntax of this level, with the macro calls inside of it left unexpanded.
+    ///
+    /// For the root of an item's derives, this is the item itself.
+    pub expansion: SyntaxNode,
+    /// The error reported while expanding this level, if any.
+    ///
+    /// Expansion can fail only partially, in which case `expansion` is still usable.
+    pub error: Option<String>,
+    /// The expansions of the macro calls inside of `expansion`, in source order.
+    ///
+    /// This is empty if the depth limit was reached.
+    pub children: Vec<ExpandedMacro>,
+}
+
+impl ExpandedMacro {
+    /// Returns `expansion` with all nested expansions spliced into it.
+    ///
+    /// Derive expansions do not replace anything, so they are not part of the result. They are
+    /// the `children` of the root.
+    pub fn expanded_syntax(&self) -> SyntaxNode {
+        let expanded = self.expansion.clone_for_update();
+        let mut replacements = Vec::new();
+        for child in self.children.iter().filter(|it| !it.is_derive()) {
+            let new_node = child.expanded_syntax();
+            // check if the whole original syntax is replaced
+            if child.call == self.expansion {
+                return new_node;
+            }
+            let range = child.call.text_range();
+            let old_node = expanded
+                .covering_element(range)
+                .ancestors()
+                .take_while(|it| it.text_range() == range)
+                .find(|it| it.kind() == child.call.kind());
+            if let Some(old_node) = old_node {
+                replacements.push((old_node, new_node));
+            }
+        }
+
+        replacements.into_iter().rev().for_each(|(old, new)| ted::replace(old, new));
+        expanded
+    }
+
+    fn is_derive(&self) -> bool {
+        self.def.map_or(false, |it| it.kind() == MacroKind::Derive)
+    }
+}
@@ -18,10 +18,12 @@ use hir_def::{
     resolver::{resolver_for_scope, Resolver, TypeNs, ValueNs},
     AsMacroCall, DefWithBodyId, FieldId, FunctionId, LocalFieldId, VariantId,
 };
-use hir_expand::{hygiene::Hygiene, name::AsName, HirFileId, InFile};
+use hir_expand::{hygiene::Hygiene, name::AsName, HirFileId, InFile, MacroCallId};
 use hir_ty::{
+    consteval::{eval_const, ComputedExpr, ConstEvalCtx, ConstEvalError},
     diagnostics::{record_literal_missing_fields, record_pattern_missing_fields},
-    InferenceResult, Interner, Substitution, TyExt, TyLoweringContext,
+    primitive::IntTy,
+    InferenceResult, Interner, Scalar, Substitution, TyExt, TyKind, TyLoweringContext,
 };
 use syntax::{
     ast::{self, AstNode},
@@ -29,8 +31,9 @@ use syntax::{
 };
 
 use crate::{
-    db::HirDatabase, semantics::PathResolution, Adt, BuiltinType, Const, Field, Function, Local,
-    MacroDef, ModuleDef, Static, Struct, Trait, Type, TypeAlias, TypeParam, Variant,
+    db::HirDatabase, semantics::PathResolution, Adt, BindingMode, BuiltinType, Const, Field,
+    Function, Local, MacroDef, ModuleDef, Mutability, Static, Struct, Trait, Type, TypeAlias,
+    TypeParam, Variant,
 };
 use base_db::CrateId;
 
@@ -132,6 +135,24 @@ impl SourceAnalyzer {
         mk_ty(ty.clone()).zip(Some(coerced.and_then(mk_ty)))
     }
 
+    pub(crate) fn eval_const_expr(
+        &self,
+        db: &dyn HirDatabase,
+        expr: &ast::Expr,
+    ) -> Option<Result<ComputedExpr, ConstEvalError>> {
+        let expr_id = self.expr_id(db, expr)?;
+        let mut ctx = ConstEvalCtx {
+            db,
+            resolver: self.resolver.clone(),
+            body: self.body.as_deref()?,
+            infer: self.infer.as_deref(),
+            default_int: Scalar::Int(IntTy::I32),
+            local_data: Default::default(),
+            call_depth: 0,
+        };
+        Some(eval_const(expr_id, &mut ctx))
+    }
+
     pub(crate) fn type_of_pat(
         &self,
         db: &dyn HirDatabase,
@@ -148,6 +169,37 @@ impl SourceAnalyzer {
         mk_ty(ty.clone()).zip(Some(coerced.and_then(mk_ty)))
     }
 
+    pub(crate) fn binding_mode_of_pat(
+        &self,
+        _db: &dyn HirDatabase,
+        pat: &ast::IdentPat,
+    ) -> Option<BindingMode> {
+        let pat_id = self.pat_id(&pat.clone().into())?;
+        let infer = self.infer.as_ref()?;
+        infer.pat_binding_modes.get(&pat_id).map(|bm| match bm {
+            hir_ty::BindingMode::Move => BindingMode::Move,
+            hir_ty::BindingMode::Ref(hir_ty::Mutability::Mut) => BindingMode::Ref(Mutability::Mut),
+            hir_ty::BindingMode::Ref(hir_ty::Mutability::Not) => {
+                BindingMode::Ref(Mutability::Shared)
+            }
+        })
+    }
+
+    pub(crate) fn pattern_adjustments(
+        &self,
+        db: &dyn HirDatabase,
+        pat: &ast::Pat,
+    ) -> Option<Vec<Type>> {
+        let pat_id = self.pat_id(pat)?;
+        let infer = self.infer.as_ref()?;
+        infer
+            .pat_adjustments
+            .get(&pat_id)?
+            .iter()
+            .map(|adjust| Type::new_with_resolver(db, &self.resolver, adjust.target.clone()))
+            .collect()
+    }
+
     pub(crate) fn type_of_self(
         &self,
         db: &dyn HirDatabase,
@@ -168,6 +220,26 @@ impl SourceAnalyzer {
         self.infer.as_ref()?.method_resolution(expr_id)
     }
 
+    pub(crate) fn resolve_call_substitution(
+        &self,
+        db: &dyn HirDatabase,
+        call: &ast::Expr,
+    ) -> Option<Substitution> {
+        match call {
+            ast::Expr::MethodCallExpr(call) => {
+                self.resolve_method_call(db, call).map(|(_, subst)| subst)
+            }
+            ast::Expr::CallExpr(call) => {
+                let callee = self.expr_id(db, &call.expr()?)?;
+                match self.infer.as_ref()?[callee].kind(&Interner) {
+                    TyKind::FnDef(_, subst) => Some(subst.clone()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
     pub(crate) fn resolve_field(
         &self,
         db: &dyn HirDatabase,
@@ -396,11 +468,19 @@ impl SourceAnalyzer {
         db: &dyn HirDatabase,
         macro_call: InFile<&ast::MacroCall>,
     ) -> Option<HirFileId> {
+        let macro_call_id = self.macro_call_id(db, macro_call)?;
+        Some(macro_call_id.as_file()).filter(|it| it.expansion_level(db.upcast()) < 64)
+    }
+
+    pub(crate) fn macro_call_id(
+        &self,
+        db: &dyn HirDatabase,
+        macro_call: InFile<&ast::MacroCall>,
+    ) -> Option<MacroCallId> {
         let krate = self.resolver.krate()?;
-        let macro_call_id = macro_call.as_call_id(db.upcast(), krate, |path| {
+        macro_call.as_call_id(db.upcast(), krate, |path| {
             self.resolver.resolve_path_as_macro(db.upcast(), &path)
-        })?;
-        Some(macro_call_id.as_file()).filter(|it| it.expansion_level(db.upcast()) < 64)
+        })
     }
 
     pub(crate) fn resolve_variant(
@@ -435,16 +515,7 @@ fn scope_for_offset(
     scopes
         .scope_by_expr()
         .iter()
-        .filter_map(|(id, scope)| {
-            let source = source_map.expr_syntax(*id).ok()?;
-            // FIXME: correctly handle macro expansion
-            if source.file_id != offset.file_id {
-                return None;
-            }
-            let root = source.file_syntax(db.upcast());
-            let node = source.value.to_node(&root);
-            Some((node.syntax().text_range(), scope))
-        })
+        .filter_map(|(id, scope)| Some((expr_range_in_file(db, source_map, *id, offset)?, scope)))
         // find containing scope
         .min_by_key(|(expr_range, _scope)| {
             (
@@ -457,6 +528,30 @@ fn scope_for_offset(
         })
 }
 
+/// Returns the range of `expr` in the file of `offset`. Expressions produced by macro
+/// expansions are mapped back to the macro call in the original file.
+fn expr_range_in_file(
+    db: &dyn HirDatabase,
+    source_map: &BodySourceMap,
+    expr: ExprId,
+    offset: InFile<TextSize>,
+) -> Option<TextRange> {
+    let source = source_map.expr_syntax(expr).ok()?;
+    let root = source.file_syntax(db.upcast());
+    let node = source.value.to_node(&root);
+    if source.file_id == offset.file_id {
+        return Some(node.syntax().text_range());
+    }
+    if offset.file_id.is_macro() {
+        return None;
+    }
+    let original = source.with_value(node.syntax()).original_file_range(db.upcast());
+    if HirFileId::from(original.file_id) != offset.file_id {
+        return None;
+    }
+    Some(original.range)
+}
+
 // XXX: during completion, cursor might be outside of any particular
 // expression. Try to figure out the correct scope...
 fn adjust(
@@ -469,16 +564,7 @@ fn adjust(
     let child_scopes = scopes
         .scope_by_expr()
         .iter()
-        .filter_map(|(id, scope)| {
-            let source = source_map.expr_syntax(*id).ok()?;
-            // FIXME: correctly handle macro expansion
-            if source.file_id != offset.file_id {
-                return None;
-            }
-            let root = source.file_syntax(db.upcast());
-            let node = source.value.to_node(&root);
-            Some((node.syntax().text_range(), scope))
-        })
+        .filter_map(|(id, scope)| Some((expr_range_in_file(db, source_map, *id, offset)?, scope)))
         .filter(|&(range, _)| {
             range.start() <= offset.value && expr_range.contains_range(range) && range != expr_range
         });
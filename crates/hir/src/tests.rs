@@ -0,0 +1,692 @@
+use base_db::fixture::WithFixture;
+use expect_test::{expect, Expect};
+use itertools::Itertools;
+use rustc_hash::FxHashSet;
+use stdx::format_to;
+use syntax::{algo::find_node_at_offset, ast, AstNode};
+
+use crate::{
+    test_db::TestDB, Adt, Crate, HasAttrs, HirDisplay, Impl, InFile, MethodOrigin, Module,
+    ModuleDef, ScopeDef, Semantics,
+};
+
+fn check_fn_ret_type_info(ra_fixture: &str, expect: Expect) {
+    let (db, position) = TestDB::with_position(ra_fixture);
+    let sema = Semantics::new(&db);
+    let file = sema.parse(position.file_id);
+    let fn_ = find_node_at_offset::<ast::Fn>(file.syntax(), position.offset).unwrap();
+    let func = sema.to_def(&fn_).unwrap();
+
+    let async_ret_type = func.async_ret_type(&db).map(|ty| ty.display(&db).to_string());
+    let impl_trait_bounds = func
+        .impl_trait_bounds_of_ret(&db)
+        .map(|traits| traits.into_iter().map(|it| it.name(&db).to_string()).collect::<Vec<_>>());
+    let actual = format!(
+        "async_ret_type: {:?}\nret_type_is_impl_trait: {}\nimpl_trait_bounds_of_ret: {:?}\n",
+        async_ret_type,
+        func.ret_type_is_impl_trait(&db),
+        impl_trait_bounds,
+    );
+    expect.assert_eq(&actual);
+}
+
+fn check_field_and_variant_attrs(ra_fixture: &str, expect: Expect) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let sema = Semantics::new(&db);
+    let file = sema.parse(file_id);
+
+    let mut actual = String::new();
+    for strukt in file.syntax().descendants().filter_map(ast::Struct::cast) {
+        let strukt = sema.to_def(&strukt).unwrap();
+        let fields = strukt.fields(&db).iter().map(|it| it.name(&db).to_string()).join(", ");
+        format_to!(actual, "struct {}: [{}]\n", strukt.name(&db), fields);
+    }
+    for field in file.syntax().descendants().filter_map(ast::RecordField::cast) {
+        let field = sema.to_def(&field).unwrap();
+        format_to!(
+            actual,
+            "field {}: {} enabled={} docs={:?}\n",
+            field.name(&db),
+            field.ty(&db).display(&db),
+            field.is_cfg_enabled(&db),
+            field.docs(&db),
+        );
+    }
+    for variant in file.syntax().descendants().filter_map(ast::Variant::cast) {
+        let variant = sema.to_def(&variant).unwrap();
+        format_to!(
+            actual,
+            "variant {}: enabled={} index={} fields={} docs={:?}\n",
+            variant.name(&db),
+            variant.is_cfg_enabled(&db),
+            variant.source_order_index(&db),
+            variant.fields(&db).len(),
+            variant.docs(&db),
+        );
+    }
+    expect.assert_eq(&actual);
+}
+
+fn check_macro_expand(ra_fixture: &str, expect: Expect) {
+    let (db, position) = TestDB::with_position(ra_fixture);
+    let sema = Semantics::new(&db);
+    let file = sema.parse(position.file_id);
+    let call = find_node_at_offset::<ast::MacroCall>(file.syntax(), position.offset).unwrap();
+    let mac = sema.resolve_macro_call(&call).unwrap();
+    let expansion = mac.expand(&db, InFile::new(position.file_id.into(), &call)).unwrap();
+    expect.assert_eq(&expansion.to_string());
+}
+
+fn check_crate_targets(ra_fixture: &str, expect: Expect) {
+    let db = TestDB::with_files(ra_fixture);
+    let mut targets: Vec<_> = Crate::all(&db)
+        .into_iter()
+        .map(|krate| {
+            let name = krate.display_name(&db).map(|it| it.to_string()).unwrap_or_default();
+            format!("{}: {:?}\n", name, krate.target(&db))
+        })
+        .collect();
+    targets.sort();
+    expect.assert_eq(&targets.concat());
+}
+
+fn check_is_mutated_after(ra_fixture: &str, expect: bool) {
+    let (db, position) = TestDB::with_position(ra_fixture);
+    let sema = Semantics::new(&db);
+    let file = sema.parse(position.file_id);
+    let pat = file.syntax().descendants().find_map(ast::IdentPat::cast).unwrap();
+    let local = sema.to_def(&pat).unwrap();
+    assert_eq!(local.is_mutated_after(&db, position.offset), expect);
+}
+
+fn check_macro_kinds(ra_fixture: &str, expect: Expect) {
+    let db = TestDB::with_files(ra_fixture);
+    let krate = Crate::all(&db)
+        .into_iter()
+        .find(|krate| krate.display_name(&db).map_or(false, |it| it.to_string() == "main"))
+        .unwrap();
+    let mut macros: Vec<_> = krate
+        .root_module(&db)
+        .scope(&db, None)
+        .into_iter()
+        .filter_map(|(name, def)| match def {
+            ScopeDef::MacroDef(mac) => Some(format!(
+                "{}: proc_macro={} derive={} attr={} fn_like={}\n",
+                name,
+                mac.is_proc_macro(),
+                mac.is_derive(),
+                mac.is_attr(),
+                mac.is_fn_like(),
+            )),
+            _ => None,
+        })
+        .collect();
+    macros.sort();
+    expect.assert_eq(&macros.concat());
+}
+
+fn check_struct_kinds(ra_fixture: &str, expect: Expect) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = Semantics::new(&db).to_module_def(file_id).unwrap();
+    let mut kinds: Vec<_> = module
+        .declarations(&db)
+        .into_iter()
+        .flat_map(|def| match def {
+            ModuleDef::Adt(Adt::Struct(it)) => {
+                vec![(it.name(&db), it.is_unit(&db), it.is_tuple(&db), it.is_record(&db))]
+            }
+            ModuleDef::Adt(Adt::Enum(it)) => it
+                .variants(&db)
+                .into_iter()
+                .map(|it| (it.name(&db), it.is_unit(&db), it.is_tuple(&db), it.is_record(&db)))
+                .collect(),
+            _ => Vec::new(),
+        })
+        .map(|(name, unit, tuple, record)| {
+            format!("{}: unit={} tuple={} record={}\n", name, unit, tuple, record)
+        })
+        .collect();
+    kinds.sort();
+    expect.assert_eq(&kinds.concat());
+}
+
+fn check_array_types(ra_fixture: &str, expect: Expect) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = Semantics::new(&db).to_module_def(file_id).unwrap();
+    let arrays: Vec<_> = module
+        .declarations(&db)
+        .into_iter()
+        .filter_map(|def| match def {
+            ModuleDef::Adt(Adt::Struct(it)) => Some(it.fields(&db)),
+            _ => None,
+        })
+        .flatten()
+        .map(|field| {
+            let array = field
+                .ty(&db)
+                .as_array(&db)
+                .map(|(elem, len)| format!("element={} len={:?}", elem.display(&db), len));
+            format!("{}: {}\n", field.name(&db), array.unwrap_or_else(|| "-".to_string()))
+        })
+        .collect();
+    expect.assert_eq(&arrays.concat());
+}
+
+fn check_deref_target(ra_fixture: &str, expect: Expect) {
+    let (db, position) = TestDB::with_position(ra_fixture);
+    let sema = Semantics::new(&db);
+    let file = sema.parse(position.file_id);
+    let expr = find_node_at_offset::<ast::Expr>(file.syntax(), position.offset).unwrap();
+    let ty = sema.type_of_expr(&expr).unwrap().original;
+    let actual = match ty.deref_target(&db) {
+        Some(target) => target.display(&db).to_string(),
+        None => "None".to_string(),
+    };
+    expect.assert_eq(&actual);
+}
+
+fn check_all_method_candidates(ra_fixture: &str, expect: Expect) {
+    let (db, position) = TestDB::with_position(ra_fixture);
+    let sema = Semantics::new(&db);
+    let file = sema.parse(position.file_id);
+    let receiver = find_node_at_offset::<ast::Expr>(file.syntax(), position.offset).unwrap();
+    let scope = sema.scope(receiver.syntax());
+    let ty = sema.type_of_expr(&receiver).unwrap().original;
+
+    let mut actual = String::new();
+    ty.iterate_all_method_candidates(
+        &db,
+        scope.krate().unwrap(),
+        &scope.traits_in_scope(),
+        |_ty, func, origin| {
+            let origin = match origin {
+                MethodOrigin::Inherent(_) => "inherent".to_string(),
+                MethodOrigin::TraitInScope(it) => format!("in scope {}", it.name(&db)),
+                MethodOrigin::TraitNotInScope(it) => format!("not in scope {}", it.name(&db)),
+            };
+            format_to!(actual, "{} {}\n", func.name(&db), origin);
+            None::<()>
+        },
+    );
+    expect.assert_eq(&actual);
+}
+
+fn check_applicable_impls(ra_fixture: &str, expect: Expect) {
+    let (db, position) = TestDB::with_position(ra_fixture);
+    let sema = Semantics::new(&db);
+    let file = sema.parse(position.file_id);
+    let strukt = find_node_at_offset::<ast::Struct>(file.syntax(), position.offset).unwrap();
+    let ty = sema.to_def(&strukt).unwrap().ty(&db);
+
+    let actual = ty
+        .applicable_impls(&db)
+        .into_iter()
+        .map(|(trait_, imp)| {
+            let krate = imp.module(&db).krate().display_name(&db).unwrap();
+            let unsafe_ = if imp.is_unsafe(&db) { "unsafe " } else { "" };
+            let trait_ = trait_.map_or("inherent".to_string(), |it| it.name(&db).to_string());
+            let items =
+                imp.items_in_source_order(&db).into_iter().filter_map(|it| it.name(&db)).join(", ");
+            format!("{}: {}{} [{}]\n", krate, unsafe_, trait_, items)
+        })
+        .collect::<String>();
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn fn_ret_type_info_async() {
+    check_fn_ret_type_info(
+        r#"
+//- minicore: future
+async fn foo$0() -> u8 { 0 }
+"#,
+        expect![[r#"
+            async_ret_type: Some("u8")
+            ret_type_is_impl_trait: false
+            impl_trait_bounds_of_ret: None
+        "#]],
+    );
+}
+
+#[test]
+fn fn_ret_type_info_impl_trait() {
+    check_fn_ret_type_info(
+        r#"
+//- minicore: iterator
+fn foo$0() -> impl Iterator<Item = u8> { loop {} }
+"#,
+        expect![[r#"
+            async_ret_type: None
+            ret_type_is_impl_trait: true
+            impl_trait_bounds_of_ret: Some(["Iterator"])
+        "#]],
+    );
+}
+
+#[test]
+fn fn_ret_type_info_plain() {
+    check_fn_ret_type_info(
+        r#"
+fn foo$0() -> u8 { 0 }
+"#,
+        expect![[r#"
+            async_ret_type: None
+            ret_type_is_impl_trait: false
+            impl_trait_bounds_of_ret: None
+        "#]],
+    );
+}
+
+#[test]
+fn field_and_variant_cfg_and_docs() {
+    check_field_and_variant_attrs(
+        r#"
+//- /main.rs crate:main cfg:feature=std
+struct S {
+/// Docs for a.
+a: u8,
+#[cfg(feature = "std")]
+b: u8,
+/// Docs for c.
+#[cfg(not(feature = "std"))]
+c: u16,
+}
+enum E {
+/// Docs for X.
+#[cfg(not(feature = "std"))]
+X(u8),
+/// Docs for Y.
+Y,
+Z,
+}
+"#,
+        expect![[r#"
+            struct S: [a, b]
+            field a: u8 enabled=true docs=Some(Documentation("Docs for a."))
+            field b: u8 enabled=true docs=None
+            field c: u16 enabled=false docs=Some(Documentation("Docs for c."))
+            variant X: enabled=false index=2 fields=1 docs=Some(Documentation("Docs for X."))
+            variant Y: enabled=true index=0 fields=0 docs=Some(Documentation("Docs for Y."))
+            variant Z: enabled=true index=1 fields=0 docs=None
+        "#]],
+    );
+}
+
+#[test]
+fn crate_target_from_fixture() {
+    check_crate_targets(
+        r#"
+//- /main.rs crate:main deps:dep target:i686-unknown-linux-gnu
+fn main() {}
+//- /dep.rs crate:dep target:powerpc-unknown-linux-gnu
+pub fn f() {}
+"#,
+        expect![[r#"
+            dep: TargetInfo { triple: Some("powerpc-unknown-linux-gnu"), pointer_width: 32, endian: Big }
+            main: TargetInfo { triple: Some("i686-unknown-linux-gnu"), pointer_width: 32, endian: Little }
+        "#]],
+    );
+}
+
+#[test]
+fn mutation_before_position() {
+    check_is_mutated_after(
+        r#"
+fn f() {
+    let mut x = 0;
+    x = 1;
+    x += 1;
+    $0
+    let _y = x;
+}
+"#,
+        false,
+    );
+}
+
+#[test]
+fn assignment_through_projection_after_position() {
+    check_is_mutated_after(
+        r#"
+fn f() {
+    let mut x = (0, [0]);
+    x.0 = 1;
+    $0
+    let _y = x.0;
+    x.1[0] = 1;
+}
+"#,
+        true,
+    );
+}
+
+#[test]
+fn mut_borrow_and_method_call_after_position() {
+    check_is_mutated_after(
+        r#"
+struct S;
+impl S {
+    fn get(&self) {}
+    fn set(&mut self) {}
+}
+fn f() {
+    let mut s = S;
+    $0
+    s.get();
+    let _r = &s;
+}
+"#,
+        false,
+    );
+    check_is_mutated_after(
+        r#"
+struct S;
+impl S {
+    fn set(&mut self) {}
+}
+fn f() {
+    let mut s = S;
+    $0
+    s.set();
+}
+"#,
+        true,
+    );
+    check_is_mutated_after(
+        r#"
+struct S;
+fn f() {
+    let mut s = S;
+    $0
+    let _r = &mut s;
+}
+"#,
+        true,
+    );
+}
+
+#[test]
+fn macro_def_expand_one_level() {
+    check_macro_expand(
+        r#"
+macro_rules! inner { () => { struct Inner; } }
+macro_rules! outer { ($name:ident) => { inner!(); struct $name; } }
+outer$0!(Outer);
+"#,
+        expect![[r#"inner!();structOuter;"#]],
+    );
+}
+
+#[test]
+fn macro_kinds() {
+    check_macro_kinds(
+        r#"
+//- /main.rs crate:main deps:core,proc_macros
+use core::{column, Clone, test};
+use proc_macros::{identity, DeriveGenerated, duplicate};
+
+#[macro_export]
+macro_rules! declarative { () => {} }
+//- /core.rs crate:core
+#[rustc_builtin_macro]
+pub macro column {}
+#[rustc_builtin_macro]
+pub macro Clone {}
+#[rustc_builtin_macro]
+pub macro test {}
+//- /proc_macros.rs crate:proc_macros proc_macro:identity,derive_generated,duplicate
+#[proc_macro_attribute]
+pub fn identity(_attr: TokenStream, item: TokenStream) -> TokenStream { item }
+#[proc_macro_derive(DeriveGenerated)]
+pub fn derive_generated(item: TokenStream) -> TokenStream { item }
+#[proc_macro]
+pub fn duplicate(input: TokenStream) -> TokenStream { input }
+"#,
+        expect![[r#"
+            Clone: proc_macro=false derive=true attr=false fn_like=false
+            DeriveGenerated: proc_macro=true derive=true attr=false fn_like=false
+            column: proc_macro=false derive=false attr=false fn_like=true
+            declarative: proc_macro=false derive=false attr=false fn_like=true
+            duplicate: proc_macro=true derive=false attr=false fn_like=true
+            identity: proc_macro=true derive=false attr=true fn_like=false
+            test: proc_macro=false derive=false attr=true fn_like=false
+        "#]],
+    );
+}
+
+#[test]
+fn struct_kinds() {
+    check_struct_kinds(
+        r#"
+struct Unit;
+struct Tuple(u32, u32);
+struct Record { field: u32 }
+struct EmptyRecord {}
+enum E { UnitVariant, TupleVariant(u32), RecordVariant { field: u32 } }
+"#,
+        expect![[r#"
+            EmptyRecord: unit=false tuple=false record=true
+            Record: unit=false tuple=false record=true
+            RecordVariant: unit=false tuple=false record=true
+            Tuple: unit=false tuple=true record=false
+            TupleVariant: unit=false tuple=true record=false
+            Unit: unit=true tuple=false record=false
+            UnitVariant: unit=true tuple=false record=false
+        "#]],
+    );
+}
+
+#[test]
+fn array_types() {
+    check_array_types(
+        r#"
+struct S<T, const N: usize> {
+    bytes: [u8; 4],
+    items: [T; N],
+    nested: [[bool; 2]; 3],
+    slice: &'static [u8],
+}
+"#,
+        expect![[r#"
+            bytes: element=u8 len=Some(4)
+            items: element=T len=None
+            nested: element=[bool; 2] len=Some(3)
+            slice: -
+        "#]],
+    );
+}
+
+#[test]
+fn deref_target_of_custom_impl() {
+    check_deref_target(
+        r#"
+//- minicore: deref
+struct Inner;
+struct Wrapper<T>(T);
+impl<T> core::ops::Deref for Wrapper<T> {
+    type Target = T;
+    fn deref(&self) -> &T { &self.0 }
+}
+fn f(w: Wrapper<Inner>) { w$0; }
+"#,
+        expect![[r#"Inner"#]],
+    );
+}
+
+#[test]
+fn deref_target_without_impl() {
+    check_deref_target(
+        r#"
+//- minicore: deref
+struct S;
+fn f(s: S) { s$0; }
+"#,
+        expect![[r#"None"#]],
+    );
+}
+
+#[test]
+fn all_method_candidates_inherent_shadows_trait() {
+    check_all_method_candidates(
+        r#"
+struct S;
+impl S {
+    fn foo(&self) {}
+}
+trait Tr {
+    fn foo(&self);
+    fn bar(&self);
+}
+impl Tr for S {
+    fn foo(&self) {}
+    fn bar(&self) {}
+}
+mod m {
+    pub trait Hidden {
+        fn foo(&self);
+        fn baz(&self);
+    }
+    impl Hidden for super::S {
+        fn foo(&self) {}
+        fn baz(&self) {}
+    }
+}
+fn f(s: S) { s$0; }
+"#,
+        expect![[r#"
+            foo inherent
+            bar in scope Tr
+            baz not in scope Hidden
+        "#]],
+    );
+}
+
+#[test]
+fn all_method_candidates_by_value_trait_method_shadows_autoref_inherent() {
+    check_all_method_candidates(
+        r#"
+struct S;
+impl S {
+    fn foo(&self) {}
+}
+trait Tr {
+    fn foo(self);
+}
+impl Tr for S {
+    fn foo(self) {}
+}
+fn f(s: S) { s$0; }
+"#,
+        expect![[r#"
+            foo in scope Tr
+        "#]],
+    );
+}
+
+#[test]
+fn applicable_impls_are_grouped() {
+    check_applicable_impls(
+        r#"
+//- /main.rs crate:main deps:lib
+use lib::Foo;
+
+trait Local {
+    fn local();
+}
+impl Local for Foo {
+    fn local() {}
+}
+//- /lib.rs crate:lib
+macro_rules! gen {
+    () => { fn generated() {} };
+}
+
+pub struct Foo$0;
+
+impl Foo {
+    fn b() {}
+    gen!();
+    fn a() {}
+}
+
+pub trait Tr {}
+pub unsafe trait UnsafeTr {}
+
+impl Tr for Foo {}
+
+impl Foo {
+    const C: () = ();
+}
+
+unsafe impl UnsafeTr for Foo {}
+"#,
+        expect![[r#"
+            lib: inherent [b, generated, a]
+            lib: inherent [C]
+            lib: Tr []
+            lib: unsafe UnsafeTr []
+            main: Local [local]
+        "#]],
+    );
+}
+
+#[test]
+fn impls_for_type_and_trait_match_brute_force() {
+    let db = TestDB::with_files(
+        r#"
+//- /a.rs crate:a
+pub trait Tr {}
+pub struct S;
+impl S {}
+impl Tr for S {}
+mod inner {
+    impl super::S {}
+}
+//- /b.rs crate:b deps:a
+pub trait Tr2 {}
+pub struct T;
+impl a::Tr for T {}
+impl Tr2 for a::S {}
+impl<X> Tr2 for X {}
+//- /c.rs crate:c deps:a,b
+struct U;
+impl a::Tr for U {}
+impl b::Tr2 for a::S {}
+impl b::Tr2 for b::T {}
+"#,
+    );
+
+    fn collect_impls(db: &TestDB, module: Module, acc: &mut Vec<Impl>) {
+        acc.extend(module.impl_defs(db));
+        module.children(db).for_each(|child| collect_impls(db, child, acc));
+    }
+    let mut all_impls = Vec::new();
+    for krate in Crate::all(&db) {
+        collect_impls(&db, krate.root_module(&db), &mut all_impls);
+    }
+
+    for &impl_ in &all_impls {
+        let self_ty = impl_.self_ty(&db);
+        if let Some(adt) = self_ty.as_adt() {
+            let expected: FxHashSet<_> = all_impls
+                .iter()
+                .copied()
+                .filter(|it| {
+                    let ty = it.self_ty(&db);
+                    ty.remove_ref().unwrap_or(ty).as_adt() == Some(adt)
+                })
+                .collect();
+            let actual = Impl::all_for_type(&db, self_ty);
+            assert_eq!(actual.len(), expected.len());
+            assert_eq!(actual.into_iter().collect::<FxHashSet<_>>(), expected);
+        }
+
+        if let Some(trait_) = impl_.trait_(&db) {
+            let expected: FxHashSet<_> =
+                all_impls.iter().copied().filter(|it| it.trait_(&db) == Some(trait_)).collect();
+            let actual = Impl::all_for_trait(&db, trait_);
+            assert_eq!(actual.len(), expected.len());
+            assert_eq!(actual.into_iter().collect::<FxHashSet<_>>(), expected);
+        }
+    }
+}
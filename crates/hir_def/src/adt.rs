@@ -8,15 +8,16 @@ use hir_expand::{
     name::{AsName, Name},
     InFile,
 };
-use la_arena::{Arena, ArenaMap};
+use la_arena::{Arena, ArenaMap, Idx, RawIdx};
 use syntax::ast::{self, NameOwner, VisibilityOwner};
 use tt::{Delimiter, DelimiterKind, Leaf, Subtree, TokenTree};
 
 use crate::{
     body::{CfgExpander, LowerCtx},
+    builtin_type::{BuiltinInt, BuiltinUint},
     db::DefDatabase,
     intern::Interned,
-    item_tree::{AttrOwner, Field, Fields, ItemTree, ModItem, RawVisibilityId},
+    item_tree::{AttrOwner, Field, Fields, IdRange, ItemTree, ModItem, RawVisibilityId},
     src::HasChildSource,
     src::HasSource,
     trace::Trace,
@@ -31,7 +32,7 @@ use cfg::CfgOptions;
 pub struct StructData {
     pub name: Name,
     pub variant_data: Arc<VariantData>,
-    pub repr: Option<ReprKind>,
+    pub repr: Option<ReprData>,
     pub visibility: RawVisibility,
 }
 
@@ -39,6 +40,9 @@ pub struct StructData {
 pub struct EnumData {
     pub name: Name,
     pub variants: Arena<EnumVariantData>,
+    /// Variants removed by `#[cfg]`, their ids continue after the ones in `variants`.
+    pub cfg_disabled_variants: Vec<EnumVariantData>,
+    pub repr: Option<ReprData>,
     pub visibility: RawVisibility,
 }
 
@@ -50,8 +54,8 @@ pub struct EnumVariantData {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum VariantData {
-    Record(Arena<FieldData>),
-    Tuple(Arena<FieldData>),
+    Record { fields: Arena<FieldData>, cfg_disabled: Vec<FieldData> },
+    Tuple { fields: Arena<FieldData>, cfg_disabled: Vec<FieldData> },
     Unit,
 }
 
@@ -65,8 +69,26 @@ pub struct FieldData {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ReprKind {
-    Packed,
-    Other,
+    C,
+    BuiltinInt { builtin: Either<BuiltinInt, BuiltinUint>, is_c: bool },
+    Transparent,
+    Default,
+}
+
+/// The data of all `#[repr(...)]` attributes on an ADT, merged together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReprData {
+    pub kind: ReprKind,
+    /// `Some(n)` for `#[repr(packed(n))]`, `#[repr(packed)]` is `Some(1)`.
+    pub packed: Option<u64>,
+    /// `Some(n)` for `#[repr(align(n))]`.
+    pub align: Option<u64>,
+}
+
+impl ReprData {
+    pub fn is_packed(&self) -> bool {
+        self.packed.is_some()
+    }
 }
 
 fn repr_from_value(
@@ -74,20 +96,80 @@ fn repr_from_value(
     krate: CrateId,
     item_tree: &ItemTree,
     of: AttrOwner,
-) -> Option<ReprKind> {
-    item_tree.attrs(db, krate, of).by_key("repr").tt_values().find_map(parse_repr_tt)
+) -> Option<ReprData> {
+    item_tree.attrs(db, krate, of).by_key("repr").tt_values().fold(None, |acc, tt| {
+        match (acc, parse_repr_tt(tt)) {
+            (Some(acc), Some(new)) => Some(merge_repr(acc, new)),
+            (acc, new) => acc.or(new),
+        }
+    })
 }
 
-fn parse_repr_tt(tt: &Subtree) -> Option<ReprKind> {
+fn merge_repr(acc: ReprData, new: ReprData) -> ReprData {
+    let kind = match (acc.kind, new.kind) {
+        (ReprKind::Default, kind) | (kind, ReprKind::Default) => kind,
+        (ReprKind::C, ReprKind::BuiltinInt { builtin, .. })
+        | (ReprKind::BuiltinInt { builtin, .. }, ReprKind::C) => {
+            ReprKind::BuiltinInt { builtin, is_c: true }
+        }
+        (_, kind) => kind,
+    };
+    ReprData {
+        kind,
+        packed: match (acc.packed, new.packed) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        },
+        align: acc.align.max(new.align),
+    }
+}
+
+fn parse_repr_tt(tt: &Subtree) -> Option<ReprData> {
     match tt.delimiter {
         Some(Delimiter { kind: DelimiterKind::Parenthesis, .. }) => {}
         _ => return None,
     }
 
-    let mut it = tt.token_trees.iter();
-    match it.next()? {
-        TokenTree::Leaf(Leaf::Ident(ident)) if ident.text == "packed" => Some(ReprKind::Packed),
-        _ => Some(ReprKind::Other),
+    let mut data = ReprData { kind: ReprKind::Default, packed: None, align: None };
+    let mut tts = tt.token_trees.iter().peekable();
+    while let Some(tt) = tts.next() {
+        if let TokenTree::Leaf(Leaf::Ident(ident)) = tt {
+            // `packed(N)` and `align(N)` carry their argument in a following subtree
+            let arg = match tts.peek() {
+                Some(TokenTree::Subtree(arg)) => {
+                    tts.next();
+                    parse_repr_int_arg(arg)
+                }
+                _ => None,
+            };
+            let mut new = ReprData { kind: ReprKind::Default, packed: None, align: None };
+            match &*ident.text {
+                "packed" => new.packed = Some(arg.unwrap_or(1)),
+                "align" => new.align = arg,
+                "C" => new.kind = ReprKind::C,
+                "transparent" => new.kind = ReprKind::Transparent,
+                repr => {
+                    let builtin = match BuiltinInt::from_suffix(repr) {
+                        Some(it) => Either::Left(it),
+                        None => match BuiltinUint::from_suffix(repr) {
+                            Some(it) => Either::Right(it),
+                            None => continue,
+                        },
+                    };
+                    new.kind = ReprKind::BuiltinInt { builtin, is_c: false };
+                }
+            }
+            data = merge_repr(data, new);
+        }
+    }
+
+    Some(data)
+}
+
+fn parse_repr_int_arg(tt: &Subtree) -> Option<u64> {
+    match tt.token_trees.first()? {
+        TokenTree::Leaf(Leaf::Literal(lit)) => lit.text.parse().ok(),
+        _ => None,
     }
 }
 
@@ -132,32 +214,35 @@ impl EnumData {
         let loc = e.lookup(db);
         let krate = loc.container.krate;
         let item_tree = loc.id.item_tree(db);
+        let repr = repr_from_value(db, krate, &item_tree, ModItem::from(loc.id.value).into());
         let cfg_options = db.crate_graph()[krate].cfg_options.clone();
 
         let enum_ = &item_tree[loc.id.value];
         let mut variants = Arena::new();
+        let mut cfg_disabled_variants = Vec::new();
         for var_id in enum_.variants.clone() {
+            let var = &item_tree[var_id];
+            let var_data = lower_fields(
+                db,
+                krate,
+                &item_tree,
+                &cfg_options,
+                &var.fields,
+                Some(enum_.visibility),
+            );
+            let data = EnumVariantData { name: var.name.clone(), variant_data: Arc::new(var_data) };
             if item_tree.attrs(db, krate, var_id.into()).is_cfg_enabled(&cfg_options) {
-                let var = &item_tree[var_id];
-                let var_data = lower_fields(
-                    db,
-                    krate,
-                    &item_tree,
-                    &cfg_options,
-                    &var.fields,
-                    Some(enum_.visibility),
-                );
-
-                variants.alloc(EnumVariantData {
-                    name: var.name.clone(),
-                    variant_data: Arc::new(var_data),
-                });
+                variants.alloc(data);
+            } else {
+                cfg_disabled_variants.push(data);
             }
         }
 
         Arc::new(EnumData {
             name: enum_.name.clone(),
             variants,
+            cfg_disabled_variants,
+            repr,
             visibility: item_tree[enum_.visibility].clone(),
         })
     }
@@ -166,6 +251,21 @@ impl EnumData {
         let (id, _) = self.variants.iter().find(|(_id, data)| &data.name == name)?;
         Some(id)
     }
+
+    pub fn cfg_disabled_variants(
+        &self,
+    ) -> impl Iterator<Item = (LocalEnumVariantId, &EnumVariantData)> + '_ {
+        let offset = self.variants.len();
+        self.cfg_disabled_variants.iter().enumerate().map(move |(i, data)| (idx(offset + i), data))
+    }
+
+    /// Looks up a variant which may have been removed by `#[cfg]`.
+    pub fn variant_or_cfg_disabled(&self, id: LocalEnumVariantId) -> &EnumVariantData {
+        match raw_idx(id).checked_sub(self.variants.len()) {
+            Some(i) => &self.cfg_disabled_variants[i],
+            None => &self.variants[id],
+        }
+    }
 }
 
 impl HasChildSource<LocalEnumVariantId> for EnumId {
@@ -188,13 +288,14 @@ fn lower_enum(
     module_id: ModuleId,
 ) {
     let expander = CfgExpander::new(db, ast.file_id, module_id.krate);
-    let variants = ast
+    // Variants disabled by `#[cfg]` come last, like in `EnumData`.
+    let (variants, cfg_disabled): (Vec<_>, Vec<_>) = ast
         .value
         .variant_list()
         .into_iter()
         .flat_map(|it| it.variants())
-        .filter(|var| expander.is_cfg_enabled(db, var));
-    for var in variants {
+        .partition(|var| expander.is_cfg_enabled(db, var));
+    for var in variants.into_iter().chain(cfg_disabled) {
         trace.alloc(
             || var.clone(),
             || EnumVariantData {
@@ -209,18 +310,42 @@ impl VariantData {
     fn new(db: &dyn DefDatabase, flavor: InFile<ast::StructKind>, module_id: ModuleId) -> Self {
         let mut expander = CfgExpander::new(db, flavor.file_id, module_id.krate);
         let mut trace = Trace::new_for_arena();
-        match lower_struct(db, &mut expander, &mut trace, &flavor) {
-            StructKind::Tuple => VariantData::Tuple(trace.into_arena()),
-            StructKind::Record => VariantData::Record(trace.into_arena()),
+        let (kind, enabled) = lower_struct(db, &mut expander, &mut trace, &flavor);
+        let all = trace.into_arena();
+        let fields = all.iter().take(enabled).map(|(_, it)| it.clone()).collect();
+        let cfg_disabled = all.iter().skip(enabled).map(|(_, it)| it.clone()).collect();
+        match kind {
+            StructKind::Tuple => VariantData::Tuple { fields, cfg_disabled },
+            StructKind::Record => VariantData::Record { fields, cfg_disabled },
             StructKind::Unit => VariantData::Unit,
         }
     }
 
     pub fn fields(&self) -> &Arena<FieldData> {
+        self.all_fields().0
+    }
+
+    pub fn cfg_disabled_fields(&self) -> impl Iterator<Item = (LocalFieldId, &FieldData)> + '_ {
+        let (fields, cfg_disabled) = self.all_fields();
+        let offset = fields.len();
+        cfg_disabled.iter().enumerate().map(move |(i, data)| (idx(offset + i), data))
+    }
+
+    /// Looks up a field which may have been removed by `#[cfg]`.
+    pub fn field_or_cfg_disabled(&self, id: LocalFieldId) -> &FieldData {
+        let (fields, cfg_disabled) = self.all_fields();
+        match raw_idx(id).checked_sub(fields.len()) {
+            Some(i) => &cfg_disabled[i],
+            None => &fields[id],
+        }
+    }
+
+    fn all_fields(&self) -> (&Arena<FieldData>, &[FieldData]) {
         const EMPTY: &Arena<FieldData> = &Arena::new();
         match &self {
-            VariantData::Record(fields) | VariantData::Tuple(fields) => fields,
-            _ => EMPTY,
+            VariantData::Record { fields, cfg_disabled }
+            | VariantData::Tuple { fields, cfg_disabled } => (fields, cfg_disabled),
+            VariantData::Unit => (EMPTY, &[]),
         }
     }
 
@@ -230,8 +355,8 @@ impl VariantData {
 
     pub fn kind(&self) -> StructKind {
         match self {
-            VariantData::Record(_) => StructKind::Record,
-            VariantData::Tuple(_) => StructKind::Tuple,
+            VariantData::Record { .. } => StructKind::Record,
+            VariantData::Tuple { .. } => StructKind::Tuple,
             VariantData::Unit => StructKind::Unit,
         }
     }
@@ -274,21 +399,22 @@ pub enum StructKind {
     Unit,
 }
 
+/// Lowers the fields of `ast` into `trace`, the ones disabled by `#[cfg]` last. Returns the number
+/// of enabled fields along with the kind.
 fn lower_struct(
     db: &dyn DefDatabase,
     expander: &mut CfgExpander,
     trace: &mut Trace<FieldData, Either<ast::TupleField, ast::RecordField>>,
     ast: &InFile<ast::StructKind>,
-) -> StructKind {
+) -> (StructKind, usize) {
     let ctx = LowerCtx::new(db, ast.file_id);
 
     match &ast.value {
         ast::StructKind::Tuple(fl) => {
-            for (i, fd) in fl.fields().enumerate() {
-                if !expander.is_cfg_enabled(db, &fd) {
-                    continue;
-                }
-
+            let (fields, cfg_disabled): (Vec<_>, Vec<_>) =
+                fl.fields().enumerate().partition(|(_, fd)| expander.is_cfg_enabled(db, fd));
+            let enabled = fields.len();
+            for (i, fd) in fields.into_iter().chain(cfg_disabled) {
                 trace.alloc(
                     || Either::Left(fd.clone()),
                     || FieldData {
@@ -298,14 +424,13 @@ fn lower_struct(
                     },
                 );
             }
-            StructKind::Tuple
+            (StructKind::Tuple, enabled)
         }
         ast::StructKind::Record(fl) => {
-            for fd in fl.fields() {
-                if !expander.is_cfg_enabled(db, &fd) {
-                    continue;
-                }
-
+            let (fields, cfg_disabled): (Vec<_>, Vec<_>) =
+                fl.fields().partition(|fd| expander.is_cfg_enabled(db, fd));
+            let enabled = fields.len();
+            for fd in fields.into_iter().chain(cfg_disabled) {
                 trace.alloc(
                     || Either::Right(fd.clone()),
                     || FieldData {
@@ -315,9 +440,9 @@ fn lower_struct(
                     },
                 );
             }
-            StructKind::Record
+            (StructKind::Record, enabled)
         }
-        ast::StructKind::Unit => StructKind::Unit,
+        ast::StructKind::Unit => (StructKind::Unit, 0),
     }
 }
 
@@ -329,24 +454,27 @@ fn lower_fields(
     fields: &Fields,
     override_visibility: Option<RawVisibilityId>,
 ) -> VariantData {
+    let lower = |flds: &IdRange<Field>| {
+        let mut fields = Arena::new();
+        let mut cfg_disabled = Vec::new();
+        for field_id in flds.clone() {
+            let data = lower_field(item_tree, &item_tree[field_id], override_visibility);
+            if item_tree.attrs(db, krate, field_id.into()).is_cfg_enabled(cfg_options) {
+                fields.alloc(data);
+            } else {
+                cfg_disabled.push(data);
+            }
+        }
+        (fields, cfg_disabled)
+    };
     match fields {
         Fields::Record(flds) => {
-            let mut arena = Arena::new();
-            for field_id in flds.clone() {
-                if item_tree.attrs(db, krate, field_id.into()).is_cfg_enabled(cfg_options) {
-                    arena.alloc(lower_field(item_tree, &item_tree[field_id], override_visibility));
-                }
-            }
-            VariantData::Record(arena)
+            let (fields, cfg_disabled) = lower(flds);
+            VariantData::Record { fields, cfg_disabled }
         }
         Fields::Tuple(flds) => {
-            let mut arena = Arena::new();
-            for field_id in flds.clone() {
-                if item_tree.attrs(db, krate, field_id.into()).is_cfg_enabled(cfg_options) {
-                    arena.alloc(lower_field(item_tree, &item_tree[field_id], override_visibility));
-                }
-            }
-            VariantData::Tuple(arena)
+            let (fields, cfg_disabled) = lower(flds);
+            VariantData::Tuple { fields, cfg_disabled }
         }
         Fields::Unit => VariantData::Unit,
     }
@@ -363,3 +491,11 @@ fn lower_field(
         visibility: item_tree[override_visibility.unwrap_or(field.visibility)].clone(),
     }
 }
+
+fn idx<T>(raw: usize) -> Idx<T> {
+    Idx::from_raw(RawIdx::from(raw as u32))
+}
+
+fn raw_idx<T>(idx: Idx<T>) -> usize {
+    u32::from(idx.into_raw()) as usize
+}
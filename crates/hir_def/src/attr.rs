@@ -248,7 +248,7 @@ impl Attrs {
             _ => Some(CfgExpr::All(cfgs)),
         }
     }
-    pub(crate) fn is_cfg_enabled(&self, cfg_options: &CfgOptions) -> bool {
+    pub fn is_cfg_enabled(&self, cfg_options: &CfgOptions) -> bool {
         match self.cfg() {
             None => true,
             Some(cfg) => cfg_options.check(&cfg) != Some(false),
@@ -856,3 +856,44 @@ pub(crate) fn fields_attrs_source_map(
 
     Arc::new(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use base_db::{fixture::WithFixture, SourceDatabase};
+    use cfg::{CfgAtom, CfgExpr};
+
+    use crate::{db::DefDatabase, test_db::TestDB, AttrDefId, ModuleDefId};
+
+    fn fn_cfg(ra_fixture: &str) -> Option<CfgExpr> {
+        let db = TestDB::with_files(ra_fixture);
+        let krate = db.crate_graph().iter().next().unwrap();
+        let def_map = db.crate_def_map(krate);
+        let func = def_map[def_map.root()]
+            .scope
+            .declarations()
+            .find_map(|decl| match decl {
+                ModuleDefId::FunctionId(it) => Some(it),
+                _ => None,
+            })
+            .unwrap();
+        db.attrs(AttrDefId::FunctionId(func)).cfg()
+    }
+
+    fn flag(name: &str) -> CfgExpr {
+        CfgExpr::Atom(CfgAtom::Flag(name.into()))
+    }
+
+    #[test]
+    fn cfg_of_function() {
+        assert_eq!(fn_cfg("//- /lib.rs cfg:test\n#[cfg(test)]\nfn f() {}"), Some(flag("test")));
+        assert_eq!(fn_cfg("fn f() {}"), None);
+    }
+
+    #[test]
+    fn cfg_combines_multiple_attributes() {
+        assert_eq!(
+            fn_cfg("//- /lib.rs cfg:test,unix\n#[cfg(test)]\n#[inline]\n#[cfg(unix)]\nfn f() {}"),
+            Some(CfgExpr::All(vec![flag("test"), flag("unix")]))
+        );
+    }
+}
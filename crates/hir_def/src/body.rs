@@ -18,7 +18,7 @@ use la_arena::{Arena, ArenaMap};
 use limit::Limit;
 use profile::Count;
 use rustc_hash::FxHashMap;
-use syntax::{ast, AstNode, AstPtr, SyntaxNodePtr};
+use syntax::{ast, ted, AstNode, AstPtr, SyntaxNode, SyntaxNodePtr, TextRange, TextSize};
 
 use crate::{
     attr::{Attrs, RawAttrs},
@@ -27,8 +27,8 @@ use crate::{
     item_scope::BuiltinShadowMode,
     nameres::DefMap,
     path::{ModPath, Path},
-    src::HasSource,
-    AsMacroCall, BlockId, DefWithBodyId, HasModule, LocalModuleId, Lookup, ModuleId,
+    src::{HasChildSource, HasSource},
+    AsMacroCall, BlockId, DefWithBodyId, EnumVariantId, HasModule, LocalModuleId, Lookup, ModuleId,
     UnresolvedMacro,
 };
 
@@ -51,6 +51,46 @@ pub struct Expander {
     ast_id_map: Arc<AstIdMap>,
     module: LocalModuleId,
     recursion_limit: usize,
+    speculative: Option<SpeculativeEdit>,
+}
+
+/// The replacement of an expression in a body that is lowered speculatively, see
+/// [`Body::speculative`].
+#[derive(Debug)]
+struct SpeculativeEdit {
+    file_id: HirFileId,
+    /// The root of the unmodified syntax tree, which is what the `AstIdMap` of the file refers to.
+    original_root: SyntaxNode,
+    original_range: TextRange,
+    /// The range of the replacement in the speculative syntax tree.
+    replacement_range: TextRange,
+}
+
+impl SpeculativeEdit {
+    /// Finds the node in the unmodified tree that corresponds to `node` in the speculative tree.
+    /// Nodes within the replacement have no such node.
+    fn original_node<N: AstNode>(&self, node: &N) -> Option<N> {
+        let (original, replacement) = (self.original_range, self.replacement_range);
+        let shift = |offset: TextSize| offset - replacement.end() + original.end();
+        let range = node.syntax().text_range();
+        let range = if range.end() <= replacement.start() {
+            range
+        } else if range.start() >= replacement.end() {
+            TextRange::new(shift(range.start()), shift(range.end()))
+        } else if range != replacement && range.contains_range(replacement) {
+            TextRange::new(range.start(), shift(range.end()))
+        } else {
+            return None;
+        };
+        if !self.original_root.text_range().contains_range(range) {
+            return None;
+        }
+        self.original_root
+            .covering_element(range)
+            .ancestors()
+            .take_while(|it| it.text_range() == range)
+            .find_map(N::cast)
+    }
 }
 
 #[cfg(test)]
@@ -92,6 +132,7 @@ impl Expander {
             ast_id_map,
             module: module.local_id,
             recursion_limit: 0,
+            speculative: None,
         }
     }
 
@@ -107,6 +148,14 @@ impl Expander {
             ));
         }
 
+        let macro_call = match self.speculative_edit() {
+            // Macro calls have to exist in the real file to be expanded.
+            Some(edit) => match edit.original_node(&macro_call) {
+                Some(it) => it,
+                None => return Ok(ExpandResult { value: None, err: None }),
+            },
+            None => macro_call,
+        };
         let macro_call = InFile::new(self.current_file_id, &macro_call);
 
         let resolver =
@@ -200,9 +249,27 @@ impl Expander {
         self.def_map.resolve_path(db, self.module, path, BuiltinShadowMode::Other).0.take_macros()
     }
 
-    fn ast_id<N: AstNode>(&self, item: &N) -> AstId<N> {
-        let file_local_id = self.ast_id_map.ast_id(item);
-        AstId::new(self.current_file_id, file_local_id)
+    /// Returns the id of `item`. If the body is lowered speculatively and `item` is part of the
+    /// replacement, returns the id of the closest ancestor of the same kind that isn't.
+    fn ast_id<N: AstNode>(&self, item: &N) -> Option<AstId<N>> {
+        let file_local_id = match self.speculative_edit() {
+            Some(edit) => {
+                let original = item
+                    .syntax()
+                    .ancestors()
+                    .filter_map(N::cast)
+                    .find_map(|it| edit.original_node(&it))?;
+                self.ast_id_map.ast_id(&original)
+            }
+            None => self.ast_id_map.ast_id(item),
+        };
+        Some(AstId::new(self.current_file_id, file_local_id))
+    }
+
+    /// The speculative edit, if we are currently lowering the speculatively edited file rather than
+    /// a macro expansion.
+    fn speculative_edit(&self) -> Option<&SpeculativeEdit> {
+        self.speculative.as_ref().filter(|it| it.file_id == self.current_file_id)
     }
 }
 
@@ -332,6 +399,89 @@ impl Body {
             .map(move |block| (*block, db.block_def_map(*block).expect("block ID without DefMap")))
     }
 
+    /// Lowers the explicit discriminant expression of an enum variant, if it has one.
+    ///
+    /// Discriminants are not bodies of a [`DefWithBodyId`], so the result is not cached.
+    pub fn enum_discriminant(db: &dyn DefDatabase, variant: EnumVariantId) -> Option<Body> {
+        let src = variant.parent.child_source(db);
+        let expr = src.value[variant.local_id].expr()?;
+        let module = variant.parent.lookup(db).container;
+        let expander = Expander::new(db, src.file_id, module);
+        let (mut body, _) = Body::new(db, expander, None, Some(expr));
+        body.shrink_to_fit();
+        Some(body)
+    }
+
+    /// Lowers the body of `def` as if the expression `original` in it was replaced by
+    /// `replacement`, without touching the database.
+    ///
+    /// This lowers the whole body again, which costs about as much as the `body` query, and the
+    /// result is not cached. The source map refers to a speculative copy of the syntax tree, which
+    /// the returned expression (the spliced in `replacement`) is part of. Macro calls inside the
+    /// replacement are not expanded.
+    pub fn speculative(
+        db: &dyn DefDatabase,
+        def: DefWithBodyId,
+        original: &ast::Expr,
+        replacement: &ast::Expr,
+    ) -> Option<(Body, BodySourceMap, InFile<ast::Expr>)> {
+        let (file_id, module, owner) = match def {
+            DefWithBodyId::FunctionId(f) => {
+                let f = f.lookup(db);
+                let src = f.source(db);
+                (src.file_id, f.module(db), src.value.syntax().clone())
+            }
+            DefWithBodyId::ConstId(c) => {
+                let c = c.lookup(db);
+                let src = c.source(db);
+                (src.file_id, c.module(db), src.value.syntax().clone())
+            }
+            DefWithBodyId::StaticId(s) => {
+                let s = s.lookup(db);
+                let src = s.source(db);
+                (src.file_id, s.module(db), src.value.syntax().clone())
+            }
+        };
+        let original_range = original.syntax().text_range();
+        let original_root = owner.ancestors().last()?;
+        if !owner.text_range().contains_range(original_range)
+            || original.syntax().ancestors().last().as_ref() != Some(&original_root)
+        {
+            return None;
+        }
+
+        let speculative_root = original_root.clone_for_update();
+        let target = speculative_root
+            .covering_element(original_range)
+            .ancestors()
+            .take_while(|it| it.text_range() == original_range)
+            .find(|it| it.kind() == original.syntax().kind())?;
+        let replacement = replacement.clone_subtree().clone_for_update();
+        ted::replace(target, replacement.syntax());
+
+        let owner = replacement.syntax().ancestors().find(|it| {
+            it.kind() == owner.kind() && it.text_range().start() == owner.text_range().start()
+        })?;
+        let (params, body) = match def {
+            DefWithBodyId::FunctionId(_) => {
+                let func = ast::Fn::cast(owner)?;
+                (func.param_list(), func.body().map(ast::Expr::from))
+            }
+            DefWithBodyId::ConstId(_) => (None, ast::Const::cast(owner)?.body()),
+            DefWithBodyId::StaticId(_) => (None, ast::Static::cast(owner)?.body()),
+        };
+
+        let mut expander = Expander::new(db, file_id, module);
+        expander.speculative = Some(SpeculativeEdit {
+            file_id,
+            original_root,
+            original_range,
+            replacement_range: replacement.syntax().text_range(),
+        });
+        let (body, source_map) = Body::new(db, expander, params, body);
+        Some((body, source_map, InFile::new(file_id, replacement)))
+    }
+
     fn new(
         db: &dyn DefDatabase,
         expander: Expander,
@@ -145,6 +145,10 @@ impl ExprCollector<'_> {
     }
 
     fn ctx(&self) -> LowerCtx<'_> {
+        if self.expander.speculative_edit().is_some() {
+            // The `AstIdMap` of the file doesn't know about the speculative syntax tree.
+            return LowerCtx::with_hygiene(self.db, &self.expander.cfg_expander.hygiene);
+        }
         LowerCtx::new(self.db, self.expander.current_file_id)
     }
 
@@ -700,7 +704,10 @@ impl ExprCollector<'_> {
     }
 
     fn collect_block(&mut self, block: ast::BlockExpr) -> ExprId {
-        let ast_id = self.expander.ast_id(&block);
+        let ast_id = match self.expander.ast_id(&block) {
+            Some(it) => it,
+            None => return self.alloc_expr(Expr::Missing, AstPtr::new(&block.into())),
+        };
         let block_loc =
             BlockLoc { ast_id, module: self.expander.def_map.module_id(self.expander.module) };
         let block_id = self.db.intern_block(block_loc);
@@ -50,7 +50,7 @@ impl ExprScopes {
         Arc::new(ExprScopes::new(&*body))
     }
 
-    fn new(body: &Body) -> ExprScopes {
+    pub fn new(body: &Body) -> ExprScopes {
         let mut scopes =
             ExprScopes { scopes: Arena::default(), scope_by_expr: FxHashMap::default() };
         let root = scopes.root_scope();
@@ -16,10 +16,15 @@ use crate::{
 
 /// Find a path that can be used to refer to a certain item. This can depend on
 /// *from where* you're referring to the item, hence the `from` parameter.
-pub fn find_path(db: &dyn DefDatabase, item: ItemInNs, from: ModuleId) -> Option<ModPath> {
+pub fn find_path(
+    db: &dyn DefDatabase,
+    item: ItemInNs,
+    from: ModuleId,
+    config: FindPathConfig,
+) -> Option<ModPath> {
     let _p = profile::span("find_path");
     let mut visited_modules = FxHashSet::default();
-    find_path_inner(db, item, from, MAX_PATH_LEN, None, &mut visited_modules)
+    find_path_inner(db, item, from, MAX_PATH_LEN, None, config, &mut visited_modules)
 }
 
 pub fn find_path_prefixed(
@@ -27,10 +32,22 @@ pub fn find_path_prefixed(
     item: ItemInNs,
     from: ModuleId,
     prefix_kind: PrefixKind,
+    config: FindPathConfig,
 ) -> Option<ModPath> {
     let _p = profile::span("find_path_prefixed");
     let mut visited_modules = FxHashSet::default();
-    find_path_inner(db, item, from, MAX_PATH_LEN, Some(prefix_kind), &mut visited_modules)
+    find_path_inner(db, item, from, MAX_PATH_LEN, Some(prefix_kind), config, &mut visited_modules)
+}
+
+/// Preferences for choosing between multiple paths to the same item.
+///
+/// Regardless of these, paths through `#[doc(hidden)]` modules of other crates
+/// are only used if there is no other way to refer to the item.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FindPathConfig {
+    /// Prefer re-exports from the root of a crate, like `core::Foo`, over
+    /// paths that would usually win, like a longer `std::foo::Foo`.
+    pub prefer_crate_root_reexport: bool,
 }
 
 const MAX_PATH_LEN: usize = 15;
@@ -99,6 +116,7 @@ fn find_path_inner(
     from: ModuleId,
     max_len: usize,
     mut prefixed: Option<PrefixKind>,
+    config: FindPathConfig,
     visited_modules: &mut FxHashSet<ModuleId>,
 ) -> Option<ModPath> {
     if max_len == 0 {
@@ -173,7 +191,8 @@ fn find_path_inner(
     // Recursive case:
     // - if the item is an enum variant, refer to it via the enum
     if let Some(ModuleDefId::EnumVariantId(variant)) = item.as_module_def_id() {
-        if let Some(mut path) = find_path(db, ItemInNs::Types(variant.parent.into()), from) {
+        if let Some(mut path) = find_path(db, ItemInNs::Types(variant.parent.into()), from, config)
+        {
             let data = db.enum_data(variant.parent);
             path.push_segment(data.variants[variant.local_id].name.clone());
             return Some(path);
@@ -188,13 +207,24 @@ fn find_path_inner(
     let crate_root = def_map.crate_root(db);
     let crate_attrs = db.attrs(crate_root.into());
     let prefer_no_std = crate_attrs.by_key("no_std").exists();
-    let mut best_path = None;
+    let mut best_path: Option<(ModPath, PathRank)> = None;
     let mut best_path_len = max_len;
 
     if item.krate(db) == Some(from.krate) {
         // Item was defined in the same crate that wants to import it. It cannot be found in any
         // dependency in this case.
         for (module_id, name) in find_local_import_locations(db, item, from) {
+            let rank = PathRank {
+                is_doc_hidden: false,
+                not_at_crate_root: config.prefer_crate_root_reexport && module_id != crate_root,
+            };
+            // Only paths of the same rank compete on length, a better ranked path wins even if
+            // it is longer.
+            let max_len = match &best_path {
+                Some((_, best_rank)) if rank > *best_rank => continue,
+                Some((_, best_rank)) if rank == *best_rank => best_path_len - 1,
+                _ => max_len - 1,
+            };
             if !visited_modules.insert(module_id) {
                 cov_mark::hit!(recursive_imports);
                 continue;
@@ -203,18 +233,19 @@ fn find_path_inner(
                 db,
                 ItemInNs::Types(ModuleDefId::ModuleId(module_id)),
                 from,
-                best_path_len - 1,
+                max_len,
                 prefixed,
+                config,
                 visited_modules,
             ) {
                 path.push_segment(name);
 
                 let new_path = if let Some(best_path) = best_path {
-                    select_best_path(best_path, path, prefer_no_std)
+                    select_best_path(best_path, (path, rank), prefer_no_std)
                 } else {
-                    path
+                    (path, rank)
                 };
-                best_path_len = new_path.len();
+                best_path_len = new_path.0.len();
                 best_path = Some(new_path);
             }
         }
@@ -234,11 +265,17 @@ fn find_path_inner(
                     from,
                     best_path_len - 1,
                     prefixed,
+                    config,
                     visited_modules,
                 )?;
                 cov_mark::hit!(partially_imported);
                 path.push_segment(info.path.segments.last().unwrap().clone());
-                Some(path)
+                let rank = PathRank {
+                    is_doc_hidden: info.is_doc_hidden,
+                    not_at_crate_root: config.prefer_crate_root_reexport
+                        && info.path.segments.len() != 1,
+                };
+                Some((path, rank))
             })
         });
 
@@ -251,6 +288,7 @@ fn find_path_inner(
             best_path = Some(new_path);
         }
     }
+    let best_path = best_path.map(|(path, _)| path);
 
     // If the item is declared inside a block expression, don't use a prefix, as we don't handle
     // that correctly (FIXME).
@@ -270,8 +308,27 @@ fn find_path_inner(
     }
 }
 
-fn select_best_path(old_path: ModPath, new_path: ModPath, prefer_no_std: bool) -> ModPath {
-    if old_path.starts_with_std() && new_path.can_start_with_std() {
+/// How good a candidate path is, regardless of its length. Smaller is better.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct PathRank {
+    is_doc_hidden: bool,
+    /// Only set when the caller asked to prefer crate root re-exports.
+    not_at_crate_root: bool,
+}
+
+fn select_best_path(
+    (old_path, old_rank): (ModPath, PathRank),
+    (new_path, new_rank): (ModPath, PathRank),
+    prefer_no_std: bool,
+) -> (ModPath, PathRank) {
+    if old_rank != new_rank {
+        if new_rank < old_rank {
+            cov_mark::hit!(prefer_better_ranked_path);
+            return (new_path, new_rank);
+        }
+        return (old_path, old_rank);
+    }
+    let path = if old_path.starts_with_std() && new_path.can_start_with_std() {
         if prefer_no_std {
             cov_mark::hit!(prefer_no_std_paths);
             new_path
@@ -291,7 +348,8 @@ fn select_best_path(old_path: ModPath, new_path: ModPath, prefer_no_std: bool) -
         new_path
     } else {
         old_path
-    }
+    };
+    (path, old_rank)
 }
 
 /// Finds locations in `from.krate` from which `item` can be imported by `from`.
@@ -394,7 +452,12 @@ mod tests {
     /// `code` needs to contain a cursor marker; checks that `find_path` for the
     /// item the `path` refers to returns that same path when called from the
     /// module the cursor is in.
-    fn check_found_path_(ra_fixture: &str, path: &str, prefix_kind: Option<PrefixKind>) {
+    fn check_found_path_(
+        ra_fixture: &str,
+        path: &str,
+        prefix_kind: Option<PrefixKind>,
+        config: FindPathConfig,
+    ) {
         let (db, pos) = TestDB::with_position(ra_fixture);
         let module = db.module_at_position(pos);
         let parsed_path_file = syntax::SourceFile::parse(&format!("use {};", path));
@@ -421,6 +484,7 @@ mod tests {
             module,
             MAX_PATH_LEN,
             prefix_kind,
+            config,
             &mut visited_modules,
         );
         assert_eq!(found_path, Some(mod_path), "{:?}", prefix_kind);
@@ -433,10 +497,28 @@ mod tests {
         absolute: &str,
         self_prefixed: &str,
     ) {
-        check_found_path_(ra_fixture, unprefixed, None);
-        check_found_path_(ra_fixture, prefixed, Some(PrefixKind::Plain));
-        check_found_path_(ra_fixture, absolute, Some(PrefixKind::ByCrate));
-        check_found_path_(ra_fixture, self_prefixed, Some(PrefixKind::BySelf));
+        check_found_path_with_config(
+            ra_fixture,
+            FindPathConfig::default(),
+            unprefixed,
+            prefixed,
+            absolute,
+            self_prefixed,
+        );
+    }
+
+    fn check_found_path_with_config(
+        ra_fixture: &str,
+        config: FindPathConfig,
+        unprefixed: &str,
+        prefixed: &str,
+        absolute: &str,
+        self_prefixed: &str,
+    ) {
+        check_found_path_(ra_fixture, unprefixed, None, config);
+        check_found_path_(ra_fixture, prefixed, Some(PrefixKind::Plain), config);
+        check_found_path_(ra_fixture, absolute, Some(PrefixKind::ByCrate), config);
+        check_found_path_(ra_fixture, self_prefixed, Some(PrefixKind::BySelf), config);
     }
 
     #[test]
@@ -1099,4 +1181,149 @@ pub mod prelude {
             "None",
         );
     }
+
+    #[test]
+    fn skips_doc_hidden_module() {
+        check_found_path(
+            r#"
+//- /main.rs crate:main deps:dep
+$0
+//- /dep.rs crate:dep
+#[doc(hidden)]
+pub mod hidden {
+    pub struct S;
+}
+pub mod visible {
+    pub mod inner {
+        pub use crate::hidden::S;
+    }
+}
+        "#,
+            "dep::visible::inner::S",
+            "dep::visible::inner::S",
+            "dep::visible::inner::S",
+            "dep::visible::inner::S",
+        );
+    }
+
+    #[test]
+    fn skips_doc_hidden_module_of_other_crate() {
+        cov_mark::check!(prefer_better_ranked_path);
+        check_found_path(
+            r#"
+//- /main.rs crate:main deps:dep,facade
+$0
+//- /dep.rs crate:dep
+#[doc(hidden)]
+pub mod hidden {
+    pub struct S;
+}
+//- /facade.rs crate:facade deps:dep
+pub mod visible {
+    pub mod inner {
+        pub use dep::hidden::S;
+    }
+}
+        "#,
+            "facade::visible::inner::S",
+            "facade::visible::inner::S",
+            "facade::visible::inner::S",
+            "facade::visible::inner::S",
+        );
+    }
+
+    #[test]
+    fn falls_back_to_doc_hidden_module() {
+        check_found_path(
+            r#"
+//- /main.rs crate:main deps:dep
+$0
+//- /dep.rs crate:dep
+#[doc(hidden)]
+pub mod hidden {
+    pub struct S;
+}
+        "#,
+            "dep::hidden::S",
+            "dep::hidden::S",
+            "dep::hidden::S",
+            "dep::hidden::S",
+        );
+    }
+
+    #[test]
+    fn prefers_shortest_reexport() {
+        check_found_path(
+            r#"
+//- /main.rs crate:main deps:dep
+$0
+//- /dep.rs crate:dep
+mod private {
+    pub mod runtime {
+        pub mod task {
+            pub struct Handle {}
+        }
+    }
+}
+pub mod task {
+    pub use crate::private::runtime::task::Handle;
+}
+pub use crate::private::runtime::task::Handle;
+        "#,
+            "dep::Handle",
+            "dep::Handle",
+            "dep::Handle",
+            "dep::Handle",
+        );
+    }
+
+    #[test]
+    fn prefer_crate_root_reexport() {
+        let fixture = r#"
+//- /main.rs crate:main deps:core,std
+$0
+//- /std.rs crate:std deps:core
+pub mod foo {
+    pub use core::Foo;
+}
+//- /core.rs crate:core
+pub struct Foo;
+        "#;
+        check_found_path(
+            fixture,
+            "std::foo::Foo",
+            "std::foo::Foo",
+            "std::foo::Foo",
+            "std::foo::Foo",
+        );
+        check_found_path_with_config(
+            fixture,
+            FindPathConfig { prefer_crate_root_reexport: true },
+            "core::Foo",
+            "core::Foo",
+            "core::Foo",
+            "core::Foo",
+        );
+    }
+
+    #[test]
+    fn prefer_crate_root_reexport_of_local_item() {
+        check_found_path_with_config(
+            r#"
+pub use crate::private::S;
+mod private {
+    pub struct S;
+}
+mod a {
+    pub use crate::private::S;
+    $0
+}
+        "#,
+            FindPathConfig { prefer_crate_root_reexport: true },
+            "S",
+            "crate::S",
+            "crate::S",
+            "crate::S",
+        );
+    }
 }
@@ -25,6 +25,8 @@ pub struct ImportInfo {
     pub container: ModuleId,
     /// Whether the import is a trait associated item or not.
     pub is_trait_assoc_item: bool,
+    /// Whether the path goes through a `#[doc(hidden)]` module.
+    pub is_doc_hidden: bool,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -48,7 +50,8 @@ impl ImportPath {
 /// crate.
 ///
 /// Reexports of items are taken into account, ie. if something is exported under multiple
-/// names, the one with the shortest import path will be used.
+/// names, the one with the shortest import path will be used. Paths through `#[doc(hidden)]`
+/// modules are only used if there's no other way to import the item.
 ///
 /// Note that all paths are relative to the containing crate's root, so the crate name still needs
 /// to be prepended to the `ModPath` before the path is valid.
@@ -150,8 +153,8 @@ fn collect_import_map(db: &dyn DefDatabase, krate: CrateId) -> ImportMap {
     // We look only into modules that are public(ly reexported), starting with the crate root.
     let empty = ImportPath { segments: vec![] };
     let root = def_map.module_id(def_map.root());
-    let mut worklist = vec![(root, empty)];
-    while let Some((module, mod_path)) = worklist.pop() {
+    let mut worklist = vec![(root, empty, false)];
+    while let Some((module, mod_path, is_doc_hidden)) = worklist.pop() {
         let ext_def_map;
         let mod_data = if module.krate == krate {
             &def_map[module.local_id]
@@ -180,8 +183,20 @@ fn collect_import_map(db: &dyn DefDatabase, krate: CrateId) -> ImportMap {
             for item in per_ns.iter_items() {
                 let path = mk_path();
                 let path_len = path.len();
-                let import_info =
-                    ImportInfo { path, container: module, is_trait_assoc_item: false };
+                // A `#[doc(hidden)]` module is hidden itself, not only the items inside of it.
+                let is_doc_hidden = is_doc_hidden
+                    || match item.as_module_def_id() {
+                        Some(ModuleDefId::ModuleId(mod_id)) => {
+                            db.attrs(mod_id.into()).has_doc_hidden()
+                        }
+                        _ => false,
+                    };
+                let import_info = ImportInfo {
+                    path,
+                    container: module,
+                    is_trait_assoc_item: false,
+                    is_doc_hidden,
+                };
 
                 if let Some(ModuleDefId::TraitId(tr)) = item.as_module_def_id() {
                     import_map.collect_trait_assoc_items(
@@ -197,8 +212,10 @@ fn collect_import_map(db: &dyn DefDatabase, krate: CrateId) -> ImportMap {
                         entry.insert(import_info);
                     }
                     Entry::Occupied(mut entry) => {
-                        // If the new path is shorter, prefer that one.
-                        if path_len < entry.get().path.len() {
+                        // If the new path is shorter, prefer that one. Paths through hidden
+                        // modules always lose against visible ones.
+                        let old = entry.get();
+                        if (is_doc_hidden, path_len) < (old.is_doc_hidden, old.path.len()) {
                             *entry.get_mut() = import_info;
                         } else {
                             continue;
@@ -207,10 +224,10 @@ fn collect_import_map(db: &dyn DefDatabase, krate: CrateId) -> ImportMap {
                 }
 
                 // If we've just added a path to a module, descend into it. We might traverse
-                // modules multiple times, but only if the new path to it is shorter than the
+                // modules multiple times, but only if the new path to it is better than the
                 // first (else we `continue` above).
                 if let Some(ModuleDefId::ModuleId(mod_id)) = item.as_module_def_id() {
-                    worklist.push((mod_id, mk_path()));
+                    worklist.push((mod_id, mk_path(), is_doc_hidden));
                 }
             }
         }
@@ -239,7 +256,8 @@ impl fmt::Debug for ImportMap {
                     ItemInNs::Values(_) => "v",
                     ItemInNs::Macros(_) => "m",
                 };
-                format!("- {} ({})", info.path, ns)
+                let hidden = if info.is_doc_hidden { " (doc hidden)" } else { "" };
+                format!("- {} ({}){}", info.path, ns, hidden)
             })
             .collect();
 
@@ -651,6 +669,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn prefers_path_without_doc_hidden() {
+        check(
+            r"
+            //- /main.rs crate:main
+
+            #[doc(hidden)]
+            pub mod hidden {
+                pub struct OnlyHidden {}
+                pub struct Both {}
+            }
+
+            pub mod visible {
+                pub mod inner {
+                    pub use crate::hidden::Both;
+                }
+            }
+        ",
+            expect![[r#"
+                main:
+                - hidden (t) (doc hidden)
+                - hidden::OnlyHidden (t) (doc hidden)
+                - visible (t)
+                - visible::inner (t)
+                - visible::inner::Both (t)
+            "#]],
+        );
+    }
+
     #[test]
     fn type_reexport_cross_crate() {
         // Reexports need to be visible from a crate, even if the original crate exports the item
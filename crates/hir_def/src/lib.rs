@@ -467,7 +467,7 @@ impl VariantId {
             VariantId::StructId(it) => db.struct_data(it).variant_data.clone(),
             VariantId::UnionId(it) => db.union_data(it).variant_data.clone(),
             VariantId::EnumVariantId(it) => {
-                db.enum_data(it.parent).variants[it.local_id].variant_data.clone()
+                db.enum_data(it.parent).variant_or_cfg_disabled(it.local_id).variant_data.clone()
             }
         }
     }
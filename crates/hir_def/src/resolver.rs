@@ -563,9 +563,19 @@ pub fn resolver_for_scope(
     db: &dyn DefDatabase,
     owner: DefWithBodyId,
     scope_id: Option<ScopeId>,
+) -> Resolver {
+    resolver_for_scope_in(db, owner, db.expr_scopes(owner), scope_id)
+}
+
+/// Like [`resolver_for_scope`], but with scopes that may have been computed for a body of `owner`
+/// other than the one stored in the database, like a speculatively lowered one.
+pub fn resolver_for_scope_in(
+    db: &dyn DefDatabase,
+    owner: DefWithBodyId,
+    scopes: Arc<ExprScopes>,
+    scope_id: Option<ScopeId>,
 ) -> Resolver {
     let mut r = owner.resolver(db);
-    let scopes = db.expr_scopes(owner);
     let scope_chain = scopes.scope_chain(scope_id).collect::<Vec<_>>();
     for scope in scope_chain.into_iter().rev() {
         if let Some(block) = scopes.block(scope) {
@@ -172,18 +172,32 @@ impl TyBuilder<TraitId> {
     }
 }
 
-impl TyBuilder<TypeAliasId> {
+impl TyBuilder<(TypeAliasId, usize)> {
+    /// The arguments are pushed trait arguments first, like the parameters in `generics`. The
+    /// built substitution is in chalk's order though, which puts the arguments for a generic
+    /// associated type's own parameters first.
     pub fn assoc_type_projection(
         db: &dyn HirDatabase,
         type_alias: TypeAliasId,
-    ) -> TyBuilder<TypeAliasId> {
+    ) -> TyBuilder<(TypeAliasId, usize)> {
         let generics = generics(db.upcast(), type_alias.into());
-        let param_count = generics.len();
-        TyBuilder::new(type_alias, param_count)
+        let (param_count, _, own_param_count) = generics.len_split();
+        TyBuilder::new((type_alias, own_param_count), param_count)
+    }
+
+    /// The number of parameters declared by the associated type itself, not counting the
+    /// ones of its trait. This is only non-zero for generic associated types.
+    pub fn assoc_type_own_param_count(db: &dyn HirDatabase, type_alias: TypeAliasId) -> usize {
+        let (_, _, own) = generics(db.upcast(), type_alias.into()).len_split();
+        own
     }
 
     pub fn build(self) -> ProjectionTy {
-        let (type_alias, substitution) = self.build_internal();
+        let ((type_alias, own_param_count), substitution) = self.build_internal();
+        let params = substitution.as_slice(&Interner);
+        let (trait_params, own_params) = params.split_at(params.len() - own_param_count);
+        let substitution =
+            Substitution::from_iter(&Interner, own_params.iter().chain(trait_params).cloned());
         ProjectionTy { associated_ty_id: to_assoc_type_id(type_alias), substitution }
     }
 }
@@ -5,7 +5,12 @@ use std::sync::Arc;
 use cov_mark::hit;
 use log::debug;
 
-use chalk_ir::{cast::Cast, fold::shift::Shift, CanonicalVarKinds};
+use chalk_ir::{
+    cast::Cast,
+    fold::{shift::Shift, Fold},
+    interner::HasInterner,
+    CanonicalVarKinds,
+};
 use chalk_solve::rust_ir::{self, OpaqueTyDatumBound, WellKnownTrait};
 
 use base_db::CrateId;
@@ -23,7 +28,7 @@ use crate::{
     method_resolution::{TraitImpls, TyFingerprint, ALL_FLOAT_FPS, ALL_INT_FPS},
     to_assoc_type_id, to_chalk_trait_id,
     traits::ChalkContext,
-    utils::generics,
+    utils::{generics, Generics},
     AliasEq, AliasTy, BoundVar, CallableDefId, DebruijnIndex, FnDefId, Interner, ProjectionTy,
     ProjectionTyExt, QuantifiedWhereClause, Substitution, TraitRef, TraitRefExt, Ty, TyBuilder,
     TyExt, TyKind, WhereClause,
@@ -442,11 +447,33 @@ pub(crate) fn associated_ty_data_query(
         trait_id: to_chalk_trait_id(trait_),
         id,
         name: type_alias,
-        binders: make_only_type_binders(generic_params.len(), bound_data),
+        binders: assoc_type_binders_to_chalk(&generic_params, bound_data),
     };
     Arc::new(datum)
 }
 
+/// Binds the parameters of an associated type or associated type value in chalk's order. While
+/// `generics` puts the trait's or impl's parameters first, chalk expects a generic associated
+/// type's own parameters to come first.
+fn assoc_type_binders_to_chalk<T>(generics: &Generics, value: T) -> chalk_ir::Binders<T>
+where
+    T: HasInterner<Interner = Interner> + Fold<Interner, Result = T>,
+{
+    let (len, parent_len, own_len) = generics.len_split();
+    let binders = make_only_type_binders(len, value);
+    if own_len == 0 {
+        return binders;
+    }
+    let reordered = Substitution::from_iter(
+        &Interner,
+        (0..len).map(|idx| {
+            let idx = if idx < parent_len { own_len + idx } else { idx - parent_len };
+            TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, idx)).intern(&Interner)
+        }),
+    );
+    make_only_type_binders(len, binders.substitute(&Interner, &reordered))
+}
+
 pub(crate) fn trait_datum_query(
     db: &dyn HirDatabase,
     krate: CrateId,
@@ -650,12 +677,12 @@ fn type_alias_associated_ty_value(
         .trait_data(trait_ref.hir_trait_id())
         .associated_type_by_name(&type_alias_data.name)
         .expect("assoc ty value should not exist"); // validated when building the impl data as well
-    let (ty, binders) = db.ty(type_alias.into()).into_value_and_skipped_binders();
+    let (ty, _) = db.ty(type_alias.into()).into_value_and_skipped_binders();
     let value_bound = rust_ir::AssociatedTyValueBound { ty };
     let value = rust_ir::AssociatedTyValue {
         impl_id: impl_id.to_chalk(db),
         associated_ty_id: to_assoc_type_id(assoc_ty),
-        value: chalk_ir::Binders::new(binders, value_bound),
+        value: assoc_type_binders_to_chalk(&generics(db.upcast(), type_alias.into()), value_bound),
     };
     Arc::new(value)
 }
@@ -747,22 +774,21 @@ pub(super) fn generic_predicate_to_inline_bound(
             Some(chalk_ir::Binders::new(binders, rust_ir::InlineBound::TraitBound(trait_bound)))
         }
         WhereClause::AliasEq(AliasEq { alias: AliasTy::Projection(projection_ty), ty }) => {
-            if projection_ty.self_type_parameter(&Interner) != self_ty_shifted_in {
+            let trait_ref = projection_ty.trait_ref(db);
+            if trait_ref.self_type_parameter(&Interner) != self_ty_shifted_in {
                 return None;
             }
-            let trait_ = projection_ty.trait_(db);
-            let args_no_self = projection_ty.substitution.as_slice(&Interner)[1..]
+            let params = projection_ty.substitution.as_slice(&Interner);
+            let own_param_count = params.len() - trait_ref.substitution.len(&Interner);
+            let args_no_self = trait_ref.substitution.as_slice(&Interner)[1..]
                 .iter()
                 .map(|ty| ty.clone().cast(&Interner))
                 .collect();
             let alias_eq_bound = rust_ir::AliasEqBound {
                 value: ty.clone(),
-                trait_bound: rust_ir::TraitBound {
-                    trait_id: to_chalk_trait_id(trait_),
-                    args_no_self,
-                },
+                trait_bound: rust_ir::TraitBound { trait_id: trait_ref.trait_id, args_no_self },
                 associated_ty_id: projection_ty.associated_ty_id,
-                parameters: Vec::new(), // FIXME we don't support generic associated types yet
+                parameters: params[..own_param_count].to_vec(),
             };
             Some(chalk_ir::Binders::new(
                 binders,
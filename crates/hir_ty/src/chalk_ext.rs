@@ -9,9 +9,9 @@ use hir_def::{
 
 use crate::{
     db::HirDatabase, from_assoc_type_id, from_chalk_trait_id, from_foreign_def_id,
-    from_placeholder_idx, to_chalk_trait_id, AdtId, AliasEq, AliasTy, Binders, CallableDefId,
-    CallableSig, FnPointer, ImplTraitId, Interner, Lifetime, ProjectionTy, QuantifiedWhereClause,
-    Substitution, TraitRef, Ty, TyBuilder, TyKind, WhereClause,
+    from_placeholder_idx, to_chalk_trait_id, utils::generics, AdtId, AliasEq, AliasTy, Binders,
+    CallableDefId, CallableSig, FnPointer, ImplTraitId, Interner, Lifetime, ProjectionTy,
+    QuantifiedWhereClause, Substitution, TraitRef, Ty, TyBuilder, TyKind, WhereClause,
 };
 
 pub trait TyExt {
@@ -251,7 +251,7 @@ impl TyExt for Ty {
                                 WhereClause::AliasEq(AliasEq {
                                     alias: AliasTy::Projection(proj),
                                     ty: _,
-                                }) => &proj.self_type_parameter(&Interner) == self,
+                                }) => &proj.trait_ref(db).self_type_parameter(&Interner) == self,
                                 _ => false,
                             })
                             .collect::<Vec<_>>();
@@ -324,9 +324,16 @@ pub trait ProjectionTyExt {
 
 impl ProjectionTyExt for ProjectionTy {
     fn trait_ref(&self, db: &dyn HirDatabase) -> TraitRef {
+        // The arguments for a generic associated type's own parameters come first.
+        let trait_ = self.trait_(db);
+        let params = self.substitution.as_slice(&Interner);
+        let trait_param_count = generics(db.upcast(), trait_.into()).len();
         TraitRef {
-            trait_id: to_chalk_trait_id(self.trait_(db)),
-            substitution: self.substitution.clone(),
+            trait_id: to_chalk_trait_id(trait_),
+            substitution: Substitution::from_iter(
+                &Interner,
+                &params[params.len() - trait_param_count..],
+            ),
         }
     }
 
@@ -1,19 +1,38 @@
 //! Constant evaluation details
 
-use std::convert::TryInto;
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt::{self, Display, Write},
+};
 
+use chalk_ir::{IntTy, Scalar, UintTy};
 use hir_def::{
-    builtin_type::BuiltinUint,
-    expr::{Expr, Literal},
+    body::Body,
+    builtin_type::BuiltinType,
+    expr::{
+        ArithOp, BinaryOp, CmpOp, Expr, ExprId, Literal, LogicOp, Ordering, Pat, PatId, Statement,
+        UnaryOp,
+    },
+    path::ModPath,
+    resolver::{resolver_for_expr, HasResolver, ResolveValueResult, Resolver, TypeNs, ValueNs},
     type_ref::ConstScalar,
+    ConstId, DefWithBodyId, EnumVariantId, FunctionId,
 };
+use hir_expand::name::Name;
+use la_arena::{Idx, RawIdx};
+use rustc_hash::FxHashMap;
 
-use crate::{Const, ConstData, ConstValue, Interner, TyKind};
+use crate::{
+    db::HirDatabase, primitive, CallableDefId, Const, ConstData, ConstValue, InferenceResult,
+    Interner, TyExt, TyKind,
+};
 
 /// Extension trait for [`Const`]
 pub trait ConstExt {
     /// Is a [`Const`] unknown?
     fn is_unknown(&self) -> bool;
+    /// The value of a [`Const`] that is known to be a `usize`, like the length of an array.
+    fn as_usize(&self) -> Option<u64>;
 }
 
 impl ConstExt for Const {
@@ -33,14 +52,563 @@ impl ConstExt for Const {
             }
         }
     }
+
+    fn as_usize(&self) -> Option<u64> {
+        match self.data(&Interner).value {
+            chalk_ir::ConstValue::Concrete(chalk_ir::ConcreteConst {
+                interned: ConstScalar::Usize(value),
+            }) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// The result of evaluating a constant expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComputedExpr {
+    Literal(Literal),
+    Tuple(Box<[ComputedExpr]>),
+}
+
+impl ComputedExpr {
+    /// Returns the value of an integer result.
+    pub fn as_int(&self) -> Option<i128> {
+        match self {
+            ComputedExpr::Literal(Literal::Int(it, _)) => Some(*it),
+            ComputedExpr::Literal(Literal::Uint(it, _)) => i128::try_from(*it).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ComputedExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputedExpr::Literal(l) => match l {
+                Literal::Int(x, _) => {
+                    if *x >= 16 {
+                        write!(f, "{} ({:#X})", x, x)
+                    } else {
+                        write!(f, "{}", x)
+                    }
+                }
+                Literal::Uint(x, _) => {
+                    if *x >= 16 {
+                        write!(f, "{} ({:#X})", x, x)
+                    } else {
+                        write!(f, "{}", x)
+                    }
+                }
+                Literal::Float(x, _) => write!(f, "{}", f64::from_bits(*x)),
+                Literal::Bool(x) => write!(f, "{}", x),
+                Literal::Char(x) => write!(f, "{:?}", x),
+                Literal::String(x) => write!(f, "{:?}", x),
+                Literal::ByteString(x) => write!(f, "{:?}", x),
+            },
+            ComputedExpr::Tuple(t) => {
+                f.write_char('(')?;
+                for (i, x) in t.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    x.fmt(f)?;
+                }
+                if t.len() == 1 {
+                    f.write_char(',')?;
+                }
+                f.write_char(')')
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstEvalError {
+    /// The expression uses a feature the evaluator doesn't understand (yet).
+    NotSupported(&'static str),
+    /// The expression is not well typed, e.g. `1 + true`.
+    TypeError,
+    /// The expression is incomplete, e.g. because of syntax errors.
+    IncompleteExpr,
+    /// A path in the expression could not be resolved to a constant.
+    UnresolvedPath,
+    /// An arithmetic operation overflowed its type.
+    Overflow,
+    /// A division or remainder by zero.
+    DivisionByZero,
+    /// The constant depends on itself.
+    Cycle,
+}
+
+impl Display for ConstEvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstEvalError::NotSupported(what) => write!(f, "{} is not supported", what),
+            ConstEvalError::TypeError => f.write_str("type error"),
+            ConstEvalError::IncompleteExpr => f.write_str("incomplete expression"),
+            ConstEvalError::UnresolvedPath => f.write_str("unresolved path"),
+            ConstEvalError::Overflow => f.write_str("arithmetic overflow"),
+            ConstEvalError::DivisionByZero => f.write_str("division by zero"),
+            ConstEvalError::Cycle => f.write_str("cycle in constant evaluation"),
+        }
+    }
+}
+
+pub struct ConstEvalCtx<'a> {
+    pub db: &'a dyn HirDatabase,
+    pub resolver: Resolver,
+    pub body: &'a Body,
+    /// Types of the body's expressions, if the body has been inferred already.
+    pub infer: Option<&'a InferenceResult>,
+    /// Type of integer expressions whose type can't be inferred.
+    pub default_int: Scalar,
+    /// Values of the local bindings in scope, like function parameters.
+    pub local_data: FxHashMap<Name, ComputedExpr>,
+    /// Number of const fn calls that are being evaluated around this body.
+    pub call_depth: usize,
+}
+
+/// Const fn calls nested deeper than this are not evaluated, to guard against infinite recursion.
+const MAX_CALL_DEPTH: usize = 64;
+
+impl ConstEvalCtx<'_> {
+    fn expr_scalar(&self, expr: ExprId) -> Option<Scalar> {
+        let infer = self.infer?;
+        match infer.type_of_expr.get(expr)?.kind(&Interner) {
+            TyKind::Scalar(scalar) => Some(*scalar),
+            _ => None,
+        }
+    }
+
+    fn int_scalar(&self, expr: ExprId) -> Scalar {
+        match self.expr_scalar(expr) {
+            Some(scalar @ (Scalar::Int(_) | Scalar::Uint(_))) => scalar,
+            _ => self.default_int,
+        }
+    }
+}
+
+pub fn eval_const(
+    expr: ExprId,
+    ctx: &mut ConstEvalCtx<'_>,
+) -> Result<ComputedExpr, ConstEvalError> {
+    let body = ctx.body;
+    match &body[expr] {
+        Expr::Missing => Err(ConstEvalError::IncompleteExpr),
+        Expr::Literal(l) => match l {
+            Literal::Int(v, suffix) => {
+                let scalar = suffix
+                    .map(|it| Scalar::Int(primitive::int_ty_from_builtin(it)))
+                    .unwrap_or_else(|| ctx.int_scalar(expr));
+                make_int(*v, scalar)
+            }
+            Literal::Uint(v, suffix) => {
+                let scalar = suffix
+                    .map(|it| Scalar::Uint(primitive::uint_ty_from_builtin(it)))
+                    .unwrap_or_else(|| ctx.int_scalar(expr));
+                let v = i128::try_from(*v).map_err(|_| ConstEvalError::NotSupported("u128"))?;
+                make_int(v, scalar)
+            }
+            // The values of char literals are not lowered yet.
+            Literal::Char(_) => Err(ConstEvalError::NotSupported("char literals")),
+            _ => Ok(ComputedExpr::Literal(l.clone())),
+        },
+        &Expr::UnaryOp { expr: inner, op: UnaryOp::Neg } => {
+            let scalar = ctx.int_scalar(expr);
+            if let Scalar::Uint(_) = scalar {
+                return Err(ConstEvalError::TypeError);
+            }
+            let v = match &body[inner] {
+                // Don't evaluate the literal on its own, as `-128i8` is fine but `128i8` is not.
+                Expr::Literal(Literal::Int(v, _)) => *v,
+                Expr::Literal(Literal::Uint(v, _)) => {
+                    i128::try_from(*v).map_err(|_| ConstEvalError::Overflow)?
+                }
+                _ => eval_const(inner, ctx)?.as_int().ok_or(ConstEvalError::TypeError)?,
+            };
+            make_int(v.checked_neg().ok_or(ConstEvalError::Overflow)?, scalar)
+        }
+        &Expr::UnaryOp { expr: inner, op: UnaryOp::Not } => match eval_const(inner, ctx)? {
+            ComputedExpr::Literal(Literal::Bool(b)) => Ok(ComputedExpr::Literal(Literal::Bool(!b))),
+            value => {
+                let v = value.as_int().ok_or(ConstEvalError::TypeError)?;
+                let scalar = ctx.int_scalar(expr);
+                match scalar {
+                    Scalar::Uint(_) => make_int(int_bounds(scalar)?.1 ^ v, scalar),
+                    _ => make_int(!v, scalar),
+                }
+            }
+        },
+        Expr::UnaryOp { op: UnaryOp::Deref, .. } => Err(ConstEvalError::NotSupported("deref")),
+        &Expr::BinaryOp { lhs, rhs, op } => {
+            let op = op.ok_or(ConstEvalError::IncompleteExpr)?;
+            let lhs_value = eval_const(lhs, ctx)?;
+            let rhs_value = eval_const(rhs, ctx)?;
+            match op {
+                BinaryOp::LogicOp(op) => match (lhs_value, rhs_value) {
+                    (
+                        ComputedExpr::Literal(Literal::Bool(l)),
+                        ComputedExpr::Literal(Literal::Bool(r)),
+                    ) => Ok(ComputedExpr::Literal(Literal::Bool(match op {
+                        LogicOp::And => l && r,
+                        LogicOp::Or => l || r,
+                    }))),
+                    _ => Err(ConstEvalError::TypeError),
+                },
+                BinaryOp::CmpOp(op) => {
+                    let res = match (&lhs_value, &rhs_value) {
+                        (
+                            ComputedExpr::Literal(Literal::Bool(l)),
+                            ComputedExpr::Literal(Literal::Bool(r)),
+                        ) => compare(op, l, r),
+                        (
+                            ComputedExpr::Literal(Literal::Char(l)),
+                            ComputedExpr::Literal(Literal::Char(r)),
+                        ) => compare(op, l, r),
+                        _ => {
+                            let l = lhs_value.as_int().ok_or(ConstEvalError::TypeError)?;
+                            let r = rhs_value.as_int().ok_or(ConstEvalError::TypeError)?;
+                            compare(op, &l, &r)
+                        }
+                    };
+                    Ok(ComputedExpr::Literal(Literal::Bool(res)))
+                }
+                BinaryOp::ArithOp(op) => {
+                    let scalar = ctx.int_scalar(expr);
+                    let l = lhs_value.as_int().ok_or(ConstEvalError::TypeError)?;
+                    let r = rhs_value.as_int().ok_or(ConstEvalError::TypeError)?;
+                    let res = match op {
+                        ArithOp::Add => l.checked_add(r).ok_or(ConstEvalError::Overflow)?,
+                        ArithOp::Sub => l.checked_sub(r).ok_or(ConstEvalError::Overflow)?,
+                        ArithOp::Mul => l.checked_mul(r).ok_or(ConstEvalError::Overflow)?,
+                        ArithOp::Div | ArithOp::Rem if r == 0 => {
+                            return Err(ConstEvalError::DivisionByZero)
+                        }
+                        ArithOp::Div => l.checked_div(r).ok_or(ConstEvalError::Overflow)?,
+                        ArithOp::Rem => l.checked_rem(r).ok_or(ConstEvalError::Overflow)?,
+                        ArithOp::Shl | ArithOp::Shr => {
+                            if r < 0 || r >= i128::from(int_bits(scalar)?) {
+                                return Err(ConstEvalError::Overflow);
+                            }
+                            if op == ArithOp::Shl {
+                                // Shifting out bits is not an overflow, so truncate like `as`.
+                                return make_int(truncate(l << r, scalar)?, scalar);
+                            }
+                            l >> r
+                        }
+                        ArithOp::BitAnd => l & r,
+                        ArithOp::BitOr => l | r,
+                        ArithOp::BitXor => l ^ r,
+                    };
+                    make_int(res, scalar)
+                }
+                BinaryOp::Assignment { .. } => Err(ConstEvalError::NotSupported("assignment")),
+            }
+        }
+        &Expr::Cast { expr: inner, .. } => {
+            let value = eval_const(inner, ctx)?;
+            let target = match ctx.expr_scalar(expr) {
+                Some(scalar @ (Scalar::Int(_) | Scalar::Uint(_))) => scalar,
+                Some(_) => return Err(ConstEvalError::NotSupported("non-integer cast")),
+                None => return Err(ConstEvalError::IncompleteExpr),
+            };
+            let v = match value {
+                ComputedExpr::Literal(Literal::Bool(b)) => i128::from(b),
+                ComputedExpr::Literal(Literal::Char(c)) => i128::from(u32::from(c)),
+                value => value.as_int().ok_or(ConstEvalError::TypeError)?,
+            };
+            make_int(truncate(v, target)?, target)
+        }
+        &Expr::Block { ref statements, tail: Some(tail), label: None, .. } => {
+            // Bindings of the block go out of scope at its end.
+            let outer_locals = ctx.local_data.clone();
+            let res = statements
+                .iter()
+                .try_for_each(|statement| match *statement {
+                    Statement::Let { pat, initializer: Some(initializer), .. } => {
+                        let value = eval_const(initializer, ctx)?;
+                        bind_pat(pat, value, ctx)
+                    }
+                    Statement::Let { initializer: None, .. } => {
+                        Err(ConstEvalError::NotSupported("`let` without initializer"))
+                    }
+                    Statement::Expr { expr, .. } => eval_const(expr, ctx).map(drop),
+                })
+                .and_then(|()| eval_const(tail, ctx));
+            ctx.local_data = outer_locals;
+            res
+        }
+        &Expr::If { condition, then_branch, else_branch } => match eval_const(condition, ctx)? {
+            ComputedExpr::Literal(Literal::Bool(true)) => eval_const(then_branch, ctx),
+            ComputedExpr::Literal(Literal::Bool(false)) => match else_branch {
+                Some(else_branch) => eval_const(else_branch, ctx),
+                None => Ok(ComputedExpr::Tuple(Box::new([]))),
+            },
+            _ => Err(ConstEvalError::TypeError),
+        },
+        Expr::Tuple { exprs } => Ok(ComputedExpr::Tuple(
+            exprs.iter().map(|&it| eval_const(it, ctx)).collect::<Result<_, _>>()?,
+        )),
+        &Expr::Call { callee, ref args } => {
+            let func = ctx
+                .infer
+                .and_then(|infer| infer.type_of_expr.get(callee)?.callable_def(ctx.db))
+                .ok_or(ConstEvalError::NotSupported("function calls"))?;
+            let func = match func {
+                CallableDefId::FunctionId(it) => it,
+                CallableDefId::StructId(_) | CallableDefId::EnumVariantId(_) => {
+                    return Err(ConstEvalError::NotSupported("tuple struct constructors"))
+                }
+            };
+            let args = args.iter().map(|&it| eval_const(it, ctx)).collect::<Result<_, _>>()?;
+            eval_fn_call(func, args, ctx)
+        }
+        &Expr::MethodCall { receiver, ref args, .. } => {
+            let (func, _) = ctx
+                .infer
+                .and_then(|infer| infer.method_resolution(expr))
+                .ok_or(ConstEvalError::NotSupported("method calls"))?;
+            let args = std::iter::once(receiver)
+                .chain(args.iter().copied())
+                .map(|it| eval_const(it, ctx))
+                .collect::<Result<_, _>>()?;
+            eval_fn_call(func, args, ctx)
+        }
+        Expr::Path(p) => eval_path(p.mod_path(), ctx),
+        Expr::Loop { .. } | Expr::While { .. } | Expr::For { .. } => {
+            Err(ConstEvalError::NotSupported("loops"))
+        }
+        Expr::Box { .. } => Err(ConstEvalError::NotSupported("heap allocation")),
+        Expr::Return { .. } => Err(ConstEvalError::NotSupported("early return")),
+        _ => Err(ConstEvalError::NotSupported("this kind of expression")),
+    }
+}
+
+/// Evaluates a call to `func` with the already evaluated `args`, in a context of its own.
+fn eval_fn_call(
+    func: FunctionId,
+    args: Vec<ComputedExpr>,
+    ctx: &ConstEvalCtx<'_>,
+) -> Result<ComputedExpr, ConstEvalError> {
+    let db = ctx.db;
+    if !db.function_data(func).is_const() {
+        return Err(ConstEvalError::NotSupported("calling a non-const fn"));
+    }
+    if ctx.call_depth >= MAX_CALL_DEPTH {
+        return Err(ConstEvalError::NotSupported("deeply nested calls"));
+    }
+    let owner = DefWithBodyId::from(func);
+    let body = db.body(owner);
+    let infer = db.infer(owner);
+    let mut callee_ctx = ConstEvalCtx {
+        db,
+        resolver: resolver_for_expr(db.upcast(), owner, body.body_expr),
+        body: &body,
+        infer: Some(&infer),
+        default_int: Scalar::Int(IntTy::I32),
+        local_data: FxHashMap::default(),
+        call_depth: ctx.call_depth + 1,
+    };
+    if body.params.len() != args.len() {
+        return Err(ConstEvalError::TypeError);
+    }
+    for (&param, arg) in body.params.iter().zip(args) {
+        bind_pat(param, arg, &mut callee_ctx)?;
+    }
+    eval_const(body.body_expr, &mut callee_ctx)
+}
+
+fn bind_pat(
+    pat: PatId,
+    value: ComputedExpr,
+    ctx: &mut ConstEvalCtx<'_>,
+) -> Result<(), ConstEvalError> {
+    match &ctx.body[pat] {
+        Pat::Bind { name, subpat: None, .. } => {
+            ctx.local_data.insert(name.clone(), value);
+            Ok(())
+        }
+        Pat::Wild => Ok(()),
+        _ => Err(ConstEvalError::NotSupported("destructuring patterns")),
+    }
+}
+
+fn eval_path(path: &ModPath, ctx: &ConstEvalCtx<'_>) -> Result<ComputedExpr, ConstEvalError> {
+    if let Some(value) = path.as_ident().and_then(|name| ctx.local_data.get(name)) {
+        return Ok(value.clone());
+    }
+    let resolved = ctx
+        .resolver
+        .resolve_path_in_value_ns(ctx.db.upcast(), path)
+        .ok_or(ConstEvalError::UnresolvedPath)?;
+    match resolved {
+        ResolveValueResult::ValueNs(ValueNs::ConstId(konst)) => ctx.db.const_eval(konst),
+        // `u8::MAX` and friends
+        ResolveValueResult::Partial(TypeNs::BuiltinType(builtin), 1) => {
+            let scalar = match builtin {
+                BuiltinType::Int(it) => Scalar::Int(primitive::int_ty_from_builtin(it)),
+                BuiltinType::Uint(it) => Scalar::Uint(primitive::uint_ty_from_builtin(it)),
+                _ => return Err(ConstEvalError::NotSupported("associated constants")),
+            };
+            let (min, max) = int_bounds(scalar)?;
+            let name = path.segments().last().ok_or(ConstEvalError::IncompleteExpr)?;
+            match &*name.to_string() {
+                "MIN" => make_int(min, scalar),
+                "MAX" => make_int(max, scalar),
+                "BITS" => make_int(int_bits(scalar)?.into(), Scalar::Uint(UintTy::U32)),
+                _ => Err(ConstEvalError::UnresolvedPath),
+            }
+        }
+        ResolveValueResult::ValueNs(ValueNs::LocalBinding(_)) => {
+            Err(ConstEvalError::NotSupported("local bindings"))
+        }
+        _ => Err(ConstEvalError::NotSupported("this kind of path")),
+    }
+}
+
+fn compare<T: Ord>(op: CmpOp, l: &T, r: &T) -> bool {
+    match op {
+        CmpOp::Eq { negated } => (l == r) != negated,
+        CmpOp::Ord { ordering: Ordering::Less, strict: true } => l < r,
+        CmpOp::Ord { ordering: Ordering::Less, strict: false } => l <= r,
+        CmpOp::Ord { ordering: Ordering::Greater, strict: true } => l > r,
+        CmpOp::Ord { ordering: Ordering::Greater, strict: false } => l >= r,
+    }
 }
 
-// FIXME: support more than just evaluating literals
-pub fn eval_usize(expr: &Expr) -> Option<u64> {
-    match expr {
-        Expr::Literal(Literal::Uint(v, None | Some(BuiltinUint::Usize))) => (*v).try_into().ok(),
-        _ => None,
+// FIXME: `usize` and `isize` are assumed to be 64 bits wide, this should use
+// the target's data layout instead.
+fn int_bits(scalar: Scalar) -> Result<u32, ConstEvalError> {
+    Ok(match scalar {
+        Scalar::Int(IntTy::I8) | Scalar::Uint(UintTy::U8) => 8,
+        Scalar::Int(IntTy::I16) | Scalar::Uint(UintTy::U16) => 16,
+        Scalar::Int(IntTy::I32) | Scalar::Uint(UintTy::U32) => 32,
+        Scalar::Int(IntTy::I64 | IntTy::Isize) | Scalar::Uint(UintTy::U64 | UintTy::Usize) => 64,
+        Scalar::Int(IntTy::I128) | Scalar::Uint(UintTy::U128) => 128,
+        _ => return Err(ConstEvalError::TypeError),
+    })
+}
+
+/// Inclusive bounds of an integer type. `u128::MAX` does not fit, so it is not
+/// supported.
+fn int_bounds(scalar: Scalar) -> Result<(i128, i128), ConstEvalError> {
+    let bits = int_bits(scalar)?;
+    Ok(match scalar {
+        Scalar::Uint(UintTy::U128) => (0, i128::MAX),
+        Scalar::Uint(_) => (0, (1i128 << bits) - 1),
+        _ if bits == 128 => (i128::MIN, i128::MAX),
+        _ => (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1),
+    })
+}
+
+/// Truncates (or sign-extends) `v` to the bit width of `scalar`, like `as` does.
+fn truncate(v: i128, scalar: Scalar) -> Result<i128, ConstEvalError> {
+    let bits = int_bits(scalar)?;
+    if bits == 128 {
+        return match scalar {
+            Scalar::Uint(_) if v < 0 => Err(ConstEvalError::NotSupported("u128")),
+            _ => Ok(v),
+        };
+    }
+    let mask = (1i128 << bits) - 1;
+    let v = v & mask;
+    Ok(match scalar {
+        Scalar::Int(_) if v >> (bits - 1) == 1 => v - (1i128 << bits),
+        _ => v,
+    })
+}
+
+fn make_int(v: i128, scalar: Scalar) -> Result<ComputedExpr, ConstEvalError> {
+    let (min, max) = int_bounds(scalar)?;
+    if v < min || v > max {
+        return Err(ConstEvalError::Overflow);
     }
+    let literal = match scalar {
+        Scalar::Int(it) => Literal::Int(v, Some(primitive::builtin_int_from_ty(it))),
+        Scalar::Uint(it) => Literal::Uint(v as u128, Some(primitive::builtin_uint_from_ty(it))),
+        _ => return Err(ConstEvalError::TypeError),
+    };
+    Ok(ComputedExpr::Literal(literal))
+}
+
+pub(crate) fn const_eval_query(
+    db: &dyn HirDatabase,
+    def: ConstId,
+) -> Result<ComputedExpr, ConstEvalError> {
+    let owner = DefWithBodyId::from(def);
+    let body = db.body(owner);
+    let infer = db.infer(owner);
+    let mut ctx = ConstEvalCtx {
+        db,
+        resolver: resolver_for_expr(db.upcast(), owner, body.body_expr),
+        body: &body,
+        infer: Some(&infer),
+        default_int: Scalar::Int(IntTy::I32),
+        local_data: FxHashMap::default(),
+        call_depth: 0,
+    };
+    eval_const(body.body_expr, &mut ctx)
+}
+
+pub(crate) fn const_eval_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &ConstId,
+) -> Result<ComputedExpr, ConstEvalError> {
+    Err(ConstEvalError::Cycle)
+}
+
+pub(crate) fn const_eval_discriminant_query(
+    db: &dyn HirDatabase,
+    variant: EnumVariantId,
+) -> Result<i128, ConstEvalError> {
+    // FIXME: respect `#[repr(inttype)]`
+    let default_int = Scalar::Int(IntTy::Isize);
+    match Body::enum_discriminant(db.upcast(), variant) {
+        Some(body) => {
+            let mut ctx = ConstEvalCtx {
+                db,
+                resolver: variant.parent.resolver(db.upcast()),
+                body: &body,
+                infer: None,
+                default_int,
+                local_data: FxHashMap::default(),
+                call_depth: 0,
+            };
+            let value = eval_const(body.body_expr, &mut ctx)?;
+            value.as_int().ok_or(ConstEvalError::TypeError)
+        }
+        None => {
+            let prev_idx = u32::from(variant.local_id.into_raw());
+            if prev_idx == 0 {
+                return Ok(0);
+            }
+            let prev = EnumVariantId {
+                parent: variant.parent,
+                local_id: Idx::from_raw(RawIdx::from(prev_idx - 1)),
+            };
+            let prev = db.const_eval_discriminant(prev)?;
+            let (_, max) = int_bounds(default_int)?;
+            if prev == max {
+                return Err(ConstEvalError::Overflow);
+            }
+            Ok(prev + 1)
+        }
+    }
+}
+
+pub(crate) fn const_eval_discriminant_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _variant: &EnumVariantId,
+) -> Result<i128, ConstEvalError> {
+    Err(ConstEvalError::Cycle)
+}
+
+/// Evaluates the length of an array repeat expression, like the `N * 2` in `[0; N * 2]`.
+pub fn eval_usize(expr: ExprId, ctx: &mut ConstEvalCtx<'_>) -> Option<u64> {
+    let value = eval_const(expr, ctx).ok()?.as_int()?;
+    value.try_into().ok()
 }
 
 /// Interns a possibly-unknown target usize
@@ -53,3 +621,6 @@ pub fn usize_const(value: Option<u64>) -> Const {
     }
     .intern(&Interner)
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,234 @@
+use base_db::fixture::WithFixture;
+use hir_def::{
+    builtin_type::{BuiltinInt, BuiltinUint},
+    db::DefDatabase,
+    expr::Literal,
+    AdtId, EnumVariantId, ModuleDefId,
+};
+
+use crate::{db::HirDatabase, test_db::TestDB};
+
+use super::{ComputedExpr, ConstEvalError};
+
+fn check_number(ra_fixture: &str, answer: i128) {
+    let r = eval_goal(ra_fixture).unwrap();
+    assert_eq!(r.as_int(), Some(answer), "{:?}", r);
+}
+
+fn check_fail(ra_fixture: &str, error: ConstEvalError) {
+    assert_eq!(eval_goal(ra_fixture), Err(error));
+}
+
+fn eval_goal(ra_fixture: &str) -> Result<ComputedExpr, ConstEvalError> {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module_id = db.module_for_file(file_id);
+    let def_map = module_id.def_map(&db);
+    let scope = &def_map[module_id.local_id].scope;
+    let const_id = scope
+        .declarations()
+        .find_map(|x| match x {
+            ModuleDefId::ConstId(x) => {
+                if db.const_data(x).name.as_ref()?.to_string() == "GOAL" {
+                    Some(x)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+        .unwrap();
+    db.const_eval(const_id)
+}
+
+#[test]
+fn add() {
+    check_number(r#"const GOAL: usize = 2 + 2;"#, 4);
+}
+
+#[test]
+fn arithmetic() {
+    check_number(r#"const GOAL: i32 = 1 + 2 * 3 - 8 / 2 % 3;"#, 6);
+    check_number(r#"const GOAL: u8 = (0b1010 | 0b0101) ^ 0b1 & 0xFF;"#, 14);
+    check_number(r#"const GOAL: u32 = 1 << 4 >> 2;"#, 4);
+    check_number(r#"const GOAL: i8 = -128;"#, -128);
+    check_number(r#"const GOAL: u8 = !0;"#, 255);
+    check_number(r#"const GOAL: i16 = !0;"#, -1);
+}
+
+#[test]
+fn typed_literals() {
+    let r = eval_goal(r#"const GOAL: u16 = 1 + 2;"#).unwrap();
+    assert_eq!(r, ComputedExpr::Literal(Literal::Uint(3, Some(BuiltinUint::U16))));
+    let r = eval_goal(r#"const GOAL: i64 = -3;"#).unwrap();
+    assert_eq!(r, ComputedExpr::Literal(Literal::Int(-3, Some(BuiltinInt::I64))));
+}
+
+#[test]
+fn bools_and_comparisons() {
+    let r = eval_goal(r#"const GOAL: bool = 1 < 2 && !(3 == 4) || false;"#).unwrap();
+    assert_eq!(r, ComputedExpr::Literal(Literal::Bool(true)));
+}
+
+#[test]
+fn casts() {
+    check_number(r#"const GOAL: u8 = 300i32 as u8;"#, 44);
+    check_number(r#"const GOAL: i8 = 255u8 as i8;"#, -1);
+    check_number(r#"const GOAL: u64 = -1i32 as u64;"#, u64::MAX.into());
+    check_fail(r#"const GOAL: u32 = 'a' as u32;"#, ConstEvalError::NotSupported("char literals"));
+    check_number(r#"const GOAL: usize = true as usize;"#, 1);
+}
+
+#[test]
+fn consts_referring_to_consts() {
+    check_number(
+        r#"
+const F1: i32 = 1;
+const F3: i32 = 3 * F2;
+const F2: i32 = 2 * F1;
+const GOAL: i32 = F3;
+"#,
+        6,
+    );
+    check_number(
+        r#"
+mod m {
+    pub const X: u8 = 10;
+}
+const GOAL: u8 = m::X + { m::X };
+"#,
+        20,
+    );
+}
+
+#[test]
+fn primitive_assoc_consts() {
+    check_number(r#"const GOAL: u8 = u8::MAX;"#, 255);
+    check_number(r#"const GOAL: i32 = i32::MIN;"#, i32::MIN.into());
+    check_number(r#"const GOAL: u32 = u16::BITS;"#, 16);
+    check_number(r#"const GOAL: usize = usize::MAX as u8 as usize;"#, 255);
+}
+
+#[test]
+fn overflow() {
+    check_fail(r#"const GOAL: u8 = 255 + 1;"#, ConstEvalError::Overflow);
+    check_fail(r#"const GOAL: u8 = 0 - 1;"#, ConstEvalError::Overflow);
+    check_fail(r#"const GOAL: i8 = -128 - 1;"#, ConstEvalError::Overflow);
+    check_fail(r#"const GOAL: u32 = 1 << 32;"#, ConstEvalError::Overflow);
+    check_fail(r#"const GOAL: i32 = i32::MAX * 2;"#, ConstEvalError::Overflow);
+    check_fail(r#"const GOAL: i32 = 1 / 0;"#, ConstEvalError::DivisionByZero);
+}
+
+#[test]
+fn cycle() {
+    check_fail(
+        r#"
+const A: i32 = B;
+const B: i32 = A;
+const GOAL: i32 = A;
+"#,
+        ConstEvalError::Cycle,
+    );
+    check_fail(r#"const GOAL: i32 = GOAL + 1;"#, ConstEvalError::Cycle);
+}
+
+#[test]
+fn const_fn_calls() {
+    check_number(
+        r#"
+const fn square(x: u32) -> u32 { x * x }
+const fn low_bits(x: u32, n: u32) -> u32 {
+    let mask = (1 << n) - 1;
+    x & mask
+}
+const GOAL: u32 = low_bits(square(7) + 3, 4);
+"#,
+        4,
+    );
+    check_number(
+        r#"
+const fn fib(n: u64) -> u64 { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } }
+const GOAL: u64 = fib(10);
+"#,
+        55,
+    );
+    check_number(
+        r#"
+const fn shadowed(x: i32) -> i32 {
+    let y = { let x = x * 2; x };
+    x + y
+}
+const GOAL: i32 = shadowed(14);
+"#,
+        42,
+    );
+}
+
+#[test]
+fn not_supported() {
+    check_fail(
+        r#"
+struct Vec;
+impl Vec {
+    fn new() -> Vec { Vec }
+}
+const fn f() -> i32 { Vec::new(); 1 }
+const GOAL: i32 = f();
+"#,
+        ConstEvalError::NotSupported("calling a non-const fn"),
+    );
+    check_fail(
+        r#"
+const fn f(n: u32) -> u32 { loop {} }
+const GOAL: u32 = f(1);
+"#,
+        ConstEvalError::NotSupported("loops"),
+    );
+    check_fail(
+        r#"
+const fn f(n: u32) -> u32 { f(n) }
+const GOAL: u32 = f(1);
+"#,
+        ConstEvalError::NotSupported("deeply nested calls"),
+    );
+    check_fail(r#"const GOAL: i32 = loop {};"#, ConstEvalError::NotSupported("loops"));
+}
+
+#[test]
+fn enum_discriminants() {
+    let (db, file_id) = TestDB::with_single_file(
+        r#"
+const BASE: isize = 10;
+enum E {
+    A,
+    B = BASE * 2,
+    C,
+    D = -1,
+    E,
+}
+"#,
+    );
+    let module_id = db.module_for_file(file_id);
+    let def_map = module_id.def_map(&db);
+    let scope = &def_map[module_id.local_id].scope;
+    let enum_id = scope
+        .declarations()
+        .find_map(|x| match x {
+            ModuleDefId::AdtId(AdtId::EnumId(x)) => Some(x),
+            _ => None,
+        })
+        .unwrap();
+    let discriminants: Vec<_> = db
+        .enum_data(enum_id)
+        .variants
+        .iter()
+        .map(|(local_id, _)| {
+            db.const_eval_discriminant(EnumVariantId { parent: enum_id, local_id })
+        })
+        .collect();
+    assert_eq!(discriminants, vec![Ok(0), Ok(20), Ok(21), Ok(-1), Ok(0)]);
+}
+
+#[test]
+fn array_repeat_length_cycle() {
+    check_fail(r#"const GOAL: usize = [0; GOAL].len();"#, ConstEvalError::Cycle);
+}
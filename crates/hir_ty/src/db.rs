@@ -5,14 +5,18 @@ use std::sync::Arc;
 
 use base_db::{impl_intern_key, salsa, CrateId, Upcast};
 use hir_def::{
-    db::DefDatabase, expr::ExprId, BlockId, ConstParamId, DefWithBodyId, FunctionId, GenericDefId,
-    ImplId, LifetimeParamId, LocalFieldId, TypeParamId, VariantId,
+    db::DefDatabase, expr::ExprId, AdtId, BlockId, ConstId, ConstParamId, DefWithBodyId,
+    EnumVariantId, FunctionId, GenericDefId, ImplId, LifetimeParamId, LocalFieldId, TraitId,
+    TypeParamId, VariantId,
 };
 use la_arena::ArenaMap;
 
 use crate::{
     chalk_db,
-    method_resolution::{InherentImpls, TraitImpls},
+    consteval::{ComputedExpr, ConstEvalError},
+    layout::{Layout, LayoutError},
+    method_resolution::{InherentImpls, TraitImpls, TyFingerprint},
+    object_safety::ObjectSafetyViolation,
     Binders, CallableDefId, FnDefId, ImplTraitId, InferenceResult, Interner, PolyFnSig,
     QuantifiedWhereClause, ReturnTypeImplTraits, TraitRef, Ty, TyDefId, ValueTyDefId,
 };
@@ -25,6 +29,7 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     fn infer(&self, def: DefWithBodyId) -> Arc<InferenceResult>;
 
     #[salsa::invoke(crate::infer::infer_query)]
+    #[salsa::cycle(crate::infer::infer_recover)]
     fn infer_query(&self, def: DefWithBodyId) -> Arc<InferenceResult>;
 
     #[salsa::invoke(crate::lower::ty_query)]
@@ -34,6 +39,18 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::lower::value_ty_query)]
     fn value_ty(&self, def: ValueTyDefId) -> Binders<Ty>;
 
+    #[salsa::invoke(crate::consteval::const_eval_query)]
+    #[salsa::cycle(crate::consteval::const_eval_recover)]
+    fn const_eval(&self, def: ConstId) -> Result<ComputedExpr, ConstEvalError>;
+
+    #[salsa::invoke(crate::consteval::const_eval_discriminant_query)]
+    #[salsa::cycle(crate::consteval::const_eval_discriminant_recover)]
+    fn const_eval_discriminant(&self, variant: EnumVariantId) -> Result<i128, ConstEvalError>;
+
+    #[salsa::invoke(crate::layout::layout_of_adt_query)]
+    #[salsa::cycle(crate::layout::layout_of_adt_recover)]
+    fn layout_of_adt(&self, def: AdtId) -> Result<Layout, LayoutError>;
+
     #[salsa::invoke(crate::lower::impl_self_ty_query)]
     #[salsa::cycle(crate::lower::impl_self_ty_recover)]
     fn impl_self_ty(&self, def: ImplId) -> Binders<Ty>;
@@ -73,6 +90,9 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::cycle(crate::lower::generic_defaults_recover)]
     fn generic_defaults(&self, def: GenericDefId) -> Arc<[Binders<Ty>]>;
 
+    #[salsa::invoke(crate::object_safety::object_safety_violations_query)]
+    fn object_safety_violations(&self, trait_: TraitId) -> Arc<[ObjectSafetyViolation]>;
+
     #[salsa::invoke(InherentImpls::inherent_impls_in_crate_query)]
     fn inherent_impls_in_crate(&self, krate: CrateId) -> Arc<InherentImpls>;
 
@@ -85,6 +105,19 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(TraitImpls::trait_impls_in_deps_query)]
     fn trait_impls_in_deps(&self, krate: CrateId) -> Arc<TraitImpls>;
 
+    /// Trait impls defined in `krate` or in any crate that (transitively) depends on it.
+    #[salsa::invoke(TraitImpls::trait_impls_in_rev_deps_query)]
+    fn trait_impls_in_rev_deps(&self, krate: CrateId) -> Arc<TraitImpls>;
+
+    /// Trait impls whose self type has the fingerprint `fp`, defined in `krate` or in any crate
+    /// that (transitively) depends on it. Blanket impls are not included.
+    ///
+    /// The reverse dependency index behind this is built once per crate, so after the first
+    /// call for a crate this only costs a lookup per trait instead of walking the crate graph
+    /// and every crate's impls again.
+    #[salsa::invoke(TraitImpls::trait_impls_for_self_ty_in_deps_query)]
+    fn trait_impls_for_self_ty_in_deps(&self, krate: CrateId, fp: TyFingerprint) -> Arc<[ImplId]>;
+
     // Interned IDs for Chalk integration
     #[salsa::interned]
     fn intern_callable_def(&self, callable_def: CallableDefId) -> InternedCallableDefId;
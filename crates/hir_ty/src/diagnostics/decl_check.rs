@@ -292,7 +292,7 @@ impl<'a> DeclValidator<'a> {
         let mut struct_fields_replacements = Vec::new();
 
         if !non_snake_case_allowed {
-            if let VariantData::Record(fields) = data.variant_data.as_ref() {
+            if let VariantData::Record { fields, .. } = data.variant_data.as_ref() {
                 for (_, field) in fields.iter() {
                     let field_name = field.name.to_string();
                     if let Some(new_name) = to_lower_snake_case(&field_name) {
@@ -40,6 +40,8 @@ pub struct HirFormatter<'a> {
     buf: String,
     curr_size: usize,
     pub(crate) max_size: Option<usize>,
+    curr_depth: usize,
+    max_depth: Option<usize>,
     omit_verbose_types: bool,
     display_target: DisplayTarget,
 }
@@ -62,7 +64,14 @@ pub trait HirDisplay {
             !matches!(display_target, DisplayTarget::SourceCode { .. }),
             "HirDisplayWrapper cannot fail with DisplaySourceCodeError, use HirDisplay::hir_fmt directly instead"
         );
-        HirDisplayWrapper { db, t: self, max_size, omit_verbose_types, display_target }
+        HirDisplayWrapper {
+            db,
+            t: self,
+            max_size,
+            max_depth: None,
+            omit_verbose_types,
+            display_target,
+        }
     }
 
     /// Returns a `Display`able type that is human-readable.
@@ -75,6 +84,7 @@ pub trait HirDisplay {
             db,
             t: self,
             max_size: None,
+            max_depth: None,
             omit_verbose_types: false,
             display_target: DisplayTarget::Diagnostics,
         }
@@ -94,11 +104,33 @@ pub trait HirDisplay {
             db,
             t: self,
             max_size,
+            max_depth: None,
             omit_verbose_types: true,
             display_target: DisplayTarget::Diagnostics,
         }
     }
 
+    /// Returns a `Display`able type that is human-readable and replaces everything nested
+    /// deeper than `max_depth` with `…`.
+    /// Use this for showing types that can get arbitrarily deep (e.g. hovers)
+    fn display_max_depth<'a>(
+        &'a self,
+        db: &'a dyn HirDatabase,
+        max_depth: Option<usize>,
+    ) -> HirDisplayWrapper<'a, Self>
+    where
+        Self: Sized,
+    {
+        HirDisplayWrapper {
+            db,
+            t: self,
+            max_size: None,
+            max_depth,
+            omit_verbose_types: false,
+            display_target: DisplayTarget::Diagnostics,
+        }
+    }
+
     /// Returns a String representation of `self` that can be inserted into the given module.
     /// Use this when generating code (e.g. assists)
     fn display_source_code<'a>(
@@ -113,6 +145,8 @@ pub trait HirDisplay {
             buf: String::with_capacity(20),
             curr_size: 0,
             max_size: None,
+            curr_depth: 0,
+            max_depth: None,
             omit_verbose_types: false,
             display_target: DisplayTarget::SourceCode { module_id },
         }) {
@@ -132,6 +166,7 @@ pub trait HirDisplay {
             db,
             t: self,
             max_size: None,
+            max_depth: None,
             omit_verbose_types: false,
             display_target: DisplayTarget::Test,
         }
@@ -174,6 +209,13 @@ impl<'a> HirFormatter<'a> {
         }
     }
 
+    pub fn max_depth_reached(&self) -> bool {
+        match self.max_depth {
+            Some(max_depth) => self.curr_depth >= max_depth,
+            None => false,
+        }
+    }
+
     pub fn omit_verbose_types(&self) -> bool {
         self.omit_verbose_types
     }
@@ -224,6 +266,7 @@ pub struct HirDisplayWrapper<'a, T> {
     db: &'a dyn HirDatabase,
     t: &'a T,
     max_size: Option<usize>,
+    max_depth: Option<usize>,
     omit_verbose_types: bool,
     display_target: DisplayTarget,
 }
@@ -239,6 +282,8 @@ where
             buf: String::with_capacity(20),
             curr_size: 0,
             max_size: self.max_size,
+            curr_depth: 0,
+            max_depth: self.max_depth,
             omit_verbose_types: self.omit_verbose_types,
             display_target: self.display_target,
         }) {
@@ -272,16 +317,24 @@ impl HirDisplay for ProjectionTy {
             return write!(f, "{}", TYPE_HINT_TRUNCATION);
         }
 
-        let trait_ = f.db.trait_data(self.trait_(f.db));
+        let trait_ref = self.trait_ref(f.db);
+        let trait_ = f.db.trait_data(trait_ref.hir_trait_id());
         write!(f, "<")?;
-        self.self_type_parameter(&Interner).hir_fmt(f)?;
+        trait_ref.self_type_parameter(&Interner).hir_fmt(f)?;
         write!(f, " as {}", trait_.name)?;
-        if self.substitution.len(&Interner) > 1 {
+        if trait_ref.substitution.len(&Interner) > 1 {
             write!(f, "<")?;
-            f.write_joined(&self.substitution.as_slice(&Interner)[1..], ", ")?;
+            f.write_joined(&trait_ref.substitution.as_slice(&Interner)[1..], ", ")?;
             write!(f, ">")?;
         }
         write!(f, ">::{}", f.db.type_alias_data(from_assoc_type_id(self.associated_ty_id)).name)?;
+        let own_param_count =
+            self.substitution.len(&Interner) - trait_ref.substitution.len(&Interner);
+        if own_param_count > 0 {
+            write!(f, "<")?;
+            f.write_joined(&self.substitution.as_slice(&Interner)[..own_param_count], ", ")?;
+            write!(f, ">")?;
+        }
         Ok(())
     }
 }
@@ -331,411 +384,410 @@ impl HirDisplay for BoundVar {
 
 impl HirDisplay for Ty {
     fn hir_fmt(&self, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
-        if f.should_truncate() {
+        if f.should_truncate() || f.max_depth_reached() {
             return write!(f, "{}", TYPE_HINT_TRUNCATION);
         }
 
-        match self.kind(&Interner) {
-            TyKind::Never => write!(f, "!")?,
-            TyKind::Str => write!(f, "str")?,
-            TyKind::Scalar(Scalar::Bool) => write!(f, "bool")?,
-            TyKind::Scalar(Scalar::Char) => write!(f, "char")?,
-            &TyKind::Scalar(Scalar::Float(t)) => write!(f, "{}", primitive::float_ty_to_string(t))?,
-            &TyKind::Scalar(Scalar::Int(t)) => write!(f, "{}", primitive::int_ty_to_string(t))?,
-            &TyKind::Scalar(Scalar::Uint(t)) => write!(f, "{}", primitive::uint_ty_to_string(t))?,
-            TyKind::Slice(t) => {
-                write!(f, "[")?;
-                t.hir_fmt(f)?;
-                write!(f, "]")?;
-            }
-            TyKind::Array(t, c) => {
-                write!(f, "[")?;
-                t.hir_fmt(f)?;
-                write!(f, "; ")?;
-                c.hir_fmt(f)?;
-                write!(f, "]")?;
+        f.curr_depth += 1;
+        let res = fmt_ty(self, f);
+        f.curr_depth -= 1;
+        res
+    }
+}
+
+fn fmt_ty(this: &Ty, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
+    match this.kind(&Interner) {
+        TyKind::Never => write!(f, "!")?,
+        TyKind::Str => write!(f, "str")?,
+        TyKind::Scalar(Scalar::Bool) => write!(f, "bool")?,
+        TyKind::Scalar(Scalar::Char) => write!(f, "char")?,
+        &TyKind::Scalar(Scalar::Float(t)) => write!(f, "{}", primitive::float_ty_to_string(t))?,
+        &TyKind::Scalar(Scalar::Int(t)) => write!(f, "{}", primitive::int_ty_to_string(t))?,
+        &TyKind::Scalar(Scalar::Uint(t)) => write!(f, "{}", primitive::uint_ty_to_string(t))?,
+        TyKind::Slice(t) => {
+            write!(f, "[")?;
+            t.hir_fmt(f)?;
+            write!(f, "]")?;
+        }
+        TyKind::Array(t, c) => {
+            write!(f, "[")?;
+            t.hir_fmt(f)?;
+            write!(f, "; ")?;
+            c.hir_fmt(f)?;
+            write!(f, "]")?;
+        }
+        TyKind::Raw(m, t) | TyKind::Ref(m, _, t) => {
+            if matches!(this.kind(&Interner), TyKind::Raw(..)) {
+                write!(
+                    f,
+                    "*{}",
+                    match m {
+                        Mutability::Not => "const ",
+                        Mutability::Mut => "mut ",
+                    }
+                )?;
+            } else {
+                write!(
+                    f,
+                    "&{}",
+                    match m {
+                        Mutability::Not => "",
+                        Mutability::Mut => "mut ",
+                    }
+                )?;
             }
-            TyKind::Raw(m, t) | TyKind::Ref(m, _, t) => {
-                if matches!(self.kind(&Interner), TyKind::Raw(..)) {
-                    write!(
-                        f,
-                        "*{}",
-                        match m {
-                            Mutability::Not => "const ",
-                            Mutability::Mut => "mut ",
-                        }
-                    )?;
-                } else {
-                    write!(
-                        f,
-                        "&{}",
-                        match m {
-                            Mutability::Not => "",
-                            Mutability::Mut => "mut ",
-                        }
-                    )?;
-                }
 
-                // FIXME: all this just to decide whether to use parentheses...
-                let contains_impl_fn = |bounds: &[QuantifiedWhereClause]| {
-                    bounds.iter().any(|bound| {
-                        if let WhereClause::Implemented(trait_ref) = bound.skip_binders() {
-                            let trait_ = trait_ref.hir_trait_id();
-                            fn_traits(f.db.upcast(), trait_).any(|it| it == trait_)
-                        } else {
-                            false
-                        }
-                    })
-                };
-                let (preds_to_print, has_impl_fn_pred) = match t.kind(&Interner) {
-                    TyKind::Dyn(dyn_ty) if dyn_ty.bounds.skip_binders().interned().len() > 1 => {
-                        let bounds = dyn_ty.bounds.skip_binders().interned();
-                        (bounds.len(), contains_impl_fn(bounds))
+            // FIXME: all this just to decide whether to use parentheses...
+            let contains_impl_fn = |bounds: &[QuantifiedWhereClause]| {
+                bounds.iter().any(|bound| {
+                    if let WhereClause::Implemented(trait_ref) = bound.skip_binders() {
+                        let trait_ = trait_ref.hir_trait_id();
+                        fn_traits(f.db.upcast(), trait_).any(|it| it == trait_)
+                    } else {
+                        false
                     }
-                    TyKind::Alias(AliasTy::Opaque(OpaqueTy {
-                        opaque_ty_id,
-                        substitution: parameters,
-                    }))
-                    | TyKind::OpaqueType(opaque_ty_id, parameters) => {
-                        let impl_trait_id =
-                            f.db.lookup_intern_impl_trait_id((*opaque_ty_id).into());
-                        if let ImplTraitId::ReturnTypeImplTrait(func, idx) = impl_trait_id {
-                            let datas =
-                                f.db.return_type_impl_traits(func)
-                                    .expect("impl trait id without data");
-                            let data = (*datas)
-                                .as_ref()
-                                .map(|rpit| rpit.impl_traits[idx as usize].bounds.clone());
-                            let bounds = data.substitute(&Interner, parameters);
-                            let mut len = bounds.skip_binders().len();
-
-                            // Don't count Sized but count when it absent
-                            // (i.e. when explicit ?Sized bound is set).
-                            let default_sized = SizedByDefault::Sized {
-                                anchor: func.lookup(f.db.upcast()).module(f.db.upcast()).krate(),
-                            };
-                            let sized_bounds = bounds
-                                .skip_binders()
-                                .iter()
-                                .filter(|b| {
-                                    matches!(
-                                        b.skip_binders(),
-                                        WhereClause::Implemented(trait_ref)
-                                            if default_sized.is_sized_trait(
-                                                trait_ref.hir_trait_id(),
-                                                f.db.upcast(),
-                                            ),
-                                    )
-                                })
-                                .count();
-                            match sized_bounds {
-                                0 => len += 1,
-                                _ => {
-                                    len = len.saturating_sub(sized_bounds);
-                                }
-                            }
+                })
+            };
+            let (preds_to_print, has_impl_fn_pred) = match t.kind(&Interner) {
+                TyKind::Dyn(dyn_ty) if dyn_ty.bounds.skip_binders().interned().len() > 1 => {
+                    let bounds = dyn_ty.bounds.skip_binders().interned();
+                    (bounds.len(), contains_impl_fn(bounds))
+                }
+                TyKind::Alias(AliasTy::Opaque(OpaqueTy {
+                    opaque_ty_id,
+                    substitution: parameters,
+                }))
+                | TyKind::OpaqueType(opaque_ty_id, parameters) => {
+                    let impl_trait_id = f.db.lookup_intern_impl_trait_id((*opaque_ty_id).into());
+                    if let ImplTraitId::ReturnTypeImplTrait(func, idx) = impl_trait_id {
+                        let datas =
+                            f.db.return_type_impl_traits(func).expect("impl trait id without data");
+                        let data = (*datas)
+                            .as_ref()
+                            .map(|rpit| rpit.impl_traits[idx as usize].bounds.clone());
+                        let bounds = data.substitute(&Interner, parameters);
+                        let mut len = bounds.skip_binders().len();
 
-                            (len, contains_impl_fn(bounds.skip_binders()))
-                        } else {
-                            (0, false)
+                        // Don't count Sized but count when it absent
+                        // (i.e. when explicit ?Sized bound is set).
+                        let default_sized = SizedByDefault::Sized {
+                            anchor: func.lookup(f.db.upcast()).module(f.db.upcast()).krate(),
+                        };
+                        let sized_bounds = bounds
+                            .skip_binders()
+                            .iter()
+                            .filter(|b| {
+                                matches!(
+                                    b.skip_binders(),
+                                    WhereClause::Implemented(trait_ref)
+                                        if default_sized.is_sized_trait(
+                                            trait_ref.hir_trait_id(),
+                                            f.db.upcast(),
+                                        ),
+                                )
+                            })
+                            .count();
+                        match sized_bounds {
+                            0 => len += 1,
+                            _ => {
+                                len = len.saturating_sub(sized_bounds);
+                            }
                         }
-                    }
-                    _ => (0, false),
-                };
 
-                if has_impl_fn_pred && preds_to_print <= 2 {
-                    return t.hir_fmt(f);
+                        (len, contains_impl_fn(bounds.skip_binders()))
+                    } else {
+                        (0, false)
+                    }
                 }
+                _ => (0, false),
+            };
 
-                if preds_to_print > 1 {
-                    write!(f, "(")?;
-                    t.hir_fmt(f)?;
-                    write!(f, ")")?;
-                } else {
-                    t.hir_fmt(f)?;
-                }
-            }
-            TyKind::Tuple(_, substs) => {
-                if substs.len(&Interner) == 1 {
-                    write!(f, "(")?;
-                    substs.at(&Interner, 0).hir_fmt(f)?;
-                    write!(f, ",)")?;
-                } else {
-                    write!(f, "(")?;
-                    f.write_joined(&*substs.as_slice(&Interner), ", ")?;
-                    write!(f, ")")?;
-                }
+            if has_impl_fn_pred && preds_to_print <= 2 {
+                return t.hir_fmt(f);
             }
-            TyKind::Function(fn_ptr) => {
-                let sig = CallableSig::from_fn_ptr(fn_ptr);
-                sig.hir_fmt(f)?;
+
+            if preds_to_print > 1 {
+                write!(f, "(")?;
+                t.hir_fmt(f)?;
+                write!(f, ")")?;
+            } else {
+                t.hir_fmt(f)?;
             }
-            TyKind::FnDef(def, parameters) => {
-                let def = from_chalk(f.db, *def);
-                let sig = f.db.callable_item_signature(def).substitute(&Interner, parameters);
-                match def {
-                    CallableDefId::FunctionId(ff) => {
-                        write!(f, "fn {}", f.db.function_data(ff).name)?
-                    }
-                    CallableDefId::StructId(s) => write!(f, "{}", f.db.struct_data(s).name)?,
-                    CallableDefId::EnumVariantId(e) => {
-                        write!(f, "{}", f.db.enum_data(e.parent).variants[e.local_id].name)?
-                    }
-                };
-                if parameters.len(&Interner) > 0 {
-                    let generics = generics(f.db.upcast(), def.into());
-                    let (parent_params, self_param, type_params, _impl_trait_params) =
-                        generics.provenance_split();
-                    let total_len = parent_params + self_param + type_params;
-                    // We print all params except implicit impl Trait params. Still a bit weird; should we leave out parent and self?
-                    if total_len > 0 {
-                        write!(f, "<")?;
-                        f.write_joined(&parameters.as_slice(&Interner)[..total_len], ", ")?;
-                        write!(f, ">")?;
-                    }
-                }
+        }
+        TyKind::Tuple(_, substs) => {
+            if substs.len(&Interner) == 1 {
                 write!(f, "(")?;
-                f.write_joined(sig.params(), ", ")?;
+                substs.at(&Interner, 0).hir_fmt(f)?;
+                write!(f, ",)")?;
+            } else {
+                write!(f, "(")?;
+                f.write_joined(&*substs.as_slice(&Interner), ", ")?;
                 write!(f, ")")?;
-                let ret = sig.ret();
-                if !ret.is_unit() {
-                    write!(f, " -> ")?;
-                    ret.hir_fmt(f)?;
+            }
+        }
+        TyKind::Function(fn_ptr) => {
+            let sig = CallableSig::from_fn_ptr(fn_ptr);
+            sig.hir_fmt(f)?;
+        }
+        TyKind::FnDef(def, parameters) => {
+            let def = from_chalk(f.db, *def);
+            let sig = f.db.callable_item_signature(def).substitute(&Interner, parameters);
+            match def {
+                CallableDefId::FunctionId(ff) => write!(f, "fn {}", f.db.function_data(ff).name)?,
+                CallableDefId::StructId(s) => write!(f, "{}", f.db.struct_data(s).name)?,
+                CallableDefId::EnumVariantId(e) => {
+                    write!(f, "{}", f.db.enum_data(e.parent).variants[e.local_id].name)?
+                }
+            };
+            if parameters.len(&Interner) > 0 {
+                let generics = generics(f.db.upcast(), def.into());
+                let (parent_params, self_param, type_params, _impl_trait_params) =
+                    generics.provenance_split();
+                let total_len = parent_params + self_param + type_params;
+                // We print all params except implicit impl Trait params. Still a bit weird; should we leave out parent and self?
+                if total_len > 0 {
+                    write!(f, "<")?;
+                    f.write_joined(&parameters.as_slice(&Interner)[..total_len], ", ")?;
+                    write!(f, ">")?;
                 }
             }
-            TyKind::Adt(AdtId(def_id), parameters) => {
-                match f.display_target {
-                    DisplayTarget::Diagnostics | DisplayTarget::Test => {
-                        let name = match *def_id {
-                            hir_def::AdtId::StructId(it) => f.db.struct_data(it).name.clone(),
-                            hir_def::AdtId::UnionId(it) => f.db.union_data(it).name.clone(),
-                            hir_def::AdtId::EnumId(it) => f.db.enum_data(it).name.clone(),
-                        };
-                        write!(f, "{}", name)?;
-                    }
-                    DisplayTarget::SourceCode { module_id } => {
-                        if let Some(path) = find_path::find_path(
-                            f.db.upcast(),
-                            ItemInNs::Types((*def_id).into()),
-                            module_id,
-                        ) {
-                            write!(f, "{}", path)?;
-                        } else {
-                            return Err(HirDisplayError::DisplaySourceCodeError(
-                                DisplaySourceCodeError::PathNotFound,
-                            ));
-                        }
+            write!(f, "(")?;
+            f.write_joined(sig.params(), ", ")?;
+            write!(f, ")")?;
+            let ret = sig.ret();
+            if !ret.is_unit() {
+                write!(f, " -> ")?;
+                ret.hir_fmt(f)?;
+            }
+        }
+        TyKind::Adt(AdtId(def_id), parameters) => {
+            match f.display_target {
+                DisplayTarget::Diagnostics | DisplayTarget::Test => {
+                    let name = match *def_id {
+                        hir_def::AdtId::StructId(it) => f.db.struct_data(it).name.clone(),
+                        hir_def::AdtId::UnionId(it) => f.db.union_data(it).name.clone(),
+                        hir_def::AdtId::EnumId(it) => f.db.enum_data(it).name.clone(),
+                    };
+                    write!(f, "{}", name)?;
+                }
+                DisplayTarget::SourceCode { module_id } => {
+                    if let Some(path) = find_path::find_path(
+                        f.db.upcast(),
+                        ItemInNs::Types((*def_id).into()),
+                        module_id,
+                        find_path::FindPathConfig::default(),
+                    ) {
+                        write!(f, "{}", path)?;
+                    } else {
+                        return Err(HirDisplayError::DisplaySourceCodeError(
+                            DisplaySourceCodeError::PathNotFound,
+                        ));
                     }
                 }
+            }
 
-                if parameters.len(&Interner) > 0 {
-                    let parameters_to_write = if f.display_target.is_source_code()
-                        || f.omit_verbose_types()
+            if parameters.len(&Interner) > 0 {
+                let parameters_to_write = if f.display_target.is_source_code()
+                    || f.omit_verbose_types()
+                {
+                    match this
+                        .as_generic_def(f.db)
+                        .map(|generic_def_id| f.db.generic_defaults(generic_def_id))
+                        .filter(|defaults| !defaults.is_empty())
                     {
-                        match self
-                            .as_generic_def(f.db)
-                            .map(|generic_def_id| f.db.generic_defaults(generic_def_id))
-                            .filter(|defaults| !defaults.is_empty())
-                        {
-                            None => parameters.as_slice(&Interner),
-                            Some(default_parameters) => {
-                                let mut default_from = 0;
-                                for (i, parameter) in parameters.iter(&Interner).enumerate() {
-                                    match (
-                                        parameter.assert_ty_ref(&Interner).kind(&Interner),
-                                        default_parameters.get(i),
-                                    ) {
-                                        (&TyKind::Error, _) | (_, None) => {
+                        None => parameters.as_slice(&Interner),
+                        Some(default_parameters) => {
+                            let mut default_from = 0;
+                            for (i, parameter) in parameters.iter(&Interner).enumerate() {
+                                match (
+                                    parameter.assert_ty_ref(&Interner).kind(&Interner),
+                                    default_parameters.get(i),
+                                ) {
+                                    (&TyKind::Error, _) | (_, None) => {
+                                        default_from = i + 1;
+                                    }
+                                    (_, Some(default_parameter)) => {
+                                        let actual_default = default_parameter
+                                            .clone()
+                                            .substitute(&Interner, &subst_prefix(parameters, i));
+                                        if parameter.assert_ty_ref(&Interner) != &actual_default {
                                             default_from = i + 1;
                                         }
-                                        (_, Some(default_parameter)) => {
-                                            let actual_default =
-                                                default_parameter.clone().substitute(
-                                                    &Interner,
-                                                    &subst_prefix(parameters, i),
-                                                );
-                                            if parameter.assert_ty_ref(&Interner) != &actual_default
-                                            {
-                                                default_from = i + 1;
-                                            }
-                                        }
                                     }
                                 }
-                                &parameters.as_slice(&Interner)[0..default_from]
                             }
+                            &parameters.as_slice(&Interner)[0..default_from]
                         }
-                    } else {
-                        parameters.as_slice(&Interner)
-                    };
-                    if !parameters_to_write.is_empty() {
-                        write!(f, "<")?;
-                        f.write_joined(parameters_to_write, ", ")?;
-                        write!(f, ">")?;
                     }
+                } else {
+                    parameters.as_slice(&Interner)
+                };
+                if !parameters_to_write.is_empty() {
+                    write!(f, "<")?;
+                    f.write_joined(parameters_to_write, ", ")?;
+                    write!(f, ">")?;
                 }
             }
-            TyKind::AssociatedType(assoc_type_id, parameters) => {
-                let type_alias = from_assoc_type_id(*assoc_type_id);
-                let trait_ = match type_alias.lookup(f.db.upcast()).container {
-                    AssocContainerId::TraitId(it) => it,
-                    _ => panic!("not an associated type"),
+        }
+        TyKind::AssociatedType(assoc_type_id, parameters) => {
+            let type_alias = from_assoc_type_id(*assoc_type_id);
+            let trait_ = match type_alias.lookup(f.db.upcast()).container {
+                AssocContainerId::TraitId(it) => it,
+                _ => panic!("not an associated type"),
+            };
+            let trait_ = f.db.trait_data(trait_);
+            let type_alias_data = f.db.type_alias_data(type_alias);
+
+            // Use placeholder associated types when the target is test (https://rust-lang.github.io/chalk/book/clauses/type_equality.html#placeholder-associated-types)
+            if f.display_target.is_test() {
+                write!(f, "{}::{}", trait_.name, type_alias_data.name)?;
+                if parameters.len(&Interner) > 0 {
+                    write!(f, "<")?;
+                    f.write_joined(&*parameters.as_slice(&Interner), ", ")?;
+                    write!(f, ">")?;
+                }
+            } else {
+                let projection_ty = ProjectionTy {
+                    associated_ty_id: to_assoc_type_id(type_alias),
+                    substitution: parameters.clone(),
                 };
-                let trait_ = f.db.trait_data(trait_);
-                let type_alias_data = f.db.type_alias_data(type_alias);
 
-                // Use placeholder associated types when the target is test (https://rust-lang.github.io/chalk/book/clauses/type_equality.html#placeholder-associated-types)
-                if f.display_target.is_test() {
-                    write!(f, "{}::{}", trait_.name, type_alias_data.name)?;
-                    if parameters.len(&Interner) > 0 {
-                        write!(f, "<")?;
-                        f.write_joined(&*parameters.as_slice(&Interner), ", ")?;
-                        write!(f, ">")?;
-                    }
-                } else {
-                    let projection_ty = ProjectionTy {
-                        associated_ty_id: to_assoc_type_id(type_alias),
-                        substitution: parameters.clone(),
-                    };
-
-                    projection_ty.hir_fmt(f)?;
+                projection_ty.hir_fmt(f)?;
+            }
+        }
+        TyKind::Foreign(type_alias) => {
+            let type_alias = f.db.type_alias_data(from_foreign_def_id(*type_alias));
+            write!(f, "{}", type_alias.name)?;
+        }
+        TyKind::OpaqueType(opaque_ty_id, parameters) => {
+            let impl_trait_id = f.db.lookup_intern_impl_trait_id((*opaque_ty_id).into());
+            match impl_trait_id {
+                ImplTraitId::ReturnTypeImplTrait(func, idx) => {
+                    let datas =
+                        f.db.return_type_impl_traits(func).expect("impl trait id without data");
+                    let data =
+                        (*datas).as_ref().map(|rpit| rpit.impl_traits[idx as usize].bounds.clone());
+                    let bounds = data.substitute(&Interner, &parameters);
+                    let krate = func.lookup(f.db.upcast()).module(f.db.upcast()).krate();
+                    write_bounds_like_dyn_trait_with_prefix(
+                        "impl",
+                        bounds.skip_binders(),
+                        SizedByDefault::Sized { anchor: krate },
+                        f,
+                    )?;
+                    // FIXME: it would maybe be good to distinguish this from the alias type (when debug printing), and to show the substitution
+                }
+                ImplTraitId::AsyncBlockTypeImplTrait(..) => {
+                    write!(f, "impl Future<Output = ")?;
+                    parameters.at(&Interner, 0).hir_fmt(f)?;
+                    write!(f, ">")?;
                 }
             }
-            TyKind::Foreign(type_alias) => {
-                let type_alias = f.db.type_alias_data(from_foreign_def_id(*type_alias));
-                write!(f, "{}", type_alias.name)?;
+        }
+        TyKind::Closure(.., substs) => {
+            if f.display_target.is_source_code() {
+                return Err(HirDisplayError::DisplaySourceCodeError(
+                    DisplaySourceCodeError::Closure,
+                ));
             }
-            TyKind::OpaqueType(opaque_ty_id, parameters) => {
-                let impl_trait_id = f.db.lookup_intern_impl_trait_id((*opaque_ty_id).into());
-                match impl_trait_id {
-                    ImplTraitId::ReturnTypeImplTrait(func, idx) => {
-                        let datas =
-                            f.db.return_type_impl_traits(func).expect("impl trait id without data");
-                        let data = (*datas)
-                            .as_ref()
-                            .map(|rpit| rpit.impl_traits[idx as usize].bounds.clone());
-                        let bounds = data.substitute(&Interner, &parameters);
-                        let krate = func.lookup(f.db.upcast()).module(f.db.upcast()).krate();
-                        write_bounds_like_dyn_trait_with_prefix(
-                            "impl",
-                            bounds.skip_binders(),
-                            SizedByDefault::Sized { anchor: krate },
-                            f,
-                        )?;
-                        // FIXME: it would maybe be good to distinguish this from the alias type (when debug printing), and to show the substitution
-                    }
-                    ImplTraitId::AsyncBlockTypeImplTrait(..) => {
-                        write!(f, "impl Future<Output = ")?;
-                        parameters.at(&Interner, 0).hir_fmt(f)?;
-                        write!(f, ">")?;
-                    }
-                }
+            let sig = substs.at(&Interner, 0).assert_ty_ref(&Interner).callable_sig(f.db);
+            if let Some(sig) = sig {
+                if sig.params().is_empty() {
+                    write!(f, "||")?;
+                } else if f.omit_verbose_types() {
+                    write!(f, "|{}|", TYPE_HINT_TRUNCATION)?;
+                } else {
+                    write!(f, "|")?;
+                    f.write_joined(sig.params(), ", ")?;
+                    write!(f, "|")?;
+                };
+
+                write!(f, " -> ")?;
+                sig.ret().hir_fmt(f)?;
+            } else {
+                write!(f, "{{closure}}")?;
             }
-            TyKind::Closure(.., substs) => {
-                if f.display_target.is_source_code() {
-                    return Err(HirDisplayError::DisplaySourceCodeError(
-                        DisplaySourceCodeError::Closure,
-                    ));
+        }
+        TyKind::Placeholder(idx) => {
+            let id = from_placeholder_idx(f.db, *idx);
+            let generics = generics(f.db.upcast(), id.parent);
+            let param_data = &generics.params.types[id.local_id];
+            match param_data.provenance {
+                TypeParamProvenance::TypeParamList | TypeParamProvenance::TraitSelf => {
+                    write!(f, "{}", param_data.name.clone().unwrap_or_else(Name::missing))?
                 }
-                let sig = substs.at(&Interner, 0).assert_ty_ref(&Interner).callable_sig(f.db);
-                if let Some(sig) = sig {
-                    if sig.params().is_empty() {
-                        write!(f, "||")?;
-                    } else if f.omit_verbose_types() {
-                        write!(f, "|{}|", TYPE_HINT_TRUNCATION)?;
-                    } else {
-                        write!(f, "|")?;
-                        f.write_joined(sig.params(), ", ")?;
-                        write!(f, "|")?;
-                    };
-
-                    write!(f, " -> ")?;
-                    sig.ret().hir_fmt(f)?;
-                } else {
-                    write!(f, "{{closure}}")?;
+                TypeParamProvenance::ArgumentImplTrait => {
+                    let substs = generics.type_params_subst(f.db);
+                    let bounds =
+                        f.db.generic_predicates(id.parent)
+                            .into_iter()
+                            .map(|pred| pred.clone().substitute(&Interner, &substs))
+                            .filter(|wc| match &wc.skip_binders() {
+                                WhereClause::Implemented(tr) => {
+                                    &tr.self_type_parameter(&Interner) == this
+                                }
+                                WhereClause::AliasEq(AliasEq {
+                                    alias: AliasTy::Projection(proj),
+                                    ty: _,
+                                }) => &proj.trait_ref(f.db).self_type_parameter(&Interner) == this,
+                                _ => false,
+                            })
+                            .collect::<Vec<_>>();
+                    let krate = id.parent.module(f.db.upcast()).krate();
+                    write_bounds_like_dyn_trait_with_prefix(
+                        "impl",
+                        &bounds,
+                        SizedByDefault::Sized { anchor: krate },
+                        f,
+                    )?;
                 }
             }
-            TyKind::Placeholder(idx) => {
-                let id = from_placeholder_idx(f.db, *idx);
-                let generics = generics(f.db.upcast(), id.parent);
-                let param_data = &generics.params.types[id.local_id];
-                match param_data.provenance {
-                    TypeParamProvenance::TypeParamList | TypeParamProvenance::TraitSelf => {
-                        write!(f, "{}", param_data.name.clone().unwrap_or_else(Name::missing))?
-                    }
-                    TypeParamProvenance::ArgumentImplTrait => {
-                        let substs = generics.type_params_subst(f.db);
-                        let bounds =
-                            f.db.generic_predicates(id.parent)
-                                .into_iter()
-                                .map(|pred| pred.clone().substitute(&Interner, &substs))
-                                .filter(|wc| match &wc.skip_binders() {
-                                    WhereClause::Implemented(tr) => {
-                                        &tr.self_type_parameter(&Interner) == self
-                                    }
-                                    WhereClause::AliasEq(AliasEq {
-                                        alias: AliasTy::Projection(proj),
-                                        ty: _,
-                                    }) => &proj.self_type_parameter(&Interner) == self,
-                                    _ => false,
-                                })
-                                .collect::<Vec<_>>();
-                        let krate = id.parent.module(f.db.upcast()).krate();
-                        write_bounds_like_dyn_trait_with_prefix(
-                            "impl",
-                            &bounds,
-                            SizedByDefault::Sized { anchor: krate },
-                            f,
-                        )?;
-                    }
+        }
+        TyKind::BoundVar(idx) => idx.hir_fmt(f)?,
+        TyKind::Dyn(dyn_ty) => {
+            write_bounds_like_dyn_trait_with_prefix(
+                "dyn",
+                dyn_ty.bounds.skip_binders().interned(),
+                SizedByDefault::NotSized,
+                f,
+            )?;
+        }
+        TyKind::Alias(AliasTy::Projection(p_ty)) => p_ty.hir_fmt(f)?,
+        TyKind::Alias(AliasTy::Opaque(opaque_ty)) => {
+            let impl_trait_id = f.db.lookup_intern_impl_trait_id(opaque_ty.opaque_ty_id.into());
+            match impl_trait_id {
+                ImplTraitId::ReturnTypeImplTrait(func, idx) => {
+                    let datas =
+                        f.db.return_type_impl_traits(func).expect("impl trait id without data");
+                    let data =
+                        (*datas).as_ref().map(|rpit| rpit.impl_traits[idx as usize].bounds.clone());
+                    let bounds = data.substitute(&Interner, &opaque_ty.substitution);
+                    let krate = func.lookup(f.db.upcast()).module(f.db.upcast()).krate();
+                    write_bounds_like_dyn_trait_with_prefix(
+                        "impl",
+                        bounds.skip_binders(),
+                        SizedByDefault::Sized { anchor: krate },
+                        f,
+                    )?;
                 }
-            }
-            TyKind::BoundVar(idx) => idx.hir_fmt(f)?,
-            TyKind::Dyn(dyn_ty) => {
-                write_bounds_like_dyn_trait_with_prefix(
-                    "dyn",
-                    dyn_ty.bounds.skip_binders().interned(),
-                    SizedByDefault::NotSized,
-                    f,
-                )?;
-            }
-            TyKind::Alias(AliasTy::Projection(p_ty)) => p_ty.hir_fmt(f)?,
-            TyKind::Alias(AliasTy::Opaque(opaque_ty)) => {
-                let impl_trait_id = f.db.lookup_intern_impl_trait_id(opaque_ty.opaque_ty_id.into());
-                match impl_trait_id {
-                    ImplTraitId::ReturnTypeImplTrait(func, idx) => {
-                        let datas =
-                            f.db.return_type_impl_traits(func).expect("impl trait id without data");
-                        let data = (*datas)
-                            .as_ref()
-                            .map(|rpit| rpit.impl_traits[idx as usize].bounds.clone());
-                        let bounds = data.substitute(&Interner, &opaque_ty.substitution);
-                        let krate = func.lookup(f.db.upcast()).module(f.db.upcast()).krate();
-                        write_bounds_like_dyn_trait_with_prefix(
-                            "impl",
-                            bounds.skip_binders(),
-                            SizedByDefault::Sized { anchor: krate },
-                            f,
-                        )?;
-                    }
-                    ImplTraitId::AsyncBlockTypeImplTrait(..) => {
-                        write!(f, "{{async block}}")?;
-                    }
-                };
-            }
-            TyKind::Error => {
-                if f.display_target.is_source_code() {
-                    return Err(HirDisplayError::DisplaySourceCodeError(
-                        DisplaySourceCodeError::UnknownType,
-                    ));
+                ImplTraitId::AsyncBlockTypeImplTrait(..) => {
+                    write!(f, "{{async block}}")?;
                 }
-                write!(f, "{{unknown}}")?;
+            };
+        }
+        TyKind::Error => {
+            if f.display_target.is_source_code() {
+                return Err(HirDisplayError::DisplaySourceCodeError(
+                    DisplaySourceCodeError::UnknownType,
+                ));
             }
-            TyKind::InferenceVar(..) => write!(f, "_")?,
-            TyKind::Generator(..) => write!(f, "{{generator}}")?,
-            TyKind::GeneratorWitness(..) => write!(f, "{{generator witness}}")?,
+            write!(f, "{{unknown}}")?;
         }
-        Ok(())
+        TyKind::InferenceVar(..) => write!(f, "_")?,
+        TyKind::Generator(..) => write!(f, "{{generator}}")?,
+        TyKind::GeneratorWitness(..) => write!(f, "{{generator witness}}")?,
     }
+    Ok(())
 }
 
 impl HirDisplay for CallableSig {
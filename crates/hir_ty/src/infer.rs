@@ -18,12 +18,12 @@ use std::sync::Arc;
 
 use chalk_ir::{cast::Cast, DebruijnIndex, Mutability, Safety};
 use hir_def::{
-    body::Body,
+    body::{scope::ExprScopes, Body},
     data::{ConstData, FunctionData, StaticData},
     expr::{ArithOp, BinaryOp, BindingAnnotation, ExprId, PatId},
     lang_item::LangItemTarget,
     path::{path, Path},
-    resolver::{HasResolver, ResolveValueResult, Resolver, TypeNs, ValueNs},
+    resolver::{resolver_for_scope_in, HasResolver, ResolveValueResult, Resolver, TypeNs, ValueNs},
     type_ref::TypeRef,
     AdtId, AssocItemId, DefWithBodyId, EnumVariantId, FieldId, FunctionId, HasModule, Lookup,
     TraitId, TypeAliasId, VariantId,
@@ -57,8 +57,39 @@ mod closure;
 /// The entry point of type inference.
 pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceResult> {
     let _p = profile::span("infer_query");
+    let body = db.body(def);
+    let expr_scopes = db.expr_scopes(def);
+    Arc::new(infer_body(db, def, body, expr_scopes))
+}
+
+/// Inference can only depend on itself through const evaluation, e.g. in
+/// `const X: usize = [0; X].len();`, where the array length needs `X`.
+pub(crate) fn infer_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &DefWithBodyId,
+) -> Arc<InferenceResult> {
+    Arc::new(InferenceResult::default())
+}
+
+/// Infers the types in `body` as if it was the body of `def`, bypassing the `infer` query.
+///
+/// This is meant for speculatively lowered bodies and redoes all the work of the query, so
+/// it should only be used for one-off results.
+pub fn infer_speculative(db: &dyn HirDatabase, def: DefWithBodyId, body: Body) -> InferenceResult {
+    let _p = profile::span("infer_speculative");
+    let expr_scopes = Arc::new(ExprScopes::new(&body));
+    infer_body(db, def, Arc::new(body), expr_scopes)
+}
+
+fn infer_body(
+    db: &dyn HirDatabase,
+    def: DefWithBodyId,
+    body: Arc<Body>,
+    expr_scopes: Arc<ExprScopes>,
+) -> InferenceResult {
     let resolver = def.resolver(db.upcast());
-    let mut ctx = InferenceContext::new(db, def, resolver);
+    let mut ctx = InferenceContext::new(db, def, body, expr_scopes, resolver);
 
     match def {
         DefWithBodyId::ConstId(c) => ctx.collect_const(&db.const_data(c)),
@@ -68,7 +99,7 @@ pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<Infer
 
     ctx.infer_body();
 
-    Arc::new(ctx.resolve_all())
+    ctx.resolve_all()
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -81,7 +112,7 @@ impl_from!(ExprId, PatId for ExprOrPatId);
 /// Binding modes inferred for patterns.
 /// <https://doc.rust-lang.org/reference/patterns.html#binding-modes>
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum BindingMode {
+pub enum BindingMode {
     Move,
     Ref(Mutability),
 }
@@ -267,6 +298,8 @@ pub struct InferenceResult {
     standard_types: InternedStandardTypes,
     /// Stores the types which were implicitly dereferenced in pattern binding modes.
     pub pat_adjustments: FxHashMap<PatId, Vec<Adjustment>>,
+    /// For each binding pattern, records the binding mode it was inferred to have.
+    pub pat_binding_modes: FxHashMap<PatId, BindingMode>,
     pub expr_adjustments: FxHashMap<ExprId, Vec<Adjustment>>,
 }
 
@@ -331,6 +364,7 @@ struct InferenceContext<'a> {
     db: &'a dyn HirDatabase,
     owner: DefWithBodyId,
     body: Arc<Body>,
+    expr_scopes: Arc<ExprScopes>,
     resolver: Resolver,
     table: unify::InferenceTable<'a>,
     trait_env: Arc<TraitEnvironment>,
@@ -364,7 +398,13 @@ fn find_breakable<'c>(
 }
 
 impl<'a> InferenceContext<'a> {
-    fn new(db: &'a dyn HirDatabase, owner: DefWithBodyId, resolver: Resolver) -> Self {
+    fn new(
+        db: &'a dyn HirDatabase,
+        owner: DefWithBodyId,
+        body: Arc<Body>,
+        expr_scopes: Arc<ExprScopes>,
+        resolver: Resolver,
+    ) -> Self {
         let krate = owner.module(db.upcast()).krate();
         let trait_env = owner
             .as_generic_def_id()
@@ -376,7 +416,8 @@ impl<'a> InferenceContext<'a> {
             return_ty: TyKind::Error.intern(&Interner), // set in collect_fn_signature
             db,
             owner,
-            body: db.body(owner),
+            body,
+            expr_scopes,
             resolver,
             diverges: Diverges::Maybe,
             breakables: Vec::new(),
@@ -387,6 +428,11 @@ impl<'a> InferenceContext<'a> {
         self.result.standard_types.unknown.clone()
     }
 
+    fn resolver_for_expr(&self, expr: ExprId) -> Resolver {
+        let scope = self.expr_scopes.scope_for(expr);
+        resolver_for_scope_in(self.db.upcast(), self.owner, self.expr_scopes.clone(), scope)
+    }
+
     fn resolve_all(mut self) -> InferenceResult {
         // FIXME resolve obligations as well (use Guidance if necessary)
         self.table.resolve_obligations_as_possible();
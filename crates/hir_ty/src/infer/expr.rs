@@ -10,7 +10,6 @@ use chalk_ir::{cast::Cast, fold::Shift, Mutability, TyVariableKind};
 use hir_def::{
     expr::{Array, BinaryOp, Expr, ExprId, Literal, MatchGuard, Statement, UnaryOp},
     path::{GenericArg, GenericArgs},
-    resolver::resolver_for_expr,
     AssocContainerId, FieldId, Lookup,
 };
 use hir_expand::name::{name, Name};
@@ -158,10 +157,8 @@ impl<'a> InferenceContext<'a> {
                 coerce.complete()
             }
             Expr::Block { statements, tail, label, id: _ } => {
-                let old_resolver = mem::replace(
-                    &mut self.resolver,
-                    resolver_for_expr(self.db.upcast(), self.owner, tgt_expr),
-                );
+                let resolver = self.resolver_for_expr(tgt_expr);
+                let old_resolver = mem::replace(&mut self.resolver, resolver);
                 let ty = match label {
                     Some(_) => {
                         let break_ty = self.table.new_type_var();
@@ -393,7 +390,7 @@ impl<'a> InferenceContext<'a> {
             }
             Expr::Path(p) => {
                 // FIXME this could be more efficient...
-                let resolver = resolver_for_expr(self.db.upcast(), self.owner, tgt_expr);
+                let resolver = self.resolver_for_expr(tgt_expr);
                 self.infer_path(&resolver, p, tgt_expr.into()).unwrap_or_else(|| self.err_ty())
             }
             Expr::Continue { .. } => TyKind::Never.intern(&Interner),
@@ -795,8 +792,18 @@ impl<'a> InferenceContext<'a> {
                             ),
                         );
 
-                        let repeat_expr = &self.body.exprs[repeat];
-                        consteval::eval_usize(repeat_expr)
+                        consteval::eval_usize(
+                            repeat,
+                            &mut consteval::ConstEvalCtx {
+                                db: self.db,
+                                resolver: self.resolver_for_expr(repeat),
+                                body: &self.body,
+                                infer: None,
+                                default_int: Scalar::Uint(UintTy::Usize),
+                                local_data: Default::default(),
+                                call_depth: 0,
+                            },
+                        )
                     }
                 };
 
@@ -0,0 +1,322 @@
+//! Computes the memory layout (size, alignment and field offsets) of types.
+//!
+//! This mirrors what rustc does for the common cases, but is far from complete:
+//! generic ADTs, unsized types and anything involving niches are reported as
+//! [`LayoutError::Unknown`].
+
+use std::{cmp::Reverse, fmt};
+
+use base_db::CrateId;
+use chalk_ir::{IntTy, Scalar, UintTy};
+use either::Either;
+use hir_def::{
+    adt::{ReprData, ReprKind},
+    AdtId, EnumVariantId, HasModule, VariantId,
+};
+
+use crate::{consteval::ConstExt, db::HirDatabase, primitive, Interner, Ty, TyKind};
+
+// FIXME: this depends on the target's C ABI, 32 bits is assumed for now.
+const C_INT_SIZE: u64 = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub size: u64,
+    pub align: u64,
+    /// The offsets of the fields of a struct, union or tuple, in declaration
+    /// order. Empty for all other types, including enums.
+    pub field_offsets: Vec<u64>,
+}
+
+impl Layout {
+    fn scalar(size: u64) -> Layout {
+        Layout { size, align: size, field_offsets: Vec::new() }
+    }
+
+    fn zst() -> Layout {
+        Layout { size: 0, align: 1, field_offsets: Vec::new() }
+    }
+
+    /// A layout that fits both `self` and `other`, like a union of the two.
+    fn max_with(self, other: &Layout) -> Result<Layout, LayoutError> {
+        let align = self.align.max(other.align);
+        let size = align_to(self.size.max(other.size), align)?;
+        Ok(Layout { size, align, field_offsets: self.field_offsets })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The layout can't be computed, because the type is generic, unsized or
+    /// not supported yet.
+    Unknown,
+    SizeOverflow,
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::Unknown => f.write_str("unknown layout"),
+            LayoutError::SizeOverflow => f.write_str("size overflow"),
+        }
+    }
+}
+
+/// Computes the layout of `ty` for the target `krate` is compiled for.
+pub fn layout_of_ty(db: &dyn HirDatabase, ty: &Ty, krate: CrateId) -> Result<Layout, LayoutError> {
+    let ptr_size = pointer_size(db, krate);
+    Ok(match ty.kind(&Interner) {
+        TyKind::Scalar(scalar) => scalar_layout(*scalar, ptr_size),
+        TyKind::Tuple(_, substs) => {
+            let fields = substs
+                .iter(&Interner)
+                .map(|it| layout_of_ty(db, it.assert_ty_ref(&Interner), krate))
+                .collect::<Result<Vec<_>, _>>()?;
+            univariant(&fields, &StructRepr::default())?
+        }
+        TyKind::Array(elem, len) => {
+            let len = len.as_usize().ok_or(LayoutError::Unknown)?;
+            let elem = layout_of_ty(db, elem, krate)?;
+            let size = elem.size.checked_mul(len).ok_or(LayoutError::SizeOverflow)?;
+            Layout { size, align: elem.align, field_offsets: Vec::new() }
+        }
+        TyKind::Ref(_, _, pointee) | TyKind::Raw(_, pointee) => {
+            if is_fat_pointee(db, pointee)? {
+                Layout { size: 2 * ptr_size, align: ptr_size, field_offsets: Vec::new() }
+            } else {
+                Layout::scalar(ptr_size)
+            }
+        }
+        TyKind::Function(_) => Layout::scalar(ptr_size),
+        TyKind::FnDef(..) | TyKind::Never => Layout::zst(),
+        TyKind::Adt(crate::AdtId(def), subst) => {
+            if !subst.is_empty(&Interner) {
+                return Err(LayoutError::Unknown);
+            }
+            db.layout_of_adt(*def)?
+        }
+        _ => return Err(LayoutError::Unknown),
+    })
+}
+
+pub(crate) fn layout_of_adt_query(db: &dyn HirDatabase, def: AdtId) -> Result<Layout, LayoutError> {
+    let generics = db.generic_params(def.into());
+    if !generics.types.is_empty() || !generics.consts.is_empty() {
+        return Err(LayoutError::Unknown);
+    }
+    let krate = def.module(db.upcast()).krate();
+    match def {
+        AdtId::StructId(it) => {
+            let fields = field_layouts(db, it.into(), krate)?;
+            univariant(&fields, &StructRepr::new(db.struct_data(it).repr.as_ref()))
+        }
+        AdtId::UnionId(it) => {
+            let fields = field_layouts(db, it.into(), krate)?;
+            union_layout(&fields, &StructRepr::new(db.union_data(it).repr.as_ref()))
+        }
+        AdtId::EnumId(it) => {
+            let data = db.enum_data(it);
+            let repr = data.repr.as_ref().ok_or(LayoutError::Unknown)?;
+            let (tag, is_c) = match repr.kind {
+                ReprKind::C => (Layout::scalar(C_INT_SIZE), true),
+                ReprKind::BuiltinInt { builtin, is_c } => {
+                    let scalar = match builtin {
+                        Either::Left(it) => Scalar::Int(primitive::int_ty_from_builtin(it)),
+                        Either::Right(it) => Scalar::Uint(primitive::uint_ty_from_builtin(it)),
+                    };
+                    (scalar_layout(scalar, pointer_size(db, krate)), is_c)
+                }
+                // Without an explicit repr, rustc is free to use niches.
+                ReprKind::Transparent | ReprKind::Default => return Err(LayoutError::Unknown),
+            };
+            let variants = data
+                .variants
+                .iter()
+                .map(|(local_id, _)| {
+                    field_layouts(db, EnumVariantId { parent: it, local_id }.into(), krate)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let c_like = StructRepr { reorder: false, pack: None, align: None };
+            let mut layout = if is_c {
+                // `repr(C)`: a struct of the tag followed by a union of all variants
+                let variants = variants
+                    .iter()
+                    .map(|fields| univariant(fields, &c_like))
+                    .collect::<Result<Vec<_>, _>>()?;
+                univariant(&[tag, union_layout(&variants, &c_like)?], &c_like)?
+            } else {
+                // primitive repr: a union of structs that all start with the tag
+                let variants = variants
+                    .into_iter()
+                    .map(|fields| {
+                        let fields: Vec<_> = Some(tag.clone()).into_iter().chain(fields).collect();
+                        univariant(&fields, &c_like)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                // fieldless enums still need room for the tag
+                union_layout(&variants, &c_like)?.max_with(&tag)?
+            };
+            if let Some(align) = repr.align {
+                layout.align = layout.align.max(align);
+                layout.size = align_to(layout.size, layout.align)?;
+            }
+            layout.field_offsets.clear();
+            Ok(layout)
+        }
+    }
+}
+
+pub(crate) fn layout_of_adt_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &AdtId,
+) -> Result<Layout, LayoutError> {
+    // Infinitely sized types are an error, rustc rejects them.
+    Err(LayoutError::Unknown)
+}
+
+/// How the fields of a struct-like type are laid out.
+struct StructRepr {
+    /// Whether fields may be reordered to minimize padding, that is, whether
+    /// this is the default representation.
+    reorder: bool,
+    pack: Option<u64>,
+    align: Option<u64>,
+}
+
+impl Default for StructRepr {
+    fn default() -> Self {
+        StructRepr { reorder: true, pack: None, align: None }
+    }
+}
+
+impl StructRepr {
+    fn new(repr: Option<&ReprData>) -> StructRepr {
+        match repr {
+            Some(repr) => StructRepr {
+                reorder: repr.kind == ReprKind::Default,
+                pack: repr.packed,
+                align: repr.align,
+            },
+            None => StructRepr::default(),
+        }
+    }
+}
+
+fn field_layouts(
+    db: &dyn HirDatabase,
+    variant: VariantId,
+    krate: CrateId,
+) -> Result<Vec<Layout>, LayoutError> {
+    db.field_types(variant)
+        .iter()
+        // the ADT has no generic parameters, so there's nothing bound here
+        .map(|(_, ty)| layout_of_ty(db, ty.skip_binders(), krate))
+        .collect()
+}
+
+fn univariant(fields: &[Layout], repr: &StructRepr) -> Result<Layout, LayoutError> {
+    let field_align = |field: &Layout| match repr.pack {
+        Some(pack) => field.align.min(pack),
+        None => field.align,
+    };
+
+    let mut order: Vec<usize> = (0..fields.len()).collect();
+    if repr.reorder {
+        // Same heuristic as rustc: zero-sized fields first, then by descending
+        // alignment. The sort is stable, so equally aligned fields keep their order.
+        order.sort_by_key(|&idx| (fields[idx].size != 0, Reverse(field_align(&fields[idx]))));
+    }
+
+    let mut field_offsets = vec![0; fields.len()];
+    let mut offset = 0u64;
+    let mut align = 1;
+    for idx in order {
+        let field = &fields[idx];
+        let field_align = field_align(field);
+        offset = align_to(offset, field_align)?;
+        field_offsets[idx] = offset;
+        offset = offset.checked_add(field.size).ok_or(LayoutError::SizeOverflow)?;
+        align = align.max(field_align);
+    }
+    if let Some(repr_align) = repr.align {
+        align = align.max(repr_align);
+    }
+    Ok(Layout { size: align_to(offset, align)?, align, field_offsets })
+}
+
+fn union_layout(fields: &[Layout], repr: &StructRepr) -> Result<Layout, LayoutError> {
+    let mut size = 0;
+    let mut align = 1;
+    for field in fields {
+        size = size.max(field.size);
+        align = align.max(match repr.pack {
+            Some(pack) => field.align.min(pack),
+            None => field.align,
+        });
+    }
+    if let Some(repr_align) = repr.align {
+        align = align.max(repr_align);
+    }
+    Ok(Layout { size: align_to(size, align)?, align, field_offsets: vec![0; fields.len()] })
+}
+
+/// Whether a pointer to `ty` carries metadata, like the length of a slice.
+fn is_fat_pointee(db: &dyn HirDatabase, ty: &Ty) -> Result<bool, LayoutError> {
+    let mut ty = ty.clone();
+    // Only the last field of a struct can be unsized, follow that until we find
+    // out. The limit guards against invalid infinitely nested structs.
+    for _ in 0..64 {
+        let last_field = match ty.kind(&Interner) {
+            TyKind::Str | TyKind::Slice(_) | TyKind::Dyn(_) => return Ok(true),
+            TyKind::Adt(crate::AdtId(AdtId::StructId(it)), subst) => {
+                let field_types = db.field_types((*it).into());
+                match db.struct_data(*it).variant_data.fields().iter().last() {
+                    Some((id, _)) => field_types[id].clone().substitute(&Interner, subst),
+                    None => return Ok(false),
+                }
+            }
+            TyKind::Placeholder(_)
+            | TyKind::BoundVar(_)
+            | TyKind::Alias(_)
+            | TyKind::AssociatedType(..)
+            | TyKind::OpaqueType(..)
+            | TyKind::InferenceVar(..)
+            | TyKind::Error => return Err(LayoutError::Unknown),
+            _ => return Ok(false),
+        };
+        ty = last_field;
+    }
+    Err(LayoutError::Unknown)
+}
+
+fn pointer_size(db: &dyn HirDatabase, krate: CrateId) -> u64 {
+    u64::from(db.crate_graph()[krate].target.pointer_width / 8)
+}
+
+fn scalar_layout(scalar: Scalar, ptr_size: u64) -> Layout {
+    let size = match scalar {
+        Scalar::Bool => 1,
+        Scalar::Char => 4,
+        Scalar::Int(IntTy::I8) | Scalar::Uint(UintTy::U8) => 1,
+        Scalar::Int(IntTy::I16) | Scalar::Uint(UintTy::U16) => 2,
+        Scalar::Int(IntTy::I32) | Scalar::Uint(UintTy::U32) => 4,
+        Scalar::Int(IntTy::I64) | Scalar::Uint(UintTy::U64) => 8,
+        Scalar::Int(IntTy::Isize) | Scalar::Uint(UintTy::Usize) => ptr_size,
+        Scalar::Int(IntTy::I128) | Scalar::Uint(UintTy::U128) => {
+            // 16 bytes, but only 8-byte aligned on x86_64
+            return Layout { size: 16, align: 8, field_offsets: Vec::new() };
+        }
+        Scalar::Float(chalk_ir::FloatTy::F32) => 4,
+        Scalar::Float(chalk_ir::FloatTy::F64) => 8,
+    };
+    Layout::scalar(size)
+}
+
+fn align_to(offset: u64, align: u64) -> Result<u64, LayoutError> {
+    let mask = align - 1;
+    offset.checked_add(mask).map(|it| it & !mask).ok_or(LayoutError::SizeOverflow)
+}
+
+#[cfg(test)]
+mod tests;
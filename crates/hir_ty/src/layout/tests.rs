@@ -0,0 +1,153 @@
+use base_db::fixture::WithFixture;
+use hir_def::ModuleDefId;
+
+use crate::{db::HirDatabase, test_db::TestDB, AdtId, Interner, Substitution, TyKind};
+
+use super::{layout_of_ty, Layout, LayoutError};
+
+/// Computes the layout of the struct, union, enum or type alias named `Goal`.
+fn eval_goal(ra_fixture: &str) -> Result<Layout, LayoutError> {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module_id = db.module_for_file(file_id);
+    let def_map = module_id.def_map(&db);
+    let scope = &def_map[module_id.local_id].scope;
+    let goal = scope
+        .entries()
+        .find_map(|(name, def)| if name.to_string() == "Goal" { def.take_types() } else { None })
+        .unwrap();
+    let ty = match goal {
+        ModuleDefId::AdtId(adt) => {
+            TyKind::Adt(AdtId(adt), Substitution::empty(&Interner)).intern(&Interner)
+        }
+        ModuleDefId::TypeAliasId(alias) => db.ty(alias.into()).skip_binders().clone(),
+        _ => panic!("`Goal` must be an ADT or a type alias"),
+    };
+    layout_of_ty(&db, &ty, module_id.krate())
+}
+
+fn check_size_and_align(ra_fixture: &str, size: u64, align: u64) {
+    let layout = eval_goal(ra_fixture).unwrap();
+    assert_eq!((layout.size, layout.align), (size, align));
+}
+
+fn check_field_offsets(ra_fixture: &str, offsets: &[u64]) {
+    let layout = eval_goal(ra_fixture).unwrap();
+    assert_eq!(layout.field_offsets, offsets);
+}
+
+fn check_unknown(ra_fixture: &str) {
+    assert_eq!(eval_goal(ra_fixture), Err(LayoutError::Unknown));
+}
+
+#[test]
+fn scalars() {
+    check_size_and_align("type Goal = bool;", 1, 1);
+    check_size_and_align("type Goal = char;", 4, 4);
+    check_size_and_align("type Goal = u16;", 2, 2);
+    check_size_and_align("type Goal = i64;", 8, 8);
+    check_size_and_align("type Goal = usize;", 8, 8);
+    check_size_and_align("type Goal = f32;", 4, 4);
+    check_size_and_align("type Goal = ();", 0, 1);
+}
+
+#[test]
+fn pointers() {
+    check_size_and_align("type Goal = &u8;", 8, 8);
+    check_size_and_align("type Goal = *mut u64;", 8, 8);
+    check_size_and_align("type Goal = &str;", 16, 8);
+    check_size_and_align("type Goal = *const [u32];", 16, 8);
+    check_size_and_align("trait Tr {} type Goal = &dyn Tr;", 16, 8);
+    check_size_and_align("struct S(u8, [u16]); type Goal = &S;", 16, 8);
+    check_size_and_align("type Goal = fn(u8) -> u32;", 8, 8);
+}
+
+#[test]
+fn pointers_on_32_bit_target() {
+    let target = "//- /main.rs crate:main target:i686-unknown-linux-gnu\n";
+    check_size_and_align(&format!("{}type Goal = usize;", target), 4, 4);
+    check_size_and_align(&format!("{}type Goal = &u64;", target), 4, 4);
+    check_size_and_align(&format!("{}type Goal = &[u8];", target), 8, 4);
+    check_size_and_align(&format!("{}struct Goal(u8, *const u8);", target), 8, 4);
+}
+
+#[test]
+fn tuples_and_arrays() {
+    check_size_and_align("type Goal = (u8, u64);", 16, 8);
+    check_size_and_align("type Goal = (u8, u16, u8);", 4, 2);
+    check_size_and_align("type Goal = [u16; 3];", 6, 2);
+    check_size_and_align("type Goal = [(u32, u8); 2];", 16, 4);
+    check_size_and_align("type Goal = [u64; 0];", 0, 8);
+}
+
+#[test]
+fn default_repr_struct() {
+    let fixture = "struct Goal { a: u8, b: u32, c: u16 }";
+    check_size_and_align(fixture, 8, 4);
+    check_field_offsets(fixture, &[6, 0, 4]);
+    check_size_and_align("struct Goal;", 0, 1);
+    check_size_and_align("struct Goal(u8, ());", 1, 1);
+}
+
+#[test]
+fn repr_c_struct() {
+    let fixture = "#[repr(C)] struct Goal { a: u8, b: u32, c: u16 }";
+    check_size_and_align(fixture, 12, 4);
+    check_field_offsets(fixture, &[0, 4, 8]);
+}
+
+#[test]
+fn packed_struct() {
+    let fixture = "#[repr(packed)] struct Goal { a: u8, b: u32, c: u16 }";
+    check_size_and_align(fixture, 7, 1);
+    check_field_offsets(fixture, &[0, 1, 5]);
+
+    let fixture = "#[repr(C, packed(2))] struct Goal { a: u8, b: u32, c: u16 }";
+    check_size_and_align(fixture, 8, 2);
+    check_field_offsets(fixture, &[0, 2, 6]);
+}
+
+#[test]
+fn aligned_struct() {
+    check_size_and_align("#[repr(align(16))] struct Goal { a: u8 }", 16, 16);
+    check_size_and_align("#[repr(C)] #[repr(align(8))] struct Goal(u8, u16);", 8, 8);
+}
+
+#[test]
+fn nested_structs() {
+    check_size_and_align(
+        r#"
+struct Inner(u8, u32);
+#[repr(C)]
+struct Goal { a: u8, inner: Inner, b: [u8; 3] }
+"#,
+        16,
+        4,
+    );
+}
+
+#[test]
+fn unions() {
+    check_size_and_align("union Goal { a: u8, b: u32, c: [u8; 5] }", 8, 4);
+    check_field_offsets("union Goal { a: u8, b: u32 }", &[0, 0]);
+}
+
+#[test]
+fn enums_with_explicit_repr() {
+    check_size_and_align("#[repr(u8)] enum Goal { A, B, C }", 1, 1);
+    check_size_and_align("#[repr(i64)] enum Goal { A = -1 }", 8, 8);
+    check_size_and_align("#[repr(C)] enum Goal { A, B }", 4, 4);
+    check_size_and_align("#[repr(u16)] enum Goal { A(u8), B(u32) }", 8, 4);
+    check_size_and_align("#[repr(u8)] enum Goal { A(u8, u8), B { x: u16 } }", 4, 2);
+    check_size_and_align("#[repr(C)] enum Goal { A(u8), B(u64) }", 16, 8);
+    check_size_and_align("#[repr(C, u8)] enum Goal { A(u8), B(u16) }", 4, 2);
+}
+
+#[test]
+fn unknown_layouts() {
+    check_unknown("type Goal = str;");
+    check_unknown("type Goal = [u8];");
+    check_unknown("struct Goal<T>(T);");
+    check_unknown("struct S<T>(T); type Goal = S<u8>;");
+    check_unknown("enum Goal { A, B }");
+    check_unknown("struct Goal(Goal);");
+}
@@ -22,7 +22,9 @@ mod walk;
 pub mod db;
 pub mod diagnostics;
 pub mod display;
+pub mod layout;
 pub mod method_resolution;
+pub mod object_safety;
 pub mod primitive;
 pub mod traits;
 
@@ -49,7 +51,9 @@ use crate::{db::HirDatabase, utils::generics};
 pub use autoderef::autoderef;
 pub use builder::TyBuilder;
 pub use chalk_ext::*;
-pub use infer::{could_unify, InferenceDiagnostic, InferenceResult};
+pub use infer::{
+    could_unify, infer_speculative, BindingMode, InferenceDiagnostic, InferenceResult,
+};
 pub use interner::Interner;
 pub use lower::{
     associated_type_shorthand_candidates, callable_item_sig, CallableDefId, ImplTraitLoweringMode,
@@ -256,6 +260,13 @@ pub struct ReturnTypeImplTraits {
 
 has_interner!(ReturnTypeImplTraits);
 
+impl ReturnTypeImplTraits {
+    /// Returns the bounds of the `idx`-th `impl Trait` in the return type.
+    pub fn bounds(&self, idx: usize) -> Option<&Binders<Vec<QuantifiedWhereClause>>> {
+        self.impl_traits.get(idx).map(|it| &it.bounds)
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub(crate) struct ReturnTypeImplTrait {
     pub(crate) bounds: Binders<Vec<QuantifiedWhereClause>>,
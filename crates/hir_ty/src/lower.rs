@@ -418,10 +418,14 @@ impl<'a> TyLoweringContext<'a> {
                         .associated_type_by_name(segment.name);
                     match found {
                         Some(associated_ty) => {
-                            // FIXME handle type parameters on the segment
+                            let substitution = self.assoc_type_substs(
+                                associated_ty,
+                                trait_ref.substitution,
+                                segment,
+                            );
                             TyKind::Alias(AliasTy::Projection(ProjectionTy {
                                 associated_ty_id: to_assoc_type_id(associated_ty),
-                                substitution: trait_ref.substitution,
+                                substitution,
                             }))
                             .intern(&Interner)
                         }
@@ -549,7 +553,7 @@ impl<'a> TyLoweringContext<'a> {
                         // We need to shift in the bound vars, since
                         // associated_type_shorthand_candidates does not do that
                         let substs = substs.shifted_in_from(&Interner, self.in_binders);
-                        // FIXME handle type parameters on the segment
+                        let substs = self.assoc_type_substs(associated_ty, substs, segment.clone());
                         return Some(
                             TyKind::Alias(AliasTy::Projection(ProjectionTy {
                                 associated_ty_id: to_assoc_type_id(associated_ty),
@@ -620,6 +624,25 @@ impl<'a> TyLoweringContext<'a> {
         self.substs_from_path_segment(segment, generic_def, infer_args, None)
     }
 
+    /// Adds the arguments for the associated type's own parameters, which only generic
+    /// associated types have, to the substitution of its trait. Like chalk expects, they come
+    /// before the trait's arguments.
+    fn assoc_type_substs(
+        &self,
+        associated_ty: TypeAliasId,
+        trait_substs: Substitution,
+        segment: PathSegment<'_>,
+    ) -> Substitution {
+        let (_, _, own_params) = generics(self.db.upcast(), associated_ty.into()).len_split();
+        if own_params == 0 {
+            return trait_substs;
+        }
+        let substs =
+            self.substs_from_path_segment(segment, Some(associated_ty.into()), false, None);
+        let own_args = substs.iter(&Interner).skip(trait_substs.len(&Interner)).cloned();
+        Substitution::from_iter(&Interner, own_args.chain(trait_substs.iter(&Interner).cloned()))
+    }
+
     fn substs_from_path_segment(
         &self,
         segment: PathSegment<'_>,
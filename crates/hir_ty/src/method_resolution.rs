@@ -197,6 +197,29 @@ impl TraitImpls {
         Arc::new(res)
     }
 
+    pub(crate) fn trait_impls_in_rev_deps_query(db: &dyn HirDatabase, krate: CrateId) -> Arc<Self> {
+        let _p = profile::span("trait_impls_in_rev_deps_query");
+        let crate_graph = db.crate_graph();
+        let mut res = Self { map: FxHashMap::default() };
+
+        let mut rev_deps: Vec<_> = crate_graph.transitive_rev_deps(krate).collect();
+        rev_deps.sort();
+        for krate in rev_deps {
+            res.merge(&db.trait_impls_in_crate(krate));
+        }
+
+        Arc::new(res)
+    }
+
+    pub(crate) fn trait_impls_for_self_ty_in_deps_query(
+        db: &dyn HirDatabase,
+        krate: CrateId,
+        fp: TyFingerprint,
+    ) -> Arc<[ImplId]> {
+        let _p = profile::span("trait_impls_for_self_ty_in_deps_query");
+        db.trait_impls_in_rev_deps(krate).for_self_ty_without_blanket_impls(fp).collect()
+    }
+
     fn merge(&mut self, other: &Self) {
         for (trait_, other_map) in &other.map {
             let map = self.map.entry(*trait_).or_default();
@@ -239,6 +262,11 @@ impl TraitImpls {
             .flat_map(|v| v.iter().copied())
     }
 
+    /// Queries all traits that have at least one impl.
+    pub fn traits(&self) -> impl Iterator<Item = TraitId> + '_ {
+        self.map.keys().copied()
+    }
+
     pub fn all_impls(&self) -> impl Iterator<Item = ImplId> + '_ {
         self.map.values().flat_map(|map| map.values().flat_map(|v| v.iter().copied()))
     }
@@ -0,0 +1,152 @@
+//! Checks whether a trait is object safe, that is whether `dyn Trait` is a
+//! valid type.
+//!
+//! This implements the classic rules from the reference, see
+//! <https://doc.rust-lang.org/reference/items/traits.html#object-safety>. The
+//! checks are mostly syntactic, like rustc we only look at the signatures of
+//! the trait's items.
+
+use std::sync::Arc;
+
+use hir_def::{
+    generics::{WherePredicate, WherePredicateTypeTarget},
+    path::Path,
+    resolver::{HasResolver, TypeNs},
+    type_ref::{TraitBoundModifier, TypeRef},
+    AssocItemId, ConstId, FunctionId, GenericDefId, Lookup, TraitId, TypeAliasId,
+};
+use hir_expand::name::name;
+
+use crate::{db::HirDatabase, utils::all_super_traits};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectSafetyViolation {
+    /// The trait, or one of its supertraits, requires `Self: Sized`.
+    SizedSelf,
+    AssocConst(ConstId),
+    /// A generic associated type, like `type Item<'a>;`.
+    GenericAssocType(TypeAliasId),
+    Method(FunctionId, MethodViolationCode),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodViolationCode {
+    /// The method has no receiver, like `fn new() -> Self`.
+    StaticMethod,
+    /// The method takes `Self` in a parameter other than the receiver.
+    ReferencesSelfInput,
+    /// The method returns `Self`, or some type mentioning it.
+    ReferencesSelfOutput,
+    /// The method has type or const parameters.
+    Generic,
+}
+
+/// Returns the reasons why `dyn Trait` is invalid for `trait_`, including the
+/// ones caused by its supertraits. Empty if the trait is object safe.
+pub(crate) fn object_safety_violations_query(
+    db: &dyn HirDatabase,
+    trait_: TraitId,
+) -> Arc<[ObjectSafetyViolation]> {
+    let mut violations = Vec::new();
+    let sized = sized_trait(db, trait_);
+    let super_traits = all_super_traits(db.upcast(), trait_);
+    if sized.map_or(false, |sized| super_traits.contains(&sized)) {
+        violations.push(ObjectSafetyViolation::SizedSelf);
+    }
+
+    for super_trait in super_traits {
+        for (_, item) in db.trait_data(super_trait).items.iter() {
+            match *item {
+                AssocItemId::ConstId(it) => violations.push(ObjectSafetyViolation::AssocConst(it)),
+                AssocItemId::TypeAliasId(it) => {
+                    let params = db.generic_params(it.into());
+                    if !params.types.is_empty()
+                        || !params.lifetimes.is_empty()
+                        || !params.consts.is_empty()
+                    {
+                        violations.push(ObjectSafetyViolation::GenericAssocType(it));
+                    }
+                }
+                AssocItemId::FunctionId(it) => {
+                    // `where Self: Sized` opts the method out of being callable on `dyn Trait`
+                    if sized.map_or(false, |sized| has_self_bound(db, it.into(), sized)) {
+                        continue;
+                    }
+                    if let Some(code) = method_violation(db, it) {
+                        violations.push(ObjectSafetyViolation::Method(it, code));
+                    }
+                }
+            }
+        }
+    }
+
+    violations.into()
+}
+
+fn method_violation(db: &dyn HirDatabase, func: FunctionId) -> Option<MethodViolationCode> {
+    let data = db.function_data(func);
+    if !data.has_self_param() {
+        return Some(MethodViolationCode::StaticMethod);
+    }
+    // the receiver is the first parameter, it's allowed to mention `Self`
+    if data.params.iter().skip(1).any(|param| mentions_self(param)) {
+        return Some(MethodViolationCode::ReferencesSelfInput);
+    }
+    if mentions_self(&data.ret_type) {
+        return Some(MethodViolationCode::ReferencesSelfOutput);
+    }
+    let params = db.generic_params(func.into());
+    if !params.types.is_empty() || !params.consts.is_empty() {
+        return Some(MethodViolationCode::Generic);
+    }
+    None
+}
+
+/// Whether `type_ref` mentions the `Self` type itself. Projections like
+/// `Self::Item` are fine.
+fn mentions_self(type_ref: &TypeRef) -> bool {
+    let self_path = Path::from(name![Self]);
+    let mut res = false;
+    type_ref.walk(&mut |type_ref| {
+        if let TypeRef::Path(path) = type_ref {
+            res |= *path == self_path;
+        }
+    });
+    res
+}
+
+/// Whether `def` has a `Self: Trait` bound, either as a where clause or, for
+/// traits, as a supertrait.
+fn has_self_bound(db: &dyn HirDatabase, def: GenericDefId, trait_: TraitId) -> bool {
+    let resolver = def.resolver(db.upcast());
+    let params = db.generic_params(def);
+    let trait_self = params.find_trait_self_param();
+    params.where_predicates.iter().any(|pred| {
+        let (target, bound) = match pred {
+            WherePredicate::TypeBound { target, bound }
+            | WherePredicate::ForLifetime { target, bound, .. } => (target, bound),
+            WherePredicate::Lifetime { .. } => return false,
+        };
+        let is_self = match target {
+            WherePredicateTypeTarget::TypeRef(type_ref) => {
+                matches!(&**type_ref, TypeRef::Path(p) if *p == Path::from(name![Self]))
+            }
+            WherePredicateTypeTarget::TypeParam(local_id) => Some(*local_id) == trait_self,
+        };
+        match bound.as_path() {
+            Some((path, TraitBoundModifier::None)) if is_self => matches!(
+                resolver.resolve_path_in_type_ns_fully(db.upcast(), path.mod_path()),
+                Some(TypeNs::TraitId(it)) if it == trait_
+            ),
+            _ => false,
+        }
+    })
+}
+
+fn sized_trait(db: &dyn HirDatabase, trait_: TraitId) -> Option<TraitId> {
+    let krate = trait_.lookup(db.upcast()).container.krate();
+    db.lang_item(krate, "sized".into()).and_then(|it| it.as_trait())
+}
+
+#[cfg(test)]
+mod tests;
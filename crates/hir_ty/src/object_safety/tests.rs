@@ -0,0 +1,184 @@
+use base_db::fixture::WithFixture;
+use expect_test::{expect, Expect};
+use hir_def::{db::DefDatabase, ModuleDefId};
+
+use crate::{db::HirDatabase, test_db::TestDB};
+
+use super::ObjectSafetyViolation;
+
+/// Checks the object safety violations of the trait named `Goal`.
+fn check(ra_fixture: &str, expect: Expect) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module_id = db.module_for_file(file_id);
+    let def_map = module_id.def_map(&db);
+    let scope = &def_map[module_id.local_id].scope;
+    let trait_ = scope
+        .declarations()
+        .find_map(|it| match it {
+            ModuleDefId::TraitId(it) if db.trait_data(it).name.to_string() == "Goal" => Some(it),
+            _ => None,
+        })
+        .unwrap();
+    let actual = db
+        .object_safety_violations(trait_)
+        .iter()
+        .map(|violation| match violation {
+            ObjectSafetyViolation::SizedSelf => "SizedSelf\n".to_string(),
+            ObjectSafetyViolation::AssocConst(it) => {
+                format!("AssocConst {}\n", db.const_data(*it).name.as_ref().unwrap())
+            }
+            ObjectSafetyViolation::GenericAssocType(it) => {
+                format!("GenericAssocType {}\n", db.type_alias_data(*it).name)
+            }
+            ObjectSafetyViolation::Method(it, code) => {
+                format!("Method {} {:?}\n", db.function_data(*it).name, code)
+            }
+        })
+        .collect::<String>();
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn object_safe() {
+    check(
+        r#"
+#[lang = "sized"]
+trait Sized {}
+trait Goal {
+    type Item;
+    fn by_ref(&self) -> Self::Item;
+    fn by_mut(&mut self, item: Self::Item);
+    fn by_value(self);
+    fn new() -> Self where Self: Sized;
+    fn generic<T>(&self, t: T) where Self: Sized;
+    fn other(&self, other: &Self) where Self: Sized;
+}
+"#,
+        expect![[""]],
+    );
+}
+
+#[test]
+fn sized_self() {
+    check(
+        r#"
+#[lang = "sized"]
+trait Sized {}
+trait Goal: Sized {}
+"#,
+        expect![[r#"
+            SizedSelf
+        "#]],
+    );
+    check(
+        r#"
+#[lang = "sized"]
+trait Sized {}
+trait Super where Self: Sized {}
+trait Goal: Super {}
+"#,
+        expect![[r#"
+            SizedSelf
+        "#]],
+    );
+}
+
+#[test]
+fn assoc_const() {
+    check(
+        r#"
+trait Goal {
+    const N: usize;
+}
+"#,
+        expect![[r#"
+            AssocConst N
+        "#]],
+    );
+}
+
+#[test]
+fn generic_assoc_type() {
+    check(
+        r#"
+trait Goal {
+    type Lending<'a>;
+    type Generic<T>;
+    type Fine;
+}
+"#,
+        expect![[r#"
+            GenericAssocType Lending
+            GenericAssocType Generic
+        "#]],
+    );
+}
+
+#[test]
+fn static_method() {
+    check(
+        r#"
+trait Goal {
+    fn new() -> u32;
+}
+"#,
+        expect![[r#"
+            Method new StaticMethod
+        "#]],
+    );
+}
+
+#[test]
+fn generic_method() {
+    check(
+        r#"
+trait Goal {
+    fn generic<T>(&self, t: T);
+    fn impl_trait(&self, t: impl Copy);
+    fn lifetime_only<'a>(&'a self) -> &'a u8;
+}
+"#,
+        expect![[r#"
+            Method generic Generic
+            Method impl_trait Generic
+        "#]],
+    );
+}
+
+#[test]
+fn method_references_self() {
+    check(
+        r#"
+trait Goal {
+    fn returns_self(&self) -> Self;
+    fn returns_option(&self) -> Option<Self>;
+    fn takes_self(&self, other: Self);
+    fn takes_ref(&self, other: &Self);
+    fn boxed_receiver(self: Box<Self>);
+}
+"#,
+        expect![[r#"
+            Method returns_self ReferencesSelfOutput
+            Method returns_option ReferencesSelfOutput
+            Method takes_self ReferencesSelfInput
+            Method takes_ref ReferencesSelfInput
+        "#]],
+    );
+}
+
+#[test]
+fn supertrait_violations() {
+    check(
+        r#"
+trait Super {
+    fn clone_me(&self) -> Self;
+}
+trait Goal: Super {
+    fn fine(&self);
+}
+"#,
+        expect![[r#"
+            Method clone_me ReferencesSelfOutput
+        "#]],
+    );
+}
@@ -7,6 +7,8 @@ mod traits;
 mod method_resolution;
 mod macros;
 mod display_source_code;
+mod display_truncated;
+mod speculative;
 mod incremental;
 
 use std::{collections::HashMap, env, sync::Arc};
@@ -60,23 +62,34 @@ fn setup_tracing() -> Option<tracing::subscriber::DefaultGuard> {
     Some(tracing::subscriber::set_default(subscriber))
 }
 
+#[derive(Clone, Copy)]
+enum TypeDisplay {
+    Test,
+    SourceCode,
+    MaxDepth(usize),
+}
+
 fn check_types(ra_fixture: &str) {
-    check_impl(ra_fixture, false, true, false)
+    check_impl(ra_fixture, false, true, TypeDisplay::Test)
 }
 
 fn check_types_source_code(ra_fixture: &str) {
-    check_impl(ra_fixture, false, true, true)
+    check_impl(ra_fixture, false, true, TypeDisplay::SourceCode)
+}
+
+fn check_types_max_depth(ra_fixture: &str, max_depth: usize) {
+    check_impl(ra_fixture, false, true, TypeDisplay::MaxDepth(max_depth))
 }
 
 fn check_no_mismatches(ra_fixture: &str) {
-    check_impl(ra_fixture, true, false, false)
+    check_impl(ra_fixture, true, false, TypeDisplay::Test)
 }
 
 fn check(ra_fixture: &str) {
-    check_impl(ra_fixture, false, false, false)
+    check_impl(ra_fixture, false, false, TypeDisplay::Test)
 }
 
-fn check_impl(ra_fixture: &str, allow_none: bool, only_types: bool, display_source: bool) {
+fn check_impl(ra_fixture: &str, allow_none: bool, only_types: bool, display: TypeDisplay) {
     let _tracing = setup_tracing();
     let (db, files) = TestDB::with_many_files(ra_fixture);
 
@@ -146,10 +159,14 @@ fn check_impl(ra_fixture: &str, allow_none: bool, only_types: bool, display_sour
             };
             let range = node.as_ref().original_file_range(&db);
             if let Some(expected) = types.remove(&range) {
-                let actual = if display_source {
-                    ty.display_source_code(&db, def.module(&db)).unwrap()
-                } else {
-                    ty.display_test(&db).to_string()
+                let actual = match display {
+                    TypeDisplay::Test => ty.display_test(&db).to_string(),
+                    TypeDisplay::SourceCode => {
+                        ty.display_source_code(&db, def.module(&db)).unwrap()
+                    }
+                    TypeDisplay::MaxDepth(max_depth) => {
+                        ty.display_max_depth(&db, Some(max_depth)).to_string()
+                    }
                 };
                 assert_eq!(actual, expected);
             }
@@ -162,10 +179,14 @@ fn check_impl(ra_fixture: &str, allow_none: bool, only_types: bool, display_sour
             };
             let range = node.as_ref().original_file_range(&db);
             if let Some(expected) = types.remove(&range) {
-                let actual = if display_source {
-                    ty.display_source_code(&db, def.module(&db)).unwrap()
-                } else {
-                    ty.display_test(&db).to_string()
+                let actual = match display {
+                    TypeDisplay::Test => ty.display_test(&db).to_string(),
+                    TypeDisplay::SourceCode => {
+                        ty.display_source_code(&db, def.module(&db)).unwrap()
+                    }
+                    TypeDisplay::MaxDepth(max_depth) => {
+                        ty.display_max_depth(&db, Some(max_depth)).to_string()
+                    }
                 };
                 assert_eq!(actual, expected);
             }
@@ -0,0 +1,34 @@
+use super::check_types_max_depth;
+
+#[test]
+fn truncates_nested_generic_args() {
+    check_types_max_depth(
+        r#"
+struct Wrap<T>(T);
+fn main() {
+    let shallow = Wrap(0u8);
+    shallow;
+  //^^^^^^^ Wrap<u8>
+    let deep = Wrap(Wrap(Wrap(Wrap(0u8))));
+    deep;
+} //^^^^ Wrap<Wrap<…>>
+"#,
+        2,
+    );
+}
+
+#[test]
+fn truncates_nested_tuples_and_references() {
+    check_types_max_depth(
+        r#"
+fn main() {
+    let tuple = ((1u8, (2u8, 3u8)), 4u8);
+    tuple;
+  //^^^^^ ((…, …), u8)
+    let reference = &&&0u8;
+    reference;
+} //^^^^^^^^^ &&…
+"#,
+        2,
+    );
+}
@@ -0,0 +1,69 @@
+use base_db::{fixture::WithFixture, SourceDatabase};
+use expect_test::{expect, Expect};
+use hir_def::body::Body;
+use syntax::{algo::find_node_at_offset, ast, AstNode};
+
+use crate::{display::HirDisplay, infer_speculative, test_db::TestDB};
+
+use super::visit_module;
+
+fn check_speculative_type(ra_fixture: &str, replacement: &str, expect: Expect) {
+    let (db, position) = TestDB::with_position(ra_fixture);
+    let module = db.module_for_file(position.file_id);
+    let def_map = module.def_map(&db);
+    let file = db.parse(position.file_id).tree();
+    let original = find_node_at_offset::<ast::Expr>(file.syntax(), position.offset).unwrap();
+    let replacement = ast::Expr::parse(replacement).unwrap();
+
+    let mut defs = Vec::new();
+    visit_module(&db, &def_map, module.local_id, &mut |it| defs.push(it));
+    let (def, (body, source_map, expr)) = defs
+        .into_iter()
+        .find_map(|def| Some((def, Body::speculative(&db, def, &original, &replacement)?)))
+        .unwrap();
+    let expr_id = source_map.node_expr(expr.as_ref()).unwrap();
+    let infer = infer_speculative(&db, def, body);
+    expect.assert_eq(&infer[expr_id].display(&db).to_string());
+}
+
+#[test]
+fn speculative_type_of_incomplete_let() {
+    check_speculative_type(
+        r#"
+struct S { a: u8 }
+fn main() {
+    let s = S { a: 0 };
+    $0{ let x = }
+}
+"#,
+        "{ let x = s.a; x }",
+        expect![[r#"u8"#]],
+    );
+}
+
+#[test]
+fn speculative_type_of_method_call() {
+    check_speculative_type(
+        r#"
+struct S;
+impl S { fn len(&self) -> usize { 0 } }
+fn main() {
+    let s = S;
+    let x = $0s;
+}
+"#,
+        "s.len()",
+        expect![[r#"usize"#]],
+    );
+}
+
+#[test]
+fn speculative_type_of_expr_in_const() {
+    check_speculative_type(
+        r#"
+const C: u64 = $01;
+"#,
+        "2 + 3",
+        expect![[r#"u64"#]],
+    );
+}
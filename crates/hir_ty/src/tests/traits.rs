@@ -3578,3 +3578,50 @@ fn f<F: Foo>() {
         "#]],
     );
 }
+
+#[test]
+fn gat_with_lifetime_param() {
+    check_types(
+        r#"
+trait LendingIterator {
+    type Item<'a> where Self: 'a;
+    fn next<'a>(&'a mut self) -> Self::Item<'a>;
+}
+
+struct Windows;
+impl LendingIterator for Windows {
+    type Item<'a> = &'a [u8];
+    fn next<'a>(&'a mut self) -> Self::Item<'a> { loop {} }
+}
+
+fn test(w: &mut Windows) {
+    let item = w.next();
+    item;
+} //^^^^ &[u8]
+"#,
+    );
+}
+
+#[test]
+fn gat_with_type_param() {
+    check_types(
+        r#"
+trait Container {
+    type Wrapped<T>;
+    fn wrap<T>(&self, t: T) -> Self::Wrapped<T>;
+}
+
+struct Wrapper<T>(T);
+struct Boxes;
+impl Container for Boxes {
+    type Wrapped<T> = Wrapper<T>;
+    fn wrap<T>(&self, t: T) -> Self::Wrapped<T> { Wrapper(t) }
+}
+
+fn test(boxes: Boxes) {
+    let wrapped = boxes.wrap(0u8);
+    wrapped;
+} //^^^^^^^ Wrapper<u8>
+"#,
+    );
+}
@@ -12,7 +12,7 @@ use stdx::panic_context;
 
 use crate::{
     db::HirDatabase, AliasEq, AliasTy, Canonical, DomainGoal, Goal, Guidance, InEnvironment,
-    Interner, Solution, TraitRefExt, Ty, TyKind, WhereClause,
+    Interner, ProjectionTyExt, Solution, TraitRefExt, Ty, TyKind, WhereClause,
 };
 
 /// This controls how much 'time' we give the Chalk solver before giving up.
@@ -88,7 +88,9 @@ pub(crate) fn trait_solve_query(
         ..
     }))) = &goal.value.goal.data(&Interner)
     {
-        if let TyKind::BoundVar(_) = projection_ty.self_type_parameter(&Interner).kind(&Interner) {
+        if let TyKind::BoundVar(_) =
+            projection_ty.trait_ref(db).self_type_parameter(&Interner).kind(&Interner)
+        {
             // Hack: don't ask Chalk to normalize with an unknown self type, it'll say that's impossible
             return Some(Solution::Ambig(Guidance::Unknown));
         }
@@ -132,7 +132,8 @@ pub(crate) fn resolve_annotation(db: &RootDatabase, mut annotation: Annotation)
             *data = goto_implementation(db, *position).map(|range| range.info);
         }
         AnnotationKind::HasReferences { position, data } => {
-            *data = find_all_refs(&Semantics::new(db), *position, None).map(|result| {
+            let sema = Semantics::new(db);
+            *data = find_all_refs(&sema, *position, None, &Default::default()).map(|result| {
                 result
                     .references
                     .into_iter()
@@ -160,22 +161,21 @@ fn should_skip_runnable(kind: &RunnableKind, binary_target: bool) -> bool {
 mod tests {
     use expect_test::{expect, Expect};
 
-    use crate::{fixture, Annotation, AnnotationConfig};
+    use crate::{fixture, Annotation, AnnotationConfig, AnnotationKind};
+
+    const CONFIG: AnnotationConfig = AnnotationConfig {
+        binary_target: true,
+        annotate_runnables: true,
+        annotate_impls: true,
+        annotate_references: true,
+        annotate_method_references: true,
+    };
 
     fn check(ra_fixture: &str, expect: Expect) {
         let (analysis, file_id) = fixture::file(ra_fixture);
 
         let annotations: Vec<Annotation> = analysis
-            .annotations(
-                &AnnotationConfig {
-                    binary_target: true,
-                    annotate_runnables: true,
-                    annotate_impls: true,
-                    annotate_references: true,
-                    annotate_method_references: true,
-                },
-                file_id,
-            )
+            .annotations(&CONFIG, file_id)
             .unwrap()
             .into_iter()
             .map(|annotation| analysis.resolve_annotation(annotation).unwrap())
@@ -810,4 +810,57 @@ m!();
             "#]],
         );
     }
+
+    #[test]
+    fn annotations_are_resolved_lazily() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct Foo;
+trait Trait {}
+impl Trait for Foo {}
+
+fn main() {
+    let _ = Foo;
+}
+"#,
+        );
+
+        let annotations = {
+            cov_mark::check_count!(find_usages_search, 0);
+            analysis.annotations(&CONFIG, file_id).unwrap()
+        };
+        let counts = |annotations: &[Annotation]| {
+            annotations
+                .iter()
+                .filter_map(|annotation| match &annotation.kind {
+                    AnnotationKind::HasImpls { data, .. } => {
+                        Some(("impls", data.as_ref().map(|it| it.len())))
+                    }
+                    AnnotationKind::HasReferences { data, .. } => {
+                        Some(("references", data.as_ref().map(|it| it.len())))
+                    }
+                    AnnotationKind::Runnable(_) => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert!(counts(&annotations).iter().all(|(_, count)| count.is_none()));
+
+        let resolved: Vec<_> = {
+            cov_mark::check!(find_usages_search);
+            annotations
+                .into_iter()
+                .map(|annotation| analysis.resolve_annotation(annotation).unwrap())
+                .collect()
+        };
+        assert_eq!(
+            counts(&resolved),
+            vec![
+                ("impls", Some(1)),
+                ("references", Some(2)),
+                ("impls", Some(1)),
+                ("references", Some(1)),
+                ("references", Some(0)),
+            ]
+        );
+    }
 }
@@ -3,8 +3,12 @@
 use indexmap::IndexMap;
 
 use hir::Semantics;
-use ide_db::{call_info::FnCallNode, RootDatabase};
-use syntax::{ast, AstNode, TextRange};
+use ide_db::{
+    defs::{Definition, NameRefClass},
+    RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{ast, AstNode, SyntaxKind::IDENT, TextRange};
 
 use crate::{
     display::TryToNav, goto_definition, references, FilePosition, NavigationTarget, RangeInfo,
@@ -42,31 +46,108 @@ pub(crate) fn incoming_calls(db: &RootDatabase, position: FilePosition) -> Optio
     // 1. Find all refs
     // 2. Loop through refs and determine unique fndef. This will become our `from: CallHierarchyItem,` in the reply.
     // 3. Add ranges relative to the start of the fndef.
-    let refs = references::find_all_refs(&sema, position, None)?;
+    let refs = references::find_all_refs(&sema, position, None, &Default::default())?;
 
     let mut calls = CallLocations::default();
 
     for (file_id, references) in refs.references {
         let file = sema.parse(file_id);
         let file = file.syntax();
-        for (relative_range, token) in references
-            .into_iter()
-            .filter_map(|(range, _)| Some(range).zip(file.token_at_offset(range.start()).next()))
-        {
+        for (relative_range, token) in references.into_iter().filter_map(|(range, _)| {
+            Some(range).zip(file.token_at_offset(range.start()).right_biased())
+        }) {
             let token = sema.descend_into_macros(token);
-            // This target is the containing function
-            if let Some(nav) = token.ancestors().find_map(|node| {
+            // This target is the containing function, which is outside of the macro call if the
+            // reference was found in an expansion
+            if let Some(nav) = sema.token_ancestors_with_macros(token.clone()).find_map(|node| {
                 let def = ast::Fn::cast(node).and_then(|fn_| sema.to_def(&fn_))?;
                 def.try_to_nav(sema.db)
             }) {
-                calls.add(&nav, relative_range);
+                let range = match token.parent() {
+                    Some(parent) => sema.original_range(&parent).range,
+                    None => relative_range,
+                };
+                calls.add(&nav, range);
             }
         }
     }
 
+    let file = sema.parse(position.file_id);
+    if let Some(def) = references::find_def(&sema, file.syntax(), position.offset) {
+        add_calls_from_macro_rules(&sema, def, &mut calls);
+    }
+
     Some(calls.into_items())
 }
 
+/// Adds the calls to `def` that are written in the body of a `macro_rules!`, which the reference
+/// search can't resolve. They are found in the expansions of the macro and reported at the macro
+/// call site.
+fn add_calls_from_macro_rules(
+    sema: &Semantics<RootDatabase>,
+    def: Definition,
+    calls: &mut CallLocations,
+) {
+    let name = match def {
+        Definition::ModuleDef(hir::ModuleDef::Function(func)) => func.name(sema.db).to_string(),
+        _ => return,
+    };
+    let mentions_name = |tt: ast::TokenTree| {
+        tt.syntax()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .any(|token| token.kind() == IDENT && token.text() == name)
+    };
+
+    for (file_id, search_range) in def.search_scope(sema.db) {
+        let file = sema.parse(file_id);
+        let macros = file
+            .syntax()
+            .descendants()
+            .filter_map(ast::MacroRules::cast)
+            .filter(|it| {
+                search_range.map_or(true, |range| range.contains_range(it.syntax().text_range()))
+            })
+            .filter(|it| it.token_tree().map_or(false, mentions_name))
+            .filter_map(|it| sema.to_def(&ast::Macro::from(it)));
+        for mac in macros {
+            let macro_calls = Definition::Macro(mac)
+                .usages(sema)
+                .all()
+                .into_iter()
+                .flat_map(|(_, refs)| refs)
+                .filter_map(|it| it.name.syntax().ancestors().find_map(ast::MacroCall::cast));
+            for call in macro_calls {
+                let expansion = match sema.expand(&call) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let call_range = sema.original_range(call.syntax()).range;
+                let nav = match sema.ancestors_with_macros(call.syntax().clone()).find_map(|node| {
+                    let def = ast::Fn::cast(node).and_then(|fn_| sema.to_def(&fn_))?;
+                    def.try_to_nav(sema.db)
+                }) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                for name_ref in expansion.descendants().filter_map(ast::NameRef::cast) {
+                    if name_ref.text() != name.as_str() {
+                        continue;
+                    }
+                    match NameRefClass::classify(sema, &name_ref) {
+                        Some(NameRefClass::Definition(it)) if it == def => (),
+                        _ => continue,
+                    }
+                    // A name that maps into the call's input was found by the reference search.
+                    if sema.original_range(name_ref.syntax()).range == call_range {
+                        calls.add(&nav, call_range);
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Option<Vec<CallItem>> {
     let sema = Semantics::new(db);
     let file_id = position.file_id;
@@ -74,32 +155,22 @@ pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Optio
     let file = file.syntax();
     let token = file.token_at_offset(position.offset).next()?;
     let token = sema.descend_into_macros(token);
+    let function = sema
+        .token_ancestors_with_macros(token)
+        .find_map(|node| ast::Fn::cast(node).and_then(|fn_| sema.to_def(&fn_)))?;
 
     let mut calls = CallLocations::default();
 
-    token
-        .parent()
+    // Walk the lowered body rather than the syntax, so that calls produced by macros are found.
+    function
+        .callees(db)
         .into_iter()
-        .flat_map(|it| it.descendants())
-        .filter_map(|node| FnCallNode::with_node_exact(&node))
-        .filter_map(|call_node| {
-            let name_ref = call_node.name_ref()?;
-            let func_target = match call_node {
-                FnCallNode::CallExpr(expr) => {
-                    let callable = sema.type_of_expr(&expr.expr()?)?.original.as_callable(db)?;
-                    match callable.kind() {
-                        hir::CallableKind::Function(it) => it.try_to_nav(db),
-                        _ => None,
-                    }
-                }
-                FnCallNode::MethodCallExpr(expr) => {
-                    let function = sema.resolve_method_call(&expr)?;
-                    function.try_to_nav(db)
-                }
-            }?;
-            Some((func_target, name_ref.syntax().text_range()))
+        .filter_map(|(callee, name_ref)| {
+            Some((callee.try_to_nav(db)?, sema.diagnostics_display_range(name_ref)))
         })
-        .for_each(|(nav, range)| calls.add(&nav, range));
+        .filter(|(_, range)| range.file_id == file_id)
+        .sorted_by_key(|(_, range)| range.range.start())
+        .for_each(|(nav, range)| calls.add(&nav, range.range));
 
     Some(calls.into_items())
 }
@@ -374,4 +445,73 @@ fn main() {
             &[],
         );
     }
+
+    #[test]
+    fn test_call_hierarchy_incoming_in_macro_call() {
+        check_hierarchy(
+            r#"
+macro_rules! ensure {
+    ($e:expr) => { if !$e { loop {} } };
+}
+
+fn call$0ee() -> bool { true }
+
+fn caller() {
+    ensure!(callee());
+}
+"#,
+            "callee Function FileId(0) 66..94 69..75",
+            &["caller Function FileId(0) 96..134 99..105 : [122..128]"],
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_call_hierarchy_outgoing_methods_and_macros() {
+        check_hierarchy(
+            r#"
+macro_rules! call {
+    ($f:ident) => { $f() };
+}
+
+trait Tr {
+    fn method(&self);
+}
+
+fn callee() {}
+
+fn call$0er(tr: &dyn Tr) {
+    callee();
+    tr.method();
+    call!(callee);
+}
+"#,
+            "caller Function FileId(0) 103..179 106..112",
+            &[],
+            &[
+                "callee Function FileId(0) 87..101 90..96 : [132..138, 169..175]",
+                "method Function FileId(0) 66..83 69..75 : [149..155]",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_call_hierarchy_incoming_from_macro_rules_body() {
+        check_hierarchy(
+            r#"
+macro_rules! call_callee {
+    () => { callee() };
+}
+
+fn call$0ee() {}
+
+fn caller() {
+    call_callee!();
+}
+"#,
+            "callee Function FileId(0) 54..68 57..63",
+            &["caller Function FileId(0) 70..105 73..79 : [88..102]"],
+            &[],
+        );
+    }
 }
@@ -0,0 +1,133 @@
+use hir::Semantics;
+use ide_db::{
+    base_db::FilePosition,
+    defs::{Definition, NameClass, NameRefClass},
+    helpers::pick_best_token,
+    RootDatabase,
+};
+use syntax::{ast, match_ast, AstNode, SyntaxKind::IDENT};
+
+// Feature: Interpret Function
+//
+// Evaluates the const fn call or the constant under the cursor and shows the
+// resulting value. Evaluation stops at the first construct the evaluator does
+// not support, like a loop or a call to a non-const fn, and reports it instead.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Rust Analyzer: Interpret Function**
+// |===
+pub(crate) fn eval_function(db: &RootDatabase, position: FilePosition) -> Option<String> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let token =
+        pick_best_token(file.syntax().token_at_offset(position.offset), |kind| match kind {
+            IDENT => 2,
+            kind if kind.is_trivia() => 0,
+            _ => 1,
+        })?;
+    let token = sema.descend_into_macros(token);
+
+    let konst = token.parent().and_then(|node| {
+        let def = match_ast! {
+            match node {
+                ast::NameRef(name_ref) => match NameRefClass::classify(&sema, &name_ref)? {
+                    NameRefClass::Definition(def) => def,
+                    NameRefClass::FieldShorthand { .. } => return None,
+                },
+                ast::Name(name) => NameClass::classify(&sema, &name)?.defined()?,
+                _ => return None,
+            }
+        };
+        match def {
+            Definition::ModuleDef(hir::ModuleDef::Const(it)) => Some(it),
+            _ => None,
+        }
+    });
+    let value = match konst {
+        Some(konst) => konst.eval(db),
+        None => {
+            let call = token
+                .ancestors()
+                .take_while(|it| !ast::Item::can_cast(it.kind()))
+                .find_map(|node| {
+                    match_ast! {
+                        match node {
+                            ast::CallExpr(it) => Some(ast::Expr::from(it)),
+                            ast::MethodCallExpr(it) => Some(ast::Expr::from(it)),
+                            _ => None,
+                        }
+                    }
+                })?;
+            sema.eval_const_expr(&call)?
+        }
+    };
+    Some(match value {
+        Ok(value) => value.to_string(),
+        Err(err) => format!("evaluation failed: {}", err),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let actual = analysis.eval_function(position).unwrap();
+        expect.assert_eq(&actual.unwrap_or_default());
+    }
+
+    #[test]
+    fn eval_integer_arithmetic() {
+        check(
+            r#"
+const fn mix(a: u32, b: u32) -> u32 {
+    let shifted = a << 4;
+    if b > 3 { shifted | b } else { shifted ^ b }
+}
+fn main() {
+    let x = mi$0x(2, 5);
+}
+"#,
+            expect![[r#"37 (0x25)"#]],
+        );
+    }
+
+    #[test]
+    fn eval_const_item() {
+        check(
+            r#"
+const fn double(x: i64) -> i64 { x * 2 }
+const DOUBLED: i64 = double(-21);
+fn main() {
+    let x = DOUB$0LED;
+}
+"#,
+            expect![[r#"-42"#]],
+        );
+    }
+
+    #[test]
+    fn eval_reports_unsupported_construct() {
+        check(
+            r#"
+struct Vec;
+impl Vec {
+    fn new() -> Vec { Vec }
+}
+const fn make() -> usize {
+    Vec::new();
+    0
+}
+fn main() {
+    let v = ma$0ke();
+}
+"#,
+            expect![[r#"evaluation failed: calling a non-const fn is not supported"#]],
+        );
+    }
+}
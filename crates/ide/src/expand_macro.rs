@@ -2,13 +2,17 @@ use std::iter;
 
 use hir::Semantics;
 use ide_db::{helpers::pick_best_token, RootDatabase};
-use syntax::{ast, ted, AstNode, NodeOrToken, SyntaxKind, SyntaxKind::*, SyntaxNode, WalkEvent, T};
+use itertools::Itertools;
+use syntax::{ast, AstNode, NodeOrToken, SyntaxKind, SyntaxKind::*, SyntaxNode, WalkEvent, T};
 
 use crate::FilePosition;
 
 pub struct ExpandedMacro {
     pub name: String,
     pub expansion: String,
+    /// The errors reported while expanding the macro call and the calls nested in it, in source
+    /// order, each prefixed with the name of the failing macro.
+    pub errors: Vec<String>,
 }
 
 // Feature: Expand Macro Recursively
@@ -31,57 +35,52 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
         _ => 0,
     })?;
     let mut expanded = None;
-    let mut name = None;
     for node in tok.ancestors() {
-        if let Some(item) = ast::Item::cast(node.clone()) {
-            expanded = sema.expand_attr_macro(&item);
+        if let Some(mac) = ast::MacroCall::cast(node.clone()) {
+            expanded = sema.expand_recursive(mac, None);
+            break;
+        }
+
+        if let Some(item) = ast::Item::cast(node) {
+            expanded = sema.expand_recursive(item, None);
             if expanded.is_some() {
-                // FIXME: add the macro name
-                // FIXME: make this recursive too
-                name = Some("?".to_string());
                 break;
             }
         }
-
-        if let Some(mac) = ast::MacroCall::cast(node) {
-            name = Some(mac.path()?.segment()?.name_ref()?.to_string());
-            expanded = expand_macro_recur(&sema, &mac);
-            break;
-        }
     }
 
     // FIXME:
     // macro expansion may lose all white space information
     // But we hope someday we can use ra_fmt for that
-    let expansion = insert_whitespaces(expanded?);
-    Some(ExpandedMacro { name: name?, expansion })
+    let expanded = expanded?;
+    let mut errors = Vec::new();
+    collect_errors(&expanded, &mut errors);
+    Some(ExpandedMacro { expansion: expanded_text(&expanded), name: expanded.name, errors })
 }
 
-fn expand_macro_recur(
-    sema: &Semantics<RootDatabase>,
-    macro_call: &ast::MacroCall,
-) -> Option<SyntaxNode> {
-    let expanded = sema.expand(macro_call)?.clone_for_update();
-
-    let children = expanded.descendants().filter_map(ast::MacroCall::cast);
-    let mut replacements = Vec::new();
-
-    for child in children {
-        if let Some(new_node) = expand_macro_recur(sema, &child) {
-            // check if the whole original syntax is replaced
-            if expanded == *child.syntax() {
-                return Some(new_node);
-            }
-            replacements.push((child, new_node));
-        }
+fn collect_errors(expanded: &hir::ExpandedMacro, acc: &mut Vec<String>) {
+    if let Some(error) = &expanded.error {
+        acc.push(format!("{}!: {}", expanded.name, error));
+    }
+    for child in &expanded.children {
+        collect_errors(child, acc);
     }
+}
 
-    replacements.into_iter().rev().for_each(|(old, new)| ted::replace(old.syntax(), new));
-    Some(expanded)
+/// Returns the fully expanded, formatted text.
+///
+/// For the root of an item's derives, this is the text of the derive expansions, one after
+/// another, without the item.
+pub(crate) fn expanded_text(expanded: &hir::ExpandedMacro) -> String {
+    if expanded.def.is_some() {
+        return insert_whitespaces(expanded.expanded_syntax());
+    }
+    expanded.children.iter().map(expanded_text).join("\n")
 }
 
 // FIXME: It would also be cool to share logic here and in the mbe tests,
 // which are pretty unreadable at the moment.
+/// Formats the tokens of `syn`, which may have lost all whitespace information in expansion.
 fn insert_whitespaces(syn: SyntaxNode) -> String {
     let mut res = String::new();
     let mut token_iter = syn
@@ -155,16 +154,51 @@ fn insert_whitespaces(syn: SyntaxNode) -> String {
 #[cfg(test)]
 mod tests {
     use expect_test::{expect, Expect};
+    use hir::{ExpandedMacro, Semantics};
+    use stdx::format_to;
+    use syntax::{algo::find_node_at_offset, ast, AstNode};
 
     use crate::fixture;
 
+    use super::insert_whitespaces;
+
     fn check(ra_fixture: &str, expect: Expect) {
         let (analysis, pos) = fixture::position(ra_fixture);
         let expansion = analysis.expand_macro(pos).unwrap().unwrap();
-        let actual = format!("{}\n{}", expansion.name, expansion.expansion);
+        let mut actual = format!("{}\n{}", expansion.name, expansion.expansion);
+        for error in &expansion.errors {
+            format_to!(actual, "\nerror: {}", error);
+        }
         expect.assert_eq(&actual);
     }
 
+    fn check_levels(ra_fixture: &str, depth: Option<usize>, expect: Expect) {
+        let (analysis, pos) = fixture::position(ra_fixture);
+        let sema = Semantics::new(&*analysis.db);
+        let file = sema.parse(pos.file_id);
+        let mac = find_node_at_offset::<ast::MacroCall>(file.syntax(), pos.offset).unwrap();
+        let expanded = sema.expand_recursive(mac, depth).unwrap();
+
+        let mut actual = String::new();
+        format_level(&mut actual, &expanded, 0);
+        expect.assert_eq(&actual);
+
+        fn format_level(buf: &mut String, expanded: &ExpandedMacro, indent: usize) {
+            let text = match expanded.def {
+                Some(_) => insert_whitespaces(expanded.expansion.clone()),
+                None => expanded.expansion.to_string(),
+            };
+            format_to!(buf, "{}{}: {:?}", "  ".repeat(indent), expanded.name, text);
+            if let Some(error) = &expanded.error {
+                format_to!(buf, " (error: {})", error);
+            }
+            buf.push('\n');
+            for child in &expanded.children {
+                format_level(buf, child, indent + 1);
+            }
+        }
+    }
+
     #[test]
     fn macro_expand_recursive_expansion() {
         check(
@@ -289,7 +323,8 @@ fn main() {
 "#,
             expect![[r#"
                 foo
-            "#]],
+
+                error: bar!: expected leaf: `BAD`"#]],
         );
     }
 
@@ -314,4 +349,71 @@ fn main() {
                 0 "#]],
         );
     }
+
+    #[test]
+    fn macro_expand_levels_depth_one() {
+        check_levels(
+            r#"
+macro_rules! bar { () => { fn b() {} } }
+macro_rules! baz { (x) => {} }
+macro_rules! foo { () => { bar!(); baz!(); fn a() {} } }
+f$0oo!();
+"#,
+            Some(1),
+            expect![[r#"
+                foo: "bar!();\nbaz!();\nfn a(){}\n"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn macro_expand_levels_full() {
+        check_levels(
+            r#"
+macro_rules! bar { () => { fn b() {} } }
+macro_rules! baz { (x) => {} }
+macro_rules! foo { () => { bar!(); baz!(); fn a() {} } }
+f$0oo!();
+"#,
+            None,
+            expect![[r#"
+                foo: "bar!();\nbaz!();\nfn a(){}\n"
+                  bar: "fn b(){}\n"
+                  baz: "" (error: expected leaf: `x`)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn macro_expand_reports_nested_errors() {
+        check(
+            r#"
+macro_rules! bar { () => { fn b() {} } }
+macro_rules! baz { (x) => {} }
+macro_rules! foo { () => { bar!(); baz!(); fn a() {} } }
+f$0oo!();
+"#,
+            expect![[r#"
+                foo
+                fn b(){}
+                fn a(){}
+
+                error: baz!: expected leaf: `x`"#]],
+        );
+    }
+
+    #[test]
+    fn macro_expand_derive() {
+        check(
+            r#"
+//- minicore: clone, derive
+#[derive(Clo$0ne)]
+struct Foo;
+"#,
+            expect![[r#"
+                derive
+                impl< >core::clone::Clone for Foo< >{}
+            "#]],
+        );
+    }
 }
@@ -4,7 +4,7 @@ use hir::Semantics;
 use ide_db::RootDatabase;
 use syntax::{
     algo::{self, skip_trivia_token},
-    ast::{self, AstNode, AstToken},
+    ast::{self, AstNode, AstToken, IsString},
     Direction, NodeOrToken,
     SyntaxKind::{self, *},
     SyntaxNode, SyntaxToken, TextRange, TextSize, TokenAtOffset, T,
@@ -83,6 +83,11 @@ fn try_extend_selection(
     let node = match root.covering_element(range) {
         NodeOrToken::Token(token) => {
             if token.text_range() != range {
+                if let Some(contents) = string_contents(&token) {
+                    if contents.contains_range(range) && contents != range {
+                        return Some(contents);
+                    }
+                }
                 return Some(token.text_range());
             }
             if let Some(comment) = ast::Comment::cast(token.clone()) {
@@ -105,6 +110,13 @@ fn try_extend_selection(
     }
 
     if node.text_range() != range {
+        if let Some(arg_list) = ast::ArgList::cast(node.clone()) {
+            if let Some(args) = arg_list_contents(&arg_list) {
+                if args.contains_range(range) && args != range {
+                    return Some(args);
+                }
+            }
+        }
         return Some(node.text_range());
     }
 
@@ -218,6 +230,25 @@ fn extend_single_word_in_comment_or_string(
     }
 }
 
+/// The range of a string literal without its quotes.
+fn string_contents(token: &SyntaxToken) -> Option<TextRange> {
+    match token.kind() {
+        STRING => ast::String::cast(token.clone())?.text_range_between_quotes(),
+        BYTE_STRING => ast::ByteString::cast(token.clone())?.text_range_between_quotes(),
+        _ => None,
+    }
+}
+
+/// The range of the arguments, without the parentheses and surrounding whitespace.
+fn arg_list_contents(arg_list: &ast::ArgList) -> Option<TextRange> {
+    let first = skip_trivia_token(arg_list.l_paren_token()?.next_token()?, Direction::Next)?;
+    let last = skip_trivia_token(arg_list.r_paren_token()?.prev_token()?, Direction::Prev)?;
+    if first.text_range().start() >= last.text_range().end() {
+        return None;
+    }
+    Some(first.text_range().cover(last.text_range()))
+}
+
 fn extend_ws(root: &SyntaxNode, ws: SyntaxToken, offset: TextSize) -> TextRange {
     let ws_text = ws.text();
     let suffix = TextRange::new(offset, ws.text_range().end()) - ws.text_range().start();
@@ -496,7 +527,50 @@ fn bar(){}
 
 " fn f$0oo() {"
 "#,
-            &["foo", "\" fn foo() {\""],
+            &["foo", " fn foo() {", "\" fn foo() {\""],
+        );
+    }
+
+    #[test]
+    fn test_extend_selection_string_contents() {
+        do_check(
+            r#"fn main() { let s = "hello wo$0rld"; }"#,
+            &["world", "hello world", "\"hello world\"", "let s = \"hello world\";"],
+        );
+        do_check(
+            r#"fn main() { let s = b" x$0y "; }"#,
+            &["xy", " xy ", "b\" xy \"", "let s = b\" xy \";"],
+        );
+    }
+
+    #[test]
+    fn test_extend_selection_arg_list() {
+        do_check(
+            r#"fn main() { foo(a, b$0 + 1, c); }"#,
+            &["b", "b + 1", "b + 1, ", "a, b + 1, c", "(a, b + 1, c)", "foo(a, b + 1, c)"],
+        );
+        do_check(
+            r#"fn main() { foo( a, b, c$0 ); }"#,
+            &["c", ", c", "a, b, c", "( a, b, c )", "foo( a, b, c )"],
+        );
+        do_check(r#"fn main() { foo(a$0); }"#, &["a", "(a)", "foo(a)"]);
+    }
+
+    #[test]
+    fn test_extend_selection_match_arm() {
+        do_check(
+            r#"fn main() { match x { Some(y$0) => 1, None => 2 } }"#,
+            &[
+                "y",
+                "Some(y)",
+                "Some(y) => 1,",
+                "{ Some(y) => 1, None => 2 }",
+                "match x { Some(y) => 1, None => 2 }",
+            ],
+        );
+        do_check(
+            r#"fn main() { match x { Some(y) => 1, N$0one if f() => 2 } }"#,
+            &["None", "None if f() => 2", "{ Some(y) => 1, None if f() => 2 }"],
         );
     }
 
@@ -156,13 +156,15 @@ fn structure_node(node: &SyntaxNode) -> Option<StructureNode> {
             ast::Static(it) => decl_with_type_ref(&it, it.ty(), StructureNodeKind::SymbolKind(SymbolKind::Static)),
             ast::Impl(it) => {
                 let target_type = it.self_ty()?;
-                let target_trait = it.trait_();
-                let label = match target_trait {
-                    None => format!("impl {}", target_type.syntax().text()),
-                    Some(t) => {
-                        format!("impl {} for {}", t.syntax().text(), target_type.syntax().text(),)
+                let mut label = String::from("impl ");
+                if let Some(target_trait) = it.trait_() {
+                    if it.excl_token().is_some() {
+                        label.push('!');
                     }
-                };
+                    collapse_ws(target_trait.syntax(), &mut label);
+                    label.push_str(" for ");
+                }
+                collapse_ws(target_type.syntax(), &mut label);
 
                 let node = StructureNode {
                     parent: None,
@@ -576,4 +578,162 @@ fn g() {}
             "#]],
         );
     }
+
+    #[test]
+    fn test_file_structure_nests_impl_items() {
+        check(
+            r#"
+struct Foo<T>(T);
+
+impl<T> Foo<T> {
+    fn new(t: T) -> Self { Foo(t) }
+}
+
+impl<T: fmt::Display> fmt::Display
+    for Foo<T>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { Ok(()) }
+}
+
+impl !Send for Foo<()> {}
+
+mod outer {
+    mod inner {
+        macro_rules! m { () => {} }
+        const C: u8 = 0;
+    }
+}
+"#,
+            expect![[r#"
+                [
+                    StructureNode {
+                        parent: None,
+                        label: "Foo",
+                        navigation_range: 8..11,
+                        node_range: 1..18,
+                        kind: SymbolKind(
+                            Struct,
+                        ),
+                        detail: None,
+                        deprecated: false,
+                    },
+                    StructureNode {
+                        parent: None,
+                        label: "impl Foo<T>",
+                        navigation_range: 28..34,
+                        node_range: 20..74,
+                        kind: SymbolKind(
+                            Impl,
+                        ),
+                        detail: None,
+                        deprecated: false,
+                    },
+                    StructureNode {
+                        parent: Some(
+                            1,
+                        ),
+                        label: "new",
+                        navigation_range: 44..47,
+                        node_range: 41..72,
+                        kind: SymbolKind(
+                            Function,
+                        ),
+                        detail: Some(
+                            "fn(t: T) -> Self",
+                        ),
+                        deprecated: false,
+                    },
+                    StructureNode {
+                        parent: None,
+                        label: "impl fmt::Display for Foo<T>",
+                        navigation_range: 119..125,
+                        node_range: 76..201,
+                        kind: SymbolKind(
+                            Impl,
+                        ),
+                        detail: None,
+                        deprecated: false,
+                    },
+                    StructureNode {
+                        parent: Some(
+                            3,
+                        ),
+                        label: "fmt",
+                        navigation_range: 135..138,
+                        node_range: 132..199,
+                        kind: SymbolKind(
+                            Function,
+                        ),
+                        detail: Some(
+                            "fn(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result",
+                        ),
+                        deprecated: false,
+                    },
+                    StructureNode {
+                        parent: None,
+                        label: "impl !Send for Foo<()>",
+                        navigation_range: 218..225,
+                        node_range: 203..228,
+                        kind: SymbolKind(
+                            Impl,
+                        ),
+                        detail: None,
+                        deprecated: false,
+                    },
+                    StructureNode {
+                        parent: None,
+                        label: "outer",
+                        navigation_range: 234..239,
+                        node_range: 230..326,
+                        kind: SymbolKind(
+                            Module,
+                        ),
+                        detail: None,
+                        deprecated: false,
+                    },
+                    StructureNode {
+                        parent: Some(
+                            6,
+                        ),
+                        label: "inner",
+                        navigation_range: 250..255,
+                        node_range: 246..324,
+                        kind: SymbolKind(
+                            Module,
+                        ),
+                        detail: None,
+                        deprecated: false,
+                    },
+                    StructureNode {
+                        parent: Some(
+                            7,
+                        ),
+                        label: "m",
+                        navigation_range: 279..280,
+                        node_range: 266..293,
+                        kind: SymbolKind(
+                            Macro,
+                        ),
+                        detail: None,
+                        deprecated: false,
+                    },
+                    StructureNode {
+                        parent: Some(
+                            7,
+                        ),
+                        label: "C",
+                        navigation_range: 308..309,
+                        node_range: 302..318,
+                        kind: SymbolKind(
+                            Const,
+                        ),
+                        detail: Some(
+                            "u8",
+                        ),
+                        deprecated: false,
+                    },
+                ]
+            "#]],
+        );
+    }
 }
@@ -1,4 +1,5 @@
 //! Utilities for creating `Analysis` instances for tests.
+use hir::db::DefDatabase;
 use ide_db::base_db::fixture::ChangeFixture;
 use test_utils::{extract_annotations, RangeOrOffset};
 
@@ -12,9 +13,25 @@ pub(crate) fn file(ra_fixture: &str) -> (Analysis, FileId) {
     (host.analysis(), change_fixture.files[0])
 }
 
+/// Like [`file`], but returns the `AnalysisHost` itself, for tests needing mutable access.
+pub(crate) fn host_and_file(ra_fixture: &str) -> (AnalysisHost, FileId) {
+    let mut host = AnalysisHost::default();
+    let change_fixture = ChangeFixture::parse(ra_fixture);
+    host.db.apply_change(change_fixture.change);
+    (host, change_fixture.files[0])
+}
+
 /// Creates analysis from a multi-file fixture, returns positions marked with $0.
 pub(crate) fn position(ra_fixture: &str) -> (Analysis, FilePosition) {
-    let mut host = AnalysisHost::default();
+    position_in(AnalysisHost::default(), ra_fixture)
+}
+
+/// Like [`position`], but also expands the attribute proc macros of the fixture.
+pub(crate) fn position_with_proc_macros(ra_fixture: &str) -> (Analysis, FilePosition) {
+    position_in(host_with_proc_macros(), ra_fixture)
+}
+
+fn position_in(mut host: AnalysisHost, ra_fixture: &str) -> (Analysis, FilePosition) {
     let change_fixture = ChangeFixture::parse(ra_fixture);
     host.db.apply_change(change_fixture.change);
     let (file_id, range_or_offset) = change_fixture.file_position.expect("expected a marker ($0)");
@@ -60,3 +77,9 @@ pub(crate) fn annotations(ra_fixture: &str) -> (Analysis, FilePosition, Vec<(Fil
         .collect();
     (host.analysis(), FilePosition { file_id, offset }, annotations)
 }
+
+fn host_with_proc_macros() -> AnalysisHost {
+    let mut host = AnalysisHost::default();
+    host.raw_database_mut().set_enable_proc_attr_macros(true);
+    host
+}
@@ -1,10 +1,10 @@
 use rustc_hash::FxHashSet;
 
 use syntax::{
-    ast::{self, AstNode, AstToken},
+    ast::{self, AstNode, AstToken, AttrsOwner},
     match_ast, Direction, NodeOrToken, SourceFile,
     SyntaxKind::{self, *},
-    TextRange, TextSize,
+    SyntaxNode, TextRange, TextSize,
 };
 
 use std::hash::Hash;
@@ -25,6 +25,8 @@ pub enum FoldKind {
     Array,
     WhereClause,
     ReturnType,
+    MatchArm,
+    TestModule,
 }
 
 #[derive(Debug)]
@@ -36,7 +38,7 @@ pub struct Fold {
 // Feature: Folding
 //
 // Defines folding regions for curly braced blocks, runs of consecutive use, mod, const or static
-// items, and `region` / `endregion` comment markers.
+// items, multi-line match arms, `#[cfg(test)]` modules and `region` / `endregion` comment markers.
 pub(crate) fn folding_ranges(file: &SourceFile) -> Vec<Fold> {
     let mut res = vec![];
     let mut visited_comments = FxHashSet::default();
@@ -56,6 +58,12 @@ pub(crate) fn folding_ranges(file: &SourceFile) -> Vec<Fold> {
                 NodeOrToken::Token(token) => token.text().contains('\n'),
             };
             if is_multiline {
+                let kind = match &element {
+                    NodeOrToken::Node(node) if is_test_module_item_list(node) => {
+                        FoldKind::TestModule
+                    }
+                    _ => kind,
+                };
                 res.push(Fold { range: element.text_range(), kind });
                 continue;
             }
@@ -134,6 +142,7 @@ fn fold_kind(kind: SyntaxKind) -> Option<FoldKind> {
         ARG_LIST | PARAM_LIST => Some(FoldKind::ArgList),
         ARRAY_EXPR => Some(FoldKind::Array),
         RET_TYPE => Some(FoldKind::ReturnType),
+        MATCH_ARM => Some(FoldKind::MatchArm),
         ASSOC_ITEM_LIST
         | RECORD_FIELD_LIST
         | RECORD_PAT_FIELD_LIST
@@ -149,6 +158,17 @@ fn fold_kind(kind: SyntaxKind) -> Option<FoldKind> {
     }
 }
 
+fn is_test_module_item_list(node: &SyntaxNode) -> bool {
+    let module = match node.parent().and_then(ast::Module::cast) {
+        Some(it) if node.kind() == ITEM_LIST => it,
+        _ => return false,
+    };
+    module
+        .attrs()
+        .filter_map(|attr| attr.as_simple_call())
+        .any(|(name, tt)| name == "cfg" && tt.syntax().text() == "(test)")
+}
+
 fn contiguous_range_for_item_group<N>(first: N, visited: &mut FxHashSet<N>) -> Option<TextRange>
 where
     N: ast::VisibilityOwner + Clone + Hash + Eq,
@@ -300,6 +320,8 @@ mod tests {
                 FoldKind::Array => "array",
                 FoldKind::WhereClause => "whereclause",
                 FoldKind::ReturnType => "returntype",
+                FoldKind::MatchArm => "matcharm",
+                FoldKind::TestModule => "testmodule",
             };
             assert_eq!(kind, &attr.unwrap());
         }
@@ -457,6 +479,54 @@ fn main() <fold block>{
         );
     }
 
+    #[test]
+    fn fold_multiline_match_arms() {
+        check(
+            r#"
+fn main() <fold block>{
+    match 0 <fold block>{
+        0 => 0,
+        <fold matcharm>1 => frobnicate<fold arglist>(
+            1,
+            2,
+        )</fold>,</fold>
+        <fold matcharm>_ => <fold block>{
+            1
+        }</fold></fold>
+    }</fold>
+}</fold>
+"#,
+        );
+    }
+
+    #[test]
+    fn fold_test_module() {
+        check(
+            r#"
+#[cfg(test)]
+mod tests <fold testmodule>{
+    fn test() <fold block>{
+    }</fold>
+}</fold>
+
+#[cfg(feature = "test")]
+mod not_tests <fold block>{
+    fn foo() {}
+}</fold>
+"#,
+        );
+    }
+
+    #[test]
+    fn fold_nothing_for_single_line_items() {
+        check(
+            r#"
+mod tests { fn test() {} }
+fn foo<T>(x: T) -> u32 where T: Copy { match x { _ => frobnicate(1, 2) } }
+"#,
+        );
+    }
+
     #[test]
     fn fold_big_calls() {
         check(
@@ -5,10 +5,16 @@ use hir::{AsAssocItem, InFile, ModuleDef, Semantics};
 use ide_db::{
     base_db::{AnchoredPath, FileId, FileLoader},
     defs::{Definition, NameClass, NameRefClass},
-    helpers::{pick_best_token, try_resolve_derive_input_at},
+    helpers::{
+        format_string::{format_arg_names, is_format_string},
+        pick_best_token, try_resolve_derive_input_at,
+    },
     RootDatabase,
 };
-use syntax::{ast, match_ast, AstNode, AstToken, SyntaxKind::*, SyntaxToken, TextRange, T};
+use syntax::{
+    algo::skip_trivia_token, ast, match_ast, AstNode, AstToken, Direction, SyntaxKind::*,
+    SyntaxToken, TextRange, T,
+};
 
 use crate::{
     display::{ToNav, TryToNav},
@@ -53,6 +59,9 @@ pub(crate) fn goto_definition(
         let nav = resolve_doc_path_for_def(db, def, &link, ns)?.try_to_nav(db)?;
         return Some(RangeInfo::new(original_token.text_range(), vec![nav]));
     }
+    if let Some(string) = ast::String::cast(token.clone()).filter(is_format_string) {
+        return try_lookup_format_arg(&sema, &string, &original_token, position);
+    }
 
     let navs = match_ast! {
         match parent {
@@ -122,6 +131,62 @@ fn try_lookup_include_path_or_derive(
     }
 }
 
+/// Resolves the argument referenced at the cursor inside of a format string, either to a named
+/// `name = expr` argument of the macro call or to an implicitly captured variable.
+fn try_lookup_format_arg(
+    sema: &Semantics<RootDatabase>,
+    string: &ast::String,
+    original_token: &SyntaxToken,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let token_start = original_token.text_range().start();
+    let (range, name) = format_arg_names(string)
+        .into_iter()
+        .map(|(range, name)| (range + token_start, name))
+        .find(|(range, _)| range.contains_inclusive(position.offset))?;
+
+    let named_arg = original_token
+        .siblings_with_tokens(Direction::Next)
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == IDENT && it.text() == name && is_named_arg(it));
+    let navs = match named_arg {
+        Some(arg) => vec![NavigationTarget {
+            file_id: position.file_id,
+            full_range: arg.text_range(),
+            focus_range: Some(arg.text_range()),
+            name: name.into(),
+            kind: None,
+            container_name: None,
+            description: None,
+            docs: None,
+        }],
+        None => {
+            let path = ast::Path::parse(&name).ok()?;
+            let scope = sema.scope_at_offset(original_token, position.offset);
+            def_to_nav(sema.db, scope.speculative_resolve(&path)?.into())
+        }
+    };
+    Some(RangeInfo::new(range, navs))
+}
+
+/// Checks whether `ident` is the name of a `, name = expr` macro argument.
+fn is_named_arg(ident: &SyntaxToken) -> bool {
+    let next_token = |token: &SyntaxToken, direction| {
+        let token = match direction {
+            Direction::Next => token.next_token()?,
+            Direction::Prev => token.prev_token()?,
+        };
+        skip_trivia_token(token, direction)
+    };
+    let kind_of = |token: Option<SyntaxToken>| token.map(|it| it.kind());
+
+    let eq = next_token(ident, Direction::Next);
+    let after_eq = eq.as_ref().and_then(|eq| next_token(eq, Direction::Next));
+    kind_of(next_token(ident, Direction::Prev)) == Some(T![,])
+        && kind_of(eq) == Some(T![=])
+        && kind_of(after_eq) != Some(T![=])
+}
+
 /// finds the trait definition of an impl'd item
 /// e.g.
 /// ```rust
@@ -396,6 +461,24 @@ fn bar() {
         );
     }
 
+    #[test]
+    fn goto_def_through_duplicating_proc_macro() {
+        check(
+            r#"
+//- /main.rs crate:main deps:proc_macros
+fn foo() {}
+ //^^^
+
+fn bar() {
+    proc_macros::duplicate!(foo$0(););
+}
+//- /proc_macros.rs crate:proc_macros proc_macro:duplicate
+#[proc_macro]
+pub fn duplicate(input: TokenStream) -> TokenStream { input }
+"#,
+        );
+    }
+
     #[test]
     fn goto_definition_works_for_macro_inside_pattern() {
         check(
@@ -1417,4 +1500,82 @@ struct Foo;
             "#,
         );
     }
+
+    #[test]
+    fn goto_def_format_string_implicit_capture() {
+        check(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! format_args {
+    ($fmt:expr) => ({ /* compiler built-in */ });
+    ($fmt:expr, $($args:tt)*) => ({ /* compiler built-in */ })
+}
+macro_rules! print {
+    ($($arg:tt)*) => (format_args!($($arg)*))
+}
+fn main() {
+    let name = 92;
+      //^^^^
+    print!("{{escaped}} {na$0me}");
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_format_string_named_argument() {
+        check(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! format_args {
+    ($fmt:expr) => ({ /* compiler built-in */ });
+    ($fmt:expr, $($args:tt)*) => ({ /* compiler built-in */ })
+}
+macro_rules! print {
+    ($($arg:tt)*) => (format_args!($($arg)*))
+}
+fn main() {
+    let value = 92;
+    print!("{:>wid$0th$}", value, width = 4);
+                              //^^^^^
+}
+"#,
+        );
+        check(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! format_args {
+    ($fmt:expr) => ({ /* compiler built-in */ });
+    ($fmt:expr, $($args:tt)*) => ({ /* compiler built-in */ })
+}
+macro_rules! print {
+    ($($arg:tt)*) => (format_args!($($arg)*))
+}
+fn main() {
+    print!("{val$0ue}", value = 92);
+                    //^^^^^
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn goto_def_format_string_positional_argument() {
+        let (analysis, position) = fixture::position(
+            r#"
+#[rustc_builtin_macro]
+macro_rules! format_args {
+    ($fmt:expr) => ({ /* compiler built-in */ });
+    ($fmt:expr, $($args:tt)*) => ({ /* compiler built-in */ })
+}
+macro_rules! print {
+    ($($arg:tt)*) => (format_args!($($arg)*))
+}
+fn main() {
+    print!("{0$0}", 92);
+}
+"#,
+        );
+        assert!(analysis.goto_definition(position).unwrap().is_none());
+    }
 }
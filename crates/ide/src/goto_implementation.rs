@@ -75,7 +75,10 @@ pub(crate) fn goto_implementation(
 }
 
 fn impls_for_ty(sema: &Semantics<RootDatabase>, ty: hir::Type) -> Vec<NavigationTarget> {
-    Impl::all_for_type(sema.db, ty).into_iter().filter_map(|imp| imp.try_to_nav(sema.db)).collect()
+    ty.applicable_impls(sema.db)
+        .into_iter()
+        .filter_map(|(_, imp)| imp.try_to_nav(sema.db))
+        .collect()
 }
 
 fn impls_for_trait(sema: &Semantics<RootDatabase>, trait_: hir::Trait) -> Vec<NavigationTarget> {
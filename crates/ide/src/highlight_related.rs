@@ -31,7 +31,7 @@ pub struct HighlightRelatedConfig {
 // Highlights constructs related to the thing under the cursor:
 // - if on an identifier, highlights all references to that identifier in the current file
 // - if on an `async` or `await token, highlights all yield points for that async context
-// - if on a `return` token, `?` character or `->` return type arrow, highlights all exit points for that context
+// - if on a `return` or `fn` token, `?` character or `->` return type arrow, highlights all exit points for that context
 // - if on a `break`, `loop`, `while` or `for` token, highlights all break points for that loop or block context
 pub(crate) fn highlight_related(
     sema: &Semantics<RootDatabase>,
@@ -46,6 +46,7 @@ pub(crate) fn highlight_related(
         T![await]
         | T![async]
         | T![return]
+        | T![fn]
         | T![break]
         | T![loop]
         | T![for]
@@ -56,6 +57,12 @@ pub(crate) fn highlight_related(
 
     match token.kind() {
         T![return] | T![?] | T![->] if config.exit_points => highlight_exit_points(sema, token),
+        T![fn]
+            if config.exit_points
+                && token.parent().map_or(false, |it| ast::Fn::can_cast(it.kind())) =>
+        {
+            highlight_exit_points(sema, token)
+        }
         T![await] | T![async] if config.yield_points => highlight_yield_points(token),
         T![break] | T![loop] | T![for] | T![while] if config.break_points => {
             highlight_break_points(token)
@@ -474,6 +481,46 @@ fn foo() ->$0 u32 {
         );
     }
 
+    #[test]
+    fn test_hl_exit_points_fn_keyword() {
+        check(
+            r#"
+fn$0 foo() -> u32 {
+    let f = || { return 1; };
+    if true {
+        return 0;
+     // ^^^^^^
+    }
+
+    0?;
+  // ^
+    0xDEAD_BEEF
+ // ^^^^^^^^^^^
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_hl_exit_points_async_fn() {
+        check(
+            r#"
+async fn$0 foo() -> u32 {
+    let inner = async {
+        0?;
+        return 1;
+    };
+    if true {
+        return inner.await;
+     // ^^^^^^
+    }
+    0
+ // ^
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_hl_prefer_ref_over_tail_exit() {
         check(
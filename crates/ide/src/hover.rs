@@ -22,6 +22,7 @@ use crate::{
         doc_attributes, extract_definitions_from_docs, remove_links, resolve_doc_path_for_def,
         rewrite_links,
     },
+    expand_macro,
     markdown_remove::remove_markdown,
     markup::Markup,
     runnables::{runnable_fn, runnable_mod},
@@ -32,6 +33,16 @@ use crate::{
 pub struct HoverConfig {
     pub links_in_hover: bool,
     pub documentation: Option<HoverDocFormat>,
+    /// Whether to show a preview of the expansion when hovering a macro call.
+    pub macro_expansion: bool,
+    /// Whether to show the inferred generic arguments when hovering a generic function call.
+    pub generic_args: bool,
+    /// Whether to show the types a local or expression auto-derefs to.
+    pub deref_chain: bool,
+    /// Documentation longer than this many lines is truncated.
+    pub max_doc_lines: Option<usize>,
+    /// Types nested deeper than this are truncated with `…`.
+    pub max_type_depth: Option<usize>,
 }
 
 impl HoverConfig {
@@ -52,6 +63,8 @@ pub enum HoverAction {
     Implementation(FilePosition),
     Reference(FilePosition),
     GoToType(Vec<HoverGotoTypeData>),
+    /// Evaluates the const fn call or constant at the position.
+    EvalFunction(FilePosition),
 }
 
 impl HoverAction {
@@ -174,9 +187,35 @@ pub(crate) fn hover(
             }
             _ => None,
         };
-        if let Some(markup) = hover_for_definition(db, definition, famous_defs.as_ref(), config) {
+        let krate = sema.scope(&node).krate();
+        if let Some(markup) =
+            hover_for_definition(db, definition, famous_defs.as_ref(), krate, config)
+        {
             let mut res = HoverResult::default();
             res.markup = process_markup(sema.db, definition, &markup, config);
+            if config.macro_expansion && matches!(definition, Definition::Macro(_)) {
+                // The expansion has no links to rewrite, so it's appended after processing.
+                if let Some(preview) = macro_expansion_preview(&sema, &node) {
+                    res.markup =
+                        format!("{}\n___\n\nExpansion:\n\n```rust\n{}\n```", res.markup, preview)
+                            .into();
+                }
+            }
+            if config.generic_args {
+                if let Definition::ModuleDef(hir::ModuleDef::Function(func)) = definition {
+                    if let Some(generics) = call_generics(&sema, func, &node) {
+                        res.markup =
+                            format!("{}\n___\n\ncalled with `{}`", res.markup, generics).into();
+                    }
+                }
+            }
+            if config.deref_chain {
+                if let Definition::Local(local) = definition {
+                    if let Some(chain) = deref_chain(db, &local.ty(db), config) {
+                        res.markup = format!("{}\n___\n\nderefs to `{}`", res.markup, chain).into();
+                    }
+                }
+            }
             if let Some(action) = show_implementations_action(db, definition) {
                 res.actions.push(action);
             }
@@ -193,6 +232,12 @@ pub(crate) fn hover(
                 res.actions.push(action);
             }
 
+            if let Some(action) =
+                eval_function_action(db, definition, &node, FilePosition { file_id, offset })
+            {
+                res.actions.push(action);
+            }
+
             let range = range_override.unwrap_or_else(|| sema.original_range(&node).range);
             return Some(RangeInfo::new(range, res));
         }
@@ -225,6 +270,81 @@ pub(crate) fn hover(
     Some(RangeInfo::new(range, res))
 }
 
+/// How many lines of a macro expansion are shown when hovering a macro call.
+const MACRO_EXPANSION_PREVIEW_LINES: usize = 10;
+
+/// Returns the first few lines of the expansion of the macro call `node` is
+/// the name of.
+fn macro_expansion_preview(sema: &Semantics<RootDatabase>, node: &SyntaxNode) -> Option<String> {
+    let macro_call = node.ancestors().find_map(ast::MacroCall::cast)?;
+    // Only hovering the macro's name should show the expansion, not hovering
+    // some macro inside of its arguments.
+    if !macro_call.path()?.syntax().text_range().contains_range(node.text_range()) {
+        return None;
+    }
+    let expansion = expand_macro::expanded_text(&sema.expand_recursive(macro_call, None)?);
+    let expansion = expansion.trim();
+    if expansion.is_empty() {
+        return None;
+    }
+
+    let mut lines = expansion.lines();
+    let mut preview = lines.by_ref().take(MACRO_EXPANSION_PREVIEW_LINES).join("\n");
+    if lines.next().is_some() {
+        preview.push_str("\n// …");
+    }
+    Some(preview)
+}
+
+/// Renders the inferred generic arguments of `func` like `T = String, U = u8`, if `node` is the
+/// name of the function at a call site.
+fn call_generics(
+    sema: &Semantics<RootDatabase>,
+    func: hir::Function,
+    node: &SyntaxNode,
+) -> Option<String> {
+    let name_ref = ast::NameRef::cast(node.clone())?;
+    let call = match name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+        Some(method_call) => ast::Expr::from(method_call),
+        None => {
+            let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+            let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+            if call.expr()?.syntax() != path_expr.syntax() {
+                return None;
+            }
+            call.into()
+        }
+    };
+
+    // The parameters of the parent impl or trait come before the function's own.
+    let parent = func.as_assoc_item(sema.db).map(|it| match it.container(sema.db) {
+        hir::AssocItemContainer::Trait(it) => hir::GenericDef::from(it),
+        hir::AssocItemContainer::Impl(it) => hir::GenericDef::from(it),
+    });
+    let params: Vec<_> = parent
+        .into_iter()
+        .chain(Some(hir::GenericDef::from(func)))
+        .flat_map(|def| def.type_params(sema.db))
+        .collect();
+    let args = sema.resolve_method_call_generics(&call)?;
+    if params.len() != args.len() {
+        return None;
+    }
+    let generics = params
+        .into_iter()
+        .zip(args)
+        .map(|(param, arg)| (param.name(sema.db), arg))
+        .filter(|(name, arg)| {
+            *name != hir::known::SELF_TYPE && *name != hir::Name::missing() && !arg.is_unknown()
+        })
+        .map(|(name, arg)| format!("{} = {}", name, arg.display(sema.db)))
+        .join(", ");
+    if generics.is_empty() {
+        return None;
+    }
+    Some(generics)
+}
+
 fn hover_ranged(
     file: &SyntaxNode,
     range: syntax::TextRange,
@@ -270,8 +390,8 @@ fn hover_type_info(
 
     res.markup = if let Some(adjusted_ty) = adjusted {
         walk_and_push_ty(sema.db, &adjusted_ty, &mut push_new_def);
-        let original = original.display(sema.db).to_string();
-        let adjusted = adjusted_ty.display(sema.db).to_string();
+        let original = original.display_truncated(sema.db, config.max_type_depth).to_string();
+        let adjusted = adjusted_ty.display_truncated(sema.db, config.max_type_depth).to_string();
         format!(
             "```text\nType: {:>apad$}\nCoerced to: {:>opad$}\n```\n",
             uncoerced = original,
@@ -282,16 +402,43 @@ fn hover_type_info(
         )
         .into()
     } else {
+        let original = original.display_truncated(sema.db, config.max_type_depth);
         if config.markdown() {
-            Markup::fenced_block(&original.display(sema.db))
+            Markup::fenced_block(&original)
         } else {
-            original.display(sema.db).to_string().into()
+            original.to_string().into()
         }
     };
+    if config.deref_chain {
+        if let Some(chain) = deref_chain(sema.db, &original, config) {
+            res.markup = format!("{}\n___\n\nderefs to `{}`", res.markup, chain).into();
+        }
+    }
     res.actions.push(HoverAction::goto_type_from_targets(sema.db, targets));
     Some(res)
 }
 
+/// Renders the types `ty` auto-derefs to, like `Box<Foo> -> Foo`, or `None` if `ty` doesn't
+/// implement `Deref`.
+fn deref_chain(db: &RootDatabase, ty: &hir::Type, config: &HoverConfig) -> Option<String> {
+    const MAX_DEREF_STEPS: usize = 5;
+
+    let mut steps = ty.autoderef(db).skip(1).take(MAX_DEREF_STEPS + 1).collect::<Vec<_>>();
+    if steps.is_empty() {
+        return None;
+    }
+    let truncated = steps.len() > MAX_DEREF_STEPS;
+    steps.truncate(MAX_DEREF_STEPS);
+    let mut chain = steps
+        .iter()
+        .map(|it| it.display_truncated(db, config.max_type_depth).to_string())
+        .join(" -> ");
+    if truncated {
+        chain.push_str(" -> …");
+    }
+    Some(chain)
+}
+
 fn try_hover_for_lint(attr: &ast::Attr, token: &SyntaxToken) -> Option<RangeInfo<HoverResult>> {
     let (path, tt) = attr.as_simple_call()?;
     if !tt.syntax().text_range().contains(token.text_range().start()) {
@@ -417,6 +564,29 @@ fn goto_type_action_for_def(db: &RootDatabase, def: Definition) -> Option<HoverA
     Some(HoverAction::goto_type_from_targets(db, targets))
 }
 
+fn eval_function_action(
+    db: &RootDatabase,
+    def: Definition,
+    node: &SyntaxNode,
+    position: FilePosition,
+) -> Option<HoverAction> {
+    match def {
+        Definition::ModuleDef(hir::ModuleDef::Const(_)) => {}
+        Definition::ModuleDef(hir::ModuleDef::Function(it)) if it.is_const(db) => {
+            // Only calls can be evaluated, not the function itself.
+            ast::NameRef::cast(node.clone())?
+                .syntax()
+                .ancestors()
+                .take_while(|it| !ast::Item::can_cast(it.kind()))
+                .find(|it| {
+                    ast::CallExpr::can_cast(it.kind()) || ast::MethodCallExpr::can_cast(it.kind())
+                })?;
+        }
+        _ => return None,
+    }
+    Some(HoverAction::EvalFunction(position))
+}
+
 fn walk_and_push_ty(
     db: &RootDatabase,
     ty: &hir::Type,
@@ -451,6 +621,30 @@ fn hover_markup(docs: Option<String>, desc: String, mod_path: Option<String>) ->
     Some(buf.into())
 }
 
+/// Cuts `docs` down to `max_lines` lines, closing a code block that would be
+/// left open so that the rest of the markup still renders properly.
+fn truncate_docs(docs: String, max_lines: Option<usize>) -> String {
+    let max_lines = match max_lines {
+        Some(it) if docs.lines().count() > it => it,
+        _ => return docs,
+    };
+
+    let mut res = String::new();
+    let mut in_code_block = false;
+    for line in docs.lines().take(max_lines) {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        res.push_str(line);
+        res.push('\n');
+    }
+    if in_code_block {
+        res.push_str("```\n");
+    }
+    res.push_str("\n… (truncated)");
+    res
+}
+
 fn process_markup(
     db: &RootDatabase,
     def: Definition,
@@ -507,6 +701,7 @@ fn hover_for_definition(
     db: &RootDatabase,
     def: Definition,
     famous_defs: Option<&FamousDefs>,
+    krate: Option<hir::Crate>,
     config: &HoverConfig,
 ) -> Option<Markup> {
     let mod_path = definition_mod_path(db, &def);
@@ -521,29 +716,34 @@ fn hover_for_definition(
         Definition::Field(def) => label_and_docs(db, def),
         Definition::ModuleDef(it) => match it {
             hir::ModuleDef::Module(it) => label_and_docs(db, it),
-            hir::ModuleDef::Function(it) => label_and_docs(db, it),
+            hir::ModuleDef::Function(it) => function_label_and_docs(db, it),
             hir::ModuleDef::Adt(it) => label_and_docs(db, it),
             hir::ModuleDef::Variant(it) => label_and_docs(db, it),
             hir::ModuleDef::Const(it) => label_and_docs(db, it),
             hir::ModuleDef::Static(it) => label_and_docs(db, it),
             hir::ModuleDef::Trait(it) => label_and_docs(db, it),
-            hir::ModuleDef::TypeAlias(it) => label_and_docs(db, it),
+            hir::ModuleDef::TypeAlias(it) => type_alias_label_and_docs(db, it),
             hir::ModuleDef::BuiltinType(it) => {
                 return famous_defs
                     .and_then(|fd| hover_for_builtin(fd, it))
                     .or_else(|| Some(Markup::fenced_block(&it.name())))
             }
         },
-        Definition::Local(it) => return hover_for_local(it, db),
+        Definition::Local(it) => return hover_for_local(it, db, config),
         Definition::SelfType(impl_def) => {
             impl_def.self_ty(db).as_adt().map(|adt| label_and_docs(db, adt))?
         }
         Definition::GenericParam(it) => label_and_docs(db, it),
         Definition::Label(it) => return Some(Markup::fenced_block(&it.name(db))),
     };
+    let label = match krate.and_then(|krate| cfg_label(db, def, krate)) {
+        Some(cfg) => format!("{}\n{}", cfg, label),
+        None => label,
+    };
 
     return hover_markup(
-        docs.filter(|_| config.documentation.is_some()).map(Into::into),
+        docs.filter(|_| config.documentation.is_some())
+            .map(|docs| truncate_docs(docs.into(), config.max_doc_lines)),
         label,
         mod_path,
     );
@@ -556,11 +756,69 @@ fn hover_for_definition(
         let docs = def.attrs(db).docs();
         (label, docs)
     }
+
+    fn function_label_and_docs(
+        db: &RootDatabase,
+        it: hir::Function,
+    ) -> (String, Option<hir::Documentation>) {
+        let (label, docs) = label_and_docs(db, it);
+        // Attributes affecting code generation are of interest when looking into performance.
+        let attrs = it.attrs(db);
+        let mut res = String::new();
+        for key in ["inline", "cold", "no_mangle", "track_caller"] {
+            let query = attrs.by_key(key);
+            if query.exists() {
+                match query.tt_values().next() {
+                    Some(args) => format_to!(res, "#[{}{}]\n", key, args),
+                    None => format_to!(res, "#[{}]\n", key),
+                }
+            }
+        }
+        res.push_str(&label);
+        (res, docs)
+    }
+
+    fn type_alias_label_and_docs(
+        db: &RootDatabase,
+        it: hir::TypeAlias,
+    ) -> (String, Option<hir::Documentation>) {
+        let (mut label, docs) = label_and_docs(db, it);
+        // Generic aliases can't be resolved without arguments, so only the definition is shown.
+        if hir::GenericDef::from(it).params(db).is_empty() {
+            if let Some(type_ref) = it.type_ref(db) {
+                let written = type_ref.display(db).to_string();
+                let resolved = it.ty(db).display(db).to_string();
+                if written != resolved {
+                    format_to!(label, "\n// = {}", resolved);
+                }
+            }
+        }
+        (label, docs)
+    }
+}
+
+/// Renders the `#[cfg]` attributes guarding `def`, noting whether they are active in `krate`,
+/// the crate the hover was requested from.
+fn cfg_label(db: &RootDatabase, def: Definition, krate: hir::Crate) -> Option<String> {
+    let attrs = match def {
+        Definition::ModuleDef(it) => it.attrs(db)?,
+        Definition::Field(it) => it.attrs(db),
+        Definition::Macro(it) => it.attrs(db),
+        _ => return None,
+    };
+    let cfg = attrs.cfg()?;
+    let mut res = attrs.by_key("cfg").tt_values().map(|args| format!("#[cfg{}]", args)).join("\n");
+    match krate.cfg(db).check(&cfg) {
+        Some(true) => res.push_str(" // active"),
+        Some(false) => res.push_str(" // inactive"),
+        None => (),
+    }
+    Some(res)
 }
 
-fn hover_for_local(it: hir::Local, db: &RootDatabase) -> Option<Markup> {
+fn hover_for_local(it: hir::Local, db: &RootDatabase, config: &HoverConfig) -> Option<Markup> {
     let ty = it.ty(db);
-    let ty = ty.display(db);
+    let ty = ty.display_truncated(db, config.max_type_depth);
     let is_mut = if it.is_mut(db) { "mut " } else { "" };
     let desc = match it.source(db).value {
         Either::Left(ident) => {
@@ -626,7 +884,7 @@ mod tests {
     use ide_db::base_db::{FileLoader, FileRange};
     use syntax::TextRange;
 
-    use crate::{fixture, hover::HoverDocFormat, HoverConfig};
+    use crate::{fixture, hover::HoverDocFormat, Analysis, FilePosition, HoverConfig};
 
     fn check_hover_no_result(ra_fixture: &str) {
         let (analysis, position) = fixture::position(ra_fixture);
@@ -635,6 +893,11 @@ mod tests {
                 &HoverConfig {
                     links_in_hover: true,
                     documentation: Some(HoverDocFormat::Markdown),
+                    macro_expansion: true,
+                    generic_args: true,
+                    deref_chain: false,
+                    max_doc_lines: None,
+                    max_type_depth: None,
                 },
                 FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
             )
@@ -642,14 +905,32 @@ mod tests {
         assert!(hover.is_none());
     }
 
+    const HOVER_BASE_CONFIG: HoverConfig = HoverConfig {
+        links_in_hover: true,
+        documentation: Some(HoverDocFormat::Markdown),
+        macro_expansion: true,
+        generic_args: true,
+        deref_chain: false,
+        max_doc_lines: None,
+        max_type_depth: None,
+    };
+
     fn check(ra_fixture: &str, expect: Expect) {
-        let (analysis, position) = fixture::position(ra_fixture);
+        check_with_config(HOVER_BASE_CONFIG, ra_fixture, expect);
+    }
+
+    fn check_with_config(config: HoverConfig, ra_fixture: &str, expect: Expect) {
+        check_hover_position(config, fixture::position(ra_fixture), expect)
+    }
+
+    fn check_hover_position(
+        config: HoverConfig,
+        (analysis, position): (Analysis, FilePosition),
+        expect: Expect,
+    ) {
         let hover = analysis
             .hover(
-                &HoverConfig {
-                    links_in_hover: true,
-                    documentation: Some(HoverDocFormat::Markdown),
-                },
+                &config,
                 FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
             )
             .unwrap()
@@ -669,6 +950,11 @@ mod tests {
                 &HoverConfig {
                     links_in_hover: false,
                     documentation: Some(HoverDocFormat::Markdown),
+                    macro_expansion: true,
+                    generic_args: true,
+                    deref_chain: false,
+                    max_doc_lines: None,
+                    max_type_depth: None,
                 },
                 FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
             )
@@ -689,6 +975,11 @@ mod tests {
                 &HoverConfig {
                     links_in_hover: true,
                     documentation: Some(HoverDocFormat::PlainText),
+                    macro_expansion: true,
+                    generic_args: true,
+                    deref_chain: false,
+                    max_doc_lines: None,
+                    max_type_depth: None,
                 },
                 FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
             )
@@ -709,6 +1000,11 @@ mod tests {
                 &HoverConfig {
                     links_in_hover: true,
                     documentation: Some(HoverDocFormat::Markdown),
+                    macro_expansion: true,
+                    generic_args: true,
+                    deref_chain: false,
+                    max_doc_lines: None,
+                    max_type_depth: None,
                 },
                 FileRange { file_id, range: position.range_or_empty() },
             )
@@ -724,6 +1020,11 @@ mod tests {
                 &HoverConfig {
                     links_in_hover: false,
                     documentation: Some(HoverDocFormat::Markdown),
+                    macro_expansion: true,
+                    generic_args: true,
+                    deref_chain: false,
+                    max_doc_lines: None,
+                    max_type_depth: None,
                 },
                 range,
             )
@@ -739,6 +1040,11 @@ mod tests {
                 &HoverConfig {
                     links_in_hover: false,
                     documentation: Some(HoverDocFormat::Markdown),
+                    macro_expansion: true,
+                    generic_args: true,
+                    deref_chain: false,
+                    max_doc_lines: None,
+                    max_type_depth: None,
                 },
                 range,
             )
@@ -815,6 +1121,250 @@ fn main() {
         );
     }
 
+    #[test]
+    fn hover_inside_attribute_expanded_fn() {
+        check_hover_position(
+            HOVER_BASE_CONFIG,
+            fixture::position_with_proc_macros(
+                r#"
+//- /main.rs crate:main deps:proc_macros
+#[proc_macros::identity]
+fn foo() {
+    let bar$0 = 92u32;
+}
+//- /proc_macros.rs crate:proc_macros proc_macro:identity
+#[proc_macro_attribute]
+pub fn identity(_attr: TokenStream, item: TokenStream) -> TokenStream { item }
+"#,
+            ),
+            expect![[r#"
+                *bar*
+
+                ```rust
+                let bar: u32
+                ```
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_shows_generic_args_of_call() {
+        check(
+            r#"
+struct String;
+fn wrap<T, U>(t: T, u: U) -> (T, U) { (t, u) }
+
+fn main() { wr$0ap(String, 0u8); }
+"#,
+            expect![[r#"
+                *wrap*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                fn wrap<T, U>(t: T, u: U) -> (T, U)
+                ```
+                ___
+
+                called with `T = String, U = u8`
+            "#]],
+        );
+        check(
+            r#"
+struct S<A>(A);
+impl<A> S<A> {
+    fn map<B>(self, b: B) -> S<B> { S(b) }
+}
+
+fn main() { S(0u32).ma$0p(true); }
+"#,
+            expect![[r#"
+                *map*
+
+                ```rust
+                test::S
+                ```
+
+                ```rust
+                fn map<B>(self, b: B) -> S<B>
+                ```
+                ___
+
+                called with `A = u32, B = bool`
+            "#]],
+        );
+        check(
+            r#"
+fn wrap<T>(t: T) -> T { t }
+
+fn main() { let f = wr$0ap::<i32>; }
+"#,
+            expect![[r#"
+                *wrap*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                fn wrap<T>(t: T) -> T
+                ```
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_shows_deref_chain() {
+        let fixture = r#"
+//- minicore: deref
+use core::ops::Deref;
+struct Rc<T>(T);
+impl<T> Deref for Rc<T> {
+    type Target = T;
+    fn deref(&self) -> &T { &self.0 }
+}
+struct Boxed<T>(T);
+impl<T> Deref for Boxed<T> {
+    type Target = T;
+    fn deref(&self) -> &T { &self.0 }
+}
+struct Foo;
+fn main() {
+    let foo$0 = Rc(Boxed(Foo));
+    foo;
+}
+"#;
+        check_with_config(
+            HoverConfig { deref_chain: true, ..HOVER_BASE_CONFIG },
+            fixture,
+            expect![[r#"
+            *foo*
+
+            ```rust
+            let foo: Rc<Boxed<Foo>>
+            ```
+            ___
+
+            derefs to `Boxed<Foo> -> Foo`
+        "#]],
+        );
+        check_with_config(
+            HoverConfig { deref_chain: true, ..HOVER_BASE_CONFIG },
+            &fixture.replace("let foo$0", "let foo").replace("    foo;", "    fo$0o;"),
+            expect![[r#"
+                *foo*
+
+                ```rust
+                let foo: Rc<Boxed<Foo>>
+                ```
+                ___
+
+                derefs to `Boxed<Foo> -> Foo`
+            "#]],
+        );
+        check_with_config(
+            HoverConfig { deref_chain: true, ..HOVER_BASE_CONFIG },
+            &fixture.replace("let foo$0", "let foo").replace("    foo;", "    $0&foo;"),
+            expect![[r#"
+                *&foo*
+                ```rust
+                &Rc<Boxed<Foo>>
+                ```
+                ___
+
+                derefs to `Rc<Boxed<Foo>> -> Boxed<Foo> -> Foo`
+            "#]],
+        );
+        check(
+            fixture,
+            expect![[r#"
+            *foo*
+
+            ```rust
+            let foo: Rc<Boxed<Foo>>
+            ```
+        "#]],
+        );
+    }
+
+    #[test]
+    fn hover_shows_generic_args_of_parent_impl() {
+        check(
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Self { loop {} }
+}
+
+fn main() { let v: Vec<i32> = Vec::ne$0w(); }
+"#,
+            expect![[r#"
+                *new*
+
+                ```rust
+                test::Vec
+                ```
+
+                ```rust
+                fn new() -> Self
+                ```
+                ___
+
+                called with `T = i32`
+            "#]],
+        );
+        check(
+            r#"
+trait Convert<T> {
+    fn convert(self) -> T;
+}
+impl Convert<u64> for u32 {
+    fn convert(self) -> u64 { 0 }
+}
+
+fn main() { let x = 0u32.conv$0ert(); }
+"#,
+            expect![[r#"
+                *convert*
+
+                ```rust
+                test::Convert
+                ```
+
+                ```rust
+                fn convert(self) -> T
+                ```
+                ___
+
+                called with `T = u64`
+            "#]],
+        );
+        check_with_config(
+            HoverConfig { generic_args: false, ..HOVER_BASE_CONFIG },
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Self { loop {} }
+}
+
+fn main() { let v: Vec<i32> = Vec::ne$0w(); }
+"#,
+            expect![[r#"
+                *new*
+
+                ```rust
+                test::Vec
+                ```
+
+                ```rust
+                fn new() -> Self
+                ```
+            "#]],
+        );
+    }
+
     #[test]
     fn hover_shows_fn_signature() {
         // Single file with result
@@ -911,6 +1461,31 @@ fn main() { }
         );
     }
 
+    #[test]
+    fn hover_shows_fn_codegen_attrs() {
+        check(
+            r#"
+#[inline(always)]
+#[track_caller]
+#[allow(dead_code)]
+fn foo$0() {}
+"#,
+            expect![[r##"
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                #[inline(always)]
+                #[track_caller]
+                fn foo()
+                ```
+            "##]],
+        );
+    }
+
     #[test]
     fn hover_shows_fn_doc() {
         check(
@@ -1042,14 +1617,77 @@ fn main() {
         check(
             r#"static foo$0: u32 = 456;"#,
             expect![[r#"
-                *foo*
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                static foo: u32
+                ```
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_type_alias() {
+        check(
+            r#"
+type Id = u64;
+fn foo(id: Id$0) {}
+"#,
+            expect![[r#"
+                *Id*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                type Id = u64
+                ```
+            "#]],
+        );
+        check(
+            r#"
+struct Foo;
+type Bar = Foo;
+type Baz = Bar;
+fn foo(baz: Baz$0) {}
+"#,
+            expect![[r#"
+                *Baz*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                type Baz = Bar
+                // = Foo
+                ```
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_generic_type_alias() {
+        check(
+            r#"
+struct Wrapper<T, U>(T, U);
+type Pair<T> = Wrapper<T, T>;
+fn foo(pair: Pair$0<u8>) {}
+"#,
+            expect![[r#"
+                *Pair*
 
                 ```rust
                 test
                 ```
 
                 ```rust
-                static foo: u32
+                type Pair<T> = Wrapper<T, T>
                 ```
             "#]],
         );
@@ -1449,6 +2087,108 @@ fn f() { fo$0o!(); }
         )
     }
 
+    #[test]
+    fn hover_macro_invocation_shows_expansion() {
+        check(
+            r#"
+macro_rules! foo {
+    () => {
+        fn some_thing() -> u32 {
+            let a = 0;
+            a + 10
+        }
+    }
+}
+f$0oo!();
+"#,
+            expect![[r#"
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                macro_rules! foo
+                ```
+                ___
+
+                Expansion:
+
+                ```rust
+                fn some_thing() -> u32 {
+                  let a = 0;
+                  a+10
+                }
+                ```
+            "#]],
+        )
+    }
+
+    #[test]
+    fn hover_macro_invocation_truncates_expansion() {
+        check(
+            r#"
+macro_rules! structs {
+    ($($name:ident)*) => { $(struct $name;)* }
+}
+struct$0s!(S0 S1 S2 S3 S4 S5 S6 S7 S8 S9 S10 S11);
+"#,
+            expect![[r#"
+                *structs*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                macro_rules! structs
+                ```
+                ___
+
+                Expansion:
+
+                ```rust
+                struct S0;
+                struct S1;
+                struct S2;
+                struct S3;
+                struct S4;
+                struct S5;
+                struct S6;
+                struct S7;
+                struct S8;
+                struct S9;
+                // …
+                ```
+            "#]],
+        )
+    }
+
+    #[test]
+    fn hover_macro_invocation_no_expansion() {
+        check_with_config(
+            HoverConfig { macro_expansion: false, ..HOVER_BASE_CONFIG },
+            r#"
+macro_rules! foo {
+    () => { fn some_thing() {} }
+}
+f$0oo!();
+"#,
+            expect![[r#"
+                *foo*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                macro_rules! foo
+                ```
+            "#]],
+        )
+    }
+
     #[test]
     fn test_hover_macro2_invocation() {
         check(
@@ -1845,6 +2585,134 @@ fn foo() { let bar = Ba$0r; }
         );
     }
 
+    #[test]
+    fn hover_docs_under_max_doc_lines() {
+        check_with_config(
+            HoverConfig { max_doc_lines: Some(8), ..HOVER_BASE_CONFIG },
+            r#"
+/// This is an example
+/// multiline doc
+///
+/// # Example
+///
+/// ```
+/// let five = 5;
+/// ```
+struct Bar;
+
+fn foo() { let bar = Ba$0r; }
+"#,
+            expect![[r##"
+                *Bar*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                struct Bar
+                ```
+
+                ---
+
+                This is an example
+                multiline doc
+
+                # Example
+
+                ```
+                let five = 5;
+                ```
+            "##]],
+        );
+    }
+
+    #[test]
+    fn hover_docs_over_max_doc_lines() {
+        check_with_config(
+            HoverConfig { max_doc_lines: Some(7), ..HOVER_BASE_CONFIG },
+            r#"
+/// This is an example
+/// multiline doc
+///
+/// # Example
+///
+/// ```
+/// let five = 5;
+///
+/// assert_eq!(6, my_crate::add_one(5));
+/// ```
+struct Bar;
+
+fn foo() { let bar = Ba$0r; }
+"#,
+            expect![[r##"
+                *Bar*
+
+                ```rust
+                test
+                ```
+
+                ```rust
+                struct Bar
+                ```
+
+                ---
+
+                This is an example
+                multiline doc
+
+                # Example
+
+                ```
+                let five = 5;
+                ```
+
+                … (truncated)
+            "##]],
+        );
+    }
+
+    #[test]
+    fn hover_local_type_under_max_type_depth() {
+        check_with_config(
+            HoverConfig { max_type_depth: Some(3), ..HOVER_BASE_CONFIG },
+            r#"
+struct W<T>(T);
+fn main() {
+    let foo$0 = W(W(0u8));
+}
+"#,
+            expect![[r#"
+                *foo*
+
+                ```rust
+                let foo: W<W<u8>>
+                ```
+            "#]],
+        );
+    }
+
+    #[test]
+    fn hover_local_type_over_max_type_depth() {
+        check_with_config(
+            HoverConfig { max_type_depth: Some(3), ..HOVER_BASE_CONFIG },
+            r#"
+struct W<T>(T);
+fn main() {
+    let foo$0 = W((W(W(W(W(0u8)))), &W(W(1u8))));
+}
+"#,
+            expect![[r#"
+                *foo*
+
+                ```rust
+                let foo: W<(W<…>, &…)>
+                ```
+            "#]],
+        );
+    }
+
     #[test]
     fn test_hover_struct_doc_attr() {
         check(
@@ -2134,6 +3002,54 @@ fn foo() { let bar = Bar; bar.fo$0o(); }
         );
     }
 
+    #[test]
+    fn test_hover_const_fn_call_has_eval_action() {
+        check_actions(
+            r#"
+const fn double(x: u32) -> u32 { x * 2 }
+fn main() { doub$0le(21); }
+"#,
+            expect![[r#"
+                [
+                    Reference(
+                        FilePosition {
+                            file_id: FileId(
+                                0,
+                            ),
+                            offset: 9,
+                        },
+                    ),
+                    EvalFunction(
+                        FilePosition {
+                            file_id: FileId(
+                                0,
+                            ),
+                            offset: 57,
+                        },
+                    ),
+                ]
+            "#]],
+        );
+        check_actions(
+            r#"
+const fn double(x: u32) -> u32 { x * 2 }
+fn main() { let f = doub$0le; }
+"#,
+            expect![[r#"
+                [
+                    Reference(
+                        FilePosition {
+                            file_id: FileId(
+                                0,
+                            ),
+                            offset: 9,
+                        },
+                    ),
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn test_hover_union_has_impl_action() {
         check_actions(
@@ -4255,4 +5171,52 @@ fn foo() {
             "#]],
         );
     }
+
+    #[test]
+    fn hover_shows_active_cfg() {
+        check(
+            r#"
+//- /main.rs crate:main cfg:feature=x
+#[cfg(feature = "x")]
+fn fo$0o() {}
+"#,
+            expect![[r##"
+                *foo*
+
+                ```rust
+                main
+                ```
+
+                ```rust
+                #[cfg(feature = "x")] // active
+                fn foo()
+                ```
+            "##]],
+        );
+    }
+
+    #[test]
+    fn hover_shows_inactive_cfg() {
+        check(
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() { dep::fo$0o() }
+//- /dep.rs crate:dep cfg:feature=x
+#[cfg(feature = "x")]
+pub fn foo() {}
+"#,
+            expect![[r##"
+                *foo*
+
+                ```rust
+                dep
+                ```
+
+                ```rust
+                #[cfg(feature = "x")] // inactive
+                pub fn foo()
+                ```
+            "##]],
+        );
+    }
 }
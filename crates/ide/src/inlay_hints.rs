@@ -1,11 +1,12 @@
 use either::Either;
-use hir::{known, Callable, HasVisibility, HirDisplay, Semantics, TypeInfo};
+use hir::{known, Callable, HasVisibility, HirDisplay, Mutability, Semantics, TypeInfo};
 use ide_db::helpers::FamousDefs;
 use ide_db::RootDatabase;
+use itertools::Itertools;
 use stdx::to_lower_snake_case;
 use syntax::{
-    ast::{self, ArgListOwner, AstNode, NameOwner},
-    match_ast, Direction, NodeOrToken, SmolStr, SyntaxKind, TextRange, T,
+    ast::{self, ArgListOwner, AstNode, GenericParamsOwner, NameOwner},
+    match_ast, Direction, NodeOrToken, SmolStr, SyntaxKind, TextRange, WalkEvent, T,
 };
 
 use crate::FileId;
@@ -15,14 +16,37 @@ pub struct InlayHintsConfig {
     pub type_hints: bool,
     pub parameter_hints: bool,
     pub chaining_hints: bool,
+    pub binding_mode_hints: bool,
+    pub closure_return_type_hints: ClosureReturnTypeHints,
+    pub lifetime_elision_hints: LifetimeElisionHints,
     pub max_length: Option<usize>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClosureReturnTypeHints {
+    Always,
+    /// Only for closures whose body is a block, as the type of a single expression is easy to
+    /// tell at a glance.
+    WithBlock,
+    Never,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LifetimeElisionHints {
+    Always,
+    /// Hides the hints for signatures with a single input lifetime, where elision is obvious.
+    SkipTrivial,
+    Never,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    BindingModeHint,
+    ClosureReturnTypeHint,
+    LifetimeHint,
 }
 
 #[derive(Debug)]
@@ -42,6 +66,9 @@ pub struct InlayHint {
 // * types of local variables
 // * names of function arguments
 // * types of chained expressions
+// * binding modes of patterns changed by match ergonomics
+// * return types of closures
+// * elided lifetimes of function signatures
 //
 // **Note:** VS Code does not have native support for inlay hints https://github.com/microsoft/vscode/issues/16221[yet] and the hints are implemented using decorations.
 // This approach has limitations, the caret movement and bracket highlighting near the edges of the hint may be weird:
@@ -68,12 +95,17 @@ pub(crate) fn inlay_hints(
         if let Some(expr) = ast::Expr::cast(node.clone()) {
             get_chaining_hints(&mut res, &sema, config, expr);
         }
+        if let Some(pat) = ast::Pat::cast(node.clone()) {
+            get_binding_mode_hints(&mut res, &sema, config, pat);
+        }
 
         match_ast! {
             match node {
                 ast::CallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
                 ast::MethodCallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
                 ast::IdentPat(it) => { get_bind_pat_hints(&mut res, &sema, config, it); },
+                ast::ClosureExpr(it) => { get_closure_return_type_hints(&mut res, &sema, config, it); },
+                ast::Fn(it) => { get_lifetime_elision_hints(&mut res, config, it); },
                 _ => (),
             }
         }
@@ -132,7 +164,9 @@ fn get_chaining_hints(
                 range: expr.syntax().text_range(),
                 kind: InlayKind::ChainingHint,
                 label: hint_iterator(sema, &famous_defs, config, &ty).unwrap_or_else(|| {
-                    ty.display_truncated(sema.db, config.max_length).to_string().into()
+                    HirDisplay::display_truncated(&ty, sema.db, config.max_length)
+                        .to_string()
+                        .into()
                 }),
             });
         }
@@ -140,6 +174,209 @@ fn get_chaining_hints(
     Some(())
 }
 
+fn get_binding_mode_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    pat: ast::Pat,
+) -> Option<()> {
+    if !config.binding_mode_hints {
+        return None;
+    }
+
+    let range = pat.syntax().text_range();
+    // Each adjustment is a reference that was implicitly dereferenced to match `pat`.
+    acc.extend(sema.pattern_adjustments(&pat).iter().map(|ty| InlayHint {
+        range,
+        kind: InlayKind::BindingModeHint,
+        label: if ty.is_mutable_reference() { "&mut" } else { "&" }.into(),
+    }));
+
+    if let ast::Pat::IdentPat(pat) = pat {
+        if pat.ref_token().is_none() && pat.mut_token().is_none() {
+            let label = match sema.binding_mode_of_pat(&pat)? {
+                hir::BindingMode::Move => return None,
+                hir::BindingMode::Ref(Mutability::Shared) => "ref",
+                hir::BindingMode::Ref(Mutability::Mut) => "ref mut",
+            };
+            acc.push(InlayHint { range, kind: InlayKind::BindingModeHint, label: label.into() });
+        }
+    }
+    Some(())
+}
+
+fn get_closure_return_type_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    closure: ast::ClosureExpr,
+) -> Option<()> {
+    let with_block_only = match config.closure_return_type_hints {
+        ClosureReturnTypeHints::Always => false,
+        ClosureReturnTypeHints::WithBlock => true,
+        ClosureReturnTypeHints::Never => return None,
+    };
+    if closure.ret_type().is_some() {
+        return None;
+    }
+    if with_block_only && !matches!(closure.body()?, ast::Expr::BlockExpr(_)) {
+        return None;
+    }
+    let param_list = closure.param_list()?;
+
+    let ty = sema.type_of_expr(&ast::Expr::ClosureExpr(closure))?.original;
+    let ret_ty = ty.as_callable(sema.db)?.return_type();
+    if ret_ty.contains_unknown() || ret_ty.is_unit() {
+        return None;
+    }
+
+    let krate = sema.scope(param_list.syntax()).module().map(|it| it.krate());
+    let famous_defs = FamousDefs(sema, krate);
+    acc.push(InlayHint {
+        range: param_list.syntax().text_range(),
+        kind: InlayKind::ClosureReturnTypeHint,
+        label: hint_iterator(sema, &famous_defs, config, &ret_ty).unwrap_or_else(|| {
+            HirDisplay::display_truncated(&ret_ty, sema.db, config.max_length).to_string().into()
+        }),
+    });
+    Some(())
+}
+
+fn get_lifetime_elision_hints(
+    acc: &mut Vec<InlayHint>,
+    config: &InlayHintsConfig,
+    func: ast::Fn,
+) -> Option<()> {
+    let skip_trivial = match config.lifetime_elision_hints {
+        LifetimeElisionHints::Always => false,
+        LifetimeElisionHints::SkipTrivial => true,
+        LifetimeElisionHints::Never => return None,
+    };
+    let param_list = func.param_list()?;
+    let self_param = param_list.self_param();
+
+    let ret_ty = func.ret_type().and_then(|it| it.ty());
+    // `'_` has to be given a name to be hinted, which is not worth it.
+    let signature = param_list
+        .syntax()
+        .descendants()
+        .chain(ret_ty.iter().flat_map(|it| it.syntax().descendants()));
+    if signature.filter_map(ast::Lifetime::cast).any(|it| it.syntax().text() == "'_") {
+        return None;
+    }
+
+    let mut elided = Vec::new();
+    let mut explicit = Vec::new();
+    // The lifetime the return type gets under the self rule, if it applies.
+    let mut self_lifetime = None;
+    if let Some(self_param) = &self_param {
+        match (self_param.amp_token(), self_param.lifetime()) {
+            (Some(_), Some(lifetime)) => {
+                self_lifetime = Some(SmolStr::from(lifetime.to_string()));
+                explicit.push(lifetime.to_string());
+            }
+            (Some(amp), None) => {
+                self_lifetime = Some(elided_lifetime_name(elided.len()));
+                elided.push(amp.text_range());
+            }
+            (None, _) => {
+                if let Some(ast::Type::RefType(ty)) = self_param.ty() {
+                    self_lifetime = Some(match ty.lifetime() {
+                        Some(lifetime) => lifetime.to_string().into(),
+                        None => elided_lifetime_name(elided.len()),
+                    });
+                }
+                if let Some(ty) = self_param.ty() {
+                    collect_lifetime_positions(&ty, &mut elided, &mut explicit);
+                }
+            }
+        }
+    }
+    for param in param_list.params() {
+        if let Some(ty) = param.ty() {
+            collect_lifetime_positions(&ty, &mut elided, &mut explicit);
+        }
+    }
+
+    let mut output = Vec::new();
+    if let Some(ty) = &ret_ty {
+        collect_lifetime_positions(ty, &mut output, &mut Vec::new());
+    }
+    explicit.sort();
+    explicit.dedup();
+    let output_lifetime = match (self_lifetime, &*elided, &*explicit) {
+        (Some(it), ..) => Some(it),
+        (None, [_], []) => Some(elided_lifetime_name(0)),
+        (None, [], [it]) => Some(it.as_str().into()),
+        _ => None,
+    };
+
+    if elided.is_empty() && (output.is_empty() || output_lifetime.is_none()) {
+        return None;
+    }
+    if skip_trivial && elided.len() + explicit.len() == 1 {
+        return None;
+    }
+
+    if !elided.is_empty() {
+        let names = (0..elided.len()).map(elided_lifetime_name).join(", ");
+        let (range, label) = match func.generic_param_list().and_then(|it| it.l_angle_token()) {
+            Some(l_angle) => (l_angle.text_range(), format!("{},", names)),
+            None => (func.name()?.syntax().text_range(), format!("<{}>", names)),
+        };
+        acc.push(InlayHint { range, kind: InlayKind::LifetimeHint, label: label.into() });
+    }
+    acc.extend(elided.into_iter().enumerate().map(|(idx, range)| InlayHint {
+        range,
+        kind: InlayKind::LifetimeHint,
+        label: elided_lifetime_name(idx),
+    }));
+    if let Some(output_lifetime) = output_lifetime {
+        acc.extend(output.into_iter().map(|range| InlayHint {
+            range,
+            kind: InlayKind::LifetimeHint,
+            label: output_lifetime.clone(),
+        }));
+    }
+    Some(())
+}
+
+fn elided_lifetime_name(idx: usize) -> SmolStr {
+    format!("'{}", idx).into()
+}
+
+/// Collects the `&` of references without a lifetime in `ty` into `elided`, and the written
+/// lifetimes into `explicit`. Function pointers and `Fn` sugar have their own elision scope, so
+/// they are skipped.
+fn collect_lifetime_positions(
+    ty: &ast::Type,
+    elided: &mut Vec<TextRange>,
+    explicit: &mut Vec<String>,
+) {
+    let mut preorder = ty.syntax().preorder();
+    while let Some(event) = preorder.next() {
+        let node = match event {
+            WalkEvent::Enter(node) => node,
+            WalkEvent::Leave(_) => continue,
+        };
+        match_ast! {
+            match node {
+                ast::FnPtrType(_it) => preorder.skip_subtree(),
+                ast::ParamList(_it) => preorder.skip_subtree(),
+                ast::RefType(it) => {
+                    if it.lifetime().is_none() {
+                        if let Some(amp) = it.amp_token() {
+                            elided.push(amp.text_range());
+                        }
+                    }
+                },
+                ast::Lifetime(it) => explicit.push(it.to_string()),
+                _ => (),
+            }
+        }
+    }
+}
+
 fn get_param_name_hints(
     acc: &mut Vec<InlayHint>,
     sema: &Semantics<RootDatabase>,
@@ -201,8 +438,9 @@ fn get_bind_pat_hints(
             None => pat.syntax().text_range(),
         },
         kind: InlayKind::TypeHint,
-        label: hint_iterator(sema, &famous_defs, config, &ty)
-            .unwrap_or_else(|| ty.display_truncated(sema.db, config.max_length).to_string().into()),
+        label: hint_iterator(sema, &famous_defs, config, &ty).unwrap_or_else(|| {
+            HirDisplay::display_truncated(&ty, sema.db, config.max_length).to_string().into()
+        }),
     });
 
     Some(())
@@ -236,14 +474,15 @@ fn hint_iterator(
             hir::AssocItem::TypeAlias(alias) if alias.name(db) == known::Item => Some(alias),
             _ => None,
         })?;
-        if let Some(ty) = ty.normalize_trait_assoc_type(db, &[], assoc_type_item) {
+        if let Some(ty) = ty.normalize_trait_assoc_type(db, &[], assoc_type_item, &[]) {
             const LABEL_START: &str = "impl Iterator<Item = ";
             const LABEL_END: &str = ">";
 
             let ty_display = hint_iterator(sema, famous_defs, config, &ty)
                 .map(|assoc_type_impl| assoc_type_impl.to_string())
                 .unwrap_or_else(|| {
-                    ty.display_truncated(
+                    HirDisplay::display_truncated(
+                        &ty,
                         db,
                         config
                             .max_length
@@ -445,12 +684,18 @@ mod tests {
     use expect_test::{expect, Expect};
     use test_utils::extract_annotations;
 
-    use crate::{fixture, inlay_hints::InlayHintsConfig};
+    use crate::{
+        fixture,
+        inlay_hints::{ClosureReturnTypeHints, InlayHintsConfig, LifetimeElisionHints},
+    };
 
     const TEST_CONFIG: InlayHintsConfig = InlayHintsConfig {
         type_hints: true,
         parameter_hints: true,
         chaining_hints: true,
+        binding_mode_hints: true,
+        closure_return_type_hints: ClosureReturnTypeHints::Never,
+        lifetime_elision_hints: LifetimeElisionHints::Never,
         max_length: None,
     };
 
@@ -464,6 +709,9 @@ mod tests {
                 parameter_hints: true,
                 type_hints: false,
                 chaining_hints: false,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
                 max_length: None,
             },
             ra_fixture,
@@ -476,6 +724,9 @@ mod tests {
                 parameter_hints: false,
                 type_hints: true,
                 chaining_hints: false,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
                 max_length: None,
             },
             ra_fixture,
@@ -488,6 +739,54 @@ mod tests {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
+                max_length: None,
+            },
+            ra_fixture,
+        );
+    }
+
+    fn check_binding_modes(ra_fixture: &str) {
+        check_with_config(
+            InlayHintsConfig {
+                parameter_hints: false,
+                type_hints: false,
+                chaining_hints: false,
+                binding_mode_hints: true,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
+                max_length: None,
+            },
+            ra_fixture,
+        );
+    }
+
+    fn check_closure_return_types(mode: ClosureReturnTypeHints, ra_fixture: &str) {
+        check_with_config(
+            InlayHintsConfig {
+                parameter_hints: false,
+                type_hints: false,
+                chaining_hints: false,
+                binding_mode_hints: false,
+                closure_return_type_hints: mode,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
+                max_length: None,
+            },
+            ra_fixture,
+        );
+    }
+
+    fn check_lifetime_elision(mode: LifetimeElisionHints, ra_fixture: &str) {
+        check_with_config(
+            InlayHintsConfig {
+                parameter_hints: false,
+                type_hints: false,
+                chaining_hints: false,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: mode,
                 max_length: None,
             },
             ra_fixture,
@@ -516,6 +815,9 @@ mod tests {
                 type_hints: false,
                 parameter_hints: false,
                 chaining_hints: false,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
                 max_length: None,
             },
             r#"
@@ -1273,6 +1575,9 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
                 max_length: None,
             },
             r#"
@@ -1329,6 +1634,9 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
                 max_length: None,
             },
             r#"
@@ -1373,6 +1681,9 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
                 max_length: None,
             },
             r#"
@@ -1418,6 +1729,9 @@ fn main() {
                 parameter_hints: false,
                 type_hints: false,
                 chaining_hints: true,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
                 max_length: None,
             },
             r#"
@@ -1467,4 +1781,247 @@ fn main() {
             "#]],
         );
     }
+
+    #[test]
+    fn chaining_hints_for_iterator_steps() {
+        check_expect(
+            InlayHintsConfig {
+                parameter_hints: false,
+                type_hints: false,
+                chaining_hints: true,
+                binding_mode_hints: false,
+                closure_return_type_hints: ClosureReturnTypeHints::Never,
+                lifetime_elision_hints: LifetimeElisionHints::Never,
+                max_length: None,
+            },
+            r#"
+//- minicore: iterators
+struct MyIter(u32);
+
+impl Iterator for MyIter {
+    type Item = u32;
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+fn main() {
+    let _x = MyIter(0)
+        .by_ref()
+        .take(2)
+        .by_ref();
+}
+"#,
+            expect![[r#"
+                [
+                    InlayHint {
+                        range: 163..207,
+                        kind: ChainingHint,
+                        label: "impl Iterator<Item = u32>",
+                    },
+                    InlayHint {
+                        range: 163..190,
+                        kind: ChainingHint,
+                        label: "&mut MyIter",
+                    },
+                    InlayHint {
+                        range: 163..172,
+                        kind: ChainingHint,
+                        label: "MyIter",
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    // Binding mode hint tests
+
+    #[test]
+    fn binding_mode_hints_for_match_ergonomics() {
+        check_binding_modes(
+            r#"
+//- minicore: option
+fn main() {
+    let opt = Some(0);
+    match &opt {
+        Some(x) => {}
+      //^^^^^^^&
+           //^ref
+        None => {}
+      //^^^^&
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn binding_mode_hints_for_mutable_references() {
+        check_binding_modes(
+            r#"
+fn main() {
+    let mut pair = (0, 1);
+    let (a, ref b) = &mut pair;
+      //^^^^^^^^^^&mut
+       //^ref mut
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_binding_mode_hints_without_match_ergonomics() {
+        check_binding_modes(
+            r#"
+fn main() {
+    let pair = (0, 1);
+    let (a, b) = pair;
+    let &(c, d) = &pair;
+}
+"#,
+        );
+    }
+
+    // Closure return type hint tests
+
+    #[test]
+    fn closure_hints_for_map() {
+        check_with_config(
+            InlayHintsConfig {
+                closure_return_type_hints: ClosureReturnTypeHints::Always,
+                ..TEST_CONFIG
+            },
+            r#"
+//- minicore: fn
+struct W<T>(T);
+impl<T> W<T> {
+    fn map<U, F: FnOnce(T) -> U>(self, f: F) -> W<U> { W(f(self.0)) }
+}
+
+fn main() {
+    W(1u32).map(|x| x as u64 + 1);
+              //^^^ u64
+               //^ u32
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_closure_return_type_hint_when_written() {
+        check_closure_return_types(
+            ClosureReturnTypeHints::Always,
+            r#"
+//- minicore: fn
+fn apply(f: impl FnOnce(u32) -> u64) {}
+
+fn main() {
+    apply(|x| -> u64 { x as u64 });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_closure_return_type_hint_when_unknown() {
+        check_closure_return_types(
+            ClosureReturnTypeHints::Always,
+            r#"
+fn main() {
+    let id = |x| x;
+    let nothing = || {};
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn closure_return_type_hints_with_block() {
+        check_closure_return_types(
+            ClosureReturnTypeHints::WithBlock,
+            r#"
+//- minicore: fn
+fn apply(f: impl FnOnce(u32) -> u64) {}
+
+fn main() {
+    apply(|x| x as u64);
+    apply(|x| {
+        //^^^ u64
+        let y = x + 1;
+        y as u64
+    });
+}
+"#,
+        );
+    }
+
+    // Lifetime elision hint tests
+
+    #[test]
+    fn lifetime_elision_hints_self_rule() {
+        check_lifetime_elision(
+            LifetimeElisionHints::Always,
+            r#"
+struct Map;
+struct Value;
+impl Map {
+    fn get(&self, key: &str) -> &Value { loop {} }
+     //^^^<'0, '1>
+         //^'0         ^'1      ^'0
+    fn get_mut<K>(&mut self, key: &K) -> &mut Value { loop {} }
+            //^'0, '1,
+                //^'0             ^'1    ^'0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn lifetime_elision_hints_single_input_rule() {
+        check_lifetime_elision(
+            LifetimeElisionHints::Always,
+            r#"
+fn pick(list: &[u32]) -> &u32 { loop {} }
+ //^^^^<'0>
+            //^'0        ^'0
+fn trim<'a>(s: &'a str) -> &str { loop {} }
+                         //^'a
+fn pair(a: &u32, b: &u32) -> &u32 { loop {} }
+ //^^^^<'0, '1>
+         //^'0      ^'1
+"#,
+        );
+    }
+
+    #[test]
+    fn lifetime_elision_hints_skip_trivial() {
+        check_lifetime_elision(
+            LifetimeElisionHints::SkipTrivial,
+            r#"
+struct Map;
+impl Map {
+    fn name(&self) -> &str { loop {} }
+    fn get(&self, key: &str) -> &str { loop {} }
+     //^^^<'0, '1>
+         //^'0         ^'1      ^'0
+}
+fn pick(list: &[u32]) -> &u32 { loop {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn no_lifetime_elision_hints_when_written() {
+        check_lifetime_elision(
+            LifetimeElisionHints::Always,
+            r#"
+struct Map;
+impl Map {
+    fn get<'a, 'b>(&'a self, key: &'b str) -> &'a str { loop {} }
+}
+fn apply(f: fn(&str) -> &str) {}
+fn anon(s: &'_ str) -> &str { loop {} }
+"#,
+        );
+    }
 }
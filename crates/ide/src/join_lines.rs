@@ -15,6 +15,7 @@ pub struct JoinLinesConfig {
     pub join_else_if: bool,
     pub remove_trailing_comma: bool,
     pub unwrap_trivial_blocks: bool,
+    pub join_string_literals: bool,
 }
 
 // Feature: Join Lines
@@ -98,6 +99,14 @@ fn remove_newline(
             suff.bytes().take_while(|&b| b == b' ').count()
         };
 
+        if config.join_string_literals {
+            if let Some(range) = escaped_line_break(token, offset) {
+                cov_mark::hit!(join_string_literal_escaped_line_break);
+                edit.delete(range);
+                return;
+            }
+        }
+
         let mut no_space = false;
         if let Some(string) = ast::String::cast(token.clone()) {
             if let Some(range) = string.open_quote_text_range() {
@@ -133,6 +142,10 @@ fn remove_newline(
                 return;
             }
             T!['}'] => {
+                // `use foo::{bar,$0\n};` collapses all the way to `use foo::bar;`
+                if config.unwrap_trivial_blocks && join_single_use_tree(edit, token).is_some() {
+                    return;
+                }
                 // Removes: comma, newline (incl. surrounding whitespace)
                 let space = if let Some(left) = prev.prev_sibling_or_token() {
                     compute_ws(left.kind(), next.kind())
@@ -149,6 +162,23 @@ fn remove_newline(
         }
     }
 
+    if config.join_string_literals {
+        if let (Some(prev), Some(next)) = (
+            prev.as_token().cloned().and_then(ast::String::cast),
+            next.as_token().cloned().and_then(ast::String::cast),
+        ) {
+            // Merges `"foo"$0\n"bar"`, as in `concat!` arguments, into `"foobar"`
+            if !prev.is_raw() && !next.is_raw() {
+                let close_quote = prev.close_quote_text_range();
+                let open_quote = next.open_quote_text_range();
+                if let (Some(close_quote), Some(open_quote)) = (close_quote, open_quote) {
+                    edit.delete(TextRange::new(close_quote.start(), open_quote.end()));
+                    return;
+                }
+            }
+        }
+    }
+
     if config.join_else_if {
         if let (Some(prev), Some(_next)) = (as_if_expr(&prev), as_if_expr(&next)) {
             match prev.else_token() {
@@ -160,6 +190,10 @@ fn remove_newline(
                 }
             }
         }
+        // Special case that turns `} else {$0\n if b {} }` into `} else if b {}`
+        if join_else_block(edit, token).is_some() {
+            return;
+        }
     }
 
     if config.unwrap_trivial_blocks {
@@ -225,6 +259,41 @@ fn join_single_expr_block(edit: &mut TextEditBuilder, token: &SyntaxToken) -> Op
     Some(())
 }
 
+fn join_else_block(edit: &mut TextEditBuilder, token: &SyntaxToken) -> Option<()> {
+    let block_expr = ast::BlockExpr::cast(token.parent()?)?;
+    let parent_if = ast::IfExpr::cast(block_expr.syntax().parent()?)?;
+    match parent_if.else_branch()? {
+        ast::ElseBranch::Block(else_block) if else_block == block_expr => (),
+        _ => return None,
+    }
+    let if_expr = match extract_trivial_expression(&block_expr)? {
+        ast::Expr::IfExpr(it) => it,
+        _ => return None,
+    };
+    edit.replace(block_expr.syntax().text_range(), if_expr.syntax().text().to_string());
+    Some(())
+}
+
+/// Returns the range of a `\` line continuation together with the indentation it skips, if the
+/// line break at `offset` inside a string literal is escaped.
+fn escaped_line_break(token: &SyntaxToken, offset: TextSize) -> Option<TextRange> {
+    let string = ast::String::cast(token.clone())?;
+    if string.is_raw() {
+        return None;
+    }
+    let offset_in_token = offset - token.text_range().start();
+    let (before, after) = token.text().split_at(offset_in_token.into());
+    let backslashes = before.bytes().rev().take_while(|&b| b == b'\\').count();
+    if backslashes % 2 == 0 {
+        return None;
+    }
+    let indent = after[1..].bytes().take_while(|&b| b == b' ' || b == b'\t').count();
+    Some(TextRange::new(
+        offset - TextSize::of('\\'),
+        offset + TextSize::of('\n') + TextSize::try_from(indent).unwrap(),
+    ))
+}
+
 fn join_single_use_tree(edit: &mut TextEditBuilder, token: &SyntaxToken) -> Option<()> {
     let use_tree_list = ast::UseTreeList::cast(token.parent()?)?;
     let (tree,) = use_tree_list.use_trees().collect_tuple()?;
@@ -270,18 +339,27 @@ mod tests {
 
     use super::*;
 
+    const CONFIG: JoinLinesConfig = JoinLinesConfig {
+        join_else_if: true,
+        remove_trailing_comma: true,
+        unwrap_trivial_blocks: true,
+        join_string_literals: true,
+    };
+
     fn check_join_lines(ra_fixture_before: &str, ra_fixture_after: &str) {
-        let config = JoinLinesConfig {
-            join_else_if: true,
-            remove_trailing_comma: true,
-            unwrap_trivial_blocks: true,
-        };
+        check_join_lines_with_config(&CONFIG, ra_fixture_before, ra_fixture_after)
+    }
 
+    fn check_join_lines_with_config(
+        config: &JoinLinesConfig,
+        ra_fixture_before: &str,
+        ra_fixture_after: &str,
+    ) {
         let (before_cursor_pos, before) = extract_offset(ra_fixture_before);
         let file = SourceFile::parse(&before).ok().unwrap();
 
         let range = TextRange::empty(before_cursor_pos);
-        let result = join_lines(&config, &file, range);
+        let result = join_lines(config, &file, range);
 
         let actual = {
             let mut actual = before;
@@ -296,15 +374,9 @@ mod tests {
     }
 
     fn check_join_lines_sel(ra_fixture_before: &str, ra_fixture_after: &str) {
-        let config = JoinLinesConfig {
-            join_else_if: true,
-            remove_trailing_comma: true,
-            unwrap_trivial_blocks: true,
-        };
-
         let (sel, before) = extract_range(ra_fixture_before);
         let parse = SourceFile::parse(&before);
-        let result = join_lines(&config, &parse.tree(), sel);
+        let result = join_lines(&CONFIG, &parse.tree(), sel);
         let actual = {
             let mut actual = before;
             result.apply(&mut actual);
@@ -990,6 +1062,156 @@ fn main() {
 
     }
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn join_else_onto_closing_brace() {
+        check_join_lines(
+            r#"
+fn main() {
+    if foo {
+    }$0
+    else {
+    }
+}
+"#,
+            r#"
+fn main() {
+    if foo {
+    }$0 else {
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn join_else_block_into_else_if() {
+        let before = r#"
+fn main() {
+    if foo {
+    $0} else {
+        if bar {
+            baz()
+        }
+    }
+}
+"#;
+        check_join_lines(
+            before,
+            r#"
+fn main() {
+    if foo {
+    $0} else if bar {
+            baz()
+        }
+}
+"#,
+        );
+        check_join_lines_with_config(
+            &JoinLinesConfig { join_else_if: false, ..CONFIG },
+            before,
+            r#"
+fn main() {
+    if foo {
+    $0} else { if bar {
+            baz()
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn join_adjacent_string_literals() {
+        let before = r#"
+fn main() {
+    $0concat!("hello "
+        "world");
+}
+"#;
+        check_join_lines(
+            before,
+            r#"
+fn main() {
+    $0concat!("hello world");
+}
+"#,
+        );
+        check_join_lines_with_config(
+            &JoinLinesConfig { join_string_literals: false, ..CONFIG },
+            before,
+            r#"
+fn main() {
+    $0concat!("hello " "world");
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn join_escaped_line_break_in_string() {
+        let before = r#"
+fn main() {
+    $0let s = "hello \
+        world";
+}
+"#;
+        {
+            cov_mark::check!(join_string_literal_escaped_line_break);
+            check_join_lines(
+                before,
+                r#"
+fn main() {
+    $0let s = "hello world";
+}
+"#,
+            );
+        }
+        check_join_lines_with_config(
+            &JoinLinesConfig { join_string_literals: false, ..CONFIG },
+            before,
+            r#"
+fn main() {
+    $0let s = "hello \ world";
+}
+"#,
+        );
+        check_join_lines(
+            r#"
+fn main() {
+    $0let s = "hello \\
+world";
+}
+"#,
+            r#"
+fn main() {
+    $0let s = "hello \\ world";
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn join_use_tree_with_trailing_comma() {
+        let before = r#"
+$0use foo::{bar,
+};
+"#;
+        check_join_lines(
+            before,
+            r#"
+$0use foo::bar;
+"#,
+        );
+        check_join_lines_with_config(
+            &JoinLinesConfig { unwrap_trivial_blocks: false, ..CONFIG },
+            before,
+            r#"
+$0use foo::{bar};
 "#,
         );
     }
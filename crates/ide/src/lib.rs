@@ -25,6 +25,7 @@ mod display;
 mod annotations;
 mod call_hierarchy;
 mod doc_links;
+mod eval_function;
 mod highlight_related;
 mod expand_macro;
 mod extend_selection;
@@ -40,6 +41,7 @@ mod inlay_hints;
 mod join_lines;
 mod markdown_remove;
 mod matching_brace;
+mod memory_usage;
 mod move_item;
 mod parent_module;
 mod references;
@@ -78,12 +80,15 @@ pub use crate::{
     folding_ranges::{Fold, FoldKind},
     highlight_related::{HighlightRelatedConfig, HighlightedRange},
     hover::{HoverAction, HoverConfig, HoverDocFormat, HoverGotoTypeData, HoverResult},
-    inlay_hints::{InlayHint, InlayHintsConfig, InlayKind},
+    inlay_hints::{
+        ClosureReturnTypeHints, InlayHint, InlayHintsConfig, InlayKind, LifetimeElisionHints,
+    },
     join_lines::JoinLinesConfig,
     markup::Markup,
+    memory_usage::MemoryUsage,
     move_item::Direction,
     prime_caches::PrimeCachesProgress,
-    references::ReferenceSearchResult,
+    references::{ReferenceSearchConfig, ReferenceSearchResult},
     rename::RenameError,
     runnables::{Runnable, RunnableKind, TestId},
     syntax_highlighting::{
@@ -100,12 +105,13 @@ pub use ide_completion::{
 };
 pub use ide_db::{
     base_db::{
-        Cancelled, Change, CrateGraph, CrateId, Edition, FileId, FilePosition, FileRange,
-        SourceRoot, SourceRootId,
+        Cancelled, Change, CrateGraph, CrateId, CrateOrigin, Edition, FileId, FilePosition,
+        FileRange, SourceRoot, SourceRootId, TargetInfo,
     },
     call_info::CallInfo,
+    helpers::SnippetCap,
     label::Label,
-    line_index::{LineCol, LineColUtf16, LineIndex},
+    line_index::{LineCol, LineColUtf16, LineColUtf32, LineIndex},
     search::{ReferenceAccess, SearchScope},
     source_change::{FileSystemEdit, SourceChange},
     symbol_index::Query,
@@ -165,6 +171,14 @@ impl AnalysisHost {
     pub fn per_query_memory_usage(&mut self) -> Vec<(String, profile::Bytes)> {
         self.db.per_query_memory_usage()
     }
+    /// NB: this clears the database
+    pub fn memory_usage(&mut self) -> MemoryUsage {
+        memory_usage::memory_usage(&mut self.db)
+    }
+    /// Drops cached analysis results, which are recomputed on demand.
+    pub fn clear_memory(&mut self) {
+        self.db.clear_memory();
+    }
     pub fn request_cancellation(&mut self) {
         self.db.request_cancellation();
     }
@@ -221,8 +235,10 @@ impl Analysis {
             None,
             cfg_options.clone(),
             cfg_options,
+            TargetInfo::host(),
             Env::default(),
             Default::default(),
+            CrateOrigin::Workspace,
         );
         change.change_file(file_id, Some(Arc::new(text)));
         change.set_crate_graph(crate_graph);
@@ -289,6 +305,11 @@ impl Analysis {
         self.with_db(|db| syntax_tree::syntax_tree(db, file_id, text_range))
     }
 
+    /// Evaluates the const fn call or the constant at `position`.
+    pub fn eval_function(&self, position: FilePosition) -> Cancellable<Option<String>> {
+        self.with_db(|db| eval_function::eval_function(db, position))
+    }
+
     pub fn view_hir(&self, position: FilePosition) -> Cancellable<String> {
         self.with_db(|db| view_hir::view_hir(db, position))
     }
@@ -318,8 +339,12 @@ impl Analysis {
     /// Returns an edit which should be applied when opening a new line, fixing
     /// up minor stuff like continuing the comment.
     /// The edit will be a snippet (with `$0`).
-    pub fn on_enter(&self, position: FilePosition) -> Cancellable<Option<TextEdit>> {
-        self.with_db(|db| typing::on_enter(db, position))
+    pub fn on_enter(
+        &self,
+        position: FilePosition,
+        snippet_cap: Option<SnippetCap>,
+    ) -> Cancellable<Option<TextEdit>> {
+        self.with_db(|db| typing::on_enter(db, position, snippet_cap))
     }
 
     /// Returns an edit which should be applied after a character was typed.
@@ -405,8 +430,11 @@ impl Analysis {
         &self,
         position: FilePosition,
         search_scope: Option<SearchScope>,
+        config: &ReferenceSearchConfig,
     ) -> Cancellable<Option<ReferenceSearchResult>> {
-        self.with_db(|db| references::find_all_refs(&Semantics::new(db), position, search_scope))
+        self.with_db(|db| {
+            references::find_all_refs(&Semantics::new(db), position, search_scope, config)
+        })
     }
 
     /// Finds all methods and free functions for the file. Does not return tests!
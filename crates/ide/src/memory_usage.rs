@@ -0,0 +1,131 @@
+//! Reports the memory used by the analysis database, query group by query group.
+
+use std::fmt;
+
+use ide_db::{
+    base_db::salsa::debug::{DebugQueryTable, TableEntry},
+    symbol_index::{FileSymbolsQuery, LibrarySymbolsQuery},
+    RootDatabase,
+};
+use itertools::Itertools;
+use profile::Bytes;
+
+/// A breakdown of the memory used by the analysis database.
+pub struct MemoryUsage {
+    /// Memory freed by clearing the queries of each query group, largest first.
+    pub query_groups: Vec<(&'static str, Bytes)>,
+    /// Memory freed by clearing each query, largest first.
+    pub queries: Vec<(String, Bytes)>,
+    /// Estimated size of the symbol indices, measured before clearing them.
+    pub symbol_index: Bytes,
+    /// Memory still allocated once the database is cleared.
+    pub remaining: Bytes,
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Per-group memory usage:")?;
+        for (group, bytes) in &self.query_groups {
+            writeln!(f, "{:>8} {}", bytes, group)?;
+        }
+        writeln!(f, "\nPer-query memory usage:")?;
+        for (query, bytes) in &self.queries {
+            writeln!(f, "{:>8} {}", bytes, query)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:>8} Symbol index", self.symbol_index)?;
+        write!(f, "{:>8} Remaining", self.remaining)
+    }
+}
+
+/// NB: this clears the database.
+pub(crate) fn memory_usage(db: &mut RootDatabase) -> MemoryUsage {
+    let _p = profile::span("memory_usage");
+    let mut symbol_index = Bytes::default();
+    for entry in FileSymbolsQuery.in_db(db).entries::<Vec<TableEntry<_, _>>>() {
+        if let Some(index) = entry.value {
+            symbol_index += index.memory_size();
+        }
+    }
+    for entry in LibrarySymbolsQuery.in_db(db).entries::<Vec<TableEntry<_, _>>>() {
+        if let Some(indices) = entry.value {
+            for index in indices.values() {
+                symbol_index += index.memory_size();
+            }
+        }
+    }
+
+    let usage = db.query_memory_usage();
+    let query_groups = usage
+        .iter()
+        .map(|it| (it.group, it.bytes))
+        .into_group_map()
+        .into_iter()
+        .map(|(group, bytes)| {
+            let mut total = Bytes::default();
+            bytes.into_iter().for_each(|it| total += it);
+            (group, total)
+        })
+        .sorted_by_key(|&(group, bytes)| (std::cmp::Reverse(bytes), group))
+        .collect();
+    let queries = usage
+        .into_iter()
+        .map(|it| (it.name, it.bytes))
+        .sorted_by_key(|it| std::cmp::Reverse(it.1))
+        .collect();
+
+    MemoryUsage {
+        query_groups,
+        queries,
+        symbol_index,
+        remaining: profile::memory_usage().allocated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    #[test]
+    fn reports_all_query_groups() {
+        let (mut host, file_id) = fixture::host_and_file(
+            r#"
+struct Foo;
+fn foo() -> Foo { Foo }
+"#,
+        );
+        host.analysis().file_structure(file_id).unwrap();
+        host.analysis().symbol_search(crate::Query::new("foo".to_string())).unwrap();
+
+        let usage = host.memory_usage();
+        let mut groups = usage.query_groups.iter().map(|(group, _)| *group).collect::<Vec<_>>();
+        groups.sort_unstable();
+        assert_eq!(
+            groups,
+            [
+                "AstDatabase",
+                "DefDatabase",
+                "HirDatabase",
+                "InternDatabase",
+                "LineIndexDatabase",
+                "SourceDatabase",
+                "SourceDatabaseExt",
+                "SymbolsDatabase",
+            ]
+        );
+        assert!(usage.queries.iter().any(|(query, _)| query == "ParseQuery"));
+
+        let text = usage.to_string();
+        assert!(text.contains("Per-group memory usage:"));
+        assert!(text.contains("Symbol index"));
+        assert!(text.ends_with("Remaining"));
+    }
+
+    #[test]
+    fn analysis_works_after_clearing_memory() {
+        let (mut host, file_id) = fixture::host_and_file("fn foo() {}");
+        assert_eq!(host.analysis().file_structure(file_id).unwrap().len(), 1);
+        host.clear_memory();
+        assert_eq!(host.analysis().file_structure(file_id).unwrap().len(), 1);
+    }
+}
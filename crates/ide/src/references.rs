@@ -9,7 +9,7 @@
 //! at the index that the match starts at and its tree parent is
 //! resolved to the search element definition, we get a reference.
 
-use hir::{PathResolution, Semantics};
+use hir::{AsAssocItem, PathResolution, Semantics};
 use ide_db::{
     base_db::FileId,
     defs::{Definition, NameClass, NameRefClass},
@@ -25,10 +25,20 @@ use syntax::{
 
 use crate::{display::TryToNav, FilePosition, NavigationTarget};
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReferenceSearchConfig {
+    /// For associated functions of traits, also search the trait declaration and all
+    /// implementations of the function.
+    pub include_trait_fallback: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct ReferenceSearchResult {
     pub declaration: Option<Declaration>,
     pub references: FxHashMap<FileId, Vec<(TextRange, Option<ReferenceAccess>)>>,
+    /// Results for the sibling definitions of a trait function, one per definition.
+    /// Only populated if `include_trait_fallback` is set.
+    pub related: Vec<ReferenceSearchResult>,
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +62,7 @@ pub(crate) fn find_all_refs(
     sema: &Semantics<RootDatabase>,
     position: FilePosition,
     search_scope: Option<SearchScope>,
+    config: &ReferenceSearchConfig,
 ) -> Option<ReferenceSearchResult> {
     let _p = profile::span("find_all_refs");
     let syntax = sema.parse(position.file_id).syntax().clone();
@@ -69,6 +80,23 @@ pub(crate) fn find_all_refs(
         find_def(sema, &syntax, position.offset)?
     };
 
+    let mut result = search_def(sema, def, &syntax, search_scope.clone(), is_literal_search);
+    if config.include_trait_fallback {
+        result.related = trait_siblings(sema.db, def)
+            .into_iter()
+            .map(|sibling| search_def(sema, sibling, &syntax, search_scope.clone(), false))
+            .collect();
+    }
+    Some(result)
+}
+
+fn search_def(
+    sema: &Semantics<RootDatabase>,
+    def: Definition,
+    syntax: &SyntaxNode,
+    search_scope: Option<SearchScope>,
+    is_literal_search: bool,
+) -> ReferenceSearchResult {
     let mut usages = def.usages(sema).set_scope(search_scope).include_self_refs().all();
     let declaration = match def {
         Definition::ModuleDef(hir::ModuleDef::Module(module)) => {
@@ -78,7 +106,7 @@ pub(crate) fn find_all_refs(
     }
     .map(|nav| {
         let decl_range = nav.focus_or_full_range();
-        Declaration { nav, access: decl_access(&def, &syntax, decl_range) }
+        Declaration { nav, access: decl_access(&def, syntax, decl_range) }
     });
     if is_literal_search {
         retain_adt_literal_usages(&mut usages, def, sema);
@@ -91,7 +119,32 @@ pub(crate) fn find_all_refs(
         })
         .collect();
 
-    Some(ReferenceSearchResult { declaration, references })
+    ReferenceSearchResult { declaration, references, related: Vec::new() }
+}
+
+/// Returns the trait declaration and all implementations of the trait function `def`,
+/// excluding `def` itself.
+fn trait_siblings(db: &RootDatabase, def: Definition) -> Vec<Definition> {
+    let func = match def {
+        Definition::ModuleDef(hir::ModuleDef::Function(func)) => func,
+        _ => return Vec::new(),
+    };
+    let trait_ = match func.as_assoc_item(db).and_then(|it| it.containing_trait_or_trait_impl(db)) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let name = func.name(db);
+    let same_fn = |item: hir::AssocItem| match item {
+        hir::AssocItem::Function(it) if it != func && it.name(db) == name => Some(it),
+        _ => None,
+    };
+
+    let mut siblings: Vec<hir::Function> =
+        trait_.items(db).into_iter().filter_map(same_fn).collect();
+    for impl_ in hir::Impl::all_for_trait(db, trait_) {
+        siblings.extend(impl_.items(db).into_iter().filter_map(same_fn));
+    }
+    siblings.into_iter().map(|it| Definition::ModuleDef(it.into())).collect()
 }
 
 pub(crate) fn find_def(
@@ -268,7 +321,7 @@ mod tests {
     use ide_db::base_db::FileId;
     use stdx::format_to;
 
-    use crate::{fixture, SearchScope};
+    use crate::{fixture, ReferenceSearchConfig, ReferenceSearchResult, SearchScope};
 
     #[test]
     fn test_struct_literal_after_space() {
@@ -879,6 +932,82 @@ fn foo() {
         );
     }
 
+    #[test]
+    fn test_highlight_index_assignment_write() {
+        check(
+            r#"
+//- minicore: index, slice
+fn foo() {
+    let mut v$0 = [0; 2];
+    v[0] = 1;
+}
+"#,
+            expect![[r#"
+                v Local FileId(0) 19..24 23..24 Write
+
+                FileId(0) 39..40 Write
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_highlight_deref_compound_assignment_write() {
+        check(
+            r#"
+fn foo(p$0: &mut i32) {
+    *p += 1;
+}
+"#,
+            expect![[r#"
+                p ValueParam FileId(0) 7..8 7..8
+
+                FileId(0) 27..28 Write
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_highlight_mut_self_method_call_write() {
+        check(
+            r#"
+struct Vec;
+impl Vec {
+    fn push(&mut self, x: i32) {}
+    fn len(&self) -> usize { 0 }
+}
+
+fn foo(x: i32) {
+    let mut v$0 = Vec;
+    v.push(x);
+    v.len();
+}
+"#,
+            expect![[r#"
+                v Local FileId(0) 118..123 122..123 Write
+
+                FileId(0) 135..136 Write
+                FileId(0) 150..151 Read
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_highlight_plain_read() {
+        check(
+            r#"
+fn foo() {
+    let v$0 = 0;
+    let w = v;
+}
+"#,
+            expect![[r#"
+                v Local FileId(0) 19..20 19..20
+
+                FileId(0) 38..39 Read
+            "#]],
+        );
+    }
+
     #[test]
     fn test_basic_highlight_decl_no_write() {
         check(
@@ -1064,28 +1193,50 @@ impl Foo {
     }
 
     fn check_with_scope(ra_fixture: &str, search_scope: Option<SearchScope>, expect: Expect) {
+        check_with_config(ra_fixture, search_scope, &ReferenceSearchConfig::default(), expect)
+    }
+
+    fn check_trait_fallback(ra_fixture: &str, expect: Expect) {
+        let config = ReferenceSearchConfig { include_trait_fallback: true };
+        check_with_config(ra_fixture, None, &config, expect)
+    }
+
+    fn check_with_config(
+        ra_fixture: &str,
+        search_scope: Option<SearchScope>,
+        config: &ReferenceSearchConfig,
+        expect: Expect,
+    ) {
         let (analysis, pos) = fixture::position(ra_fixture);
-        let refs = analysis.find_all_refs(pos, search_scope).unwrap().unwrap();
+        let refs = analysis.find_all_refs(pos, search_scope, config).unwrap().unwrap();
 
         let mut actual = String::new();
-        if let Some(decl) = refs.declaration {
+        render_refs(&mut actual, &refs);
+        for related in &refs.related {
+            actual += "\n";
+            render_refs(&mut actual, related);
+        }
+        expect.assert_eq(actual.trim_start())
+    }
+
+    fn render_refs(actual: &mut String, refs: &ReferenceSearchResult) {
+        if let Some(decl) = &refs.declaration {
             format_to!(actual, "{}", decl.nav.debug_render());
             if let Some(access) = decl.access {
                 format_to!(actual, " {:?}", access)
             }
-            actual += "\n\n";
+            *actual += "\n\n";
         }
 
-        for (file_id, references) in refs.references {
+        for (file_id, references) in &refs.references {
             for (range, access) in references {
                 format_to!(actual, "{:?} {:?}", file_id, range);
                 if let Some(access) = access {
                     format_to!(actual, " {:?}", access);
                 }
-                actual += "\n";
+                *actual += "\n";
             }
         }
-        expect.assert_eq(actual.trim_start())
     }
 
     #[test]
@@ -1443,4 +1594,123 @@ m$0!();
             "#]],
         );
     }
+
+    #[test]
+    fn test_trait_fn_without_trait_fallback() {
+        check(
+            r#"
+trait Shape {
+    fn area$0(&self) -> u32;
+}
+struct Square;
+impl Shape for Square {
+    fn area(&self) -> u32 { 0 }
+}
+struct Circle;
+impl Shape for Circle {
+    fn area(&self) -> u32 { 1 }
+}
+fn dyn_call(shape: &dyn Shape) -> u32 {
+    shape.area()
+}
+fn concrete_call() -> u32 {
+    Circle.area()
+}
+"#,
+            expect![[r#"
+                area Function FileId(0) 18..40 21..25
+
+                FileId(0) 89..93
+                FileId(0) 162..166
+                FileId(0) 239..243
+                FileId(0) 287..291
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_trait_fn_with_trait_fallback() {
+        check_trait_fallback(
+            r#"
+trait Shape {
+    fn area$0(&self) -> u32;
+}
+struct Square;
+impl Shape for Square {
+    fn area(&self) -> u32 { 0 }
+}
+struct Circle;
+impl Shape for Circle {
+    fn area(&self) -> u32 { 1 }
+}
+fn dyn_call(shape: &dyn Shape) -> u32 {
+    shape.area()
+}
+fn concrete_call() -> u32 {
+    Circle.area()
+}
+"#,
+            expect![[r#"
+                area Function FileId(0) 18..40 21..25
+
+                FileId(0) 89..93
+                FileId(0) 162..166
+                FileId(0) 239..243
+                FileId(0) 287..291
+
+                area Function FileId(0) 86..113 89..93
+
+                FileId(0) 21..25
+                FileId(0) 162..166
+
+                area Function FileId(0) 159..186 162..166
+
+                FileId(0) 21..25
+                FileId(0) 89..93
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_trait_impl_fn_with_trait_fallback() {
+        check_trait_fallback(
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+struct Square;
+impl Shape for Square {
+    fn area$0(&self) -> u32 { 0 }
+}
+struct Circle;
+impl Shape for Circle {
+    fn area(&self) -> u32 { 1 }
+}
+fn dyn_call(shape: &dyn Shape) -> u32 {
+    shape.area()
+}
+fn concrete_call() -> u32 {
+    Circle.area()
+}
+"#,
+            expect![[r#"
+                area Function FileId(0) 86..113 89..93
+
+                FileId(0) 21..25
+                FileId(0) 162..166
+
+                area Function FileId(0) 18..40 21..25
+
+                FileId(0) 89..93
+                FileId(0) 162..166
+                FileId(0) 239..243
+                FileId(0) 287..291
+
+                area Function FileId(0) 159..186 162..166
+
+                FileId(0) 21..25
+                FileId(0) 89..93
+            "#]],
+        );
+    }
 }
@@ -2,20 +2,25 @@
 //!
 //! This is mostly front-end for [`ide_db::rename`], but it also includes the
 //! tests. This module also implements a couple of magic tricks, like renaming
-//! `self` and to `self` (to switch between associated function and method).
+//! `self` and to `self` (to switch between associated function and method),
+//! and updating intra-doc links which point to the renamed item.
+use either::Either;
 use hir::{AsAssocItem, InFile, Semantics};
 use ide_db::{
-    base_db::FileId,
+    base_db::{FileId, FileRange, SourceDatabaseExt},
     defs::{Definition, NameClass, NameRefClass},
     rename::{bail, format_err, source_edit_from_references, IdentifierKind},
     RootDatabase,
 };
 use stdx::{always, never};
-use syntax::{ast, AstNode, SyntaxNode};
+use syntax::{ast, AstNode, SyntaxKind, SyntaxNode, TextRange, TextSize};
 
 use text_edit::TextEdit;
 
-use crate::{FilePosition, RangeInfo, SourceChange};
+use crate::{
+    doc_links::{doc_attributes, extract_definitions_from_docs, resolve_doc_path_for_def},
+    FilePosition, RangeInfo, SourceChange,
+};
 
 pub use ide_db::rename::RenameError;
 
@@ -74,7 +79,9 @@ pub(crate) fn rename(
         }
     }
 
-    def.rename(&sema, new_name)
+    let mut source_change = def.rename(&sema, new_name)?;
+    source_change.extend(doc_link_edits(&sema, def, new_name));
+    Ok(source_change)
 }
 
 /// Called by the client when it is about to rename a file.
@@ -88,9 +95,97 @@ pub(crate) fn will_rename_file(
     let def = Definition::ModuleDef(module.into());
     let mut change = def.rename(&sema, new_name_stem).ok()?;
     change.file_system_edits.clear();
+    change.extend(doc_link_edits(&sema, def, new_name_stem));
     Some(change)
 }
 
+/// Renames the intra-doc links resolving to `def` in the docs of all items that can see it.
+fn doc_link_edits(
+    sema: &Semantics<RootDatabase>,
+    def: Definition,
+    new_name: &str,
+) -> Vec<(FileId, TextEdit)> {
+    let target = match def {
+        Definition::ModuleDef(it) => Either::Left(it),
+        Definition::Macro(it) => Either::Right(it),
+        _ => return Vec::new(),
+    };
+    let old_name = match def.name(sema.db) {
+        Some(it) => it.to_string(),
+        None => return Vec::new(),
+    };
+
+    // Only the items with the name in a doc comment or `#[doc]` string can link to `def`.
+    let mut owners: Vec<(FileId, Vec<SyntaxNode>)> = Vec::new();
+    for FileRange { file_id, range } in def.name_occurrences(sema.db) {
+        let token = match sema.parse(file_id).syntax().token_at_offset(range.start()).right_biased()
+        {
+            Some(it) if matches!(it.kind(), SyntaxKind::COMMENT | SyntaxKind::STRING) => it,
+            _ => continue,
+        };
+        let owner = match token.ancestors().find(|node| doc_attributes(sema, node).is_some()) {
+            Some(it) => it,
+            None => continue,
+        };
+        match owners.iter_mut().find(|(it, _)| *it == file_id) {
+            Some((_, nodes)) if nodes.contains(&owner) => (),
+            Some((_, nodes)) => nodes.push(owner),
+            None => owners.push((file_id, vec![owner])),
+        }
+    }
+
+    owners
+        .into_iter()
+        .filter_map(|(file_id, nodes)| {
+            let text = sema.db.file_text(file_id);
+            let mut edit = TextEdit::builder();
+            let mut has_edits = false;
+            for node in nodes {
+                let (attrs, owner) = match doc_attributes(sema, &node) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let (docs, doc_mapping) = match attrs.docs_with_rangemap(sema.db) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                for (range, link, ns) in extract_definitions_from_docs(&docs) {
+                    if resolve_doc_path_for_def(sema.db, owner, &link, ns) != Some(target) {
+                        continue;
+                    }
+                    let range = match doc_mapping.map(range) {
+                        Some(InFile { file_id: range_file, value })
+                            if range_file == file_id.into() =>
+                        {
+                            value
+                        }
+                        _ => continue,
+                    };
+                    if let Some(name_range) = link_name_range(&text[range], &link, &old_name) {
+                        edit.replace(name_range + range.start(), new_name.to_string());
+                        has_edits = true;
+                    }
+                }
+            }
+            if has_edits {
+                Some((file_id, edit.finish()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Finds the range of the last path segment of the `link` target inside of the link markup.
+fn link_name_range(markup: &str, link: &str, old_name: &str) -> Option<TextRange> {
+    let segment_start = link.rfind("::").map_or(0, |idx| idx + 2);
+    if &link[segment_start..] != old_name {
+        return None;
+    }
+    let start = markup.rfind(link)? + segment_start;
+    Some(TextRange::at(TextSize::of(&markup[..start]), TextSize::of(old_name)))
+}
+
 fn find_definition(
     sema: &Semantics<RootDatabase>,
     syntax: &SyntaxNode,
@@ -877,6 +972,59 @@ mod fo$0o;
                             ],
                         },
                     },
+                    file_system_edits: [
+                        MoveDir {
+                            src: AnchoredPathBuf {
+                                anchor: FileId(
+                                    1,
+                                ),
+                                path: "../foo",
+                            },
+                            src_id: FileId(
+                                1,
+                            ),
+                            dst: AnchoredPathBuf {
+                                anchor: FileId(
+                                    1,
+                                ),
+                                path: "../foo2",
+                            },
+                        },
+                    ],
+                    is_snippet: false,
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_rename_mod_with_child_dir() {
+        check_expect(
+            "bar",
+            r#"
+//- /lib.rs
+mod fo$0o;
+
+//- /foo.rs
+mod child;
+
+//- /foo/child.rs
+// empty
+"#,
+            expect![[r#"
+                SourceChange {
+                    source_file_edits: {
+                        FileId(
+                            0,
+                        ): TextEdit {
+                            indels: [
+                                Indel {
+                                    insert: "bar",
+                                    delete: 4..7,
+                                },
+                            ],
+                        },
+                    },
                     file_system_edits: [
                         MoveFile {
                             src: FileId(
@@ -886,7 +1034,24 @@ mod fo$0o;
                                 anchor: FileId(
                                     1,
                                 ),
-                                path: "../foo2/mod.rs",
+                                path: "bar.rs",
+                            },
+                        },
+                        MoveDir {
+                            src: AnchoredPathBuf {
+                                anchor: FileId(
+                                    1,
+                                ),
+                                path: "foo",
+                            },
+                            src_id: FileId(
+                                1,
+                            ),
+                            dst: AnchoredPathBuf {
+                                anchor: FileId(
+                                    1,
+                                ),
+                                path: "bar",
                             },
                         },
                     ],
@@ -896,6 +1061,77 @@ mod fo$0o;
         );
     }
 
+    #[test]
+    fn test_rename_mod_with_path_attr() {
+        check_expect(
+            "bar",
+            r#"
+//- /lib.rs
+#[path = "other.rs"]
+mod fo$0o;
+
+//- /other.rs
+// empty
+"#,
+            expect![[r#"
+                SourceChange {
+                    source_file_edits: {
+                        FileId(
+                            0,
+                        ): TextEdit {
+                            indels: [
+                                Indel {
+                                    insert: "bar",
+                                    delete: 25..28,
+                                },
+                            ],
+                        },
+                    },
+                    file_system_edits: [],
+                    is_snippet: false,
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_rename_mod_file_conflict() {
+        check(
+            "bar",
+            r#"
+//- /lib.rs
+mod fo$0o;
+mod bar;
+
+//- /foo.rs
+// empty
+
+//- /bar.rs
+// empty
+"#,
+            "error: Cannot rename module to `bar`: file `bar.rs` already exists",
+        );
+    }
+
+    #[test]
+    fn test_rename_mod_dir_conflict() {
+        check(
+            "bar",
+            r#"
+//- /lib.rs
+mod fo$0o;
+mod bar;
+
+//- /foo/mod.rs
+// empty
+
+//- /bar/mod.rs
+// empty
+"#,
+            "error: Cannot rename module to `bar`: file `../bar/mod.rs` already exists",
+        );
+    }
+
     #[test]
     fn test_rename_unusually_nested_mod() {
         check_expect(
@@ -1381,6 +1617,93 @@ fn foo(foo: Foo) {
         );
     }
 
+    #[test]
+    fn test_rename_field_in_pat_shorthand() {
+        check(
+            "j",
+            r#"
+struct Foo {
+    i$0: i32,
+}
+
+fn foo(foo: Foo) {
+    let Foo { i } = foo;
+    let _ = i;
+}
+"#,
+            r#"
+struct Foo {
+    j: i32,
+}
+
+fn foo(foo: Foo) {
+    let Foo { j: i } = foo;
+    let _ = i;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_updates_intra_doc_links() {
+        check(
+            "Baz",
+            r#"
+mod foo {
+    pub struct Bar$0;
+}
+
+mod other {
+    /// Converts into a [`Bar`] and back, see also [crate::foo::Bar].
+    ///
+    /// Not a link: Bar, [`Bar`](crate::foo::Bar).
+    pub fn convert(_: crate::foo::Bar) {}
+    use crate::foo::Bar;
+}
+"#,
+            r#"
+mod foo {
+    pub struct Baz;
+}
+
+mod other {
+    /// Converts into a [`Baz`] and back, see also [crate::foo::Baz].
+    ///
+    /// Not a link: Bar, [`Bar`](crate::foo::Baz).
+    pub fn convert(_: crate::foo::Baz) {}
+    use crate::foo::Baz;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_keeps_unrelated_intra_doc_links() {
+        check(
+            "renamed",
+            r#"
+mod a {
+    pub fn func$0() {}
+}
+mod b {
+    pub fn func() {}
+    /// Calls [`func`] and [`crate::a::func`].
+    pub fn caller() {}
+}
+"#,
+            r#"
+mod a {
+    pub fn renamed() {}
+}
+mod b {
+    pub fn func() {}
+    /// Calls [`func`] and [`crate::a::renamed`].
+    pub fn caller() {}
+}
+"#,
+        );
+    }
+
     #[test]
     fn test_rename_binding_in_destructure_param_pat() {
         check(
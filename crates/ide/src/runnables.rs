@@ -158,6 +158,11 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
                 _ => None,
             };
             add_opt(runnable.or_else(|| module_def_doctest(sema.db, def)), Some(def));
+            if let hir::ModuleDef::Trait(trait_) = def {
+                trait_.items(db).into_iter().for_each(|assoc| {
+                    add_opt(module_def_doctest(sema.db, assoc.into()), Some(assoc.into()))
+                });
+            }
         }
         Either::Right(impl_) => {
             add_opt(runnable_impl(&sema, &impl_), None);
@@ -216,7 +221,7 @@ pub(crate) fn related_tests(
 
     find_related_tests(&sema, position, search_scope, &mut res);
 
-    res.into_iter().collect_vec()
+    res.into_iter().sorted_by_key(|it| (it.nav.file_id, it.nav.full_range.start())).collect_vec()
 }
 
 fn find_related_tests(
@@ -225,7 +230,8 @@ fn find_related_tests(
     search_scope: Option<SearchScope>,
     tests: &mut FxHashSet<Runnable>,
 ) {
-    if let Some(refs) = references::find_all_refs(sema, position, search_scope) {
+    if let Some(refs) = references::find_all_refs(sema, position, search_scope, &Default::default())
+    {
         for (file_id, refs) in refs.references {
             let file = sema.parse(file_id);
             let file = file.syntax();
@@ -309,7 +315,7 @@ pub(crate) fn runnable_fn(sema: &Semantics<RootDatabase>, def: hir::Function) ->
         if test_related_attribute(&func.value).is_some() {
             let attr = TestAttr::from_fn(&func.value);
             RunnableKind::Test { test_id, attr }
-        } else if func.value.has_atom_attr("bench") {
+        } else if def.is_bench(sema.db) {
             RunnableKind::Bench { test_id }
         } else {
             return None;
@@ -422,6 +428,10 @@ fn module_def_doctest(db: &RootDatabase, def: hir::ModuleDef) -> Option<Runnable
                     return Some(path);
                 }
             }
+            if let hir::AssocItemContainer::Trait(trait_) = assoc_item.container(db) {
+                format_to!(path, "{}::{}", trait_.name(db), def_name);
+                return Some(path);
+            }
         }
         format_to!(path, "{}", def_name);
         Some(path)
@@ -545,6 +555,82 @@ mod tests {
         expect.assert_debug_eq(&tests);
     }
 
+    #[test]
+    fn test_runnables_test_and_bench_attrs() {
+        check(
+            r#"
+//- /lib.rs
+$0
+#[test]
+fn test() {}
+
+#[bench]
+fn bench() {}
+
+fn helper() {}
+"#,
+            &[Test, Bench, TestMod],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 1..21,
+                            focus_range: 12..16,
+                            name: "test",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "test",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 23..45,
+                            focus_range: 35..40,
+                            name: "bench",
+                            kind: Function,
+                        },
+                        kind: Bench {
+                            test_id: Path(
+                                "bench",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 0..62,
+                            name: "",
+                            kind: Module,
+                        },
+                        kind: TestMod {
+                            path: "",
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn test_runnables() {
         check(
@@ -968,6 +1054,127 @@ impl Data {
         );
     }
 
+    #[test]
+    fn test_runnables_doc_test_in_trait() {
+        check(
+            r#"
+//- /lib.rs
+$0
+trait Tr {
+    /// ```
+    /// let x = 5;
+    /// ```
+    fn provided() {}
+}
+"#,
+            &[DocTest],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 16..75,
+                            name: "provided",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "Tr::provided",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_runnables_doc_test_reexported() {
+        check(
+            r#"
+//- /lib.rs
+$0
+pub use inner::Data as Renamed;
+mod inner {
+    pub struct Data;
+    impl Data {
+        /// ```
+        /// let x = 5;
+        /// ```
+        pub fn foo() {}
+    }
+}
+"#,
+            &[DocTest],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 90..160,
+                            name: "foo",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "inner::Data::foo",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_runnables_doc_test_in_macro_generated_impl() {
+        check(
+            r#"
+//- /lib.rs
+$0
+macro_rules! gen {
+    () => {
+        impl Data {
+            /// ```
+            /// let x = 5;
+            /// ```
+            fn foo() {}
+        }
+    };
+}
+struct Data;
+gen!();
+"#,
+            &[DocTest],
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 175..182,
+                            name: "foo",
+                        },
+                        kind: DocTest {
+                            test_id: Path(
+                                "Data::foo",
+                            ),
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn test_runnables_module() {
         check(
@@ -1955,6 +2162,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn finds_tests_in_other_crates() {
+        check_tests(
+            r#"
+//- /lib.rs crate:lib cfg:test
+pub fn foo$0() {}
+
+#[cfg(test)]
+mod tests {
+    use super::foo;
+
+    #[test]
+    fn foo_test() {
+        foo();
+    }
+
+    #[test]
+    fn foo2_test() {
+        foo();
+    }
+}
+
+//- /tests/integration.rs crate:integration deps:lib
+#[test]
+fn integration_test() {
+    lib::foo();
+    lib::foo();
+}
+"#,
+            expect![[r#"
+                [
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 67..115,
+                            focus_range: 82..90,
+                            name: "foo_test",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "tests::foo_test",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 121..170,
+                            focus_range: 136..145,
+                            name: "foo2_test",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "tests::foo2_test",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                    Runnable {
+                        use_name_in_title: false,
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                1,
+                            ),
+                            full_range: 0..65,
+                            focus_range: 11..27,
+                            name: "integration_test",
+                            kind: Function,
+                        },
+                        kind: Test {
+                            test_id: Path(
+                                "integration_test",
+                            ),
+                            attr: TestAttr {
+                                ignore: false,
+                            },
+                        },
+                        cfg: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
     #[test]
     fn doc_test_type_params() {
         check(
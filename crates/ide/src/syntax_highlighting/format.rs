@@ -1,8 +1,8 @@
 //! Syntax highlighting for format macro strings.
-use ide_db::SymbolKind;
+use ide_db::{helpers::format_string::is_format_string, SymbolKind};
 use syntax::{
     ast::{self, FormatSpecifier, HasFormatSpecifier},
-    AstNode, AstToken, TextRange,
+    TextRange,
 };
 
 use crate::{syntax_highlighting::highlights::Highlights, HlRange, HlTag};
@@ -12,7 +12,7 @@ pub(super) fn highlight_format_string(
     string: &ast::String,
     range: TextRange,
 ) {
-    if is_format_string(string).is_none() {
+    if !is_format_string(string) {
         return;
     }
 
@@ -27,25 +27,6 @@ pub(super) fn highlight_format_string(
     });
 }
 
-fn is_format_string(string: &ast::String) -> Option<()> {
-    let parent = string.syntax().parent()?;
-
-    let name = parent.parent().and_then(ast::MacroCall::cast)?.path()?.segment()?.name_ref()?;
-    if !matches!(name.text().as_str(), "format_args" | "format_args_nl") {
-        return None;
-    }
-
-    let first_literal = parent
-        .children_with_tokens()
-        .filter_map(|it| it.as_token().cloned().and_then(ast::String::cast))
-        .next()?;
-    if &first_literal != string {
-        return None;
-    }
-
-    Some(())
-}
-
 fn highlight_format_specifier(kind: FormatSpecifier) -> Option<HlTag> {
     Some(match kind {
         FormatSpecifier::Open
@@ -236,7 +236,7 @@ fn highlight_name_ref_in_attr(sema: &Semantics<RootDatabase>, name_ref: ast::Nam
             {
                 HlTag::Symbol(SymbolKind::Module)
             }
-            NameRefClass::Definition(Definition::Macro(m)) if m.kind() == hir::MacroKind::Attr => {
+            NameRefClass::Definition(Definition::Macro(m)) if m.is_attr() => {
                 HlTag::Symbol(SymbolKind::Macro)
             }
             _ => HlTag::BuiltinAttr,
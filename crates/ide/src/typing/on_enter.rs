@@ -2,7 +2,7 @@
 //! comments, but should handle indent some time in the future as well.
 
 use ide_db::base_db::{FilePosition, SourceDatabase};
-use ide_db::RootDatabase;
+use ide_db::{helpers::SnippetCap, RootDatabase};
 use syntax::{
     algo::find_node_at_offset,
     ast::{self, edit::IndentLevel, AstToken},
@@ -20,7 +20,10 @@ use text_edit::TextEdit;
 // - kbd:[Enter] inside triple-slash comments automatically inserts `///`
 // - kbd:[Enter] in the middle or after a trailing space in `//` inserts `//`
 // - kbd:[Enter] inside `//!` doc comments automatically inserts `//!`
+// - kbd:[Enter] inside `/** */` and `/*! */` doc comments automatically inserts ` * ` and puts
+//   the closing `*/` on its own line
 // - kbd:[Enter] after `{` indents contents and closing `}` of single-line block
+// - kbd:[Enter] between `{` and `}` of an empty block adds an indented blank line
 //
 // This action needs to be assigned to shortcut explicitly.
 //
@@ -37,7 +40,11 @@ use text_edit::TextEdit;
 // ----
 //
 // image::https://user-images.githubusercontent.com/48062697/113065578-04c21800-91b1-11eb-82b8-22b8c481e645.gif[]
-pub(crate) fn on_enter(db: &RootDatabase, position: FilePosition) -> Option<TextEdit> {
+pub(crate) fn on_enter(
+    db: &RootDatabase,
+    position: FilePosition,
+    snippet_cap: Option<SnippetCap>,
+) -> Option<TextEdit> {
     let parse = db.parse(position.file_id);
     let file = parse.tree();
     let token = file.syntax().token_at_offset(position.offset).left_biased()?;
@@ -47,6 +54,13 @@ pub(crate) fn on_enter(db: &RootDatabase, position: FilePosition) -> Option<Text
     }
 
     if token.kind() == L_CURLY {
+        // Typing enter between `{` and `}`. Without snippets the cursor would end up after the
+        // `}`, so this is only done when the client supports them.
+        if let Some(edit) = snippet_cap.and_then(|_| on_enter_in_empty_braces(&token, position)) {
+            cov_mark::hit!(indent_empty_braces);
+            return Some(edit);
+        }
+
         // Typing enter after the `{` of a block expression, where the `}` is on the same line
         if let Some(edit) = find_node_at_offset(file.syntax(), position.offset - TextSize::of('{'))
             .and_then(|block| on_enter_in_block(block, position))
@@ -73,7 +87,7 @@ fn on_enter_in_comment(
     offset: TextSize,
 ) -> Option<TextEdit> {
     if comment.kind().shape.is_block() {
-        return None;
+        return on_enter_in_block_doc_comment(comment, file, offset);
     }
 
     let prefix = comment.prefix();
@@ -106,6 +120,42 @@ fn on_enter_in_comment(
     Some(edit)
 }
 
+fn on_enter_in_block_doc_comment(
+    comment: &ast::Comment,
+    file: &ast::SourceFile,
+    offset: TextSize,
+) -> Option<TextEdit> {
+    comment.kind().doc?;
+    let comment_range = comment.syntax().text_range();
+    let contents_start = comment_range.start() + TextSize::of(comment.prefix());
+    let contents_end = comment_range.end() - TextSize::of("*/");
+    if !comment.text().ends_with("*/") || offset < contents_start || offset > contents_end {
+        return None;
+    }
+
+    let indent = node_indent(file, comment.syntax())?;
+    let rest = &comment.text()[TextRange::new(offset, contents_end) - comment_range.start()];
+    let edit = if rest.trim().is_empty() {
+        // Nothing but the closing `*/` follows, so move it to its own line, aligned with the `*`s.
+        cov_mark::hit!(aligns_block_doc_comment_end);
+        TextEdit::replace(
+            TextRange::new(offset, contents_end),
+            format!("\n{} * $0\n{} ", indent, indent),
+        )
+    } else {
+        TextEdit::insert(offset, format!("\n{} * $0", indent))
+    };
+    Some(edit)
+}
+
+fn on_enter_in_empty_braces(l_curly: &SyntaxToken, position: FilePosition) -> Option<TextEdit> {
+    if l_curly.next_token()?.kind() != R_CURLY {
+        return None;
+    }
+    let indent = IndentLevel::from_token(l_curly);
+    Some(TextEdit::insert(position.offset, format!("\n{}$0\n{}", indent + 1, indent)))
+}
+
 fn on_enter_in_block(block: ast::BlockExpr, position: FilePosition) -> Option<TextEdit> {
     let contents = block_contents(&block)?;
 
@@ -182,6 +232,7 @@ fn node_indent(file: &SourceFile, token: &SyntaxToken) -> Option<SmolStr> {
 
 #[cfg(test)]
 mod tests {
+    use ide_db::helpers::SnippetCap;
     use stdx::trim_indent;
     use test_utils::assert_eq_text;
 
@@ -189,7 +240,7 @@ mod tests {
 
     fn apply_on_enter(before: &str) -> Option<String> {
         let (analysis, position) = fixture::position(before);
-        let result = analysis.on_enter(position).unwrap()?;
+        let result = analysis.on_enter(position, SnippetCap::new(true)).unwrap()?;
 
         let mut actual = analysis.file_text(position.file_id).unwrap().to_string();
         result.apply(&mut actual);
@@ -464,19 +515,44 @@ fn f() {
     }
 
     #[test]
-    fn does_not_indent_empty_block() {
-        do_check_noop(
+    fn indents_empty_block() {
+        cov_mark::check!(indent_empty_braces);
+        do_check(
             r#"
 fn f() {$0}
+        "#,
+            r#"
+fn f() {
+    $0
+}
         "#,
         );
-        do_check_noop(
+        do_check(
             r#"
-fn f() {{$0}}
+impl S {
+    fn f() {{$0}}
+}
+        "#,
+            r#"
+impl S {
+    fn f() {{
+        $0
+    }}
+}
         "#,
         );
     }
 
+    #[test]
+    fn does_not_indent_empty_block_without_snippets() {
+        let (analysis, position) = fixture::position(
+            r#"
+fn f() {$0}
+"#,
+        );
+        assert!(analysis.on_enter(position, None).unwrap().is_none());
+    }
+
     #[test]
     fn does_not_indent_block_with_too_much_content() {
         do_check_noop(
@@ -599,4 +675,99 @@ use path::{$0
             "#,
         );
     }
+
+    #[test]
+    fn continues_inner_doc_comment() {
+        do_check(
+            r"
+//! Crate docs$0
+fn foo() {}
+",
+            r"
+//! Crate docs
+//! $0
+fn foo() {}
+",
+        );
+    }
+
+    #[test]
+    fn continues_block_doc_comment() {
+        do_check(
+            r"
+    /**
+     * Some$0 docs
+     */
+    fn foo() {}
+",
+            r"
+    /**
+     * Some
+     * $0 docs
+     */
+    fn foo() {}
+",
+        );
+        do_check(
+            r"
+/*! Crate$0 docs */
+",
+            r"
+/*! Crate
+ * $0 docs */
+",
+        );
+    }
+
+    #[test]
+    fn aligns_end_of_block_doc_comment() {
+        cov_mark::check_count!(aligns_block_doc_comment_end, 2);
+        do_check(
+            r"
+impl S {
+    /** Some docs$0 */
+    fn foo() {}
+}
+",
+            r"
+impl S {
+    /** Some docs
+     * $0
+     */
+    fn foo() {}
+}
+",
+        );
+        do_check(
+            r"
+/**
+ * Some docs$0
+ */
+fn foo() {}
+",
+            r"
+/**
+ * Some docs
+ * $0
+ */
+fn foo() {}
+",
+        );
+    }
+
+    #[test]
+    fn does_not_continue_block_comment() {
+        do_check_noop(
+            r"
+/* Some$0 comment */
+fn foo() {}
+",
+        );
+        do_check_noop(
+            r"
+/** Some docs */$0
+fn foo() {}
+",
+        );
+    }
 }
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use dot::{Id, LabelText};
 use ide_db::{
-    base_db::{CrateGraph, CrateId, Dependency, SourceDatabase, SourceDatabaseExt},
+    base_db::{CrateGraph, CrateId, CrateOrigin, Dependency, SourceDatabase},
     RootDatabase,
 };
 use rustc_hash::FxHashSet;
@@ -12,7 +12,8 @@ use rustc_hash::FxHashSet;
 // Renders the currently loaded crate graph as an SVG graphic. Requires the `dot` tool, which
 // is part of graphviz, to be installed.
 //
-// Only workspace crates are included, no crates.io dependencies or sysroot crates.
+// Only workspace crates and their direct dependencies are included. The full graph, with all
+// transitive and sysroot dependencies, can be rendered with **Rust Analyzer: View Crate Graph (Full)**.
 //
 // |===
 // | Editor  | Action Name
@@ -21,18 +22,16 @@ use rustc_hash::FxHashSet;
 // |===
 pub(crate) fn view_crate_graph(db: &RootDatabase, full: bool) -> Result<String, String> {
     let crate_graph = db.crate_graph();
-    let crates_to_render = crate_graph
-        .iter()
-        .filter(|krate| {
-            if full {
-                true
-            } else {
-                // Only render workspace crates
-                let root_id = db.file_source_root(crate_graph[*krate].root_file_id);
-                !db.source_root(root_id).is_library
-            }
-        })
-        .collect();
+    let mut crates_to_render = FxHashSet::default();
+    for krate in crate_graph.iter() {
+        if full {
+            crates_to_render.insert(krate);
+        } else if crate_graph[krate].origin == CrateOrigin::Workspace {
+            // Workspace crates bring their direct dependencies along.
+            crates_to_render.insert(krate);
+            crates_to_render.extend(crate_graph[krate].dependencies.iter().map(|dep| dep.crate_id));
+        }
+    }
     let graph = DotCrateGraph { graph: crate_graph, crates_to_render };
 
     let mut dot = Vec::new();
@@ -49,18 +48,18 @@ type Edge<'a> = (CrateId, &'a Dependency);
 
 impl<'a> dot::GraphWalk<'a, CrateId, Edge<'a>> for DotCrateGraph {
     fn nodes(&'a self) -> dot::Nodes<'a, CrateId> {
-        self.crates_to_render.iter().copied().collect()
+        self.sorted_crates().into()
     }
 
     fn edges(&'a self) -> dot::Edges<'a, Edge<'a>> {
-        self.crates_to_render
-            .iter()
+        self.sorted_crates()
+            .into_iter()
             .flat_map(|krate| {
-                self.graph[*krate]
+                self.graph[krate]
                     .dependencies
                     .iter()
                     .filter(|dep| self.crates_to_render.contains(&dep.crate_id))
-                    .map(move |dep| (*krate, dep))
+                    .map(move |dep| (krate, dep))
             })
             .collect()
     }
@@ -88,7 +87,82 @@ impl<'a> dot::Labeller<'a, CrateId, Edge<'a>> for DotCrateGraph {
     }
 
     fn node_label(&'a self, n: &CrateId) -> LabelText<'a> {
-        let name = self.graph[*n].display_name.as_ref().map_or("(unnamed crate)", |name| &*name);
-        LabelText::LabelStr(name.into())
+        let data = &self.graph[*n];
+        let name = data.display_name.as_ref().map_or("(unnamed crate)", |name| &*name);
+        let origin = match data.origin {
+            CrateOrigin::Workspace => "workspace member",
+            CrateOrigin::Dependency => "dependency",
+        };
+        LabelText::LabelStr(format!("{}\nedition {}\n{}", name, data.edition, origin).into())
+    }
+
+    fn edge_label(&'a self, (_, dep): &Edge<'a>) -> LabelText<'a> {
+        // Only renamed dependencies get a label, the edge itself says enough otherwise.
+        let renamed = match &self.graph[dep.crate_id].display_name {
+            Some(name) => **name != *dep.name,
+            None => true,
+        };
+        let label = if renamed { dep.name.to_string() } else { String::new() };
+        LabelText::LabelStr(label.into())
+    }
+}
+
+impl DotCrateGraph {
+    fn sorted_crates(&self) -> Vec<CrateId> {
+        let mut crates: Vec<_> = self.crates_to_render.iter().copied().collect();
+        crates.sort();
+        crates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    fn check(ra_fixture: &str, full: bool, expect: Expect) {
+        let (analysis, _) = fixture::file(ra_fixture);
+        let dot = analysis.view_crate_graph(full).unwrap().unwrap();
+        expect.assert_eq(&dot);
+    }
+
+    const FIXTURE: &str = r#"
+//- /main.rs crate:main deps:serde
+fn main() {}
+//- /serde/lib.rs crate:serde deps:serde_derive new_source_root:library
+//- /serde_derive/lib.rs crate:serde_derive edition:2015
+"#;
+
+    #[test]
+    fn workspace_crates_and_direct_deps() {
+        check(
+            FIXTURE,
+            false,
+            expect![[r#"
+            digraph rust_analyzer_crate_graph {
+                _0[label="main\nedition 2018\nworkspace member"][shape="box"];
+                _1[label="serde\nedition 2018\ndependency"][shape="box"];
+                _0 -> _1[label=""];
+            }
+        "#]],
+        );
+    }
+
+    #[test]
+    fn full_crate_graph() {
+        check(
+            FIXTURE,
+            true,
+            expect![[r#"
+            digraph rust_analyzer_crate_graph {
+                _0[label="main\nedition 2018\nworkspace member"][shape="box"];
+                _1[label="serde\nedition 2018\ndependency"][shape="box"];
+                _2[label="serde_derive\nedition 2015\ndependency"][shape="box"];
+                _0 -> _1[label=""];
+                _1 -> _2[label=""];
+            }
+        "#]],
+        );
     }
 }
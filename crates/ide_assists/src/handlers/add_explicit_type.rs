@@ -143,6 +143,19 @@ fn f() {
         check_assist_not_applicable(add_explicit_type, r#"fn f() { let a$0 = None; }"#);
     }
 
+    #[test]
+    fn add_explicit_type_not_applicable_partially_inferred() {
+        cov_mark::check!(add_explicit_type_not_applicable_if_ty_not_inferred);
+        check_assist_not_applicable(
+            add_explicit_type,
+            r#"
+struct Wrapper<T>(T);
+fn make<T>() -> Wrapper<T> { loop {} }
+fn f() { let a$0 = make(); }
+"#,
+        );
+    }
+
     #[test]
     fn add_explicit_type_not_applicable_closure_expr() {
         check_assist_not_applicable(add_explicit_type, r#"fn f() { let a$0 = || {}; }"#);
@@ -177,6 +190,19 @@ fn main() {
 fn main() {
     let v: [f64; 2] = [0.0; 2];
 }
+"#,
+        );
+        check_assist(
+            add_explicit_type,
+            r#"
+fn main() {
+    let $0l = [0.0; 2+2];
+}
+"#,
+            r#"
+fn main() {
+    let l: [f64; 4] = [0.0; 2+2];
+}
 "#,
         );
         // note: this may break later if we add more consteval. it just needs to be something that our
@@ -184,8 +210,9 @@ fn main() {
         check_assist_not_applicable(
             add_explicit_type,
             r#"
+fn len() -> usize { 4 }
 fn main() {
-    let $0l = [0.0; 2+2];
+    let $0l = [0.0; len()];
 }
 "#,
         );
@@ -212,6 +239,82 @@ fn main() {
         );
     }
 
+    #[test]
+    fn add_explicit_type_generic_struct() {
+        check_assist(
+            add_explicit_type,
+            r#"
+struct Wrapper<T>(T);
+
+fn main() {
+    let w$0 = Wrapper((1u8, "x"));
+}
+"#,
+            r#"
+struct Wrapper<T>(T);
+
+fn main() {
+    let w: Wrapper<(u8, &str)> = Wrapper((1u8, "x"));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_qualifies_path_from_other_module() {
+        check_assist(
+            add_explicit_type,
+            r#"
+mod foo {
+    pub mod bar {
+        pub struct Baz;
+    }
+    pub fn baz() -> bar::Baz { bar::Baz }
+}
+
+fn main() {
+    let baz$0 = foo::baz();
+}
+"#,
+            r#"
+mod foo {
+    pub mod bar {
+        pub struct Baz;
+    }
+    pub fn baz() -> bar::Baz { bar::Baz }
+}
+
+fn main() {
+    let baz: foo::bar::Baz = foo::baz();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_explicit_type_qualifies_path_from_other_crate() {
+        check_assist(
+            add_explicit_type,
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() {
+    let foo$0 = dep::make();
+}
+//- /dep.rs crate:dep
+mod private {
+    pub struct Foo;
+}
+pub use private::Foo;
+pub fn make() -> Foo { Foo }
+"#,
+            r#"
+fn main() {
+    let foo: dep::Foo = dep::make();
+}
+"#,
+        );
+    }
+
     #[test]
     fn type_should_be_added_after_pattern() {
         // LetStmt = Attr* 'let' Pat (':' Type)? '=' initializer:Expr ';'
@@ -0,0 +1,135 @@
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, AttrsOwner, NameOwner},
+    SyntaxKind::{COMMENT, WHITESPACE},
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: add_must_use
+//
+// Adds a `#[must_use]` attribute to a function returning a value, leaving the cursor where
+// an optional message can be added.
+//
+// ```
+// pub fn len$0(&self) -> usize { 0 }
+// ```
+// ->
+// ```
+// #[must_use$0]
+// pub fn len(&self) -> usize { 0 }
+// ```
+pub(crate) fn add_must_use(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::Fn>()?;
+    if let Some(body) = fn_def.body() {
+        if body.syntax().text_range().contains(ctx.offset()) {
+            return None;
+        }
+    }
+    if fn_def.attrs().any(|attr| attr.simple_name().map_or(false, |name| name == "must_use")) {
+        cov_mark::hit!(add_must_use_already_annotated);
+        return None;
+    }
+    let func = ctx.sema.to_def(&fn_def)?;
+    if func.ret_type(ctx.db()).is_unit() {
+        return None;
+    }
+
+    // Doc comments are kept above the new attribute.
+    let offset = fn_def
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?
+        .text_range()
+        .start();
+    let indent = IndentLevel::from_node(fn_def.syntax());
+    let target = fn_def.name()?.syntax().text_range();
+    acc.add(AssistId("add_must_use", AssistKind::None), "Add `#[must_use]`", target, |builder| {
+        match ctx.config.snippet_cap {
+            Some(cap) => builder.insert_snippet(cap, offset, format!("#[must_use$0]\n{}", indent)),
+            None => builder.insert(offset, format!("#[must_use]\n{}", indent)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn adds_must_use_to_fn_returning_value() {
+        check_assist(
+            add_must_use,
+            r#"
+fn f$0() -> u32 { 0 }
+"#,
+            r#"
+#[must_use$0]
+fn f() -> u32 { 0 }
+"#,
+        );
+    }
+
+    #[test]
+    fn adds_must_use_after_doc_comment() {
+        check_assist(
+            add_must_use,
+            r#"
+struct S;
+impl S {
+    /// Returns zero.
+    #[inline]
+    pub fn zero(&self) -> u32$0 { 0 }
+}
+"#,
+            r#"
+struct S;
+impl S {
+    /// Returns zero.
+    #[must_use$0]
+    #[inline]
+    pub fn zero(&self) -> u32 { 0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_fn_without_return_value() {
+        check_assist_not_applicable(
+            add_must_use,
+            r#"
+fn f$0() {}
+"#,
+        );
+        check_assist_not_applicable(
+            add_must_use,
+            r#"
+fn f$0() -> () {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_already_annotated() {
+        cov_mark::check!(add_must_use_already_annotated);
+        check_assist_not_applicable(
+            add_must_use,
+            r#"
+#[must_use = "the count is the only effect"]
+fn f$0() -> u32 { 0 }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_fn_body() {
+        check_assist_not_applicable(
+            add_must_use,
+            r#"
+fn f() -> u32 { $00 }
+"#,
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use ide_db::RootDatabase;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, BinaryOp},
+    SyntaxKind,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: convert_index_to_get
+//
+// Replaces indexing of a slice, array or `Vec` with a call to `get`, which returns
+// `None` instead of panicking for out-of-bounds indices.
+//
+// ```
+// fn main() {
+//     let v = [1, 2, 3];
+//     let x = v[$01];
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let v = [1, 2, 3];
+//     let x = v.get(1);
+// }
+// ```
+pub(crate) fn convert_index_to_get(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let index_expr = ctx.find_node_at_offset::<ast::IndexExpr>()?;
+    let base = index_expr.base()?;
+    let index = index_expr.index()?;
+    if matches!(index, ast::Expr::RangeExpr(_)) || is_mutable_place(&index_expr) {
+        return None;
+    }
+    let base_ty = ctx.sema.type_of_expr(&base)?.original;
+    if !derefs_to_slice_or_array(ctx.db(), &base_ty) {
+        return None;
+    }
+
+    let group = GroupLabel("Replace index with `.get()`".into());
+    let target = index_expr.syntax().text_range();
+    let get_call = format!("{}.get({})", base, index);
+    acc.add_group(
+        &group,
+        AssistId("convert_index_to_get", AssistKind::RefactorRewrite),
+        "Replace index with `.get()`",
+        target,
+        |builder| builder.replace(target, get_call.clone()),
+    );
+
+    let stmt = enclosing_stmt(&index_expr)?;
+    acc.add_group(
+        &group,
+        AssistId("convert_index_to_get_if_let", AssistKind::RefactorRewrite),
+        "Replace index with `if let Some(..) = .get()`",
+        target,
+        |builder| {
+            let stmt_range = stmt.syntax().text_range();
+            let indent = IndentLevel::from_node(stmt.syntax());
+            let mut body = stmt.syntax().text().to_string();
+            let index_range = target - stmt_range.start();
+            body.replace_range(std::ops::Range::<usize>::from(index_range), "*elem");
+            let body = body.replace('\n', &format!("\n{}", IndentLevel(1)));
+            let replacement = format!(
+                "if let Some(elem) = {} {{\n{}{}\n{}}}",
+                get_call,
+                indent + 1,
+                body,
+                indent
+            );
+            builder.replace(stmt_range, replacement);
+        },
+    )
+}
+
+fn derefs_to_slice_or_array(db: &RootDatabase, ty: &hir::Type) -> bool {
+    ty.autoderef(db).any(|ty| ty.as_slice_element().is_some() || ty.as_array(db).is_some())
+}
+
+/// `.get()` only gives shared access, so assignments and mutable borrows can't use it.
+fn is_mutable_place(index_expr: &ast::IndexExpr) -> bool {
+    match index_expr.syntax().parent().and_then(ast::Expr::cast) {
+        Some(ast::Expr::BinExpr(bin_expr)) => {
+            matches!(bin_expr.op_kind(), Some(BinaryOp::Assignment { .. }))
+                && bin_expr.lhs().map_or(false, |lhs| lhs.syntax() == index_expr.syntax())
+        }
+        Some(ast::Expr::RefExpr(ref_expr)) => ref_expr.mut_token().is_some(),
+        _ => false,
+    }
+}
+
+/// The expression statement containing `index_expr`, if wrapping it in an `if let` doesn't
+/// move the index out of the scope of any bindings.
+fn enclosing_stmt(index_expr: &ast::IndexExpr) -> Option<ast::ExprStmt> {
+    for node in index_expr.syntax().ancestors().skip(1) {
+        match node.kind() {
+            SyntaxKind::EXPR_STMT => return ast::ExprStmt::cast(node),
+            SyntaxKind::BLOCK_EXPR
+            | SyntaxKind::CLOSURE_EXPR
+            | SyntaxKind::MATCH_ARM
+            | SyntaxKind::CONDITION
+            | SyntaxKind::LET_STMT => return None,
+            _ => (),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn converts_slice_index() {
+        check_assist_by_label(
+            convert_index_to_get,
+            r#"
+//- minicore: index, slice
+fn first(items: &[u32]) -> Option<&u32> {
+    let x = items[$00];
+    x
+}
+"#,
+            r#"
+fn first(items: &[u32]) -> Option<&u32> {
+    let x = items.get(0);
+    x
+}
+"#,
+            "Replace index with `.get()`",
+        );
+    }
+
+    #[test]
+    fn converts_vec_index_in_if_let() {
+        check_assist_by_label(
+            convert_index_to_get,
+            r#"
+//- minicore: index, slice, deref
+struct Vec<T>(T);
+impl<T> core::ops::Deref for Vec<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] { loop {} }
+}
+fn consume(x: u32) {}
+fn main() {
+    let v: Vec<u32> = Vec(1);
+    let i = 2;
+    consume(v[$0i]);
+}
+"#,
+            r#"
+struct Vec<T>(T);
+impl<T> core::ops::Deref for Vec<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] { loop {} }
+}
+fn consume(x: u32) {}
+fn main() {
+    let v: Vec<u32> = Vec(1);
+    let i = 2;
+    if let Some(elem) = v.get(i) {
+        consume(*elem);
+    }
+}
+"#,
+            "Replace index with `if let Some(..) = .get()`",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_assignments() {
+        check_assist_not_applicable(
+            convert_index_to_get,
+            r#"
+//- minicore: index, slice
+fn main() {
+    let mut a = [1, 2];
+    a[$00] = 3;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_non_slices() {
+        check_assist_not_applicable(
+            convert_index_to_get,
+            r#"
+//- minicore: index
+struct Map;
+impl core::ops::Index<u32> for Map {
+    type Output = u32;
+    fn index(&self, key: u32) -> &u32 { loop {} }
+}
+fn main() {
+    let m = Map;
+    let x = m[$01];
+}
+"#,
+        );
+    }
+}
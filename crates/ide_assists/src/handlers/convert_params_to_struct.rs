@@ -0,0 +1,273 @@
+use hir::{HasSource, PathResolution};
+use ide_db::RootDatabase;
+use itertools::Itertools;
+use syntax::{
+    ast::{self, edit::IndentLevel, ArgListOwner, GenericParamsOwner, NameOwner, VisibilityOwner},
+    AstNode, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_params_to_struct
+//
+// Converts the parameters of the called function into a struct with named fields, and
+// rewrites the call under the cursor to pass that struct.
+//
+// ```
+// fn connect(host: &str, port: u16, secure: bool) {}
+//
+// fn main() {
+//     connect$0("localhost", 80, false);
+// }
+// ```
+// ->
+// ```
+// struct ConnectArgs<'a> {
+//     host: &'a str,
+//     port: u16,
+//     secure: bool,
+// }
+//
+// fn connect(ConnectArgs { host, port, secure }: ConnectArgs) {}
+//
+// fn main() {
+//     connect(ConnectArgs { host: "localhost", port: 80, secure: false });
+// }
+// ```
+pub(crate) fn convert_params_to_struct(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let path_expr = ctx.find_node_at_offset::<ast::PathExpr>()?;
+    let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+    let path = path_expr.path()?;
+    let function = match ctx.sema.resolve_path(&path)? {
+        PathResolution::Def(hir::ModuleDef::Function(it)) => it,
+        _ => return None,
+    };
+    if function.self_param(ctx.db()).is_some() {
+        return None;
+    }
+
+    let fn_source = function.source(ctx.db())?;
+    let fn_file_id = fn_source.file_id.original_file(ctx.db());
+    let fn_def = fn_source.value;
+    if fn_def.generic_param_list().is_some() {
+        cov_mark::hit!(convert_params_to_struct_not_applicable_to_generic_fns);
+        return None;
+    }
+    let param_list = fn_def.param_list()?;
+    let args = call.arg_list()?.args().collect::<Vec<_>>();
+
+    let mut fields = Vec::new();
+    for (param, hir_param) in param_list.params().zip(function.assoc_fn_params(ctx.db())) {
+        let name = hir_param.name(ctx.db())?;
+        let pat = match param.pat()? {
+            ast::Pat::IdentPat(it) if it.pat().is_none() => it,
+            _ => return None,
+        };
+        fields.push((name, pat, param.ty()?));
+    }
+    if fields.len() < 2 || fields.len() != args.len() {
+        return None;
+    }
+
+    let struct_name = format!("{}Args", to_camel_case(&fn_def.name()?.text()));
+    if name_is_taken(ctx.db(), function.module(ctx.db()), &struct_name) {
+        return None;
+    }
+
+    let target = call.syntax().text_range();
+    acc.add(
+        AssistId("convert_params_to_struct", AssistKind::RefactorRewrite),
+        format!("Convert parameters of `{}` into `{}`", function.name(ctx.db()), struct_name),
+        target,
+        |builder| {
+            // Refer to the struct the same way the call refers to the function.
+            let struct_path = match path.qualifier() {
+                Some(qualifier) => format!("{}::{}", qualifier, struct_name),
+                None => struct_name.clone(),
+            };
+            let field_inits = fields
+                .iter()
+                .zip(&args)
+                .map(|((name, _, _), arg)| match arg {
+                    ast::Expr::PathExpr(path) if path.to_string() == name.to_string() => {
+                        name.to_string()
+                    }
+                    _ => format!("{}: {}", name, arg),
+                })
+                .join(", ");
+            builder.replace(
+                call.arg_list().unwrap().syntax().text_range(),
+                format!("({} {{ {} }})", struct_path, field_inits),
+            );
+
+            builder.edit_file(fn_file_id);
+            let pats = fields.iter().map(|(_, pat, _)| pat).join(", ");
+            builder.replace(
+                param_list.syntax().text_range(),
+                format!("({} {{ {} }}: {})", struct_name, pats, struct_name),
+            );
+
+            let indent = IndentLevel::from_node(fn_def.syntax());
+            let vis = fn_def.visibility().map_or(String::new(), |vis| format!("{} ", vis));
+            let mut has_lifetimes = false;
+            let struct_fields = fields
+                .iter()
+                .map(|(name, _, ty)| {
+                    let ty = explicit_lifetimes(ty, &mut has_lifetimes);
+                    format!("{}    {}{}: {},", indent, vis, name, ty)
+                })
+                .join("\n");
+            let generics = if has_lifetimes { "<'a>" } else { "" };
+            builder.insert(
+                fn_def.syntax().text_range().start(),
+                format!(
+                    "{}struct {}{} {{\n{}\n{}}}\n\n{}",
+                    vis, struct_name, generics, struct_fields, indent, indent
+                ),
+            );
+        },
+    )
+}
+
+/// Spells out the lifetimes of references, which can't be elided in a struct field.
+fn explicit_lifetimes(ty: &ast::Type, has_lifetimes: &mut bool) -> String {
+    let mut res = String::new();
+    for token in ty.syntax().descendants_with_tokens().filter_map(|it| it.into_token()) {
+        res.push_str(token.text());
+        let is_elided_ref = token.kind() == T![&]
+            && token
+                .parent()
+                .and_then(ast::RefType::cast)
+                .map_or(false, |it| it.lifetime().is_none());
+        if is_elided_ref {
+            *has_lifetimes = true;
+            res.push_str("'a ");
+        }
+    }
+    res
+}
+
+fn name_is_taken(db: &RootDatabase, module: hir::Module, name: &str) -> bool {
+    module.scope(db, None).into_iter().any(|(it, _)| it.to_string() == name)
+}
+
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|it| !it.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| {
+                first.to_uppercase().chain(chars).collect::<String>()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_three_params() {
+        check_assist(
+            convert_params_to_struct,
+            r#"
+fn set_flags(verbose: bool, mut level: u8, name: String) {
+    level += 1;
+}
+
+fn main() {
+    let verbose = true;
+    set_flags$0(verbose, 3, String::new());
+}
+"#,
+            r#"
+struct SetFlagsArgs {
+    verbose: bool,
+    level: u8,
+    name: String,
+}
+
+fn set_flags(SetFlagsArgs { verbose, mut level, name }: SetFlagsArgs) {
+    level += 1;
+}
+
+fn main() {
+    let verbose = true;
+    set_flags(SetFlagsArgs { verbose, level: 3, name: String::new() });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_pub_fn_in_module() {
+        check_assist(
+            convert_params_to_struct,
+            r#"
+mod m {
+    pub fn area(width: u32, height: u32, unit: &str) -> u32 { width * height }
+}
+
+fn main() {
+    m::area$0(1, 2, "cm");
+}
+"#,
+            r#"
+mod m {
+    pub struct AreaArgs<'a> {
+        pub width: u32,
+        pub height: u32,
+        pub unit: &'a str,
+    }
+
+    pub fn area(AreaArgs { width, height, unit }: AreaArgs) -> u32 { width * height }
+}
+
+fn main() {
+    m::area(m::AreaArgs { width: 1, height: 2, unit: "cm" });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_unsupported_params() {
+        check_assist_not_applicable(
+            convert_params_to_struct,
+            r#"
+fn f(a: u8, (b, c): (u8, u8)) {}
+fn main() { f$0(1, (2, 3)); }
+"#,
+        );
+        check_assist_not_applicable(
+            convert_params_to_struct,
+            r#"
+fn f(a: u8, b: u8) {}
+fn main() { f$0(1); }
+"#,
+        );
+        check_assist_not_applicable(
+            convert_params_to_struct,
+            r#"
+struct FArgs;
+fn f(a: u8, b: u8) {}
+fn main() { f$0(1, 2); }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_generic_fns() {
+        cov_mark::check!(convert_params_to_struct_not_applicable_to_generic_fns);
+        check_assist_not_applicable(
+            convert_params_to_struct,
+            r#"
+fn f<T>(a: T, b: u8) {}
+fn main() { f$0(1, 2); }
+"#,
+        );
+    }
+}
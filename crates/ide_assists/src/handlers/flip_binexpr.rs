@@ -105,6 +105,25 @@ mod tests {
         check_assist(flip_binexpr, "fn f() { let res = 1 <=$0 2; }", "fn f() { let res = 2 >= 1; }")
     }
 
+    #[test]
+    fn flip_binexpr_works_for_lt() {
+        check_assist(flip_binexpr, "fn f() { let res = 1 <$0 2; }", "fn f() { let res = 2 > 1; }")
+    }
+
+    #[test]
+    fn flip_binexpr_works_for_gteq() {
+        check_assist(flip_binexpr, "fn f() { let res = 1 >=$0 2; }", "fn f() { let res = 2 <= 1; }")
+    }
+
+    #[test]
+    fn flip_binexpr_works_for_method_calls() {
+        check_assist(
+            flip_binexpr,
+            "fn f(a: &[u8], b: &str) { let res = a.len() <$0 b.trim().len(); }",
+            "fn f(a: &[u8], b: &str) { let res = b.trim().len() > a.len(); }",
+        )
+    }
+
     #[test]
     fn flip_binexpr_works_for_complex_expr() {
         check_assist(
@@ -0,0 +1,279 @@
+use hir::HirDisplay;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode},
+    SyntaxKind::{ITEM_LIST, LITERAL_PAT, MODULE, SOURCE_FILE},
+    SyntaxNode,
+};
+
+use crate::{
+    assist_context::AssistBuilder, AssistContext, AssistId, AssistKind, Assists, GroupLabel,
+};
+
+// Assist: introduce_named_constant
+//
+// Replaces a literal with a named constant, declared in the enclosing function or, outside of
+// functions, before the enclosing item.
+//
+// ```
+// fn area(r: f64) -> f64 {
+//     3.14$0 * r * r
+// }
+// ```
+// ->
+// ```
+// fn area(r: f64) -> f64 {
+//     const $0CONSTANT: f64 = 3.14;
+//     CONSTANT * r * r
+// }
+// ```
+pub(crate) fn introduce_named_constant(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let literal = ctx.find_node_at_offset::<ast::Literal>()?;
+    if matches!(literal.kind(), ast::LiteralKind::Bool(_)) || !is_replaceable(&literal) {
+        return None;
+    }
+    if literal
+        .syntax()
+        .parent()
+        .map_or(false, |it| ast::Const::can_cast(it.kind()) || ast::Static::can_cast(it.kind()))
+    {
+        cov_mark::hit!(introduce_named_constant_already_const);
+        return None;
+    }
+
+    let ty = ctx.sema.type_of_expr(&ast::Expr::Literal(literal.clone()))?.original;
+    if ty.is_unknown() {
+        return None;
+    }
+    let module = ctx.sema.scope(literal.syntax()).module()?;
+    let ty_text = ty.display_source_code(ctx.db(), module.into()).ok()?;
+
+    let (scope, insert_pos, suffix) = match enclosing_fn_body(&literal) {
+        Some(body) => {
+            let first = body
+                .statements()
+                .map(|it| it.syntax().clone())
+                .chain(body.tail_expr().map(|it| it.syntax().clone()))
+                .next()?;
+            let suffix = format!("\n{}", IndentLevel::from_node(&first));
+            (body.syntax().clone(), first.text_range().start(), suffix)
+        }
+        None => {
+            let item = literal.syntax().ancestors().find(|it| {
+                ast::Item::can_cast(it.kind()) && it.parent().map_or(false, is_module_item_list)
+            })?;
+            let suffix = format!("\n\n{}", IndentLevel::from_node(&item));
+            (item.clone(), item.text_range().start(), suffix)
+        }
+    };
+
+    let token = literal.token();
+    let occurrences: Vec<_> = scope
+        .descendants()
+        .filter_map(ast::Literal::cast)
+        .filter(|it| {
+            let other = it.token();
+            other.kind() == token.kind() && other.text() == token.text() && is_replaceable(it)
+        })
+        .filter(|it| {
+            ctx.sema.type_of_expr(&ast::Expr::Literal(it.clone())).map(|it| it.original)
+                == Some(ty.clone())
+        })
+        .map(|it| it.syntax().text_range())
+        .collect();
+
+    let group = GroupLabel("Introduce named constant".into());
+    let target = literal.syntax().text_range();
+    let decl = format!("const CONSTANT: {} = {};{}", ty_text, literal, suffix);
+    let insert_const = |builder: &mut AssistBuilder| match ctx.config.snippet_cap {
+        Some(cap) => {
+            builder.insert_snippet(cap, insert_pos, decl.replacen("CONSTANT", "$0CONSTANT", 1))
+        }
+        None => builder.insert(insert_pos, decl.clone()),
+    };
+
+    acc.add_group(
+        &group,
+        AssistId("introduce_named_constant", AssistKind::RefactorExtract),
+        "Introduce named constant",
+        target,
+        |builder| {
+            builder.replace(target, "CONSTANT");
+            insert_const(builder);
+        },
+    );
+    if occurrences.len() > 1 {
+        acc.add_group(
+            &group,
+            AssistId("introduce_named_constant", AssistKind::RefactorExtract),
+            format!("Introduce named constant for all {} occurrences", occurrences.len()),
+            target,
+            |builder| {
+                for range in occurrences {
+                    builder.replace(range, "CONSTANT");
+                }
+                insert_const(builder);
+            },
+        );
+    }
+    Some(())
+}
+
+/// Whether `literal` is an expression outside of attributes, so that a constant can stand in.
+fn is_replaceable(literal: &ast::Literal) -> bool {
+    let in_pat = literal.syntax().parent().map_or(true, |it| it.kind() == LITERAL_PAT);
+    !in_pat && !literal.syntax().ancestors().any(|it| ast::Attr::can_cast(it.kind()))
+}
+
+/// The body of the innermost function `literal` is in, if it is in a function body at all.
+fn enclosing_fn_body(literal: &ast::Literal) -> Option<ast::BlockExpr> {
+    let body = literal.syntax().ancestors().find_map(ast::Fn::cast)?.body()?;
+    let range = literal.syntax().text_range();
+    if body.syntax().text_range().contains_range(range) {
+        Some(body)
+    } else {
+        None
+    }
+}
+
+fn is_module_item_list(node: SyntaxNode) -> bool {
+    node.kind() == SOURCE_FILE
+        || (node.kind() == ITEM_LIST && node.parent().map_or(false, |it| it.kind() == MODULE))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn introduce_for_numeric_literal() {
+        check_assist(
+            introduce_named_constant,
+            r#"
+fn area(r: f64) -> f64 {
+    let unit = 1.0;
+    3.14$0 * r * r * unit
+}
+"#,
+            r#"
+fn area(r: f64) -> f64 {
+    const $0CONSTANT: f64 = 3.14;
+    let unit = 1.0;
+    CONSTANT * r * r * unit
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_for_string_literal() {
+        check_assist(
+            introduce_named_constant,
+            r#"
+fn greet() {
+    let greeting = "hello$0";
+}
+"#,
+            r#"
+fn greet() {
+    const $0CONSTANT: &str = "hello";
+    let greeting = CONSTANT;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_for_integer_literal_uses_inferred_type() {
+        check_assist(
+            introduce_named_constant,
+            r#"
+fn f(x: u8) -> u8 {
+    x + 42$0
+}
+"#,
+            r#"
+fn f(x: u8) -> u8 {
+    const $0CONSTANT: u8 = 42;
+    x + CONSTANT
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_outside_of_fn() {
+        check_assist(
+            introduce_named_constant,
+            r#"
+mod m {
+    pub const TWO_PI: f64 = 3.14$0 * 2.0;
+}
+"#,
+            r#"
+mod m {
+    const $0CONSTANT: f64 = 3.14;
+
+    pub const TWO_PI: f64 = CONSTANT * 2.0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn introduce_for_all_occurrences() {
+        check_assist_by_label(
+            introduce_named_constant,
+            r#"
+fn f(a: u32, b: u32, c: u64) -> (u32, u32, u64) {
+    (a * 60$0, b * 60, c * 60)
+}
+"#,
+            r#"
+fn f(a: u32, b: u32, c: u64) -> (u32, u32, u64) {
+    const $0CONSTANT: u32 = 60;
+    (a * CONSTANT, b * CONSTANT, c * 60)
+}
+"#,
+            "Introduce named constant for all 2 occurrences",
+        );
+    }
+
+    #[test]
+    fn introduce_for_single_occurrence_only() {
+        check_assist_by_label(
+            introduce_named_constant,
+            r#"
+fn f(a: u32, b: u32) -> (u32, u32) {
+    (a * 60$0, b * 60)
+}
+"#,
+            r#"
+fn f(a: u32, b: u32) -> (u32, u32) {
+    const $0CONSTANT: u32 = 60;
+    (a * CONSTANT, b * 60)
+}
+"#,
+            "Introduce named constant",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_const_initializer() {
+        cov_mark::check!(introduce_named_constant_already_const);
+        check_assist_not_applicable(introduce_named_constant, "const C: u32 = 92$0;");
+    }
+
+    #[test]
+    fn not_applicable_to_bool_or_attribute() {
+        check_assist_not_applicable(introduce_named_constant, "fn f() -> bool { true$0 }");
+        check_assist_not_applicable(
+            introduce_named_constant,
+            r#"
+#[doc = "docs$0"]
+fn f() {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use itertools::Itertools;
+use syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner},
+    SmolStr, TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: merge_into_tuple_let
+//
+// Merges the selected consecutive `let` statements into a single `let` with a tuple pattern.
+//
+// ```
+// fn main() {
+//     $0let mut a = 1;
+//     let b = 2;$0
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let (mut a, b) = (1, 2);
+// }
+// ```
+pub(crate) fn merge_into_tuple_let(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let range = ctx.frange.range;
+    if range.is_empty() {
+        return None;
+    }
+    let block = ctx.covering_element().ancestors().find_map(ast::BlockExpr::cast)?;
+
+    let stmts = block
+        .statements()
+        .filter(|stmt| {
+            stmt.syntax().text_range().intersect(range).map_or(false, |it| !it.is_empty())
+        })
+        .collect::<Vec<_>>();
+    if stmts.len() < 2 {
+        return None;
+    }
+    let lets = stmts
+        .iter()
+        .map(|stmt| match stmt {
+            ast::Stmt::LetStmt(it) => simple_let(it),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut bound_names: Vec<SmolStr> = Vec::new();
+    for (binding, _, initializer) in &lets {
+        let uses_bound_name = initializer
+            .syntax()
+            .descendants()
+            .filter_map(ast::NameRef::cast)
+            .any(|name_ref| bound_names.iter().any(|name| *name == name_ref.text().as_str()));
+        if uses_bound_name {
+            cov_mark::hit!(merge_into_tuple_let_not_applicable_if_lets_depend_on_each_other);
+            return None;
+        }
+        let name = SmolStr::from(binding.name()?.text().as_str());
+        if bound_names.contains(&name) {
+            return None;
+        }
+        bound_names.push(name);
+    }
+
+    let target = TextRange::new(
+        stmts.first()?.syntax().text_range().start(),
+        stmts.last()?.syntax().text_range().end(),
+    );
+    acc.add(
+        AssistId("merge_into_tuple_let", AssistKind::RefactorRewrite),
+        "Merge into a tuple `let`",
+        target,
+        |builder| {
+            let pats = lets.iter().map(|(binding, _, _)| binding).join(", ");
+            let exprs = lets.iter().map(|(_, _, initializer)| initializer).join(", ");
+            let merged = if lets.iter().any(|(_, ty, _)| ty.is_some()) {
+                let tys = lets
+                    .iter()
+                    .map(|(_, ty, _)| {
+                        ty.as_ref().map_or_else(|| "_".to_string(), |it| it.to_string())
+                    })
+                    .join(", ");
+                format!("let ({}): ({}) = ({});", pats, tys, exprs)
+            } else {
+                format!("let ({}) = ({});", pats, exprs)
+            };
+            builder.replace(target, merged);
+        },
+    )
+}
+
+/// Destructures `let name: Ty = expr;`, where `name` is a plain binding.
+fn simple_let(let_stmt: &ast::LetStmt) -> Option<(ast::IdentPat, Option<ast::Type>, ast::Expr)> {
+    if let_stmt.attrs().next().is_some() {
+        return None;
+    }
+    let binding = match let_stmt.pat()? {
+        ast::Pat::IdentPat(it) if it.pat().is_none() => it,
+        _ => return None,
+    };
+    Some((binding, let_stmt.ty(), let_stmt.initializer()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn merge_two_lets() {
+        check_assist(
+            merge_into_tuple_let,
+            r#"
+fn main() {
+    let x = 0;
+    $0let a = 1;
+    let mut b = "b";$0
+    let c = 3;
+}
+"#,
+            r#"
+fn main() {
+    let x = 0;
+    let (a, mut b) = (1, "b");
+    let c = 3;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn merge_partially_selected_lets_with_types() {
+        check_assist(
+            merge_into_tuple_let,
+            r#"
+fn main() {
+    let a: u8 $0= 1;
+    let b = 2;
+    let c: &str = $0"c";
+}
+"#,
+            r#"
+fn main() {
+    let (a, b, c): (u8, _, &str) = (1, 2, "c");
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_single_let_or_other_statements() {
+        check_assist_not_applicable(
+            merge_into_tuple_let,
+            r#"
+fn main() {
+    $0let a = 1;$0
+    let b = 2;
+}
+"#,
+        );
+        check_assist_not_applicable(
+            merge_into_tuple_let,
+            r#"
+fn main() {
+    $0let a = 1;
+    foo();
+    let b = 2;$0
+}
+"#,
+        );
+        check_assist_not_applicable(
+            merge_into_tuple_let,
+            r#"
+fn main() {
+    $0let a = 1;
+    let a = 2;$0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_lets_depend_on_each_other() {
+        cov_mark::check!(merge_into_tuple_let_not_applicable_if_lets_depend_on_each_other);
+        check_assist_not_applicable(
+            merge_into_tuple_let,
+            r#"
+fn main() {
+    $0let a = 1;
+    let b = a + 1;$0
+}
+"#,
+        );
+    }
+}
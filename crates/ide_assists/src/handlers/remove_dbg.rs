@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use syntax::{
-    ast::{self, AstNode, AstToken},
-    match_ast, NodeOrToken, SyntaxElement, TextSize, T,
+    ast::{self, AstNode, AstToken, BinaryOp, LogicOp},
+    match_ast, NodeOrToken, SyntaxElement, TextRange, TextSize, T,
 };
 
 use crate::{AssistContext, AssistId, AssistKind, Assists};
@@ -23,6 +23,59 @@ use crate::{AssistContext, AssistId, AssistKind, Assists};
 // ```
 pub(crate) fn remove_dbg(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let (range, text) = compute_dbg_replacement(&macro_call)?;
+
+    acc.add(AssistId("remove_dbg", AssistKind::Refactor), "Remove dbg!()", range, |builder| {
+        builder.replace(range, text);
+    })
+}
+
+// Assist: remove_all_dbg
+//
+// Removes all `dbg!()` macro calls in the body of the function.
+//
+// ```
+// fn main() {
+//     $0let x = dbg!(1 + 1) * 2;
+//     dbg!();
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = (1 + 1) * 2;
+// }
+// ```
+pub(crate) fn remove_all_dbg(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let func = ctx.find_node_at_offset::<ast::Fn>()?;
+    let body = func.body()?;
+
+    let replacements: Vec<_> = body
+        .syntax()
+        .descendants()
+        .filter_map(ast::MacroCall::cast)
+        .filter_map(|macro_call| compute_dbg_replacement(&macro_call))
+        .collect();
+    if replacements.is_empty() {
+        return None;
+    }
+
+    let target = func.syntax().text_range();
+    acc.add(
+        AssistId("remove_all_dbg", AssistKind::Refactor),
+        "Remove all dbg! in function",
+        target,
+        |builder| {
+            for (range, text) in replacements {
+                builder.replace(range, text);
+            }
+        },
+    )
+}
+
+/// Returns the range to replace and the replacement text for a `dbg!` call,
+/// or `None` if `macro_call` is not a `dbg!` invocation.
+fn compute_dbg_replacement(macro_call: &ast::MacroCall) -> Option<(TextRange, String)> {
     let tt = macro_call.token_tree()?;
     let r_delim = NodeOrToken::Token(tt.right_delimiter_token()?);
     if macro_call.path()?.segment()?.name_ref()?.text() != "dbg"
@@ -37,14 +90,15 @@ pub(crate) fn remove_dbg(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
         .into_iter()
         .filter_map(|(is_sep, group)| (!is_sep).then(|| group))
         .map(|mut tokens| ast::Expr::parse(&tokens.join("")))
+        .map(|expr| expr.and_then(remove_nested_dbgs))
         .collect::<Result<Vec<ast::Expr>, _>>()
         .ok()?;
 
-    let parent = macro_call.syntax().parent()?;
-    let (range, text) = match &*input_expressions {
+    let parent = macro_call.syntax().parent();
+    let res = match &*input_expressions {
         // dbg!()
-        [] => {
-            match_ast! {
+        [] => match parent {
+            Some(parent) => match_ast! {
                 match parent {
                     ast::BlockExpr(__) => {
                         let range = macro_call.syntax().text_range();
@@ -64,40 +118,13 @@ pub(crate) fn remove_dbg(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
                     },
                     _ => (macro_call.syntax().text_range(), "()".to_owned())
                 }
-            }
-        }
+            },
+            None => (macro_call.syntax().text_range(), "()".to_owned()),
+        },
         // dbg!(expr0)
         [expr] => {
-            let wrap = match ast::Expr::cast(parent) {
-                Some(parent) => match (expr, parent) {
-                    (ast::Expr::CastExpr(_), ast::Expr::CastExpr(_)) => false,
-                    (
-                        ast::Expr::BoxExpr(_) | ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_),
-                        ast::Expr::AwaitExpr(_)
-                        | ast::Expr::CallExpr(_)
-                        | ast::Expr::CastExpr(_)
-                        | ast::Expr::FieldExpr(_)
-                        | ast::Expr::IndexExpr(_)
-                        | ast::Expr::MethodCallExpr(_)
-                        | ast::Expr::RangeExpr(_)
-                        | ast::Expr::TryExpr(_),
-                    ) => true,
-                    (
-                        ast::Expr::BinExpr(_) | ast::Expr::CastExpr(_) | ast::Expr::RangeExpr(_),
-                        ast::Expr::AwaitExpr(_)
-                        | ast::Expr::BinExpr(_)
-                        | ast::Expr::CallExpr(_)
-                        | ast::Expr::CastExpr(_)
-                        | ast::Expr::FieldExpr(_)
-                        | ast::Expr::IndexExpr(_)
-                        | ast::Expr::MethodCallExpr(_)
-                        | ast::Expr::PrefixExpr(_)
-                        | ast::Expr::RangeExpr(_)
-                        | ast::Expr::RefExpr(_)
-                        | ast::Expr::TryExpr(_),
-                    ) => true,
-                    _ => false,
-                },
+            let wrap = match parent.and_then(ast::Expr::cast) {
+                Some(parent) => needs_parens_in(expr, macro_call.syntax(), &parent),
                 None => false,
             };
             (
@@ -108,10 +135,100 @@ pub(crate) fn remove_dbg(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
         // dbg!(expr0, expr1, ...)
         exprs => (macro_call.syntax().text_range(), format!("({})", exprs.iter().format(", "))),
     };
+    Some(res)
+}
 
-    acc.add(AssistId("remove_dbg", AssistKind::Refactor), "Remove dbg!()", range, |builder| {
-        builder.replace(range, text);
-    })
+/// Strips the `dbg!` calls nested inside of `expr`, innermost first.
+fn remove_nested_dbgs(expr: ast::Expr) -> Result<ast::Expr, ()> {
+    let mut replacements: Vec<_> = expr
+        .syntax()
+        .descendants()
+        .filter_map(ast::MacroCall::cast)
+        .filter_map(|macro_call| compute_dbg_replacement(&macro_call))
+        .collect();
+    if replacements.is_empty() {
+        return Ok(expr);
+    }
+    replacements.sort_by_key(|(range, _)| range.start());
+
+    let mut text = expr.syntax().text().to_string();
+    for (range, replacement) in replacements.into_iter().rev() {
+        text.replace_range(std::ops::Range::<usize>::from(range), &replacement);
+    }
+    ast::Expr::parse(&text)
+}
+
+/// Whether `expr`, substituted for `child` inside of `parent`, has to be
+/// wrapped in parentheses to keep the meaning of the parent expression.
+fn needs_parens_in(expr: &ast::Expr, child: &syntax::SyntaxNode, parent: &ast::Expr) -> bool {
+    let expr_prec = precedence(expr);
+    let parent_prec = precedence(parent);
+    let is_first_child = parent.syntax().children().next().map_or(false, |first| &first == child);
+    match parent {
+        // Only the receiver/callee/base is affected by precedence, everything
+        // else is delimited.
+        ast::Expr::AwaitExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::TryExpr(_) => is_first_child && expr_prec < parent_prec,
+        ast::Expr::BoxExpr(_) | ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) => {
+            expr_prec < parent_prec
+        }
+        // `as` is left associative.
+        ast::Expr::CastExpr(_) => expr_prec < parent_prec,
+        ast::Expr::BinExpr(bin_expr) => {
+            if expr_prec != parent_prec {
+                return expr_prec < parent_prec;
+            }
+            match bin_expr.op_kind() {
+                // Comparisons are not associative at all.
+                Some(BinaryOp::CmpOp(_)) => true,
+                // Assignments are right associative.
+                Some(BinaryOp::Assignment { .. }) => is_first_child,
+                _ => !is_first_child,
+            }
+        }
+        // Ranges are not associative either.
+        ast::Expr::RangeExpr(_) => expr_prec <= parent_prec,
+        _ => false,
+    }
+}
+
+/// Binding power of the expression, higher binds tighter.
+fn precedence(expr: &ast::Expr) -> u8 {
+    match expr {
+        ast::Expr::ClosureExpr(_)
+        | ast::Expr::ReturnExpr(_)
+        | ast::Expr::BreakExpr(_)
+        | ast::Expr::ContinueExpr(_)
+        | ast::Expr::YieldExpr(_) => 1,
+        ast::Expr::RangeExpr(_) => 3,
+        ast::Expr::BinExpr(bin_expr) => match bin_expr.op_kind() {
+            Some(BinaryOp::Assignment { .. }) | None => 2,
+            Some(BinaryOp::LogicOp(LogicOp::Or)) => 4,
+            Some(BinaryOp::LogicOp(LogicOp::And)) => 5,
+            Some(BinaryOp::CmpOp(_)) => 6,
+            Some(BinaryOp::ArithOp(op)) => match op {
+                ast::ArithOp::BitOr => 7,
+                ast::ArithOp::BitXor => 8,
+                ast::ArithOp::BitAnd => 9,
+                ast::ArithOp::Shl | ast::ArithOp::Shr => 10,
+                ast::ArithOp::Add | ast::ArithOp::Sub => 11,
+                ast::ArithOp::Mul | ast::ArithOp::Div | ast::ArithOp::Rem => 12,
+            },
+        },
+        ast::Expr::CastExpr(_) => 13,
+        ast::Expr::BoxExpr(_) | ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) => 14,
+        ast::Expr::AwaitExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::TryExpr(_) => 15,
+        _ => 16,
+    }
 }
 
 fn whitespace_start(it: Option<SyntaxElement>) -> Option<TextSize> {
@@ -238,4 +355,64 @@ fn foo() {
         check(r#"$0dbg!(0, 1)"#, r#"(0, 1)"#);
         check(r#"$0dbg!(0, (1, 2))"#, r#"(0, (1, 2))"#);
     }
+
+    #[test]
+    fn test_remove_dbg_precedence() {
+        check(r#"let res = $0dbg!(1 + 1) * 2;"#, r#"let res = (1 + 1) * 2;"#);
+        check(r#"let res = $0dbg!(1 * 2) + 1;"#, r#"let res = 1 * 2 + 1;"#);
+        check(r#"let res = 1 - $0dbg!(2 - 3);"#, r#"let res = 1 - (2 - 3);"#);
+        check(r#"let res = $0dbg!(1 - 2) - 3;"#, r#"let res = 1 - 2 - 3;"#);
+        check(r#"let res = $0dbg!(a == b) == c;"#, r#"let res = (a == b) == c;"#);
+        check(r#"let res = -$0dbg!(a + b);"#, r#"let res = -(a + b);"#);
+        check(r#"let res = foo($0dbg!(a + b));"#, r#"let res = foo(a + b);"#);
+        check(r#"let res = array[$0dbg!(a + b)];"#, r#"let res = array[a + b];"#);
+    }
+
+    #[test]
+    fn test_remove_nested_dbg() {
+        check(r#"let res = $0dbg!(dbg!(1));"#, r#"let res = 1;"#);
+        check(r#"let res = $0dbg!(dbg!(1 + 1)) * 2;"#, r#"let res = (1 + 1) * 2;"#);
+        check(r#"let res = $0dbg!(dbg!(dbg!(a), b));"#, r#"let res = (a, b);"#);
+        check(r#"let res = $0dbg!(foo(dbg!(1 + 1) * 2));"#, r#"let res = foo((1 + 1) * 2);"#);
+    }
+
+    #[test]
+    fn test_remove_all_dbg() {
+        check_assist(
+            remove_all_dbg,
+            r#"
+fn foo() {
+    $0let x = dbg!(1 + 1) * 2;
+    dbg!();
+    let y = dbg!(dbg!(x), 3);
+    if dbg!(x == 4) {
+        dbg!(y);
+    }
+}
+"#,
+            r#"
+fn foo() {
+    let x = (1 + 1) * 2;
+    let y = (x, 3);
+    if x == 4 {
+        y;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_remove_all_dbg_not_applicable() {
+        check_assist_not_applicable(
+            remove_all_dbg,
+            r#"
+fn foo() {
+    $0let x = vec![1, 2];
+    println!("{:?}", x);
+}
+"#,
+        );
+        check_assist_not_applicable(remove_all_dbg, r#"struct Foo$0;"#);
+    }
 }
@@ -0,0 +1,186 @@
+use hir::{
+    known, AsAssocItem, AssocItemContainer, GenericDef, GenericParam, ModuleDef, Name,
+    PathResolution, Semantics,
+};
+use ide_db::RootDatabase;
+use syntax::{
+    ast::{self, AstNode, LetStmt},
+    TextRange,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: remove_explicit_type
+//
+// Removes the type of a let binding if inference already produces exactly that type.
+//
+// ```
+// fn main() {
+//     let x$0: bool = true;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = true;
+// }
+// ```
+pub(crate) fn remove_explicit_type(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let let_stmt = ctx.find_node_at_offset::<LetStmt>()?;
+    let eq_range = let_stmt.eq_token()?.text_range();
+    if ctx.offset() >= eq_range.start() {
+        return None;
+    }
+    let colon = let_stmt.colon_token()?;
+    let ascribed_ty = let_stmt.ty()?;
+    let initializer = let_stmt.initializer()?;
+
+    let ty = ctx.sema.resolve_type(&ascribed_ty)?;
+    let inferred = ctx.sema.type_of_expr(&initializer)?;
+    // A coerced initializer has a different type without the annotation.
+    if ty.contains_unknown() || inferred.adjusted.is_some() || inferred.original != ty {
+        cov_mark::hit!(remove_explicit_type_not_applicable_if_ty_differs);
+        return None;
+    }
+    // The annotation may be what determines the initializer's type in the first place.
+    if !ty_is_independent_of_annotation(&ctx.sema, &initializer) {
+        cov_mark::hit!(remove_explicit_type_not_applicable_if_ty_is_inferred_from_annotation);
+        return None;
+    }
+
+    let range = TextRange::new(colon.text_range().start(), ascribed_ty.syntax().text_range().end());
+    acc.add(
+        AssistId("remove_explicit_type", AssistKind::RefactorRewrite),
+        "Remove explicit type",
+        ascribed_ty.syntax().text_range(),
+        |builder| builder.delete(range),
+    )
+}
+
+/// Conservatively checks whether inference arrives at the type of `expr` without
+/// knowing the expected type, i.e. there are no generic items or unsuffixed numeric
+/// literals whose type could be picked based on it.
+fn ty_is_independent_of_annotation(sema: &Semantics<RootDatabase>, expr: &ast::Expr) -> bool {
+    let db = sema.db;
+    let has_default_type = |expr: &ast::Expr, default: Name| {
+        sema.type_of_expr(expr)
+            .and_then(|it| it.original.as_builtin())
+            .map_or(false, |it| it.name() == default)
+    };
+    expr.syntax().descendants().filter_map(ast::Expr::cast).all(|expr| match &expr {
+        ast::Expr::Literal(lit) => match lit.kind() {
+            ast::LiteralKind::IntNumber(num) if num.suffix().is_none() => {
+                has_default_type(&expr, known::i32)
+            }
+            ast::LiteralKind::FloatNumber(num) if num.suffix().is_none() => {
+                has_default_type(&expr, known::f64)
+            }
+            _ => true,
+        },
+        ast::Expr::PathExpr(path) => match path.path().and_then(|it| sema.resolve_path(&it)) {
+            Some(PathResolution::Local(_)) => true,
+            Some(PathResolution::Def(def)) => !is_generic(db, def),
+            _ => false,
+        },
+        ast::Expr::MethodCallExpr(call) => {
+            sema.resolve_method_call(call).map_or(false, |it| !is_generic(db, it.into()))
+        }
+        ast::Expr::ClosureExpr(_) | ast::Expr::MacroCall(_) => false,
+        _ => true,
+    })
+}
+
+fn is_generic(db: &RootDatabase, def: ModuleDef) -> bool {
+    let has_type_params = |def: GenericDef| {
+        def.params(db).into_iter().any(|it| matches!(it, GenericParam::TypeParam(_)))
+    };
+    match def {
+        ModuleDef::Function(it) => {
+            let container_is_generic = match it.as_assoc_item(db).map(|it| it.container(db)) {
+                Some(AssocItemContainer::Trait(_)) => true,
+                Some(AssocItemContainer::Impl(it)) => has_type_params(it.into()),
+                None => false,
+            };
+            container_is_generic || has_type_params(it.into())
+        }
+        ModuleDef::Adt(it) => has_type_params(it.into()),
+        ModuleDef::Variant(it) => has_type_params(it.parent_enum(db).into()),
+        ModuleDef::Const(_) | ModuleDef::Static(_) => false,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn remove_explicit_type_simple() {
+        check_assist(
+            remove_explicit_type,
+            r#"fn f() { let a$0: i32 = 1; }"#,
+            r#"fn f() { let a = 1; }"#,
+        );
+    }
+
+    #[test]
+    fn remove_explicit_type_struct() {
+        check_assist(
+            remove_explicit_type,
+            r#"
+struct Foo { x: u8 }
+fn f(x: u8) {
+    let foo: Foo$0 = Foo { x };
+}
+"#,
+            r#"
+struct Foo { x: u8 }
+fn f(x: u8) {
+    let foo = Foo { x };
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_explicit_type_not_applicable_literal_type_from_annotation() {
+        cov_mark::check!(remove_explicit_type_not_applicable_if_ty_is_inferred_from_annotation);
+        check_assist_not_applicable(remove_explicit_type, r#"fn f() { let a$0: u64 = 1; }"#);
+    }
+
+    #[test]
+    fn remove_explicit_type_not_applicable_generic_call() {
+        cov_mark::check!(remove_explicit_type_not_applicable_if_ty_is_inferred_from_annotation);
+        check_assist_not_applicable(
+            remove_explicit_type,
+            r#"
+struct Wrapper<T>(T);
+fn make<T>() -> Wrapper<T> { loop {} }
+fn f() {
+    let a$0: Wrapper<u8> = make();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_explicit_type_not_applicable_coercion() {
+        cov_mark::check!(remove_explicit_type_not_applicable_if_ty_differs);
+        check_assist_not_applicable(
+            remove_explicit_type,
+            r#"
+//- minicore: coerce_unsized
+fn f() {
+    let a$0: &[i32] = &[1, 2];
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_explicit_type_not_applicable_after_eq() {
+        check_assist_not_applicable(remove_explicit_type, r#"fn f() { let a: i32 = $01; }"#);
+    }
+}
@@ -73,7 +73,11 @@ pub(crate) fn replace_derive_with_manual_impl(
     })
     .flat_map(|trait_| {
         current_module
-            .find_use_path(ctx.sema.db, hir::ModuleDef::Trait(trait_))
+            .find_use_path(
+                ctx.sema.db,
+                hir::ModuleDef::Trait(trait_),
+                hir::FindPathConfig::default(),
+            )
             .as_ref()
             .map(mod_path_to_ast)
             .zip(Some(trait_))
@@ -773,6 +773,79 @@ fn foo() {
         Bar(bar) => println!("bar {}", bar),
     }
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_with_if_let_none_arm_first() {
+        check_assist(
+            replace_match_with_if_let,
+            r#"
+//- minicore: option
+fn foo(x: Option<i32>) {
+    $0match x {
+        None => println!("none"),
+        Some(x) => println!("{}", x),
+    }
+}
+"#,
+            r#"
+fn foo(x: Option<i32>) {
+    if let Some(x) = x {
+        println!("{}", x)
+    } else {
+        println!("none")
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_with_if_let_two_variant_enum() {
+        check_assist(
+            replace_match_with_if_let,
+            r#"
+enum Shape { Circle(u32), Square }
+
+fn area(shape: Shape) -> u32 {
+    $0match shape {
+        Shape::Square => 1,
+        Shape::Circle(r) => {
+            let d = 2 * r;
+            d * d
+        }
+    }
+}
+"#,
+            r#"
+enum Shape { Circle(u32), Square }
+
+fn area(shape: Shape) -> u32 {
+    if let Shape::Circle(r) = shape {
+        let d = 2 * r;
+        d * d
+    } else {
+        1
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_with_if_let_rejects_guards() {
+        check_assist_not_applicable(
+            replace_match_with_if_let,
+            r#"
+//- minicore: option
+fn foo(x: Option<i32>) {
+    $0match x {
+        Some(x) if x > 0 => println!("{}", x),
+        _ => println!("none"),
+    }
+}
 "#,
         );
     }
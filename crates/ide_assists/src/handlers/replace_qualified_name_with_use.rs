@@ -65,7 +65,12 @@ pub(crate) fn replace_qualified_name_with_use(
     let path_to_qualifier = starts_with_name_ref
         .then(|| {
             ctx.sema.scope(path.syntax()).module().and_then(|m| {
-                m.find_use_path_prefixed(ctx.sema.db, module, ctx.config.insert_use.prefix_kind)
+                m.find_use_path_prefixed(
+                    ctx.sema.db,
+                    module,
+                    ctx.config.insert_use.prefix_kind,
+                    hir::FindPathConfig::default(),
+                )
             })
         })
         .flatten();
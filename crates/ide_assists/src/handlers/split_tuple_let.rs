@@ -0,0 +1,192 @@
+use itertools::Itertools;
+use syntax::{
+    ast::{self, edit::IndentLevel, AstNode, AttrsOwner, NameOwner},
+    SmolStr,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: split_tuple_let
+//
+// Splits a `let` with a tuple pattern and a tuple initializer into one `let` per element.
+//
+// ```
+// fn main() {
+//     let (mut a, b)$0 = (1, 2);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let mut a = 1;
+//     let b = 2;
+// }
+// ```
+pub(crate) fn split_tuple_let(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    if let_stmt.attrs().next().is_some() {
+        return None;
+    }
+    let pat = match let_stmt.pat()? {
+        ast::Pat::TuplePat(it) => it,
+        _ => return None,
+    };
+    let initializer = match let_stmt.initializer()? {
+        ast::Expr::TupleExpr(it) => it,
+        _ => return None,
+    };
+
+    let bindings = pat
+        .fields()
+        .map(|pat| match pat {
+            ast::Pat::IdentPat(it) if it.pat().is_none() => Some(it),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let exprs = initializer.fields().collect::<Vec<_>>();
+    if bindings.is_empty() || bindings.len() != exprs.len() {
+        return None;
+    }
+    let tys = match let_stmt.ty() {
+        Some(ast::Type::TupleType(ty)) => {
+            let fields = ty.fields().map(Some).collect::<Vec<_>>();
+            if fields.len() != bindings.len() {
+                return None;
+            }
+            fields
+        }
+        Some(_) => return None,
+        None => vec![None; bindings.len()],
+    };
+
+    // `let (a, b) = (b, a);` swaps the values, splitting it would not.
+    let mut bound_names: Vec<SmolStr> = Vec::new();
+    for (binding, expr) in bindings.iter().zip(&exprs) {
+        let uses_bound_name = expr
+            .syntax()
+            .descendants()
+            .filter_map(ast::NameRef::cast)
+            .any(|name_ref| bound_names.iter().any(|name| *name == name_ref.text().as_str()));
+        if uses_bound_name {
+            cov_mark::hit!(split_tuple_let_not_applicable_if_elements_depend_on_bindings);
+            return None;
+        }
+        bound_names.push(binding.name()?.text().into());
+    }
+
+    let target = let_stmt.syntax().text_range();
+    acc.add(
+        AssistId("split_tuple_let", AssistKind::RefactorRewrite),
+        "Split into multiple `let`s",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(let_stmt.syntax());
+            let lets = bindings
+                .iter()
+                .zip(tys)
+                .zip(&exprs)
+                .map(|((binding, ty), expr)| match ty {
+                    Some(ty) => format!("let {}: {} = {};", binding, ty, expr),
+                    None => format!("let {} = {};", binding, expr),
+                })
+                .join(&format!("\n{}", indent));
+            builder.replace(target, lets);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn split_pair() {
+        check_assist(
+            split_tuple_let,
+            r#"
+fn main() {
+    let (a, b)$0 = (1, 2);
+}
+"#,
+            r#"
+fn main() {
+    let a = 1;
+    let b = 2;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_preserves_mutability_and_types() {
+        check_assist(
+            split_tuple_let,
+            r#"
+fn main() {
+    if true {
+        let (mut a, b, ref c)$0: (u8, i64, &str) = (1, 2 + 3, "c");
+    }
+}
+"#,
+            r#"
+fn main() {
+    if true {
+        let mut a: u8 = 1;
+        let b: i64 = 2 + 3;
+        let ref c: &str = "c";
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_non_tuple_initializer() {
+        check_assist_not_applicable(
+            split_tuple_let,
+            r#"
+fn pair() -> (i32, i32) { (1, 2) }
+fn main() {
+    let (a, b)$0 = pair();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_mismatched_arity_or_nested_patterns() {
+        check_assist_not_applicable(
+            split_tuple_let,
+            r#"
+fn main() {
+    let (a, ..)$0 = (1, 2);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            split_tuple_let,
+            r#"
+fn main() {
+    let (a, (b, c))$0 = (1, (2, 3));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_elements_depend_on_bindings() {
+        cov_mark::check!(split_tuple_let_not_applicable_if_elements_depend_on_bindings);
+        check_assist_not_applicable(
+            split_tuple_let,
+            r#"
+fn main() {
+    let a = 1;
+    let b = 2;
+    let (a, b)$0 = (b, a);
+}
+"#,
+        );
+    }
+}
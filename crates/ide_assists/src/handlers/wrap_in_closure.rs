@@ -0,0 +1,157 @@
+use syntax::ast::{self, AstNode};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+// Assist: wrap_in_closure
+//
+// Wraps the selected expression in a closure, so that it is evaluated lazily.
+//
+// ```
+// fn main() {
+//     let x = 92;
+//     let f = $0x + 1$0;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = 92;
+//     let f = |$0| x + 1;
+// }
+// ```
+pub(crate) fn wrap_in_closure(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    if ctx.frange.range.is_empty() {
+        return None;
+    }
+    let expr = ctx
+        .covering_element()
+        .ancestors()
+        .take_while(|it| it.text_range().contains_range(ctx.frange.range))
+        .find_map(ast::Expr::cast)?;
+    if matches!(expr, ast::Expr::ClosureExpr(_)) {
+        return None;
+    }
+
+    // A closure body extends as far to the right as possible, so the closure needs parentheses
+    // whenever it is an operand of another expression.
+    let needs_parens = expr.syntax().parent().and_then(ast::Expr::cast).map_or(false, |parent| {
+        !matches!(
+            parent,
+            ast::Expr::ParenExpr(_)
+                | ast::Expr::BlockExpr(_)
+                | ast::Expr::ReturnExpr(_)
+                | ast::Expr::BreakExpr(_)
+        )
+    });
+
+    let group = GroupLabel("Wrap in closure".into());
+    let target = expr.syntax().text_range();
+    for (id, label, prefix) in [
+        ("wrap_in_closure", "Wrap in closure", ""),
+        ("wrap_in_move_closure", "Wrap in `move` closure", "move "),
+    ] {
+        acc.add_group(
+            &group,
+            AssistId(id, AssistKind::RefactorRewrite),
+            label,
+            target,
+            |builder| {
+                let closure = |params: &str| {
+                    let closure = format!("{}{} {}", prefix, params, expr);
+                    if needs_parens {
+                        format!("({})", closure)
+                    } else {
+                        closure
+                    }
+                };
+                // The cursor goes between the pipes, where parameters would be added.
+                match ctx.config.snippet_cap {
+                    Some(cap) => builder.replace_snippet(cap, target, closure("|$0|")),
+                    None => builder.replace(target, closure("||")),
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn wraps_simple_expression() {
+        check_assist_by_label(
+            wrap_in_closure,
+            r#"
+fn main() {
+    let v = vec![1];
+    consume($0v.len()$0);
+}
+"#,
+            r#"
+fn main() {
+    let v = vec![1];
+    consume(|$0| v.len());
+}
+"#,
+            "Wrap in closure",
+        );
+    }
+
+    #[test]
+    fn wraps_block_expression_in_move_closure() {
+        check_assist_by_label(
+            wrap_in_closure,
+            r#"
+fn main() {
+    let name = String::new();
+    spawn($0{
+        println!("{}", name);
+    }$0);
+}
+"#,
+            r#"
+fn main() {
+    let name = String::new();
+    spawn(move |$0| {
+        println!("{}", name);
+    });
+}
+"#,
+            "Wrap in `move` closure",
+        );
+    }
+
+    #[test]
+    fn adds_parens_in_operand_position() {
+        check_assist_by_label(
+            wrap_in_closure,
+            r#"
+fn main() {
+    let x = $0compute()$0.unwrap_or(0);
+}
+"#,
+            r#"
+fn main() {
+    let x = (|$0| compute()).unwrap_or(0);
+}
+"#,
+            "Wrap in closure",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_selection() {
+        check_assist_not_applicable(
+            wrap_in_closure,
+            r#"
+fn main() {
+    let x = 1 +$0 2;
+}
+"#,
+        );
+    }
+}
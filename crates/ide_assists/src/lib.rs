@@ -51,13 +51,16 @@ mod handlers {
     mod add_explicit_type;
     mod add_lifetime_to_type;
     mod add_missing_impl_members;
+    mod add_must_use;
     mod add_turbo_fish;
     mod apply_demorgan;
     mod auto_import;
     mod change_visibility;
     mod convert_bool_then;
     mod convert_comment_block;
+    mod convert_index_to_get;
     mod convert_integer_literal;
+    mod convert_params_to_struct;
     mod convert_into_to_from;
     mod convert_iter_for_each_to_for;
     mod convert_tuple_struct_to_named_struct;
@@ -88,9 +91,11 @@ mod handlers {
     mod infer_function_return_type;
     mod inline_call;
     mod inline_local_variable;
+    mod introduce_named_constant;
     mod introduce_named_lifetime;
     mod invert_if;
     mod merge_imports;
+    mod merge_into_tuple_let;
     mod merge_match_arms;
     mod move_bounds;
     mod move_guard;
@@ -99,6 +104,7 @@ mod handlers {
     mod qualify_path;
     mod raw_string;
     mod remove_dbg;
+    mod remove_explicit_type;
     mod remove_mut;
     mod remove_unused_param;
     mod reorder_fields;
@@ -111,10 +117,12 @@ mod handlers {
     mod replace_qualified_name_with_use;
     mod replace_string_with_char;
     mod split_import;
+    mod split_tuple_let;
     mod sort_items;
     mod toggle_ignore;
     mod unmerge_use;
     mod unwrap_block;
+    mod wrap_in_closure;
     mod wrap_return_type_in_result;
 
     pub(crate) fn all() -> &'static [Handler] {
@@ -122,6 +130,7 @@ mod handlers {
             // These are alphabetic for the foolish consistency
             add_explicit_type::add_explicit_type,
             add_lifetime_to_type::add_lifetime_to_type,
+            add_must_use::add_must_use,
             add_turbo_fish::add_turbo_fish,
             apply_demorgan::apply_demorgan,
             auto_import::auto_import,
@@ -129,7 +138,9 @@ mod handlers {
             convert_bool_then::convert_bool_then_to_if,
             convert_bool_then::convert_if_to_bool_then,
             convert_comment_block::convert_comment_block,
+            convert_index_to_get::convert_index_to_get,
             convert_integer_literal::convert_integer_literal,
+            convert_params_to_struct::convert_params_to_struct,
             convert_into_to_from::convert_into_to_from,
             convert_iter_for_each_to_for::convert_iter_for_each_to_for,
             convert_to_guarded_return::convert_to_guarded_return,
@@ -160,6 +171,7 @@ mod handlers {
             introduce_named_lifetime::introduce_named_lifetime,
             invert_if::invert_if,
             merge_imports::merge_imports,
+            merge_into_tuple_let::merge_into_tuple_let,
             merge_match_arms::merge_match_arms,
             move_bounds::move_bounds_to_where_clause,
             move_guard::move_arm_cond_to_match_guard,
@@ -171,6 +183,8 @@ mod handlers {
             raw_string::make_usual_string,
             raw_string::remove_hash,
             remove_dbg::remove_dbg,
+            remove_dbg::remove_all_dbg,
+            remove_explicit_type::remove_explicit_type,
             remove_mut::remove_mut,
             remove_unused_param::remove_unused_param,
             reorder_fields::reorder_fields,
@@ -184,6 +198,7 @@ mod handlers {
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             sort_items::sort_items,
             split_import::split_import,
+            split_tuple_let::split_tuple_let,
             toggle_ignore::toggle_ignore,
             unmerge_use::unmerge_use,
             unwrap_block::unwrap_block,
@@ -201,6 +216,8 @@ mod handlers {
             //
             extract_variable::extract_variable,
             extract_function::extract_function,
+            introduce_named_constant::introduce_named_constant,
+            wrap_in_closure::wrap_in_closure,
             //
             generate_getter::generate_getter,
             generate_getter::generate_getter_mut,
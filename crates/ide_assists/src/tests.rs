@@ -246,6 +246,8 @@ pub fn test_some_range(a: int) -> bool {
         Convert integer base
         Extract into variable
         Extract into function
+        Introduce named constant
+        Wrap in closure
         Replace if let with match
     "#]]
     .assert_eq(&expected);
@@ -275,6 +277,8 @@ pub fn test_some_range(a: int) -> bool {
             Convert integer base
             Extract into variable
             Extract into function
+            Introduce named constant
+            Wrap in closure
             Replace if let with match
         "#]]
         .assert_eq(&expected);
@@ -289,6 +293,7 @@ pub fn test_some_range(a: int) -> bool {
         expect![[r#"
             Extract into variable
             Extract into function
+            Introduce named constant
         "#]]
         .assert_eq(&expected);
     }
@@ -322,7 +327,7 @@ pub fn test_some_range(a: int) -> bool {
 
     {
         let assists = assists(&db, &cfg, AssistResolveStrategy::None, frange);
-        assert_eq!(2, assists.len());
+        assert_eq!(3, assists.len());
         let mut assists = assists.into_iter();
 
         let extract_into_variable_assist = assists.next().unwrap();
@@ -366,7 +371,7 @@ pub fn test_some_range(a: int) -> bool {
             }),
             frange,
         );
-        assert_eq!(2, assists.len());
+        assert_eq!(3, assists.len());
         let mut assists = assists.into_iter();
 
         let extract_into_variable_assist = assists.next().unwrap();
@@ -410,7 +415,7 @@ pub fn test_some_range(a: int) -> bool {
             }),
             frange,
         );
-        assert_eq!(2, assists.len());
+        assert_eq!(3, assists.len());
         let mut assists = assists.into_iter();
 
         let extract_into_variable_assist = assists.next().unwrap();
@@ -467,7 +472,7 @@ pub fn test_some_range(a: int) -> bool {
 
     {
         let assists = assists(&db, &cfg, AssistResolveStrategy::All, frange);
-        assert_eq!(2, assists.len());
+        assert_eq!(3, assists.len());
         let mut assists = assists.into_iter();
 
         let extract_into_variable_assist = assists.next().unwrap();
@@ -121,6 +121,20 @@ struct Point<'a> {
     )
 }
 
+#[test]
+fn doctest_add_must_use() {
+    check_doc_test(
+        "add_must_use",
+        r#####"
+pub fn len$0(&self) -> usize { 0 }
+"#####,
+        r#####"
+#[must_use$0]
+pub fn len(&self) -> usize { 0 }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_turbo_fish() {
     check_doc_test(
@@ -235,6 +249,25 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_index_to_get() {
+    check_doc_test(
+        "convert_index_to_get",
+        r#####"
+fn main() {
+    let v = [1, 2, 3];
+    let x = v[$01];
+}
+"#####,
+        r#####"
+fn main() {
+    let v = [1, 2, 3];
+    let x = v.get(1);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_integer_literal() {
     check_doc_test(
@@ -302,6 +335,33 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_params_to_struct() {
+    check_doc_test(
+        "convert_params_to_struct",
+        r#####"
+fn connect(host: &str, port: u16, secure: bool) {}
+
+fn main() {
+    connect$0("localhost", 80, false);
+}
+"#####,
+        r#####"
+struct ConnectArgs<'a> {
+    host: &'a str,
+    port: u16,
+    secure: bool,
+}
+
+fn connect(ConnectArgs { host, port, secure }: ConnectArgs) {}
+
+fn main() {
+    connect(ConnectArgs { host: "localhost", port: 80, secure: false });
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -1001,6 +1061,24 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_introduce_named_constant() {
+    check_doc_test(
+        "introduce_named_constant",
+        r#####"
+fn area(r: f64) -> f64 {
+    3.14$0 * r * r
+}
+"#####,
+        r#####"
+fn area(r: f64) -> f64 {
+    const $0CONSTANT: f64 = 3.14;
+    CONSTANT * r * r
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_named_lifetime() {
     check_doc_test(
@@ -1091,6 +1169,24 @@ use std::{fmt::Formatter, io};
     )
 }
 
+#[test]
+fn doctest_merge_into_tuple_let() {
+    check_doc_test(
+        "merge_into_tuple_let",
+        r#####"
+fn main() {
+    $0let mut a = 1;
+    let b = 2;$0
+}
+"#####,
+        r#####"
+fn main() {
+    let (mut a, b) = (1, 2);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_merge_match_arms() {
     check_doc_test(
@@ -1253,6 +1349,24 @@ pub mod std { pub mod collections { pub struct HashMap { } } }
     )
 }
 
+#[test]
+fn doctest_remove_all_dbg() {
+    check_doc_test(
+        "remove_all_dbg",
+        r#####"
+fn main() {
+    $0let x = dbg!(1 + 1) * 2;
+    dbg!();
+}
+"#####,
+        r#####"
+fn main() {
+    let x = (1 + 1) * 2;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_remove_dbg() {
     check_doc_test(
@@ -1270,6 +1384,23 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_remove_explicit_type() {
+    check_doc_test(
+        "remove_explicit_type",
+        r#####"
+fn main() {
+    let x$0: bool = true;
+}
+"#####,
+        r#####"
+fn main() {
+    let x = true;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_remove_hash() {
     check_doc_test(
@@ -1671,6 +1802,24 @@ use std::{collections::HashMap};
     )
 }
 
+#[test]
+fn doctest_split_tuple_let() {
+    check_doc_test(
+        "split_tuple_let",
+        r#####"
+fn main() {
+    let (mut a, b)$0 = (1, 2);
+}
+"#####,
+        r#####"
+fn main() {
+    let mut a = 1;
+    let b = 2;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_toggle_ignore() {
     check_doc_test(
@@ -1724,6 +1873,25 @@ fn foo() {
     )
 }
 
+#[test]
+fn doctest_wrap_in_closure() {
+    check_doc_test(
+        "wrap_in_closure",
+        r#####"
+fn main() {
+    let x = 92;
+    let f = $0x + 1$0;
+}
+"#####,
+        r#####"
+fn main() {
+    let x = 92;
+    let f = |$0| x + 1;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_wrap_return_type_in_result() {
     check_doc_test(
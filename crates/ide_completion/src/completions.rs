@@ -1,9 +1,11 @@
 //! This module defines an accumulator for completions which are going to be presented to user.
 
 pub(crate) mod attribute;
+pub(crate) mod doc_link;
 pub(crate) mod dot;
 pub(crate) mod flyimport;
 pub(crate) mod fn_param;
+pub(crate) mod format_string;
 pub(crate) mod keyword;
 pub(crate) mod lifetime;
 pub(crate) mod mod_;
@@ -256,7 +258,11 @@ fn enum_variants_with_paths(
     }
 
     for variant in variants {
-        if let Some(path) = module.find_use_path(ctx.db, hir::ModuleDef::from(variant)) {
+        if let Some(path) = module.find_use_path(
+            ctx.db,
+            hir::ModuleDef::from(variant),
+            hir::FindPathConfig::default(),
+        ) {
             // Variants with trivial paths are already added by the existing completion logic,
             // so we should avoid adding these twice
             if path.segments().len() > 1 {
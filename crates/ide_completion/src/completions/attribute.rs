@@ -17,6 +17,7 @@ use crate::{
 
 mod cfg;
 mod derive;
+mod helper;
 mod lint;
 mod repr;
 
@@ -29,12 +30,13 @@ pub(crate) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext)
             "feature" => lint::complete_lint(acc, ctx, token_tree, FEATURES),
             "allow" | "warn" | "deny" | "forbid" => {
                 lint::complete_lint(acc, ctx, token_tree.clone(), DEFAULT_LINTS);
-                lint::complete_lint(acc, ctx, token_tree, CLIPPY_LINTS);
+                lint::complete_lint(acc, ctx, token_tree.clone(), CLIPPY_LINTS);
+                lint::complete_lint(acc, ctx, token_tree, lint::RUSTDOC_LINTS);
             }
             "cfg" => {
                 cfg::complete_cfg(acc, ctx);
             }
-            _ => (),
+            name => helper::complete_helper_attr(acc, ctx, attribute, name, token_tree),
         },
         (None, Some(_)) => (),
         _ => complete_new_attribute(acc, ctx, attribute),
@@ -92,7 +94,7 @@ fn complete_new_attribute(acc: &mut Completions, ctx: &CompletionContext, attrib
     // FIXME: write a test for this when we can
     ctx.scope.process_all_names(&mut |name, scope_def| {
         if let hir::ScopeDef::MacroDef(mac) = scope_def {
-            if mac.kind() == hir::MacroKind::Attr {
+            if mac.is_attr() {
                 let mut item = CompletionItem::new(
                     CompletionKind::Attribute,
                     ctx.source_range(),
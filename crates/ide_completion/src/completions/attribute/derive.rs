@@ -56,7 +56,7 @@ fn get_derive_names_in_scope(
     let mut result = FxHashMap::default();
     ctx.process_all_names(&mut |name, scope_def| {
         if let hir::ScopeDef::MacroDef(mac) = scope_def {
-            if mac.kind() == hir::MacroKind::Derive {
+            if mac.is_derive() {
                 result.insert(name.to_string(), mac.docs(ctx.db));
             }
         }
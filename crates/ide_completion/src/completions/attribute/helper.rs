@@ -0,0 +1,131 @@
+//! Completion for arguments of derive helper attributes, like `#[serde(...)]`.
+//!
+//! Helper attributes are defined by proc macros, so we can't know their
+//! arguments in general. Instead, the argument sets of some popular derives
+//! are built in, more can be registered through
+//! [`CompletionConfig::helper_attributes`](crate::CompletionConfig::helper_attributes).
+//! Arguments are offered if the annotated item derives one of the owning traits.
+
+use once_cell::sync::Lazy;
+use syntax::{
+    ast::{self, AttrsOwner},
+    AstNode, SyntaxKind,
+};
+
+use crate::{
+    context::CompletionContext,
+    item::{CompletionItem, CompletionItemKind, CompletionKind},
+    Completions, HelperArg, HelperAttribute,
+};
+
+pub(super) fn complete_helper_attr(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    attribute: &ast::Attr,
+    name: &str,
+    helper_input: ast::TokenTree,
+) {
+    let existing_args = match super::parse_comma_sep_input(helper_input) {
+        Some(it) => it,
+        None => return,
+    };
+    let helpers = BUILTIN_HELPER_ATTRIBUTES
+        .iter()
+        .chain(ctx.config.helper_attributes.iter())
+        .filter(|helper| helper.name == name && is_derived_by_adt(attribute, &helper.derives));
+    for helper in helpers {
+        let args = match attribute.syntax().parent().map(|it| it.kind()) {
+            Some(SyntaxKind::RECORD_FIELD | SyntaxKind::TUPLE_FIELD) => &helper.field_args,
+            Some(SyntaxKind::VARIANT) => &helper.variant_args,
+            Some(kind) if ast::Adt::can_cast(kind) => &helper.container_args,
+            _ => continue,
+        };
+        for arg in args {
+            if existing_args.iter().any(|it| it.split('=').next().map(str::trim) == Some(arg.key()))
+            {
+                continue;
+            }
+            let mut item = CompletionItem::new(
+                CompletionKind::Attribute,
+                ctx.source_range(),
+                arg.label.clone(),
+            );
+            item.kind(CompletionItemKind::Attribute);
+            if let Some(lookup) = &arg.lookup {
+                item.lookup_by(lookup.clone());
+            }
+            if let Some((snippet, cap)) = arg.snippet.as_ref().zip(ctx.config.snippet_cap) {
+                item.insert_snippet(cap, snippet.clone());
+            }
+            item.add_to(acc);
+        }
+    }
+}
+
+/// Checks whether the ADT `attribute` belongs to (either directly or through
+/// one of its fields or variants) derives any of `derives`.
+fn is_derived_by_adt(attribute: &ast::Attr, derives: &[String]) -> bool {
+    let adt = match attribute.syntax().ancestors().find_map(ast::Adt::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    adt.attrs()
+        .filter(|attr| attr.simple_name().as_deref() == Some("derive"))
+        .filter_map(|attr| attr.token_tree())
+        .filter_map(super::parse_comma_sep_input)
+        .flatten()
+        .any(|derive| {
+            let name = derive.rsplit("::").next().unwrap_or(&derive).trim();
+            derives.iter().any(|it| it == name)
+        })
+}
+
+/// Known helper attributes, new ones can simply be added to this list.
+static BUILTIN_HELPER_ATTRIBUTES: Lazy<Vec<HelperAttribute>> = Lazy::new(|| {
+    use HelperArg as Arg;
+    vec![HelperAttribute {
+        name: "serde".to_string(),
+        derives: vec!["Serialize".to_string(), "Deserialize".to_string()],
+        container_args: vec![
+            Arg::with_value("bound", "T: MyTrait"),
+            Arg::with_value("content", "content"),
+            Arg::flag("default"),
+            Arg::flag("deny_unknown_fields"),
+            Arg::with_value("from", "FromType"),
+            Arg::with_value("into", "IntoType"),
+            Arg::with_value("rename", "name"),
+            Arg::with_value("rename_all", "snake_case"),
+            Arg::with_value("tag", "type"),
+            Arg::flag("transparent"),
+            Arg::with_value("try_from", "FromType"),
+            Arg::flag("untagged"),
+        ],
+        variant_args: vec![
+            Arg::with_value("alias", "name"),
+            Arg::with_value("bound", "T: MyTrait"),
+            Arg::with_value("deserialize_with", "path"),
+            Arg::flag("other"),
+            Arg::with_value("rename", "name"),
+            Arg::with_value("rename_all", "snake_case"),
+            Arg::with_value("serialize_with", "path"),
+            Arg::flag("skip"),
+            Arg::flag("skip_deserializing"),
+            Arg::flag("skip_serializing"),
+            Arg::with_value("with", "module"),
+        ],
+        field_args: vec![
+            Arg::with_value("alias", "name"),
+            Arg::with_value("bound", "T: MyTrait"),
+            Arg::flag("default"),
+            Arg::with_value("deserialize_with", "path"),
+            Arg::flag("flatten"),
+            Arg::with_value("rename", "name"),
+            Arg::with_value("serialize_with", "path"),
+            Arg::flag("skip"),
+            Arg::flag("skip_deserializing"),
+            Arg::flag("skip_serializing"),
+            Arg::with_value("skip_serializing_if", "path"),
+            Arg::with_value("with", "module"),
+        ],
+    }]
+});
@@ -1,6 +1,6 @@
 //! Completion for lints
 use ide_db::helpers::generated_lints::Lint;
-use syntax::ast;
+use syntax::{ast, SyntaxKind::IDENT, T};
 
 use crate::{
     context::CompletionContext,
@@ -14,18 +14,84 @@ pub(super) fn complete_lint(
     derive_input: ast::TokenTree,
     lints_completions: &[Lint],
 ) {
+    let qualifier = lint_qualifier(ctx);
     if let Some(existing_lints) = super::parse_comma_sep_input(derive_input) {
         for lint_completion in
             lints_completions.iter().filter(|completion| !existing_lints.contains(completion.label))
         {
-            let mut item = CompletionItem::new(
-                CompletionKind::Attribute,
-                ctx.source_range(),
-                lint_completion.label,
-            );
+            // With a `tool::` prefix typed, only that tool's lints apply and the prefix is kept.
+            let label = match &qualifier {
+                Some(qualifier) => match lint_completion
+                    .label
+                    .strip_prefix(qualifier.as_str())
+                    .and_then(|it| it.strip_prefix("::"))
+                {
+                    Some(name) => name,
+                    None => continue,
+                },
+                None => lint_completion.label,
+            };
+            let mut item =
+                CompletionItem::new(CompletionKind::Attribute, ctx.source_range(), label);
             item.kind(CompletionItemKind::Attribute)
                 .documentation(hir::Documentation::new(lint_completion.description.to_owned()));
             item.add_to(acc)
         }
     }
 }
+
+/// Returns the tool name in front of the lint under the cursor, like `clippy` in `clippy::$0`.
+fn lint_qualifier(ctx: &CompletionContext) -> Option<String> {
+    let mut token = ctx.original_token.clone();
+    if token.kind() == IDENT {
+        token = token.prev_token()?;
+    }
+    match token.kind() {
+        T![::] => token = token.prev_token()?,
+        T![:] => {
+            token = token.prev_token().filter(|it| it.kind() == T![:])?.prev_token()?;
+        }
+        _ => return None,
+    }
+    if token.kind() == IDENT {
+        Some(token.text().to_string())
+    } else {
+        None
+    }
+}
+
+pub(super) const RUSTDOC_LINTS: &[Lint] = &[
+    Lint { label: "rustdoc::bare_urls", description: r##"detects URLs that are not hyperlinks"## },
+    Lint {
+        label: "rustdoc::broken_intra_doc_links",
+        description: r##"failures in resolving intra-doc link targets"##,
+    },
+    Lint {
+        label: "rustdoc::invalid_codeblock_attributes",
+        description: r##"codeblock attribute looks a lot like a known one"##,
+    },
+    Lint {
+        label: "rustdoc::invalid_html_tags",
+        description: r##"detects invalid HTML tags in doc comments"##,
+    },
+    Lint {
+        label: "rustdoc::invalid_rust_codeblocks",
+        description: r##"codeblock could not be parsed as valid Rust or is empty"##,
+    },
+    Lint {
+        label: "rustdoc::missing_crate_level_docs",
+        description: r##"detects crates with no crate-level documentation"##,
+    },
+    Lint {
+        label: "rustdoc::missing_doc_code_examples",
+        description: r##"detects publicly-exported items without code samples in their documentation"##,
+    },
+    Lint {
+        label: "rustdoc::private_doc_tests",
+        description: r##"detects code samples in docs of private items not documented by rustdoc"##,
+    },
+    Lint {
+        label: "rustdoc::private_intra_doc_links",
+        description: r##"linking from a public item to a private one"##,
+    },
+];
@@ -0,0 +1,312 @@
+//! Completes intra-doc links in doc comments, i.e. `/// [Fo$0]` or `/// [Foo](crate::Fo$0)`.
+
+use hir::{PathResolution, ScopeDef};
+use ide_db::{defs::Definition, SymbolKind};
+use syntax::{
+    ast::{self, make, AstToken},
+    TextRange, TextSize,
+};
+
+use crate::{
+    context::CompletionContext,
+    item::{CompletionItem, CompletionItemKind, CompletionKind},
+    Completions,
+};
+
+pub(crate) fn complete_doc_link(acc: &mut Completions, ctx: &CompletionContext) -> Option<()> {
+    let doc_comment_ctx = ctx.doc_comment_ctx.as_ref()?;
+    let link = doc_link_at(&doc_comment_ctx.comment, ctx.position.offset)?;
+
+    let _p = profile::span("completion::complete_doc_link");
+
+    let candidates = match &link.qualifier {
+        // rustdoc resolves `Self` to the documented item.
+        Some(qualifier) if qualifier == "Self" => match doc_comment_ctx.owner? {
+            Definition::ModuleDef(hir::ModuleDef::Adt(hir::Adt::Enum(e))) => variants(ctx, e),
+            _ => return None,
+        },
+        Some(qualifier) => {
+            match ctx.scope.speculative_resolve(&make::path_from_text(qualifier))? {
+                PathResolution::Def(hir::ModuleDef::Module(module)) => {
+                    module.scope(ctx.db, ctx.scope.module())
+                }
+                PathResolution::Def(hir::ModuleDef::Adt(hir::Adt::Enum(e))) => variants(ctx, e),
+                _ => return None,
+            }
+        }
+        None => {
+            let mut candidates = Vec::new();
+            ctx.scope.process_all_names(&mut |name, def| candidates.push((name, def)));
+            candidates
+        }
+    };
+
+    for (name, def) in candidates {
+        let kind = match doc_link_kind(&def) {
+            Some(it) => it,
+            None => continue,
+        };
+        let path = match &link.qualifier {
+            Some(qualifier) => format!("{}::{}", qualifier, name),
+            None => name.to_string(),
+        };
+        let insert = if link.is_destination { path } else { format!("`{}`", path) };
+
+        let mut item = CompletionItem::new(CompletionKind::Reference, link.range, name.to_string());
+        item.kind(kind).lookup_by(insert.clone()).insert_text(insert);
+        if let ScopeDef::ModuleDef(def) = def {
+            item.set_documentation(def.docs(ctx.db));
+        }
+        item.add_to(acc);
+    }
+    None
+}
+
+fn variants(ctx: &CompletionContext, e: hir::Enum) -> Vec<(hir::Name, ScopeDef)> {
+    e.variants(ctx.db)
+        .into_iter()
+        .map(|variant| (variant.name(ctx.db), ScopeDef::ModuleDef(variant.into())))
+        .collect()
+}
+
+struct DocLink {
+    /// The path typed so far, together with its backticks.
+    range: TextRange,
+    /// The `foo::bar` in `[foo::bar::Ba$0]`.
+    qualifier: Option<String>,
+    /// Whether we are in the `(...)` part of `[Name](path)`.
+    is_destination: bool,
+}
+
+fn doc_link_at(comment: &ast::Comment, offset: TextSize) -> Option<DocLink> {
+    let comment_start = comment.syntax().text_range().start();
+    let (text, rest) = comment.text().split_at(usize::from(offset - comment_start));
+
+    let link_start = text.rfind('[')? + 1;
+    let mut typed = &text[link_start..];
+    let mut typed_start = link_start;
+    let is_destination = match typed.find(']') {
+        Some(idx) => {
+            let destination = typed[idx + 1..].strip_prefix('(')?;
+            typed_start += idx + 2;
+            typed = destination;
+            true
+        }
+        None => false,
+    };
+
+    let (path, has_backtick) = match typed.strip_prefix('`') {
+        Some(path) if !is_destination => (path, true),
+        _ => (typed, false),
+    };
+    if !path.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':') {
+        return None;
+    }
+    let qualifier = path.rfind("::").map(|idx| path[..idx].to_string());
+    if qualifier.as_deref() == Some("") {
+        return None;
+    }
+
+    // Also replace the remainder of the name under the cursor, like `Ba` in `[Fo$0Ba]`.
+    let mut rest_len =
+        rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+    if has_backtick && rest[rest_len..].starts_with('`') {
+        rest_len += 1;
+    }
+    let range = TextRange::new(
+        comment_start + TextSize::of(&text[..typed_start]),
+        offset + TextSize::of(&rest[..rest_len]),
+    );
+    Some(DocLink { range, qualifier, is_destination })
+}
+
+fn doc_link_kind(def: &ScopeDef) -> Option<CompletionItemKind> {
+    use hir::ModuleDef::*;
+
+    let kind = match def {
+        ScopeDef::ModuleDef(Module(_)) => SymbolKind::Module,
+        ScopeDef::ModuleDef(Function(_)) => SymbolKind::Function,
+        ScopeDef::ModuleDef(Adt(hir::Adt::Struct(_))) => SymbolKind::Struct,
+        ScopeDef::ModuleDef(Adt(hir::Adt::Union(_))) => SymbolKind::Union,
+        ScopeDef::ModuleDef(Adt(hir::Adt::Enum(_))) => SymbolKind::Enum,
+        ScopeDef::ModuleDef(Variant(_)) => SymbolKind::Variant,
+        ScopeDef::ModuleDef(Const(_)) => SymbolKind::Const,
+        ScopeDef::ModuleDef(Static(_)) => SymbolKind::Static,
+        ScopeDef::ModuleDef(Trait(_)) => SymbolKind::Trait,
+        ScopeDef::ModuleDef(TypeAlias(_)) => SymbolKind::TypeAlias,
+        ScopeDef::MacroDef(_) => SymbolKind::Macro,
+        _ => return None,
+    };
+    Some(CompletionItemKind::SymbolKind(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::tests::{check_edit, completion_list};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture);
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn completes_sibling_items() {
+        check(
+            r#"
+struct Bar;
+fn baz() {}
+macro_rules! mac { () => {} }
+
+/// See [$0]
+struct Foo;
+"#,
+            expect![[r#"
+                st Foo
+                fn baz
+                st Bar
+                ma mac
+            "#]],
+        );
+    }
+
+    #[test]
+    fn links_to_sibling_struct() {
+        check_edit(
+            "`Bar`",
+            r#"
+struct Bar;
+
+/// Wraps a [$0]
+struct Foo(Bar);
+"#,
+            r#"
+struct Bar;
+
+/// Wraps a [`Bar`]
+struct Foo(Bar);
+"#,
+        );
+        check_edit(
+            "`Bar`",
+            r#"
+struct Bar;
+
+/// Wraps a [`Ba$0]
+struct Foo(Bar);
+"#,
+            r#"
+struct Bar;
+
+/// Wraps a [`Bar`]
+struct Foo(Bar);
+"#,
+        );
+    }
+
+    #[test]
+    fn completes_qualified_paths() {
+        check_edit(
+            "`inner::Bar`",
+            r#"
+mod inner {
+    pub struct Bar;
+}
+
+/// Uses [inner::$0]
+struct Foo;
+"#,
+            r#"
+mod inner {
+    pub struct Bar;
+}
+
+/// Uses [`inner::Bar`]
+struct Foo;
+"#,
+        );
+        check_edit(
+            "`E::Variant`",
+            r#"
+enum E { Variant }
+
+/// Returns [`E::$0`]
+fn f() -> E { E::Variant }
+"#,
+            r#"
+enum E { Variant }
+
+/// Returns [`E::Variant`]
+fn f() -> E { E::Variant }
+"#,
+        );
+    }
+
+    #[test]
+    fn completes_variants_of_documented_enum_through_self() {
+        check_edit(
+            "`Self::B`",
+            r#"
+/// Defaults to [Self::$0]
+enum Foo { A, B }
+"#,
+            r#"
+/// Defaults to [`Self::B`]
+enum Foo { A, B }
+"#,
+        );
+        check(
+            r#"
+/// Defaults to [Self::$0]
+struct Foo;
+"#,
+            expect![[""]],
+        );
+    }
+
+    #[test]
+    fn completes_link_destination() {
+        check_edit(
+            "inner::Bar",
+            r#"
+mod inner {
+    pub struct Bar;
+}
+
+/// Uses [the bar](inner::$0)
+struct Foo;
+"#,
+            r#"
+mod inner {
+    pub struct Bar;
+}
+
+/// Uses [the bar](inner::Bar)
+struct Foo;
+"#,
+        );
+    }
+
+    #[test]
+    fn no_completions_outside_links() {
+        check(
+            r#"
+struct Bar;
+
+/// Wraps a $0
+struct Foo;
+"#,
+            expect![[""]],
+        );
+        check(
+            r#"
+struct Bar;
+
+// See [$0]
+struct Foo;
+"#,
+            expect![[""]],
+        );
+    }
+}
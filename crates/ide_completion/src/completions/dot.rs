@@ -61,16 +61,19 @@ fn complete_fields(
     receiver: &hir::Type,
     mut f: impl FnMut(Either<hir::Field, usize>, hir::Type),
 ) {
+    // Fields of earlier autoderef steps shadow fields of the same name further down the chain.
+    let mut seen_names = FxHashSet::default();
     for receiver in receiver.autoderef(ctx.db) {
         for (field, ty) in receiver.fields(ctx.db) {
-            if !ctx.is_visible(&field) {
-                continue;
+            if ctx.is_visible(&field) && seen_names.insert(field.name(ctx.db)) {
+                f(Either::Left(field), ty);
             }
-            f(Either::Left(field), ty);
         }
         for (i, ty) in receiver.tuple_fields(ctx.db).into_iter().enumerate() {
             // Tuple fields are always public (tuple struct fields are handled above).
-            f(Either::Right(i), ty);
+            if seen_names.insert(hir::Name::new_tuple_field(i)) {
+                f(Either::Right(i), ty);
+            }
         }
     }
 }
@@ -98,9 +101,12 @@ fn complete_methods(
 #[cfg(test)]
 mod tests {
     use expect_test::{expect, Expect};
+    use hir::Semantics;
+    use stdx::format_to;
+    use syntax::{algo::find_node_at_offset, ast, AstNode};
 
     use crate::{
-        tests::{check_edit, filtered_completion_list},
+        tests::{check_edit, filtered_completion_list, position},
         CompletionKind,
     };
 
@@ -109,6 +115,32 @@ mod tests {
         expect.assert_eq(&actual);
     }
 
+    fn check_method_candidates_with_traits(ra_fixture: &str, expect: Expect) {
+        let (db, position) = position(ra_fixture);
+        let sema = Semantics::new(&db);
+        let file = sema.parse(position.file_id);
+        let receiver = find_node_at_offset::<ast::Expr>(file.syntax(), position.offset).unwrap();
+        let scope = sema.scope(receiver.syntax());
+        let ty = sema.type_of_expr(&receiver).unwrap().original;
+
+        let mut actual = String::new();
+        ty.iterate_method_candidates_with_traits(
+            &db,
+            scope.krate().unwrap(),
+            &scope.traits_in_scope(),
+            None,
+            |_ty, func, trait_to_import| {
+                format_to!(actual, "{}", func.name(&db));
+                if let Some(trait_) = trait_to_import {
+                    format_to!(actual, " (import {})", trait_.name(&db));
+                }
+                actual.push('\n');
+                None::<()>
+            },
+        );
+        expect.assert_eq(&actual);
+    }
+
     #[test]
     fn test_struct_field_and_method_completion() {
         check(
@@ -158,6 +190,51 @@ impl A {
         )
     }
 
+    #[test]
+    fn test_field_completion_through_box() {
+        check(
+            r#"
+//- minicore: deref
+struct Box<T>(T);
+impl<T> core::ops::Deref for Box<T> {
+    type Target = T;
+    fn deref(&self) -> &T { &self.0 }
+}
+struct Foo { foo_field: u32 }
+impl Foo {
+    fn foo_method(&self) {}
+}
+fn f(b: Box<Foo>) { b.$0 }
+"#,
+            expect![[r#"
+                fd 0            Foo
+                fd foo_field    u32
+                me foo_method() fn(&self)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_field_completion_through_custom_deref_is_shadowed() {
+        check(
+            r#"
+//- minicore: deref
+struct Inner { shared: u32, inner_only: i64 }
+struct Outer { shared: u8, inner: Inner }
+impl core::ops::Deref for Outer {
+    type Target = Inner;
+    fn deref(&self) -> &Inner { &self.inner }
+}
+fn f(o: Outer) { o.$0 }
+"#,
+            expect![[r#"
+                fd shared     u8
+                fd inner      Inner
+                fd inner_only i64
+            "#]],
+        );
+    }
+
     #[test]
     fn test_no_struct_field_completion_for_method_call() {
         cov_mark::check!(test_no_struct_field_completion_for_method_call);
@@ -425,6 +502,23 @@ fn foo() {
         );
     }
 
+    #[test]
+    fn test_tuple_field_completion_three_elements() {
+        check(
+            r#"
+fn foo() {
+    let t = (1, "a", (true, 'c'));
+    t.$0
+}
+"#,
+            expect![[r#"
+                fd 0 i32
+                fd 1 &str
+                fd 2 (bool, char)
+            "#]],
+        );
+    }
+
     #[test]
     fn test_tuple_struct_field_completion() {
         check(
@@ -672,4 +766,36 @@ impl Foo { fn foo(&mut self) { $0 } }"#,
             "#]],
         );
     }
+
+    #[test]
+    fn method_candidates_with_extension_trait() {
+        check_method_candidates_with_traits(
+            r#"
+struct S;
+impl S {
+    fn inherent(&self) {}
+}
+trait InScope {
+    fn in_scope(&self);
+}
+impl InScope for S {
+    fn in_scope(&self) {}
+}
+mod ext {
+    pub trait SExt {
+        fn extension(&self);
+    }
+    impl SExt for super::S {
+        fn extension(&self) {}
+    }
+}
+fn f(s: S) { s$0; }
+"#,
+            expect![[r#"
+                inherent
+                in_scope
+                extension (import SExt)
+            "#]],
+        );
+    }
 }
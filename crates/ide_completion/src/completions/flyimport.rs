@@ -226,7 +226,10 @@ mod tests {
 
     use crate::{
         item::CompletionKind,
-        tests::{check_edit, check_edit_with_config, filtered_completion_list, TEST_CONFIG},
+        tests::{
+            check_edit, check_edit_with_config, filtered_completion_list,
+            filtered_completion_list_with_config, TEST_CONFIG,
+        },
     };
 
     fn check(ra_fixture: &str, expect: Expect) {
@@ -514,6 +517,57 @@ fn main() {
         );
     }
 
+    #[test]
+    fn extension_trait_method_completion_after_dot() {
+        let fixture = r#"
+mod ext {
+    pub trait StrExt {
+        fn shout(&self);
+    }
+    impl StrExt for str {
+        fn shout(&self) {}
+    }
+}
+
+fn main() {
+    "hello".$0
+}
+"#;
+
+        check(
+            fixture,
+            expect![[r#"
+                me shout() (use ext::StrExt) fn(&self)
+            "#]],
+        );
+
+        let mut config = TEST_CONFIG;
+        config.enable_imports_on_the_fly = false;
+        let actual = filtered_completion_list_with_config(config, fixture, CompletionKind::Magic);
+        expect![[r#""#]].assert_eq(&actual);
+
+        check_edit(
+            "shout",
+            fixture,
+            r#"
+use ext::StrExt;
+
+mod ext {
+    pub trait StrExt {
+        fn shout(&self);
+    }
+    impl StrExt for str {
+        fn shout(&self) {}
+    }
+}
+
+fn main() {
+    "hello".shout()$0
+}
+"#,
+        );
+    }
+
     #[test]
     fn no_trait_type_fuzzy_completion() {
         check(
@@ -1185,6 +1239,77 @@ impl<T> Private for T {}
         );
     }
 
+    #[test]
+    fn import_path_skips_doc_hidden_module() {
+        check(
+            r#"
+//- /lib.rs crate:dep
+#[doc(hidden)]
+pub mod hidden {
+    pub struct ThirdStruct;
+}
+pub mod visible {
+    pub use crate::hidden::ThirdStruct;
+}
+
+//- /main.rs crate:main deps:dep
+fn main() {
+    Third$0
+}
+"#,
+            expect![[r#"
+                st ThirdStruct (use dep::visible::ThirdStruct)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn import_path_prefers_shortest_reexport() {
+        check(
+            r#"
+//- /lib.rs crate:dep
+mod private {
+    pub mod runtime {
+        pub struct ThirdStruct;
+    }
+}
+pub mod runtime {
+    pub use crate::private::runtime::ThirdStruct;
+}
+pub use crate::private::runtime::ThirdStruct;
+
+//- /main.rs crate:main deps:dep
+fn main() {
+    Third$0
+}
+"#,
+            expect![[r#"
+                st ThirdStruct (use dep::ThirdStruct)
+            "#]],
+        );
+    }
+
+    #[test]
+    fn import_path_falls_back_to_doc_hidden_module() {
+        check(
+            r#"
+//- /lib.rs crate:dep
+#[doc(hidden)]
+pub mod hidden {
+    pub struct ThirdStruct;
+}
+
+//- /main.rs crate:main deps:dep
+fn main() {
+    Third$0
+}
+"#,
+            expect![[r#"
+                st ThirdStruct (use dep::hidden::ThirdStruct)
+            "#]],
+        );
+    }
+
     #[test]
     fn regression_9760() {
         check(
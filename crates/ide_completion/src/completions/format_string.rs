@@ -0,0 +1,185 @@
+//! Completes format specifiers in format strings, i.e. the `?` in `println!("{:?}", foo)`.
+
+use ide_db::helpers::format_string::is_format_string;
+use syntax::{ast, AstToken, TextRange, TextSize};
+
+use crate::{
+    context::CompletionContext,
+    item::{CompletionItem, CompletionItemKind, CompletionKind},
+    Completions,
+};
+
+/// `(label, insert text, detail)` of the specifiers we offer.
+const SPECIFIERS: &[(&str, &str, &str)] = &[
+    ("?", "?", "Debug"),
+    ("#?", "#?", "Debug, pretty-printed"),
+    ("x", "x", "LowerHex"),
+    ("X", "X", "UpperHex"),
+    ("#x", "#x", "LowerHex, with `0x` prefix"),
+    ("b", "b", "Binary"),
+    ("o", "o", "Octal"),
+    ("e", "e", "LowerExp"),
+    ("E", "E", "UpperExp"),
+    ("width", "${1:width}", "minimum width, like `{:5}`"),
+    ("precision", ".${1:precision}", "precision, like `{:.2}`"),
+];
+
+pub(crate) fn complete_format_string(acc: &mut Completions, ctx: &CompletionContext) -> Option<()> {
+    ast::String::cast(ctx.token.clone()).filter(is_format_string)?;
+    let original = ast::String::cast(ctx.original_token.clone())?;
+    let range = spec_range_at(&original, ctx.position.offset)?;
+
+    let _p = profile::span("completion::complete_format_string");
+
+    for &(label, insert, detail) in SPECIFIERS {
+        let mut item = CompletionItem::new(CompletionKind::Magic, range, label);
+        item.kind(CompletionItemKind::Snippet).detail(detail);
+        match ctx.config.snippet_cap {
+            Some(cap) if insert != label => item.insert_snippet(cap, insert),
+            _ => item.insert_text(label),
+        };
+        item.add_to(acc);
+    }
+    None
+}
+
+/// If `offset` is in the format spec of a placeholder, i.e. after the `:` of `{name:`,
+/// returns the range of the spec typed so far.
+fn spec_range_at(string: &ast::String, offset: TextSize) -> Option<TextRange> {
+    let string_start = string.syntax().text_range().start();
+    let text = string.text();
+    let before = text.get(..usize::from(offset.checked_sub(string_start)?))?;
+
+    let placeholder_start = before.rfind('{')?;
+    // `{{` is an escaped brace, not a placeholder.
+    let braces = before[..=placeholder_start].chars().rev().take_while(|&c| c == '{').count();
+    if braces % 2 == 0 {
+        return None;
+    }
+    let placeholder = &before[placeholder_start + 1..];
+    if placeholder.contains('}') {
+        return None;
+    }
+    let spec_start = placeholder_start + 1 + placeholder.find(':')? + 1;
+    Some(TextRange::new(string_start + TextSize::from(spec_start as u32), offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::tests::{check_edit, completion_list};
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture);
+        expect.assert_eq(&actual);
+    }
+
+    const FORMAT_ARGS: &str = r#"
+#[rustc_builtin_macro]
+macro_rules! format_args {
+    ($fmt:expr) => ({ /* compiler built-in */ });
+    ($fmt:expr, $($args:tt)*) => ({ /* compiler built-in */ })
+}
+"#;
+
+    #[test]
+    fn completes_specifiers_after_colon() {
+        check(
+            &format!(
+                "{}{}",
+                FORMAT_ARGS,
+                r#"
+fn main() {
+    format_args!("{:$0}", 92);
+}
+"#
+            ),
+            expect![[r##"
+                sn ?         Debug
+                sn #?        Debug, pretty-printed
+                sn x         LowerHex
+                sn X         UpperHex
+                sn #x        LowerHex, with `0x` prefix
+                sn b         Binary
+                sn o         Octal
+                sn e         LowerExp
+                sn E         UpperExp
+                sn width     minimum width, like `{:5}`
+                sn precision precision, like `{:.2}`
+            "##]],
+        );
+    }
+
+    #[test]
+    fn completes_width_as_snippet() {
+        check_edit(
+            "width",
+            &format!(
+                "{}{}",
+                FORMAT_ARGS,
+                r#"
+fn main() {
+    format_args!("{value:$0}", value = 92);
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                FORMAT_ARGS,
+                r#"
+fn main() {
+    format_args!("{value:${1:width}}", value = 92);
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn completes_through_print_macro() {
+        check_edit(
+            "#?",
+            &format!(
+                "{}{}",
+                FORMAT_ARGS,
+                r#"
+macro_rules! print {
+    ($($arg:tt)*) => (format_args!($($arg)*))
+}
+fn main() {
+    print!("{:#$0}", 92);
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                FORMAT_ARGS,
+                r#"
+macro_rules! print {
+    ($($arg:tt)*) => (format_args!($($arg)*))
+}
+fn main() {
+    print!("{:#?}", 92);
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn no_completions_outside_placeholders() {
+        check(
+            &format!(
+                "{}{}",
+                FORMAT_ARGS,
+                r#"
+fn main() {
+    format_args!("{{:$0}} {}", 92);
+}
+"#
+            ),
+            expect![[r#""#]],
+        );
+    }
+}
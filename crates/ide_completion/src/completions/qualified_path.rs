@@ -2,11 +2,15 @@
 
 use std::iter;
 
+use ide_db::SymbolKind;
+use itertools::Itertools;
 use rustc_hash::FxHashSet;
-use syntax::{ast, AstNode};
+use syntax::{ast, AstNode, TextRange};
+use text_edit::TextEdit;
 
 use crate::{
-    context::PathCompletionContext, patterns::ImmediateLocation, CompletionContext, Completions,
+    context::PathCompletionContext, item::CompletionKind, patterns::ImmediateLocation,
+    CompletionContext, CompletionItem, Completions,
 };
 
 pub(crate) fn complete_qualified_path(acc: &mut Completions, ctx: &CompletionContext) {
@@ -130,9 +134,7 @@ pub(crate) fn complete_qualified_path(acc: &mut Completions, ctx: &CompletionCon
             }
         }
         hir::PathResolution::Def(
-            def
-            @
-            (hir::ModuleDef::Adt(_)
+            def @ (hir::ModuleDef::Adt(_)
             | hir::ModuleDef::TypeAlias(_)
             | hir::ModuleDef::BuiltinType(_)),
         ) => {
@@ -163,6 +165,16 @@ pub(crate) fn complete_qualified_path(acc: &mut Completions, ctx: &CompletionCon
             // XXX: For parity with Rust bug #22519, this does not complete Ty::AssocType.
             // (where AssocType is defined on a trait, not an inherent impl)
 
+            // Generic arguments that are already spelled out don't need a turbofish.
+            let turbofish_params = match def {
+                hir::ModuleDef::Adt(adt)
+                    if path.segment().and_then(|it| it.generic_arg_list()).is_none() =>
+                {
+                    hir::GenericDef::from(adt).type_params(ctx.db)
+                }
+                _ => Vec::new(),
+            };
+
             let krate = ctx.krate;
             if let Some(krate) = krate {
                 let traits_in_scope = ctx.scope.traits_in_scope();
@@ -171,6 +183,9 @@ pub(crate) fn complete_qualified_path(acc: &mut Completions, ctx: &CompletionCon
                         return None;
                     }
                     add_assoc_item(acc, ctx, item);
+                    if let hir::AssocItem::Function(func) = item {
+                        add_turbofish_fn(acc, ctx, path, &turbofish_params, func);
+                    }
                     None::<()>
                 });
 
@@ -221,6 +236,24 @@ pub(crate) fn complete_qualified_path(acc: &mut Completions, ctx: &CompletionCon
                     }
                     None::<()>
                 });
+
+                // Inside of a trait, `Self` is only known to implement the trait itself. Its
+                // associated types were already added as shorthand candidates above.
+                if let hir::PathResolution::TypeParam(param) = resolution {
+                    if let hir::GenericDef::Trait(t) = param.parent(ctx.db) {
+                        if param.name(ctx.db) == hir::known::SELF_TYPE {
+                            cov_mark::hit!(completes_trait_items_through_self);
+                            for item in t.items_with_supertraits(ctx.db) {
+                                if matches!(item, hir::AssocItem::TypeAlias(_)) {
+                                    continue;
+                                }
+                                if ctx.is_visible(&item) && seen.insert(item) {
+                                    add_assoc_item(acc, ctx, item);
+                                }
+                            }
+                        }
+                    }
+                }
             }
         }
         _ => {}
@@ -238,6 +271,38 @@ fn add_assoc_item(acc: &mut Completions, ctx: &CompletionContext, item: hir::Ass
     }
 }
 
+/// Offers `Foo::new$0` as `Foo::<${1:T}>::new()`, for calls where the generic arguments of
+/// the type can't be inferred.
+fn add_turbofish_fn(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    qualifier: &ast::Path,
+    params: &[hir::TypeParam],
+    func: hir::Function,
+) -> Option<()> {
+    if params.is_empty() || ctx.expects_type() || ctx.in_use_tree() {
+        return None;
+    }
+    let cap = ctx.config.snippet_cap?;
+
+    let names = params.iter().map(|param| param.name(ctx.db)).collect::<Vec<_>>();
+    let placeholders =
+        names.iter().enumerate().map(|(idx, name)| format!("${{{}:{}}}", idx + 1, name)).join(", ");
+    let fn_name = func.name(ctx.db);
+    let args = if func.assoc_fn_params(ctx.db).is_empty() { "()$0" } else { "($0)" };
+    // Replaces the `::` after the qualifier as well, so that all tab stops are in one edit.
+    let range = TextRange::new(qualifier.syntax().text_range().end(), ctx.source_range().end());
+    let snippet = format!("::<{}>::{}{}", placeholders, fn_name, args);
+
+    let label = format!("::<{}>::{}()", names.iter().join(", "), fn_name);
+    let mut item = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label);
+    item.kind(SymbolKind::Function)
+        .lookup_by(format!("{}::<>", fn_name))
+        .snippet_edit(cap, TextEdit::replace(range, snippet));
+    item.add_to(acc);
+    Some(())
+}
+
 fn add_enum_variants(acc: &mut Completions, ctx: &CompletionContext, e: hir::Enum) {
     if ctx.expects_type() {
         return;
@@ -250,7 +315,7 @@ mod tests {
     use expect_test::{expect, Expect};
 
     use crate::{
-        tests::{check_edit, filtered_completion_list},
+        tests::{check_edit, completion_list, filtered_completion_list},
         CompletionKind,
     };
 
@@ -301,6 +366,25 @@ fn foo() { let _ = U::$0 }
         );
     }
 
+    #[test]
+    fn completes_derive_generated_associated_method() {
+        check(
+            r#"
+//- /main.rs crate:main deps:proc_macros
+#[derive(proc_macros::DeriveGenerated)]
+struct S;
+
+fn foo() { let _ = S::$0 }
+//- /proc_macros.rs crate:proc_macros proc_macro:derive_generated
+#[proc_macro_derive(DeriveGenerated)]
+pub fn derive_generated(item: TokenStream) -> TokenStream { item }
+"#,
+            expect![[r#"
+                fn generated() fn()
+            "#]],
+        );
+    }
+
     #[test]
     fn completes_trait_associated_method_1() {
         check(
@@ -627,6 +711,63 @@ impl Foo {
         );
     }
 
+    #[test]
+    fn completes_assoc_items_through_self_in_inherent_impl() {
+        check(
+            r#"
+trait Trait {
+    type Output;
+    fn trait_method();
+}
+struct S;
+impl S {
+    const CONST: u32 = 0;
+    fn assoc_fn() {}
+    fn method(&self) {
+        Self::$0
+    }
+}
+impl Trait for S {
+    type Output = ();
+    fn trait_method() {}
+}
+"#,
+            expect![[r#"
+                ct CONST                  const CONST: u32 = 0;
+                fn assoc_fn()             fn()
+                me method(…)              fn(&self)
+                fn trait_method() (as Trait) fn()
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_assoc_items_through_self_in_trait() {
+        cov_mark::check!(completes_trait_items_through_self);
+        check(
+            r#"
+trait Super {
+    fn super_fn();
+}
+trait Trait: Super {
+    const CONST: u32;
+    type Output;
+    fn assoc_fn();
+    fn method(&self) {
+        Self::$0
+    }
+}
+"#,
+            expect![[r#"
+                ta Output (as Trait)     type Output;
+                fn super_fn() (as Super) fn()
+                ct CONST (as Trait)      const CONST: u32;
+                fn assoc_fn() (as Trait) fn()
+                me method(…) (as Trait)  fn(&self)
+            "#]],
+        );
+    }
+
     #[test]
     fn completes_primitive_assoc_const() {
         cov_mark::check!(completes_primitive_assoc_const);
@@ -700,4 +841,64 @@ pub mod m {}
             expect![[r#""#]],
         )
     }
+
+    #[test]
+    fn completes_turbofish_for_generic_type() {
+        check_edit(
+            "new::<>",
+            r#"
+struct HashMap<K, V>(K, V);
+impl<K, V> HashMap<K, V> {
+    fn new() -> Self { loop {} }
+}
+
+fn main() { let m = HashMap::ne$0 }
+"#,
+            r#"
+struct HashMap<K, V>(K, V);
+impl<K, V> HashMap<K, V> {
+    fn new() -> Self { loop {} }
+}
+
+fn main() { let m = HashMap::<${1:K}, ${2:V}>::new()$0 }
+"#,
+        );
+        check_edit(
+            "with_capacity::<>",
+            r#"
+struct HashMap<K, V>(K, V);
+impl<K, V> HashMap<K, V> {
+    fn with_capacity(capacity: usize) -> Self { loop {} }
+}
+
+fn main() { let m = HashMap::$0 }
+"#,
+            r#"
+struct HashMap<K, V>(K, V);
+impl<K, V> HashMap<K, V> {
+    fn with_capacity(capacity: usize) -> Self { loop {} }
+}
+
+fn main() { let m = HashMap::<${1:K}, ${2:V}>::with_capacity($0) }
+"#,
+        );
+    }
+
+    #[test]
+    fn no_turbofish_if_generic_args_are_given() {
+        let actual = completion_list(
+            r#"
+struct HashMap<K, V>(K, V);
+impl<K, V> HashMap<K, V> {
+    fn new() -> Self { loop {} }
+}
+
+fn main() { let m = HashMap::<u8, u8>::$0 }
+"#,
+        );
+        expect![[r#"
+            fn new() fn() -> HashMap<K, V>
+        "#]]
+        .assert_eq(&actual);
+    }
 }
@@ -41,7 +41,7 @@ pub(crate) fn complete_unqualified_path(acc: &mut Completions, ctx: &CompletionC
             });
             return;
         }
-        Some(ImmediateLocation::TypeBound) => {
+        Some(ImmediateLocation::TypeBound | ImmediateLocation::ImplHeaderTrait) => {
             ctx.process_all_names(&mut |name, res| {
                 let add_resolution = match res {
                     ScopeDef::MacroDef(mac) => mac.is_fn_like(),
@@ -92,6 +92,10 @@ pub(crate) fn complete_unqualified_path(acc: &mut Completions, ctx: &CompletionC
             ScopeDef::ImplSelfType(_) => {
                 !ctx.previous_token_is(syntax::T![impl]) && !ctx.previous_token_is(syntax::T![for])
             }
+            // A bare trait isn't a type, it needs a `dyn` in front.
+            ScopeDef::ModuleDef(hir::ModuleDef::Trait(_)) => {
+                !matches!(ctx.completion_location, Some(ImmediateLocation::ImplHeaderSelfType))
+            }
             // Don't suggest attribute macros and derives.
             ScopeDef::MacroDef(mac) => mac.is_fn_like(),
             // no values in type places
@@ -304,4 +308,58 @@ pub mod prelude {
             "#]],
         );
     }
+
+    #[test]
+    fn completes_types_in_inherent_impl_head() {
+        check(
+            r#"
+struct Foo;
+enum Bar {}
+trait Tr {}
+fn func() {}
+const C: u32 = 0;
+mod m {}
+impl $0
+"#,
+            expect![[r#"
+                st Foo
+                en Bar
+                md m
+                tt Tr
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completes_types_in_trait_impl_head() {
+        check(
+            r#"
+struct Foo;
+enum Bar {}
+trait Tr {}
+fn func() {}
+const C: u32 = 0;
+mod m {}
+impl Tr for $0
+"#,
+            expect![[r#"
+                st Foo
+                en Bar
+                md m
+            "#]],
+        );
+        check(
+            r#"
+struct Foo;
+enum Bar {}
+trait Tr {}
+mod m {}
+impl $0 for Foo {}
+"#,
+            expect![[r#"
+                md m
+                tt Tr
+            "#]],
+        );
+    }
 }
@@ -15,4 +15,49 @@ pub struct CompletionConfig {
     pub add_call_argument_snippets: bool,
     pub snippet_cap: Option<SnippetCap>,
     pub insert_use: InsertUseConfig,
+    /// Derive helper attributes to complete the arguments of, in addition to the built-in ones.
+    pub helper_attributes: Vec<HelperAttribute>,
+}
+
+/// A derive helper attribute whose arguments are completed, like `#[serde(...)]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HelperAttribute {
+    /// Name of the helper attribute.
+    pub name: String,
+    /// Derives that declare the helper attribute.
+    pub derives: Vec<String>,
+    /// Arguments accepted on the struct, enum or union itself.
+    pub container_args: Vec<HelperArg>,
+    /// Arguments accepted on enum variants.
+    pub variant_args: Vec<HelperArg>,
+    /// Arguments accepted on fields.
+    pub field_args: Vec<HelperArg>,
+}
+
+/// An argument of a [`HelperAttribute`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HelperArg {
+    pub label: String,
+    pub lookup: Option<String>,
+    pub snippet: Option<String>,
+}
+
+impl HelperArg {
+    /// An argument without a value, like `default`.
+    pub fn flag(name: &str) -> HelperArg {
+        HelperArg { label: name.to_string(), lookup: None, snippet: None }
+    }
+
+    /// An argument with a string value, like `rename = "name"`.
+    pub fn with_value(name: &str, placeholder: &str) -> HelperArg {
+        HelperArg {
+            label: format!(r#"{} = "…""#, name),
+            lookup: Some(name.to_string()),
+            snippet: Some(format!(r#"{} = "${{0:{}}}""#, name, placeholder)),
+        }
+    }
+
+    pub(crate) fn key(&self) -> &str {
+        self.lookup.as_deref().unwrap_or(&self.label)
+    }
 }
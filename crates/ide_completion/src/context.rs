@@ -5,12 +5,17 @@ use hir::{Local, Name, ScopeDef, Semantics, SemanticsScope, Type, TypeInfo};
 use ide_db::{
     base_db::{FilePosition, SourceDatabase},
     call_info::ActiveParameter,
+    defs::Definition,
+    helpers::{
+        format_string::{format_trait_of_arg, FormatTrait},
+        FamousDefs,
+    },
     RootDatabase,
 };
 use syntax::{
-    algo::find_node_at_offset,
-    ast::{self, NameOrNameRef, NameOwner},
-    match_ast, AstNode, NodeOrToken,
+    algo::{find_node_at_offset, skip_trivia_token},
+    ast::{self, AstToken, NameOrNameRef, NameOwner},
+    match_ast, AstNode, Direction, NodeOrToken,
     SyntaxKind::{self, *},
     SyntaxNode, SyntaxToken, TextRange, TextSize, T,
 };
@@ -60,6 +65,15 @@ pub(super) struct PatternContext {
     pub(super) is_param: Option<ParamKind>,
 }
 
+/// The cursor is inside a doc comment, completions are computed as if a path was written in
+/// the scope of the documented item.
+#[derive(Debug)]
+pub(super) struct DocCommentContext {
+    pub(super) comment: ast::Comment,
+    /// The item the doc comment belongs to.
+    pub(super) owner: Option<Definition>,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(crate) enum CallKind {
     Pat,
@@ -108,7 +122,10 @@ pub(crate) struct CompletionContext<'a> {
 
     pub(super) pattern_ctx: Option<PatternContext>,
     pub(super) path_context: Option<PathCompletionContext>,
+    pub(super) doc_comment_ctx: Option<DocCommentContext>,
     pub(super) active_parameter: Option<ActiveParameter>,
+    /// The formatting trait used for the argument of `format!`, `write!`, etc. being completed.
+    pub(super) format_trait: Option<hir::Trait>,
     pub(super) locals: Vec<(String, Local)>,
 
     pub(super) incomplete_let: bool,
@@ -172,12 +189,19 @@ impl<'a> CompletionContext<'a> {
             attribute_under_caret: None,
             previous_token: None,
             path_context: None,
+            doc_comment_ctx: None,
             active_parameter: ActiveParameter::at(db, position),
+            format_trait: None,
             locals,
             incomplete_let: false,
             no_completion_required: false,
         };
 
+        if let Some(comment) = ast::Comment::cast(ctx.original_token.clone()) {
+            ctx.fill_doc_comment(comment);
+        }
+        ctx.fill_format_trait();
+
         let mut original_file = original_file.syntax().clone();
         let mut speculative_file = file_with_fake_ident.syntax().clone();
         let mut offset = position.offset;
@@ -449,7 +473,12 @@ impl<'a> CompletionContext<'a> {
     }
 
     fn expected_type_and_name(&self) -> (Option<Type>, Option<NameOrNameRef>) {
-        let mut node = match self.token.parent() {
+        // `let x: T = $0` without a semicolon leaves the cursor outside of the let statement.
+        let let_stmt = previous_token(self.token.clone().into())
+            .filter(|it| self.token.kind().is_trivia() && it.kind() == T![=])
+            .and_then(|it| it.parent())
+            .filter(|it| it.kind() == LET_STMT);
+        let mut node = match let_stmt.or_else(|| self.token.parent()) {
             Some(it) => it,
             None => return (None, None),
         };
@@ -471,14 +500,25 @@ impl<'a> CompletionContext<'a> {
 
                         (ty, name)
                     },
-                    ast::ArgList(_it) => {
+                    ast::ArgList(it) => {
                         cov_mark::hit!(expected_type_fn_param);
+                        // `bar(&s.$0)` has the reference further up than the token before the cursor.
+                        let in_ref_arg = it.args().any(|arg| {
+                            matches!(arg, ast::Expr::RefExpr(_))
+                                && arg.syntax().text_range().contains_inclusive(self.position.offset)
+                        });
                         ActiveParameter::at_token(
                             &self.sema,
                             self.token.clone(),
                         ).map(|ap| {
                             let name = ap.ident().map(NameOrNameRef::Name);
-                            let ty = if has_ref(&self.token) {
+                            if is_receiver(&self.original_token) {
+                                // The parameter's type is that of a method call or field access
+                                // on the completed expression, not of the expression itself.
+                                cov_mark::hit!(expected_type_fn_param_receiver);
+                                return (None, name);
+                            }
+                            let ty = if has_ref(&self.token) || in_ref_arg {
                                 cov_mark::hit!(expected_type_fn_param_ref);
                                 ap.ty.remove_ref()
                             } else {
@@ -568,6 +608,41 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    fn fill_format_trait(&mut self) {
+        let macro_call = match self.original_token.ancestors().find_map(ast::MacroCall::cast) {
+            Some(it) => it,
+            None => return,
+        };
+        let famous_defs = FamousDefs(&self.sema, self.krate);
+        self.format_trait = match format_trait_of_arg(&macro_call, self.position.offset) {
+            Some(FormatTrait::Display) => famous_defs.core_fmt_Display(),
+            Some(FormatTrait::Debug) => famous_defs.core_fmt_Debug(),
+            None => None,
+        };
+    }
+
+    fn fill_doc_comment(&mut self, comment: ast::Comment) {
+        if comment.kind().doc.is_none() {
+            return;
+        }
+        let mut owner_node = match comment.syntax().parent() {
+            Some(it) => it,
+            None => return,
+        };
+        // Inner doc comments of inline modules live in the module's item list.
+        if owner_node.kind() == ITEM_LIST {
+            owner_node = match owner_node.parent() {
+                Some(it) => it,
+                None => return,
+            };
+        }
+        let owner = doc_comment_owner(&self.sema, &owner_node);
+        if owner.is_some() {
+            self.scope = self.sema.scope(&owner_node);
+        }
+        self.doc_comment_ctx = Some(DocCommentContext { comment, owner });
+    }
+
     fn fill(
         &mut self,
         original_file: &SyntaxNode,
@@ -802,6 +877,14 @@ fn path_or_use_tree_qualifier(path: &ast::Path) -> Option<(ast::Path, bool)> {
     use_tree.path().zip(Some(true))
 }
 
+/// Whether the expression ending in `token` is followed by a `.`, making it a receiver.
+fn is_receiver(token: &SyntaxToken) -> bool {
+    token
+        .next_token()
+        .and_then(|it| skip_trivia_token(it, Direction::Next))
+        .map_or(false, |it| it.kind() == T![.])
+}
+
 fn has_ref(token: &SyntaxToken) -> bool {
     let mut token = token.clone();
     for skip in [WHITESPACE, IDENT, T![mut]] {
@@ -815,6 +898,29 @@ fn has_ref(token: &SyntaxToken) -> bool {
     token.kind() == T![&]
 }
 
+fn doc_comment_owner(sema: &Semantics<RootDatabase>, node: &SyntaxNode) -> Option<Definition> {
+    match_ast! {
+        match node {
+            ast::SourceFile(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(def.into())),
+            ast::Module(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(def.into())),
+            ast::Fn(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(def.into())),
+            ast::Struct(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(hir::Adt::from(def).into())),
+            ast::Union(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(hir::Adt::from(def).into())),
+            ast::Enum(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(hir::Adt::from(def).into())),
+            ast::Variant(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(def.into())),
+            ast::Trait(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(def.into())),
+            ast::Static(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(def.into())),
+            ast::Const(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(def.into())),
+            ast::TypeAlias(it) => sema.to_def(&it).map(|def| Definition::ModuleDef(def.into())),
+            ast::Impl(it) => sema.to_def(&it).map(Definition::SelfType),
+            ast::RecordField(it) => sema.to_def(&it).map(Definition::Field),
+            ast::TupleField(it) => sema.to_def(&it).map(Definition::Field),
+            ast::Macro(it) => sema.to_def(&it).map(Definition::Macro),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use expect_test::{expect, Expect};
@@ -824,9 +930,79 @@ mod tests {
 
     use super::CompletionContext;
 
+    fn check_doc_comment_owner(ra_fixture: &str, expect: Expect) {
+        let (db, pos) = position(ra_fixture);
+        let config = TEST_CONFIG;
+        let completion_context = CompletionContext::new(&db, pos, &config).unwrap();
+
+        let actual = match completion_context.doc_comment_ctx {
+            Some(doc_ctx) => match doc_ctx.owner {
+                Some(owner) => format!(
+                    "owner: {}",
+                    owner.name(&db).map_or_else(|| "?".to_owned(), |name| name.to_string())
+                ),
+                None => "owner: ?".to_owned(),
+            },
+            None => "not in doc comment".to_owned(),
+        };
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn doc_comment_owner_outer() {
+        check_doc_comment_owner(
+            r#"
+struct Bar;
+/// See [$0]
+struct Foo { field: Bar }
+"#,
+            expect![[r#"owner: Foo"#]],
+        );
+        check_doc_comment_owner(
+            r#"
+struct Foo {
+    /// The [$0]
+    field: u32,
+}
+"#,
+            expect![[r#"owner: field"#]],
+        );
+    }
+
+    #[test]
+    fn doc_comment_owner_inner() {
+        check_doc_comment_owner(
+            r#"
+mod inner {
+    //! Contains [$0]
+}
+"#,
+            expect![[r#"owner: inner"#]],
+        );
+    }
+
+    #[test]
+    fn not_in_doc_comment() {
+        check_doc_comment_owner(
+            r#"
+// See [$0]
+struct Foo;
+"#,
+            expect![[r#"not in doc comment"#]],
+        );
+        check_doc_comment_owner(
+            r#"
+/// See [Bar]
+struct Foo($0);
+"#,
+            expect![[r#"not in doc comment"#]],
+        );
+    }
+
     fn check_expected_type_and_name(ra_fixture: &str, expect: Expect) {
         let (db, pos) = position(ra_fixture);
-        let completion_context = CompletionContext::new(&db, pos, &TEST_CONFIG).unwrap();
+        let config = TEST_CONFIG;
+        let completion_context = CompletionContext::new(&db, pos, &config).unwrap();
 
         let ty = completion_context
             .expected_type
@@ -853,6 +1029,19 @@ fn foo() {
         );
     }
 
+    #[test]
+    fn expected_type_let_without_semicolon() {
+        check_expected_type_and_name(
+            r#"
+enum MyEnum { A, B }
+fn foo() {
+    let x: MyEnum = $0
+}
+"#,
+            expect![[r#"ty: MyEnum, name: x"#]],
+        );
+    }
+
     #[test]
     fn expected_type_let_with_leading_char() {
         cov_mark::check!(expected_type_let_with_leading_char);
@@ -931,6 +1120,73 @@ fn bar(x: &u32) {}
         );
     }
 
+    #[test]
+    fn expected_type_fn_param_field_access() {
+        check_expected_type_and_name(
+            r#"
+struct S { a: u32 }
+fn foo(s: S) { bar(s.$0); }
+fn bar(x: u32) {}
+"#,
+            expect![[r#"ty: u32, name: x"#]],
+        );
+        check_expected_type_and_name(
+            r#"
+struct S { a: u32 }
+fn foo(s: S) { bar(s.a$0); }
+fn bar(x: u32) {}
+"#,
+            expect![[r#"ty: u32, name: x"#]],
+        );
+        check_expected_type_and_name(
+            r#"
+struct S { a: u32 }
+fn foo(s: S) { bar(&s.$0); }
+fn bar(x: &u32) {}
+"#,
+            expect![[r#"ty: u32, name: x"#]],
+        );
+        check_expected_type_and_name(
+            r#"
+struct S { a: u32 }
+fn foo(s: S) { bar(0, s.$0); }
+fn bar(x: u32, y: &str) {}
+"#,
+            expect![[r#"ty: &str, name: y"#]],
+        );
+        check_expected_type_and_name(
+            r#"
+struct S { a: u32 }
+impl S { fn m(&self, y: u32) {} }
+fn foo(s: S) { s.m(s.$0); }
+"#,
+            expect![[r#"ty: u32, name: y"#]],
+        );
+    }
+
+    #[test]
+    fn expected_type_fn_param_method_receiver() {
+        cov_mark::check!(expected_type_fn_param_receiver);
+        check_expected_type_and_name(
+            r#"
+struct S;
+impl S { fn to_u32(&self) -> u32 { 0 } }
+fn foo(s: S) { bar($0.to_u32()); }
+fn bar(x: u32) {}
+"#,
+            expect![[r#"ty: ?, name: x"#]],
+        );
+        check_expected_type_and_name(
+            r#"
+struct S;
+impl S { fn to_u32(&self) -> u32 { 0 } }
+fn foo(s: S) { bar(s$0.to_u32()); }
+fn bar(x: u32) {}
+"#,
+            expect![[r#"ty: ?, name: x"#]],
+        );
+    }
+
     #[test]
     fn expected_type_struct_field_without_leading_char() {
         cov_mark::check!(expected_type_struct_field_without_leading_char);
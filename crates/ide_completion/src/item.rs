@@ -138,6 +138,14 @@ pub struct CompletionRelevance {
     /// This is set in cases like these:
     ///
     /// ```
+    /// fn f(f: &mut fmt::Formatter, name: String) {
+    ///     write!(f, "{}", $0) // `name` implements `Display`
+    /// }
+    /// ```
+    pub implements_format_trait: bool,
+    /// This is set in cases like these:
+    ///
+    /// ```
     /// fn foo(a: u32) {
     ///     let b = 0;
     ///     $0 // `a` and `b` are local
@@ -153,6 +161,10 @@ pub struct CompletionRelevance {
     /// Basically, we want to guarantee that postfix snippets always takes
     /// precedence over everything else.
     pub exact_postfix_snippet_match: bool,
+    /// This is set for items gated behind a `#[cfg]` that is disabled for the
+    /// crate we are completing in. Such items are still offered, as the user may
+    /// be reading code for a different target, but are ranked below everything else.
+    pub is_cfg_inactive: bool,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -192,6 +204,10 @@ impl CompletionRelevance {
     pub fn score(&self) -> u32 {
         let mut score = 0;
 
+        if !self.is_cfg_inactive {
+            score += 1;
+        }
+
         if self.exact_name_match {
             score += 1;
         }
@@ -203,6 +219,9 @@ impl CompletionRelevance {
         if self.is_local {
             score += 1;
         }
+        if self.implements_format_trait {
+            score += 1;
+        }
         if self.exact_postfix_snippet_match {
             score += 100;
         }
@@ -213,7 +232,7 @@ impl CompletionRelevance {
     /// some threshold such that we think it is especially likely
     /// to be relevant.
     pub fn is_relevant(&self) -> bool {
-        self.score() > 0
+        self.score() > CompletionRelevance::default().score()
     }
 }
 
@@ -367,6 +386,15 @@ impl CompletionItem {
     pub fn import_to_add(&self) -> Option<&ImportEdit> {
         self.import_to_add.as_ref()
     }
+
+    /// Ranks the item last and notes in its detail that it is disabled by a `#[cfg]`.
+    pub(crate) fn mark_cfg_inactive(&mut self) {
+        self.relevance.is_cfg_inactive = true;
+        self.detail = Some(match self.detail.take() {
+            Some(detail) => format!("{} (inactive cfg)", detail),
+            None => "(inactive cfg)".to_string(),
+        });
+    }
 }
 
 /// An extra import to add after the completion is applied.
@@ -585,10 +613,15 @@ mod tests {
         // This test asserts that the relevance score for these items is ascending, and
         // that any items in the same vec have the same score.
         let expected_relevance_order = vec![
+            vec![CompletionRelevance { is_cfg_inactive: true, ..CompletionRelevance::default() }],
             vec![CompletionRelevance::default()],
             vec![
                 CompletionRelevance { exact_name_match: true, ..CompletionRelevance::default() },
                 CompletionRelevance { is_local: true, ..CompletionRelevance::default() },
+                CompletionRelevance {
+                    implements_format_trait: true,
+                    ..CompletionRelevance::default()
+                },
             ],
             vec![CompletionRelevance {
                 exact_name_match: true,
@@ -617,8 +650,10 @@ mod tests {
             vec![CompletionRelevance {
                 exact_name_match: false,
                 type_match: None,
+                implements_format_trait: false,
                 is_local: false,
                 exact_postfix_snippet_match: true,
+                is_cfg_inactive: false,
             }],
         ];
 
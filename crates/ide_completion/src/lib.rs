@@ -24,7 +24,7 @@ use text_edit::TextEdit;
 use crate::{completions::Completions, context::CompletionContext, item::CompletionKind};
 
 pub use crate::{
-    config::CompletionConfig,
+    config::{CompletionConfig, HelperArg, HelperAttribute},
     item::{CompletionItem, CompletionItemKind, CompletionRelevance, ImportEdit},
 };
 
@@ -155,6 +155,8 @@ pub fn completions(
     completions::qualified_path::complete_qualified_path(&mut acc, &ctx);
     completions::unqualified_path::complete_unqualified_path(&mut acc, &ctx);
     completions::dot::complete_dot(&mut acc, &ctx);
+    completions::doc_link::complete_doc_link(&mut acc, &ctx);
+    completions::format_string::complete_format_string(&mut acc, &ctx);
     completions::record::complete_record(&mut acc, &ctx);
     completions::record::complete_record_literal(&mut acc, &ctx);
     completions::pattern::complete_pattern(&mut acc, &ctx);
@@ -192,7 +194,12 @@ pub fn resolve_completion_edits(
     )
     .filter_map(|candidate| {
         current_module
-            .find_use_path_prefixed(db, candidate, config.insert_use.prefix_kind)
+            .find_use_path_prefixed(
+                db,
+                candidate,
+                config.insert_use.prefix_kind,
+                hir::FindPathConfig::default(),
+            )
             .zip(Some(candidate))
     })
     .find(|(mod_path, _)| mod_path.to_string() == full_import_path)?;
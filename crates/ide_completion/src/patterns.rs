@@ -41,6 +41,10 @@ pub(crate) enum ImmediateLocation {
     BlockExpr,
     ItemList,
     TypeBound,
+    /// The trait of a trait impl, `impl $0 for Foo`
+    ImplHeaderTrait,
+    /// The self type of a trait impl, `impl Trait for $0`
+    ImplHeaderSelfType,
     // Fake file ast node
     Attribute(ast::Attr),
     // Fake file ast node
@@ -217,6 +221,12 @@ pub(crate) fn determine_location(
             ast::TupleFieldList(_it) => ImmediateLocation::TupleField,
             ast::TypeBound(_it) => ImmediateLocation::TypeBound,
             ast::TypeBoundList(_it) => ImmediateLocation::TypeBound,
+            // In `impl $0` we can't tell yet whether a trait or the self type is being typed.
+            ast::Impl(it) => match it.for_token() {
+                Some(for_token) if offset <= for_token.text_range().start() => ImmediateLocation::ImplHeaderTrait,
+                Some(_) => ImmediateLocation::ImplHeaderSelfType,
+                None => return None,
+            },
             ast::AssocItemList(it) => match it.syntax().parent().map(|it| it.kind()) {
                 Some(IMPL) => ImmediateLocation::Impl,
                 Some(TRAIT) => ImmediateLocation::Trait,
@@ -403,6 +413,13 @@ mod tests {
         check_location(r"impl A { fn f$0 }", None);
     }
 
+    #[test]
+    fn test_impl_header_loc() {
+        check_location(r"impl A$0 for B {}", ImmediateLocation::ImplHeaderTrait);
+        check_location(r"impl<T> A for B$0 {}", ImmediateLocation::ImplHeaderSelfType);
+        check_location(r"impl A for B$0", ImmediateLocation::ImplHeaderSelfType);
+    }
+
     #[test]
     fn test_use_loc() {
         check_location(r"use f$0", ImmediateLocation::Use);
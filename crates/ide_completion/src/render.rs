@@ -71,6 +71,15 @@ impl<'a> RenderContext<'a> {
                 .unwrap_or(false)
     }
 
+    fn is_cfg_inactive(&self, resolution: &hir::ScopeDef) -> bool {
+        let db = self.db();
+        let cfg = match resolution.attrs(db).and_then(|attrs| attrs.cfg()) {
+            Some(cfg) => cfg,
+            None => return false,
+        };
+        self.completion.krate.map_or(false, |krate| krate.cfg(db).check(&cfg) == Some(false))
+    }
+
     fn docs(&self, node: impl HasAttrs) -> Option<hir::Documentation> {
         node.docs(self.db())
     }
@@ -156,6 +165,20 @@ fn render_resolution_(
     resolution: &hir::ScopeDef,
 ) -> Option<CompletionItem> {
     let _p = profile::span("render_resolution");
+    let is_cfg_inactive = ctx.is_cfg_inactive(resolution);
+    let mut item = render_scope_def(ctx, local_name, import_to_add, resolution)?;
+    if is_cfg_inactive {
+        item.mark_cfg_inactive();
+    }
+    Some(item)
+}
+
+fn render_scope_def(
+    ctx: RenderContext<'_>,
+    local_name: hir::Name,
+    import_to_add: Option<ImportEdit>,
+    resolution: &hir::ScopeDef,
+) -> Option<CompletionItem> {
     use hir::ModuleDef::*;
 
     let completion_kind = match resolution {
@@ -224,6 +247,7 @@ fn render_resolution_(
         item.set_relevance(CompletionRelevance {
             type_match: compute_type_match(ctx.completion, &ty),
             exact_name_match: compute_exact_name_match(ctx.completion, &local_name),
+            implements_format_trait: compute_implements_format_trait(ctx.completion, &ty),
             is_local: true,
             ..CompletionRelevance::default()
         });
@@ -261,15 +285,8 @@ fn render_resolution_(
 }
 
 fn scope_def_docs(db: &RootDatabase, resolution: &hir::ScopeDef) -> Option<hir::Documentation> {
-    use hir::ModuleDef::*;
     match resolution {
-        hir::ScopeDef::ModuleDef(Module(it)) => it.docs(db),
-        hir::ScopeDef::ModuleDef(Adt(it)) => it.docs(db),
-        hir::ScopeDef::ModuleDef(Variant(it)) => it.docs(db),
-        hir::ScopeDef::ModuleDef(Const(it)) => it.docs(db),
-        hir::ScopeDef::ModuleDef(Static(it)) => it.docs(db),
-        hir::ScopeDef::ModuleDef(Trait(it)) => it.docs(db),
-        hir::ScopeDef::ModuleDef(TypeAlias(it)) => it.docs(db),
+        hir::ScopeDef::ModuleDef(it) => it.docs(db),
         _ => None,
     }
 }
@@ -305,6 +322,10 @@ fn compute_type_match(
     }
 }
 
+fn compute_implements_format_trait(ctx: &CompletionContext, completion_ty: &hir::Type) -> bool {
+    ctx.format_trait.map_or(false, |trait_| completion_ty.impls_trait(ctx.db, trait_, &[]))
+}
+
 fn compute_exact_name_match(ctx: &CompletionContext, completion_name: &str) -> bool {
     ctx.expected_name.as_ref().map_or(false, |name| name.text() == completion_name)
 }
@@ -389,8 +410,10 @@ mod tests {
                     "type_could_unify",
                 ),
                 (relevance.exact_name_match, "name"),
+                (relevance.implements_format_trait, "format_trait"),
                 (relevance.is_local, "local"),
                 (relevance.exact_postfix_snippet_match, "snippet"),
+                (relevance.is_cfg_inactive, "cfg_inactive"),
             ]
             .into_iter()
             .filter_map(|(cond, desc)| if cond { Some(desc) } else { None })
@@ -541,8 +564,10 @@ fn main() { let _: m::Spam = S$0 }
                             type_match: Some(
                                 Exact,
                             ),
+                            implements_format_trait: false,
                             is_local: false,
                             exact_postfix_snippet_match: false,
+                            is_cfg_inactive: false,
                         },
                         trigger_call_info: true,
                     },
@@ -570,8 +595,10 @@ fn main() { let _: m::Spam = S$0 }
                             type_match: Some(
                                 Exact,
                             ),
+                            implements_format_trait: false,
                             is_local: false,
                             exact_postfix_snippet_match: false,
+                            is_cfg_inactive: false,
                         },
                     },
                     CompletionItem {
@@ -664,8 +691,10 @@ fn foo() { A { the$0 } }
                             type_match: Some(
                                 CouldUnify,
                             ),
+                            implements_format_trait: false,
                             is_local: false,
                             exact_postfix_snippet_match: false,
+                            is_cfg_inactive: false,
                         },
                     },
                 ]
@@ -934,6 +963,46 @@ fn foo(s: S) { test(s.$0) }
         );
     }
 
+    #[test]
+    fn format_arg_relevance() {
+        check_relevance(
+            r#"
+//- minicore: fmt
+use core::fmt::{self, Debug, Display, Formatter};
+macro_rules! write {
+    ($dst:expr, $($arg:tt)*) => { $dst.write_fmt($($arg)*) };
+}
+struct Name;
+impl Display for Name {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { loop {} }
+}
+struct Id;
+impl Debug for Id {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { loop {} }
+}
+fn f(id: Id, name: Name) {
+    write!(f, "{} ({:?})", $0);
+}
+"#,
+            expect![[r#"
+                lc name [format_trait+local]
+                lc id [local]
+                st Id []
+                tt Display []
+                fn f(…) []
+                tt Debug []
+                st Name []
+                st Formatter []
+                md fmt []
+                ma write!(…) []
+                md core []
+                en Result []
+                ev Err(…) []
+                ev Ok(…) []
+            "#]],
+        );
+    }
+
     #[test]
     fn record_field_relevances() {
         check_relevance(
@@ -1253,6 +1322,94 @@ fn bar(t: &Foo) {}
         );
     }
 
+    #[test]
+    fn enum_expected_in_let() {
+        check_relevance(
+            r#"
+enum MyEnum { A, B }
+fn foo() {
+    let x: MyEnum = $0
+}
+"#,
+            expect![[r#"
+                ev MyEnum::A [type]
+                ev MyEnum::B [type]
+                fn foo() []
+                en MyEnum []
+            "#]],
+        );
+    }
+
+    #[test]
+    fn enum_expected_in_let_glob_imported() {
+        check_relevance(
+            r#"
+use self::MyEnum::*;
+enum MyEnum { A, B }
+fn foo() {
+    let x: MyEnum = $0
+}
+"#,
+            expect![[r#"
+                ev B [type]
+                ev A [type]
+                en MyEnum []
+                fn foo() []
+            "#]],
+        );
+    }
+
+    #[test]
+    fn enum_expected_from_other_module() {
+        check_relevance(
+            r#"
+mod m {
+    pub enum MyEnum { A, B }
+    pub fn bar(e: MyEnum) {}
+}
+fn foo() {
+    m::bar($0);
+}
+"#,
+            expect![[r#"
+                ev m::MyEnum::A [type]
+                ev m::MyEnum::B [type]
+                md m []
+                fn foo() []
+            "#]],
+        );
+    }
+
+    #[test]
+    fn field_access_in_fn_arg() {
+        check_relevance(
+            r#"
+struct S { name: &'static str, len: usize }
+fn greet(name: &str) {}
+fn foo(s: S) { greet(s.$0) }
+"#,
+            expect![[r#"
+                fd name [type+name]
+                fd len []
+            "#]],
+        );
+    }
+
+    #[test]
+    fn field_access_in_ref_fn_arg() {
+        check_relevance(
+            r#"
+struct S { count: u32, flag: bool }
+fn take(count: &u32) {}
+fn foo(s: S) { take(&s.$0) }
+"#,
+            expect![[r#"
+                fd count [type+name]
+                fd flag []
+            "#]],
+        );
+    }
+
     #[test]
     fn suggest_deref_fn_ret() {
         check_relevance(
@@ -1396,4 +1553,25 @@ fn main() {
             "#]],
         );
     }
+
+    #[test]
+    fn cfg_inactive_items_are_ranked_last() {
+        check_relevance(
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() { dep::$0 }
+//- /dep.rs crate:dep cfg:feature=std
+#[cfg(feature = "std")]
+pub fn with_std() {}
+#[cfg(not(feature = "no_std"))]
+pub fn without_no_std() {}
+pub fn always() {}
+"#,
+            expect![[r#"
+                fn always() []
+                fn without_no_std() []
+                fn with_std() [cfg_inactive]
+            "#]],
+        );
+    }
 }
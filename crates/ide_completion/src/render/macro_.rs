@@ -80,7 +80,7 @@ impl<'a> MacroRender<'a> {
     fn label(&self) -> String {
         if self.needs_bang() && self.ctx.snippet_cap().is_some() {
             format!("{}!{}…{}", self.name, self.bra, self.ket)
-        } else if self.macro_.kind() == hir::MacroKind::Derive {
+        } else if self.macro_.is_derive() {
             self.name.to_string()
         } else {
             self.banged_name()
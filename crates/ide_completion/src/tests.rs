@@ -73,13 +73,14 @@ pub(crate) const TEST_CONFIG: CompletionConfig = CompletionConfig {
         group: true,
         skip_glob_imports: true,
     },
+    helper_attributes: Vec::new(),
 };
 
 pub(crate) fn completion_list(code: &str) -> String {
     completion_list_with_config(TEST_CONFIG, code)
 }
 
-fn completion_list_with_config(config: CompletionConfig, code: &str) -> String {
+pub(crate) fn completion_list_with_config(config: CompletionConfig, code: &str) -> String {
     // filter out all but one builtintype completion for smaller test outputs
     let items = get_all_items(config, code);
     let mut bt_seen = false;
@@ -642,6 +642,127 @@ mod derive {
     }
 }
 
+mod helper {
+    use crate::{
+        tests::{completion_list_with_config, TEST_CONFIG},
+        CompletionConfig, HelperArg, HelperAttribute,
+    };
+
+    use super::*;
+
+    fn check_helper(ra_fixture: &str, expect: Expect) {
+        let actual = completion_list(ra_fixture);
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn not_derived() {
+        check_helper(r#"#[serde($0)] struct Test;"#, expect![[]])
+    }
+
+    #[test]
+    fn serde_container() {
+        check_helper(
+            r#"
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase", $0)]
+struct Test;
+"#,
+            expect![[r#"
+                at bound = "…"
+                at content = "…"
+                at default
+                at deny_unknown_fields
+                at from = "…"
+                at into = "…"
+                at rename = "…"
+                at tag = "…"
+                at transparent
+                at try_from = "…"
+                at untagged
+            "#]],
+        );
+    }
+
+    #[test]
+    fn serde_field() {
+        check_helper(
+            r#"
+#[derive(Debug, serde::Deserialize)]
+struct Test {
+    #[serde(default, skip, $0)]
+    field: u32,
+}
+"#,
+            expect![[r#"
+                at alias = "…"
+                at bound = "…"
+                at deserialize_with = "…"
+                at flatten
+                at rename = "…"
+                at serialize_with = "…"
+                at skip_deserializing
+                at skip_serializing
+                at skip_serializing_if = "…"
+                at with = "…"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn serde_variant() {
+        check_helper(
+            r#"
+#[derive(Deserialize)]
+enum Test {
+    #[serde(rename = "a", $0)]
+    A,
+}
+"#,
+            expect![[r#"
+                at alias = "…"
+                at bound = "…"
+                at deserialize_with = "…"
+                at other
+                at rename_all = "…"
+                at serialize_with = "…"
+                at skip
+                at skip_deserializing
+                at skip_serializing
+                at with = "…"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn registered_helper() {
+        let config = CompletionConfig {
+            helper_attributes: vec![HelperAttribute {
+                name: "builder".to_string(),
+                derives: vec!["Builder".to_string()],
+                container_args: vec![HelperArg::with_value("name", "FooBuilder")],
+                variant_args: Vec::new(),
+                field_args: vec![HelperArg::flag("default")],
+            }],
+            ..TEST_CONFIG
+        };
+        let actual = completion_list_with_config(
+            config,
+            r#"
+#[derive(Builder)]
+struct Test {
+    #[builder($0)]
+    field: u32,
+}
+"#,
+        );
+        expect![[r#"
+            at default
+        "#]]
+        .assert_eq(&actual);
+    }
+}
+
 mod lint {
     use super::*;
 
@@ -666,10 +787,34 @@ mod lint {
     #[test]
     fn lint_qualified() {
         check_edit(
-            "deprecated",
+            "clippy::as_conversions",
             r#"#[allow(keyword_idents, $0)] struct Test;"#,
-            r#"#[allow(keyword_idents, deprecated)] struct Test;"#,
-        )
+            r#"#[allow(keyword_idents, clippy::as_conversions)] struct Test;"#,
+        );
+        check_edit(
+            "as_conversions",
+            r#"#[forbid(clippy::$0)] struct Test;"#,
+            r#"#[forbid(clippy::as_conversions)] struct Test;"#,
+        );
+        check_edit(
+            "as_conversions",
+            r#"#[warn(clippy::as_$0)] struct Test;"#,
+            r#"#[warn(clippy::as_conversions)] struct Test;"#,
+        );
+        check_edit(
+            "broken_intra_doc_links",
+            r#"#[deny(rustdoc::$0)] struct Test;"#,
+            r#"#[deny(rustdoc::broken_intra_doc_links)] struct Test;"#,
+        );
+    }
+
+    #[test]
+    fn lint_qualified_only_offers_tool_lints() {
+        let completions = completion_list(r#"#[forbid(clippy::$0)] struct Test;"#);
+        let labels: Vec<_> = completions.lines().map(|it| it.trim()).collect();
+        assert!(labels.contains(&"at as_conversions"));
+        assert!(!labels.contains(&"at deprecated"));
+        assert!(!labels.contains(&"at broken_intra_doc_links"));
     }
 
     #[test]
@@ -680,6 +825,16 @@ mod lint {
             r#"#[feature(box_syntax)] struct Test;"#,
         )
     }
+
+    #[test]
+    fn lint_feature_inner_attribute() {
+        check_edit("box_syntax", r#"#![feature($0)]"#, r#"#![feature(box_syntax)]"#);
+        check_edit(
+            "box_patterns",
+            r#"#![feature(box_syntax, box_p$0)]"#,
+            r#"#![feature(box_syntax, box_patterns)]"#,
+        );
+    }
 }
 
 mod repr {
@@ -44,7 +44,6 @@ impl Trait for Str$0
             kw self
             kw super
             kw crate
-            tt Trait
             en Enum
             st Record
             st Tuple
@@ -11,6 +11,14 @@ use rustc_hash::FxHashSet;
 
 use crate::{symbol_index::SymbolsDatabase, RootDatabase};
 
+/// Memory freed by discarding the results of a single query.
+pub struct QueryMemoryUsage {
+    /// The query group (database trait) the query belongs to.
+    pub group: &'static str,
+    pub name: String,
+    pub bytes: Bytes,
+}
+
 impl RootDatabase {
     pub fn request_cancellation(&mut self) {
         let _p = profile::span("RootDatabase::request_cancellation");
@@ -64,6 +72,25 @@ impl RootDatabase {
         hir::db::BodyQuery.in_db(self).sweep(sweep);
     }
 
+    /// Drops the memoized results of the largest derived queries, which will
+    /// be recomputed on demand. Unlike [`RootDatabase::per_query_memory_usage`],
+    /// this leaves inputs and interned data alone.
+    pub fn clear_memory(&mut self) {
+        let _p = profile::span("RootDatabase::clear_memory");
+        self.request_cancellation();
+
+        base_db::ParseQuery.in_db(self).purge();
+        hir::db::ParseMacroExpansionQuery.in_db(self).purge();
+        hir::db::AstIdMapQuery.in_db(self).purge();
+        hir::db::BodyWithSourceMapQuery.in_db(self).purge();
+        hir::db::BodyQuery.in_db(self).purge();
+        hir::db::ExprScopesQuery.in_db(self).purge();
+        hir::db::InferQueryQuery.in_db(self).purge();
+        hir::db::TraitSolveQueryQuery.in_db(self).purge();
+        crate::symbol_index::FileSymbolsQuery.in_db(self).purge();
+        crate::symbol_index::LibrarySymbolsQuery.in_db(self).purge();
+    }
+
     // Feature: Memory Usage
     //
     // Clears rust-analyzer's internal database and prints memory usage statistics.
@@ -75,52 +102,67 @@ impl RootDatabase {
     // |===
     // image::https://user-images.githubusercontent.com/48062697/113065592-08559f00-91b1-11eb-8c96-64b88068ec02.gif[]
     pub fn per_query_memory_usage(&mut self) -> Vec<(String, Bytes)> {
-        let mut acc: Vec<(String, Bytes)> = vec![];
+        let mut acc: Vec<(String, Bytes)> =
+            self.query_memory_usage().into_iter().map(|it| (it.name, it.bytes)).collect();
+        // Ties are broken by name, so that the report is stable between runs.
+        acc.sort_by(|(lhs_name, lhs), (rhs_name, rhs)| {
+            rhs.cmp(lhs).then_with(|| lhs_name.cmp(rhs_name))
+        });
+        acc
+    }
+
+    /// Like [`RootDatabase::per_query_memory_usage`], but also records the
+    /// query group of each query. NB: this clears the database.
+    pub fn query_memory_usage(&mut self) -> Vec<QueryMemoryUsage> {
+        let mut acc: Vec<QueryMemoryUsage> = vec![];
         let sweep = SweepStrategy::default().discard_values().sweep_all_revisions();
         macro_rules! sweep_each_query {
-            ($($q:path)*) => {$(
+            ($($group:ident: $($q:path)*;)*) => {$($(
                 let before = memory_usage().allocated;
                 $q.in_db(self).sweep(sweep);
                 let after = memory_usage().allocated;
                 let q: $q = Default::default();
                 let name = format!("{:?}", q);
-                acc.push((name, before - after));
+                let group = stringify!($group);
+                acc.push(QueryMemoryUsage { group, name, bytes: before - after });
 
                 let before = memory_usage().allocated;
                 $q.in_db(self).sweep(sweep.discard_everything());
                 let after = memory_usage().allocated;
                 let q: $q = Default::default();
                 let name = format!("{:?} (deps)", q);
-                acc.push((name, before - after));
+                let group = stringify!($group);
+                acc.push(QueryMemoryUsage { group, name, bytes: before - after });
 
                 let before = memory_usage().allocated;
                 $q.in_db(self).purge();
                 let after = memory_usage().allocated;
                 let q: $q = Default::default();
                 let name = format!("{:?} (purge)", q);
-                acc.push((name, before - after));
-            )*}
+                let group = stringify!($group);
+                acc.push(QueryMemoryUsage { group, name, bytes: before - after });
+            )*)*}
         }
         sweep_each_query![
-            // SourceDatabase
+            SourceDatabase:
             base_db::ParseQuery
-            base_db::CrateGraphQuery
+            base_db::CrateGraphQuery;
 
-            // SourceDatabaseExt
+            SourceDatabaseExt:
             base_db::FileTextQuery
             base_db::FileSourceRootQuery
             base_db::SourceRootQuery
-            base_db::SourceRootCratesQuery
+            base_db::SourceRootCratesQuery;
 
-            // AstDatabase
+            AstDatabase:
             hir::db::AstIdMapQuery
             hir::db::MacroArgTextQuery
             hir::db::MacroDefQuery
             hir::db::ParseMacroExpansionQuery
             hir::db::MacroExpandQuery
-            hir::db::HygieneFrameQuery
+            hir::db::HygieneFrameQuery;
 
-            // DefDatabase
+            DefDatabase:
             hir::db::FileItemTreeQuery
             hir::db::BlockDefMapQuery
             hir::db::CrateDefMapQueryQuery
@@ -144,12 +186,15 @@ impl RootDatabase {
             hir::db::AttrsQuery
             hir::db::CrateLangItemsQuery
             hir::db::LangItemQuery
-            hir::db::ImportMapQuery
+            hir::db::ImportMapQuery;
 
-            // HirDatabase
+            HirDatabase:
             hir::db::InferQueryQuery
             hir::db::TyQuery
             hir::db::ValueTyQuery
+            hir::db::ConstEvalQuery
+            hir::db::ConstEvalDiscriminantQuery
+            hir::db::LayoutOfAdtQuery
             hir::db::ImplSelfTyQuery
             hir::db::ImplTraitQuery
             hir::db::FieldTypesQuery
@@ -161,6 +206,9 @@ impl RootDatabase {
             hir::db::TraitEnvironmentQuery
             hir::db::TraitImplsInCrateQuery
             hir::db::TraitImplsInDepsQuery
+            hir::db::TraitImplsInRevDepsQuery
+            hir::db::TraitImplsForSelfTyInDepsQuery
+            hir::db::ObjectSafetyViolationsQuery
             hir::db::AssociatedTyDataQuery
             hir::db::AssociatedTyDataQuery
             hir::db::TraitDatumQuery
@@ -173,16 +221,16 @@ impl RootDatabase {
             hir::db::InternImplTraitIdQuery
             hir::db::InternClosureQuery
             hir::db::AssociatedTyValueQuery
-            hir::db::TraitSolveQueryQuery
+            hir::db::TraitSolveQueryQuery;
 
-            // SymbolsDatabase
+            SymbolsDatabase:
             crate::symbol_index::FileSymbolsQuery
             crate::symbol_index::LibrarySymbolsQuery
             crate::symbol_index::LocalRootsQuery
-            crate::symbol_index::LibraryRootsQuery
+            crate::symbol_index::LibraryRootsQuery;
 
-            // LineIndexDatabase
-            crate::LineIndexQuery
+            LineIndexDatabase:
+            crate::LineIndexQuery;
         ];
 
         // To collect interned data, we need to bump the revision counter by performing a synthetic
@@ -192,10 +240,10 @@ impl RootDatabase {
         self.salsa_runtime_mut().synthetic_write(Durability::HIGH);
 
         sweep_each_query![
-            // AstDatabase
-            hir::db::InternMacroQuery
+            AstDatabase:
+            hir::db::InternMacroQuery;
 
-            // InternDatabase
+            InternDatabase:
             hir::db::InternFunctionQuery
             hir::db::InternStructQuery
             hir::db::InternUnionQuery
@@ -204,13 +252,12 @@ impl RootDatabase {
             hir::db::InternStaticQuery
             hir::db::InternTraitQuery
             hir::db::InternTypeAliasQuery
-            hir::db::InternImplQuery
+            hir::db::InternImplQuery;
 
-            // HirDatabase
-            hir::db::InternTypeParamIdQuery
+            HirDatabase:
+            hir::db::InternTypeParamIdQuery;
         ];
 
-        acc.sort_by_key(|it| std::cmp::Reverse(it.1));
         acc
     }
 }
@@ -2,6 +2,7 @@
 use base_db::FilePosition;
 use either::Either;
 use hir::{HasAttrs, HirDisplay, Semantics, Type};
+use itertools::Itertools;
 use stdx::format_to;
 use syntax::{
     algo,
@@ -29,7 +30,9 @@ impl CallInfo {
         &self.parameters
     }
     fn push_param(&mut self, param: &str) {
-        if !self.signature.ends_with('(') {
+        if self.signature.ends_with('{') {
+            self.signature.push(' ');
+        } else if !self.signature.ends_with('(') && !self.signature.ends_with('<') {
             self.signature.push_str(", ");
         }
         let start = TextSize::of(&self.signature);
@@ -52,6 +55,19 @@ pub fn call_info(db: &RootDatabase, position: FilePosition) -> Option<CallInfo>
         .and_then(|tok| algo::skip_trivia_token(tok, Direction::Prev))?;
     let token = sema.descend_into_macros(token);
 
+    // Use the innermost construct the cursor is in, so that `foo(S { $0 })` describes `S`.
+    for node in token.parent()?.ancestors() {
+        match_ast! {
+            match node {
+                ast::ArgList(_it) => break,
+                ast::GenericArgList(it) => return call_info_for_generic_args(&sema, &token, it),
+                ast::RecordExprFieldList(it) => return call_info_for_record_expr(&sema, &token, it),
+                ast::TupleStructPat(it) => return call_info_for_tuple_struct_pat(&sema, &token, it),
+                _ => (),
+            }
+        }
+    }
+
     let (callable, active_parameter) = call_info_impl(&sema, token)?;
 
     let mut res =
@@ -110,6 +126,225 @@ pub fn call_info(db: &RootDatabase, position: FilePosition) -> Option<CallInfo>
     Some(res)
 }
 
+fn call_info_for_generic_args(
+    sema: &Semantics<RootDatabase>,
+    token: &SyntaxToken,
+    arg_list: ast::GenericArgList,
+) -> Option<CallInfo> {
+    let db = sema.db;
+    let parent = arg_list.syntax().parent()?;
+    let generic_def: hir::GenericDef = match_ast! {
+        match parent {
+            ast::PathSegment(segment) => match sema.resolve_path(&segment.parent_path())? {
+                hir::PathResolution::Def(hir::ModuleDef::Adt(it)) => it.into(),
+                hir::PathResolution::Def(hir::ModuleDef::Function(it)) => it.into(),
+                hir::PathResolution::Def(hir::ModuleDef::Trait(it)) => it.into(),
+                hir::PathResolution::Def(hir::ModuleDef::TypeAlias(it)) => it.into(),
+                hir::PathResolution::Def(hir::ModuleDef::Variant(it)) => {
+                    it.parent_enum(db).into()
+                }
+                _ => return None,
+            },
+            ast::MethodCallExpr(call) => sema.resolve_method_call(&call)?.into(),
+            _ => return None,
+        }
+    };
+
+    let mut res = CallInfo {
+        doc: None,
+        signature: String::new(),
+        parameters: vec![],
+        active_parameter: None,
+    };
+    let name = match generic_def {
+        hir::GenericDef::Function(it) => {
+            res.doc = it.docs(db).map(Into::into);
+            format!("fn {}", it.name(db))
+        }
+        hir::GenericDef::Adt(hir::Adt::Struct(it)) => {
+            res.doc = it.docs(db).map(Into::into);
+            format!("struct {}", it.name(db))
+        }
+        hir::GenericDef::Adt(hir::Adt::Enum(it)) => {
+            res.doc = it.docs(db).map(Into::into);
+            format!("enum {}", it.name(db))
+        }
+        hir::GenericDef::Adt(hir::Adt::Union(it)) => {
+            res.doc = it.docs(db).map(Into::into);
+            format!("union {}", it.name(db))
+        }
+        hir::GenericDef::Trait(it) => {
+            res.doc = it.docs(db).map(Into::into);
+            format!("trait {}", it.name(db))
+        }
+        hir::GenericDef::TypeAlias(it) => {
+            res.doc = it.docs(db).map(Into::into);
+            format!("type {}", it.name(db))
+        }
+        _ => return None,
+    };
+    res.signature.push_str(&name);
+    res.signature.push('<');
+
+    // Lifetimes go first, like they have to be written.
+    let mut params = generic_def.params(db);
+    params.sort_by_key(|param| !matches!(param, hir::GenericParam::LifetimeParam(_)));
+    let mut num_lifetimes = 0;
+    let mut buf = String::new();
+    for param in params {
+        buf.clear();
+        match param {
+            hir::GenericParam::LifetimeParam(it) => {
+                num_lifetimes += 1;
+                format_to!(buf, "{}", it.name(db));
+            }
+            hir::GenericParam::TypeParam(it) => {
+                let name = it.name(db);
+                // Skip the implicit `Self` of traits and `impl Trait` parameters.
+                if name == hir::known::SELF_TYPE || name == hir::Name::missing() {
+                    continue;
+                }
+                format_to!(buf, "{}", name);
+                let bounds = it.trait_bounds(db);
+                if !bounds.is_empty() {
+                    format_to!(buf, ": {}", bounds.iter().map(|it| it.name(db)).format(" + "));
+                }
+                if let Some(default) = it.default(db).filter(|it| !it.is_unknown()) {
+                    format_to!(buf, " = {}", default.display(db));
+                }
+            }
+            hir::GenericParam::ConstParam(it) => {
+                format_to!(buf, "const {}: {}", it.name(db), it.ty(db).display(db));
+            }
+        }
+        res.push_param(&buf);
+    }
+    res.signature.push('>');
+
+    let args_before = arg_list
+        .generic_args()
+        .take_while(|arg| arg.syntax().text_range().end() <= token.text_range().start())
+        .count();
+    // Lifetime arguments are usually elided, in which case they don't shift the other arguments.
+    let has_lifetime_args =
+        arg_list.generic_args().any(|arg| matches!(arg, ast::GenericArg::LifetimeArg(_)));
+    let active = if has_lifetime_args { args_before } else { num_lifetimes + args_before };
+    res.active_parameter = Some(active).filter(|&it| it < res.parameters.len());
+    Some(res)
+}
+
+fn call_info_for_record_expr(
+    sema: &Semantics<RootDatabase>,
+    token: &SyntaxToken,
+    field_list: ast::RecordExprFieldList,
+) -> Option<CallInfo> {
+    let db = sema.db;
+    let record_expr = ast::RecordExpr::cast(field_list.syntax().parent()?)?;
+    let variant = resolve_variant(sema, &record_expr.path()?)?;
+
+    let current_field = token
+        .parent()?
+        .ancestors()
+        .take_while(|it| it != field_list.syntax())
+        .find_map(ast::RecordExprField::cast)
+        .and_then(|it| it.field_name());
+    let written_fields = field_list
+        .fields()
+        .filter_map(|it| it.field_name())
+        .filter(|it| Some(it) != current_field.as_ref())
+        .map(|it| it.to_string())
+        .collect::<Vec<_>>();
+
+    let mut res = CallInfo {
+        doc: None,
+        signature: String::new(),
+        parameters: vec![],
+        active_parameter: None,
+    };
+    push_variant_name(db, &mut res, variant);
+    res.signature.push_str(" {");
+    for field in variant.fields(db) {
+        let name = field.name(db).to_string();
+        if written_fields.contains(&name) {
+            continue;
+        }
+        if current_field.as_ref().map_or(false, |it| it.text() == name.as_str()) {
+            res.active_parameter = Some(res.parameters.len());
+        }
+        res.push_param(&format!("{}: {}", name, field.ty(db).display(db)));
+    }
+    if res.parameters.is_empty() {
+        return None;
+    }
+    res.signature.push_str(" }");
+    Some(res)
+}
+
+fn call_info_for_tuple_struct_pat(
+    sema: &Semantics<RootDatabase>,
+    token: &SyntaxToken,
+    pat: ast::TupleStructPat,
+) -> Option<CallInfo> {
+    let db = sema.db;
+    let l_paren = pat.l_paren_token()?;
+    if token.text_range().start() < l_paren.text_range().start() {
+        return None;
+    }
+    let variant = resolve_variant(sema, &pat.path()?)?;
+
+    let mut res = CallInfo {
+        doc: None,
+        signature: String::new(),
+        parameters: vec![],
+        active_parameter: None,
+    };
+    push_variant_name(db, &mut res, variant);
+    res.signature.push('(');
+    for field in variant.fields(db) {
+        res.push_param(&field.ty(db).display(db).to_string());
+    }
+    res.signature.push(')');
+
+    let active = pat
+        .fields()
+        .take_while(|it| it.syntax().text_range().end() <= token.text_range().start())
+        .count();
+    res.active_parameter = Some(active).filter(|&it| it < res.parameters.len());
+    Some(res)
+}
+
+fn resolve_variant(sema: &Semantics<RootDatabase>, path: &ast::Path) -> Option<hir::VariantDef> {
+    let variant = match sema.resolve_path(path)? {
+        hir::PathResolution::Def(hir::ModuleDef::Adt(hir::Adt::Struct(it))) => it.into(),
+        hir::PathResolution::Def(hir::ModuleDef::Adt(hir::Adt::Union(it))) => it.into(),
+        hir::PathResolution::Def(hir::ModuleDef::Variant(it)) => it.into(),
+        hir::PathResolution::SelfType(it) => match it.self_ty(sema.db).as_adt()? {
+            hir::Adt::Struct(it) => it.into(),
+            hir::Adt::Union(it) => it.into(),
+            hir::Adt::Enum(_) => return None,
+        },
+        _ => return None,
+    };
+    Some(variant)
+}
+
+fn push_variant_name(db: &RootDatabase, res: &mut CallInfo, variant: hir::VariantDef) {
+    match variant {
+        hir::VariantDef::Struct(it) => {
+            res.doc = it.docs(db).map(Into::into);
+            format_to!(res.signature, "struct {}", it.name(db));
+        }
+        hir::VariantDef::Union(it) => {
+            res.doc = it.docs(db).map(Into::into);
+            format_to!(res.signature, "union {}", it.name(db));
+        }
+        hir::VariantDef::Variant(it) => {
+            res.doc = it.docs(db).map(Into::into);
+            format_to!(res.signature, "enum {}::{}", it.parent_enum(db).name(db), it.name(db));
+        }
+    }
+}
+
 fn call_info_impl(
     sema: &Semantics<RootDatabase>,
     token: SyntaxToken,
@@ -563,3 +563,193 @@ fn main() {
         "#]],
     )
 }
+
+#[test]
+fn call_info_for_tuple_struct_pat() {
+    check(
+        r#"
+/// A cool tuple struct
+struct S(u32, i32);
+fn main(s: S) {
+    let S(a, $0) = s;
+}
+"#,
+        expect![[r#"
+            A cool tuple struct
+            ------
+            struct S(u32, i32)
+            (u32, <i32>)
+        "#]],
+    );
+    check(
+        r#"
+enum E { A(u8, (u8, u8)) }
+fn main(e: E) {
+    match e {
+        E::A($0) => {}
+    }
+}
+"#,
+        expect![[r#"
+            enum E::A(u8, (u8, u8))
+            (<u8>, (u8, u8))
+        "#]],
+    );
+}
+
+#[test]
+fn call_info_for_record_expr() {
+    check(
+        r#"
+/// A record
+struct S { x: u32, y: i32, z: bool }
+fn main() {
+    let s = S { y: 1, $0 };
+}
+"#,
+        expect![[r#"
+            A record
+            ------
+            struct S { x: u32, z: bool }
+            (x: u32, z: bool)
+        "#]],
+    );
+    check(
+        r#"
+struct S { x: u32, y: i32, z: bool }
+fn main() {
+    let s = S { x: 0, z: $0 };
+}
+"#,
+        expect![[r#"
+            struct S { y: i32, z: bool }
+            (y: i32, <z: bool>)
+        "#]],
+    );
+    check(
+        r#"
+enum E { C { a: i32, b: i32 } }
+fn foo(e: E) {}
+fn main() {
+    foo(E::C { a: 1, $0 });
+}
+"#,
+        expect![[r#"
+            enum E::C { b: i32 }
+            (b: i32)
+        "#]],
+    );
+    check(
+        r#"
+struct S { x: u32 }
+fn main() {
+    let s = S { x: 0, $0 };
+}
+"#,
+        expect![[""]],
+    );
+}
+
+#[test]
+fn call_info_for_generic_args() {
+    check(
+        r#"
+trait Hash {}
+struct RandomState;
+/// A map
+struct HashMap<K: Hash, V, S = RandomState>(K, V, S);
+fn main() {
+    let m: HashMap<u32, $0>;
+}
+"#,
+        expect![[r#"
+            A map
+            ------
+            struct HashMap<K: Hash, V, S = RandomState>
+            (K: Hash, <V>, S = RandomState)
+        "#]],
+    );
+    check(
+        r#"
+struct Ref<'a, T, const N: usize>(&'a T);
+fn main() {
+    let r: Ref<$0>;
+}
+"#,
+        expect![[r#"
+            struct Ref<'a, T, const N: usize>
+            ('a, <T>, const N: usize)
+        "#]],
+    );
+    check(
+        r#"
+struct Ref<'a, T, const N: usize>(&'a T);
+fn main() {
+    let r: Ref<'static, u8, $0>;
+}
+"#,
+        expect![[r#"
+            struct Ref<'a, T, const N: usize>
+            ('a, T, <const N: usize>)
+        "#]],
+    );
+}
+
+#[test]
+fn call_info_for_nested_generic_args() {
+    check(
+        r#"
+struct Pair<A, B>(A, B);
+struct Vec<T>(T);
+fn main() {
+    let p: Pair<Vec<u8>, $0>;
+}
+"#,
+        expect![[r#"
+            struct Pair<A, B>
+            (A, <B>)
+        "#]],
+    );
+    check(
+        r#"
+struct Pair<A, B>(A, B);
+struct Vec<T>(T);
+fn main() {
+    let p: Pair<u8, Vec<$0>>;
+}
+"#,
+        expect![[r#"
+            struct Vec<T>
+            (<T>)
+        "#]],
+    );
+}
+
+#[test]
+fn call_info_for_turbofish() {
+    check(
+        r#"
+fn make<T, U>() {}
+fn main() {
+    make::<u8, $0>();
+}
+"#,
+        expect![[r#"
+            fn make<T, U>
+            (T, <U>)
+        "#]],
+    );
+    check(
+        r#"
+struct S;
+impl S { fn collect<B>(self) -> B { loop {} } }
+fn main() {
+    S.collect::<$0>();
+}
+"#,
+        expect![[r#"
+            fn collect<B>
+            (<B>)
+        "#]],
+    );
+}
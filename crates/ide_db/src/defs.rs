@@ -17,7 +17,7 @@ use syntax::{
 use crate::RootDatabase;
 
 // FIXME: a more precise name would probably be `Symbol`?
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
 pub enum Definition {
     Macro(MacroDef),
     Field(Field),
@@ -372,7 +372,7 @@ impl NameRefClass {
                             cov_mark::hit!(name_ref_classify_attr_path_qualifier);
                             Some(NameRefClass::Definition(Definition::ModuleDef(module)))
                         }
-                        PathResolution::Macro(mac) if mac.kind() == hir::MacroKind::Attr => {
+                        PathResolution::Macro(mac) if mac.is_attr() => {
                             Some(NameRefClass::Definition(Definition::Macro(mac)))
                         }
                         _ => None,
@@ -1,4 +1,5 @@
 //! A module with ide helpers for high-level ide features.
+pub mod format_string;
 pub mod import_assets;
 pub mod insert_use;
 pub mod merge_imports;
@@ -50,9 +51,7 @@ pub fn try_resolve_derive_input_at(
         .collect();
     let path = ast::Path::parse(&tokens.into_iter().rev().join("")).ok()?;
     match sema.scope(tt.syntax()).speculative_resolve(&path) {
-        Some(hir::PathResolution::Macro(makro)) if makro.kind() == hir::MacroKind::Derive => {
-            Some(makro)
-        }
+        Some(hir::PathResolution::Macro(makro)) if makro.is_derive() => Some(makro),
         _ => None,
     }
 }
@@ -178,6 +177,14 @@ impl FamousDefs<'_, '_> {
         self.find_trait("core:ops:Deref")
     }
 
+    pub fn core_fmt_Debug(&self) -> Option<Trait> {
+        self.find_trait("core:fmt:Debug")
+    }
+
+    pub fn core_fmt_Display(&self) -> Option<Trait> {
+        self.find_trait("core:fmt:Display")
+    }
+
     fn find_trait(&self, path: &str) -> Option<Trait> {
         match self.find_def(path)? {
             hir::ScopeDef::ModuleDef(hir::ModuleDef::Trait(it)) => Some(it),
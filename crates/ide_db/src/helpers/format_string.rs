@@ -0,0 +1,186 @@
+//! Tools to work with format string literals for the `format_args!` family of macros.
+use syntax::{
+    ast::{self, FormatSpecifier, HasFormatSpecifier},
+    AstNode, AstToken, SyntaxElement,
+    SyntaxKind::IDENT,
+    TextRange, TextSize, T,
+};
+
+/// Returns `true` if `string` is the format string of a `format_args!` or `format_args_nl!` call.
+pub fn is_format_string(string: &ast::String) -> bool {
+    format_macro_call_of(string).is_some()
+}
+
+fn format_macro_call_of(string: &ast::String) -> Option<ast::MacroCall> {
+    let parent = string.syntax().parent()?;
+
+    let macro_call = parent.parent().and_then(ast::MacroCall::cast)?;
+    let name = macro_call.path()?.segment()?.name_ref()?;
+    if !matches!(name.text().as_str(), "format_args" | "format_args_nl") {
+        return None;
+    }
+
+    let first_literal = parent
+        .children_with_tokens()
+        .filter_map(|it| it.as_token().cloned().and_then(ast::String::cast))
+        .next()?;
+    if &first_literal != string {
+        return None;
+    }
+
+    Some(macro_call)
+}
+
+/// Returns the names of the arguments referenced by `string`, like `name` in `{name}` or
+/// `width` in `{:width$}`, together with their ranges relative to the start of the token.
+/// Escaped braces (`{{`) are skipped.
+pub fn format_arg_names(string: &ast::String) -> Vec<(TextRange, String)> {
+    let text = string.text();
+    let mut names = Vec::new();
+    string.lex_format_specifier(|range, kind| {
+        if let FormatSpecifier::Identifier = kind {
+            names.push((range, text[range].to_string()));
+        }
+    });
+    names
+}
+
+/// The formatting trait a placeholder of a format string uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatTrait {
+    /// `{}`
+    Display,
+    /// `{:?}`
+    Debug,
+}
+
+/// If `offset` is inside an argument of a call to `format!`, `write!` or a similar macro,
+/// returns the trait the placeholders referring to that argument format it with.
+///
+/// This works on the syntax of the call only, as the expansion of these macros loses the
+/// connection between the arguments and the format string.
+pub fn format_trait_of_arg(macro_call: &ast::MacroCall, offset: TextSize) -> Option<FormatTrait> {
+    let name = macro_call.path()?.segment()?.name_ref()?;
+    if !matches!(
+        name.text().as_str(),
+        "format"
+            | "format_args"
+            | "format_args_nl"
+            | "print"
+            | "println"
+            | "eprint"
+            | "eprintln"
+            | "write"
+            | "writeln"
+            | "panic"
+    ) {
+        return None;
+    }
+
+    // Split the arguments at the top-level commas.
+    let mut args = vec![Vec::new()];
+    let mut active_arg = 0;
+    for element in macro_call.token_tree()?.syntax().children_with_tokens() {
+        match element.kind() {
+            T!['('] | T![')'] | T!['['] | T![']'] | T!['{'] | T!['}'] => (),
+            T![,] => {
+                if element.text_range().start() < offset {
+                    active_arg += 1;
+                }
+                args.push(Vec::new());
+            }
+            _ => args.last_mut()?.push(element),
+        }
+    }
+
+    let format_string_idx = args.iter().position(|arg| {
+        arg.iter().any(|it| it.as_token().map_or(false, |it| ast::String::can_cast(it.kind())))
+    })?;
+    if active_arg <= format_string_idx {
+        return None;
+    }
+    let format_string = args[format_string_idx]
+        .iter()
+        .find_map(|it| it.as_token().cloned().and_then(ast::String::cast))?;
+
+    // `name = value` arguments are referred to by name, all others by position.
+    let arg = &args[active_arg];
+    let significant = |it: &&SyntaxElement| !it.kind().is_trivia();
+    let mut tokens = arg.iter().filter(significant);
+    let referenced_as = match (tokens.next(), tokens.next()) {
+        (Some(name), Some(eq)) if name.kind() == IDENT && eq.kind() == T![=] => {
+            ArgRef::Name(name.as_token()?.text().to_string())
+        }
+        _ => ArgRef::Position(active_arg - format_string_idx - 1),
+    };
+
+    let mut res = None;
+    for placeholder in placeholders(&format_string) {
+        if placeholder.arg != referenced_as {
+            continue;
+        }
+        match res {
+            None => res = Some(placeholder.format_trait?),
+            Some(it) if Some(it) != placeholder.format_trait => return None,
+            Some(_) => (),
+        }
+    }
+    res
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ArgRef {
+    Position(usize),
+    Name(String),
+}
+
+struct Placeholder {
+    arg: ArgRef,
+    /// `None` for traits other than `Display` and `Debug`, like `LowerHex` in `{:x}`.
+    format_trait: Option<FormatTrait>,
+}
+
+fn placeholders(string: &ast::String) -> Vec<Placeholder> {
+    let text = string.text();
+    let mut res = Vec::new();
+    let mut next_position = 0;
+    // The argument of the current placeholder, if it is given explicitly.
+    let mut arg = None;
+    let mut after_colon = false;
+    // The type of formatting, like `x` in `{:x}`. Identifiers followed by `$` are widths instead.
+    let mut type_name = None;
+    let mut is_debug = false;
+    string.lex_format_specifier(|range, kind| match kind {
+        FormatSpecifier::Open => {
+            arg = None;
+            after_colon = false;
+            type_name = None;
+            is_debug = false;
+        }
+        FormatSpecifier::Integer if !after_colon => {
+            arg = text[range].parse().ok().map(ArgRef::Position);
+        }
+        FormatSpecifier::Identifier if !after_colon => {
+            arg = Some(ArgRef::Name(text[range].to_string()));
+        }
+        FormatSpecifier::Identifier => type_name = Some(range),
+        FormatSpecifier::DollarSign => type_name = None,
+        FormatSpecifier::Colon => after_colon = true,
+        FormatSpecifier::QuestionMark => is_debug = true,
+        FormatSpecifier::Close => {
+            let arg = arg.take().unwrap_or_else(|| {
+                next_position += 1;
+                ArgRef::Position(next_position - 1)
+            });
+            // `{:x?}` formats with `Debug` as well, just with hexadecimal integers.
+            let format_trait = match (type_name, is_debug) {
+                (None, false) => Some(FormatTrait::Display),
+                (_, true) => Some(FormatTrait::Debug),
+                (Some(_), false) => None,
+            };
+            res.push(Placeholder { arg, format_trait });
+        }
+        _ => (),
+    });
+    res
+}
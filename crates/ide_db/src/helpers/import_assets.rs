@@ -1,7 +1,7 @@
 //! Look up accessible paths for items.
 use hir::{
-    AsAssocItem, AssocItem, AssocItemContainer, Crate, ItemInNs, MacroDef, ModPath, Module,
-    ModuleDef, PathResolution, PrefixKind, ScopeDef, Semantics, Type,
+    AsAssocItem, AssocItem, AssocItemContainer, Crate, FindPathConfig, ItemInNs, MacroDef, ModPath,
+    Module, ModuleDef, PathResolution, PrefixKind, ScopeDef, Semantics, Type,
 };
 use itertools::Itertools;
 use rustc_hash::FxHashSet;
@@ -529,9 +529,14 @@ fn get_mod_path(
     prefixed: Option<PrefixKind>,
 ) -> Option<ModPath> {
     if let Some(prefix_kind) = prefixed {
-        module_with_candidate.find_use_path_prefixed(db, item_to_search, prefix_kind)
+        module_with_candidate.find_use_path_prefixed(
+            db,
+            item_to_search,
+            prefix_kind,
+            FindPathConfig::default(),
+        )
     } else {
-        module_with_candidate.find_use_path(db, item_to_search)
+        module_with_candidate.find_use_path(db, item_to_search, FindPathConfig::default())
     }
 }
 
@@ -33,6 +33,8 @@ use crate::{line_index::LineIndex, symbol_index::SymbolsDatabase};
 /// `base_db` is normally also needed in places where `ide_db` is used, so this re-export is for convenience.
 pub use base_db;
 
+pub use crate::apply_change::QueryMemoryUsage;
+
 #[salsa::database(
     base_db::SourceDatabaseStorage,
     base_db::SourceDatabaseExtStorage,
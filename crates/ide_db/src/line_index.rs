@@ -21,6 +21,14 @@ pub struct LineColUtf16 {
     pub col: u32,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LineColUtf32 {
+    /// Zero-based
+    pub line: u32,
+    /// Zero-based, counted in unicode scalar values
+    pub col: u32,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct LineCol {
     /// Zero-based
@@ -116,6 +124,16 @@ impl LineIndex {
         LineCol { line: line_col.line, col: col.into() }
     }
 
+    pub fn to_utf32(&self, line_col: LineCol) -> LineColUtf32 {
+        let col = self.utf8_to_utf32_col(line_col.line, line_col.col.into());
+        LineColUtf32 { line: line_col.line, col: col as u32 }
+    }
+
+    pub fn from_utf32(&self, line_col: LineColUtf32) -> LineCol {
+        let col = self.utf32_to_utf8_col(line_col.line, line_col.col);
+        LineCol { line: line_col.line, col: col.into() }
+    }
+
     pub fn lines(&self, range: TextRange) -> impl Iterator<Item = TextRange> + '_ {
         let lo = self.newlines.partition_point(|&it| it < range.start());
         let hi = self.newlines.partition_point(|&it| it <= range.end());
@@ -160,6 +178,34 @@ impl LineIndex {
 
         col.into()
     }
+
+    fn utf8_to_utf32_col(&self, line: u32, col: TextSize) -> usize {
+        let mut res: usize = col.into();
+        if let Some(utf16_chars) = self.utf16_lines.get(&line) {
+            for c in utf16_chars {
+                if c.end <= col {
+                    res -= usize::from(c.len()) - 1;
+                } else {
+                    break;
+                }
+            }
+        }
+        res
+    }
+
+    fn utf32_to_utf8_col(&self, line: u32, mut col: u32) -> TextSize {
+        if let Some(utf16_chars) = self.utf16_lines.get(&line) {
+            for c in utf16_chars {
+                if col > u32::from(c.start) {
+                    col += u32::from(c.len()) - 1;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        col.into()
+    }
 }
 
 #[cfg(test)]
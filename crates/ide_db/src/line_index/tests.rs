@@ -104,6 +104,32 @@ const C: char = \"メ メ\";
     assert_eq!(col_index.utf16_to_utf8_col(2, 15), TextSize::from(15));
 }
 
+#[test]
+fn test_utf32() {
+    // 𐐏 is outside of the basic multilingual plane: four bytes in UTF-8, two
+    // code units in UTF-16, but a single UTF-32 code unit.
+    let col_index = LineIndex::new("\na𐐏bメc");
+
+    // UTF-8 to UTF-32
+    assert_eq!(col_index.utf8_to_utf32_col(0, 0.into()), 0);
+    assert_eq!(col_index.utf8_to_utf32_col(1, 1.into()), 1);
+    assert_eq!(col_index.utf8_to_utf32_col(1, 5.into()), 2);
+    assert_eq!(col_index.utf8_to_utf32_col(1, 6.into()), 3);
+    assert_eq!(col_index.utf8_to_utf32_col(1, 9.into()), 4);
+    assert_eq!(col_index.utf8_to_utf32_col(1, 10.into()), 5);
+
+    // UTF-32 to UTF-8
+    assert_eq!(col_index.utf32_to_utf8_col(1, 1), TextSize::from(1));
+    assert_eq!(col_index.utf32_to_utf8_col(1, 2), TextSize::from(5));
+    assert_eq!(col_index.utf32_to_utf8_col(1, 3), TextSize::from(6));
+    assert_eq!(col_index.utf32_to_utf8_col(1, 4), TextSize::from(9));
+    assert_eq!(col_index.utf32_to_utf8_col(1, 5), TextSize::from(10));
+
+    let line_col = LineCol { line: 1, col: 9 };
+    assert_eq!(col_index.to_utf32(line_col), LineColUtf32 { line: 1, col: 4 });
+    assert_eq!(col_index.from_utf32(col_index.to_utf32(line_col)), line_col);
+}
+
 #[test]
 fn test_splitlines() {
     fn r(lo: u32, hi: u32) -> TextRange {
@@ -149,8 +149,11 @@ impl<'a> Ctx<'a> {
                 }
             }
             hir::PathResolution::Def(def) => {
-                let found_path =
-                    self.target_module.find_use_path(self.source_scope.db.upcast(), def)?;
+                let found_path = self.target_module.find_use_path(
+                    self.source_scope.db.upcast(),
+                    def,
+                    hir::FindPathConfig::default(),
+                )?;
                 let res = mod_path_to_ast(&found_path).clone_for_update();
                 if let Some(args) = path.segment().and_then(|it| it.generic_arg_list()) {
                     if let Some(segment) = res.segment() {
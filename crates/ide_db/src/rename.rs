@@ -22,12 +22,12 @@
 //! Our current behavior is ¯\_(ツ)_/¯.
 use std::fmt;
 
-use base_db::{AnchoredPathBuf, FileId, FileRange};
+use base_db::{AnchoredPath, AnchoredPathBuf, FileId, FileLoader, FileRange};
 use either::Either;
 use hir::{AsAssocItem, FieldSource, HasSource, InFile, ModuleSource, Semantics};
 use stdx::never;
 use syntax::{
-    ast::{self, NameOwner},
+    ast::{self, AttrsOwner, NameOwner},
     lex_single_syntax_kind, AstNode, SyntaxKind, TextRange, T,
 };
 use text_edit::TextEdit;
@@ -188,17 +188,40 @@ fn rename_mod(
     let mut source_change = SourceChange::default();
 
     let InFile { file_id, value: def_source } = module.definition_source(sema.db);
-    let file_id = file_id.original_file(sema.db);
-    if let ModuleSource::SourceFile(..) = def_source {
-        // mod is defined in path/to/dir/mod.rs
-        let path = if module.is_mod_rs(sema.db) {
-            format!("../{}/mod.rs", new_name)
-        } else {
-            format!("{}.rs", new_name)
-        };
-        let dst = AnchoredPathBuf { anchor: file_id, path };
-        let move_file = FileSystemEdit::MoveFile { src: file_id, dst };
-        source_change.push_file_system_edit(move_file);
+    let anchor = file_id.original_file(sema.db);
+    // Modules with a `#[path]` attribute keep their file where it is
+    let has_path_attr = module.declaration_source(sema.db).map_or(false, |decl| {
+        decl.value.attrs().any(|attr| attr.simple_name().as_deref() == Some("path"))
+    });
+    if let (ModuleSource::SourceFile(..), false) = (def_source, has_path_attr) {
+        let is_mod_rs = module.is_mod_rs(sema.db);
+        // For `mod.rs` the anchor lives inside of the module's directory
+        let parent_dir = if is_mod_rs { "../" } else { "" };
+        for path in
+            [format!("{}{}.rs", parent_dir, new_name), format!("{}{}/mod.rs", parent_dir, new_name)]
+        {
+            if sema.db.resolve_path(AnchoredPath { anchor, path: &path }).is_some() {
+                bail!("Cannot rename module to `{}`: file `{}` already exists", new_name, path);
+            }
+        }
+
+        if !is_mod_rs {
+            let dst = AnchoredPathBuf { anchor, path: format!("{}.rs", new_name) };
+            source_change.push_file_system_edit(FileSystemEdit::MoveFile { src: anchor, dst });
+        }
+
+        // The module's directory has to move as well if it contains the module itself or
+        // any of its file-backed children
+        let has_file_child = module.children(sema.db).any(|child| !child.is_inline(sema.db));
+        if let (true, Some(old_name)) = (is_mod_rs || has_file_child, module.name(sema.db)) {
+            let src = AnchoredPathBuf { anchor, path: format!("{}{}", parent_dir, old_name) };
+            let dst = AnchoredPathBuf { anchor, path: format!("{}{}", parent_dir, new_name) };
+            source_change.push_file_system_edit(FileSystemEdit::MoveDir {
+                src,
+                src_id: anchor,
+                dst,
+            });
+        }
     }
 
     if let Some(InFile { file_id, value: decl_source }) = module.declaration_source(sema.db) {
@@ -8,8 +8,8 @@ use std::{convert::TryInto, mem};
 
 use base_db::{FileId, FileRange, SourceDatabase, SourceDatabaseExt};
 use hir::{
-    AsAssocItem, DefWithBody, HasAttrs, HasSource, InFile, ModuleDef, ModuleSource, Semantics,
-    Visibility,
+    Access, AsAssocItem, DefWithBody, HasAttrs, HasSource, InFile, ModuleDef, ModuleSource,
+    Semantics, Visibility,
 };
 use once_cell::unsync::Lazy;
 use rustc_hash::FxHashMap;
@@ -71,6 +71,7 @@ pub enum ReferenceAccess {
 /// For `pub(crate)` things it's a crate, for `pub` things it's a crate and dependant crates.
 /// In some cases, the location of the references is known to within a `TextRange`,
 /// e.g. for things like local variables.
+#[derive(Clone)]
 pub struct SearchScope {
     entries: FxHashMap<FileId, Option<TextRange>>,
 }
@@ -208,7 +209,8 @@ impl IntoIterator for SearchScope {
 }
 
 impl Definition {
-    fn search_scope(&self, db: &RootDatabase) -> SearchScope {
+    /// The files, or parts of files, that may contain references to this definition.
+    pub fn search_scope(&self, db: &RootDatabase) -> SearchScope {
         let _p = profile::span("search_scope");
 
         if let Definition::ModuleDef(hir::ModuleDef::BuiltinType(_)) = self {
@@ -300,6 +302,31 @@ impl Definition {
         }
     }
 
+    /// Finds the textual occurrences of the name of this definition in its search scope.
+    ///
+    /// Unlike [`FindUsages`], this also reports occurrences in comments and string literals,
+    /// for callers looking for references that are not part of the syntax tree.
+    pub fn name_occurrences(&self, db: &RootDatabase) -> Vec<FileRange> {
+        let name = match self.name(db) {
+            Some(it) => it.to_string(),
+            None => return Vec::new(),
+        };
+        let mut res = Vec::new();
+        for (file_id, search_range) in self.search_scope(db) {
+            let text = db.file_text(file_id);
+            let search_range =
+                search_range.unwrap_or_else(|| TextRange::up_to(TextSize::of(text.as_str())));
+            for (idx, _) in text.match_indices(name.as_str()) {
+                let offset: TextSize = idx.try_into().unwrap();
+                let range = TextRange::at(offset, TextSize::of(name.as_str()));
+                if search_range.contains_range(range) {
+                    res.push(FileRange { file_id, range });
+                }
+            }
+        }
+        res
+    }
+
     pub fn usages<'a>(self, sema: &'a Semantics<RootDatabase>) -> FindUsages<'a> {
         FindUsages {
             def: self,
@@ -357,6 +384,7 @@ impl<'a> FindUsages<'a> {
 
     fn search(self, sink: &mut dyn FnMut(FileId, FileReference) -> bool) {
         let _p = profile::span("FindUsages:search");
+        cov_mark::hit!(find_usages_search);
         let sema = self.sema;
 
         let search_scope = {
@@ -528,7 +556,7 @@ impl<'a> FindUsages<'a> {
                 let reference = FileReference {
                     range,
                     name: ast::NameLike::NameRef(name_ref.clone()),
-                    access: reference_access(&def, name_ref),
+                    access: reference_access(self.sema, &def, name_ref),
                 };
                 sink(file_id, reference)
             }
@@ -538,7 +566,7 @@ impl<'a> FindUsages<'a> {
                     let reference = FileReference {
                         range,
                         name: ast::NameLike::NameRef(name_ref.clone()),
-                        access: reference_access(&def, name_ref),
+                        access: reference_access(self.sema, &def, name_ref),
                     };
                     sink(file_id, reference)
                 } else {
@@ -549,9 +577,11 @@ impl<'a> FindUsages<'a> {
                 let field = Definition::Field(field);
                 let FileRange { file_id, range } = self.sema.original_range(name_ref.syntax());
                 let access = match self.def {
-                    Definition::Field(_) if field == self.def => reference_access(&field, name_ref),
+                    Definition::Field(_) if field == self.def => {
+                        reference_access(self.sema, &field, name_ref)
+                    }
                     Definition::Local(l) if local == l => {
-                        reference_access(&Definition::Local(local), name_ref)
+                        reference_access(self.sema, &Definition::Local(local), name_ref)
                     }
                     _ => return false,
                 };
@@ -641,12 +671,22 @@ fn def_to_ty(sema: &Semantics<RootDatabase>, def: &Definition) -> Option<hir::Ty
     }
 }
 
-fn reference_access(def: &Definition, name_ref: &ast::NameRef) -> Option<ReferenceAccess> {
+fn reference_access(
+    sema: &Semantics<RootDatabase>,
+    def: &Definition,
+    name_ref: &ast::NameRef,
+) -> Option<ReferenceAccess> {
     // Only Locals and Fields have accesses for now.
     if !matches!(def, Definition::Local(_) | Definition::Field(_)) {
         return None;
     }
 
+    if let Definition::Local(_) = def {
+        if let Some(access) = semantic_local_access(sema, name_ref) {
+            return Some(access);
+        }
+    }
+
     let mode = name_ref.syntax().ancestors().find_map(|node| {
         match_ast! {
             match (node) {
@@ -670,3 +710,16 @@ fn reference_access(def: &Definition, name_ref: &ast::NameRef) -> Option<Referen
     // Default Locals and Fields to read
     mode.or(Some(ReferenceAccess::Read))
 }
+
+fn semantic_local_access(
+    sema: &Semantics<RootDatabase>,
+    name_ref: &ast::NameRef,
+) -> Option<ReferenceAccess> {
+    let segment = ast::PathSegment::cast(name_ref.syntax().parent()?)?;
+    let path_expr = ast::PathExpr::cast(segment.parent_path().syntax().parent()?)?;
+    let access = match sema.classify_local_access(&ast::Expr::PathExpr(path_expr))? {
+        Access::Exclusive => ReferenceAccess::Write,
+        Access::Shared | Access::Owned => ReferenceAccess::Read,
+    };
+    Some(access)
+}
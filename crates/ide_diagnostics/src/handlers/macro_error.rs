@@ -32,6 +32,20 @@ macro_rules! include { () => {} }
         );
     }
 
+    #[test]
+    fn proc_macro_expansion_timeout() {
+        check_diagnostics(
+            r#"
+//- /main.rs crate:main deps:proc_macros
+  proc_macros::timeout!(struct S;);
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ error: proc macro expansion timed out: `timeout` did not finish within 10s
+//- /proc_macros.rs crate:proc_macros proc_macro:timeout
+#[proc_macro]
+pub fn timeout(input: TokenStream) -> TokenStream { input }
+"#,
+        );
+    }
+
     #[test]
     fn include_macro_should_allow_empty_content() {
         let mut config = DiagnosticsConfig::default();
@@ -30,6 +30,7 @@
 // it will apply to the whole workspace.
 //
 // Placeholders may be given constraints by writing them as `${<name>:<constraint1>:<constraint2>...}`.
+// Paths in `ty` and `fn` constraints are resolved like other paths in the search pattern.
 //
 // Supported constraints:
 //
@@ -37,6 +38,8 @@
 // | Constraint    | Restricts placeholder
 //
 // | kind(literal) | Is a literal (e.g. `42` or `"forty two"`)
+// | ty(path)      | Is an expression whose type unifies with the type at `path` (e.g. `ty(String)`)
+// | fn(path)      | Is a call of, or a reference to, the function at `path` (e.g. `fn(std::mem::drop)`)
 // | not(a)        | Negates the constraint `a`
 // |===
 //
@@ -343,6 +346,11 @@ impl Match {
     pub fn matched_text(&self) -> String {
         self.matched_node.text().to_string()
     }
+
+    /// The file the match was found in.
+    pub fn file_id(&self) -> FileId {
+        self.range.file_id
+    }
 }
 
 impl std::error::Error for SsrError {}
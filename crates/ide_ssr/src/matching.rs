@@ -329,10 +329,50 @@ impl<'db, 'sema> Matcher<'db, 'sema> {
                     fail_match!("Constraint {:?} failed for '{}'", constraint, code.text());
                 }
             }
+            Constraint::Type(path) => {
+                let expected = match self.rule.pattern.constraint_types.get(path) {
+                    Some(it) => it,
+                    None => fail_match!("Type `{}` wasn't resolved", path),
+                };
+                let ty = ast::Expr::cast(code.clone())
+                    .and_then(|expr| self.sema.type_of_expr(&expr))
+                    .map(|it| it.original);
+                match ty {
+                    Some(ty) if ty.could_unify_with(self.sema.db, expected) => {}
+                    _ => fail_match!("'{}' doesn't have type `{}`", code.text(), path),
+                }
+            }
+            Constraint::Fn(path) => {
+                let expected = match self.rule.pattern.constraint_functions.get(path) {
+                    Some(it) => it,
+                    None => fail_match!("Function `{}` wasn't resolved", path),
+                };
+                if self.resolve_callee(code).as_ref() != Some(expected) {
+                    fail_match!("'{}' doesn't call `{}`", code.text(), path);
+                }
+            }
         }
         Ok(())
     }
 
+    /// Returns the function that `code` calls or refers to.
+    fn resolve_callee(&self, code: &SyntaxNode) -> Option<hir::Function> {
+        let path = match ast::Expr::cast(code.clone())? {
+            ast::Expr::MethodCallExpr(call) => return self.sema.resolve_method_call(&call),
+            ast::Expr::CallExpr(call) => match call.expr()? {
+                ast::Expr::PathExpr(path_expr) => path_expr.path()?,
+                _ => return None,
+            },
+            ast::Expr::PathExpr(path_expr) => path_expr.path()?,
+            _ => return None,
+        };
+        match self.sema.resolve_path(&path)? {
+            hir::PathResolution::Def(hir::ModuleDef::Function(function))
+            | hir::PathResolution::AssocItem(hir::AssocItem::Function(function)) => Some(function),
+            _ => None,
+        }
+    }
+
     /// Paths are matched based on whether they refer to the same thing, even if they're written
     /// differently.
     fn attempt_match_path(
@@ -653,9 +693,11 @@ impl Match {
             .ok_or_else(|| match_error!("Matched node isn't in a module"))?;
         for (path, resolved_path) in &template.resolved_paths {
             if let hir::PathResolution::Def(module_def) = resolved_path.resolution {
-                let mod_path = module.find_use_path(sema.db, module_def).ok_or_else(|| {
-                    match_error!("Failed to render template path `{}` at match location")
-                })?;
+                let mod_path = module
+                    .find_use_path(sema.db, module_def, hir::FindPathConfig::default())
+                    .ok_or_else(|| {
+                        match_error!("Failed to render template path `{}` at match location")
+                    })?;
                 self.rendered_template_paths.insert(path.clone(), mod_path);
             }
         }
@@ -47,6 +47,10 @@ pub(crate) struct Var(pub(crate) String);
 pub(crate) enum Constraint {
     Kind(NodeKind),
     Not(Box<Constraint>),
+    /// The path of the type that the expression must have, e.g. `std::string::String`.
+    Type(SmolStr),
+    /// The path of the function that the call must resolve to, e.g. `std::mem::drop`.
+    Fn(SmolStr),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -309,6 +313,8 @@ fn parse_placeholder(tokens: &mut std::vec::IntoIter<Token>) -> Result<Placehold
     Ok(Placeholder::new(name, constraints))
 }
 
+/// Parses a placeholder constraint, one of `kind(literal)`, `not(<constraint>)`,
+/// `ty(path::To::Type)` or `fn(path::to::func)`.
 fn parse_constraint(tokens: &mut std::vec::IntoIter<Token>) -> Result<Constraint, SsrError> {
     let constraint_type = tokens
         .next()
@@ -333,10 +339,41 @@ fn parse_constraint(tokens: &mut std::vec::IntoIter<Token>) -> Result<Constraint
             expect_token(tokens, ")")?;
             Ok(Constraint::Not(Box::new(sub)))
         }
+        "ty" => {
+            expect_token(tokens, "(")?;
+            Ok(Constraint::Type(parse_constraint_path(tokens)?))
+        }
+        "fn" => {
+            expect_token(tokens, "(")?;
+            Ok(Constraint::Fn(parse_constraint_path(tokens)?))
+        }
         x => bail!("Unsupported constraint type '{}'", x),
     }
 }
 
+/// Consumes the tokens of a path up to and including the closing parenthesis of the constraint.
+fn parse_constraint_path(tokens: &mut std::vec::IntoIter<Token>) -> Result<SmolStr, SsrError> {
+    let mut path = String::new();
+    let mut depth = 0;
+    loop {
+        let t = tokens
+            .next()
+            .ok_or_else(|| SsrError::new("Unexpected end of constraint while looking for ')'"))?;
+        match t.kind {
+            T!['('] | T![<] => depth += 1,
+            T![')'] if depth == 0 => break,
+            T![')'] | T![>] => depth -= 1,
+            SyntaxKind::WHITESPACE => continue,
+            _ => {}
+        }
+        path.push_str(&t.text);
+    }
+    if path.is_empty() {
+        bail!("Expected a path in constraint");
+    }
+    Ok(path.into())
+}
+
 fn expect_token(tokens: &mut std::vec::IntoIter<Token>, expected: &str) -> Result<(), SsrError> {
     if let Some(t) = tokens.next() {
         if t.text == expected {
@@ -1,9 +1,9 @@
 //! This module is responsible for resolving paths within rules.
 
-use crate::errors::error;
+use crate::errors::{bail, error};
 use crate::{parsing, SsrError};
 use ide_db::base_db::FilePosition;
-use parsing::Placeholder;
+use parsing::{Constraint, Placeholder};
 use rustc_hash::FxHashMap;
 use syntax::{ast, SmolStr, SyntaxKind, SyntaxNode, SyntaxToken};
 
@@ -25,6 +25,10 @@ pub(crate) struct ResolvedPattern {
     pub(crate) resolved_paths: FxHashMap<SyntaxNode, ResolvedPath>,
     pub(crate) ufcs_function_calls: FxHashMap<SyntaxNode, UfcsCallInfo>,
     pub(crate) contains_self: bool,
+    /// Types and functions referred to by `ty(..)` and `fn(..)` placeholder constraints, keyed by
+    /// the path as written in the constraint.
+    pub(crate) constraint_types: FxHashMap<SmolStr, hir::Type>,
+    pub(crate) constraint_functions: FxHashMap<SmolStr, hir::Function>,
 }
 
 pub(crate) struct ResolvedPath {
@@ -102,15 +106,75 @@ impl Resolver<'_, '_> {
                 SyntaxElement::Token(t) => t.kind() == T![self],
                 _ => false,
             });
+        let mut constraint_types = FxHashMap::default();
+        let mut constraint_functions = FxHashMap::default();
+        for placeholder in self.placeholders_by_stand_in.values() {
+            for constraint in &placeholder.constraints {
+                self.resolve_constraint(
+                    constraint,
+                    &mut constraint_types,
+                    &mut constraint_functions,
+                )?;
+            }
+        }
         Ok(ResolvedPattern {
             node: pattern,
             resolved_paths,
             placeholders_by_stand_in: self.placeholders_by_stand_in.clone(),
             ufcs_function_calls,
             contains_self,
+            constraint_types,
+            constraint_functions,
         })
     }
 
+    fn resolve_constraint(
+        &self,
+        constraint: &Constraint,
+        types: &mut FxHashMap<SmolStr, hir::Type>,
+        functions: &mut FxHashMap<SmolStr, hir::Function>,
+    ) -> Result<(), SsrError> {
+        let db = self.resolution_scope.scope.db;
+        match constraint {
+            Constraint::Kind(_) => {}
+            Constraint::Not(sub) => self.resolve_constraint(sub, types, functions)?,
+            Constraint::Type(path) => {
+                let ty = match self.resolve_constraint_path(path)? {
+                    hir::PathResolution::Def(hir::ModuleDef::Adt(adt)) => adt.ty(db),
+                    hir::PathResolution::Def(hir::ModuleDef::TypeAlias(alias)) => alias.ty(db),
+                    hir::PathResolution::Def(hir::ModuleDef::BuiltinType(builtin)) => {
+                        let module = self
+                            .resolution_scope
+                            .scope
+                            .module()
+                            .ok_or_else(|| error!("Failed to resolve type `{}`", path))?;
+                        builtin.ty(db, module)
+                    }
+                    _ => bail!("`{}` in a `ty` constraint isn't a type", path),
+                };
+                types.insert(path.clone(), ty);
+            }
+            Constraint::Fn(path) => {
+                let function = match self.resolve_constraint_path(path)? {
+                    hir::PathResolution::Def(hir::ModuleDef::Function(function))
+                    | hir::PathResolution::AssocItem(hir::AssocItem::Function(function)) => {
+                        function
+                    }
+                    _ => bail!("`{}` in a `fn` constraint isn't a function", path),
+                };
+                functions.insert(path.clone(), function);
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_constraint_path(&self, path: &str) -> Result<hir::PathResolution, SsrError> {
+        ast::Path::parse(path)
+            .ok()
+            .and_then(|it| self.resolution_scope.resolve_path(&it))
+            .ok_or_else(|| error!("Failed to resolve path `{}`", path))
+    }
+
     fn resolve(
         &self,
         node: SyntaxNode,
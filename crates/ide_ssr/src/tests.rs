@@ -524,6 +524,76 @@ fn literal_constraint() {
     assert_matches("Some(${a:not(kind(literal))})", code, &["Some(x1)", "Some(40 + 2)"]);
 }
 
+#[test]
+fn type_constraint() {
+    assert_ssr_transform(
+        "${x:ty(String)}.len() == 0 ==>> $x.is_empty()",
+        r#"
+        struct String;
+        impl String {
+            fn len(&self) -> usize { 0 }
+            fn is_empty(&self) -> bool { true }
+        }
+        struct Buffer;
+        impl Buffer {
+            fn len(&self) -> usize { 0 }
+        }
+        fn f(s: String, b: Buffer) {
+            if s.len() == 0 {}
+            if b.len() == 0 {}
+        }
+        "#,
+        expect![[r#"
+            struct String;
+            impl String {
+                fn len(&self) -> usize { 0 }
+                fn is_empty(&self) -> bool { true }
+            }
+            struct Buffer;
+            impl Buffer {
+                fn len(&self) -> usize { 0 }
+            }
+            fn f(s: String, b: Buffer) {
+                if s.is_empty() {}
+                if b.len() == 0 {}
+            }
+        "#]],
+    );
+    assert_matches(
+        "wrap(${a:not(ty(i32))})",
+        "fn wrap<T>(x: T) {} fn f() { wrap(1u8); wrap(1i32); }",
+        &["wrap(1u8)"],
+    );
+}
+
+#[test]
+fn fn_constraint() {
+    let code = r#"
+        mod m {
+            pub fn foo(x: i32) -> i32 { x }
+            pub fn bar(x: i32) -> i32 { x }
+        }
+        fn wrap<T>(x: T) {}
+        fn f() {
+            wrap(m::foo(1));
+            wrap(m::bar(2));
+        }
+        "#;
+    assert_matches("wrap(${c:fn(m::foo)})", code, &["wrap(m::foo(1))"]);
+    assert_matches("wrap(${c:not(fn(m::foo))})", code, &["wrap(m::bar(2))"]);
+}
+
+#[test]
+fn unresolved_constraint_path() {
+    let (db, position, selections) = single_file("fn f() {}");
+    let mut match_finder = MatchFinder::in_context(&db, position, selections);
+    let rule: SsrRule = "${a:ty(Missing)} ==>> $a".parse().unwrap();
+    assert_eq!(
+        match_finder.add_rule(rule).unwrap_err().to_string(),
+        "Parse error: Failed to resolve path `Missing`"
+    );
+}
+
 #[test]
 fn match_reordered_struct_instantiation() {
     assert_matches(
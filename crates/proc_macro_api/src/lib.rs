@@ -16,15 +16,22 @@ use std::{
     ffi::OsStr,
     io,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use tt::{SmolStr, Subtree};
 
 use crate::process::ProcMacroProcessSrv;
 
-pub use rpc::{ExpansionResult, ExpansionTask, ListMacrosResult, ListMacrosTask, ProcMacroKind};
+pub use rpc::{
+    ExpansionResult, ExpansionTask, ExpansionTiming, ExpansionTimingsResult, ListMacrosResult,
+    ListMacrosTask, ProcMacroKind,
+};
 pub use version::{read_dylib_info, RustCInfo};
 
+/// How long the server lets a single expansion run, unless configured otherwise.
+pub const DEFAULT_EXPANSION_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone)]
 struct ProcMacroProcessExpander {
     process: Arc<Mutex<ProcMacroProcessSrv>>,
@@ -48,19 +55,17 @@ impl base_db::ProcMacroExpander for ProcMacroProcessExpander {
         attr: Option<&Subtree>,
         env: &Env,
     ) -> Result<Subtree, tt::ExpansionError> {
+        let mut process = self.process.lock().unwrap_or_else(|e| e.into_inner());
         let task = ExpansionTask {
             macro_body: subtree.clone(),
             macro_name: self.name.to_string(),
             attributes: attr.cloned(),
             lib: self.dylib_path.to_path_buf(),
             env: env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            timeout: process.expansion_timeout,
         };
 
-        let result: ExpansionResult = self
-            .process
-            .lock()
-            .unwrap_or_else(|e| e.into_inner())
-            .send_task(msg::Request::ExpansionMacro(task))?;
+        let result: ExpansionResult = process.send_task(msg::Request::ExpansionMacro(task))?;
         Ok(result.expansion)
     }
 }
@@ -86,6 +91,18 @@ impl ProcMacroClient {
         Ok(ProcMacroClient { process: Arc::new(Mutex::new(process)) })
     }
 
+    /// Sets how long the server lets a single expansion run. Once an expansion
+    /// times out, the server refuses to expand further macros of the same
+    /// library until it is rebuilt. `None` disables the timeout.
+    pub fn set_expansion_timeout(&self, timeout: Option<Duration>) {
+        self.process.lock().unwrap_or_else(|e| e.into_inner()).expansion_timeout = timeout;
+    }
+
+    /// The OS process id of the proc macro server.
+    pub fn process_id(&self) -> u32 {
+        self.process.lock().unwrap_or_else(|e| e.into_inner()).process_id()
+    }
+
     pub fn by_dylib_path(&self, dylib_path: &AbsPath) -> Vec<ProcMacro> {
         let _p = profile::span("ProcMacroClient::by_dylib_path");
         match version::read_dylib_info(dylib_path) {
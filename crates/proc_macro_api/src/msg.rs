@@ -8,7 +8,7 @@ use std::{
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
-    rpc::{ListMacrosResult, ListMacrosTask},
+    rpc::{ExpansionTimingsResult, ListMacrosResult, ListMacrosTask},
     ExpansionResult, ExpansionTask,
 };
 
@@ -16,6 +16,8 @@ use crate::{
 pub enum Request {
     ListMacro(ListMacrosTask),
     ExpansionMacro(ExpansionTask),
+    /// Debugging aid: asks for the time spent in each macro expanded so far.
+    ExpansionTimings,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,6 +25,7 @@ pub enum Response {
     Error(ResponseError),
     ListMacro(ListMacrosResult),
     ExpansionMacro(ExpansionResult),
+    ExpansionTimings(ExpansionTimingsResult),
 }
 
 macro_rules! impl_try_from_response {
@@ -41,6 +44,7 @@ macro_rules! impl_try_from_response {
 
 impl_try_from_response!(ListMacrosResult, ListMacro);
 impl_try_from_response!(ExpansionResult, ExpansionMacro);
+impl_try_from_response!(ExpansionTimingsResult, ExpansionTimings);
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ResponseError {
@@ -52,6 +56,8 @@ pub struct ResponseError {
 pub enum ErrorCode {
     ServerErrorEnd,
     ExpansionError,
+    /// The expansion didn't finish within the timeout of its task.
+    ExpansionTimeout,
 }
 
 pub trait Message: Serialize + DeserializeOwned {
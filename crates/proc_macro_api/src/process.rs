@@ -5,6 +5,7 @@ use std::{
     ffi::{OsStr, OsString},
     io::{self, BufRead, BufReader, Write},
     process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    time::Duration,
 };
 
 use paths::{AbsPath, AbsPathBuf};
@@ -13,6 +14,7 @@ use stdx::JodChild;
 use crate::{
     msg::{ErrorCode, Message, Request, Response, ResponseError},
     rpc::{ListMacrosResult, ListMacrosTask, ProcMacroKind},
+    DEFAULT_EXPANSION_TIMEOUT,
 };
 
 #[derive(Debug)]
@@ -20,6 +22,8 @@ pub(crate) struct ProcMacroProcessSrv {
     process: Process,
     stdin: ChildStdin,
     stdout: BufReader<ChildStdout>,
+    /// Sent along with every expansion task.
+    pub(crate) expansion_timeout: Option<Duration>,
 }
 
 impl ProcMacroProcessSrv {
@@ -30,11 +34,20 @@ impl ProcMacroProcessSrv {
         let mut process = Process::run(process_path, args)?;
         let (stdin, stdout) = process.stdio().expect("couldn't access child stdio");
 
-        let srv = ProcMacroProcessSrv { process, stdin, stdout };
+        let srv = ProcMacroProcessSrv {
+            process,
+            stdin,
+            stdout,
+            expansion_timeout: Some(DEFAULT_EXPANSION_TIMEOUT),
+        };
 
         Ok(srv)
     }
 
+    pub(crate) fn process_id(&self) -> u32 {
+        self.process.child.id()
+    }
+
     pub(crate) fn find_proc_macros(
         &mut self,
         dylib_path: &AbsPath,
@@ -68,7 +81,12 @@ impl ProcMacroProcessSrv {
         };
 
         match res {
-            Some(Response::Error(err)) => Err(tt::ExpansionError::ExpansionError(err.message)),
+            Some(Response::Error(err)) => Err(match err.code {
+                ErrorCode::ExpansionTimeout => tt::ExpansionError::ExpansionTimeout(err.message),
+                ErrorCode::ServerErrorEnd | ErrorCode::ExpansionError => {
+                    tt::ExpansionError::ExpansionError(err.message)
+                }
+            }),
             Some(res) => Ok(res.try_into().map_err(|err| {
                 tt::ExpansionError::Unknown(format!("Fail to get response, reason : {:#?} ", err))
             })?),
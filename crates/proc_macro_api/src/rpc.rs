@@ -6,6 +6,8 @@
 //! to be much easier, we deliberately duplicate `tt` structs with `#[serde(with = "XXDef")]`
 //! for separation of code responsibility.
 
+use std::time::Duration;
+
 use paths::AbsPathBuf;
 use serde::{Deserialize, Serialize};
 use tt::{
@@ -53,6 +55,10 @@ pub struct ExpansionTask {
 
     /// Environment variables to set during macro expansion.
     pub env: Vec<(String, String)>,
+
+    /// How long the expansion may take before the server gives up on it.
+    #[serde(default)]
+    pub timeout: Option<Duration>,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
@@ -61,6 +67,21 @@ pub struct ExpansionResult {
     pub expansion: Subtree,
 }
 
+/// Time spent expanding a single macro, accumulated over all of its expansions.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ExpansionTiming {
+    pub lib: AbsPathBuf,
+    pub macro_name: String,
+    pub count: u32,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct ExpansionTimingsResult {
+    pub timings: Vec<ExpansionTiming>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(remote = "DelimiterKind")]
 enum DelimiterKindDef {
@@ -273,12 +294,14 @@ mod tests {
             attributes: None,
             lib: AbsPathBuf::assert(std::env::current_dir().unwrap()),
             env: Default::default(),
+            timeout: Some(Duration::from_secs(10)),
         };
 
         let json = serde_json::to_string(&task).unwrap();
         let back: ExpansionTask = serde_json::from_str(&json).unwrap();
 
         assert_eq!(task.macro_body, back.macro_body);
+        assert_eq!(task.timeout, back.timeout);
 
         let result = ExpansionResult { expansion: tt };
         let json = serde_json::to_string(&result).unwrap();
@@ -0,0 +1,84 @@
+//! A small least-recently-used cache for macro expansions.
+//!
+//! While the user is typing, the same macro invocations get expanded over and
+//! over again with identical input, so we remember the most recent results.
+//! The cache belongs to a `dylib::Expander`: reloading a library creates a new
+//! expander, and with it an empty cache.
+
+use std::{collections::HashMap, hash::Hash};
+
+pub(crate) struct LruCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, u64)>,
+    /// Incremented on every access, used to find the least recently used entry.
+    tick: u64,
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruCache<K, V> {
+    pub(crate) fn new(capacity: usize) -> LruCache<K, V> {
+        assert!(capacity > 0);
+        LruCache { capacity, entries: HashMap::new(), tick: 0, hits: 0, misses: 0 }
+    }
+
+    pub(crate) fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        match self.entries.get_mut(key) {
+            Some((value, last_used)) => {
+                *last_used = self.tick;
+                self.hits += 1;
+                Some(value.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        self.tick += 1;
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            // The cache is small, so a linear scan is cheaper than maintaining
+            // a separate recency list.
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, (value, self.tick));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        assert_eq!(cache.get(&1), Some("one"));
+        cache.insert(3, "three");
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.get(&3), Some("three"));
+        assert_eq!((cache.hits, cache.misses), (3, 1));
+    }
+
+    #[test]
+    fn reinserting_does_not_evict() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        cache.insert(2, "deux");
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.get(&2), Some("deux"));
+    }
+}
@@ -10,18 +10,20 @@ pub fn run() -> io::Result<()> {
 
     while let Some(req) = read_request(&mut buf)? {
         let res = match req {
-            msg::Request::ListMacro(task) => srv.list_macros(&task).map(msg::Response::ListMacro),
+            msg::Request::ListMacro(task) => {
+                srv.list_macros(&task).map(msg::Response::ListMacro).map_err(|message| {
+                    msg::ResponseError { code: msg::ErrorCode::ExpansionError, message }
+                })
+            }
             msg::Request::ExpansionMacro(task) => {
                 srv.expand(&task).map(msg::Response::ExpansionMacro)
             }
+            msg::Request::ExpansionTimings => {
+                Ok(msg::Response::ExpansionTimings(srv.expansion_timings()))
+            }
         };
 
-        let msg = res.unwrap_or_else(|err| {
-            msg::Response::Error(msg::ResponseError {
-                code: msg::ErrorCode::ExpansionError,
-                message: err,
-            })
-        });
+        let msg = res.unwrap_or_else(msg::Response::Error);
 
         if let Err(err) = write_response(msg) {
             eprintln!("Write message error: {}", err);
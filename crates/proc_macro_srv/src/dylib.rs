@@ -6,6 +6,8 @@ use std::{
     fs::File,
     io,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
 use libloading::Library;
@@ -14,7 +16,11 @@ use object::Object;
 use paths::AbsPath;
 use proc_macro_api::{read_dylib_info, ProcMacroKind};
 
-use super::abis::Abi;
+use super::{
+    abis::Abi,
+    cache::LruCache,
+    watchdog::{run_guarded, WatchdogError},
+};
 
 const NEW_REGISTRAR_SYMBOL: &str = "_rustc_proc_macro_decls_";
 
@@ -129,8 +135,27 @@ impl ProcMacroLibraryLibloading {
     }
 }
 
+/// Number of expansions remembered per library.
+const EXPANSION_CACHE_CAPACITY: usize = 256;
+
+/// The environment is part of the key, as macros may read it (e.g. `env!("CARGO_PKG_NAME")`).
+type ExpansionKey = (String, tt::Subtree, Option<tt::Subtree>, Vec<(String, String)>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpansionFailure {
+    /// The macro panicked, or our side of the bridge did.
+    Panicked(String),
+    /// The expansion didn't finish in time, or an earlier one of the same
+    /// library didn't.
+    TimedOut(String),
+}
+
 pub struct Expander {
-    inner: ProcMacroLibraryLibloading,
+    inner: Arc<ProcMacroLibraryLibloading>,
+    cache: LruCache<ExpansionKey, Result<tt::Subtree, String>>,
+    /// The macro whose expansion timed out. It might still be running, so we
+    /// don't call into the library anymore.
+    poisoned_by: Option<String>,
 }
 
 impl Expander {
@@ -143,17 +168,60 @@ impl Expander {
 
         let library = ProcMacroLibraryLibloading::open(lib.as_ref())?;
 
-        Ok(Expander { inner: library })
+        Ok(Expander {
+            inner: Arc::new(library),
+            cache: LruCache::new(EXPANSION_CACHE_CAPACITY),
+            poisoned_by: None,
+        })
     }
 
     pub fn expand(
-        &self,
+        &mut self,
         macro_name: &str,
         macro_body: &tt::Subtree,
         attributes: Option<&tt::Subtree>,
-    ) -> Result<tt::Subtree, String> {
-        let result = self.inner.abi.expand(macro_name, macro_body, attributes);
-        result.map_err(|e| e.as_str().unwrap_or_else(|| "<unknown error>".to_string()))
+        env: &[(String, String)],
+        timeout: Option<Duration>,
+    ) -> Result<tt::Subtree, ExpansionFailure> {
+        if let Some(poisoned_by) = &self.poisoned_by {
+            return Err(ExpansionFailure::TimedOut(format!(
+                "macros of this library are disabled until it is rebuilt, as `{}` timed out",
+                poisoned_by
+            )));
+        }
+        let key = (macro_name.to_string(), macro_body.clone(), attributes.cloned(), env.to_vec());
+        if let Some(result) = self.cache.get(&key) {
+            return result.map_err(ExpansionFailure::Panicked);
+        }
+
+        let inner = self.inner.clone();
+        let (name, body, attrs) = (key.0.clone(), key.1.clone(), key.2.clone());
+        let result = run_guarded(timeout, move || {
+            inner
+                .abi
+                .expand(&name, &body, attrs.as_ref())
+                .map_err(|e| e.as_str().unwrap_or_else(|| "<unknown error>".to_string()))
+        });
+        let result = match result {
+            Ok(result) => result,
+            Err(WatchdogError::Panicked(msg)) => Err(msg),
+            Err(WatchdogError::TimedOut) => {
+                self.poisoned_by = Some(macro_name.to_string());
+                return Err(ExpansionFailure::TimedOut(format!(
+                    "`{}` did not finish within {:?}, macros of this library are disabled until it is rebuilt",
+                    macro_name,
+                    timeout.unwrap_or_default()
+                )));
+            }
+        };
+        self.cache.insert(key, result.clone());
+        result.map_err(ExpansionFailure::Panicked)
+    }
+
+    /// Number of expansions which were actually performed by the library.
+    #[cfg(test)]
+    pub(crate) fn cache_misses(&self) -> u64 {
+        self.cache.misses
     }
 
     pub fn list_macros(&self) -> Vec<(String, ProcMacroKind)> {
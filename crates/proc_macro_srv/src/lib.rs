@@ -12,25 +12,35 @@
 #![allow(unreachable_pub)]
 
 mod dylib;
+mod cache;
+mod watchdog;
 
 mod abis;
 
-use proc_macro_api::{ExpansionResult, ExpansionTask, ListMacrosResult, ListMacrosTask};
+use paths::AbsPathBuf;
+use proc_macro_api::{
+    msg::{ErrorCode, ResponseError},
+    ExpansionResult, ExpansionTask, ExpansionTiming, ExpansionTimingsResult, ListMacrosResult,
+    ListMacrosTask,
+};
 use std::{
     collections::{hash_map::Entry, HashMap},
     env, fs,
     path::{Path, PathBuf},
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
 
 #[derive(Default)]
 pub(crate) struct ProcMacroSrv {
     expanders: HashMap<(PathBuf, SystemTime), dylib::Expander>,
+    timings: HashMap<(AbsPathBuf, String), ExpansionTiming>,
 }
 
 impl ProcMacroSrv {
-    pub fn expand(&mut self, task: &ExpansionTask) -> Result<ExpansionResult, String> {
-        let expander = self.expander(task.lib.as_ref())?;
+    pub fn expand(&mut self, task: &ExpansionTask) -> Result<ExpansionResult, ResponseError> {
+        let expander = self
+            .expander(task.lib.as_ref())
+            .map_err(|message| ResponseError { code: ErrorCode::ExpansionError, message })?;
 
         let mut prev_env = HashMap::new();
         for (k, v) in &task.env {
@@ -38,7 +48,15 @@ impl ProcMacroSrv {
             env::set_var(k, v);
         }
 
-        let result = expander.expand(&task.macro_name, &task.macro_body, task.attributes.as_ref());
+        let start = Instant::now();
+        let result = expander.expand(
+            &task.macro_name,
+            &task.macro_body,
+            task.attributes.as_ref(),
+            &task.env,
+            task.timeout,
+        );
+        self.record_timing(task, start.elapsed());
 
         for (k, _) in &task.env {
             match &prev_env[k.as_str()] {
@@ -49,16 +67,45 @@ impl ProcMacroSrv {
 
         match result {
             Ok(expansion) => Ok(ExpansionResult { expansion }),
-            Err(msg) => Err(format!("proc-macro panicked: {}", msg)),
+            Err(dylib::ExpansionFailure::Panicked(msg)) => Err(ResponseError {
+                code: ErrorCode::ExpansionError,
+                message: format!("proc-macro panicked: {}", msg),
+            }),
+            Err(dylib::ExpansionFailure::TimedOut(message)) => {
+                Err(ResponseError { code: ErrorCode::ExpansionTimeout, message })
+            }
         }
     }
 
+    /// Returns the time spent in each macro expanded so far, slowest first.
+    pub fn expansion_timings(&self) -> ExpansionTimingsResult {
+        let mut timings = self.timings.values().cloned().collect::<Vec<_>>();
+        timings.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.macro_name.cmp(&b.macro_name)));
+        ExpansionTimingsResult { timings }
+    }
+
+    fn record_timing(&mut self, task: &ExpansionTask, elapsed: Duration) {
+        let timing = self
+            .timings
+            .entry((task.lib.clone(), task.macro_name.clone()))
+            .or_insert_with(|| ExpansionTiming {
+                lib: task.lib.clone(),
+                macro_name: task.macro_name.clone(),
+                count: 0,
+                total: Duration::default(),
+                max: Duration::default(),
+            });
+        timing.count += 1;
+        timing.total += elapsed;
+        timing.max = timing.max.max(elapsed);
+    }
+
     pub fn list_macros(&mut self, task: &ListMacrosTask) -> Result<ListMacrosResult, String> {
         let expander = self.expander(task.lib.as_ref())?;
         Ok(ListMacrosResult { macros: expander.list_macros() })
     }
 
-    fn expander(&mut self, path: &Path) -> Result<&dylib::Expander, String> {
+    fn expander(&mut self, path: &Path) -> Result<&mut dylib::Expander, String> {
         let time = fs::metadata(path).and_then(|it| it.modified()).map_err(|err| {
             format!("Failed to get file metadata for {}: {:?}", path.display(), err)
         })?;
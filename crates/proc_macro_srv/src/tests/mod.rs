@@ -2,10 +2,15 @@
 
 #[macro_use]
 mod utils;
+use std::time::Duration;
+
 use expect_test::expect;
 use paths::AbsPathBuf;
+use proc_macro_api::ExpansionTask;
 use utils::*;
 
+use crate::{dylib::ExpansionFailure, ProcMacroSrv};
+
 #[test]
 fn test_derive_empty() {
     assert_expand("DeriveEmpty", r#"struct S;"#, expect![[r#"SUBTREE $"#]]);
@@ -56,6 +61,36 @@ fn test_fn_like_macro2() {
     );
 }
 
+/// Round-trips literals, doc comments and groups through the bridge of the ABI
+/// matching the toolchain the test macros are built with.
+#[test]
+fn test_fn_like_macro_clone_tokens() {
+    assert_expand(
+        "fn_like_clone_tokens",
+        r#"/// doc
+foo(1u16, [b'x' 2.5f32], { "s" 'c' });"#,
+        expect![[r#"
+            SUBTREE $
+              PUNCH   # [alone] 4294967295
+              SUBTREE [] 4294967295
+                IDENT   doc 4294967295
+                PUNCH   = [alone] 4294967295
+                LITERAL " doc" 4294967295
+              IDENT   foo 4294967295
+              SUBTREE () 4294967295
+                LITERAL 1u16 4294967295
+                PUNCH   , [alone] 4294967295
+                SUBTREE [] 4294967295
+                  LITERAL b'x' 4294967295
+                  LITERAL 2.5f32 4294967295
+                PUNCH   , [alone] 4294967295
+                SUBTREE {} 4294967295
+                  LITERAL "s" 4294967295
+                  LITERAL 'c' 4294967295
+              PUNCH   ; [alone] 4294967295"#]],
+    );
+}
+
 #[test]
 fn test_attr_macro() {
     // Corresponds to
@@ -84,6 +119,7 @@ fn list_test_macros() {
         fn_like_noop [FuncLike]
         fn_like_panic [FuncLike]
         fn_like_error [FuncLike]
+        fn_like_sleep [FuncLike]
         fn_like_clone_tokens [FuncLike]
         attr_noop [Attr]
         attr_panic [Attr]
@@ -100,3 +136,79 @@ fn test_version_check() {
     let info = proc_macro_api::read_dylib_info(&path).unwrap();
     assert!(info.version.1 >= 50);
 }
+
+#[test]
+fn records_expansion_timings() {
+    let task = ExpansionTask {
+        macro_body: tt::Subtree::default(),
+        macro_name: "fn_like_noop".to_string(),
+        attributes: None,
+        lib: AbsPathBuf::assert(fixtures::proc_macro_test_dylib_path()),
+        env: Vec::new(),
+        timeout: None,
+    };
+    let mut srv = ProcMacroSrv::default();
+    srv.expand(&task).unwrap();
+    srv.expand(&task).unwrap();
+
+    let timings = srv.expansion_timings().timings;
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0].macro_name, "fn_like_noop");
+    assert_eq!(timings[0].count, 2);
+    assert!(timings[0].max <= timings[0].total);
+}
+
+#[test]
+fn repeated_expansion_hits_the_cache() {
+    let mut expander =
+        crate::dylib::Expander::new(&fixtures::proc_macro_test_dylib_path()).unwrap();
+    let body = tt::Subtree::default();
+
+    let first = expander.expand("fn_like_clone_tokens", &body, None, &[], None);
+    let second = expander.expand("fn_like_clone_tokens", &body, None, &[], None);
+    assert_eq!(first, second);
+    assert_eq!(expander.cache_misses(), 1);
+
+    let attr = tt::Subtree::default();
+    expander.expand("attr_noop", &body, Some(&attr), &[], None).unwrap();
+    expander.expand("attr_noop", &body, None, &[], None).unwrap();
+    assert_eq!(expander.cache_misses(), 3);
+}
+
+#[test]
+fn expansion_cache_respects_env() {
+    let mut expander =
+        crate::dylib::Expander::new(&fixtures::proc_macro_test_dylib_path()).unwrap();
+    let body = tt::Subtree::default();
+    let env = |name: &str| vec![("CARGO_PKG_NAME".to_string(), name.to_string())];
+
+    expander.expand("fn_like_noop", &body, None, &env("foo"), None).unwrap();
+    expander.expand("fn_like_noop", &body, None, &env("bar"), None).unwrap();
+    assert_eq!(expander.cache_misses(), 2);
+    expander.expand("fn_like_noop", &body, None, &env("foo"), None).unwrap();
+    assert_eq!(expander.cache_misses(), 2);
+}
+
+#[test]
+fn panics_are_reported_as_errors() {
+    let mut expander =
+        crate::dylib::Expander::new(&fixtures::proc_macro_test_dylib_path()).unwrap();
+    let body = tt::Subtree::default();
+
+    let res = expander.expand("fn_like_panic", &body, None, &[], Some(Duration::from_secs(10)));
+    assert!(matches!(res, Err(ExpansionFailure::Panicked(_))), "{:?}", res);
+    // The library is still usable afterwards.
+    expander.expand("fn_like_noop", &body, None, &[], Some(Duration::from_secs(10))).unwrap();
+}
+
+#[test]
+fn timeout_disables_the_library() {
+    let mut expander =
+        crate::dylib::Expander::new(&fixtures::proc_macro_test_dylib_path()).unwrap();
+    let body = tt::Subtree::default();
+
+    let res = expander.expand("fn_like_sleep", &body, None, &[], Some(Duration::from_millis(100)));
+    assert!(matches!(res, Err(ExpansionFailure::TimedOut(_))), "{:?}", res);
+    let res = expander.expand("fn_like_noop", &body, None, &[], Some(Duration::from_secs(10)));
+    assert!(matches!(res, Err(ExpansionFailure::TimedOut(_))), "{:?}", res);
+}
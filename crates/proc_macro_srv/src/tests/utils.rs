@@ -33,11 +33,12 @@ pub fn assert_expand_attr(macro_name: &str, ra_fixture: &str, attr_args: &str, e
 
 fn assert_expand_impl(macro_name: &str, input: &str, attr: Option<&str>, expect: Expect) {
     let path = fixtures::proc_macro_test_dylib_path();
-    let expander = dylib::Expander::new(&path).unwrap();
+    let mut expander = dylib::Expander::new(&path).unwrap();
     let fixture = parse_string(input).unwrap();
     let attr = attr.map(|attr| parse_string(attr).unwrap().into_subtree());
 
-    let res = expander.expand(macro_name, &fixture.into_subtree(), attr.as_ref()).unwrap();
+    let res =
+        expander.expand(macro_name, &fixture.into_subtree(), attr.as_ref(), &[], None).unwrap();
     expect.assert_eq(&format!("{:?}", res));
 }
 
@@ -0,0 +1,102 @@
+//! Runs expansions so that a misbehaving proc macro can't take the server down.
+//!
+//! Panics are caught and turned into errors. With a timeout, the expansion
+//! runs on a thread of its own, which we stop waiting for once the timeout
+//! elapses. There is no way to stop the thread itself, so it keeps running in
+//! the background; the caller is expected to stop using the library the macro
+//! came from.
+
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+/// Proc macros can recurse deeply, so give them as much stack as the main thread has.
+const EXPANSION_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WatchdogError {
+    Panicked(String),
+    TimedOut,
+}
+
+/// Runs `f`, giving up on it after `timeout`.
+pub(crate) fn run_guarded<T, F>(timeout: Option<Duration>, f: F) -> Result<T, WatchdogError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let timeout = match timeout {
+        Some(it) => it,
+        None => return catch_panic(f),
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name("ProcMacroExpansion".into())
+        .stack_size(EXPANSION_STACK_SIZE)
+        .spawn(move || {
+            // The receiver is gone if we timed out, nobody is interested in the result then.
+            let _ = sender.send(catch_panic(f));
+        });
+    if let Err(err) = spawned {
+        return Err(WatchdogError::Panicked(format!("failed to spawn expansion thread: {}", err)));
+    }
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(WatchdogError::TimedOut),
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            Err(WatchdogError::Panicked("expansion thread exited unexpectedly".to_string()))
+        }
+    }
+}
+
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, WatchdogError> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| WatchdogError::Panicked(panic_message(&*payload)))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<unknown error>".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn returns_result_in_time() {
+        assert_eq!(run_guarded(Some(Duration::from_secs(10)), || 92), Ok(92));
+        assert_eq!(run_guarded(None, || 92), Ok(92));
+    }
+
+    #[test]
+    fn catches_panics() {
+        for timeout in [None, Some(Duration::from_secs(10))] {
+            let res: Result<(), _> = run_guarded(timeout, || panic!("boom {}", 92));
+            assert_eq!(res, Err(WatchdogError::Panicked("boom 92".to_string())));
+        }
+    }
+
+    #[test]
+    fn gives_up_on_slow_expansions() {
+        let start = Instant::now();
+        let res = run_guarded(Some(Duration::from_millis(50)), || {
+            thread::sleep(Duration::from_secs(60));
+        });
+        assert_eq!(res, Err(WatchdogError::TimedOut));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -1,6 +1,6 @@
 //! Exports a few trivial procedural macros for testing.
 
-use proc_macro::{Group, Ident, Punct, TokenStream, TokenTree};
+use proc_macro::{Group, Ident, Literal, Punct, TokenStream, TokenTree};
 
 #[proc_macro]
 pub fn fn_like_noop(args: TokenStream) -> TokenStream {
@@ -17,6 +17,12 @@ pub fn fn_like_error(args: TokenStream) -> TokenStream {
     format!("compile_error!(\"fn_like_error!({})\");", args).parse().unwrap()
 }
 
+#[proc_macro]
+pub fn fn_like_sleep(args: TokenStream) -> TokenStream {
+    std::thread::sleep(std::time::Duration::from_secs(60 * 60));
+    args
+}
+
 #[proc_macro]
 pub fn fn_like_clone_tokens(args: TokenStream) -> TokenStream {
     clone_stream(args)
@@ -69,6 +75,10 @@ fn clone_tree(t: TokenTree) -> TokenTree {
             new.set_span(orig.span());
             TokenTree::Punct(new)
         }
-        TokenTree::Literal(_orig) => unimplemented!(),
+        TokenTree::Literal(orig) => {
+            let mut new: Literal = orig.to_string().parse().unwrap();
+            new.set_span(orig.span());
+            TokenTree::Literal(new)
+        }
     }
 }
@@ -11,7 +11,7 @@ use std::cell::RefCell;
 
 pub use crate::{
     hprof::{heartbeat, heartbeat_span, init, init_from, span},
-    memory_usage::{Bytes, MemoryUsage},
+    memory_usage::{process_rss, Bytes, MemoryUsage},
     stop_watch::{StopWatch, StopWatchSpan},
 };
 
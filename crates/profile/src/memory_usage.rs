@@ -56,6 +56,22 @@ impl MemoryUsage {
     }
 }
 
+/// Returns the resident set size of another process, if the platform allows
+/// querying it.
+pub fn process_rss(pid: u32) -> Option<Bytes> {
+    cfg_if! {
+        if #[cfg(target_os = "linux")] {
+            let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+            let line = status.lines().find_map(|it| it.strip_prefix("VmRSS:"))?;
+            let kb = line.trim().strip_suffix("kB")?.trim().parse::<isize>().ok()?;
+            Some(Bytes(kb * 1024))
+        } else {
+            let _ = pid;
+            None
+        }
+    }
+}
+
 #[cfg(all(target_os = "linux", target_env = "gnu", not(feature = "jemalloc")))]
 fn memusage_linux() -> MemoryUsage {
     // Linux/glibc has 2 APIs for allocator introspection that we can use: mallinfo and mallinfo2.
@@ -119,6 +135,18 @@ impl std::ops::AddAssign<usize> for Bytes {
     }
 }
 
+impl std::ops::AddAssign for Bytes {
+    fn add_assign(&mut self, x: Bytes) {
+        self.0 += x.0;
+    }
+}
+
+impl From<Bytes> for isize {
+    fn from(bytes: Bytes) -> isize {
+        bytes.0
+    }
+}
+
 impl std::ops::Sub for Bytes {
     type Output = Bytes;
     fn sub(self, rhs: Bytes) -> Bytes {
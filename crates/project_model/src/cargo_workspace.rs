@@ -378,6 +378,54 @@ impl CargoWorkspace {
         &self.workspace_root
     }
 
+    /// Finds the member package owning the file at `path`, along with the lib or bin target of
+    /// that package the file belongs to, if the directory layout tells it apart.
+    ///
+    /// Returns `None` if the file doesn't belong to a single member package, like a build script
+    /// or a file outside of any package.
+    pub fn package_for_file(&self, path: &AbsPath) -> Option<(Package, Option<Target>)> {
+        let depth = |dir: &AbsPath| AsRef::<std::path::Path>::as_ref(dir).components().count();
+
+        let package = self
+            .packages()
+            .filter(|&pkg| self[pkg].is_member && path.starts_with(self[pkg].manifest.parent()))
+            .max_by_key(|&pkg| depth(self[pkg].manifest.parent()))?;
+        let targets = &self[package].targets;
+        if let Some(&target) = targets.iter().find(|&&it| &self[it].root == path) {
+            return match self[target].kind {
+                TargetKind::BuildScript => None,
+                TargetKind::Lib | TargetKind::Bin => Some((package, Some(target))),
+                _ => Some((package, None)),
+            };
+        }
+
+        // Modules live next to, or below, the root file of their target. If several targets
+        // share the closest such directory, like `src/lib.rs` and `src/main.rs` do, the file
+        // could belong to any of them.
+        let mut candidates = Vec::new();
+        let mut candidate_depth = 0;
+        for &target in targets {
+            let dir = match self[target].root.parent() {
+                Some(it) if path.starts_with(it) => it,
+                _ => continue,
+            };
+            if depth(dir) > candidate_depth {
+                candidates.clear();
+                candidate_depth = depth(dir);
+            }
+            if depth(dir) == candidate_depth {
+                candidates.push(target);
+            }
+        }
+        let target = match candidates[..] {
+            [target] if matches!(self[target].kind, TargetKind::Lib | TargetKind::Bin) => {
+                Some(target)
+            }
+            _ => None,
+        };
+        Some((package, target))
+    }
+
     pub fn package_flag(&self, package: &PackageData) -> String {
         if self.is_unique(&*package.name) {
             package.name.clone()
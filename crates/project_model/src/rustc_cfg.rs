@@ -3,6 +3,7 @@
 use std::process::Command;
 
 use anyhow::Result;
+use base_db::{Endian, TargetInfo};
 
 use crate::{cfg_flag::CfgFlag, utf8_stdout, ManifestPath};
 
@@ -26,6 +27,28 @@ pub(crate) fn get(cargo_toml: Option<&ManifestPath>, target: Option<&str>) -> Ve
     res
 }
 
+/// Extracts the target information from the cfgs reported by `rustc`, falling back to guessing it
+/// from the target triple or to the host if `rustc` didn't tell.
+pub(crate) fn target_info(rustc_cfg: &[CfgFlag], triple: Option<&str>) -> TargetInfo {
+    let mut res = triple.map_or_else(TargetInfo::host, TargetInfo::from_triple);
+    for flag in rustc_cfg {
+        if let CfgFlag::KeyValue { key, value } = flag {
+            match key.as_str() {
+                "target_pointer_width" => {
+                    if let Ok(width) = value.parse() {
+                        res.pointer_width = width;
+                    }
+                }
+                "target_endian" => {
+                    res.endian = if value == "big" { Endian::Big } else { Endian::Little };
+                }
+                _ => (),
+            }
+        }
+    }
+    res
+}
+
 fn get_rust_cfgs(cargo_toml: Option<&ManifestPath>, target: Option<&str>) -> Result<String> {
     let cargo_rust_cfgs = match cargo_toml {
         Some(cargo_toml) => {
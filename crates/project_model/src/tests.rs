@@ -5,7 +5,7 @@ use std::{
 
 use base_db::{CrateGraph, FileId};
 use expect_test::{expect, Expect};
-use paths::AbsPath;
+use paths::{AbsPath, AbsPathBuf};
 use serde::de::DeserializeOwned;
 
 use crate::{
@@ -14,24 +14,30 @@ use crate::{
 };
 
 fn load_cargo(file: &str) -> CrateGraph {
-    let meta = get_test_json_file(file);
-    let cargo_workspace = CargoWorkspace::new(meta);
+    let cargo_workspace = load_cargo_workspace(file);
     let project_workspace = ProjectWorkspace::Cargo {
         cargo: cargo_workspace,
         build_scripts: WorkspaceBuildScripts::default(),
         sysroot: Sysroot::default(),
         rustc: None,
         rustc_cfg: Vec::new(),
+        target: None,
         cfg_overrides: CfgOverrides::default(),
     };
     to_crate_graph(project_workspace)
 }
 
+fn load_cargo_workspace(file: &str) -> CargoWorkspace {
+    let meta = get_test_json_file(file);
+    CargoWorkspace::new(meta)
+}
+
 fn load_rust_project(file: &str) -> CrateGraph {
     let data = get_test_json_file(file);
     let project = rooted_project_json(data);
     let sysroot = Some(get_fake_sysroot());
-    let project_workspace = ProjectWorkspace::Json { project, sysroot, rustc_cfg: Vec::new() };
+    let project_workspace =
+        ProjectWorkspace::Json { project, sysroot, rustc_cfg: Vec::new(), target: None };
     to_crate_graph(project_workspace)
 }
 
@@ -134,6 +140,11 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -164,6 +175,7 @@ fn cargo_hello_world_project_model() {
                             },
                         ],
                         proc_macro: [],
+                        origin: Workspace,
                     },
                     CrateId(
                         5,
@@ -201,6 +213,11 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -231,6 +248,7 @@ fn cargo_hello_world_project_model() {
                             },
                         ],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         2,
@@ -259,6 +277,11 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -297,6 +320,7 @@ fn cargo_hello_world_project_model() {
                             },
                         ],
                         proc_macro: [],
+                        origin: Workspace,
                     },
                     CrateId(
                         4,
@@ -334,6 +358,11 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -355,6 +384,7 @@ fn cargo_hello_world_project_model() {
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         1,
@@ -383,6 +413,11 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -421,6 +456,7 @@ fn cargo_hello_world_project_model() {
                             },
                         ],
                         proc_macro: [],
+                        origin: Workspace,
                     },
                     CrateId(
                         6,
@@ -458,6 +494,11 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -479,6 +520,7 @@ fn cargo_hello_world_project_model() {
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         3,
@@ -507,6 +549,11 @@ fn cargo_hello_world_project_model() {
                                 "test",
                             ],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {
                                 "CARGO_PKG_LICENSE": "",
@@ -545,6 +592,7 @@ fn cargo_hello_world_project_model() {
                             },
                         ],
                         proc_macro: [],
+                        origin: Workspace,
                     },
                 },
             }"#]],
@@ -580,6 +628,11 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
@@ -594,6 +647,7 @@ fn rust_project_hello_world_project_model() {
                             },
                         ],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         10,
@@ -616,11 +670,17 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         7,
@@ -643,11 +703,17 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         4,
@@ -670,6 +736,11 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
@@ -684,6 +755,7 @@ fn rust_project_hello_world_project_model() {
                             },
                         ],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         1,
@@ -706,11 +778,17 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         11,
@@ -733,6 +811,11 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
@@ -763,6 +846,7 @@ fn rust_project_hello_world_project_model() {
                             },
                         ],
                         proc_macro: [],
+                        origin: Workspace,
                     },
                     CrateId(
                         8,
@@ -785,11 +869,17 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         5,
@@ -812,11 +902,17 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         2,
@@ -839,11 +935,17 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         9,
@@ -866,11 +968,17 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         6,
@@ -893,6 +1001,11 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
@@ -971,6 +1084,7 @@ fn rust_project_hello_world_project_model() {
                             },
                         ],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                     CrateId(
                         3,
@@ -993,11 +1107,17 @@ fn rust_project_hello_world_project_model() {
                         potential_cfg_options: CfgOptions(
                             [],
                         ),
+                        target: TargetInfo {
+                            triple: None,
+                            pointer_width: 64,
+                            endian: Little,
+                        },
                         env: Env {
                             entries: {},
                         },
                         dependencies: [],
                         proc_macro: [],
+                        origin: Dependency,
                     },
                 },
             }"#]],
@@ -1015,3 +1135,35 @@ fn rust_project_is_proc_macro_has_proc_macro_dep() {
     // on the proc_macro sysroot crate.
     crate_data.dependencies.iter().find(|&dep| dep.name.deref() == "proc_macro").unwrap();
 }
+
+#[test]
+fn cargo_hello_world_package_for_file() {
+    let cargo = load_cargo_workspace("hello-world-metadata.json");
+    let root = if cfg!(windows) { r#"C:\ROOT\"# } else { "/ROOT/" };
+    let package_for_file = |path: &str| {
+        let path = AbsPathBuf::assert(PathBuf::from(format!("{}{}", root, path)));
+        cargo.package_for_file(&path).map(|(package, target)| {
+            let target = target.map(|it| format!("{} {:?}", cargo[it].name, cargo[it].kind));
+            (cargo[package].name.clone(), target)
+        })
+    };
+    let in_hello_world =
+        |target: Option<&str>| Some(("hello-world".to_string(), target.map(|it| it.to_string())));
+
+    assert_eq!(package_for_file("hello-world/src/lib.rs"), in_hello_world(Some("hello-world Lib")));
+    assert_eq!(
+        package_for_file("hello-world/src/main.rs"),
+        in_hello_world(Some("hello-world Bin"))
+    );
+    // `src/` holds the roots of both the lib and the bin target.
+    assert_eq!(package_for_file("hello-world/src/module.rs"), in_hello_world(None));
+    assert_eq!(package_for_file("hello-world/tests/it.rs"), in_hello_world(None));
+    assert_eq!(package_for_file("hello-world/examples/an-example.rs"), in_hello_world(None));
+    // Neither dependencies nor files outside of the packages belong to a member.
+    assert_eq!(
+        package_for_file(".cargo/registry/src/github.com-1ecc6299db9ec823/libc-0.2.98/src/lib.rs"),
+        None
+    );
+    assert_eq!(package_for_file("build.rs"), None);
+    assert_eq!(package_for_file("hello-world.rs"), None);
+}
@@ -5,7 +5,10 @@
 use std::{collections::VecDeque, convert::TryFrom, fmt, fs, process::Command};
 
 use anyhow::{format_err, Context, Result};
-use base_db::{CrateDisplayName, CrateGraph, CrateId, CrateName, Edition, Env, FileId, ProcMacro};
+use base_db::{
+    CrateDisplayName, CrateGraph, CrateId, CrateName, CrateOrigin, Edition, Env, FileId, ProcMacro,
+    TargetInfo,
+};
 use cfg::{CfgDiff, CfgOptions};
 use paths::{AbsPath, AbsPathBuf};
 use proc_macro_api::ProcMacroClient;
@@ -49,10 +52,17 @@ pub enum ProjectWorkspace {
         /// FIXME: make this a per-crate map, as, eg, build.rs might have a
         /// different target.
         rustc_cfg: Vec<CfgFlag>,
+        /// The target triple passed to `cargo` and `rustc`, `None` for the host.
+        target: Option<String>,
         cfg_overrides: CfgOverrides,
     },
     /// Project workspace was manually specified using a `rust-project.json` file.
-    Json { project: ProjectJson, sysroot: Option<Sysroot>, rustc_cfg: Vec<CfgFlag> },
+    Json {
+        project: ProjectJson,
+        sysroot: Option<Sysroot>,
+        rustc_cfg: Vec<CfgFlag>,
+        target: Option<String>,
+    },
 
     // FIXME: The primary limitation of this approach is that the set of detached files needs to be fixed at the beginning.
     // That's not the end user experience we should strive for.
@@ -77,6 +87,7 @@ impl fmt::Debug for ProjectWorkspace {
                 sysroot,
                 rustc,
                 rustc_cfg,
+                target,
                 cfg_overrides,
             } => f
                 .debug_struct("Cargo")
@@ -88,15 +99,17 @@ impl fmt::Debug for ProjectWorkspace {
                     &rustc.as_ref().map_or(0, |rc| rc.packages().len()),
                 )
                 .field("n_rustc_cfg", &rustc_cfg.len())
+                .field("target", target)
                 .field("n_cfg_overrides", &cfg_overrides.len())
                 .finish(),
-            ProjectWorkspace::Json { project, sysroot, rustc_cfg } => {
+            ProjectWorkspace::Json { project, sysroot, rustc_cfg, target } => {
                 let mut debug_struct = f.debug_struct("Json");
                 debug_struct.field("n_crates", &project.n_crates());
                 if let Some(sysroot) = sysroot {
                     debug_struct.field("n_sysroot_crates", &sysroot.crates().len());
                 }
                 debug_struct.field("n_rustc_cfg", &rustc_cfg.len());
+                debug_struct.field("target", target);
                 debug_struct.finish()
             }
             ProjectWorkspace::DetachedFiles { files, sysroot, rustc_cfg } => f
@@ -173,6 +186,7 @@ impl ProjectWorkspace {
                 };
 
                 let rustc_cfg = rustc_cfg::get(Some(&cargo_toml), config.target.as_deref());
+                let target = config.target.clone();
 
                 let cfg_overrides = config.cfg_overrides();
                 ProjectWorkspace::Cargo {
@@ -181,6 +195,7 @@ impl ProjectWorkspace {
                     sysroot,
                     rustc,
                     rustc_cfg,
+                    target,
                     cfg_overrides,
                 }
             }
@@ -198,7 +213,8 @@ impl ProjectWorkspace {
             None => None,
         };
         let rustc_cfg = rustc_cfg::get(None, target);
-        Ok(ProjectWorkspace::Json { project: project_json, sysroot, rustc_cfg })
+        let target = target.map(ToString::to_string);
+        Ok(ProjectWorkspace::Json { project: project_json, sysroot, rustc_cfg, target })
     }
 
     pub fn load_detached_files(detached_files: Vec<AbsPathBuf>) -> Result<ProjectWorkspace> {
@@ -241,7 +257,7 @@ impl ProjectWorkspace {
     /// the root is a member of the current workspace
     pub fn to_roots(&self) -> Vec<PackageRoot> {
         match self {
-            ProjectWorkspace::Json { project, sysroot, rustc_cfg: _ } => project
+            ProjectWorkspace::Json { project, sysroot, rustc_cfg: _, target: _ } => project
                 .crates()
                 .map(|(_, krate)| PackageRoot {
                     is_member: krate.is_workspace_member,
@@ -263,6 +279,7 @@ impl ProjectWorkspace {
                 sysroot,
                 rustc,
                 rustc_cfg: _,
+                target: _,
                 cfg_overrides: _,
                 build_scripts,
             } => {
@@ -359,22 +376,27 @@ impl ProjectWorkspace {
         };
 
         let mut crate_graph = match self {
-            ProjectWorkspace::Json { project, sysroot, rustc_cfg } => project_json_to_crate_graph(
-                rustc_cfg.clone(),
-                &proc_macro_loader,
-                load,
-                project,
-                sysroot,
-            ),
+            ProjectWorkspace::Json { project, sysroot, rustc_cfg, target } => {
+                project_json_to_crate_graph(
+                    rustc_cfg.clone(),
+                    target.as_deref(),
+                    &proc_macro_loader,
+                    load,
+                    project,
+                    sysroot,
+                )
+            }
             ProjectWorkspace::Cargo {
                 cargo,
                 sysroot,
                 rustc,
                 rustc_cfg,
+                target,
                 cfg_overrides,
                 build_scripts,
             } => cargo_to_crate_graph(
                 rustc_cfg.clone(),
+                &rustc_cfg::target_info(rustc_cfg, target.as_deref()),
                 cfg_overrides,
                 &proc_macro_loader,
                 load,
@@ -398,15 +420,17 @@ impl ProjectWorkspace {
 
 fn project_json_to_crate_graph(
     rustc_cfg: Vec<CfgFlag>,
+    target: Option<&str>,
     proc_macro_loader: &dyn Fn(&AbsPath) -> Vec<ProcMacro>,
     load: &mut dyn FnMut(&AbsPath) -> Option<FileId>,
     project: &ProjectJson,
     sysroot: &Option<Sysroot>,
 ) -> CrateGraph {
     let mut crate_graph = CrateGraph::default();
-    let sysroot_deps = sysroot
-        .as_ref()
-        .map(|sysroot| sysroot_to_crate_graph(&mut crate_graph, sysroot, rustc_cfg.clone(), load));
+    let sysroot_target = rustc_cfg::target_info(&rustc_cfg, target);
+    let sysroot_deps = sysroot.as_ref().map(|sysroot| {
+        sysroot_to_crate_graph(&mut crate_graph, sysroot, rustc_cfg.clone(), &sysroot_target, load)
+    });
 
     let mut cfg_cache: FxHashMap<&str, Vec<CfgFlag>> = FxHashMap::default();
     let crates: FxHashMap<CrateId, CrateId> = project
@@ -426,6 +450,8 @@ fn project_json_to_crate_graph(
                 }
                 None => &rustc_cfg,
             };
+            let target_info =
+                rustc_cfg::target_info(target_cfgs, krate.target.as_deref().or(target));
 
             let mut cfg_options = CfgOptions::default();
             cfg_options.extend(target_cfgs.iter().chain(krate.cfg.iter()).cloned());
@@ -437,8 +463,14 @@ fn project_json_to_crate_graph(
                     krate.display_name.clone(),
                     cfg_options.clone(),
                     cfg_options,
+                    target_info,
                     env,
                     proc_macro.unwrap_or_default(),
+                    if krate.is_workspace_member {
+                        CrateOrigin::Workspace
+                    } else {
+                        CrateOrigin::Dependency
+                    },
                 ),
             )
         })
@@ -474,6 +506,7 @@ fn project_json_to_crate_graph(
 
 fn cargo_to_crate_graph(
     rustc_cfg: Vec<CfgFlag>,
+    target: &TargetInfo,
     override_cfg: &CfgOverrides,
     proc_macro_loader: &dyn Fn(&AbsPath) -> Vec<ProcMacro>,
     load: &mut dyn FnMut(&AbsPath) -> Option<FileId>,
@@ -485,7 +518,7 @@ fn cargo_to_crate_graph(
     let _p = profile::span("cargo_to_crate_graph");
     let mut crate_graph = CrateGraph::default();
     let (public_deps, libproc_macro) =
-        sysroot_to_crate_graph(&mut crate_graph, sysroot, rustc_cfg.clone(), load);
+        sysroot_to_crate_graph(&mut crate_graph, sysroot, rustc_cfg.clone(), target, load);
 
     let mut cfg_options = CfgOptions::default();
     cfg_options.extend(rustc_cfg);
@@ -525,9 +558,15 @@ fn cargo_to_crate_graph(
                     &cargo[pkg],
                     build_scripts.outputs.get(pkg),
                     &cfg_options,
+                    target,
                     proc_macro_loader,
                     file_id,
                     &cargo[tgt].name,
+                    if cargo[pkg].is_member {
+                        CrateOrigin::Workspace
+                    } else {
+                        CrateOrigin::Dependency
+                    },
                 );
                 if cargo[tgt].kind == TargetKind::Lib {
                     lib_tgt = Some((crate_id, cargo[tgt].name.clone()));
@@ -596,6 +635,7 @@ fn cargo_to_crate_graph(
                 load,
                 &mut crate_graph,
                 &cfg_options,
+                target,
                 proc_macro_loader,
                 &mut pkg_to_lib_crate,
                 &public_deps,
@@ -615,8 +655,9 @@ fn detached_files_to_crate_graph(
 ) -> CrateGraph {
     let _p = profile::span("detached_files_to_crate_graph");
     let mut crate_graph = CrateGraph::default();
+    let target = rustc_cfg::target_info(&rustc_cfg, None);
     let (public_deps, _libproc_macro) =
-        sysroot_to_crate_graph(&mut crate_graph, sysroot, rustc_cfg.clone(), load);
+        sysroot_to_crate_graph(&mut crate_graph, sysroot, rustc_cfg.clone(), &target, load);
 
     let mut cfg_options = CfgOptions::default();
     cfg_options.extend(rustc_cfg);
@@ -639,8 +680,10 @@ fn detached_files_to_crate_graph(
             display_name,
             cfg_options.clone(),
             cfg_options.clone(),
+            target.clone(),
             Env::default(),
             Vec::new(),
+            CrateOrigin::Workspace,
         );
 
         for (name, krate) in public_deps.iter() {
@@ -655,6 +698,7 @@ fn handle_rustc_crates(
     load: &mut dyn FnMut(&AbsPath) -> Option<FileId>,
     crate_graph: &mut CrateGraph,
     cfg_options: &CfgOptions,
+    target: &TargetInfo,
     proc_macro_loader: &dyn Fn(&AbsPath) -> Vec<ProcMacro>,
     pkg_to_lib_crate: &mut FxHashMap<la_arena::Idx<crate::PackageData>, CrateId>,
     public_deps: &[(CrateName, CrateId)],
@@ -691,9 +735,11 @@ fn handle_rustc_crates(
                         &rustc_workspace[pkg],
                         None,
                         cfg_options,
+                        target,
                         proc_macro_loader,
                         file_id,
                         &rustc_workspace[tgt].name,
+                        CrateOrigin::Dependency,
                     );
                     pkg_to_lib_crate.insert(pkg, crate_id);
                     // Add dependencies on core / std / alloc for this crate
@@ -747,9 +793,11 @@ fn add_target_crate_root(
     pkg: &PackageData,
     build_data: Option<&BuildScriptOutput>,
     cfg_options: &CfgOptions,
+    target: &TargetInfo,
     proc_macro_loader: &dyn Fn(&AbsPath) -> Vec<ProcMacro>,
     file_id: FileId,
     cargo_name: &str,
+    origin: CrateOrigin,
 ) -> CrateId {
     let edition = pkg.edition;
     let cfg_options = {
@@ -792,8 +840,10 @@ fn add_target_crate_root(
         Some(display_name),
         cfg_options,
         potential_cfg_options,
+        target.clone(),
         env,
         proc_macro,
+        origin,
     );
 
     crate_id
@@ -803,6 +853,7 @@ fn sysroot_to_crate_graph(
     crate_graph: &mut CrateGraph,
     sysroot: &Sysroot,
     rustc_cfg: Vec<CfgFlag>,
+    target: &TargetInfo,
     load: &mut dyn FnMut(&AbsPath) -> Option<FileId>,
 ) -> (Vec<(CrateName, CrateId)>, Option<CrateId>) {
     let _p = profile::span("sysroot_to_crate_graph");
@@ -822,8 +873,10 @@ fn sysroot_to_crate_graph(
                 Some(display_name),
                 cfg_options.clone(),
                 cfg_options.clone(),
+                target.clone(),
                 env,
                 proc_macro,
+                CrateOrigin::Dependency,
             );
             Some((krate, crate_id))
         })
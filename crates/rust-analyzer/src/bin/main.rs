@@ -8,7 +8,7 @@ use std::{convert::TryFrom, env, fs, path::Path, process};
 
 use lsp_server::Connection;
 use project_model::ProjectManifest;
-use rust_analyzer::{cli::flags, config::Config, from_json, lsp_ext::supports_utf8, Result};
+use rust_analyzer::{cli::flags, config::Config, from_json, OffsetEncoding, Result};
 use vfs::AbsPathBuf;
 
 #[cfg(all(feature = "mimalloc"))]
@@ -85,6 +85,11 @@ fn try_main() -> Result<()> {
         flags::RustAnalyzerCmd::Highlight(cmd) => cmd.run()?,
         flags::RustAnalyzerCmd::AnalysisStats(cmd) => cmd.run(verbosity)?,
         flags::RustAnalyzerCmd::Diagnostics(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::UnresolvedImports(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::Runnables(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::Lsif(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::CrateGraph(cmd) => cmd.run()?,
+        flags::RustAnalyzerCmd::ExpandMacro(cmd) => cmd.run()?,
         flags::RustAnalyzerCmd::Ssr(cmd) => cmd.run()?,
         flags::RustAnalyzerCmd::Search(cmd) => cmd.run()?,
     }
@@ -140,6 +145,12 @@ fn run_server() -> Result<()> {
 
     let (initialize_id, initialize_params) = connection.initialize_start()?;
     log::info!("InitializeParams: {}", initialize_params);
+    // `lsp_types` doesn't know about the standard `positionEncodings` capability yet.
+    let position_encodings = initialize_params
+        .pointer("/capabilities/general/positionEncodings")
+        .cloned()
+        .and_then(|it| serde_json::from_value::<Vec<String>>(it).ok())
+        .unwrap_or_default();
     let initialize_params =
         from_json::<lsp_types::InitializeParams>("InitializeParams", initialize_params)?;
 
@@ -156,11 +167,26 @@ fn run_server() -> Result<()> {
     };
 
     let mut config = Config::new(root_path, initialize_params.capabilities);
+    config.position_encodings = position_encodings;
+    if let Some(workspace_roots) = initialize_params
+        .workspace_folders
+        .map(|workspaces| {
+            workspaces
+                .into_iter()
+                .filter_map(|it| it.uri.to_file_path().ok())
+                .filter_map(|it| AbsPathBuf::try_from(it).ok())
+                .collect::<Vec<_>>()
+        })
+        .filter(|workspaces| !workspaces.is_empty())
+    {
+        config.workspace_roots = workspace_roots;
+    }
     if let Some(json) = initialize_params.initialization_options {
-        config.update(json);
+        config.update(json, None);
     }
 
     let server_capabilities = rust_analyzer::server_capabilities(&config);
+    let encoding = config.offset_encoding();
 
     let initialize_result = lsp_types::InitializeResult {
         capabilities: server_capabilities,
@@ -168,10 +194,14 @@ fn run_server() -> Result<()> {
             name: String::from("rust-analyzer"),
             version: Some(String::from(env!("REV"))),
         }),
-        offset_encoding: if supports_utf8(&config.caps) { Some("utf-8".to_string()) } else { None },
+        offset_encoding: match encoding {
+            OffsetEncoding::Utf16 => None,
+            encoding => Some(encoding.as_str().to_string()),
+        },
     };
 
-    let initialize_result = serde_json::to_value(initialize_result).unwrap();
+    let mut initialize_result = serde_json::to_value(initialize_result).unwrap();
+    initialize_result["capabilities"]["positionEncoding"] = encoding.as_str().into();
 
     connection.initialize_finish(initialize_id, initialize_result)?;
 
@@ -180,22 +210,10 @@ fn run_server() -> Result<()> {
     }
 
     if config.linked_projects().is_empty() && config.detached_files().is_empty() {
-        let workspace_roots = initialize_params
-            .workspace_folders
-            .map(|workspaces| {
-                workspaces
-                    .into_iter()
-                    .filter_map(|it| it.uri.to_file_path().ok())
-                    .filter_map(|it| AbsPathBuf::try_from(it).ok())
-                    .collect::<Vec<_>>()
-            })
-            .filter(|workspaces| !workspaces.is_empty())
-            .unwrap_or_else(|| vec![config.root_path.clone()]);
-
-        let discovered = ProjectManifest::discover_all(&workspace_roots);
+        let discovered = ProjectManifest::discover_all(&config.workspace_roots);
         log::info!("discovered projects: {:?}", discovered);
         if discovered.is_empty() {
-            log::error!("failed to find any projects in {:?}", workspace_roots);
+            log::error!("failed to find any projects in {:?}", config.workspace_roots);
         }
         config.discovered_projects = Some(discovered);
     }
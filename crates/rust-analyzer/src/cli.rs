@@ -7,14 +7,23 @@ mod symbols;
 mod highlight;
 mod analysis_stats;
 mod diagnostics;
+mod unresolved_imports;
+mod runnables;
+mod lsif;
+mod crate_graph;
+mod expand_macro;
 mod ssr;
 
+mod diff;
 mod progress_report;
 
 use std::io::Read;
 
 use anyhow::Result;
-use ide::AnalysisHost;
+use hir::{db::HirDatabase, Crate, Module};
+use ide::{AnalysisHost, RootDatabase};
+use ide_db::base_db::salsa::{self, ParallelDatabase};
+use rayon::prelude::*;
 use vfs::Vfs;
 
 #[derive(Clone, Copy)]
@@ -40,6 +49,43 @@ fn read_stdin() -> Result<String> {
     Ok(buff)
 }
 
+fn all_modules(db: &dyn HirDatabase) -> Vec<Module> {
+    let mut worklist: Vec<_> =
+        Crate::all(db).into_iter().map(|krate| krate.root_module(db)).collect();
+    let mut modules = Vec::new();
+
+    while let Some(module) = worklist.pop() {
+        modules.push(module);
+        worklist.extend(module.children(db));
+    }
+
+    modules
+}
+
+/// Need to wrap Snapshot to provide `Clone` impl for `map_with`
+struct Snap<DB>(DB);
+impl<DB: ParallelDatabase> Clone for Snap<salsa::Snapshot<DB>> {
+    fn clone(&self) -> Snap<salsa::Snapshot<DB>> {
+        Snap(self.0.snapshot())
+    }
+}
+
+/// Maps `f` over `items`, either on the current thread or, if `parallel` is
+/// set, on the rayon thread pool with a database snapshot per worker. The
+/// results are in the order of `items` either way.
+fn par_map_with_snapshots<T, R, F>(db: &RootDatabase, items: &[T], parallel: bool, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&RootDatabase, &T) -> R + Sync,
+{
+    if parallel {
+        items.par_iter().map_with(Snap(db.snapshot()), |snap, item| f(&snap.0, item)).collect()
+    } else {
+        items.iter().map(|item| f(db, item)).collect()
+    }
+}
+
 fn report_metric(metric: &str, value: u64, unit: &str) {
     if std::env::var("RA_METRICS").is_err() {
         return;
@@ -3,52 +3,82 @@
 
 use std::{
     env,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use hir::{
     db::{AstDatabase, DefDatabase, HirDatabase},
-    AssocItem, Crate, Function, HasSource, HirDisplay, ModuleDef,
+    AssocItem, Crate, DefWithBody, HasSource, HirDisplay, ModuleDef,
 };
-use hir_def::{body::BodySourceMap, expr::ExprId, FunctionId};
+use hir_def::{body::BodySourceMap, expr::ExprId, DefWithBodyId};
 use hir_ty::{TyExt, TypeWalk};
 use ide::{Analysis, AnalysisHost, LineCol, RootDatabase};
-use ide_db::base_db::{
-    salsa::{self, debug::DebugQueryTable, ParallelDatabase},
-    SourceDatabase, SourceDatabaseExt,
-};
+use ide_db::base_db::{salsa::debug::DebugQueryTable, FileId, SourceDatabase, SourceDatabaseExt};
 use itertools::Itertools;
 use oorandom::Rand32;
 use profile::{Bytes, StopWatch};
 use project_model::CargoConfig;
-use rayon::prelude::*;
 use rustc_hash::FxHashSet;
+use serde::Serialize;
 use stdx::format_to;
-use syntax::{AstNode, SyntaxNode};
+use syntax::{AstNode, SyntaxNode, TextRange};
 use vfs::{Vfs, VfsPath};
 
 use crate::cli::{
-    flags,
+    flags::{self, OutputFormat},
     load_cargo::{load_workspace_at, LoadCargoConfig},
-    print_memory_usage,
+    par_map_with_snapshots, print_memory_usage,
     progress_report::ProgressReport,
     report_metric, Result, Verbosity,
 };
 
-/// Need to wrap Snapshot to provide `Clone` impl for `map_with`
-struct Snap<DB>(DB);
-impl<DB: ParallelDatabase> Clone for Snap<salsa::Snapshot<DB>> {
-    fn clone(&self) -> Snap<salsa::Snapshot<DB>> {
-        Snap(self.0.snapshot())
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Summary {
+    crates: usize,
+    modules: usize,
+    declarations: usize,
+    bodies: usize,
+    inference: InferenceStats,
+    phases: Vec<PhaseStats>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InferenceStats {
+    exprs: u64,
+    unknown_types: u64,
+    partially_unknown_types: u64,
+    type_mismatches: u64,
+}
+
+impl std::ops::AddAssign for InferenceStats {
+    fn add_assign(&mut self, other: InferenceStats) {
+        self.exprs += other.exprs;
+        self.unknown_types += other.unknown_types;
+        self.partially_unknown_types += other.partially_unknown_types;
+        self.type_mismatches += other.type_mismatches;
     }
 }
 
+/// Timings of a single phase. `cpu_ms` is the time spent on the individual
+/// items, summed over all threads, so it exceeds `wall_ms` in parallel runs.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhaseStats {
+    name: &'static str,
+    items: usize,
+    wall_ms: f64,
+    cpu_ms: f64,
+}
+
 impl flags::AnalysisStats {
     pub fn run(self, verbosity: Verbosity) -> Result<()> {
         let mut rng = {
             let seed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
             Rand32::new(seed)
         };
+        let output = self.output.unwrap_or(OutputFormat::Text);
 
         let mut db_load_sw = self.stop_watch();
         let mut cargo_config = CargoConfig::default();
@@ -64,67 +94,40 @@ impl flags::AnalysisStats {
         eprintln!("{:<20} {}", "Database loaded:", db_load_sw.elapsed());
 
         let mut analysis_sw = self.stop_watch();
-        let mut num_crates = 0;
-        let mut visited_modules = FxHashSet::default();
-        let mut visit_queue = Vec::new();
-
-        let mut krates = Crate::all(db);
+        let mut krates: Vec<Crate> = Crate::all(db)
+            .into_iter()
+            .filter(|&krate| {
+                let file_id = db.crate_graph()[krate.into()].root_file_id;
+                let source_root = db.source_root(db.file_source_root(file_id));
+                !source_root.is_library || self.with_deps
+            })
+            .collect();
         if self.randomize {
             shuffle(&mut rng, &mut krates);
         }
-        for krate in krates {
-            let module = krate.root_module(db);
-            let file_id = module.definition_source(db).file_id;
-            let file_id = file_id.original_file(db);
-            let source_root = db.file_source_root(file_id);
-            let source_root = db.source_root(source_root);
-            if !source_root.is_library || self.with_deps {
-                num_crates += 1;
-                visit_queue.push(module);
-            }
-        }
-
-        if self.randomize {
-            shuffle(&mut rng, &mut visit_queue);
-        }
 
-        eprint!("  crates: {}", num_crates);
-        let mut num_decls = 0;
-        let mut funcs = Vec::new();
-        while let Some(module) = visit_queue.pop() {
-            if visited_modules.insert(module) {
-                visit_queue.extend(module.children(db));
-
-                for decl in module.declarations(db) {
-                    num_decls += 1;
-                    if let ModuleDef::Function(f) = decl {
-                        funcs.push(f);
-                    }
-                }
+        let options = AnalysisOptions {
+            parallel: self.parallel,
+            only: self.only.as_deref(),
+            skip_inference: self.skip_inference,
+        };
+        let rng = if self.randomize { Some(&mut rng) } else { None };
+        let (summary, bodies) = analyze(db, &krates, &options, rng);
 
-                for impl_def in module.impl_defs(db) {
-                    for item in impl_def.items(db) {
-                        num_decls += 1;
-                        if let AssocItem::Function(f) = item {
-                            funcs.push(f);
-                        }
-                    }
-                }
-            }
+        if verbosity.is_verbose() && !self.skip_inference {
+            self.print_inference_details(&host, db, &vfs, &bodies, verbosity);
         }
-        eprintln!(", mods: {}, decls: {}, fns: {}", visited_modules.len(), num_decls, funcs.len());
-        eprintln!("{:<20} {}", "Item Collection:", analysis_sw.elapsed());
 
-        if self.randomize {
-            shuffle(&mut rng, &mut funcs);
+        let total_span = analysis_sw.elapsed();
+        match output {
+            OutputFormat::Text => print_summary(&summary),
+            OutputFormat::Json => println!("{}", serde_json::to_string(&summary)?),
         }
-
+        eprintln!("{:<20} {}", "Total:", total_span);
         if !self.skip_inference {
-            self.run_inference(&host, db, &vfs, &funcs, verbosity);
+            report_metric("unknown type", summary.inference.unknown_types, "#");
+            report_metric("type mismatches", summary.inference.type_mismatches, "#");
         }
-
-        let total_span = analysis_sw.elapsed();
-        eprintln!("{:<20} {}", "Total:", total_span);
         report_metric("total time", total_span.time.as_millis() as u64, "ms");
         if let Some(instructions) = total_span.instructions {
             report_metric("total instructions", instructions, "#instr");
@@ -159,104 +162,53 @@ impl flags::AnalysisStats {
         Ok(())
     }
 
-    fn run_inference(
+    /// Prints unknown types and type mismatches of every body. Inference has
+    /// already run at this point, so this only reads the cached results.
+    fn print_inference_details(
         &self,
         host: &AnalysisHost,
         db: &RootDatabase,
         vfs: &Vfs,
-        funcs: &[Function],
+        bodies: &[DefWithBody],
         verbosity: Verbosity,
     ) {
         let mut bar = match verbosity {
-            Verbosity::Quiet | Verbosity::Spammy => ProgressReport::hidden(),
-            _ if self.parallel => ProgressReport::hidden(),
-            _ => ProgressReport::new(funcs.len() as u64),
+            Verbosity::Spammy => ProgressReport::hidden(),
+            _ => ProgressReport::new(bodies.len() as u64),
         };
 
-        if self.parallel {
-            let mut inference_sw = self.stop_watch();
-            let snap = Snap(db.snapshot());
-            funcs
-                .par_iter()
-                .map_with(snap, |snap, &f| {
-                    let f_id = FunctionId::from(f);
-                    snap.0.body(f_id.into());
-                    snap.0.infer(f_id.into());
-                })
-                .count();
-            eprintln!("{:<20} {}", "Parallel Inference:", inference_sw.elapsed());
-        }
-
-        let mut inference_sw = self.stop_watch();
         bar.tick();
-        let mut num_exprs = 0;
-        let mut num_exprs_unknown = 0;
-        let mut num_exprs_partially_unknown = 0;
-        let mut num_type_mismatches = 0;
         let analysis = host.analysis();
-        for f in funcs.iter().copied() {
-            let name = f.name(db);
-            let full_name = f
-                .module(db)
-                .path_to_root(db)
-                .into_iter()
-                .rev()
-                .filter_map(|it| it.name(db))
-                .chain(Some(f.name(db)))
-                .join("::");
-            if let Some(only_name) = self.only.as_deref() {
-                if name.to_string() != only_name && full_name != only_name {
-                    continue;
-                }
-            }
+        for &def in bodies {
+            let name = def.name(db).map_or_else(|| "_".to_string(), |name| name.to_string());
+            let full_name = full_name(db, def);
             let mut msg = format!("processing: {}", full_name);
-            if verbosity.is_verbose() {
-                if let Some(src) = f.source(db) {
-                    let original_file = src.file_id.original_file(db);
-                    let path = vfs.file_path(original_file);
-                    let syntax_range = src.value.syntax().text_range();
-                    format_to!(msg, " ({} {:?})", path, syntax_range);
-                }
+            if let Some((file_id, syntax_range)) = body_source_range(db, def) {
+                format_to!(msg, " ({} {:?})", vfs.file_path(file_id), syntax_range);
             }
             if verbosity.is_spammy() {
                 bar.println(msg.to_string());
             }
             bar.set_message(&msg);
-            let f_id = FunctionId::from(f);
-            let (body, sm) = db.body_with_source_map(f_id.into());
-            let inference_result = db.infer(f_id.into());
-            let (previous_exprs, previous_unknown, previous_partially_unknown) =
-                (num_exprs, num_exprs_unknown, num_exprs_partially_unknown);
+            let body_id = DefWithBodyId::from(def);
+            let (body, sm) = db.body_with_source_map(body_id);
+            let inference_result = db.infer(body_id);
             for (expr_id, _) in body.exprs.iter() {
                 let ty = &inference_result[expr_id];
-                num_exprs += 1;
-                if ty.is_unknown() {
-                    num_exprs_unknown += 1;
-                    if verbosity.is_spammy() {
-                        if let Some((path, start, end)) =
-                            expr_syntax_range(db, &analysis, vfs, &sm, expr_id)
-                        {
-                            bar.println(format!(
-                                "{} {}:{}-{}:{}: Unknown type",
-                                path,
-                                start.line + 1,
-                                start.col,
-                                end.line + 1,
-                                end.col,
-                            ));
-                        } else {
-                            bar.println(format!("{}: Unknown type", name,));
-                        }
-                    }
-                } else {
-                    let mut is_partially_unknown = false;
-                    ty.walk(&mut |ty| {
-                        if ty.is_unknown() {
-                            is_partially_unknown = true;
-                        }
-                    });
-                    if is_partially_unknown {
-                        num_exprs_partially_unknown += 1;
+                if ty.is_unknown() && verbosity.is_spammy() {
+                    if let Some((path, start, end)) =
+                        expr_syntax_range(db, &analysis, vfs, &sm, expr_id)
+                    {
+                        bar.println(format!(
+                            "{} {}:{}-{}:{}: Unknown type",
+                            path,
+                            start.line + 1,
+                            start.col,
+                            end.line + 1,
+                            end.col,
+                        ));
+                    } else {
+                        bar.println(format!("{}: Unknown type", name,));
                     }
                 }
                 if self.only.is_some() && verbosity.is_spammy() {
@@ -277,63 +229,261 @@ impl flags::AnalysisStats {
                     }
                 }
                 if let Some(mismatch) = inference_result.type_mismatch_for_expr(expr_id) {
-                    num_type_mismatches += 1;
-                    if verbosity.is_verbose() {
-                        if let Some((path, start, end)) =
-                            expr_syntax_range(db, &analysis, vfs, &sm, expr_id)
-                        {
-                            bar.println(format!(
-                                "{} {}:{}-{}:{}: Expected {}, got {}",
-                                path,
-                                start.line + 1,
-                                start.col,
-                                end.line + 1,
-                                end.col,
-                                mismatch.expected.display(db),
-                                mismatch.actual.display(db)
-                            ));
-                        } else {
-                            bar.println(format!(
-                                "{}: Expected {}, got {}",
-                                name,
-                                mismatch.expected.display(db),
-                                mismatch.actual.display(db)
-                            ));
-                        }
+                    if let Some((path, start, end)) =
+                        expr_syntax_range(db, &analysis, vfs, &sm, expr_id)
+                    {
+                        bar.println(format!(
+                            "{} {}:{}-{}:{}: Expected {}, got {}",
+                            path,
+                            start.line + 1,
+                            start.col,
+                            end.line + 1,
+                            end.col,
+                            mismatch.expected.display(db),
+                            mismatch.actual.display(db)
+                        ));
+                    } else {
+                        bar.println(format!(
+                            "{}: Expected {}, got {}",
+                            name,
+                            mismatch.expected.display(db),
+                            mismatch.actual.display(db)
+                        ));
                     }
                 }
             }
             if verbosity.is_spammy() {
+                let stats = body_stats(db, def);
                 bar.println(format!(
                     "In {}: {} exprs, {} unknown, {} partial",
-                    full_name,
-                    num_exprs - previous_exprs,
-                    num_exprs_unknown - previous_unknown,
-                    num_exprs_partially_unknown - previous_partially_unknown
+                    full_name, stats.exprs, stats.unknown_types, stats.partially_unknown_types
                 ));
             }
             bar.inc(1);
         }
 
         bar.finish_and_clear();
+    }
+
+    fn stop_watch(&self) -> StopWatch {
+        StopWatch::start().memory(self.memory_usage)
+    }
+}
+
+struct AnalysisOptions<'a> {
+    parallel: bool,
+    only: Option<&'a str>,
+    skip_inference: bool,
+}
+
+/// Runs the analysis phases over `krates` and returns their summary, along
+/// with the bodies that were type-checked.
+fn analyze(
+    db: &RootDatabase,
+    krates: &[Crate],
+    options: &AnalysisOptions,
+    rng: Option<&mut Rand32>,
+) -> (Summary, Vec<DefWithBody>) {
+    let mut phases = Vec::new();
+
+    let mut files = FxHashSet::default();
+    for &krate in krates {
+        let file_id = db.crate_graph()[krate.into()].root_file_id;
+        let source_root = db.source_root(db.file_source_root(file_id));
+        files.extend(source_root.iter());
+    }
+    let files: Vec<FileId> = files.into_iter().sorted().collect();
+    let (_, phase) = run_phase(db, "item trees", &files, options.parallel, |db, &file_id| {
+        db.file_item_tree(file_id.into());
+    });
+    phases.push(phase);
+
+    let (root_modules, phase) =
+        run_phase(db, "crate def maps", krates, options.parallel, |db, krate| {
+            krate.root_module(db)
+        });
+    phases.push(phase);
+
+    let mut visited_modules = FxHashSet::default();
+    let mut visit_queue = root_modules;
+    let mut num_decls = 0;
+    let mut bodies: Vec<DefWithBody> = Vec::new();
+    while let Some(module) = visit_queue.pop() {
+        if visited_modules.insert(module) {
+            visit_queue.extend(module.children(db));
+
+            for decl in module.declarations(db) {
+                num_decls += 1;
+                match decl {
+                    ModuleDef::Function(it) => bodies.push(it.into()),
+                    ModuleDef::Const(it) => bodies.push(it.into()),
+                    ModuleDef::Static(it) => bodies.push(it.into()),
+                    _ => (),
+                }
+            }
+
+            for impl_def in module.impl_defs(db) {
+                for item in impl_def.items(db) {
+                    num_decls += 1;
+                    match item {
+                        AssocItem::Function(it) => bodies.push(it.into()),
+                        AssocItem::Const(it) => bodies.push(it.into()),
+                        AssocItem::TypeAlias(_) => (),
+                    }
+                }
+            }
+        }
+    }
+    let num_bodies = bodies.len();
+
+    if let Some(only) = options.only {
+        bodies.retain(|&def| {
+            def.name(db).map_or(false, |name| name.to_string() == only)
+                || full_name(db, def) == only
+        });
+    }
+    if let Some(rng) = rng {
+        shuffle(rng, &mut bodies);
+    }
+
+    let mut inference = InferenceStats::default();
+    if !options.skip_inference {
+        let (stats, phase) =
+            run_phase(db, "inference", &bodies, options.parallel, |db, &def| body_stats(db, def));
+        phases.push(phase);
+        for it in stats {
+            inference += it;
+        }
+
+        let consts: Vec<_> = bodies
+            .iter()
+            .filter_map(|def| match def {
+                &DefWithBody::Const(it) => Some(it),
+                _ => None,
+            })
+            .collect();
+        let (_, phase) = run_phase(db, "const eval", &consts, options.parallel, |db, konst| {
+            let _ = konst.eval(db);
+        });
+        phases.push(phase);
+    }
+
+    let summary = Summary {
+        crates: krates.len(),
+        modules: visited_modules.len(),
+        declarations: num_decls,
+        bodies: num_bodies,
+        inference,
+        phases,
+    };
+    (summary, bodies)
+}
+
+fn run_phase<T, R, F>(
+    db: &RootDatabase,
+    name: &'static str,
+    items: &[T],
+    parallel: bool,
+    f: F,
+) -> (Vec<R>, PhaseStats)
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&RootDatabase, &T) -> R + Sync,
+{
+    let wall = Instant::now();
+    let results = par_map_with_snapshots(db, items, parallel, |db, item| {
+        let start = Instant::now();
+        let res = f(db, item);
+        (res, start.elapsed())
+    });
+    let wall = wall.elapsed();
+    let cpu: Duration = results.iter().map(|(_, elapsed)| *elapsed).sum();
+    let phase = PhaseStats {
+        name,
+        items: items.len(),
+        wall_ms: wall.as_secs_f64() * 1000.0,
+        cpu_ms: cpu.as_secs_f64() * 1000.0,
+    };
+    (results.into_iter().map(|(res, _)| res).collect(), phase)
+}
+
+fn body_stats(db: &RootDatabase, def: DefWithBody) -> InferenceStats {
+    let body_id = DefWithBodyId::from(def);
+    let body = db.body(body_id);
+    let inference_result = db.infer(body_id);
+    let mut stats = InferenceStats::default();
+    for (expr_id, _) in body.exprs.iter() {
+        let ty = &inference_result[expr_id];
+        stats.exprs += 1;
+        if ty.is_unknown() {
+            stats.unknown_types += 1;
+        } else {
+            let mut is_partially_unknown = false;
+            ty.walk(&mut |ty| {
+                if ty.is_unknown() {
+                    is_partially_unknown = true;
+                }
+            });
+            if is_partially_unknown {
+                stats.partially_unknown_types += 1;
+            }
+        }
+        if inference_result.type_mismatch_for_expr(expr_id).is_some() {
+            stats.type_mismatches += 1;
+        }
+    }
+    stats
+}
+
+fn print_summary(summary: &Summary) {
+    eprintln!(
+        "  crates: {}, mods: {}, decls: {}, bodies: {}",
+        summary.crates, summary.modules, summary.declarations, summary.bodies
+    );
+    for phase in &summary.phases {
+        eprintln!(
+            "{:<20} {:.2}ms wall, {:.2}ms cpu, {} items",
+            format!("{}:", phase.name),
+            phase.wall_ms,
+            phase.cpu_ms,
+            phase.items
+        );
+    }
+    let inference = &summary.inference;
+    if summary.phases.iter().any(|phase| phase.name == "inference") {
         eprintln!(
             "  exprs: {}, ??ty: {} ({}%), ?ty: {} ({}%), !ty: {}",
-            num_exprs,
-            num_exprs_unknown,
-            percentage(num_exprs_unknown, num_exprs),
-            num_exprs_partially_unknown,
-            percentage(num_exprs_partially_unknown, num_exprs),
-            num_type_mismatches
+            inference.exprs,
+            inference.unknown_types,
+            percentage(inference.unknown_types, inference.exprs),
+            inference.partially_unknown_types,
+            percentage(inference.partially_unknown_types, inference.exprs),
+            inference.type_mismatches
         );
-        report_metric("unknown type", num_exprs_unknown, "#");
-        report_metric("type mismatches", num_type_mismatches, "#");
-
-        eprintln!("{:<20} {}", "Inference:", inference_sw.elapsed());
     }
+}
 
-    fn stop_watch(&self) -> StopWatch {
-        StopWatch::start().memory(self.memory_usage)
+fn full_name(db: &RootDatabase, def: DefWithBody) -> String {
+    def.module(db)
+        .path_to_root(db)
+        .into_iter()
+        .rev()
+        .filter_map(|it| it.name(db))
+        .chain(def.name(db))
+        .join("::")
+}
+
+fn body_source_range(db: &RootDatabase, def: DefWithBody) -> Option<(FileId, TextRange)> {
+    let (file_id, range) = match def {
+        DefWithBody::Function(it) => {
+            it.source(db).map(|src| src.syntax().map(|it| it.text_range()))
+        }
+        DefWithBody::Static(it) => it.source(db).map(|src| src.syntax().map(|it| it.text_range())),
+        DefWithBody::Const(it) => it.source(db).map(|src| src.syntax().map(|it| it.text_range())),
     }
+    .map(|src| (src.file_id, src.value))?;
+    Some((file_id.original_file(db), range))
 }
 
 fn expr_syntax_range(
@@ -382,3 +532,72 @@ fn syntax_len(node: SyntaxNode) -> usize {
     // to make macro and non-macro code comparable.
     node.to_string().replace(|it: char| it.is_ascii_whitespace(), "").len()
 }
+
+#[cfg(test)]
+mod tests {
+    use ide_db::base_db::fixture::ChangeFixture;
+
+    use super::*;
+
+    #[test]
+    fn parallel_and_serial_runs_agree() {
+        let ChangeFixture { change, .. } = ChangeFixture::parse(
+            r#"
+//- /main.rs crate:main deps:shapes
+use shapes::Circle;
+
+const RADIUS: f64 = 2.0;
+static ORIGIN: (f64, f64) = (0.0, 0.0);
+
+fn main() {
+    let circle = Circle { radius: RADIUS };
+    let area = circle.area();
+    let missing = unknown_fn(area);
+}
+
+//- /lib.rs crate:shapes
+pub struct Circle {
+    pub radius: f64,
+}
+
+impl Circle {
+    pub const PI: f64 = 3.14;
+
+    pub fn area(&self) -> f64 {
+        Self::PI * self.radius * self.radius
+    }
+}
+"#,
+        );
+        let mut host = AnalysisHost::default();
+        host.apply_change(change);
+        let db = host.raw_database();
+        let krates = Crate::all(db);
+
+        let run = |parallel| {
+            let options = AnalysisOptions { parallel, only: None, skip_inference: false };
+            analyze(db, &krates, &options, None).0
+        };
+        let (serial, parallel) = (run(false), run(true));
+
+        assert_eq!(
+            (serial.crates, serial.modules, serial.declarations, serial.bodies),
+            (2, 2, 6, 5)
+        );
+        assert_eq!(
+            (parallel.crates, parallel.modules, parallel.declarations, parallel.bodies),
+            (serial.crates, serial.modules, serial.declarations, serial.bodies)
+        );
+        assert_eq!(parallel.inference, serial.inference);
+        assert!(serial.inference.exprs > 0);
+        assert_eq!(serial.inference.unknown_types, parallel.inference.unknown_types);
+        let phase_items = |summary: &Summary| {
+            summary.phases.iter().map(|phase| (phase.name, phase.items)).collect::<Vec<_>>()
+        };
+        assert_eq!(phase_items(&parallel), phase_items(&serial));
+        assert_eq!(
+            phase_items(&serial),
+            vec![("item trees", 2), ("crate def maps", 2), ("inference", 5), ("const eval", 2)]
+        );
+    }
+}
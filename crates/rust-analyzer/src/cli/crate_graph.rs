@@ -0,0 +1,25 @@
+//! Prints the crate graph of a project in Graphviz DOT format, e.g. to pipe it
+//! into `dot -Tsvg`.
+
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace_at, LoadCargoConfig},
+};
+
+impl flags::CrateGraph {
+    pub fn run(self) -> anyhow::Result<()> {
+        let cargo_config = Default::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro: !self.disable_proc_macros,
+            prefill_caches: false,
+        };
+        let (host, _vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+
+        let dot = host.analysis().view_crate_graph(self.full)?.map_err(anyhow::Error::msg)?;
+        println!("{}", dot);
+
+        Ok(())
+    }
+}
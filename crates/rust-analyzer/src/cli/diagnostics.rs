@@ -1,26 +1,49 @@
 //! Analyze all modules in a project for diagnostics. Exits with a non-zero
-//! status code if any errors are found.
+//! status code if any diagnostics at or above the `--error-on` severity are
+//! found.
+
+use std::io::{self, Write};
 
 use rustc_hash::FxHashSet;
+use serde::Serialize;
 
-use hir::{db::HirDatabase, Crate, Module};
-use ide::{AssistResolveStrategy, DiagnosticsConfig, Severity};
+use ide::{AssistResolveStrategy, Diagnostic, DiagnosticsConfig, LineIndex, Severity};
 use ide_db::base_db::SourceDatabaseExt;
 
 use crate::cli::{
-    flags,
+    all_modules,
+    flags::{self, ErrorOn, OutputFormat},
     load_cargo::{load_workspace_at, LoadCargoConfig},
+    runnables::{one_based_range, Range},
 };
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticData {
+    file: String,
+    range: Range,
+    code: &'static str,
+    severity: &'static str,
+    message: String,
+    has_fixes: bool,
+}
+
 impl flags::Diagnostics {
     pub fn run(self) -> anyhow::Result<()> {
+        self.run_with_output(&mut io::stdout())
+    }
+
+    fn run_with_output(self, out: &mut dyn Write) -> anyhow::Result<()> {
+        let format = self.format.unwrap_or(OutputFormat::Text);
+        let error_on = self.error_on.unwrap_or(ErrorOn::Error);
+
         let cargo_config = Default::default();
         let load_cargo_config = LoadCargoConfig {
             load_out_dirs_from_check: !self.disable_build_scripts,
             with_proc_macro: !self.disable_proc_macros,
             prefill_caches: false,
         };
-        let (host, _vfs, _proc_macro) =
+        let (host, vfs, _proc_macro) =
             load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
         let db = host.raw_database();
         let analysis = host.analysis();
@@ -40,7 +63,11 @@ impl flags::Diagnostics {
             if !visited_files.contains(&file_id) {
                 let crate_name =
                     module.krate().display_name(db).as_deref().unwrap_or("unknown").to_string();
-                println!("processing crate: {}, module: {}", crate_name, _vfs.file_path(file_id));
+                let file = vfs.file_path(file_id).to_string();
+                if format == OutputFormat::Text {
+                    writeln!(out, "processing crate: {}, module: {}", crate_name, file)?;
+                }
+                let line_index = analysis.file_line_index(file_id)?;
                 for diagnostic in analysis
                     .diagnostics(
                         &DiagnosticsConfig::default(),
@@ -49,22 +76,32 @@ impl flags::Diagnostics {
                     )
                     .unwrap()
                 {
-                    if matches!(diagnostic.severity, Severity::Error) {
+                    if fails_on(error_on, diagnostic.severity) {
                         found_error = true;
                     }
 
-                    println!("{:?}", diagnostic);
+                    match format {
+                        OutputFormat::Text => writeln!(out, "{:?}", diagnostic)?,
+                        OutputFormat::Json => {
+                            let data = diagnostic_data(file.clone(), &line_index, &diagnostic);
+                            writeln!(out, "{}", serde_json::to_string(&data)?)?;
+                        }
+                    }
                 }
 
                 visited_files.insert(file_id);
             }
         }
 
-        println!();
-        println!("diagnostic scan complete");
+        if format == OutputFormat::Text {
+            writeln!(out)?;
+            writeln!(out, "diagnostic scan complete")?;
+        }
 
         if found_error {
-            println!();
+            if format == OutputFormat::Text {
+                writeln!(out)?;
+            }
             anyhow::bail!("diagnostic error detected")
         }
 
@@ -72,15 +109,122 @@ impl flags::Diagnostics {
     }
 }
 
-fn all_modules(db: &dyn HirDatabase) -> Vec<Module> {
-    let mut worklist: Vec<_> =
-        Crate::all(db).into_iter().map(|krate| krate.root_module(db)).collect();
-    let mut modules = Vec::new();
+fn fails_on(error_on: ErrorOn, severity: Severity) -> bool {
+    match error_on {
+        ErrorOn::Warning => true,
+        ErrorOn::Error => matches!(severity, Severity::Error),
+        ErrorOn::None => false,
+    }
+}
+
+fn diagnostic_data(
+    file: String,
+    line_index: &LineIndex,
+    diagnostic: &Diagnostic,
+) -> DiagnosticData {
+    DiagnosticData {
+        file,
+        range: one_based_range(line_index, diagnostic.range),
+        code: diagnostic.code.0,
+        severity: match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::WeakWarning => "warning",
+        },
+        message: diagnostic.message.clone(),
+        has_fixes: diagnostic.fixes.as_ref().map_or(false, |fixes| !fixes.is_empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use expect_test::expect;
+    use ide::Analysis;
+    use test_utils::{skip_slow_tests, Fixture};
+
+    use super::*;
 
-    while let Some(module) = worklist.pop() {
-        modules.push(module);
-        worklist.extend(module.children(db));
+    #[test]
+    fn serializes_diagnostics() {
+        let (analysis, file_id) = Analysis::from_single_file(
+            r#"
+mod missing;
+fn Foo() {}
+"#
+            .to_string(),
+        );
+        let line_index = analysis.file_line_index(file_id).unwrap();
+        let diagnostics = analysis
+            .diagnostics(&DiagnosticsConfig::default(), AssistResolveStrategy::None, file_id)
+            .unwrap();
+        let lines = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let data = diagnostic_data("/main.rs".to_string(), &line_index, diagnostic);
+                serde_json::to_string(&data).unwrap()
+            })
+            .collect::<Vec<_>>();
+        expect![[r#"
+            {"file":"/main.rs","range":{"start":{"line":2,"col":1},"end":{"line":2,"col":13}},"code":"unresolved-module","severity":"error","message":"unresolved module","hasFixes":true}
+            {"file":"/main.rs","range":{"start":{"line":3,"col":4},"end":{"line":3,"col":7}},"code":"incorrect-ident-case","severity":"warning","message":"Function `Foo` should have snake_case name, e.g. `foo`","hasFixes":true}"#]].assert_eq(&lines.join("\n"));
     }
 
-    modules
+    #[test]
+    fn error_on_policy() {
+        assert!(fails_on(ErrorOn::Warning, Severity::WeakWarning));
+        assert!(fails_on(ErrorOn::Warning, Severity::Error));
+        assert!(!fails_on(ErrorOn::Error, Severity::WeakWarning));
+        assert!(fails_on(ErrorOn::Error, Severity::Error));
+        assert!(!fails_on(ErrorOn::None, Severity::Error));
+    }
+
+    #[test]
+    fn run_on_workspace() {
+        if skip_slow_tests() {
+            return;
+        }
+
+        let dir = std::env::temp_dir().join(format!("ra-diagnostics-{}", std::process::id()));
+        for entry in Fixture::parse(
+            r#"
+//- /Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- /src/lib.rs
+fn Foo() {}
+"#,
+        )
+        .1
+        {
+            let path = dir.join(entry.path.trim_start_matches('/'));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, entry.text).unwrap();
+        }
+        let run = |error_on| {
+            let flags = flags::Diagnostics {
+                path: dir.clone(),
+                disable_build_scripts: true,
+                disable_proc_macros: true,
+                format: Some(OutputFormat::Json),
+                error_on: Some(error_on),
+            };
+            let mut out = Vec::new();
+            let res = flags.run_with_output(&mut out);
+            let out = String::from_utf8(out).unwrap().replace(&dir.display().to_string(), "$DIR");
+            (res.map_err(|err| err.to_string()), out)
+        };
+
+        let (res, out) = run(ErrorOn::Error);
+        expect![[r#"
+            {"file":"$DIR/src/lib.rs","range":{"start":{"line":1,"col":4},"end":{"line":1,"col":7}},"code":"incorrect-ident-case","severity":"warning","message":"Function `Foo` should have snake_case name, e.g. `foo`","hasFixes":true}
+        "#]].assert_eq(&out);
+        assert_eq!(res, Ok(()));
+        let (res, _) = run(ErrorOn::Warning);
+        assert_eq!(res, Err("diagnostic error detected".to_string()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
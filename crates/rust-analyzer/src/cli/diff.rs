@@ -0,0 +1,209 @@
+//! Renders a `TextEdit` as a unified diff, for commands that show changes
+//! instead of writing them to disk.
+
+use std::ops::Range;
+
+use text_edit::{Indel, TextEdit};
+
+/// Number of unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+/// Returns the unified diff between `text` and `text` with `edit` applied,
+/// with `path` in the `---`/`+++` headers. The diff is empty if the edit
+/// doesn't change anything.
+pub(crate) fn unified_diff(path: &str, text: &str, edit: &TextEdit) -> String {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let mut line_starts = Vec::with_capacity(lines.len() + 1);
+    let mut offset = 0;
+    for line in &lines {
+        line_starts.push(offset);
+        offset += line.len();
+    }
+    line_starts.push(offset);
+    let line_of = |offset: usize| {
+        if offset == text.len() && (text.is_empty() || text.ends_with('\n')) {
+            // After the last line, as opposed to inside a last line without a newline.
+            lines.len()
+        } else {
+            line_starts[..lines.len()].partition_point(|&start| start <= offset) - 1
+        }
+    };
+
+    // The lines touched by each indel, with indels on shared or adjacent lines merged.
+    let mut changes: Vec<(Range<usize>, Vec<&Indel>)> = Vec::new();
+    for indel in edit.iter() {
+        let (start, end) = (usize::from(indel.delete.start()), usize::from(indel.delete.end()));
+        let first = line_of(start);
+        let last = if end > start { line_of(end - 1) + 1 } else { (first + 1).min(lines.len()) };
+        match changes.last_mut() {
+            Some((range, indels)) if first <= range.end => {
+                range.end = range.end.max(last);
+                indels.push(indel);
+            }
+            _ => changes.push((first..last, vec![indel])),
+        }
+    }
+
+    let changes: Vec<(Range<usize>, Vec<String>)> = changes
+        .into_iter()
+        .filter_map(|(range, indels)| {
+            let chunk_start = line_starts[range.start];
+            let old = &text[chunk_start..line_starts[range.end]];
+            let mut new = old.to_string();
+            for indel in indels.iter().rev() {
+                let delete = usize::from(indel.delete.start()) - chunk_start
+                    ..usize::from(indel.delete.end()) - chunk_start;
+                new.replace_range(delete, &indel.insert);
+            }
+            if new == old {
+                return None;
+            }
+            Some((range, new.split_inclusive('\n').map(|it| it.to_string()).collect()))
+        })
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    // Difference between the line numbers of the new and of the old text.
+    let mut shift = 0isize;
+    let mut changes = changes.into_iter().peekable();
+    while let Some((range, new_lines)) = changes.next() {
+        // Changes that are close enough to share context go into the same hunk.
+        let mut hunk = vec![(range, new_lines)];
+        while let Some((next, _)) = changes.peek() {
+            if next.start - hunk.last().unwrap().0.end > 2 * CONTEXT {
+                break;
+            }
+            hunk.push(changes.next().unwrap());
+        }
+
+        let start = hunk[0].0.start.saturating_sub(CONTEXT);
+        let end = (hunk.last().unwrap().0.end + CONTEXT).min(lines.len());
+        let mut body = String::new();
+        let mut old_len = 0;
+        let mut new_len = 0;
+        let mut line = start;
+        for (range, new_lines) in &hunk {
+            for context in &lines[line..range.start] {
+                push_line(&mut body, ' ', context);
+            }
+            for removed in &lines[range.clone()] {
+                push_line(&mut body, '-', removed);
+            }
+            for added in new_lines {
+                push_line(&mut body, '+', added);
+            }
+            old_len += range.start - line + range.len();
+            new_len += range.start - line + new_lines.len();
+            line = range.end;
+        }
+        for context in &lines[line..end] {
+            push_line(&mut body, ' ', context);
+        }
+        old_len += end - line;
+        new_len += end - line;
+
+        let new_start = (start as isize + shift) as usize;
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(start, old_len),
+            hunk_range(new_start, new_len)
+        ));
+        diff.push_str(&body);
+        shift += new_len as isize - old_len as isize;
+    }
+    diff
+}
+
+fn push_line(buf: &mut String, marker: char, line: &str) {
+    buf.push(marker);
+    buf.push_str(line);
+    if !line.ends_with('\n') {
+        buf.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+/// Formats the `start,len` of a hunk header, where `start` is 1-based, or the
+/// line before the hunk for empty ranges.
+fn hunk_range(start: usize, len: usize) -> String {
+    if len == 0 {
+        format!("{},0", start)
+    } else {
+        format!("{},{}", start + 1, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use syntax::{TextRange, TextSize};
+
+    use super::*;
+
+    fn replace(text: &str, from: &str, to: &str) -> TextEdit {
+        let mut builder = TextEdit::builder();
+        for (offset, _) in text.match_indices(from) {
+            let range = TextRange::at(TextSize::from(offset as u32), TextSize::of(from));
+            builder.replace(range, to.to_string());
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn renders_separate_and_merged_hunks() {
+        let text = (1..=20).map(|i| format!("line {}\n", i)).collect::<String>();
+        let text = text.replace("line 2\n", "foo(2)\n").replace("line 5\n", "foo(5)\n");
+        let text = text.replace("line 18\n", "foo(18)\n");
+        let edit = replace(&text, "foo", "bar");
+        expect![[r#"
+            --- a/src/lib.rs
+            +++ b/src/lib.rs
+            @@ -1,8 +1,8 @@
+             line 1
+            -foo(2)
+            +bar(2)
+             line 3
+             line 4
+            -foo(5)
+            +bar(5)
+             line 6
+             line 7
+             line 8
+            @@ -15,6 +15,6 @@
+             line 15
+             line 16
+             line 17
+            -foo(18)
+            +bar(18)
+             line 19
+             line 20
+        "#]]
+        .assert_eq(&unified_diff("src/lib.rs", &text, &edit));
+    }
+
+    #[test]
+    fn renders_changed_line_counts() {
+        let text = "fn main() {\n    foo();\n}";
+        let edit = replace(text, "    foo();\n", "    bar();\n    baz();\n");
+        expect![[r#"
+            --- a/main.rs
+            +++ b/main.rs
+            @@ -1,3 +1,4 @@
+             fn main() {
+            -    foo();
+            +    bar();
+            +    baz();
+             }
+            \ No newline at end of file
+        "#]]
+        .assert_eq(&unified_diff("main.rs", text, &edit));
+    }
+
+    #[test]
+    fn no_diff_for_noop_edit() {
+        let text = "foo\n";
+        assert_eq!(unified_diff("main.rs", text, &replace(text, "foo", "foo")), "");
+    }
+}
@@ -0,0 +1,106 @@
+//! Expands the macro call at a position from the command line, for debugging
+//! macro issues.
+
+use anyhow::{bail, format_err};
+use hir::Semantics;
+use ide::{FilePosition, LineCol, LineIndex};
+use project_model::CargoConfig;
+use syntax::{algo::find_node_at_offset, ast, AstNode, TextSize};
+use vfs::{AbsPathBuf, VfsPath};
+
+use crate::cli::{
+    flags,
+    load_cargo::{load_workspace_at, LoadCargoConfig},
+    Result,
+};
+
+impl flags::ExpandMacro {
+    pub fn run(self) -> Result<()> {
+        let cargo_config = CargoConfig::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro: !self.disable_proc_macros,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&self.file, &cargo_config, &load_cargo_config, &|_| {})?;
+        let path = AbsPathBuf::assert(std::env::current_dir()?.join(&self.file)).normalize();
+        let file_id = vfs
+            .file_id(&VfsPath::from(path))
+            .ok_or_else(|| format_err!("{} is not part of the workspace", self.file.display()))?;
+
+        let analysis = host.analysis();
+        let line_index = analysis.file_line_index(file_id)?;
+        let text = analysis.file_text(file_id)?;
+        let offset = offset_of(&text, &line_index, self.line, self.col)
+            .map_err(|err| format_err!("{}: {}", self.file.display(), err))?;
+        let position = FilePosition { file_id, offset };
+
+        let db = host.raw_database();
+        let sema = Semantics::new(db);
+        let macro_call: Option<ast::MacroCall> =
+            find_node_at_offset(sema.parse(file_id).syntax(), offset);
+
+        match analysis.expand_macro(position)? {
+            Some(expanded) => {
+                // The expansion might be incomplete, so report the errors first
+                for err in &expanded.errors {
+                    eprintln!("error: failed to expand macro: {}", err);
+                }
+                println!("// Recursive expansion of {}! macro", expanded.name);
+                println!("{}", expanded.expansion);
+            }
+            None if macro_call.is_some() => bail!("failed to resolve the macro call"),
+            None => bail!("no macro call at {}:{}:{}", self.file.display(), self.line, self.col),
+        }
+        Ok(())
+    }
+}
+
+/// Converts a one-based `line:col` into an offset into `text`, checking that it
+/// points into the file.
+fn offset_of(text: &str, line_index: &LineIndex, line: u32, col: u32) -> Result<TextSize> {
+    if line == 0 || col == 0 {
+        bail!("line and column are one-based");
+    }
+    let line_col = LineCol { line: line - 1, col: col - 1 };
+    let end = line_index.line_col(TextSize::of(text));
+    if line_col.line > end.line {
+        bail!("there are only {} lines", end.line + 1);
+    }
+    let line_len = match text.lines().nth(line_col.line as usize) {
+        Some(it) => TextSize::of(it),
+        None => 0.into(),
+    };
+    // The column right after the last character is fine, the cursor can be there.
+    if TextSize::from(line_col.col) > line_len {
+        bail!("line {} has only {} columns", line, u32::from(line_len) + 1);
+    }
+    Ok(line_index.offset(line_col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(text: &str, line: u32, col: u32) -> Result<u32> {
+        offset_of(text, &LineIndex::new(text), line, col).map(u32::from)
+    }
+
+    #[test]
+    fn converts_positions_in_bounds() {
+        let text = "fn main() {\n    m!();\n}";
+        assert_eq!(check(text, 1, 1).unwrap(), 0);
+        assert_eq!(check(text, 2, 5).unwrap(), 16);
+        assert_eq!(check(text, 3, 2).unwrap(), 23);
+    }
+
+    #[test]
+    fn rejects_positions_past_the_end() {
+        let text = "fn main() {\n    m!();\n}";
+        assert_eq!(check(text, 2, 11).unwrap_err().to_string(), "line 2 has only 10 columns");
+        assert_eq!(check(text, 3, 3).unwrap_err().to_string(), "line 3 has only 2 columns");
+        assert_eq!(check(text, 4, 1).unwrap_err().to_string(), "there are only 3 lines");
+        assert!(check(text, 0, 1).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 //! Grammar for the command-line arguments.
 #![allow(unreachable_pub)]
-use std::path::PathBuf;
+use std::{path::PathBuf, str::FromStr};
 
 use ide_ssr::{SsrPattern, SsrRule};
 
@@ -56,7 +56,7 @@ xflags::xflags! {
         {
             /// Randomize order in which crates, modules, and items are processed.
             optional --randomize
-            /// Run type inference in parallel.
+            /// Run the analysis phases in parallel.
             optional --parallel
             /// Collect memory usage statistics.
             optional --memory-usage
@@ -76,11 +76,79 @@ xflags::xflags! {
             optional --disable-proc-macros
             /// Only resolve names, don't run type inference.
             optional --skip-inference
+            /// Output format of the summary, `text` (the default) or `json`.
+            optional --output format: OutputFormat
         }
 
+        /// Print the diagnostics of all modules of the project.
         cmd diagnostics
             /// Directory with Cargo.toml.
             required path: PathBuf
+        {
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+            /// Don't use expand proc macros.
+            optional --disable-proc-macros
+            /// Output format, `text` (the default) or `json` (one object per line).
+            optional --format format: OutputFormat
+            /// Lowest severity which makes the command fail: `warning`, `error` (the default) or `none`.
+            optional --error-on severity: ErrorOn
+        }
+
+        /// Report unresolved imports and extern crates, failing if there are any.
+        cmd unresolved-imports
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+        {
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+            /// Don't use expand proc macros.
+            optional --disable-proc-macros
+        }
+
+        /// Print all runnables of the project as JSON.
+        cmd runnables
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+        {
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+            /// Don't use expand proc macros.
+            optional --disable-proc-macros
+        }
+
+        /// Print an LSIF dump of the project, as JSON lines.
+        cmd lsif
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+        {
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+            /// Don't use expand proc macros.
+            optional --disable-proc-macros
+        }
+
+        /// Print the crate graph of the project in Graphviz DOT format.
+        cmd crate-graph
+            /// Directory with Cargo.toml.
+            required path: PathBuf
+        {
+            /// Include all transitive and sysroot dependencies, not only the direct ones.
+            optional --full
+            /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
+            optional --disable-build-scripts
+            /// Don't use expand proc macros.
+            optional --disable-proc-macros
+        }
+
+        /// Expand the macro call at the given position and print the expansion.
+        cmd expand-macro
+            /// The file containing the macro call.
+            required file: PathBuf
+            /// One-based line of the macro call.
+            required line: u32
+            /// One-based column of the macro call.
+            required col: u32
         {
             /// Don't run build scripts or load `OUT_DIR` values by running `cargo check` before analysis.
             optional --disable-build-scripts
@@ -91,7 +159,12 @@ xflags::xflags! {
         cmd ssr
             /// A structured search replace rule (`$a.foo($b) ==> bar($a, $b)`)
             repeated rule: SsrRule
-        {}
+        {
+            /// Print a unified diff of the changes instead of applying them.
+            optional --dry-run
+            /// Only change files matching this glob, relative to the current directory.
+            repeated --path glob: String
+        }
 
         cmd search
             /// A structured search replace pattern (`$a.foo($b)`)
@@ -126,6 +199,11 @@ pub enum RustAnalyzerCmd {
     Highlight(Highlight),
     AnalysisStats(AnalysisStats),
     Diagnostics(Diagnostics),
+    UnresolvedImports(UnresolvedImports),
+    Runnables(Runnables),
+    Lsif(Lsif),
+    CrateGraph(CrateGraph),
+    ExpandMacro(ExpandMacro),
     Ssr(Ssr),
     Search(Search),
     ProcMacro(ProcMacro),
@@ -165,12 +243,58 @@ pub struct AnalysisStats {
     pub disable_build_scripts: bool,
     pub disable_proc_macros: bool,
     pub skip_inference: bool,
+    pub output: Option<OutputFormat>,
 }
 
 #[derive(Debug)]
 pub struct Diagnostics {
     pub path: PathBuf,
 
+    pub disable_build_scripts: bool,
+    pub disable_proc_macros: bool,
+    pub format: Option<OutputFormat>,
+    pub error_on: Option<ErrorOn>,
+}
+
+#[derive(Debug)]
+pub struct UnresolvedImports {
+    pub path: PathBuf,
+
+    pub disable_build_scripts: bool,
+    pub disable_proc_macros: bool,
+}
+
+#[derive(Debug)]
+pub struct Runnables {
+    pub path: PathBuf,
+
+    pub disable_build_scripts: bool,
+    pub disable_proc_macros: bool,
+}
+
+#[derive(Debug)]
+pub struct Lsif {
+    pub path: PathBuf,
+
+    pub disable_build_scripts: bool,
+    pub disable_proc_macros: bool,
+}
+
+#[derive(Debug)]
+pub struct CrateGraph {
+    pub path: PathBuf,
+
+    pub full: bool,
+    pub disable_build_scripts: bool,
+    pub disable_proc_macros: bool,
+}
+
+#[derive(Debug)]
+pub struct ExpandMacro {
+    pub file: PathBuf,
+    pub line: u32,
+    pub col: u32,
+
     pub disable_build_scripts: bool,
     pub disable_proc_macros: bool,
 }
@@ -178,6 +302,9 @@ pub struct Diagnostics {
 #[derive(Debug)]
 pub struct Ssr {
     pub rule: Vec<SsrRule>,
+
+    pub dry_run: bool,
+    pub path: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -217,3 +344,41 @@ impl RustAnalyzer {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("unknown output format `{}`, expected `text` or `json`", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorOn {
+    Warning,
+    Error,
+    None,
+}
+
+impl FromStr for ErrorOn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warning" => Ok(ErrorOn::Warning),
+            "error" => Ok(ErrorOn::Error),
+            "none" => Ok(ErrorOn::None),
+            _ => Err(format!("unknown severity `{}`, expected `warning`, `error` or `none`", s)),
+        }
+    }
+}
@@ -0,0 +1,661 @@
+//! Emits an [LSIF](https://microsoft.github.io/language-server-protocol/specifications/lsif/0.5.0/specification/)
+//! dump of a project, so that code navigation works without a running server.
+//!
+//! The traversal of the project produces a format agnostic [`Index`], which an
+//! [`Emitter`] then writes out.
+
+use std::{io::Write, sync::Arc};
+
+use hir::Visibility;
+use ide::{
+    Analysis, FileId, FileRange, HoverConfig, HoverDocFormat, LineIndex, RootDatabase, Semantics,
+    TextRange,
+};
+use ide_db::{
+    base_db::SourceDatabaseExt,
+    defs::{Definition, NameClass, NameRefClass},
+};
+use lsp_types::{MonikerKind, UniquenessLevel, Url};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use syntax::{ast, AstNode, SyntaxKind, SyntaxToken, T};
+use vfs::AbsPathBuf;
+
+use crate::{
+    cli::{
+        all_modules, flags,
+        load_cargo::{load_workspace_at, LoadCargoConfig},
+    },
+    to_proto::url_from_abs_path,
+};
+
+impl flags::Lsif {
+    pub fn run(self) -> anyhow::Result<()> {
+        let cargo_config = Default::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro: !self.disable_proc_macros,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+
+        let mut files = Vec::new();
+        let mut visited_files = FxHashSet::default();
+        for module in all_modules(db) {
+            let file_id = module.definition_source(db).file_id.original_file(db);
+            let source_root = db.source_root(db.file_source_root(file_id));
+            if source_root.is_library || !visited_files.insert(file_id) {
+                continue;
+            }
+            if let Some(path) = vfs.file_path(file_id).as_path() {
+                files.push((file_id, url_from_abs_path(path)));
+            }
+        }
+
+        let index = Index::compute(&host.analysis(), db, files)?;
+        let project_root = AbsPathBuf::assert(std::env::current_dir()?.join(&self.path));
+        let stdout = std::io::stdout();
+        let mut emitter = LsifEmitter::new(stdout.lock(), url_from_abs_path(&project_root));
+        emitter.emit(&index)
+    }
+}
+
+/// The definitions of a project and where they occur.
+pub(crate) struct Index {
+    files: Vec<FileIndex>,
+    definitions: Vec<DefinitionData>,
+}
+
+struct FileIndex {
+    uri: Url,
+    line_index: Arc<LineIndex>,
+    occurrences: Vec<Occurrence>,
+}
+
+struct Occurrence {
+    range: TextRange,
+    /// Index into [`Index::definitions`].
+    definition: usize,
+    is_definition: bool,
+}
+
+struct DefinitionData {
+    hover: Option<String>,
+    moniker: Option<MonikerData>,
+}
+
+struct MonikerData {
+    identifier: String,
+    kind: MonikerKind,
+    package: Option<PackageData>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct PackageData {
+    name: String,
+    version: String,
+}
+
+impl Index {
+    pub(crate) fn compute(
+        analysis: &Analysis,
+        db: &RootDatabase,
+        files: Vec<(FileId, Url)>,
+    ) -> anyhow::Result<Index> {
+        let sema = Semantics::new(db);
+        let hover_config = HoverConfig {
+            links_in_hover: false,
+            documentation: Some(HoverDocFormat::Markdown),
+            macro_expansion: false,
+            generic_args: false,
+            deref_chain: false,
+            max_doc_lines: None,
+            max_type_depth: Some(10),
+        };
+
+        let mut definitions = Vec::new();
+        let mut definition_ids = FxHashMap::default();
+        let mut file_indices = Vec::new();
+        for (file_id, uri) in files {
+            let source_file = sema.parse(file_id);
+            let mut occurrences = Vec::new();
+            let tokens = source_file.syntax().descendants_with_tokens().filter_map(|it| {
+                it.into_token().filter(|it| {
+                    matches!(
+                        it.kind(),
+                        SyntaxKind::IDENT
+                            | SyntaxKind::LIFETIME_IDENT
+                            | T![self]
+                            | T![super]
+                            | T![crate]
+                    )
+                })
+            });
+            for token in tokens {
+                let (def, is_definition) = match classify(&sema, &token) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let range = token.text_range();
+                let definition = match definition_ids.get(&def) {
+                    Some(&it) => it,
+                    None => {
+                        // Hovers are the expensive part, so they are computed once per
+                        // definition, at its first occurrence.
+                        let position =
+                            FileRange { file_id, range: TextRange::empty(range.start()) };
+                        let hover = analysis
+                            .hover(&hover_config, position)?
+                            .map(|it| it.info.markup.to_string());
+                        definitions.push(DefinitionData { hover, moniker: moniker(db, def) });
+                        definition_ids.insert(def, definitions.len() - 1);
+                        definitions.len() - 1
+                    }
+                };
+                occurrences.push(Occurrence { range, definition, is_definition });
+            }
+            let line_index = analysis.file_line_index(file_id)?;
+            file_indices.push(FileIndex { uri, line_index, occurrences });
+        }
+
+        Ok(Index { files: file_indices, definitions })
+    }
+}
+
+/// Returns the definition `token` refers to, and whether `token` is its name.
+fn classify(sema: &Semantics<RootDatabase>, token: &SyntaxToken) -> Option<(Definition, bool)> {
+    let parent = token.parent()?;
+    if let Some(name) = ast::Name::cast(parent.clone()) {
+        return match NameClass::classify(sema, &name)? {
+            NameClass::ConstReference(def) => Some((def, false)),
+            it => Some((it.defined()?, true)),
+        };
+    }
+    if let Some(name_ref) = ast::NameRef::cast(parent.clone()) {
+        return match NameRefClass::classify(sema, &name_ref)? {
+            NameRefClass::Definition(def) => Some((def, false)),
+            NameRefClass::FieldShorthand { local_ref, field_ref: _ } => {
+                Some((Definition::Local(local_ref), false))
+            }
+        };
+    }
+    let lifetime = ast::Lifetime::cast(parent)?;
+    if let Some(def) = NameClass::classify_lifetime(sema, &lifetime).and_then(NameClass::defined) {
+        return Some((def, true));
+    }
+    match NameRefClass::classify_lifetime(sema, &lifetime)? {
+        NameRefClass::Definition(def) => Some((def, false)),
+        NameRefClass::FieldShorthand { .. } => None,
+    }
+}
+
+/// Public items get a moniker, which links them across dumps of different packages.
+fn moniker(db: &RootDatabase, def: Definition) -> Option<MonikerData> {
+    if def.visibility(db)? != Visibility::Public {
+        return None;
+    }
+    let def = match def {
+        Definition::ModuleDef(it) => it,
+        _ => return None,
+    };
+    let krate = def.module(db)?.krate();
+    let name = krate.display_name(db)?.to_string();
+    let is_library = db.source_root(db.file_source_root(krate.root_file(db))).is_library;
+    Some(MonikerData {
+        identifier: format!("{}::{}", name, def.canonical_path(db)?),
+        kind: if is_library { MonikerKind::Import } else { MonikerKind::Export },
+        package: krate.version(db).map(|version| PackageData { name, version }),
+    })
+}
+
+/// Writes out an [`Index`].
+pub(crate) trait Emitter {
+    fn emit(&mut self, index: &Index) -> anyhow::Result<()>;
+}
+
+pub(crate) struct LsifEmitter<W> {
+    out: W,
+    project_root: Url,
+    next_id: u64,
+}
+
+impl<W: Write> LsifEmitter<W> {
+    pub(crate) fn new(out: W, project_root: Url) -> LsifEmitter<W> {
+        LsifEmitter { out, project_root, next_id: 0 }
+    }
+
+    fn add(&mut self, element: Element) -> anyhow::Result<u64> {
+        self.next_id += 1;
+        let entry = Entry { id: self.next_id, element };
+        serde_json::to_writer(&mut self.out, &entry)?;
+        writeln!(self.out)?;
+        Ok(self.next_id)
+    }
+
+    fn vertex(&mut self, vertex: Vertex) -> anyhow::Result<u64> {
+        self.add(Element::Vertex(vertex))
+    }
+
+    fn edge(&mut self, edge: Edge) -> anyhow::Result<u64> {
+        self.add(Element::Edge(edge))
+    }
+}
+
+impl<W: Write> Emitter for LsifEmitter<W> {
+    fn emit(&mut self, index: &Index) -> anyhow::Result<()> {
+        self.vertex(Vertex::MetaData {
+            version: "0.5.0",
+            project_root: self.project_root.clone(),
+            position_encoding: "utf-16",
+            tool_info: ToolInfo { name: "rust-analyzer" },
+        })?;
+
+        let mut result_sets = Vec::with_capacity(index.definitions.len());
+        let mut packages = FxHashMap::default();
+        for definition in &index.definitions {
+            let result_set = self.vertex(Vertex::ResultSet {})?;
+            result_sets.push(result_set);
+            if let Some(hover) = &definition.hover {
+                let contents = lsp_types::MarkupContent {
+                    kind: lsp_types::MarkupKind::Markdown,
+                    value: hover.clone(),
+                };
+                let hover = self.vertex(Vertex::HoverResult {
+                    result: lsp_types::Hover {
+                        contents: lsp_types::HoverContents::Markup(contents),
+                        range: None,
+                    },
+                })?;
+                self.edge(Edge::Hover(EdgeData { out_v: result_set, in_v: hover }))?;
+            }
+            if let Some(moniker) = &definition.moniker {
+                let moniker_id = self.vertex(Vertex::Moniker(lsp_types::Moniker {
+                    scheme: "rust-analyzer".to_string(),
+                    identifier: moniker.identifier.clone(),
+                    unique: UniquenessLevel::Scheme,
+                    kind: Some(moniker.kind),
+                }))?;
+                self.edge(Edge::Moniker(EdgeData { out_v: result_set, in_v: moniker_id }))?;
+                if let Some(package) = &moniker.package {
+                    let package_id = match packages.get(package) {
+                        Some(&it) => it,
+                        None => {
+                            let id = self.vertex(Vertex::PackageInformation {
+                                name: package.name.clone(),
+                                manager: "cargo",
+                                version: package.version.clone(),
+                            })?;
+                            packages.insert(package.clone(), id);
+                            id
+                        }
+                    };
+                    self.edge(Edge::PackageInformation(EdgeData {
+                        out_v: moniker_id,
+                        in_v: package_id,
+                    }))?;
+                }
+            }
+        }
+
+        // The ranges of each definition, by document.
+        let mut definition_ranges = vec![Vec::new(); index.definitions.len()];
+        let mut reference_ranges = vec![Vec::new(); index.definitions.len()];
+        for file in &index.files {
+            let document =
+                self.vertex(Vertex::Document { uri: file.uri.clone(), language_id: "rust" })?;
+            let mut ranges = Vec::with_capacity(file.occurrences.len());
+            for occurrence in &file.occurrences {
+                let range = self.vertex(Vertex::Range {
+                    start: position(&file.line_index, occurrence.range.start()),
+                    end: position(&file.line_index, occurrence.range.end()),
+                })?;
+                self.edge(Edge::Next(EdgeData {
+                    out_v: range,
+                    in_v: result_sets[occurrence.definition],
+                }))?;
+                let ranges_of_kind = if occurrence.is_definition {
+                    &mut definition_ranges
+                } else {
+                    &mut reference_ranges
+                };
+                ranges_of_kind[occurrence.definition].push((document, range));
+                ranges.push(range);
+            }
+            if !ranges.is_empty() {
+                self.edge(Edge::Contains(EdgeDataMultiIn { out_v: document, in_vs: ranges }))?;
+            }
+        }
+
+        for (definition, &result_set) in result_sets.iter().enumerate() {
+            let definitions = &definition_ranges[definition];
+            let references = &reference_ranges[definition];
+            if !definitions.is_empty() {
+                let result = self.vertex(Vertex::DefinitionResult {})?;
+                self.edge(Edge::Definition(EdgeData { out_v: result_set, in_v: result }))?;
+                self.items(result, definitions, None)?;
+            }
+            if !definitions.is_empty() || !references.is_empty() {
+                let result = self.vertex(Vertex::ReferenceResult {})?;
+                self.edge(Edge::References(EdgeData { out_v: result_set, in_v: result }))?;
+                self.items(result, definitions, Some(ItemProperty::Definitions))?;
+                self.items(result, references, Some(ItemProperty::References))?;
+            }
+        }
+
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+impl<W: Write> LsifEmitter<W> {
+    /// Emits one `item` edge per document `ranges` are in.
+    fn items(
+        &mut self,
+        result: u64,
+        ranges: &[(u64, u64)],
+        property: Option<ItemProperty>,
+    ) -> anyhow::Result<()> {
+        let mut by_document: Vec<(u64, Vec<u64>)> = Vec::new();
+        for &(document, range) in ranges {
+            match by_document.iter_mut().find(|(it, _)| *it == document) {
+                Some((_, ranges)) => ranges.push(range),
+                None => by_document.push((document, vec![range])),
+            }
+        }
+        for (document, in_vs) in by_document {
+            self.edge(Edge::Item(Item { out_v: result, in_vs, shard: document, property }))?;
+        }
+        Ok(())
+    }
+}
+
+fn position(line_index: &LineIndex, offset: ide::TextSize) -> lsp_types::Position {
+    let line_col = line_index.to_utf16(line_index.line_col(offset));
+    lsp_types::Position::new(line_col.line, line_col.col)
+}
+
+#[derive(Serialize)]
+struct Entry {
+    id: u64,
+    #[serde(flatten)]
+    element: Element,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Element {
+    Vertex(Vertex),
+    Edge(Edge),
+}
+
+#[derive(Serialize)]
+#[serde(tag = "label", rename_all = "camelCase")]
+enum Vertex {
+    #[serde(rename_all = "camelCase")]
+    MetaData {
+        version: &'static str,
+        project_root: Url,
+        position_encoding: &'static str,
+        tool_info: ToolInfo,
+    },
+    #[serde(rename_all = "camelCase")]
+    Document {
+        uri: Url,
+        language_id: &'static str,
+    },
+    Range {
+        start: lsp_types::Position,
+        end: lsp_types::Position,
+    },
+    ResultSet {},
+    HoverResult {
+        result: lsp_types::Hover,
+    },
+    DefinitionResult {},
+    ReferenceResult {},
+    Moniker(lsp_types::Moniker),
+    PackageInformation {
+        name: String,
+        manager: &'static str,
+        version: String,
+    },
+}
+
+#[derive(Serialize)]
+struct ToolInfo {
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "label")]
+enum Edge {
+    #[serde(rename = "contains")]
+    Contains(EdgeDataMultiIn),
+    #[serde(rename = "next")]
+    Next(EdgeData),
+    #[serde(rename = "moniker")]
+    Moniker(EdgeData),
+    #[serde(rename = "packageInformation")]
+    PackageInformation(EdgeData),
+    #[serde(rename = "textDocument/hover")]
+    Hover(EdgeData),
+    #[serde(rename = "textDocument/definition")]
+    Definition(EdgeData),
+    #[serde(rename = "textDocument/references")]
+    References(EdgeData),
+    #[serde(rename = "item")]
+    Item(Item),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EdgeData {
+    out_v: u64,
+    in_v: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EdgeDataMultiIn {
+    out_v: u64,
+    in_vs: Vec<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    out_v: u64,
+    in_vs: Vec<u64>,
+    shard: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    property: Option<ItemProperty>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ItemProperty {
+    Definitions,
+    References,
+}
+
+#[cfg(test)]
+mod tests {
+    use ide::AnalysisHost;
+    use ide_db::base_db::fixture::ChangeFixture;
+    use rustc_hash::FxHashMap;
+    use serde_json::Value;
+
+    use super::*;
+
+    /// The elements of a dump, checked to only refer to elements emitted before them.
+    struct Dump {
+        elements: FxHashMap<u64, Value>,
+    }
+
+    impl Dump {
+        fn new(fixture: &str) -> Dump {
+            let ChangeFixture { change, files, .. } = ChangeFixture::parse(fixture);
+            let mut host = AnalysisHost::default();
+            host.apply_change(change);
+            let files = files
+                .into_iter()
+                .enumerate()
+                .map(|(idx, file_id)| {
+                    (file_id, Url::parse(&format!("file:///{}.rs", idx)).unwrap())
+                })
+                .collect();
+            let index = Index::compute(&host.analysis(), host.raw_database(), files).unwrap();
+            let mut out = Vec::new();
+            LsifEmitter::new(&mut out, Url::parse("file:///").unwrap()).emit(&index).unwrap();
+
+            let mut elements = FxHashMap::default();
+            for (line, expected_id) in String::from_utf8(out).unwrap().lines().zip(1..) {
+                let element: Value = serde_json::from_str(line).unwrap();
+                assert_eq!(element["id"], expected_id, "ids are consecutive: {}", line);
+                let element_type = element["type"].as_str().unwrap();
+                assert!(element_type == "vertex" || element_type == "edge", "{}", line);
+                if element_type == "edge" {
+                    let mut targets = vec![&element["outV"]];
+                    targets.extend(element.get("inV"));
+                    targets.extend(element.get("shard"));
+                    targets.extend(
+                        element.get("inVs").into_iter().flat_map(|it| it.as_array().unwrap()),
+                    );
+                    assert!(targets.len() >= 2, "{}", line);
+                    for target in targets {
+                        let target = target.as_u64().unwrap();
+                        assert_eq!(
+                            elements.get(&target).map(|it: &Value| &it["type"]),
+                            Some(&"vertex".into()),
+                            "{}",
+                            line
+                        );
+                    }
+                }
+                elements.insert(expected_id, element);
+            }
+            Dump { elements }
+        }
+
+        fn vertices(&self, label: &str) -> Vec<&Value> {
+            let mut res: Vec<_> = self
+                .elements
+                .values()
+                .filter(|it| it["type"] == "vertex" && it["label"] == label)
+                .collect();
+            res.sort_by_key(|it| it["id"].as_u64());
+            res
+        }
+
+        /// The vertices `label` edges lead to from `vertex`.
+        fn follow(&self, vertex: &Value, label: &str) -> Vec<&Value> {
+            let mut res: Vec<_> = self
+                .elements
+                .values()
+                .filter(|it| {
+                    it["type"] == "edge" && it["label"] == label && it["outV"] == vertex["id"]
+                })
+                .flat_map(|edge| {
+                    let in_vs =
+                        edge.get("inVs").and_then(Value::as_array).cloned().unwrap_or_default();
+                    edge.get("inV").cloned().into_iter().chain(in_vs)
+                })
+                .map(|id| &self.elements[&id.as_u64().unwrap()])
+                .collect();
+            res.sort_by_key(|it| it["id"].as_u64());
+            res
+        }
+
+        fn result_set_of(&self, moniker: &str) -> &Value {
+            let moniker = self
+                .vertices("moniker")
+                .into_iter()
+                .find(|it| it["identifier"] == moniker)
+                .unwrap_or_else(|| panic!("no moniker {}", moniker));
+            self.elements
+                .values()
+                .find(|it| it["label"] == "moniker" && it["inV"] == moniker["id"])
+                .map(|edge| &self.elements[&edge["outV"].as_u64().unwrap()])
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn two_file_project() {
+        let dump = Dump::new(
+            r#"
+//- /lib.rs crate:shapes env:CARGO_PKG_VERSION=0.1.0
+mod area;
+/// A square.
+pub struct Square {
+    pub side: u32,
+}
+fn helper() {}
+//- /area.rs
+use crate::Square;
+pub fn area(square: &Square) -> u32 {
+    square.side * square.side
+}
+"#,
+        );
+
+        let documents = dump.vertices("document");
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0]["uri"], "file:///0.rs");
+        assert_eq!(documents[0]["languageId"], "rust");
+        for document in &documents {
+            assert!(!dump.follow(document, "contains").is_empty());
+        }
+        assert_eq!(dump.vertices("metaData").len(), 1);
+
+        let packages = dump.vertices("packageInformation");
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0]["name"], "shapes");
+        assert_eq!(packages[0]["version"], "0.1.0");
+
+        // `Square` is defined in the first file and referenced twice in the second one.
+        let square = dump.result_set_of("shapes::Square");
+        let hover = dump.follow(square, "textDocument/hover");
+        let contents = hover[0]["result"]["contents"]["value"].as_str().unwrap();
+        assert!(contents.contains("pub struct Square"), "{}", contents);
+        assert!(contents.contains("A square."), "{}", contents);
+
+        let definition = dump.follow(square, "textDocument/definition");
+        let definition_ranges = dump.follow(definition[0], "item");
+        assert_eq!(definition_ranges.len(), 1);
+        assert_eq!(definition_ranges[0]["start"], serde_json::json!({"line": 2, "character": 11}));
+
+        let references = dump.follow(square, "textDocument/references");
+        let items: Vec<_> = dump
+            .elements
+            .values()
+            .filter(|it| it["label"] == "item" && it["outV"] == references[0]["id"])
+            .map(|it| {
+                (
+                    it["shard"].as_u64().unwrap(),
+                    it["property"].as_str().unwrap(),
+                    it["inVs"].as_array().unwrap().len(),
+                )
+            })
+            .collect();
+        let document_ids: Vec<_> = documents.iter().map(|it| it["id"].as_u64().unwrap()).collect();
+        assert_eq!(items.len(), 2);
+        assert!(items.contains(&(document_ids[0], "definitions", 1)), "{:?}", items);
+        assert!(items.contains(&(document_ids[1], "references", 2)), "{:?}", items);
+
+        // Every range leads to a result set.
+        for range in dump.vertices("range") {
+            assert_eq!(dump.follow(range, "next").len(), 1);
+        }
+
+        // Only public items get monikers.
+        let monikers: Vec<_> =
+            dump.vertices("moniker").iter().map(|it| it["identifier"].as_str().unwrap()).collect();
+        assert!(monikers.contains(&"shapes::Square"));
+        assert!(monikers.contains(&"shapes::area::area"));
+        assert!(!monikers.iter().any(|it| it.contains("helper")));
+        assert!(dump.vertices("moniker").iter().all(|it| it["kind"] == "export"));
+    }
+}
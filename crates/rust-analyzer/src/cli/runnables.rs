@@ -0,0 +1,139 @@
+//! Lists all runnables (tests, benches, binaries, doctests) of a project as JSON,
+//! together with the cargo arguments needed to run them.
+
+use ide::{Analysis, FileId, LineCol, LineIndex, RunnableKind, TextRange, TextSize};
+use ide_db::base_db::SourceDatabaseExt;
+use project_model::{CargoConfig, ProjectManifest, ProjectWorkspace};
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+use vfs::{AbsPathBuf, Vfs};
+
+use crate::{
+    cargo_target_spec::CargoTargetSpec,
+    cli::{
+        all_modules, flags,
+        load_cargo::{load_workspace, LoadCargoConfig},
+    },
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RunnableData {
+    kind: &'static str,
+    name: String,
+    file: String,
+    range: Range,
+    cargo_args: Vec<String>,
+    executable_args: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct Range {
+    start: Position,
+    end: Position,
+}
+
+/// One-based line and column.
+#[derive(Serialize)]
+pub(super) struct Position {
+    line: u32,
+    col: u32,
+}
+
+impl flags::Runnables {
+    pub fn run(self) -> anyhow::Result<()> {
+        let cargo_config = CargoConfig::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro: !self.disable_proc_macros,
+            prefill_caches: false,
+        };
+        let root = AbsPathBuf::assert(std::env::current_dir()?.join(&self.path));
+        let manifest = ProjectManifest::discover_single(&root)?;
+        let workspace = ProjectWorkspace::load(manifest, &cargo_config, &|_| {})?;
+        let (host, vfs, _proc_macro) =
+            load_workspace(workspace.clone(), &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+
+        let mut visited_files = FxHashSet::default();
+        let mut runnables = Vec::new();
+        for module in all_modules(db) {
+            let file_id = module.definition_source(db).file_id.original_file(db);
+            let source_root = db.source_root(db.file_source_root(file_id));
+            if source_root.is_library || !visited_files.insert(file_id) {
+                continue;
+            }
+
+            let spec = cargo_target_spec(&analysis, &vfs, &workspace, file_id)?;
+            let line_index = analysis.file_line_index(file_id)?;
+            let file = vfs.file_path(file_id).to_string();
+            for runnable in analysis.runnables(file_id)? {
+                let (cargo_args, executable_args) = CargoTargetSpec::runnable_args(
+                    &cargo_config,
+                    spec.clone(),
+                    &runnable.kind,
+                    &runnable.cfg,
+                );
+                let range = runnable.nav.focus_or_full_range();
+                runnables.push((
+                    range.start(),
+                    RunnableData {
+                        kind: runnable_kind(&runnable.kind),
+                        name: runnable.nav.name.to_string(),
+                        file: file.clone(),
+                        range: one_based_range(&line_index, range),
+                        cargo_args,
+                        executable_args,
+                    },
+                ));
+            }
+        }
+
+        runnables.sort_by(|(lhs_offset, lhs), (rhs_offset, rhs)| {
+            (&lhs.file, lhs_offset).cmp(&(&rhs.file, rhs_offset))
+        });
+        let runnables: Vec<_> = runnables.into_iter().map(|(_, data)| data).collect();
+        println!("{}", serde_json::to_string_pretty(&runnables)?);
+
+        Ok(())
+    }
+}
+
+fn cargo_target_spec(
+    analysis: &Analysis,
+    vfs: &Vfs,
+    workspace: &ProjectWorkspace,
+    file_id: FileId,
+) -> anyhow::Result<Option<CargoTargetSpec>> {
+    let cargo = match workspace {
+        ProjectWorkspace::Cargo { cargo, .. } => cargo,
+        ProjectWorkspace::Json { .. } | ProjectWorkspace::DetachedFiles { .. } => return Ok(None),
+    };
+    let crate_id = match analysis.crate_for(file_id)?.first() {
+        Some(crate_id) => *crate_id,
+        None => return Ok(None),
+    };
+    let crate_root = vfs.file_path(analysis.crate_root(crate_id)?);
+    let target = crate_root.as_path().and_then(|path| cargo.target_by_root(path));
+    Ok(target.map(|target| CargoTargetSpec::for_target(cargo, target)))
+}
+
+fn runnable_kind(kind: &RunnableKind) -> &'static str {
+    match kind {
+        RunnableKind::Test { .. } => "test",
+        RunnableKind::TestMod { .. } => "testMod",
+        RunnableKind::Bench { .. } => "bench",
+        RunnableKind::DocTest { .. } => "docTest",
+        RunnableKind::Bin => "bin",
+    }
+}
+
+pub(super) fn one_based_range(line_index: &LineIndex, range: TextRange) -> Range {
+    Range { start: position(line_index, range.start()), end: position(line_index, range.end()) }
+}
+
+fn position(line_index: &LineIndex, offset: TextSize) -> Position {
+    let LineCol { line, col } = line_index.line_col(offset);
+    Position { line: line + 1, col: col + 1 }
+}
@@ -1,9 +1,16 @@
 //! Applies structured search replace rules from the command line.
 
+use std::sync::Arc;
+
+use ide::FileId;
+use ide_db::{base_db::SourceDatabaseExt, RootDatabase};
 use ide_ssr::MatchFinder;
 use project_model::CargoConfig;
+use rustc_hash::FxHashMap;
+use text_edit::TextEdit;
 
 use crate::cli::{
+    diff::unified_diff,
     flags,
     load_cargo::{load_workspace_at, LoadCargoConfig},
     Result,
@@ -11,42 +18,125 @@ use crate::cli::{
 
 impl flags::Ssr {
     pub fn run(self) -> Result<()> {
-        use ide_db::base_db::SourceDatabaseExt;
+        let current_dir = std::env::current_dir()?;
         let cargo_config = CargoConfig::default();
         let load_cargo_config = LoadCargoConfig {
             load_out_dirs_from_check: true,
             with_proc_macro: true,
             prefill_caches: false,
         };
-        let (host, vfs, _proc_macro) = load_workspace_at(
-            &std::env::current_dir()?,
-            &cargo_config,
-            &load_cargo_config,
-            &|_| {},
-        )?;
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&current_dir, &cargo_config, &load_cargo_config, &|_| {})?;
         let db = host.raw_database();
         let mut match_finder = MatchFinder::at_first_file(db)?;
         for rule in self.rule {
             match_finder.add_rule(rule)?;
         }
-        let edits = match_finder.edits();
-        for (file_id, edit) in edits {
-            if let Some(path) = vfs.file_path(file_id).as_path() {
-                let mut contents = db.file_text(file_id).to_string();
-                edit.apply(&mut contents);
+        let changes = changed_files(db, &match_finder, &self.path, |file_id| {
+            let path = vfs.file_path(file_id);
+            let path = path.as_path()?;
+            let path = path.as_ref().strip_prefix(&current_dir).unwrap_or(path.as_ref());
+            Some(path.display().to_string())
+        });
+
+        let mut num_matches = 0;
+        for change in &changes {
+            num_matches += change.matches;
+            if self.dry_run {
+                print!("{}", unified_diff(&change.path, &change.text, &change.edit));
+            } else if let Some(path) = vfs.file_path(change.file_id).as_path() {
+                let mut contents = change.text.to_string();
+                change.edit.apply(&mut contents);
                 std::fs::write(path, contents)?;
             }
         }
+        eprintln!("{} matches in {} files", num_matches, changes.len());
         Ok(())
     }
 }
 
+struct FileChange {
+    file_id: FileId,
+    /// The path shown to the user and matched against the `--path` globs.
+    path: String,
+    text: Arc<String>,
+    edit: TextEdit,
+    matches: usize,
+}
+
+/// Computes the edits of the rules in `match_finder`, for files whose path
+/// matches one of `globs`, or for all files if there are none. The changes are
+/// sorted by path.
+fn changed_files(
+    db: &RootDatabase,
+    match_finder: &MatchFinder,
+    globs: &[String],
+    path_of: impl Fn(FileId) -> Option<String>,
+) -> Vec<FileChange> {
+    let mut matches_by_file = FxHashMap::default();
+    for m in match_finder.matches().matches {
+        *matches_by_file.entry(m.file_id()).or_insert(0) += 1;
+    }
+
+    let mut changes: Vec<_> = match_finder
+        .edits()
+        .into_iter()
+        .filter_map(|(file_id, edit)| {
+            let path = path_of(file_id)?;
+            if !globs.is_empty() && !globs.iter().any(|glob| path_matches(glob, &path)) {
+                return None;
+            }
+            Some(FileChange {
+                file_id,
+                path,
+                text: db.file_text(file_id),
+                edit,
+                matches: matches_by_file.get(&file_id).copied().unwrap_or(0),
+            })
+        })
+        .collect();
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+/// Whether `path` matches `glob`, or is inside a directory that does.
+fn path_matches(glob: &str, path: &str) -> bool {
+    let dir_glob = glob.trim_end_matches('/');
+    glob_matches(glob, path)
+        || path.match_indices('/').any(|(idx, _)| glob_matches(dir_glob, &path[..idx]))
+}
+
+/// Matches `path` against a glob, where `*` matches within a path component,
+/// `**` across components, and `?` matches a single character.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    if let Some(rest) = glob.strip_prefix("**/") {
+        return glob_matches(rest, path)
+            || path.match_indices('/').any(|(idx, _)| glob_matches(rest, &path[idx + 1..]));
+    }
+    if let Some(rest) = glob.strip_prefix("**") {
+        return (0..=path.len())
+            .filter(|&idx| path.is_char_boundary(idx))
+            .any(|idx| glob_matches(rest, &path[idx..]));
+    }
+    if let Some(rest) = glob.strip_prefix('*') {
+        let component_len = path.find('/').unwrap_or_else(|| path.len());
+        return (0..=component_len)
+            .filter(|&idx| path.is_char_boundary(idx))
+            .any(|idx| glob_matches(rest, &path[idx..]));
+    }
+    match (glob.chars().next(), path.chars().next()) {
+        (None, None) => true,
+        (Some('?'), Some(c)) if c != '/' => glob_matches(&glob[1..], &path[c.len_utf8()..]),
+        (Some(g), Some(c)) if g == c => glob_matches(&glob[g.len_utf8()..], &path[c.len_utf8()..]),
+        _ => false,
+    }
+}
+
 impl flags::Search {
     /// Searches for `patterns`, printing debug information for any nodes whose text exactly matches
     /// `debug_snippet`. This is intended for debugging and probably isn't in it's current form useful
     /// for much else.
     pub fn run(self) -> Result<()> {
-        use ide_db::base_db::SourceDatabaseExt;
         use ide_db::symbol_index::SymbolsDatabase;
         let cargo_config = CargoConfig::default();
         let load_cargo_config = LoadCargoConfig {
@@ -84,3 +174,157 @@ impl flags::Search {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use ide_db::base_db::fixture::ChangeFixture;
+
+    use super::*;
+
+    fn check(globs: &[&str], expect: expect_test::Expect) {
+        let ChangeFixture { change, .. } = ChangeFixture::parse(
+            r#"
+//- /src/lib.rs crate:app
+pub fn foo(_: i32) {}
+pub fn bar(_: i32) {}
+mod a;
+mod b;
+//- /src/a/mod.rs
+use crate::{bar, foo};
+fn f() {
+    foo(1);
+}
+//- /src/b/mod.rs
+use crate::{bar, foo};
+fn g() {
+    foo(2);
+    foo(3);
+}
+"#,
+        );
+        let mut db = RootDatabase::default();
+        db.apply_change(change);
+        let mut match_finder = MatchFinder::at_first_file(&db).unwrap();
+        match_finder.add_rule("foo($a) ==>> bar($a)".parse().unwrap()).unwrap();
+        let globs: Vec<String> = globs.iter().map(|it| it.to_string()).collect();
+        let changes = changed_files(&db, &match_finder, &globs, |file_id| {
+            let source_root = db.source_root(db.file_source_root(file_id));
+            let path = source_root.path_for_file(&file_id)?.to_string();
+            Some(path.trim_start_matches('/').to_string())
+        });
+        let actual = changes
+            .iter()
+            .map(|change| {
+                format!(
+                    "matches: {}\n{}",
+                    change.matches,
+                    unified_diff(&change.path, &change.text, &change.edit)
+                )
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn renders_diffs_of_all_files() {
+        check(
+            &[],
+            expect![[r#"
+            matches: 1
+            --- a/src/a/mod.rs
+            +++ b/src/a/mod.rs
+            @@ -1,4 +1,4 @@
+             use crate::{bar, foo};
+             fn f() {
+            -    foo(1);
+            +    bar(1);
+             }
+            matches: 2
+            --- a/src/b/mod.rs
+            +++ b/src/b/mod.rs
+            @@ -1,5 +1,5 @@
+             use crate::{bar, foo};
+             fn g() {
+            -    foo(2);
+            -    foo(3);
+            +    bar(2);
+            +    bar(3);
+             }
+        "#]],
+        );
+    }
+
+    #[test]
+    fn filters_files_by_glob() {
+        check(
+            &["src/b/**"],
+            expect![[r#"
+            matches: 2
+            --- a/src/b/mod.rs
+            +++ b/src/b/mod.rs
+            @@ -1,5 +1,5 @@
+             use crate::{bar, foo};
+             fn g() {
+            -    foo(2);
+            -    foo(3);
+            +    bar(2);
+            +    bar(3);
+             }
+        "#]],
+        );
+        check(
+            &["src/*/mod.rs"],
+            expect![[r#"
+            matches: 1
+            --- a/src/a/mod.rs
+            +++ b/src/a/mod.rs
+            @@ -1,4 +1,4 @@
+             use crate::{bar, foo};
+             fn f() {
+            -    foo(1);
+            +    bar(1);
+             }
+            matches: 2
+            --- a/src/b/mod.rs
+            +++ b/src/b/mod.rs
+            @@ -1,5 +1,5 @@
+             use crate::{bar, foo};
+             fn g() {
+            -    foo(2);
+            -    foo(3);
+            +    bar(2);
+            +    bar(3);
+             }
+        "#]],
+        );
+        check(
+            &["**/a"],
+            expect![[r#"
+            matches: 1
+            --- a/src/a/mod.rs
+            +++ b/src/a/mod.rs
+            @@ -1,4 +1,4 @@
+             use crate::{bar, foo};
+             fn f() {
+            -    foo(1);
+            +    bar(1);
+             }
+        "#]],
+        );
+        check(&["tests/**"], expect![[r#""#]]);
+    }
+
+    #[test]
+    fn glob_syntax() {
+        assert!(glob_matches("src/*.rs", "src/lib.rs"));
+        assert!(!glob_matches("src/*.rs", "src/a/mod.rs"));
+        assert!(glob_matches("src/**/*.rs", "src/lib.rs"));
+        assert!(glob_matches("src/**/*.rs", "src/a/b/mod.rs"));
+        assert!(glob_matches("src/?/mod.rs", "src/a/mod.rs"));
+        assert!(!glob_matches("src/?/mod.rs", "src/ab/mod.rs"));
+        assert!(path_matches("src/a", "src/a/mod.rs"));
+        assert!(path_matches("src/a/", "src/a/mod.rs"));
+        assert!(!path_matches("src/a", "src/ab/mod.rs"));
+    }
+}
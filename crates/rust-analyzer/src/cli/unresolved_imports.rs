@@ -0,0 +1,70 @@
+//! Reports unresolved imports and extern crates in all workspace crates. Exits
+//! with a non-zero status code if any are found.
+
+use hir::{db::AstDatabase, AnyDiagnostic, InFile, Semantics};
+use ide::LineCol;
+use ide_db::base_db::SourceDatabaseExt;
+use syntax::SyntaxNodePtr;
+
+use crate::cli::{
+    all_modules, flags,
+    load_cargo::{load_workspace_at, LoadCargoConfig},
+};
+
+impl flags::UnresolvedImports {
+    pub fn run(self) -> anyhow::Result<()> {
+        let cargo_config = Default::default();
+        let load_cargo_config = LoadCargoConfig {
+            load_out_dirs_from_check: !self.disable_build_scripts,
+            with_proc_macro: !self.disable_proc_macros,
+            prefill_caches: false,
+        };
+        let (host, vfs, _proc_macro) =
+            load_workspace_at(&self.path, &cargo_config, &load_cargo_config, &|_| {})?;
+        let db = host.raw_database();
+        let analysis = host.analysis();
+        let sema = Semantics::new(db);
+
+        let mut found = 0;
+        for module in all_modules(db) {
+            let file_id = module.definition_source(db).file_id.original_file(db);
+            if db.source_root(db.file_source_root(file_id)).is_library {
+                continue;
+            }
+
+            let mut diagnostics = Vec::new();
+            module.diagnostics(db, &mut diagnostics);
+            for diagnostic in diagnostics {
+                let (what, node): (_, InFile<SyntaxNodePtr>) = match diagnostic {
+                    AnyDiagnostic::UnresolvedImport(it) => {
+                        ("unresolved import", it.decl.map(Into::into))
+                    }
+                    AnyDiagnostic::UnresolvedExternCrate(it) => {
+                        ("unresolved extern crate", it.decl.map(Into::into))
+                    }
+                    _ => continue,
+                };
+                found += 1;
+
+                let range = sema.diagnostics_display_range(node.clone());
+                let LineCol { line, col } =
+                    analysis.file_line_index(range.file_id)?.line_col(range.range.start());
+                let text = node.value.to_node(&db.parse_or_expand(node.file_id).unwrap());
+                println!(
+                    "{}:{}:{}: {} `{}`",
+                    vfs.file_path(range.file_id),
+                    line + 1,
+                    col + 1,
+                    what,
+                    text
+                );
+            }
+        }
+
+        if found != 0 {
+            anyhow::bail!("found {} unresolved import(s)", found)
+        }
+
+        Ok(())
+    }
+}
@@ -7,12 +7,20 @@
 //! configure the server itself, feature flags are passed into analysis, and
 //! tweak things like automatic insertion of `()` in completions.
 
-use std::{ffi::OsString, iter, path::PathBuf};
+use std::{
+    cmp::Reverse,
+    convert::TryFrom,
+    ffi::OsString,
+    iter,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use flycheck::FlycheckConfig;
 use ide::{
-    AssistConfig, CompletionConfig, DiagnosticsConfig, HighlightRelatedConfig, HoverConfig,
-    HoverDocFormat, InlayHintsConfig, JoinLinesConfig,
+    AssistConfig, ClosureReturnTypeHints, CompletionConfig, DiagnosticsConfig,
+    HighlightRelatedConfig, HoverConfig, HoverDocFormat, InlayHintsConfig, JoinLinesConfig,
+    LifetimeElisionHints, ReferenceSearchConfig,
 };
 use ide_db::helpers::{
     insert_use::{ImportGranularity, InsertUseConfig, PrefixKind},
@@ -22,13 +30,13 @@ use lsp_types::{ClientCapabilities, MarkupKind};
 use project_model::{CargoConfig, ProjectJson, ProjectJsonData, ProjectManifest, RustcSource};
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{de::DeserializeOwned, Deserialize};
-use vfs::AbsPathBuf;
+use vfs::{AbsPath, AbsPathBuf};
 
 use crate::{
     caps::completion_item_edit_resolve,
     diagnostics::DiagnosticsMapConfig,
     line_index::OffsetEncoding,
-    lsp_ext::supports_utf8,
+    lsp_ext::negotiated_encoding,
     lsp_ext::WorkspaceSymbolSearchScope,
     lsp_ext::{self, WorkspaceSymbolSearchKind},
 };
@@ -104,6 +112,11 @@ config_data! {
         /// checking. The command should include `--message-format=json` or
         /// similar option.
         checkOnSave_overrideCommand: Option<Vec<String>> = "null",
+        /// Check the whole workspace on save. If disabled, only the package
+        /// containing the saved file is checked (`-p <package>`), narrowed down
+        /// to its lib or bin target unless `#rust-analyzer.checkOnSave.allTargets#`
+        /// is set.
+        checkOnSave_workspace: bool                      = "true",
 
         /// Whether to add argument snippets when completing functions.
         /// Only applies when `#rust-analyzer.completion.addCallParenthesis#` is set.
@@ -171,12 +184,30 @@ config_data! {
         /// Use markdown syntax for links in hover.
         hover_linksInHover |
         hoverActions_linksInHover: bool = "true",
+        /// Whether to show a preview of the macro expansion when hovering a
+        /// macro call.
+        hover_macroExpansion: bool      = "true",
+        /// Whether to show the inferred generic arguments when hovering a
+        /// call to a generic function.
+        hover_genericArgs: bool         = "true",
+        /// Whether to show the types a local or expression auto-derefs to,
+        /// like `Rc<Box<T>> -> Box<T> -> T`.
+        hover_derefChain: bool          = "false",
+        /// Maximum number of lines of documentation to show on hover, longer
+        /// documentation is truncated. Shows everything when `null`.
+        hover_maxDocLines: Option<usize> = "null",
+        /// Maximum nesting depth of types to show on hover, deeper parts of
+        /// the type are replaced with `…`. Shows everything when `null`.
+        hover_maxTypeDepth: Option<usize> = "10",
 
         /// Whether to show `Debug` action. Only applies when
         /// `#rust-analyzer.hoverActions.enable#` is set.
         hoverActions_debug: bool           = "true",
         /// Whether to show HoverActions in Rust files.
         hoverActions_enable: bool          = "true",
+        /// Whether to show `Evaluate` action on constants and `const fn` calls.
+        /// Only applies when `#rust-analyzer.hoverActions.enable#` is set.
+        hoverActions_evalFunction: bool    = "true",
         /// Whether to show `Go to Type Definition` action. Only applies when
         /// `#rust-analyzer.hoverActions.enable#` is set.
         hoverActions_gotoTypeDef: bool     = "true",
@@ -190,8 +221,17 @@ config_data! {
         /// `#rust-analyzer.hoverActions.enable#` is set.
         hoverActions_run: bool             = "true",
 
+        /// Whether to show inlay hints for binding modes of patterns changed by
+        /// match ergonomics.
+        inlayHints_bindingModeHints: bool   = "false",
         /// Whether to show inlay type hints for method chains.
         inlayHints_chainingHints: bool      = "true",
+        /// Whether to show inlay type hints for return types of closures
+        /// without a written one.
+        inlayHints_closureReturnTypeHints: ClosureReturnTypeHintsDef = "\"never\"",
+        /// Whether to show inlay hints for elided lifetimes in function
+        /// signatures.
+        inlayHints_lifetimeElisionHints: LifetimeElisionHintsDef = "\"never\"",
         /// Maximum length for inlay hints. Set to null to have an unlimited length.
         inlayHints_maxLength: Option<usize> = "25",
         /// Whether to show function parameter name inlay hints at the call
@@ -200,12 +240,16 @@ config_data! {
         /// Whether to show inlay type hints for variables.
         inlayHints_typeHints: bool          = "true",
 
-        /// Join lines inserts else between consecutive ifs.
+        /// Join lines inserts else between consecutive ifs and turns an
+        /// `else` block holding a single `if` into `else if`.
         joinLines_joinElseIf: bool = "true",
         /// Join lines removes trailing commas.
         joinLines_removeTrailingComma: bool = "true",
         /// Join lines unwraps trivial blocks.
         joinLines_unwrapTrivialBlock: bool = "true",
+        /// Join lines merges adjacent string literals and removes escaped
+        /// line breaks inside of them.
+        joinLines_joinStringLiterals: bool = "true",
 
         /// Whether to show `Debug` lens. Only applies when
         /// `#rust-analyzer.lens.enable#` is set.
@@ -243,10 +287,18 @@ config_data! {
 
         /// Enable support for procedural macros, implies `#rust-analyzer.cargo.runBuildScripts#`.
         procMacro_enable: bool                     = "true",
+        /// Number of seconds a single proc macro expansion may take. Once an
+        /// expansion times out, the other macros of the same proc-macro crate are disabled
+        /// until it is rebuilt. Set to `0` to disable the timeout.
+        procMacro_expansionTimeout: usize          = "10",
         /// Internal config, path to proc-macro server executable (typically,
         /// this is rust-analyzer itself, but we override this in tests).
         procMacro_server: Option<PathBuf>          = "null",
 
+        /// Whether find all references on a trait function should also include
+        /// the references to the trait declaration and all of its implementations.
+        references_includeTraitFallback: bool = "false",
+
         /// Command to be executed instead of 'cargo' for runnables.
         runnables_overrideCargo: Option<String> = "null",
         /// Additional arguments to be passed to cargo for runnables such as
@@ -289,9 +341,19 @@ impl Default for ConfigData {
 pub struct Config {
     pub caps: lsp_types::ClientCapabilities,
     data: ConfigData,
+    /// The last non-empty settings object, kept to re-apply root overrides on top of it.
+    json: serde_json::Value,
+    /// Raw per-root override objects, as sent by the client.
+    root_overrides: Vec<(AbsPathBuf, serde_json::Value)>,
+    /// Settings of each overridden root, with the most nested root first.
+    root_data: Vec<(AbsPathBuf, ConfigData)>,
     detached_files: Vec<AbsPathBuf>,
     pub discovered_projects: Option<Vec<ProjectManifest>>,
     pub root_path: AbsPathBuf,
+    /// Workspace folders of the client, used to request scoped settings.
+    pub workspace_roots: Vec<AbsPathBuf>,
+    /// The `general.positionEncodings` client capability, most preferred first.
+    pub position_encodings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -346,6 +408,7 @@ pub struct HoverActionsConfig {
     pub run: bool,
     pub debug: bool,
     pub goto_type_def: bool,
+    pub eval_function: bool,
 }
 
 impl HoverActionsConfig {
@@ -355,10 +418,15 @@ impl HoverActionsConfig {
         run: false,
         debug: false,
         goto_type_def: false,
+        eval_function: false,
     };
 
     pub fn any(&self) -> bool {
-        self.implementations || self.references || self.runnable() || self.goto_type_def
+        self.implementations
+            || self.references
+            || self.runnable()
+            || self.goto_type_def
+            || self.eval_function
     }
 
     pub fn none(&self) -> bool {
@@ -417,6 +485,7 @@ pub struct ClientCommandsConfig {
     pub show_reference: bool,
     pub goto_location: bool,
     pub trigger_parameter_hints: bool,
+    pub eval_function: bool,
 }
 
 impl Config {
@@ -424,21 +493,56 @@ impl Config {
         Config {
             caps,
             data: ConfigData::default(),
+            json: serde_json::Value::Null,
+            root_overrides: Vec::new(),
+            root_data: Vec::new(),
             detached_files: Vec::new(),
             discovered_projects: None,
+            workspace_roots: vec![root_path.clone()],
+            position_encodings: Vec::new(),
             root_path,
         }
     }
-    pub fn update(&mut self, mut json: serde_json::Value) {
+    /// Updates the settings from `json`.
+    ///
+    /// `root_overrides` maps workspace roots to settings objects which take precedence over
+    /// `json` for everything inside that root. If it is `None`, the `workspaceFolders` field of
+    /// `json` is used instead, and if that is missing too, the previous overrides are kept.
+    pub fn update(
+        &mut self,
+        mut json: serde_json::Value,
+        root_overrides: Option<FxHashMap<AbsPathBuf, serde_json::Value>>,
+    ) {
         log::info!("updating config from JSON: {:#}", json);
-        if json.is_null() || json.as_object().map_or(false, |it| it.is_empty()) {
-            return;
+        let root_overrides = root_overrides.or_else(|| {
+            let folders = json.get_mut("workspaceFolders")?.take();
+            Some(root_overrides_from_json(folders))
+        });
+        if let Some(root_overrides) = root_overrides {
+            self.root_overrides = root_overrides.into_iter().collect();
+        }
+
+        if !(json.is_null() || json.as_object().map_or(false, |it| it.is_empty())) {
+            self.detached_files = get_field::<Vec<PathBuf>>(&mut json, "detachedFiles", None, "[]")
+                .into_iter()
+                .map(AbsPathBuf::assert)
+                .collect();
+            self.data = ConfigData::from_json(json.clone());
+            self.json = json;
         }
-        self.detached_files = get_field::<Vec<PathBuf>>(&mut json, "detachedFiles", None, "[]")
-            .into_iter()
-            .map(AbsPathBuf::assert)
-            .collect();
-        self.data = ConfigData::from_json(json);
+
+        let mut root_data = self
+            .root_overrides
+            .iter()
+            .map(|(root, overrides)| {
+                let mut json = self.json.clone();
+                merge_json(&mut json, overrides.clone());
+                (root.clone(), ConfigData::from_json(json))
+            })
+            .collect::<Vec<_>>();
+        root_data
+            .sort_by_key(|(root, _)| Reverse(AsRef::<Path>::as_ref(root).components().count()));
+        self.root_data = root_data;
     }
 
     pub fn json_schema() -> serde_json::Value {
@@ -458,6 +562,14 @@ macro_rules! try_or {
 }
 
 impl Config {
+    /// Returns the settings that apply to `path`, taking root overrides into account.
+    fn data_for(&self, path: &AbsPath) -> (&AbsPath, &ConfigData) {
+        self.root_data
+            .iter()
+            .find(|(root, _)| path.starts_with(root))
+            .map_or((self.root_path.as_path(), &self.data), |(root, data)| (root.as_path(), data))
+    }
+
     pub fn linked_projects(&self) -> Vec<LinkedProject> {
         if self.data.linkedProjects.is_empty() {
             self.discovered_projects
@@ -583,11 +695,7 @@ impl Config {
         )
     }
     pub fn offset_encoding(&self) -> OffsetEncoding {
-        if supports_utf8(&self.caps) {
-            OffsetEncoding::Utf8
-        } else {
-            OffsetEncoding::Utf16
-        }
+        negotiated_encoding(&self.caps, &self.position_encodings)
     }
 
     fn experimental(&self, index: &'static str) -> bool {
@@ -629,6 +737,12 @@ impl Config {
         };
         Some((path, vec!["proc-macro".into()]))
     }
+    pub fn proc_macro_expansion_timeout(&self) -> Option<Duration> {
+        match self.data.procMacro_expansionTimeout {
+            0 => None,
+            secs => Some(Duration::from_secs(secs as u64)),
+        }
+    }
     pub fn expand_proc_attr_macros(&self) -> bool {
         self.data.experimental_procAttrMacros
     }
@@ -638,7 +752,10 @@ impl Config {
                 "notify" => FilesWatcher::Notify,
                 "client" | _ => FilesWatcher::Client,
             },
-            exclude: self.data.files_excludeDirs.iter().map(|it| self.root_path.join(it)).collect(),
+            exclude: iter::once((&self.root_path, &self.data))
+                .chain(self.root_data.iter().map(|(root, data)| (root, data)))
+                .flat_map(|(root, data)| data.files_excludeDirs.iter().map(move |it| root.join(it)))
+                .collect(),
         }
     }
     pub fn notifications(&self) -> NotificationsConfig {
@@ -651,24 +768,12 @@ impl Config {
         self.data.cargo_runBuildScripts || self.data.procMacro_enable
     }
     pub fn cargo(&self) -> CargoConfig {
-        let rustc_source = self.data.rustcSource.as_ref().map(|rustc_src| {
-            if rustc_src == "discover" {
-                RustcSource::Discover
-            } else {
-                RustcSource::Path(self.root_path.join(rustc_src))
-            }
-        });
-
-        CargoConfig {
-            no_default_features: self.data.cargo_noDefaultFeatures,
-            all_features: self.data.cargo_allFeatures,
-            features: self.data.cargo_features.clone(),
-            target: self.data.cargo_target.clone(),
-            rustc_source,
-            no_sysroot: self.data.cargo_noSysroot,
-            unset_test_crates: self.data.cargo_unsetTest.clone(),
-            wrap_rustc_in_build_scripts: self.data.cargo_useRustcWrapperForBuildScripts,
-        }
+        cargo_config(&self.root_path, &self.data)
+    }
+    /// Like [`Config::cargo`], but for the workspace containing `path`.
+    pub fn cargo_for(&self, path: &AbsPath) -> CargoConfig {
+        let (root, data) = self.data_for(path);
+        cargo_config(root, data)
     }
 
     pub fn rustfmt(&self) -> RustfmtConfig {
@@ -685,40 +790,11 @@ impl Config {
         }
     }
     pub fn flycheck(&self) -> Option<FlycheckConfig> {
-        if !self.data.checkOnSave_enable {
-            return None;
-        }
-        let flycheck_config = match &self.data.checkOnSave_overrideCommand {
-            Some(args) if !args.is_empty() => {
-                let mut args = args.clone();
-                let command = args.remove(0);
-                FlycheckConfig::CustomCommand { command, args }
-            }
-            Some(_) | None => FlycheckConfig::CargoCommand {
-                command: self.data.checkOnSave_command.clone(),
-                target_triple: self
-                    .data
-                    .checkOnSave_target
-                    .clone()
-                    .or_else(|| self.data.cargo_target.clone()),
-                all_targets: self.data.checkOnSave_allTargets,
-                no_default_features: self
-                    .data
-                    .checkOnSave_noDefaultFeatures
-                    .unwrap_or(self.data.cargo_noDefaultFeatures),
-                all_features: self
-                    .data
-                    .checkOnSave_allFeatures
-                    .unwrap_or(self.data.cargo_allFeatures),
-                features: self
-                    .data
-                    .checkOnSave_features
-                    .clone()
-                    .unwrap_or_else(|| self.data.cargo_features.clone()),
-                extra_args: self.data.checkOnSave_extraArgs.clone(),
-            },
-        };
-        Some(flycheck_config)
+        flycheck_config(&self.data)
+    }
+    /// Like [`Config::flycheck`], but for the workspace containing `path`.
+    pub fn flycheck_for(&self, path: &AbsPath) -> Option<FlycheckConfig> {
+        flycheck_config(self.data_for(path).1)
     }
     pub fn runnables(&self) -> RunnablesConfig {
         RunnablesConfig {
@@ -731,6 +807,17 @@ impl Config {
             type_hints: self.data.inlayHints_typeHints,
             parameter_hints: self.data.inlayHints_parameterHints,
             chaining_hints: self.data.inlayHints_chainingHints,
+            binding_mode_hints: self.data.inlayHints_bindingModeHints,
+            closure_return_type_hints: match self.data.inlayHints_closureReturnTypeHints {
+                ClosureReturnTypeHintsDef::Always => ClosureReturnTypeHints::Always,
+                ClosureReturnTypeHintsDef::WithBlock => ClosureReturnTypeHints::WithBlock,
+                ClosureReturnTypeHintsDef::Never => ClosureReturnTypeHints::Never,
+            },
+            lifetime_elision_hints: match self.data.inlayHints_lifetimeElisionHints {
+                LifetimeElisionHintsDef::Always => LifetimeElisionHints::Always,
+                LifetimeElisionHintsDef::SkipTrivial => LifetimeElisionHints::SkipTrivial,
+                LifetimeElisionHintsDef::Never => LifetimeElisionHints::Never,
+            },
             max_length: self.data.inlayHints_maxLength,
         }
     }
@@ -772,11 +859,15 @@ impl Config {
                     .snippet_support?,
                 false
             )),
+            helper_attributes: Vec::new(),
         }
     }
+    pub fn snippet_cap(&self) -> Option<SnippetCap> {
+        SnippetCap::new(self.experimental("snippetTextEdit"))
+    }
     pub fn assist(&self) -> AssistConfig {
         AssistConfig {
-            snippet_cap: SnippetCap::new(self.experimental("snippetTextEdit")),
+            snippet_cap: self.snippet_cap(),
             allowed: None,
             insert_use: self.insert_use_config(),
         }
@@ -786,6 +877,7 @@ impl Config {
             join_else_if: self.data.joinLines_joinElseIf,
             remove_trailing_comma: self.data.joinLines_removeTrailingComma,
             unwrap_trivial_blocks: self.data.joinLines_unwrapTrivialBlock,
+            join_string_literals: self.data.joinLines_joinStringLiterals,
         }
     }
     pub fn call_info_full(&self) -> bool {
@@ -808,6 +900,7 @@ impl Config {
             run: enable && self.data.hoverActions_run,
             debug: enable && self.data.hoverActions_debug,
             goto_type_def: enable && self.data.hoverActions_gotoTypeDef,
+            eval_function: enable && self.data.hoverActions_evalFunction,
         }
     }
     pub fn highlighting_strings(&self) -> bool {
@@ -835,6 +928,11 @@ impl Config {
                     HoverDocFormat::PlainText
                 }
             }),
+            macro_expansion: self.data.hover_macroExpansion,
+            generic_args: self.data.hover_genericArgs,
+            deref_chain: self.data.hover_derefChain,
+            max_doc_lines: self.data.hover_maxDocLines,
+            max_type_depth: self.data.hover_maxTypeDepth,
         }
     }
 
@@ -888,6 +986,7 @@ impl Config {
             show_reference: get("rust-analyzer.showReferences"),
             goto_location: get("rust-analyzer.gotoLocation"),
             trigger_parameter_hints: get("editor.action.triggerParameterHints"),
+            eval_function: get("rust-analyzer.evalFunction"),
         }
     }
 
@@ -899,6 +998,10 @@ impl Config {
             yield_points: self.data.highlightRelated_yieldPoints,
         }
     }
+
+    pub fn references(&self) -> ReferenceSearchConfig {
+        ReferenceSearchConfig { include_trait_fallback: self.data.references_includeTraitFallback }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -908,6 +1011,14 @@ enum ManifestOrProjectJson {
     ProjectJson(ProjectJsonData),
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum ClosureReturnTypeHintsDef {
+    Always,
+    WithBlock,
+    Never,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 enum ImportGranularityDef {
@@ -930,6 +1041,14 @@ enum ImportPrefixDef {
     ByCrate,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum LifetimeElisionHintsDef {
+    Always,
+    SkipTrivial,
+    Never,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 enum WorskpaceSymbolSearchScopeDef {
@@ -993,6 +1112,86 @@ macro_rules! _config_data {
 }
 use _config_data as config_data;
 
+fn cargo_config(root_path: &AbsPath, data: &ConfigData) -> CargoConfig {
+    let rustc_source = data.rustcSource.as_ref().map(|rustc_src| {
+        if rustc_src == "discover" {
+            RustcSource::Discover
+        } else {
+            RustcSource::Path(root_path.join(rustc_src))
+        }
+    });
+
+    CargoConfig {
+        no_default_features: data.cargo_noDefaultFeatures,
+        all_features: data.cargo_allFeatures,
+        features: data.cargo_features.clone(),
+        target: data.cargo_target.clone(),
+        rustc_source,
+        no_sysroot: data.cargo_noSysroot,
+        unset_test_crates: data.cargo_unsetTest.clone(),
+        wrap_rustc_in_build_scripts: data.cargo_useRustcWrapperForBuildScripts,
+    }
+}
+
+fn flycheck_config(data: &ConfigData) -> Option<FlycheckConfig> {
+    if !data.checkOnSave_enable {
+        return None;
+    }
+    let flycheck_config = match &data.checkOnSave_overrideCommand {
+        Some(args) if !args.is_empty() => {
+            let mut args = args.clone();
+            let command = args.remove(0);
+            FlycheckConfig::CustomCommand { command, args }
+        }
+        Some(_) | None => FlycheckConfig::CargoCommand {
+            command: data.checkOnSave_command.clone(),
+            target_triple: data.checkOnSave_target.clone().or_else(|| data.cargo_target.clone()),
+            all_targets: data.checkOnSave_allTargets,
+            no_default_features: data
+                .checkOnSave_noDefaultFeatures
+                .unwrap_or(data.cargo_noDefaultFeatures),
+            all_features: data.checkOnSave_allFeatures.unwrap_or(data.cargo_allFeatures),
+            features: data
+                .checkOnSave_features
+                .clone()
+                .unwrap_or_else(|| data.cargo_features.clone()),
+            extra_args: data.checkOnSave_extraArgs.clone(),
+            workspace: data.checkOnSave_workspace,
+        },
+    };
+    Some(flycheck_config)
+}
+
+/// Parses the `workspaceFolders` settings object, whose keys are either file URIs or absolute
+/// paths of workspace roots.
+fn root_overrides_from_json(json: serde_json::Value) -> FxHashMap<AbsPathBuf, serde_json::Value> {
+    let map = match json {
+        serde_json::Value::Object(it) => it,
+        _ => return FxHashMap::default(),
+    };
+    map.into_iter()
+        .filter_map(|(root, overrides)| {
+            let path = match lsp_types::Url::parse(&root) {
+                Ok(url) if url.scheme() == "file" => url.to_file_path().ok()?,
+                _ => PathBuf::from(root),
+            };
+            Some((AbsPathBuf::try_from(path).ok()?, overrides))
+        })
+        .collect()
+}
+
+/// Recursively merges `overrides` into `base`, with values from `overrides` winning.
+fn merge_json(base: &mut serde_json::Value, overrides: serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                merge_json(base.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
 fn get_field<T: DeserializeOwned>(
     json: &mut serde_json::Value,
     field: &'static str,
@@ -1073,6 +1272,10 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
         "FxHashMap<String, String>" => set! {
             "type": "object",
         },
+        "usize" => set! {
+            "type": "integer",
+            "minimum": 0,
+        },
         "Option<usize>" => set! {
             "type": ["null", "integer"],
             "minimum": 0,
@@ -1099,6 +1302,15 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 "Merge imports from the same module into a single `use` statement."
             ],
         },
+        "ClosureReturnTypeHintsDef" => set! {
+            "type": "string",
+            "enum": ["always", "with_block", "never"],
+            "enumDescriptions": [
+                "Always show type hints for return types of closures.",
+                "Only show type hints for return types of closures with a block body.",
+                "Never show type hints for return types of closures."
+            ],
+        },
         "ImportGranularityDef" => set! {
             "type": "string",
             "enum": ["preserve", "crate", "module", "item"],
@@ -1122,6 +1334,15 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 "Force import paths to be absolute by always starting them with `crate` or the extern crate name they come from."
             ],
         },
+        "LifetimeElisionHintsDef" => set! {
+            "type": "string",
+            "enum": ["always", "skip_trivial", "never"],
+            "enumDescriptions": [
+                "Always show inlay hints for elided lifetimes.",
+                "Only show inlay hints for elided lifetimes in signatures with more than one input lifetime.",
+                "Never show inlay hints for elided lifetimes."
+            ],
+        },
         "Vec<ManifestOrProjectJson>" => set! {
             "type": "array",
             "items": { "type": ["string", "object"] },
@@ -1172,6 +1393,22 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn negotiates_offset_encoding() {
+        let mut config =
+            Config::new(AbsPathBuf::assert(project_root()), ClientCapabilities::default());
+        assert_eq!(config.offset_encoding(), OffsetEncoding::Utf16);
+
+        config.caps.offset_encoding = Some(vec!["utf-8".to_string()]);
+        assert_eq!(config.offset_encoding(), OffsetEncoding::Utf8);
+
+        config.position_encodings = vec!["utf-32".to_string(), "utf-8".to_string()];
+        assert_eq!(config.offset_encoding(), OffsetEncoding::Utf32);
+
+        config.position_encodings = vec!["ucs-2".to_string(), "utf-16".to_string()];
+        assert_eq!(config.offset_encoding(), OffsetEncoding::Utf16);
+    }
+
     #[test]
     fn generate_package_json_config() {
         let s = Config::json_schema();
@@ -1210,6 +1447,73 @@ mod tests {
         ensure_file_contents(&docs_path, &expected);
     }
 
+    #[test]
+    fn root_overrides_take_precedence() {
+        let root = AbsPathBuf::assert(project_root());
+        let nested = root.join("crates/nested");
+        let mut config = Config::new(root.clone(), ClientCapabilities::default());
+        let root_overrides = vec![
+            (root.clone(), serde_json::json!({ "cargo": { "features": ["root"] } })),
+            (nested.clone(), serde_json::json!({ "checkOnSave": { "command": "clippy" } })),
+        ];
+        config.update(
+            serde_json::json!({
+                "cargo": { "features": ["base"] },
+                "checkOnSave": { "command": "check" },
+                "files": { "excludeDirs": ["target"] },
+            }),
+            Some(root_overrides.into_iter().collect()),
+        );
+
+        assert_eq!(config.cargo().features, vec!["base".to_string()]);
+        assert_eq!(config.cargo_for(&root.join("src")).features, vec!["root".to_string()]);
+        // The nested root only overrides the check command and inherits the base features.
+        let nested_file = nested.join("src/lib.rs");
+        assert_eq!(config.cargo_for(&nested_file).features, vec!["base".to_string()]);
+        match config.flycheck_for(&nested_file) {
+            Some(FlycheckConfig::CargoCommand { command, .. }) => assert_eq!(command, "clippy"),
+            it => panic!("unexpected flycheck config: {:?}", it),
+        }
+        match config.flycheck_for(&root.join("src/lib.rs")) {
+            Some(FlycheckConfig::CargoCommand { command, .. }) => assert_eq!(command, "check"),
+            it => panic!("unexpected flycheck config: {:?}", it),
+        }
+
+        let exclude = config.files().exclude;
+        assert!(exclude.contains(&root.join("target")));
+        assert!(exclude.contains(&nested.join("target")));
+    }
+
+    #[test]
+    fn root_overrides_from_initialization_options() {
+        let root = AbsPathBuf::assert(project_root());
+        let other = root.join("other");
+        let mut config = Config::new(root.clone(), ClientCapabilities::default());
+        config.update(
+            serde_json::json!({
+                "cargo": { "features": ["base"] },
+                "workspaceFolders": {
+                    lsp_types::Url::from_file_path(&other).unwrap().to_string(): {
+                        "cargo": { "features": ["other"] }
+                    },
+                },
+            }),
+            None,
+        );
+        assert_eq!(config.cargo_for(&other.join("src")).features, vec!["other".to_string()]);
+        assert_eq!(config.cargo_for(&root.join("src")).features, vec!["base".to_string()]);
+
+        // Updates without overrides keep the previous ones, re-applied to the new settings.
+        config.update(serde_json::json!({ "cargo": { "allFeatures": true } }), None);
+        let cargo = config.cargo_for(&other.join("src"));
+        assert_eq!(cargo.features, vec!["other".to_string()]);
+        assert!(cargo.all_features);
+
+        // Explicit overrides replace the previous ones.
+        config.update(serde_json::json!({}), Some(FxHashMap::default()));
+        assert!(config.cargo_for(&other.join("src")).features.is_empty());
+    }
+
     fn remove_ws(text: &str) -> String {
         text.replace(char::is_whitespace, "")
     }
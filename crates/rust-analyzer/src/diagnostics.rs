@@ -39,6 +39,18 @@ impl DiagnosticCollection {
         self.changes.extend(self.check.drain().map(|(key, _value)| key))
     }
 
+    /// Like [`DiagnosticCollection::clear_check`], but keeps the diagnostics of files for which
+    /// `clear` returns `false`, as those weren't checked again.
+    pub(crate) fn clear_check_if(&mut self, clear: impl Fn(FileId) -> bool) {
+        let cleared: Vec<FileId> = self.check.keys().copied().filter(|&it| clear(it)).collect();
+        let check_fixes = Arc::make_mut(&mut self.check_fixes);
+        for file_id in cleared {
+            self.check.remove(&file_id);
+            check_fixes.remove(&file_id);
+            self.changes.insert(file_id);
+        }
+    }
+
     pub(crate) fn add_check_diagnostic(
         &mut self,
         file_id: FileId,
@@ -104,3 +116,44 @@ fn are_diagnostics_equal(left: &lsp_types::Diagnostic, right: &lsp_types::Diagno
         && left.range == right.range
         && left.message == right.message
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(message: &str) -> lsp_types::Diagnostic {
+        lsp_types::Diagnostic {
+            message: message.to_string(),
+            source: Some("rustc".to_string()),
+            ..lsp_types::Diagnostic::default()
+        }
+    }
+
+    fn messages(diagnostics: &DiagnosticCollection, file_id: FileId) -> Vec<&str> {
+        diagnostics.diagnostics_for(file_id).map(|it| it.message.as_str()).collect()
+    }
+
+    #[test]
+    fn narrower_check_retains_other_diagnostics() {
+        let (checked, other) = (FileId(0), FileId(1));
+        let mut diagnostics = DiagnosticCollection::default();
+        diagnostics.add_check_diagnostic(checked, diagnostic("stale"), Vec::new());
+        diagnostics.add_check_diagnostic(other, diagnostic("other package"), Vec::new());
+        diagnostics.set_native_diagnostics(checked, vec![diagnostic("native")]);
+        diagnostics.take_changes();
+
+        diagnostics.clear_check_if(|file_id| file_id == checked);
+        assert_eq!(diagnostics.take_changes(), Some(vec![checked].into_iter().collect()));
+        assert_eq!(messages(&diagnostics, checked), vec!["native"]);
+        assert_eq!(messages(&diagnostics, other), vec!["other package"]);
+
+        diagnostics.add_check_diagnostic(checked, diagnostic("fresh"), Vec::new());
+        diagnostics.add_check_diagnostic(other, diagnostic("other package"), Vec::new());
+        assert_eq!(messages(&diagnostics, checked), vec!["native", "fresh"]);
+        assert_eq!(messages(&diagnostics, other), vec!["other package"]);
+
+        diagnostics.clear_check();
+        assert_eq!(messages(&diagnostics, checked), vec!["native"]);
+        assert!(messages(&diagnostics, other).is_empty());
+    }
+}
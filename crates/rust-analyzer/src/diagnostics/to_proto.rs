@@ -60,6 +60,16 @@ fn location(
     lsp_types::Location { uri, range }
 }
 
+/// Checks whether a span points into an actual file of the current workspace.
+fn is_workspace_local(
+    config: &DiagnosticsMapConfig,
+    workspace_root: &AbsPath,
+    span: &DiagnosticSpan,
+) -> bool {
+    !is_dummy_macro_file(&span.file_name)
+        && resolve_path(config, workspace_root, &span.file_name).starts_with(workspace_root)
+}
+
 /// Extracts a suitable "primary" location from a rustc diagnostic.
 ///
 /// This takes locations pointing into the standard library, or generally outside the current
@@ -70,15 +80,19 @@ fn primary_location(
     span: &DiagnosticSpan,
 ) -> lsp_types::Location {
     let span_stack = std::iter::successors(Some(span), |span| Some(&span.expansion.as_ref()?.span));
-    for span in span_stack.clone() {
-        let abs_path = resolve_path(config, workspace_root, &span.file_name);
-        if !is_dummy_macro_file(&span.file_name) && abs_path.starts_with(workspace_root) {
-            return location(config, workspace_root, span);
-        }
+    if let Some(span) =
+        span_stack.clone().find(|span| is_workspace_local(config, workspace_root, span))
+    {
+        return location(config, workspace_root, span);
     }
 
-    // Fall back to the outermost macro invocation if no suitable span comes up.
-    let last_span = span_stack.last().unwrap();
+    // Fall back to the outermost macro invocation if no suitable span comes up. Dummy macro
+    // files don't exist on disk, so a diagnostic reported there would never be shown.
+    let last_span = span_stack
+        .clone()
+        .filter(|span| !is_dummy_macro_file(&span.file_name))
+        .last()
+        .unwrap_or_else(|| span_stack.last().unwrap());
     location(config, workspace_root, last_span)
 }
 
@@ -327,6 +341,11 @@ pub(crate) fn map_rust_diagnostic_to_lsp(
                         "Actual error occurred here".to_string()
                     },
                 });
+                // Spans in dependencies or generated files are only kept as part of the
+                // backtrace, the user can't do anything about a diagnostic reported there.
+                if !is_workspace_local(config, workspace_root, span) {
+                    continue;
+                }
                 // For the additional in-macro diagnostic we add the inverse message pointing to the error location in code.
                 let information_for_additional_diagnostic =
                     vec![lsp_types::DiagnosticRelatedInformation {
@@ -1529,6 +1548,142 @@ mod tests {
         );
     }
 
+    #[test]
+    fn derive_macro_error() {
+        check(
+            r##"{
+    "message": "mismatched types",
+    "code": {
+        "code": "E0308",
+        "explanation": null
+    },
+    "level": "error",
+    "spans": [
+        {
+            "file_name": "<proc-macro source code>",
+            "byte_start": 0,
+            "byte_end": 12,
+            "line_start": 1,
+            "line_end": 1,
+            "column_start": 1,
+            "column_end": 13,
+            "is_primary": true,
+            "text": [],
+            "label": "expected `u32`, found `&str`",
+            "suggested_replacement": null,
+            "suggestion_applicability": null,
+            "expansion": {
+                "span": {
+                    "file_name": "src/config.rs",
+                    "byte_start": 24,
+                    "byte_end": 31,
+                    "line_start": 3,
+                    "line_end": 3,
+                    "column_start": 10,
+                    "column_end": 17,
+                    "is_primary": false,
+                    "text": [
+                        {
+                            "text": "#[derive(Builder)]",
+                            "highlight_start": 10,
+                            "highlight_end": 17
+                        }
+                    ],
+                    "label": null,
+                    "suggested_replacement": null,
+                    "suggestion_applicability": null,
+                    "expansion": null
+                },
+                "macro_decl_name": "#[derive(Builder)]",
+                "def_site_span": {
+                    "file_name": "/home/user/.cargo/registry/src/github.com-1ecc6299db9ec823/builder-0.1.0/src/lib.rs",
+                    "byte_start": 102,
+                    "byte_end": 170,
+                    "line_start": 8,
+                    "line_end": 8,
+                    "column_start": 1,
+                    "column_end": 69,
+                    "is_primary": false,
+                    "text": [],
+                    "label": null,
+                    "suggested_replacement": null,
+                    "suggestion_applicability": null,
+                    "expansion": null
+                }
+            }
+        }
+    ],
+    "children": [],
+    "rendered": "error[E0308]: mismatched types\n --> src/config.rs:3:10\n  |\n3 | #[derive(Builder)]\n  |          ^^^^^^^ expected `u32`, found `&str`\n  |\n  = note: this error originates in the derive macro `Builder` (in Nightly builds, run with -Z macro-backtrace for more info)\n\n"
+}"##,
+            expect_file!["./test_data/derive_macro_error.txt"],
+        );
+    }
+
+    #[test]
+    fn include_macro_error() {
+        check(
+            r##"{
+    "message": "cannot find type `c_uint` in this scope",
+    "code": {
+        "code": "E0412",
+        "explanation": null
+    },
+    "level": "error",
+    "spans": [
+        {
+            "file_name": "/tmp/target/debug/build/sys-5a3b2c1d/out/bindings.rs",
+            "byte_start": 87,
+            "byte_end": 93,
+            "line_start": 4,
+            "line_end": 4,
+            "column_start": 16,
+            "column_end": 22,
+            "is_primary": true,
+            "text": [
+                {
+                    "text": "    pub flags: c_uint,",
+                    "highlight_start": 16,
+                    "highlight_end": 22
+                }
+            ],
+            "label": "not found in this scope",
+            "suggested_replacement": null,
+            "suggestion_applicability": null,
+            "expansion": {
+                "span": {
+                    "file_name": "src/lib.rs",
+                    "byte_start": 40,
+                    "byte_end": 92,
+                    "line_start": 2,
+                    "line_end": 2,
+                    "column_start": 1,
+                    "column_end": 53,
+                    "is_primary": false,
+                    "text": [
+                        {
+                            "text": "include!(concat!(env!(\"OUT_DIR\"), \"/bindings.rs\"));",
+                            "highlight_start": 1,
+                            "highlight_end": 53
+                        }
+                    ],
+                    "label": null,
+                    "suggested_replacement": null,
+                    "suggestion_applicability": null,
+                    "expansion": null
+                },
+                "macro_decl_name": "include!",
+                "def_site_span": null
+            }
+        }
+    ],
+    "children": [],
+    "rendered": "error[E0412]: cannot find type `c_uint` in this scope\n --> /tmp/target/debug/build/sys-5a3b2c1d/out/bindings.rs:4:16\n  |\n4 |     pub flags: c_uint,\n  |                ^^^^^^ not found in this scope\n\n"
+}"##,
+            expect_file!["./test_data/include_macro_error.txt"],
+        );
+    }
+
     #[test]
     fn snap_multi_line_fix() {
         check(
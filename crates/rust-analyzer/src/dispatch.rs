@@ -1,6 +1,7 @@
 //! A visitor for downcasting arbitrary request (JSON) into a specific type.
 use std::{fmt, panic};
 
+use lsp_types::Url;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
@@ -57,13 +58,20 @@ impl<'a> RequestDispatcher<'a> {
         R::Params: DeserializeOwned + Send + fmt::Debug + 'static,
         R::Result: Serialize + 'static,
     {
+        let document = self
+            .req
+            .as_ref()
+            .filter(|req| req.method == R::METHOD)
+            .and_then(|req| req.params.pointer("/textDocument/uri")?.as_str())
+            .and_then(|uri| Url::parse(uri).ok());
         let (id, params) = match self.parse::<R>() {
             Some(it) => it,
             None => return self,
         };
 
-        self.global_state.task_pool.handle.spawn({
+        let task = {
             let world = self.global_state.snapshot();
+            let id = id.clone();
 
             move || {
                 let _pctx = stdx::panic_context::enter(format!(
@@ -75,7 +83,8 @@ impl<'a> RequestDispatcher<'a> {
                 let result = f(world, params);
                 Task::Response(result_to_response::<R>(id, result))
             }
-        });
+        };
+        self.global_state.schedule_request(id, R::METHOD, document, Box::new(task));
 
         self
     }
@@ -1,7 +1,7 @@
 //! Conversion lsp_types types to rust-analyzer specific ones.
 use std::convert::TryFrom;
 
-use ide::{Annotation, AnnotationKind, AssistKind, LineCol, LineColUtf16};
+use ide::{Annotation, AnnotationKind, AssistKind, LineCol, LineColUtf16, LineColUtf32};
 use ide_db::base_db::{FileId, FilePosition, FileRange};
 use syntax::{TextRange, TextSize};
 use vfs::AbsPathBuf;
@@ -32,6 +32,11 @@ pub(crate) fn offset(line_index: &LineIndex, position: lsp_types::Position) -> T
                 LineColUtf16 { line: position.line as u32, col: position.character as u32 };
             line_index.index.to_utf8(line_col)
         }
+        OffsetEncoding::Utf32 => {
+            let line_col =
+                LineColUtf32 { line: position.line as u32, col: position.character as u32 };
+            line_index.index.from_utf32(line_col)
+        }
     };
     line_index.index.offset(line_col)
 }
@@ -27,6 +27,7 @@ use crate::{
     mem_docs::MemDocs,
     op_queue::OpQueue,
     reload::SourceRootConfig,
+    request_queue::{self, RequestQueue},
     thread_pool::TaskPool,
     to_proto::url_from_abs_path,
     Result,
@@ -40,6 +41,7 @@ pub(crate) struct Handle<H, C> {
 
 pub(crate) type ReqHandler = fn(&mut GlobalState, lsp_server::Response);
 pub(crate) type ReqQueue = lsp_server::ReqQueue<(String, Instant), ReqHandler>;
+pub(crate) type PendingTask = Box<dyn FnOnce() -> Task + Send>;
 
 /// `GlobalState` is the primary mutable state of the language server
 ///
@@ -51,6 +53,11 @@ pub(crate) type ReqQueue = lsp_server::ReqQueue<(String, Instant), ReqHandler>;
 pub(crate) struct GlobalState {
     sender: Sender<lsp_server::Message>,
     req_queue: ReqQueue,
+    /// Requests waiting for a thread pool job to pick them up.
+    request_queue: Arc<Mutex<RequestQueue<PendingTask>>>,
+    /// The document which was changed most recently. Latency-sensitive requests
+    /// for it are prioritized.
+    pub(crate) active_document: Option<Url>,
     pub(crate) task_pool: Handle<TaskPool<Task>, Receiver<Task>>,
     pub(crate) loader: Handle<Box<dyn vfs::loader::Handle>, Receiver<vfs::loader::Message>>,
     pub(crate) config: Arc<Config>,
@@ -138,6 +145,8 @@ impl GlobalState {
         let mut this = GlobalState {
             sender,
             req_queue: ReqQueue::default(),
+            request_queue: Arc::new(Mutex::new(RequestQueue::default())),
+            active_document: None,
             task_pool,
             loader,
             config: Arc::new(config.clone()),
@@ -268,7 +277,42 @@ impl GlobalState {
             self.send(response.into());
         }
     }
+    /// Queues a request to be handled on the thread pool.
+    pub(crate) fn schedule_request(
+        &mut self,
+        id: lsp_server::RequestId,
+        method: &'static str,
+        document: Option<Url>,
+        task: PendingTask,
+    ) {
+        let high_priority = request_queue::is_latency_sensitive(method)
+            && document.is_some()
+            && document == self.active_document;
+        let superseded = self.request_queue.lock().push(id, method, document, high_priority, task);
+        for id in superseded {
+            self.respond(lsp_server::Response::new_err(
+                id,
+                lsp_server::ErrorCode::ContentModified as i32,
+                "content modified".to_string(),
+            ));
+        }
+
+        // The job doesn't necessarily run the request queued above: it picks
+        // whatever request is the most important once it gets to run.
+        let request_queue = Arc::clone(&self.request_queue);
+        self.task_pool.handle.spawn_with_sender(move |sender| {
+            let task = request_queue.lock().pop();
+            if let Some((_id, task)) = task {
+                sender.send(task()).unwrap()
+            }
+        });
+    }
+    pub(crate) fn pending_requests(&self) -> usize {
+        self.request_queue.lock().len()
+    }
     pub(crate) fn cancel(&mut self, request_id: lsp_server::RequestId) {
+        // Drop the request early if it didn't start yet, releasing its snapshot.
+        drop(self.request_queue.lock().remove(&request_id));
         if let Some(response) = self.req_queue.incoming.cancel(request_id) {
             self.send(response.into());
         }
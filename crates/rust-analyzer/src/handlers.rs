@@ -4,6 +4,7 @@
 
 use std::{
     io::Write as _,
+    iter, mem,
     process::{self, Stdio},
 };
 
@@ -82,16 +83,46 @@ pub(crate) fn handle_analyzer_status(
     Ok(buf)
 }
 
-pub(crate) fn handle_memory_usage(state: &mut GlobalState, _: ()) -> Result<String> {
+pub(crate) fn handle_memory_usage(
+    state: &mut GlobalState,
+    _: (),
+) -> Result<lsp_ext::MemoryUsageResult> {
     let _p = profile::span("handle_memory_usage");
-    let mut mem = state.analysis_host.per_query_memory_usage();
-    mem.push(("Remaining".into(), profile::memory_usage().allocated));
+    let vfs = {
+        let vfs = &state.vfs.read().0;
+        let mut size = profile::Bytes::default();
+        vfs.iter().for_each(|(file_id, _)| size += vfs.file_contents(file_id).len());
+        size
+    };
+    let proc_macro_server =
+        state.proc_macro_client.as_ref().and_then(|it| profile::process_rss(it.process_id()));
+    let usage = state.analysis_host.memory_usage();
 
-    let mut out = String::new();
-    for (name, bytes) in mem {
-        format_to!(out, "{:>8} {}\n", bytes, name);
+    let mut text = format!("{}\n{:>8} VFS\n", usage, vfs);
+    if let Some(bytes) = proc_macro_server {
+        format_to!(text, "{:>8} Proc-macro server (resident)\n", bytes);
     }
-    Ok(out)
+    Ok(lsp_ext::MemoryUsageResult {
+        text,
+        query_groups: usage
+            .query_groups
+            .iter()
+            .map(|&(name, bytes)| lsp_ext::MemoryUsageEntry {
+                name: name.to_string(),
+                bytes: bytes.into(),
+            })
+            .collect(),
+        vfs: vfs.into(),
+        symbol_index: usage.symbol_index.into(),
+        proc_macro_server: proc_macro_server.map(Into::into),
+        remaining: usage.remaining.into(),
+    })
+}
+
+pub(crate) fn handle_clear_memory(state: &mut GlobalState, _: ()) -> Result<()> {
+    let _p = profile::span("handle_clear_memory");
+    state.analysis_host.clear_memory();
+    Ok(())
 }
 
 pub(crate) fn handle_syntax_tree(
@@ -116,6 +147,16 @@ pub(crate) fn handle_view_hir(
     Ok(res)
 }
 
+pub(crate) fn handle_eval_function(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<String>> {
+    let _p = profile::span("handle_eval_function");
+    let position = from_proto::file_position(&snap, params)?;
+    let res = snap.analysis.eval_function(position)?;
+    Ok(res)
+}
+
 pub(crate) fn handle_view_item_tree(
     snap: GlobalStateSnapshot,
     params: lsp_ext::ViewItemTreeParams,
@@ -145,7 +186,11 @@ pub(crate) fn handle_expand_macro(
     let offset = from_proto::offset(&line_index, params.position);
 
     let res = snap.analysis.expand_macro(FilePosition { file_id, offset })?;
-    Ok(res.map(|it| lsp_ext::ExpandedMacro { name: it.name, expansion: it.expansion }))
+    Ok(res.map(|it| lsp_ext::ExpandedMacro {
+        name: it.name,
+        expansion: it.expansion,
+        errors: it.errors,
+    }))
 }
 
 pub(crate) fn handle_selection_range(
@@ -244,7 +289,7 @@ pub(crate) fn handle_on_enter(
 ) -> Result<Option<Vec<lsp_ext::SnippetTextEdit>>> {
     let _p = profile::span("handle_on_enter");
     let position = from_proto::file_position(&snap, params)?;
-    let edit = match snap.analysis.on_enter(position)? {
+    let edit = match snap.analysis.on_enter(position, snap.config.snippet_cap())? {
         None => return Ok(None),
         Some(it) => it,
     };
@@ -922,26 +967,28 @@ pub(crate) fn handle_references(
     let _p = profile::span("handle_references");
     let position = from_proto::file_position(&snap, params.text_document_position)?;
 
-    let refs = match snap.analysis.find_all_refs(position, None)? {
+    let mut refs = match snap.analysis.find_all_refs(position, None, &snap.config.references())? {
         None => return Ok(None),
         Some(refs) => refs,
     };
 
-    let decl = if params.context.include_declaration {
-        refs.declaration.map(|decl| FileRange {
-            file_id: decl.nav.file_id,
-            range: decl.nav.focus_or_full_range(),
-        })
-    } else {
-        None
-    };
-    let locations = refs
-        .references
-        .into_iter()
-        .flat_map(|(file_id, refs)| {
-            refs.into_iter().map(move |(range, _)| FileRange { file_id, range })
+    let include_declaration = params.context.include_declaration;
+    let related = mem::take(&mut refs.related);
+    let locations = iter::once(refs)
+        .chain(related)
+        .flat_map(|refs| {
+            let decl = refs.declaration.filter(|_| include_declaration).map(|decl| FileRange {
+                file_id: decl.nav.file_id,
+                range: decl.nav.focus_or_full_range(),
+            });
+            refs.references
+                .into_iter()
+                .flat_map(|(file_id, refs)| {
+                    refs.into_iter().map(move |(range, _)| FileRange { file_id, range })
+                })
+                .chain(decl)
         })
-        .chain(decl)
+        .unique()
         .filter_map(|frange| to_proto::location(&snap, frange).ok())
         .collect();
 
@@ -1502,12 +1549,32 @@ fn show_impl_command_link(
     None
 }
 
+fn eval_function_command_link(
+    snap: &GlobalStateSnapshot,
+    position: &FilePosition,
+) -> Option<lsp_ext::CommandLinkGroup> {
+    if snap.config.hover_actions().eval_function && snap.config.client_commands().eval_function {
+        let uri = to_proto::url(snap, position.file_id);
+        let line_index = snap.file_line_index(position.file_id).ok()?;
+        let position = to_proto::position(&line_index, position.offset);
+        let command = to_proto::command::eval_function(&uri, position);
+
+        return Some(lsp_ext::CommandLinkGroup {
+            commands: vec![to_command_link(command, "Evaluate this call".into())],
+            ..Default::default()
+        });
+    }
+    None
+}
+
 fn show_ref_command_link(
     snap: &GlobalStateSnapshot,
     position: &FilePosition,
 ) -> Option<lsp_ext::CommandLinkGroup> {
     if snap.config.hover_actions().references && snap.config.client_commands().show_reference {
-        if let Some(ref_search_res) = snap.analysis.find_all_refs(*position, None).unwrap_or(None) {
+        if let Some(ref_search_res) =
+            snap.analysis.find_all_refs(*position, None, &Default::default()).unwrap_or(None)
+        {
             let uri = to_proto::url(snap, position.file_id);
             let line_index = snap.file_line_index(position.file_id).ok()?;
             let position = to_proto::position(&line_index, position.offset);
@@ -1603,6 +1670,7 @@ fn prepare_hover_actions(
             HoverAction::Reference(position) => show_ref_command_link(snap, position),
             HoverAction::Runnable(r) => runnable_action_links(snap, r.clone()),
             HoverAction::GoToType(targets) => goto_type_action_links(snap, targets),
+            HoverAction::EvalFunction(position) => eval_function_command_link(snap, position),
         })
         .collect()
 }
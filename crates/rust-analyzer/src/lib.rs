@@ -35,6 +35,7 @@ mod thread_pool;
 mod mem_docs;
 mod diff;
 mod op_queue;
+mod request_queue;
 pub mod lsp_ext;
 pub mod config;
 
@@ -45,7 +46,7 @@ use std::fmt;
 
 use serde::de::DeserializeOwned;
 
-pub use crate::{caps::server_capabilities, main_loop::main_loop};
+pub use crate::{caps::server_capabilities, line_index::OffsetEncoding, main_loop::main_loop};
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -7,9 +7,32 @@
 
 use std::sync::Arc;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OffsetEncoding {
     Utf8,
     Utf16,
+    Utf32,
+}
+
+impl OffsetEncoding {
+    /// The name of the encoding in the `positionEncoding` capability.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OffsetEncoding::Utf8 => "utf-8",
+            OffsetEncoding::Utf16 => "utf-16",
+            OffsetEncoding::Utf32 => "utf-32",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<OffsetEncoding> {
+        let res = match name {
+            "utf-8" => OffsetEncoding::Utf8,
+            "utf-16" => OffsetEncoding::Utf16,
+            "utf-32" => OffsetEncoding::Utf32,
+            _ => return None,
+        };
+        Some(res)
+    }
 }
 
 pub(crate) struct LineIndex {
@@ -9,6 +9,8 @@ use lsp_types::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::line_index::OffsetEncoding;
+
 pub enum AnalyzerStatus {}
 
 impl Request for AnalyzerStatus {
@@ -27,10 +29,37 @@ pub enum MemoryUsage {}
 
 impl Request for MemoryUsage {
     type Params = ();
-    type Result = String;
+    type Result = MemoryUsageResult;
     const METHOD: &'static str = "rust-analyzer/memoryUsage";
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryUsageResult {
+    /// Human-readable version of the report.
+    pub text: String,
+    pub query_groups: Vec<MemoryUsageEntry>,
+    pub vfs: isize,
+    pub symbol_index: isize,
+    pub proc_macro_server: Option<isize>,
+    pub remaining: isize,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryUsageEntry {
+    pub name: String,
+    pub bytes: isize,
+}
+
+pub enum ClearMemory {}
+
+impl Request for ClearMemory {
+    type Params = ();
+    type Result = ();
+    const METHOD: &'static str = "rust-analyzer/clearMemory";
+}
+
 pub enum ReloadWorkspace {}
 
 impl Request for ReloadWorkspace {
@@ -62,6 +91,14 @@ impl Request for ViewHir {
     const METHOD: &'static str = "rust-analyzer/viewHir";
 }
 
+pub enum EvalFunction {}
+
+impl Request for EvalFunction {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<String>;
+    const METHOD: &'static str = "rust-analyzer/evalFunction";
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ViewCrateGraphParams {
@@ -111,6 +148,7 @@ pub struct ExpandMacroParams {
 pub struct ExpandedMacro {
     pub name: String,
     pub expansion: String,
+    pub errors: Vec<String>,
 }
 
 pub enum MatchingBrace {}
@@ -239,6 +277,9 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    BindingModeHint,
+    ClosureReturnTypeHint,
+    LifetimeHint,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -452,8 +493,22 @@ pub(crate) enum CodeLensResolveData {
     References(lsp_types::TextDocumentPositionParams),
 }
 
-pub fn supports_utf8(caps: &lsp_types::ClientCapabilities) -> bool {
-    caps.offset_encoding.as_deref().unwrap_or_default().iter().any(|it| it == "utf-8")
+/// Picks the encoding of positions, honoring the client's order of preference.
+///
+/// `position_encodings` is the standard `general.positionEncodings` capability. If the client
+/// doesn't send it, we fall back to clangd's `offsetEncoding` extension, which is only consulted
+/// for utf-8. Without either, positions are in utf-16, as mandated by the spec.
+pub fn negotiated_encoding(
+    caps: &lsp_types::ClientCapabilities,
+    position_encodings: &[String],
+) -> OffsetEncoding {
+    if let Some(encoding) = position_encodings.iter().find_map(|it| OffsetEncoding::from_name(it)) {
+        return encoding;
+    }
+    if caps.offset_encoding.as_deref().unwrap_or_default().iter().any(|it| it == "utf-8") {
+        return OffsetEncoding::Utf8;
+    }
+    OffsetEncoding::Utf16
 }
 
 pub enum MoveItem {}
@@ -91,13 +91,14 @@ impl GlobalState {
 
 pub(crate) fn apply_document_changes(
     old_text: &mut String,
+    encoding: OffsetEncoding,
     content_changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
 ) {
     let mut line_index = LineIndex {
         index: Arc::new(ide::LineIndex::new(old_text)),
-        // We don't care about line endings or offset encoding here.
+        // We don't care about line endings here.
         endings: LineEndings::Unix,
-        encoding: OffsetEncoding::Utf16,
+        encoding,
     };
 
     // The changes we got must be applied sequentially, but can cross lines so we
@@ -199,10 +200,11 @@ mod tests {
         }
 
         let mut text = String::new();
-        apply_document_changes(&mut text, vec![]);
+        apply_document_changes(&mut text, OffsetEncoding::Utf16, vec![]);
         assert_eq!(text, "");
         apply_document_changes(
             &mut text,
+            OffsetEncoding::Utf16,
             vec![TextDocumentContentChangeEvent {
                 range: None,
                 range_length: None,
@@ -210,40 +212,70 @@ mod tests {
             }],
         );
         assert_eq!(text, "the");
-        apply_document_changes(&mut text, c![0, 3; 0, 3 => " quick"]);
+        apply_document_changes(&mut text, OffsetEncoding::Utf16, c![0, 3; 0, 3 => " quick"]);
         assert_eq!(text, "the quick");
-        apply_document_changes(&mut text, c![0, 0; 0, 4 => "", 0, 5; 0, 5 => " foxes"]);
+        apply_document_changes(
+            &mut text,
+            OffsetEncoding::Utf16,
+            c![0, 0; 0, 4 => "", 0, 5; 0, 5 => " foxes"],
+        );
         assert_eq!(text, "quick foxes");
-        apply_document_changes(&mut text, c![0, 11; 0, 11 => "\ndream"]);
+        apply_document_changes(&mut text, OffsetEncoding::Utf16, c![0, 11; 0, 11 => "\ndream"]);
         assert_eq!(text, "quick foxes\ndream");
-        apply_document_changes(&mut text, c![1, 0; 1, 0 => "have "]);
+        apply_document_changes(&mut text, OffsetEncoding::Utf16, c![1, 0; 1, 0 => "have "]);
         assert_eq!(text, "quick foxes\nhave dream");
         apply_document_changes(
             &mut text,
+            OffsetEncoding::Utf16,
             c![0, 0; 0, 0 => "the ", 1, 4; 1, 4 => " quiet", 1, 16; 1, 16 => "s\n"],
         );
         assert_eq!(text, "the quick foxes\nhave quiet dreams\n");
-        apply_document_changes(&mut text, c![0, 15; 0, 15 => "\n", 2, 17; 2, 17 => "\n"]);
+        apply_document_changes(
+            &mut text,
+            OffsetEncoding::Utf16,
+            c![0, 15; 0, 15 => "\n", 2, 17; 2, 17 => "\n"],
+        );
         assert_eq!(text, "the quick foxes\n\nhave quiet dreams\n\n");
         apply_document_changes(
             &mut text,
+            OffsetEncoding::Utf16,
             c![1, 0; 1, 0 => "DREAM", 2, 0; 2, 0 => "they ", 3, 0; 3, 0 => "DON'T THEY?"],
         );
         assert_eq!(text, "the quick foxes\nDREAM\nthey have quiet dreams\nDON'T THEY?\n");
-        apply_document_changes(&mut text, c![0, 10; 1, 5 => "", 2, 0; 2, 12 => ""]);
+        apply_document_changes(
+            &mut text,
+            OffsetEncoding::Utf16,
+            c![0, 10; 1, 5 => "", 2, 0; 2, 12 => ""],
+        );
         assert_eq!(text, "the quick \nthey have quiet dreams\n");
 
         text = String::from("❤️");
-        apply_document_changes(&mut text, c![0, 0; 0, 0 => "a"]);
+        apply_document_changes(&mut text, OffsetEncoding::Utf16, c![0, 0; 0, 0 => "a"]);
         assert_eq!(text, "a❤️");
 
         text = String::from("a\nb");
-        apply_document_changes(&mut text, c![0, 1; 1, 0 => "\nțc", 0, 1; 1, 1 => "d"]);
+        apply_document_changes(
+            &mut text,
+            OffsetEncoding::Utf16,
+            c![0, 1; 1, 0 => "\nțc", 0, 1; 1, 1 => "d"],
+        );
         assert_eq!(text, "adcb");
 
         text = String::from("a\nb");
-        apply_document_changes(&mut text, c![0, 1; 1, 0 => "ț\nc", 0, 2; 0, 2 => "c"]);
+        apply_document_changes(
+            &mut text,
+            OffsetEncoding::Utf16,
+            c![0, 1; 1, 0 => "ț\nc", 0, 2; 0, 2 => "c"],
+        );
         assert_eq!(text, "ațc\ncb");
+
+        text = String::from("𝕏𝕏");
+        apply_document_changes(&mut text, OffsetEncoding::Utf16, c![0, 2; 0, 2 => "a"]);
+        assert_eq!(text, "𝕏a𝕏");
+        apply_document_changes(&mut text, OffsetEncoding::Utf32, c![0, 2; 0, 2 => "b"]);
+        assert_eq!(text, "𝕏ab𝕏");
+        apply_document_changes(&mut text, OffsetEncoding::Utf8, c![0, 6; 0, 6 => "c"]);
+        assert_eq!(text, "𝕏abc𝕏");
     }
 
     #[test]
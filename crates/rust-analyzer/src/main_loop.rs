@@ -1,7 +1,7 @@
 //! The main loop of `rust-analyzer` responsible for dispatching LSP
 //! requests/replies and notifications back to the client.
 use std::{
-    env, fmt,
+    env, fmt, iter,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -22,8 +22,8 @@ use crate::{
     handlers, lsp_ext,
     lsp_utils::{apply_document_changes, is_cancelled, notification_is, Progress},
     mem_docs::DocumentData,
-    reload::{BuildDataProgress, ProjectWorkspaceProgress},
-    Result,
+    reload::{check_scope, BuildDataProgress, ProjectWorkspaceProgress},
+    to_proto, Result,
 };
 
 pub fn main_loop(config: Config, connection: Connection) -> Result<()> {
@@ -187,6 +187,10 @@ impl GlobalState {
         if task_queue_len > 0 {
             log::info!("task queue len: {}", task_queue_len);
         }
+        let pending_requests = self.pending_requests();
+        if pending_requests > 0 {
+            log::info!("pending requests: {}", pending_requests);
+        }
 
         let was_quiescent = self.is_quiescent();
         match event {
@@ -370,8 +374,20 @@ impl GlobalState {
 
                         flycheck::Message::Progress { id, progress } => {
                             let (state, message) = match progress {
-                                flycheck::Progress::DidStart => {
-                                    self.diagnostics.clear_check();
+                                flycheck::Progress::DidStart(scope) => {
+                                    match (&scope, self.workspaces.get(id)) {
+                                        (flycheck::CheckScope::Package { .. }, Some(ws)) => {
+                                            let vfs = &self.vfs.read().0;
+                                            self.diagnostics.clear_check_if(|file_id| {
+                                                vfs.file_path(file_id)
+                                                    .as_path()
+                                                    .map_or(false, |path| {
+                                                        scope.contains(&check_scope(ws, path))
+                                                    })
+                                            });
+                                        }
+                                        _ => self.diagnostics.clear_check(),
+                                    }
                                     (Progress::Begin, None)
                                 }
                                 flycheck::Progress::DidCheckCrate(target) => {
@@ -540,9 +556,11 @@ impl GlobalState {
                 handlers::handle_matching_brace(s.snapshot(), p)
             })?
             .on_sync::<lsp_ext::MemoryUsage>(|s, p| handlers::handle_memory_usage(s, p))?
+            .on_sync::<lsp_ext::ClearMemory>(|s, p| handlers::handle_clear_memory(s, p))?
             .on::<lsp_ext::AnalyzerStatus>(handlers::handle_analyzer_status)
             .on::<lsp_ext::SyntaxTree>(handlers::handle_syntax_tree)
             .on::<lsp_ext::ViewHir>(handlers::handle_view_hir)
+            .on::<lsp_ext::EvalFunction>(handlers::handle_eval_function)
             .on::<lsp_ext::ViewCrateGraph>(handlers::handle_view_crate_graph)
             .on::<lsp_ext::ViewItemTree>(handlers::handle_view_item_tree)
             .on::<lsp_ext::ExpandMacro>(handlers::handle_expand_macro)
@@ -629,6 +647,7 @@ impl GlobalState {
                 Ok(())
             })?
             .on::<lsp_types::notification::DidChangeTextDocument>(|this, params| {
+                this.active_document = Some(params.text_document.uri.clone());
                 if let Ok(path) = from_proto::vfs_path(&params.text_document.uri) {
                     match this.mem_docs.get_mut(&path) {
                         Some(doc) => {
@@ -645,7 +664,11 @@ impl GlobalState {
                     let vfs = &mut this.vfs.write().0;
                     let file_id = vfs.file_id(&path).unwrap();
                     let mut text = String::from_utf8(vfs.file_contents(file_id).to_vec()).unwrap();
-                    apply_document_changes(&mut text, params.content_changes);
+                    apply_document_changes(
+                        &mut text,
+                        this.config.offset_encoding(),
+                        params.content_changes,
+                    );
 
                     vfs.set_file_contents(path, Some(text.into_bytes()));
                 }
@@ -666,10 +689,15 @@ impl GlobalState {
                 Ok(())
             })?
             .on::<lsp_types::notification::DidSaveTextDocument>(|this, params| {
+                let abs_path = from_proto::abs_path(&params.text_document.uri).ok();
                 for flycheck in &this.flycheck {
-                    flycheck.update();
+                    let scope = match (&abs_path, this.workspaces.get(flycheck.id())) {
+                        (Some(path), Some(ws)) => check_scope(ws, path),
+                        _ => flycheck::CheckScope::Workspace,
+                    };
+                    flycheck.update_scope(scope);
                 }
-                if let Ok(abs_path) = from_proto::abs_path(&params.text_document.uri) {
+                if let Some(abs_path) = abs_path {
                     this.maybe_refresh(&[(abs_path, ChangeKind::Modify)]);
                 }
                 Ok(())
@@ -679,10 +707,20 @@ impl GlobalState {
                 // this notification's parameters should be ignored and the actual config queried separately.
                 this.send_request::<lsp_types::request::WorkspaceConfiguration>(
                     lsp_types::ConfigurationParams {
-                        items: vec![lsp_types::ConfigurationItem {
-                            scope_uri: None,
-                            section: Some("rust-analyzer".to_string()),
-                        }],
+                        // The first item is the global configuration, the rest are the scoped
+                        // configurations of each workspace folder, in order.
+                        items: iter::once(None)
+                            .chain(
+                                this.config
+                                    .workspace_roots
+                                    .iter()
+                                    .map(|root| Some(to_proto::url_from_abs_path(root))),
+                            )
+                            .map(|scope_uri| lsp_types::ConfigurationItem {
+                                scope_uri,
+                                section: Some("rust-analyzer".to_string()),
+                            })
+                            .collect(),
                     },
                     |this, resp| {
                         log::debug!("config update response: '{:?}", resp);
@@ -693,11 +731,23 @@ impl GlobalState {
                                 log::error!("failed to fetch the server settings: {:?}", err)
                             }
                             (None, Some(mut configs)) => {
-                                if let Some(json) = configs.get_mut(0) {
+                                if let Some(configs) =
+                                    configs.as_array_mut().filter(|it| !it.is_empty())
+                                {
                                     // Note that json can be null according to the spec if the client can't
                                     // provide a configuration. This is handled in Config::update below.
+                                    let json = configs.remove(0);
+                                    let root_overrides = (!configs.is_empty()).then(|| {
+                                        this.config
+                                            .workspace_roots
+                                            .iter()
+                                            .cloned()
+                                            .zip(configs.drain(..))
+                                            .filter(|(_, json)| !json.is_null())
+                                            .collect()
+                                    });
                                     let mut config = Config::clone(&*this.config);
-                                    config.update(json.take());
+                                    config.update(json, root_overrides);
                                     this.update_configuration(config);
                                 }
                             }
@@ -1,11 +1,13 @@
 //! Project loading & configuration updates
 use std::{mem, sync::Arc};
 
-use flycheck::{FlycheckConfig, FlycheckHandle};
+use flycheck::{CheckScope, FlycheckConfig, FlycheckHandle, PackageTarget};
 use hir::db::DefDatabase;
 use ide::Change;
 use ide_db::base_db::{CrateGraph, SourceRoot, VfsPath};
-use project_model::{ProcMacroClient, ProjectWorkspace, WorkspaceBuildScripts};
+use project_model::{
+    ProcMacroClient, ProjectManifest, ProjectWorkspace, TargetKind, WorkspaceBuildScripts,
+};
 use vfs::{file_set::FileSetConfig, AbsPath, AbsPathBuf, ChangeKind};
 
 use crate::{
@@ -45,7 +47,7 @@ impl GlobalState {
         }
         if self.config.linked_projects() != old_config.linked_projects() {
             self.fetch_workspaces_request()
-        } else if self.config.flycheck() != old_config.flycheck() {
+        } else if self.flycheck_configs(&self.config) != self.flycheck_configs(&old_config) {
             self.reload_flycheck();
         }
 
@@ -153,9 +155,22 @@ impl GlobalState {
         log::info!("will fetch workspaces");
 
         self.task_pool.handle.spawn_with_sender({
-            let linked_projects = self.config.linked_projects();
+            let linked_projects = self
+                .config
+                .linked_projects()
+                .into_iter()
+                .map(|project| {
+                    let cargo_config = match &project {
+                        LinkedProject::ProjectManifest(
+                            ProjectManifest::CargoToml(manifest)
+                            | ProjectManifest::ProjectJson(manifest),
+                        ) => self.config.cargo_for(manifest.parent()),
+                        LinkedProject::InlineJsonProject(_) => self.config.cargo(),
+                    };
+                    (project, cargo_config)
+                })
+                .collect::<Vec<_>>();
             let detached_files = self.config.detached_files().to_vec();
-            let cargo_config = self.config.cargo();
 
             move |sender| {
                 let progress = {
@@ -171,11 +186,11 @@ impl GlobalState {
 
                 let mut workspaces = linked_projects
                     .iter()
-                    .map(|project| match project {
+                    .map(|(project, cargo_config)| match project {
                         LinkedProject::ProjectManifest(manifest) => {
                             project_model::ProjectWorkspace::load(
                                 manifest.clone(),
-                                &cargo_config,
+                                cargo_config,
                                 &progress,
                             )
                         }
@@ -216,7 +231,13 @@ impl GlobalState {
         }
 
         let workspaces = Arc::clone(&self.workspaces);
-        let config = self.config.cargo();
+        let configs = workspaces
+            .iter()
+            .map(|ws| match workspace_root(ws) {
+                Some(root) => self.config.cargo_for(root),
+                None => self.config.cargo(),
+            })
+            .collect::<Vec<_>>();
         self.task_pool.handle.spawn_with_sender(move |sender| {
             sender.send(Task::FetchBuildData(BuildDataProgress::Begin)).unwrap();
 
@@ -227,8 +248,8 @@ impl GlobalState {
                 }
             };
             let mut res = Vec::new();
-            for ws in workspaces.iter() {
-                res.push(ws.run_build_scripts(&config, &progress));
+            for (ws, config) in workspaces.iter().zip(&configs) {
+                res.push(ws.run_build_scripts(config, &progress));
                 let ws = match ws {
                     ProjectWorkspace::Cargo { cargo, .. } => cargo,
                     ProjectWorkspace::DetachedFiles { .. } | ProjectWorkspace::Json { .. } => {
@@ -236,7 +257,7 @@ impl GlobalState {
                         continue;
                     }
                 };
-                res.push(WorkspaceBuildScripts::run(&config, ws, &progress))
+                res.push(WorkspaceBuildScripts::run(config, ws, &progress))
             }
             sender.send(Task::FetchBuildData(BuildDataProgress::End((workspaces, res)))).unwrap();
         });
@@ -282,10 +303,11 @@ impl GlobalState {
                     sysroot,
                     rustc,
                     rustc_cfg,
+                    target,
                     cfg_overrides,
 
                     build_scripts: _,
-                } => Some((cargo, sysroot, rustc, rustc_cfg, cfg_overrides)),
+                } => Some((cargo, sysroot, rustc, rustc_cfg, target, cfg_overrides)),
                 _ => None,
             };
             match (key(left), key(right)) {
@@ -382,6 +404,9 @@ impl GlobalState {
                 },
             };
         }
+        if let Some(client) = &self.proc_macro_client {
+            client.set_expansion_timeout(self.config.proc_macro_expansion_timeout());
+        }
 
         let watch = match files_config.watcher {
             FilesWatcher::Client => vec![],
@@ -463,42 +488,80 @@ impl GlobalState {
 
     fn reload_flycheck(&mut self) {
         let _p = profile::span("GlobalState::reload_flycheck");
-        let config = match self.config.flycheck() {
-            Some(it) => it,
-            None => {
-                self.flycheck = Vec::new();
-                return;
-            }
-        };
+        let configs = self.flycheck_configs(&self.config);
 
         let sender = self.flycheck_sender.clone();
         self.flycheck = self
             .workspaces
             .iter()
             .enumerate()
-            .filter_map(|(id, w)| match w {
-                ProjectWorkspace::Cargo { cargo, .. } => Some((id, cargo.workspace_root())),
-                ProjectWorkspace::Json { project, .. } => {
-                    // Enable flychecks for json projects if a custom flycheck command was supplied
-                    // in the workspace configuration.
-                    match config {
-                        FlycheckConfig::CustomCommand { .. } => Some((id, project.path())),
-                        _ => None,
+            .zip(configs)
+            .filter_map(|((id, w), config)| {
+                let config = config?;
+                let root = match w {
+                    ProjectWorkspace::Cargo { cargo, .. } => cargo.workspace_root(),
+                    ProjectWorkspace::Json { project, .. } => {
+                        // Enable flychecks for json projects if a custom flycheck command was supplied
+                        // in the workspace configuration.
+                        match config {
+                            FlycheckConfig::CustomCommand { .. } => project.path(),
+                            _ => return None,
+                        }
                     }
-                }
-                ProjectWorkspace::DetachedFiles { .. } => None,
+                    ProjectWorkspace::DetachedFiles { .. } => return None,
+                };
+                Some((id, config, root))
             })
-            .map(|(id, root)| {
+            .map(|(id, config, root)| {
                 let sender = sender.clone();
                 FlycheckHandle::spawn(
                     id,
                     Box::new(move |msg| sender.send(msg).unwrap()),
-                    config.clone(),
+                    config,
                     root.to_path_buf().into(),
                 )
             })
             .collect();
     }
+
+    /// Resolves the flycheck config of each workspace against the root that owns it.
+    fn flycheck_configs(&self, config: &Config) -> Vec<Option<FlycheckConfig>> {
+        self.workspaces
+            .iter()
+            .map(|ws| match workspace_root(ws) {
+                Some(root) => config.flycheck_for(root),
+                None => config.flycheck(),
+            })
+            .collect()
+    }
+}
+
+fn workspace_root(ws: &ProjectWorkspace) -> Option<&AbsPath> {
+    match ws {
+        ProjectWorkspace::Cargo { cargo, .. } => Some(cargo.workspace_root()),
+        ProjectWorkspace::Json { project, .. } => Some(project.path()),
+        ProjectWorkspace::DetachedFiles { .. } => None,
+    }
+}
+
+/// The part of `ws` which has to be checked again after the file at `path` changed.
+pub(crate) fn check_scope(ws: &ProjectWorkspace, path: &AbsPath) -> CheckScope {
+    let cargo = match ws {
+        ProjectWorkspace::Cargo { cargo, .. } => cargo,
+        ProjectWorkspace::Json { .. } | ProjectWorkspace::DetachedFiles { .. } => {
+            return CheckScope::Workspace
+        }
+    };
+    let (package, target) = match cargo.package_for_file(path) {
+        Some(it) => it,
+        None => return CheckScope::Workspace,
+    };
+    let target = target.and_then(|it| match cargo[it].kind {
+        TargetKind::Lib => Some(PackageTarget::Lib),
+        TargetKind::Bin => Some(PackageTarget::Bin(cargo[it].name.clone())),
+        _ => None,
+    });
+    CheckScope::Package { spec: cargo.package_flag(&cargo[package]), target }
 }
 
 #[derive(Default)]
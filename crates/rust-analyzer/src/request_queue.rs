@@ -0,0 +1,145 @@
+//! Scheduling of requests which are handled on the thread pool.
+//!
+//! Requests are not handed to the thread pool directly. Instead, each of them
+//! is put into a `RequestQueue`, and a thread pool job is spawned which, once
+//! it gets to run, picks the most important pending request. This way,
+//! latency-sensitive requests for the document the user is typing in overtake
+//! requests which were queued earlier, like semantic tokens of other files.
+//!
+//! Additionally, a latency-sensitive request supersedes pending requests of the
+//! same kind for the same document: the client is only interested in the
+//! completions at the most recent cursor position.
+use std::collections::VecDeque;
+
+use lsp_server::RequestId;
+use lsp_types::Url;
+
+/// Returns whether requests of this kind are directly waited on by the user
+/// while typing.
+pub(crate) fn is_latency_sensitive(method: &str) -> bool {
+    matches!(
+        method,
+        "textDocument/completion" | "textDocument/hover" | "textDocument/signatureHelp"
+    )
+}
+
+pub(crate) struct RequestQueue<T> {
+    pending: VecDeque<PendingRequest<T>>,
+}
+
+struct PendingRequest<T> {
+    id: RequestId,
+    method: &'static str,
+    document: Option<Url>,
+    high_priority: bool,
+    task: T,
+}
+
+impl<T> Default for RequestQueue<T> {
+    fn default() -> Self {
+        RequestQueue { pending: VecDeque::new() }
+    }
+}
+
+impl<T> RequestQueue<T> {
+    /// Adds a request to the queue, returning the ids of the pending requests
+    /// it supersedes. Those are removed from the queue and should be answered
+    /// with `ContentModified`.
+    pub(crate) fn push(
+        &mut self,
+        id: RequestId,
+        method: &'static str,
+        document: Option<Url>,
+        high_priority: bool,
+        task: T,
+    ) -> Vec<RequestId> {
+        let mut superseded = Vec::new();
+        if document.is_some() && is_latency_sensitive(method) {
+            self.pending.retain(|it| {
+                let keep = it.method != method || it.document != document;
+                if !keep {
+                    superseded.push(it.id.clone());
+                }
+                keep
+            });
+        }
+        self.pending.push_back(PendingRequest { id, method, document, high_priority, task });
+        superseded
+    }
+
+    /// Takes the oldest high priority request, or the oldest request if there
+    /// are none.
+    pub(crate) fn pop(&mut self) -> Option<(RequestId, T)> {
+        let idx = self.pending.iter().position(|it| it.high_priority).unwrap_or(0);
+        let request = self.pending.remove(idx)?;
+        Some((request.id, request.task))
+    }
+
+    /// Removes a request which got cancelled before it started.
+    pub(crate) fn remove(&mut self, id: &RequestId) -> Option<T> {
+        let idx = self.pending.iter().position(|it| it.id == *id)?;
+        self.pending.remove(idx).map(|it| it.task)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(path: &str) -> Option<Url> {
+        Some(Url::parse(&format!("file:///{}", path)).unwrap())
+    }
+
+    fn drain(queue: &mut RequestQueue<&'static str>) -> Vec<&'static str> {
+        std::iter::from_fn(|| queue.pop().map(|(_, task)| task)).collect()
+    }
+
+    #[test]
+    fn high_priority_requests_go_first() {
+        let mut queue = RequestQueue::default();
+        queue.push(1.into(), "textDocument/semanticTokens/full", url("a.rs"), false, "tokens a");
+        queue.push(2.into(), "textDocument/semanticTokens/full", url("b.rs"), false, "tokens b");
+        queue.push(3.into(), "textDocument/completion", url("c.rs"), true, "completion c");
+        queue.push(4.into(), "textDocument/hover", url("c.rs"), true, "hover c");
+        assert_eq!(drain(&mut queue), vec!["completion c", "hover c", "tokens a", "tokens b"]);
+    }
+
+    #[test]
+    fn newer_request_supersedes_pending_one() {
+        let mut queue = RequestQueue::default();
+        assert!(queue.push(1.into(), "textDocument/completion", url("a.rs"), true, "1").is_empty());
+        assert!(queue.push(2.into(), "textDocument/hover", url("a.rs"), true, "2").is_empty());
+        assert!(queue
+            .push(3.into(), "textDocument/completion", url("b.rs"), false, "3")
+            .is_empty());
+        let superseded = queue.push(4.into(), "textDocument/completion", url("a.rs"), true, "4");
+        assert_eq!(superseded, vec![RequestId::from(1)]);
+        assert_eq!(drain(&mut queue), vec!["2", "4", "3"]);
+    }
+
+    #[test]
+    fn other_requests_are_not_coalesced() {
+        let mut queue = RequestQueue::default();
+        let method = "textDocument/semanticTokens/full";
+        assert!(queue.push(1.into(), method, url("a.rs"), false, "1").is_empty());
+        assert!(queue.push(2.into(), method, url("a.rs"), false, "2").is_empty());
+        assert!(queue.push(3.into(), "textDocument/completion", None, false, "3").is_empty());
+        assert!(queue.push(4.into(), "textDocument/completion", None, false, "4").is_empty());
+        assert_eq!(drain(&mut queue), vec!["1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn cancelled_requests_are_removed() {
+        let mut queue = RequestQueue::default();
+        queue.push(1.into(), "textDocument/hover", url("a.rs"), false, "1");
+        queue.push(2.into(), "textDocument/hover", url("b.rs"), false, "2");
+        assert_eq!(queue.remove(&1.into()), Some("1"));
+        assert_eq!(queue.remove(&1.into()), None);
+        assert_eq!(queue.len(), 1);
+        assert_eq!(drain(&mut queue), vec!["2"]);
+    }
+}
@@ -32,6 +32,10 @@ pub(crate) fn position(line_index: &LineIndex, offset: TextSize) -> lsp_types::P
             let line_col = line_index.index.to_utf16(line_col);
             lsp_types::Position::new(line_col.line, line_col.col)
         }
+        OffsetEncoding::Utf32 => {
+            let line_col = line_index.index.to_utf32(line_col);
+            lsp_types::Position::new(line_col.line, line_col.col)
+        }
     }
 }
 
@@ -404,6 +408,9 @@ pub(crate) fn inlay_hint(line_index: &LineIndex, inlay_hint: InlayHint) -> lsp_e
             InlayKind::ParameterHint => lsp_ext::InlayKind::ParameterHint,
             InlayKind::TypeHint => lsp_ext::InlayKind::TypeHint,
             InlayKind::ChainingHint => lsp_ext::InlayKind::ChainingHint,
+            InlayKind::BindingModeHint => lsp_ext::InlayKind::BindingModeHint,
+            InlayKind::ClosureReturnTypeHint => lsp_ext::InlayKind::ClosureReturnTypeHint,
+            InlayKind::LifetimeHint => lsp_ext::InlayKind::LifetimeHint,
         },
     }
 }
@@ -568,7 +575,9 @@ pub(crate) fn folding_range(
         | FoldKind::Statics
         | FoldKind::WhereClause
         | FoldKind::ReturnType
-        | FoldKind::Array => None,
+        | FoldKind::Array
+        | FoldKind::MatchArm
+        | FoldKind::TestModule => None,
     };
 
     let range = range(line_index, fold.range);
@@ -798,6 +807,20 @@ pub(crate) fn snippet_text_document_ops(
                 rename_file,
             )))
         }
+        FileSystemEdit::MoveDir { src, src_id, dst } => {
+            let old_uri = snap.anchored_path(&src);
+            let new_uri = snap.anchored_path(&dst);
+            let mut rename_file =
+                lsp_types::RenameFile { old_uri, new_uri, options: None, annotation_id: None };
+            if snap.analysis.is_library_file(src_id).ok() == Some(true)
+                && snap.config.change_annotation_support()
+            {
+                rename_file.annotation_id = Some(outside_workspace_annotation_id())
+            }
+            ops.push(lsp_ext::SnippetDocumentChangeOperation::Op(lsp_types::ResourceOp::Rename(
+                rename_file,
+            )))
+        }
     }
     Ok(ops)
 }
@@ -960,7 +983,7 @@ pub(crate) fn runnable(
     let workspace_root = spec.as_ref().map(|it| it.workspace_root.clone());
     let target = spec.as_ref().map(|s| s.target.clone());
     let (cargo_args, executable_args) =
-        CargoTargetSpec::runnable_args(snap, spec, &runnable.kind, &runnable.cfg)?;
+        CargoTargetSpec::runnable_args(&snap.config.cargo(), spec, &runnable.kind, &runnable.cfg);
     let label = runnable.label(target);
     let location = location_link(snap, None, runnable.nav)?;
 
@@ -1131,6 +1154,17 @@ pub(crate) mod command {
         }
     }
 
+    pub(crate) fn eval_function(
+        uri: &lsp_types::Url,
+        position: lsp_types::Position,
+    ) -> lsp_types::Command {
+        lsp_types::Command {
+            title: "Evaluate".into(),
+            command: "rust-analyzer.evalFunction".into(),
+            arguments: Some(vec![to_value(uri).unwrap(), to_value(position).unwrap()]),
+        }
+    }
+
     pub(crate) fn run_single(runnable: &lsp_ext::Runnable, title: &str) -> lsp_types::Command {
         lsp_types::Command {
             title: title.to_string(),
@@ -1245,6 +1279,26 @@ fn main() {
         }
     }
 
+    #[test]
+    fn converts_positions_in_all_encodings() {
+        // `𝕏` takes four bytes in utf-8, two code units in utf-16 and one in utf-32.
+        let text = "fn f() {}\nlet 𝕏 = \"𝕏メ\"; x\n";
+        let offset_of_x = TextSize::from(text.rfind('x').unwrap() as u32);
+
+        for (encoding, expected_character) in
+            [(OffsetEncoding::Utf8, 22), (OffsetEncoding::Utf16, 16), (OffsetEncoding::Utf32, 14)]
+        {
+            let line_index = LineIndex {
+                index: Arc::new(ide::LineIndex::new(text)),
+                endings: LineEndings::Unix,
+                encoding,
+            };
+            let pos = position(&line_index, offset_of_x);
+            assert_eq!(pos, lsp_types::Position::new(1, expected_character), "{:?}", encoding);
+            assert_eq!(crate::from_proto::offset(&line_index, pos), offset_of_x, "{:?}", encoding);
+        }
+    }
+
     // `Url` is not able to parse windows paths on unix machines.
     #[test]
     #[cfg(target_os = "windows")]
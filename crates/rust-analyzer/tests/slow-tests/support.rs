@@ -125,7 +125,7 @@ impl<'a> Project<'a> {
             },
         );
         config.discovered_projects = Some(discovered_projects);
-        config.update(self.config);
+        config.update(self.config, None);
 
         Server::new(tmp_dir, config)
     }
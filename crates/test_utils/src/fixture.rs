@@ -52,6 +52,9 @@
 //! - dependencies via `deps:dep1,dep2`
 //! - configuration settings via `cfg:dbg=false,opt_level=2`
 //! - environment variables via `env:PATH=/bin,RUST_LOG=debug`
+//! - the target triple via `target:i686-unknown-linux-gnu` (defaults to the host)
+//! - in-process test proc macros exported by the crate via `proc_macro:identity,duplicate`
+//!   (see `base_db::fixture` for the list of available ones)
 //!
 //! Example using all available metadata:
 //! ```
@@ -74,7 +77,9 @@ pub struct Fixture {
     pub cfg_key_values: Vec<(String, String)>,
     pub edition: Option<String>,
     pub env: FxHashMap<String, String>,
+    pub target: Option<String>,
     pub introduce_new_source_root: Option<String>,
+    pub proc_macro: Vec<String>,
 }
 
 pub struct MiniCore {
@@ -162,7 +167,9 @@ impl Fixture {
         let mut cfg_atoms = Vec::new();
         let mut cfg_key_values = Vec::new();
         let mut env = FxHashMap::default();
+        let mut target = None;
         let mut introduce_new_source_root = None;
+        let mut proc_macro = Vec::new();
         for component in components[1..].iter() {
             let (key, value) = component
                 .split_once(':')
@@ -186,7 +193,9 @@ impl Fixture {
                         }
                     }
                 }
+                "target" => target = Some(value.to_string()),
                 "new_source_root" => introduce_new_source_root = Some(value.to_string()),
+                "proc_macro" => proc_macro = value.split(',').map(|it| it.to_string()).collect(),
                 _ => panic!("bad component: {:?}", component),
             }
         }
@@ -200,7 +209,9 @@ impl Fixture {
             cfg_key_values,
             edition,
             env,
+            target,
             introduce_new_source_root,
+            proc_macro,
         }
     }
 }
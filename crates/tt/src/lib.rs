@@ -281,6 +281,8 @@ pub enum ExpansionError {
     JsonError(String),
     Unknown(String),
     ExpansionError(String),
+    /// The proc macro server gave up on the expansion, as it took too long.
+    ExpansionTimeout(String),
 }
 
 impl fmt::Display for ExpansionError {
@@ -290,6 +292,9 @@ impl fmt::Display for ExpansionError {
             ExpansionError::JsonError(e) => write!(f, "JSON decoding error: {}", e),
             ExpansionError::Unknown(e) => e.fmt(f),
             ExpansionError::ExpansionError(e) => write!(f, "proc macro returned error: {}", e),
+            ExpansionError::ExpansionTimeout(e) => {
+                write!(f, "proc macro expansion timed out: {}", e)
+            }
         }
     }
 }
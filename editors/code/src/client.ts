@@ -184,6 +184,7 @@ class ExperimentalFeatures implements lc.StaticFeature {
                 "rust-analyzer.debugSingle",
                 "rust-analyzer.showReferences",
                 "rust-analyzer.gotoLocation",
+                "rust-analyzer.evalFunction",
                 "editor.action.triggerParameterHints",
             ]
         };
@@ -61,8 +61,8 @@ export function memoryUsage(ctx: Ctx): Cmd {
         provideTextDocumentContent(_uri: vscode.Uri): vscode.ProviderResult<string> {
             if (!vscode.window.activeTextEditor) return '';
 
-            return ctx.client.sendRequest(ra.memoryUsage).then((mem: any) => {
-                return 'Per-query memory usage:\n' + mem + '\n(note: database has been cleared)';
+            return ctx.client.sendRequest(ra.memoryUsage).then((mem) => {
+                return mem.text + '\n(note: database has been cleared)';
             });
         }
 
@@ -545,6 +545,12 @@ export function expandMacro(ctx: Ctx): Cmd {
         let result = `// Recursive expansion of ${expanded.name}! macro\n`;
         result += '// ' + '='.repeat(result.length - 3);
         result += '\n\n';
+        for (const error of expanded.errors) {
+            result += `// error: ${error}\n`;
+        }
+        if (expanded.errors.length !== 0) {
+            result += '\n';
+        }
         result += expanded.expansion;
 
         return result;
@@ -597,6 +603,10 @@ export function reloadWorkspace(ctx: Ctx): Cmd {
     return async () => ctx.client.sendRequest(ra.reloadWorkspace);
 }
 
+export function clearMemory(ctx: Ctx): Cmd {
+    return async () => ctx.client.sendRequest(ra.clearMemory);
+}
+
 async function showReferencesImpl(client: LanguageClient, uri: string, position: lc.Position, locations: lc.Location[]) {
     if (client) {
         await vscode.commands.executeCommand(
@@ -614,6 +624,32 @@ export function showReferences(ctx: Ctx): Cmd {
     };
 }
 
+export function evalFunction(ctx: Ctx): Cmd {
+    return async (uri?: string, position?: lc.Position) => {
+        const client = ctx.client;
+        if (!client) return;
+
+        let params: lc.TextDocumentPositionParams;
+        if (uri && position) {
+            params = { textDocument: { uri }, position };
+        } else {
+            const editor = ctx.activeRustEditor;
+            if (!editor) return;
+            params = {
+                textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(editor.document),
+                position: client.code2ProtocolConverter.asPosition(editor.selection.active),
+            };
+        }
+
+        const result = await client.sendRequest(ra.evalFunction, params);
+        if (result) {
+            await vscode.window.showInformationMessage(result);
+        } else {
+            await vscode.window.showInformationMessage("Nothing to evaluate at the cursor");
+        }
+    };
+}
+
 export function applyActionGroup(_ctx: Ctx): Cmd {
     return async (actions: { label: string; arguments: lc.CodeAction }[]) => {
         const selectedAction = await vscode.window.showQuickPick(actions);
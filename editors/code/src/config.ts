@@ -115,6 +115,9 @@ export class Config {
             typeHints: this.get<boolean>("inlayHints.typeHints"),
             parameterHints: this.get<boolean>("inlayHints.parameterHints"),
             chainingHints: this.get<boolean>("inlayHints.chainingHints"),
+            bindingModeHints: this.get<boolean>("inlayHints.bindingModeHints"),
+            closureReturnTypeHints: this.get<"always" | "with_block" | "never">("inlayHints.closureReturnTypeHints"),
+            lifetimeElisionHints: this.get<"always" | "skip_trivial" | "never">("inlayHints.lifetimeElisionHints"),
             smallerHints: this.get<boolean>("inlayHints.smallerHints"),
             maxLength: this.get<null | number>("inlayHints.maxLength"),
         };
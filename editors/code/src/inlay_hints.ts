@@ -14,6 +14,9 @@ interface InlayHintsStyles {
     typeHints: InlayHintStyle;
     paramHints: InlayHintStyle;
     chainingHints: InlayHintStyle;
+    bindingModeHints: InlayHintStyle;
+    closureReturnTypeHints: InlayHintStyle;
+    lifetimeHints: InlayHintStyle;
 }
 
 
@@ -23,7 +26,10 @@ export function activateInlayHints(ctx: Ctx) {
         async onConfigChange() {
             const anyEnabled = ctx.config.inlayHints.typeHints
                 || ctx.config.inlayHints.parameterHints
-                || ctx.config.inlayHints.chainingHints;
+                || ctx.config.inlayHints.chainingHints
+                || ctx.config.inlayHints.bindingModeHints
+                || ctx.config.inlayHints.closureReturnTypeHints !== "never"
+                || ctx.config.inlayHints.lifetimeElisionHints !== "never";
             const enabled = ctx.config.inlayHints.enable && anyEnabled;
 
             if (!enabled) return this.dispose();
@@ -51,13 +57,17 @@ export function activateInlayHints(ctx: Ctx) {
     maybeUpdater.onConfigChange().catch(console.error);
 }
 
-function createHintStyle(hintKind: "type" | "parameter" | "chaining", smallerHints: boolean): InlayHintStyle {
+function createHintStyle(hintKind: "type" | "parameter" | "chaining" | "bindingMode" | "closureReturnType" | "lifetime", smallerHints: boolean): InlayHintStyle {
     // U+200C is a zero-width non-joiner to prevent the editor from forming a ligature
     // between code and type hints
     const [pos, render] = ({
         type: ["after", (label: string) => `\u{200c}: ${label}`],
         parameter: ["before", (label: string) => `${label}: `],
         chaining: ["after", (label: string) => `\u{200c}: ${label}`],
+        bindingMode: ["before", (label: string) => label === "&" ? label : `${label} `],
+        closureReturnType: ["after", (label: string) => `\u{200c} -> ${label}`],
+        // `<'0>` follows a function name, other lifetimes precede a type
+        lifetime: ["after", (label: string) => label.startsWith("<") ? label : `${label} `],
     } as const)[hintKind];
 
     const fg = new vscode.ThemeColor(`rust_analyzer.inlayHints.foreground.${hintKind}Hints`);
@@ -85,12 +95,18 @@ const smallHintsStyles = {
     typeHints: createHintStyle("type", true),
     paramHints: createHintStyle("parameter", true),
     chainingHints: createHintStyle("chaining", true),
+    bindingModeHints: createHintStyle("bindingMode", true),
+    closureReturnTypeHints: createHintStyle("closureReturnType", true),
+    lifetimeHints: createHintStyle("lifetime", true),
 };
 
 const biggerHintsStyles = {
     typeHints: createHintStyle("type", false),
     paramHints: createHintStyle("parameter", false),
     chainingHints: createHintStyle("chaining", false),
+    bindingModeHints: createHintStyle("bindingMode", false),
+    closureReturnTypeHints: createHintStyle("closureReturnType", false),
+    lifetimeHints: createHintStyle("lifetime", false),
 };
 
 class HintsUpdater implements Disposable {
@@ -128,7 +144,7 @@ class HintsUpdater implements Disposable {
 
     dispose() {
         this.sourceFiles.forEach(file => file.inlaysRequest?.cancel());
-        this.ctx.visibleRustEditors.forEach(editor => this.renderDecorations(editor, { param: [], type: [], chaining: [] }));
+        this.ctx.visibleRustEditors.forEach(editor => this.renderDecorations(editor, { param: [], type: [], chaining: [], bindingMode: [], closureReturnType: [], lifetime: [] }));
         this.disposables.forEach(d => d.dispose());
     }
 
@@ -193,21 +209,27 @@ class HintsUpdater implements Disposable {
     }
 
     private renderDecorations(editor: RustEditor, decorations: InlaysDecorations) {
-        const { typeHints, paramHints, chainingHints } = this.inlayHintsStyles;
+        const { typeHints, paramHints, chainingHints, bindingModeHints, closureReturnTypeHints, lifetimeHints } = this.inlayHintsStyles;
         if (this.pendingDisposeDecorations !== undefined) {
-            const { typeHints, paramHints, chainingHints } = this.pendingDisposeDecorations;
+            const { typeHints, paramHints, chainingHints, bindingModeHints, closureReturnTypeHints, lifetimeHints } = this.pendingDisposeDecorations;
             editor.setDecorations(typeHints.decorationType, []);
             editor.setDecorations(paramHints.decorationType, []);
             editor.setDecorations(chainingHints.decorationType, []);
+            editor.setDecorations(bindingModeHints.decorationType, []);
+            editor.setDecorations(closureReturnTypeHints.decorationType, []);
+            editor.setDecorations(lifetimeHints.decorationType, []);
         }
         editor.setDecorations(typeHints.decorationType, decorations.type);
         editor.setDecorations(paramHints.decorationType, decorations.param);
         editor.setDecorations(chainingHints.decorationType, decorations.chaining);
+        editor.setDecorations(bindingModeHints.decorationType, decorations.bindingMode);
+        editor.setDecorations(closureReturnTypeHints.decorationType, decorations.closureReturnType);
+        editor.setDecorations(lifetimeHints.decorationType, decorations.lifetime);
     }
 
     private hintsToDecorations(hints: ra.InlayHint[]): InlaysDecorations {
-        const { typeHints, paramHints, chainingHints } = this.inlayHintsStyles;
-        const decorations: InlaysDecorations = { type: [], param: [], chaining: [] };
+        const { typeHints, paramHints, chainingHints, bindingModeHints, closureReturnTypeHints, lifetimeHints } = this.inlayHintsStyles;
+        const decorations: InlaysDecorations = { type: [], param: [], chaining: [], bindingMode: [], closureReturnType: [], lifetime: [] };
         const conv = this.ctx.client.protocol2CodeConverter;
 
         for (const hint of hints) {
@@ -224,6 +246,18 @@ class HintsUpdater implements Disposable {
                     decorations.chaining.push(chainingHints.toDecoration(hint, conv));
                     continue;
                 }
+                case ra.InlayHint.Kind.BindingModeHint: {
+                    decorations.bindingMode.push(bindingModeHints.toDecoration(hint, conv));
+                    continue;
+                }
+                case ra.InlayHint.Kind.ClosureReturnTypeHint: {
+                    decorations.closureReturnType.push(closureReturnTypeHints.toDecoration(hint, conv));
+                    continue;
+                }
+                case ra.InlayHint.Kind.LifetimeHint: {
+                    decorations.lifetime.push(lifetimeHints.toDecoration(hint, conv));
+                    continue;
+                }
             }
         }
         return decorations;
@@ -251,6 +285,9 @@ interface InlaysDecorations {
     type: vscode.DecorationOptions[];
     param: vscode.DecorationOptions[];
     chaining: vscode.DecorationOptions[];
+    bindingMode: vscode.DecorationOptions[];
+    closureReturnType: vscode.DecorationOptions[];
+    lifetime: vscode.DecorationOptions[];
 }
 
 interface RustSourceFile {
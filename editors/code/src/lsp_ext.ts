@@ -8,7 +8,16 @@ export interface AnalyzerStatusParams {
     textDocument?: lc.TextDocumentIdentifier;
 }
 export const analyzerStatus = new lc.RequestType<AnalyzerStatusParams, string, void>("rust-analyzer/analyzerStatus");
-export const memoryUsage = new lc.RequestType0<string, void>("rust-analyzer/memoryUsage");
+export interface MemoryUsageResult {
+    text: string;
+    queryGroups: { name: string; bytes: number }[];
+    vfs: number;
+    symbolIndex: number;
+    procMacroServer?: number;
+    remaining: number;
+}
+export const memoryUsage = new lc.RequestType0<MemoryUsageResult, void>("rust-analyzer/memoryUsage");
+export const clearMemory = new lc.RequestType0<null, void>("rust-analyzer/clearMemory");
 
 export interface ServerStatusParams {
     health: "ok" | "warning" | "error";
@@ -34,6 +43,8 @@ export const syntaxTree = new lc.RequestType<SyntaxTreeParams, string, void>("ru
 
 export const viewHir = new lc.RequestType<lc.TextDocumentPositionParams, string, void>("rust-analyzer/viewHir");
 
+export const evalFunction = new lc.RequestType<lc.TextDocumentPositionParams, string | null, void>("rust-analyzer/evalFunction");
+
 export interface ViewItemTreeParams {
     textDocument: lc.TextDocumentIdentifier;
 }
@@ -53,6 +64,7 @@ export interface ExpandMacroParams {
 export interface ExpandedMacro {
     name: string;
     expansion: string;
+    errors: string[];
 }
 export const expandMacro = new lc.RequestType<ExpandMacroParams, ExpandedMacro | null, void>("rust-analyzer/expandMacro");
 
@@ -98,13 +110,16 @@ export interface TestInfo {
 
 export const relatedTests = new lc.RequestType<lc.TextDocumentPositionParams, TestInfo[], void>("rust-analyzer/relatedTests");
 
-export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint;
+export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint | InlayHint.BindingModeHint | InlayHint.ClosureReturnTypeHint | InlayHint.LifetimeHint;
 
 export namespace InlayHint {
     export const enum Kind {
         TypeHint = "TypeHint",
         ParamHint = "ParameterHint",
         ChainingHint = "ChainingHint",
+        BindingModeHint = "BindingModeHint",
+        ClosureReturnTypeHint = "ClosureReturnTypeHint",
+        LifetimeHint = "LifetimeHint",
     }
     interface Common {
         range: lc.Range;
@@ -113,6 +128,9 @@ export namespace InlayHint {
     export type TypeHint = Common & { kind: Kind.TypeHint };
     export type ParamHint = Common & { kind: Kind.ParamHint };
     export type ChainingHint = Common & { kind: Kind.ChainingHint };
+    export type BindingModeHint = Common & { kind: Kind.BindingModeHint };
+    export type ClosureReturnTypeHint = Common & { kind: Kind.ClosureReturnTypeHint };
+    export type LifetimeHint = Common & { kind: Kind.LifetimeHint };
 }
 export interface InlayHintsParams {
     textDocument: lc.TextDocumentIdentifier;